@@ -0,0 +1,134 @@
+// times checking the areas of the containers of a term against each other,
+// building terms of 10, 100 and 250 containers and moving one container around
+// 10, 100 and 1000 others, the way dragging it with the mouse would, and pushing a container
+// over it, and hit testing terms of 10, 100 and 1000 texts
+// cargo bench --bench areas
+
+use std::hint::black_box;
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use ragout::components::{Container, Easing, Term, Text};
+use ragout::space::{border::Border, padding::Padding, Area, Pos};
+
+// the containers go in a grid of 6 x 3 cells per container, 66 of them in a row
+const COLUMNS: u16 = 66;
+
+// the [x, y] origin of the nth container of the grid
+fn origin(n: u16) -> [u16; 2] {
+    [n % COLUMNS * 6, n / COLUMNS * 3]
+}
+
+// a term of n bordered containers built one after the other
+fn built(n: u16) -> Term {
+    let mut term = Term::new(0, 400, 100);
+    for idx in 0..n {
+        let [x, y] = origin(idx);
+        Container::builder([0, idx as u8])
+            .pos(Pos::Value(x), Pos::Value(y))
+            .area(Area::Values { w: 6, h: 3 })
+            .border(Border::Uniform('#'))
+            .build(&mut term)
+            .unwrap();
+    }
+
+    term
+}
+
+// a term of n containers and one more, the one that is moved, in the right half of the term
+// past the 256 container ids the ids of the others repeat, the checks don't look at them
+fn crowded(n: u16) -> Term {
+    let mut term = Term::new(0, 400, 100);
+    for idx in 0..n {
+        let [x, y] = origin(idx);
+        let id = [0, (idx % 255) as u8];
+        let c = Container::new(id, x, y, 4, 1, Border::Uniform('#'), Padding::None);
+        term.containers.push(c);
+    }
+    let moved = Container::new([0, 255], 396, 0, 2, 1, Border::None, Padding::None);
+    term.containers.push(moved);
+
+    term
+}
+
+// a term of n 3x1 texts, 250 of them in a container, the containers side by side
+fn texts(n: u16) -> Term {
+    let mut term = Term::new(0, 400, 100);
+    for cont in 0..n.div_ceil(250) {
+        let id = [0, cont as u8];
+        let mut c = Container::new(id, cont * 100, 0, 100, 50, Border::None, Padding::None);
+        for idx in 0..(n - cont * 250).min(250) {
+            let [x, y] = [idx % 25 * 4, idx / 25 * 5];
+            let id = [0, cont as u8, idx as u8];
+            let t = Text::nonedit(id, x, y, 0, 0, 3, 1, &[], Border::None, Padding::None);
+            c.items.push(t);
+        }
+        c.invalidate();
+        term.containers.push(c);
+    }
+
+    term
+}
+
+fn build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build");
+    for n in [10, 100, 250] {
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter(|| built(n))
+        });
+    }
+    group.finish();
+}
+
+fn moves(c: &mut Criterion) {
+    let mut group = c.benchmark_group("move");
+    for n in [10, 100, 1000] {
+        let mut term = crowded(n);
+        let mut right = false;
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, _| {
+            b.iter(|| {
+                right = !right;
+                let x = if right { 397 } else { 396 };
+                let to = [x, black_box(0)];
+                term.animate_move(&[0, 255], to, Duration::ZERO, Easing::Linear)
+                    .unwrap();
+            })
+        });
+        // a move onto another container is refused
+        let taken = term.animate_move(&[0, 255], [0, 0], Duration::ZERO, Easing::Linear);
+        assert!(taken.is_err());
+    }
+    group.finish();
+}
+
+fn refuse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("refuse");
+    for n in [10, 100, 1000] {
+        let mut term = crowded(n);
+        // a container pushed over the moved one, only the area check is timed past the id check
+        let over = Container::new([1, 0], 396, 0, 2, 1, Border::None, Padding::None);
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, _| {
+            b.iter(|| term.push_container(black_box(over.clone())).unwrap_err())
+        });
+    }
+    group.finish();
+}
+
+fn hit(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hit");
+    for n in [10, 100, 1000] {
+        let term = texts(n);
+        let mut x = 0;
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, _| {
+            b.iter(|| {
+                x = (x + 1) % 400;
+                term.hit_test(black_box(x), 0)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(areas, build, moves, refuse, hit);
+criterion_main!(areas);
@@ -0,0 +1,90 @@
+// times setting, editing and rendering the value of a 10k cells nonedit, of ascii chars
+// and of some chars that take more than a byte,
+// and appending 100k lines to a following one, with and without a cap
+// cargo bench --bench text
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use ragout::components::{Container, Term, Text, Value};
+use ragout::space::{Area, Pos};
+use ragout::test::Frame;
+
+const ID: [u8; 3] = [0, 0, 1];
+
+// a 100 x 100 nonedit filling its term
+fn term() -> Term {
+    let mut term = Term::new(0, 100, 100);
+    Container::builder([0, 0])
+        .area(Area::Values { w: 100, h: 100 })
+        .build(&mut term)
+        .unwrap();
+    Text::nonedit_builder(ID)
+        .pos(Pos::Start, Pos::Start)
+        .area(Area::Values { w: 100, h: 100 })
+        .build(&mut term)
+        .unwrap();
+
+    term
+}
+
+// runs f iterations times and prints the mean time it took
+fn bench(name: &str, iterations: u32, mut f: impl FnMut()) {
+    let start = Instant::now();
+    (0..iterations).for_each(|_| f());
+    println!("{:<12} {:>12.2?} / iter", name, start.elapsed() / iterations);
+}
+
+fn main() {
+    let value = "lorem ipsum dolor sit amet, ".repeat(400)[..10_000].to_string();
+    let mut term = term();
+
+    bench("set", 100, || {
+        let text = term.nonedit_mut(&ID).unwrap();
+        text.value = Value::from(black_box(value.as_str()));
+    });
+    bench("set typed", 10, || {
+        let text = term.nonedit_mut(&ID).unwrap();
+        text.replace_value(black_box(&value));
+    });
+    bench("edit", 10_000, || {
+        let text = term.nonedit_mut(&ID).unwrap();
+        text.cx = 50;
+        text.cy = 50;
+        text.insert('x');
+        text.backspace();
+    });
+    bench("read", 1_000, || {
+        black_box(term.nonedit_ref(&ID).unwrap().value_string());
+    });
+    bench("render", 100, || {
+        black_box(Frame::of(&term));
+    });
+    // the same with a char that takes more than a byte in every word
+    let accented = value.replace('o', "ö");
+    let text = term.nonedit_mut(&ID).unwrap();
+    text.value = Value::from(accented.as_str());
+    bench("edit ö", 10_000, || {
+        let text = term.nonedit_mut(&ID).unwrap();
+        text.cx = 50;
+        text.cy = 50;
+        text.insert('x');
+        text.backspace();
+    });
+    bench("render ö", 100, || {
+        black_box(Frame::of(&term));
+    });
+    bench("append 100k", 1, || {
+        let mut term = self::term();
+        let text = term.nonedit_mut(&ID).unwrap();
+        text.set_follow(true);
+        (0..100_000).for_each(|n| text.append_line(black_box(&format!("line {}", n))));
+    });
+    bench("append capped", 1, || {
+        let mut term = self::term();
+        let text = term.nonedit_mut(&ID).unwrap();
+        text.set_follow(true);
+        text.set_max_lines(Some(10_000));
+        (0..100_000).for_each(|n| text.append_line(black_box(&format!("line {}", n))));
+    });
+}
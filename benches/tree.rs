@@ -0,0 +1,52 @@
+// times building and rendering a tree of 500 texts, 125 in each of 4 containers
+// cargo bench --bench tree
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use ragout::components::{Container, Term, Text};
+use ragout::space::{border::Border, Area, Pos};
+use ragout::test::Frame;
+
+// 4 bordered containers of 100 x 50 cells, each with 5 rows of 25 bordered 4 x 3 nonedits
+fn term() -> Term {
+    let mut term = Term::new(0, 204, 104);
+    for cid in 0..4u8 {
+        let [x, y] = [cid % 2, cid / 2].map(|n| n as u16);
+        Container::builder([0, cid])
+            .pos(Pos::Value(x * 102), Pos::Value(y * 52))
+            .area(Area::Values { w: 102, h: 52 })
+            .border(Border::Uniform('#'))
+            .build(&mut term)
+            .unwrap();
+        for idx in 0..125u16 {
+            let [col, row] = [idx % 25, idx / 25];
+            Text::nonedit_builder([0, cid, idx as u8 * 2 + 1])
+                .pos(Pos::Value(col * 4), Pos::Value(row * 3))
+                .area(Area::Values { w: 4, h: 3 })
+                .border(Border::Uniform('*'))
+                .value_str("ab")
+                .build(&mut term)
+                .unwrap();
+        }
+    }
+
+    term
+}
+
+// runs f iterations times and prints the mean time it took
+fn bench(name: &str, iterations: u32, mut f: impl FnMut()) {
+    let start = Instant::now();
+    (0..iterations).for_each(|_| f());
+    println!("{:<12} {:>12.2?} / iter", name, start.elapsed() / iterations);
+}
+
+fn main() {
+    bench("build", 10, || {
+        black_box(term());
+    });
+    let term = term();
+    bench("render", 20, || {
+        black_box(Frame::of(&term));
+    });
+}
@@ -0,0 +1,336 @@
+//! the Screen derive of ragout, use it through ragout's derive feature, see ragout::Screen
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, LitInt, LitStr};
+
+/// makes a `build(term: &mut Term) -> Result<Self, ComponentTreeError>` for a struct of
+/// component ids, every field of the struct takes one of the attributes
+/// - `#[container(area = "..", pos = "..", layer = n)]` for a `[u8; 2]` container id
+/// - `#[input(container = "field", area = "..", pos = "..", layer = n)]` for a `[u8; 3]` input id
+/// - `#[nonedit(container = "field", area = "..", pos = "..", layer = n, value = "..")]` for a
+///   `[u8; 3]` nonedit id
+///
+/// container is the name of the container field the text goes into, area is "fill", "WxH"
+/// or "W%xH%" and defaults to "fill", pos is "hpos,vpos", each one of start, center, end or
+/// a number of cells, and defaults to "center,center", layer defaults to 0
+///
+/// build makes the containers in the order of their fields, then the texts in theirs, with
+/// Term::container_from_meta, Term::input_from_meta and Term::nonedit_from_meta,
+/// and returns the first error one of them gives back
+/// every input and nonedit field also gets a `<field>_value(&self, term: &Term) -> Option<String>`
+/// accessor, the Text::value_string of the text
+#[proc_macro_derive(Screen, attributes(container, input, nonedit))]
+pub fn derive_screen(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    screen(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Container,
+    Input,
+    NonEdit,
+}
+
+impl Kind {
+    fn of(ident: &Ident) -> Option<Self> {
+        match ident.to_string().as_str() {
+            "container" => Some(Self::Container),
+            "input" => Some(Self::Input),
+            "nonedit" => Some(Self::NonEdit),
+            _ => None,
+        }
+    }
+}
+
+// a field of the struct and the options of its attribute
+struct Component {
+    field: Ident,
+    kind: Kind,
+    span: Span,
+    container: Option<LitStr>,
+    area: Option<TokenStream2>,
+    pos: Option<[TokenStream2; 2]>,
+    layer: Option<LitInt>,
+    value: Option<LitStr>,
+}
+
+fn screen(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "Screen can only be derived for a struct with named fields",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "Screen can only be derived for a struct with named fields",
+        ));
+    };
+
+    let mut components = vec![];
+    for field in &fields.named {
+        components.push(component(field)?);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut containers = vec![];
+    let mut texts = vec![];
+    let mut accessors = vec![];
+    for c in components.iter().filter(|c| c.kind == Kind::Container) {
+        containers.push(make_container(c));
+    }
+    for c in components.iter().filter(|c| c.kind != Kind::Container) {
+        texts.push(make_text(c, &components)?);
+        accessors.push(accessor(c));
+    }
+    let names = components.iter().map(|c| &c.field);
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// makes the components of this screen in term and returns their ids
+            ///
+            /// # Errors
+            /// returns the first error making one of the components gives back, the components
+            /// made before it are left in term
+            pub fn build(
+                term: &mut ::ragout::components::Term,
+            ) -> ::std::result::Result<Self, ::ragout::components::ComponentTreeError> {
+                #(#containers)*
+                #(#texts)*
+
+                ::std::result::Result::Ok(Self { #(#names),* })
+            }
+
+            #(#accessors)*
+        }
+    })
+}
+
+fn component(field: &syn::Field) -> syn::Result<Component> {
+    let ident = field.ident.clone().unwrap();
+    let mut found: Option<Component> = None;
+    for attr in &field.attrs {
+        let Some(kind) = attr.path().get_ident().and_then(Kind::of) else {
+            continue;
+        };
+        if found.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "a field takes only one of the container, input and nonedit attributes",
+            ));
+        }
+        let mut c = Component {
+            field: ident.clone(),
+            kind,
+            span: attr.span(),
+            container: None,
+            area: None,
+            pos: None,
+            layer: None,
+            value: None,
+        };
+        if !matches!(attr.meta, syn::Meta::Path(_)) {
+            attr.parse_nested_meta(|meta| option(&mut c, meta))?;
+        }
+        if kind != Kind::Container && c.container.is_none() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "missing the container option, the name of the container field the text goes into",
+            ));
+        }
+        found = Some(c);
+    }
+
+    found.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &ident,
+            "every field of a Screen takes a container, input or nonedit attribute",
+        )
+    })
+}
+
+// parses one name = value option of a component attribute into c
+fn option(c: &mut Component, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+    let Some(name) = meta.path.get_ident().map(Ident::to_string) else {
+        return Err(meta.error("unknown option"));
+    };
+    match name.as_str() {
+        "container" if c.kind != Kind::Container => {
+            let lit: LitStr = meta.value()?.parse()?;
+            c.container = Some(lit);
+        }
+        "area" => {
+            let lit: LitStr = meta.value()?.parse()?;
+            c.area = Some(area(&lit)?);
+        }
+        "pos" => {
+            let lit: LitStr = meta.value()?.parse()?;
+            c.pos = Some(pos(&lit)?);
+        }
+        "layer" => {
+            let lit: LitInt = meta.value()?.parse()?;
+            lit.base10_parse::<u8>()?;
+            c.layer = Some(lit);
+        }
+        "value" if c.kind == Kind::NonEdit => {
+            let lit: LitStr = meta.value()?.parse()?;
+            c.value = Some(lit);
+        }
+        _ => {
+            let expected = match c.kind {
+                Kind::Container => "area, pos or layer",
+                Kind::Input => "container, area, pos or layer",
+                Kind::NonEdit => "container, area, pos, layer or value",
+            };
+            return Err(meta.error(format!("unknown option `{}`, expected {}", name, expected)));
+        }
+    }
+
+    Ok(())
+}
+
+// "fill", "WxH" or "W%xH%"
+fn area(lit: &LitStr) -> syn::Result<TokenStream2> {
+    let s = lit.value();
+    if s == "fill" {
+        return Ok(quote!(::ragout::space::Area::Fill));
+    }
+    let bad = || {
+        syn::Error::new_spanned(
+            lit,
+            format!("bad area `{}`, expected \"fill\", \"WxH\" or \"W%xH%\"", s),
+        )
+    };
+    let (w, h) = s.split_once('x').ok_or_else(bad)?;
+    match (w.strip_suffix('%'), h.strip_suffix('%')) {
+        (Some(w), Some(h)) => {
+            let w: u16 = w.parse().map_err(|_| bad())?;
+            let h: u16 = h.parse().map_err(|_| bad())?;
+            if w > 100 || h > 100 {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    format!("bad area `{}`, a percentage goes up to 100%", s),
+                ));
+            }
+            Ok(quote!(::ragout::space::Area::Percent { w: #w, h: #h }))
+        }
+        (None, None) => {
+            let w: u16 = w.parse().map_err(|_| bad())?;
+            let h: u16 = h.parse().map_err(|_| bad())?;
+            Ok(quote!(::ragout::space::Area::Values { w: #w, h: #h }))
+        }
+        _ => Err(bad()),
+    }
+}
+
+// "hpos,vpos", each one start, center, end or a number of cells
+fn pos(lit: &LitStr) -> syn::Result<[TokenStream2; 2]> {
+    let s = lit.value();
+    let bad = || {
+        syn::Error::new_spanned(
+            lit,
+            format!(
+                "bad pos `{}`, expected \"hpos,vpos\", each one of start, center, end or a number",
+                s
+            ),
+        )
+    };
+    let one = |p: &str| -> syn::Result<TokenStream2> {
+        match p.trim() {
+            "start" => Ok(quote!(::ragout::space::Pos::Start)),
+            "center" => Ok(quote!(::ragout::space::Pos::Center)),
+            "end" => Ok(quote!(::ragout::space::Pos::End)),
+            n => {
+                let n: u16 = n.parse().map_err(|_| bad())?;
+                Ok(quote!(::ragout::space::Pos::Value(#n)))
+            }
+        }
+    };
+    let (h, v) = s.split_once(',').ok_or_else(bad)?;
+
+    Ok([one(h)?, one(v)?])
+}
+
+// the area, hpos, vpos and layer calls on a meta
+fn placement(c: &Component) -> TokenStream2 {
+    let mut calls = quote!();
+    if let Some(area) = &c.area {
+        calls.extend(quote!(.area(#area)));
+    }
+    if let Some([h, v]) = &c.pos {
+        calls.extend(quote!(.hpos(#h).vpos(#v)));
+    }
+    if let Some(layer) = &c.layer {
+        calls.extend(quote!(.layer(#layer)));
+    }
+
+    calls
+}
+
+fn make_container(c: &Component) -> TokenStream2 {
+    let field = &c.field;
+    let calls = placement(c);
+
+    quote_spanned! {c.span=>
+        let #field: [u8; 2] = term.container_from_meta(
+            &::ragout::components::ContainerMeta::new() #calls,
+        )?;
+    }
+}
+
+fn make_text(c: &Component, components: &[Component]) -> syn::Result<TokenStream2> {
+    let field = &c.field;
+    let lit = c.container.as_ref().unwrap();
+    let Some(parent) = components
+        .iter()
+        .find(|p| p.kind == Kind::Container && p.field == lit.value())
+    else {
+        return Err(syn::Error::new_spanned(
+            lit,
+            format!("there is no #[container] field named `{}`", lit.value()),
+        ));
+    };
+    let parent = &parent.field;
+    let mut calls = placement(c);
+    if let Some(value) = &c.value {
+        calls.extend(quote!(.value_str(#value)));
+    }
+
+    Ok(match c.kind {
+        Kind::Input => quote_spanned! {c.span=>
+            let #field: [u8; 3] = term.input_from_meta(
+                &::ragout::components::InputMeta::new().parent(#parent) #calls,
+            )?;
+        },
+        _ => quote_spanned! {c.span=>
+            let #field: [u8; 3] = term.nonedit_from_meta(
+                &::ragout::components::NonEditMeta::new().parent(#parent) #calls,
+            )?;
+        },
+    })
+}
+
+fn accessor(c: &Component) -> TokenStream2 {
+    let field = &c.field;
+    let name = format_ident!("{}_value", field);
+    let doc = format!(
+        "the value of the {} `{}`, None if it is no longer in term",
+        if c.kind == Kind::Input { "input" } else { "nonedit" },
+        field
+    );
+
+    quote! {
+        #[doc = #doc]
+        pub fn #name(&self, term: &::ragout::components::Term) -> ::std::option::Option<::std::string::String> {
+            term.text_ref(&self.#field).map(::ragout::components::Text::value_string)
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use ragout::Screen;
+
+#[derive(Screen)]
+struct Form {
+    #[container(area = "40 by 1")]
+    form: [u8; 2],
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use ragout::Screen;
+
+#[derive(Screen)]
+struct Form {
+    #[container(pos = "middle")]
+    form: [u8; 2],
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use ragout::Screen;
+
+#[derive(Screen)]
+struct Form {
+    #[container]
+    form: [u8; 2],
+    #[input(area = "40x1")]
+    name: [u8; 3],
+}
+
+fn main() {}
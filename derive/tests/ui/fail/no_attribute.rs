@@ -0,0 +1,10 @@
+use ragout::Screen;
+
+#[derive(Screen)]
+struct Form {
+    #[container]
+    form: [u8; 2],
+    name: [u8; 3],
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use ragout::Screen;
+
+#[derive(Screen)]
+struct Form([u8; 2]);
+
+fn main() {}
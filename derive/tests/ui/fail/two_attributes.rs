@@ -0,0 +1,10 @@
+use ragout::Screen;
+
+#[derive(Screen)]
+struct Form {
+    #[container]
+    #[input(container = "form")]
+    form: [u8; 2],
+}
+
+fn main() {}
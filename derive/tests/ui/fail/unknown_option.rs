@@ -0,0 +1,11 @@
+use ragout::Screen;
+
+#[derive(Screen)]
+struct Form {
+    #[container]
+    form: [u8; 2],
+    #[input(container = "form", value = "x")]
+    name: [u8; 3],
+}
+
+fn main() {}
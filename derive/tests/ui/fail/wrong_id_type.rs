@@ -0,0 +1,9 @@
+use ragout::Screen;
+
+#[derive(Screen)]
+struct Form {
+    #[container]
+    form: [u8; 3],
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use ragout::components::Term;
+use ragout::Screen;
+
+#[derive(Screen)]
+struct Panes {
+    #[container(area = "50%x100%", pos = "start,start")]
+    left: [u8; 2],
+    #[container(area = "50%x100%", pos = "end,start")]
+    right: [u8; 2],
+    #[input(container = "right")]
+    prompt: [u8; 3],
+}
+
+fn main() {
+    let mut term = Term::new(0, 80, 24);
+    let panes = Panes::build(&mut term).unwrap();
+    assert_eq!(panes.prompt[..2], panes.right);
+
+    // a second screen does not fit in the term next to the first
+    assert!(Panes::build(&mut term).is_err());
+}
@@ -0,0 +1,23 @@
+use ragout::components::{ContainerId, InputId, NonEditId, Term};
+use ragout::Screen;
+
+#[derive(Screen)]
+struct Form {
+    #[container(area = "60x10", pos = "center,start")]
+    form: ContainerId,
+    #[nonedit(container = "form", area = "6x1", pos = "start,start", value = "name: ")]
+    label: NonEditId,
+    #[input(container = "form", area = "40x1", pos = "end,start")]
+    name: InputId,
+    #[input(container = "form", area = "40x1", pos = "end,2", layer = 1)]
+    email: InputId,
+}
+
+fn main() {
+    let mut term = Term::new(0, 80, 24);
+    let form = Form::build(&mut term).unwrap();
+    assert_eq!(form.form, [0, 0]);
+    assert_eq!(form.label_value(&term).as_deref(), Some("name: "));
+    assert_eq!(form.name_value(&term).as_deref(), Some(""));
+    assert_ne!(form.name, form.email);
+}
@@ -34,6 +34,7 @@ fn main() {
         if let Interaction::KbdEvent(KbdEvent {
             char: Char::Char('c'),
             modifiers: Modifiers(2),
+            ..
         }) = ui.event
         {
             break;
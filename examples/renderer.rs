@@ -1,11 +1,9 @@
-use ragout::components::{ComponentTree as CT, Container, Term, Text};
+use ragout::components::ComponentTree as CT;
 use ragout::console::{
     cooked_mode, enter_alternate_screen, leave_alternate_screen, raw_mode, workers,
 };
 use ragout::frames;
-use ragout::space::{border::Border, padding::Padding, Area, Pos};
-
-use std::io::Write;
+use ragout::space::{border::Border, padding::Padding, Area, Polygon, Pos};
 
 fn main() {
     let mut tree = CT::new();
@@ -18,6 +16,7 @@ fn main() {
         &[0, 0],
         Pos::Start,
         Pos::Start,
+        Polygon::Free { vertices: vec![] },
         Area::Fill,
         Border::polyform('1', '2', '3', '4', '|', '-'),
         Padding::None,
@@ -28,6 +27,7 @@ fn main() {
         &[0, 0, 0],
         Pos::Start,
         Pos::Start,
+        Polygon::Free { vertices: vec![] },
         Area::Values { w: 43, h: 16 },
         Border::uniform('i'),
         Padding::None,
@@ -38,10 +38,11 @@ fn main() {
         &[0, 0, 1],
         Pos::End,
         Pos::End,
+        Polygon::Free { vertices: vec![] },
         Area::Values { w: 43, h: 16 },
         Border::uniform('n'),
         Padding::None,
-        &vec![],
+        &[],
     );
     println!("{:?}", term.containers[0].items.len());
     println!("{:?}", res2);
@@ -55,8 +56,8 @@ fn main() {
     let ts = raw_mode();
     enter_alternate_screen(&mut writer);
 
-    term.clear(&mut writer);
-    term.render(&mut writer);
+    term.clear(&mut writer).unwrap();
+    term.render(&mut writer).unwrap();
     term.render_cursor(&mut writer);
 
     let mut counter = 0;
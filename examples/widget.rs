@@ -0,0 +1,126 @@
+// a color picker of its own, a Widget in a container next to a label showing the picked color
+// Left and Right move through the colors, a click picks the one under the pointer, q quits
+// cargo run --example widget
+
+use std::ops::ControlFlow;
+
+use ragout::components::{Container, EventResult, RenderCtx, Term, Text, Widget};
+use ragout::events::{Event, EventLoop};
+use ragout::inputs::keyboard::{Char, KbdEvent, KeyKind, CC};
+use ragout::inputs::mouse::{MouseEvent, MouseKind};
+use ragout::space::{border::Border, Area, Pos};
+
+const COLORS: [&str; 4] = ["red", "green", "blue", "gray"];
+
+// the colors side by side, the picked one in brackets
+#[derive(Clone)]
+struct Picker {
+    picked: usize,
+}
+
+impl Picker {
+    // the column each color starts at, brackets included
+    fn starts() -> Vec<u16> {
+        let widths = COLORS.iter().map(|c| c.len() as u16 + 3);
+
+        widths
+            .scan(0, |at, w| {
+                *at += w;
+                Some(*at - w)
+            })
+            .collect()
+    }
+}
+
+impl Widget for Picker {
+    fn area(&self) -> [u16; 4] {
+        [1, 1, 30, 1]
+    }
+
+    fn render(&self, ctx: &mut RenderCtx) {
+        for (idx, (color, x)) in COLORS.iter().zip(Picker::starts()).enumerate() {
+            let shown = match idx == self.picked {
+                true => format!("[{color}]"),
+                false => format!(" {color} "),
+            };
+            ctx.print(x, 0, &shown);
+        }
+    }
+
+    fn handle_event(&mut self, event: &Event) -> EventResult {
+        let picked = match event {
+            Event::Key(KbdEvent {
+                char: Char::CC(CC::Left),
+                kind: KeyKind::Press,
+                ..
+            }) => self.picked.saturating_sub(1),
+            Event::Key(KbdEvent {
+                char: Char::CC(CC::Right),
+                kind: KeyKind::Press,
+                ..
+            }) => (self.picked + 1).min(COLORS.len() - 1),
+            Event::Mouse(MouseEvent {
+                kind: MouseKind::Down,
+                column,
+                ..
+            }) => Picker::starts().iter().rposition(|x| x <= column).unwrap_or(0),
+            _ => return EventResult::Ignored,
+        };
+        if picked == self.picked {
+            return EventResult::Consumed;
+        }
+        self.picked = picked;
+
+        EventResult::Redraw
+    }
+
+    fn wants_focus(&self) -> bool {
+        true
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let mut term = Term::new(0, 40, 6);
+    Container::builder([0, 0])
+        .pos(Pos::Start, Pos::Start)
+        .area(Area::Values { w: 34, h: 4 })
+        .border(Border::rounded())
+        .build(&mut term)
+        .unwrap();
+    term.push_widget([0, 0, 1], Picker { picked: 0 }).unwrap();
+    Text::nonedit_builder([0, 0, 3])
+        .pos(Pos::Value(1), Pos::Value(2))
+        .area(Area::Values { w: 30, h: 1 })
+        .value_str("picked red")
+        .build(&mut term)
+        .unwrap();
+    term.focus(&[0, 0, 1]).unwrap();
+
+    let mut writer = std::io::stdout().lock();
+    EventLoop::new().run(&mut term, &mut writer, |term, event| {
+        if let Event::Key(KbdEvent {
+            char: Char::Char('q'),
+            ..
+        }) = event
+        {
+            return ControlFlow::Break(());
+        }
+        match &event {
+            Event::Key(ke) => {
+                term.key(ke);
+            }
+            Event::Mouse(me) => {
+                term.mouse(me);
+            }
+            _ => (),
+        }
+        let picked = term.widget_mut(&[0, 0, 1], |picker: &mut Picker| picker.picked);
+        let label = term.text_mut(&[0, 0, 3]).unwrap();
+        let shown = format!("picked {}", COLORS[picked.unwrap_or(0)]);
+        if label.value_string() != shown {
+            label.replace_value(&shown);
+        }
+
+        ControlFlow::Continue(())
+    })
+}
@@ -1,4 +1,4 @@
-use crate::console::winsize::winsize;
+use crate::console::winsize::{winsize, WinsizeError};
 use crate::render_pipeline;
 use crate::space::{area_conflicts, between, border::Border, border_fit, padding::Padding};
 use crate::themes::Style;
@@ -7,17 +7,99 @@ use std::collections::{HashMap, HashSet};
 use std::io::Error;
 use std::io::StdoutLock;
 use std::io::Write;
+use std::rc::Rc;
 
+pub mod animate;
 pub mod builders;
+mod cache;
+pub mod checkbox;
+// the id and bounds checks of adding a component
+mod checks;
 pub mod container;
+pub mod cursor_info;
+pub mod direction;
+pub mod dropdown;
+pub mod dump;
+pub mod escape;
+pub mod filter;
+// the containers the focus is confined to, see Term::push_focus_scope
+mod focus_scope;
+pub mod gauge;
+pub mod grapheme;
+mod gutter;
+// the index of the areas the texts are hit in, see Term::hit_test
+mod hit;
+// what holds the callbacks given to the on_* methods of a term
+mod hook;
+// the text under the pointer, see Term::hovered
+mod hover;
+// the popup listing the key bindings, see Term::show_keymap_help
+mod keymap_help;
+pub mod list;
+pub mod log_pane;
+#[cfg(feature = "normalization")]
+pub mod normalize;
+pub mod pane_writer;
+pub mod patch;
+pub mod popup;
+pub mod render_hooks;
+pub mod scrollbar;
+pub mod session;
+mod search;
+mod soft_cursor;
+mod stream;
+pub mod sparkline;
+pub mod spinner;
+pub mod status;
+// the RenderStats of the frames, collected with the stats feature
+pub mod stats;
+// the tabs of the values, see Text::tab_width
+mod tab_stop;
+pub mod table;
+pub mod tabs;
 pub mod term;
 pub mod text;
+pub mod tree;
+pub mod validation;
+pub mod value;
+pub mod widget;
 
 // re-exports
-pub use builders::{ContainerMeta, InputMeta, NonEditMeta, TermMeta};
+pub use animate::Easing;
+pub use builders::{ContainerBuilder, ContainerMeta, InputMeta, NonEditMeta, TermMeta, TextBuilder};
+pub use checkbox::{Checkbox, Look};
 pub use container::Container;
-pub use term::Term;
-pub use text::Text;
+pub use cursor_info::CursorInfo;
+pub use direction::Direction;
+pub use dropdown::Dropdown;
+pub use escape::ControlDisplay;
+pub use filter::{InputFilter, MASK_BLANK};
+pub use gauge::Gauge;
+pub(crate) use hook::Hook;
+pub use list::List;
+pub use log_pane::{LogLine, LogPane, LogQueue};
+#[cfg(feature = "normalization")]
+pub use normalize::Normalization;
+pub use pane_writer::{PaneSender, TextWriter};
+pub use patch::PatchOp;
+pub use popup::PopupHandle;
+pub use render_hooks::RenderHookToken;
+pub use scrollbar::{Axis, Scrollbar};
+pub use session::{RestoreWarning, TermState};
+pub use sparkline::Sparkline;
+pub use spinner::Spinner;
+pub use status::Segment;
+pub use stats::RenderStats;
+pub use table::{Column, Table, Width};
+pub use tabs::Tabs;
+pub use term::{BellMode, BellToken, ScreenMode, Term};
+pub use text::{Text, TextKind};
+pub use tree::{Node, Tree};
+pub use validation::Validation;
+pub use value::Value;
+pub use widget::{EventResult, RenderCtx, Widget};
+
+pub(crate) use cache::{Cached, TabOrder};
 
 type TermTree = Vec<u8>;
 type ContainerTree = Vec<[u8; 2]>;
@@ -25,10 +107,19 @@ type TextTree = Vec<[u8; 3]>;
 
 type Styles = Vec<Style>;
 
-// #[derive(Debug)]
-pub(crate) enum Property {
+/// the id of a container, [term id, container id]
+pub type ContainerId = [u8; 2];
+/// the id of an input, [term id, container id, text id]
+pub type InputId = [u8; 3];
+/// the id of a nonedit, [term id, container id, text id]
+pub type NonEditId = [u8; 3];
+
+/// the value of a component property, e.g. the name of an input's "history-group"
+/// a clone of a Fn shares its closure, and 2 Fns are only equal if they share one
+#[derive(Clone)]
+pub enum Property {
     String(String),
-    Fn(Box<dyn Fn() -> Property>),
+    Fn(Rc<dyn Fn() -> Property>),
     Range(std::ops::Range<u64>),
     Int(i64),
     UInt(u64),
@@ -61,6 +152,23 @@ impl std::fmt::Debug for Property {
     }
 }
 
+impl PartialEq for Property {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::String(s), Self::String(o)) => s == o,
+            (Self::Fn(f), Self::Fn(o)) => Rc::ptr_eq(f, o),
+            (Self::Range(r), Self::Range(o)) => r == o,
+            (Self::Int(i), Self::Int(o)) => i == o,
+            (Self::UInt(u), Self::UInt(o)) => u == o,
+            (Self::Float(f), Self::Float(o)) => f == o,
+            (Self::Bool(b), Self::Bool(o)) => b == o,
+            (Self::Vec(v), Self::Vec(o)) => v == o,
+            (Self::Map(m), Self::Map(o)) => m == o,
+            _ => false,
+        }
+    }
+}
+
 impl Property {
     pub fn string(s: &str) -> Self {
         Self::String(s.to_string())
@@ -419,39 +527,223 @@ impl Property {
 #[derive(Debug)]
 pub struct ComponentTree {
     /// the term bufs collection
-    terms: Vec<Term>,
-    /// term that is now on focus
-    term: u8,
+    pub(crate) terms: Vec<Term>,
+    /// term that is now on focus, None once it was removed
+    pub(crate) term: Option<u8>,
+    // the term the last ComponentTree::render drew
+    pub(crate) drawn: Option<u8>,
     /// window size of the terminal window
     ws: winsize,
 }
 
-#[derive(Debug)]
+/// errors about the id given to a new component
+/// an id is 1 byte long for a term, 2 for a container and 3 for a text
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum IdError {
-    IdAlreadyTaken,
-    ParentIdNotFound,
-    NotAnInputId,
-    NotANonEditId,
+    /// another component of the same kind already has the id
+    IdAlreadyTaken { id: Vec<u8> },
+    /// there is no parent with the first bytes of the id, e.g. no container for a text
+    ParentIdNotFound { id: Vec<u8> },
+    /// not returned anymore, the kind of a text is not taken from its id, see TextKind
+    #[deprecated(note = "see ComponentTreeError::WrongKindForId")]
+    NotAnInputId { id: [u8; 3] },
+    /// not returned anymore, the kind of a text is not taken from its id, see TextKind
+    #[deprecated(note = "see ComponentTreeError::WrongKindForId")]
+    NotANonEditId { id: [u8; 3] },
 }
 
 /// errors for ComponentTree operations
-#[derive(Debug)]
+/// the methods adding components, the push_* ones and the *_from_meta ones return the same
+/// variant for the same cause
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ComponentTreeError {
-    /// id errors
+    /// another component of the same kind already has the id
+    DuplicateId { id: Vec<u8> },
+    /// there is no parent with the first bytes of the id, e.g. no container for a text
+    ParentNotFound { id: Vec<u8> },
+    /// the text with id is not of the kind expected, input is whether an input was expected,
+    /// e.g. a nonedit given to Term::push_input
+    WrongKindForId { id: [u8; 3], input: bool },
+    /// the [w, h] area of the component does not fit in the parent's available [w, h]
+    AreaOutOfBounds {
+        requested: [u16; 2],
+        available: [u16; 2],
+    },
+    /// the [x, y] origin puts the component, or part of it, outside of the parent's
+    /// available [w, h]
+    OriginOutOfBounds {
+        origin: [u16; 2],
+        available: [u16; 2],
+    },
+    /// the component takes some of the area of the component with id, on the same layer
+    AreaTaken { id: Vec<u8> },
+    /// the border and padding of the component with id don't fit its area
+    DecorationDoesNotFit { id: Vec<u8> },
+    /// the value given to a text has more cells than the text's area
+    BadValue { len: usize, available: u16 },
+    /// the patch op at index op reaches past the len chars the value had when it came,
+    /// see Text::apply_patch
+    PatchOutOfRange { op: usize, len: usize },
+    /// there is no component with the id, or it can't be used here
+    BadID { id: Vec<u8> },
+    /// no text is focused
+    NothingFocused,
+    /// a Term has to be at least 1x1, area is the [w, h] it was given
+    EmptyArea { area: [u16; 2] },
+    /// the size of the terminal window could not be detected, see winsize::detect_terminal
+    WindowSize(WinsizeError),
+    /// the text with id can't be focused, it is hidden, in a hidden container,
+    /// or it is not focusable, see Text::set_focusable
+    NotFocusable { id: [u8; 3] },
+    /// the text with id can't be focused while it is disabled, see Term::set_disabled
+    Disabled { id: [u8; 3] },
+    /// the text with id is outside of the container the focus is confined to,
+    /// see Term::push_focus_scope
+    OutOfFocusScope { id: [u8; 3], scope: [u8; 2] },
+    /// id errors, not returned anymore, the id variants replace it
+    #[deprecated(note = "use DuplicateId, ParentNotFound or WrongKindForId")]
     IdError(IdError),
-    /// space errors
+    /// space errors, not returned anymore, the space variants replace it
+    #[deprecated(note = "use AreaOutOfBounds, OriginOutOfBounds or AreaTaken")]
     SpaceError(SpaceError),
-    /// id errors
-    /// Obscure error; something about some id somewhere went wrong
-    BadID,
-    ///
-    BadValue,
-    /// when trying to assugn an ID that has already been assigned prior to this
-    IDAlreadyExists,
-    /// the parent object of some object that is being operated on was not found in this tree
-    ParentNotFound,
-    /// the space bounds rules were broken by some object trying to join this tree
-    BoundsNotRespected,
+    /// not returned anymore, DuplicateId replaces it
+    #[deprecated(note = "use DuplicateId")]
+    IDAlreadyExists { id: Vec<u8> },
+    /// not returned anymore, the space variants replace it
+    #[deprecated(
+        note = "use AreaOutOfBounds, OriginOutOfBounds, AreaTaken or DecorationDoesNotFit"
+    )]
+    BoundsNotRespected { id: Vec<u8> },
+}
+
+#[allow(deprecated)]
+impl std::fmt::Display for IdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IdAlreadyTaken { id } => write!(f, "the id {:?} is already taken", id),
+            Self::ParentIdNotFound { id } => write!(f, "there is no parent with the id {:?}", id),
+            Self::NotAnInputId { id } => write!(f, "{:?} is not an input", id),
+            Self::NotANonEditId { id } => write!(f, "{:?} is not a nonedit", id),
+        }
+    }
+}
+
+impl std::error::Error for IdError {}
+
+impl std::fmt::Display for SpaceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AreaOutOfBounds {
+                requested: [w, h],
+                available: [aw, ah],
+            } => write!(f, "a {}x{} area does not fit in the available {}x{}", w, h, aw, ah),
+            Self::OriginOutOfBounds {
+                origin: [x, y],
+                available: [aw, ah],
+            } => write!(f, "the origin [{}, {}] is out of the available {}x{}", x, y, aw, ah),
+            Self::AreaTaken { id } => write!(f, "the area is taken by the component {:?}", id),
+        }
+    }
+}
+
+impl std::error::Error for SpaceError {}
+
+#[allow(deprecated)]
+impl std::fmt::Display for ComponentTreeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DuplicateId { id } => write!(f, "the id {:?} is already taken", id),
+            Self::ParentNotFound { id } => write!(f, "there is no parent with the id {:?}", id),
+            Self::WrongKindForId { id, input: true } => write!(f, "{:?} is not an input", id),
+            Self::WrongKindForId { id, input: false } => write!(f, "{:?} is not a nonedit", id),
+            Self::AreaOutOfBounds {
+                requested: [w, h],
+                available: [aw, ah],
+            } => write!(f, "a {}x{} area does not fit in the available {}x{}", w, h, aw, ah),
+            Self::OriginOutOfBounds {
+                origin: [x, y],
+                available: [aw, ah],
+            } => write!(f, "the origin [{}, {}] is out of the available {}x{}", x, y, aw, ah),
+            Self::AreaTaken { id } => write!(f, "the area is taken by the component {:?}", id),
+            Self::DecorationDoesNotFit { id } => write!(
+                f,
+                "the border and padding of the component {:?} do not fit its area",
+                id
+            ),
+            Self::BadValue { len, available } => write!(
+                f,
+                "a value of {} cells does not fit in the {} cells of the text",
+                len, available
+            ),
+            Self::PatchOutOfRange { op, len } => {
+                write!(f, "the patch op {} reaches past the {} chars of the value", op, len)
+            }
+            Self::BadID { id } => write!(f, "no component with the id {:?} can be used here", id),
+            Self::NothingFocused => write!(f, "no text is focused"),
+            Self::EmptyArea { area } => write!(
+                f,
+                "a {}x{} term has no cells, a term is at least 1x1",
+                area[0], area[1]
+            ),
+            Self::WindowSize(_) => write!(f, "the terminal window size could not be detected"),
+            Self::NotFocusable { id } => write!(f, "the text {:?} can't be focused", id),
+            Self::Disabled { id } => write!(f, "the text {:?} is disabled", id),
+            Self::OutOfFocusScope { id, scope } => write!(
+                f,
+                "the text {:?} is outside of the focus scope {:?}",
+                id, scope
+            ),
+            // the nested errors tell the rest, see Error::source
+            Self::IdError(_) => write!(f, "bad component id"),
+            Self::SpaceError(_) => write!(f, "the component does not fit in its parent"),
+            Self::IDAlreadyExists { id } => write!(f, "the id {:?} already exists", id),
+            Self::BoundsNotRespected { id } => {
+                write!(f, "the component {:?} does not fit where it was to go", id)
+            }
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl std::error::Error for ComponentTreeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IdError(e) => Some(e),
+            Self::SpaceError(e) => Some(e),
+            Self::WindowSize(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl From<IdError> for ComponentTreeError {
+    fn from(e: IdError) -> Self {
+        match e {
+            IdError::IdAlreadyTaken { id } => Self::DuplicateId { id },
+            IdError::ParentIdNotFound { id } => Self::ParentNotFound { id },
+            IdError::NotAnInputId { id } => Self::WrongKindForId { id, input: true },
+            IdError::NotANonEditId { id } => Self::WrongKindForId { id, input: false },
+        }
+    }
+}
+
+impl From<SpaceError> for ComponentTreeError {
+    fn from(e: SpaceError) -> Self {
+        match e {
+            SpaceError::AreaOutOfBounds {
+                requested,
+                available,
+            } => Self::AreaOutOfBounds {
+                requested,
+                available,
+            },
+            SpaceError::OriginOutOfBounds { origin, available } => {
+                Self::OriginOutOfBounds { origin, available }
+            }
+            SpaceError::AreaTaken { id } => Self::AreaTaken { id },
+        }
+    }
 }
 
 impl ComponentTree {
@@ -463,11 +755,12 @@ impl ComponentTree {
     /// ```
     /// this automatically creates a new Term with the id value of 0 inside this new Tree
     pub fn new() -> Self {
-        let ws = winsize::from_ioctl();
+        let ws = crate::console::winsize::detect().unwrap_or_else(|_| winsize::fallback());
 
         Self {
             terms: vec![Term::new(0, ws.cols(), ws.rows())],
-            term: 0,
+            term: Some(0),
+            drawn: None,
             ws,
         }
     }
@@ -496,24 +789,38 @@ impl ComponentTree {
     ///
     pub fn push(&mut self, term: Term) -> Result<(), (Term, ComponentTreeError)> {
         if self.has_term(term.id) {
-            return Err((term, ComponentTreeError::IDAlreadyExists));
+            let id = vec![term.id];
+            return Err((term, ComponentTreeError::DuplicateId { id }));
         }
         self.terms.push(term);
 
         Ok(())
     }
 
-    // removes the term with the given id  from this component tree and returns it
-    // returns None if such a term does not exist
-    pub fn pull(&mut self, id: u8) -> Option<Term> {
-        if self.has_term(id) {
-            return Some(
-                self.terms
-                    .remove(self.terms.iter().position(|t| t.id == id).unwrap()),
-            );
+    /// removes the term with the given id from this component tree and returns it
+    /// the tree has no active term after the active one is removed, see ComponentTree::set_active
+    ///
+    /// # Errors
+    /// returns BadID if there is no term with the id in this tree
+    pub fn remove_term(&mut self, id: u8) -> Result<Term, ComponentTreeError> {
+        let Some(idx) = self.terms.iter().position(|t| t.id == id) else {
+            return Err(ComponentTreeError::BadID { id: vec![id] });
+        };
+        if self.term == Some(id) {
+            self.term = None;
         }
+        if self.drawn == Some(id) {
+            self.drawn = None;
+        }
+
+        Ok(self.terms.remove(idx))
+    }
 
-        None
+    /// removes the term with the given id from this component tree and returns it
+    /// returns None if such a term does not exist
+    #[deprecated(note = "use ComponentTree::remove_term")]
+    pub fn pull(&mut self, id: u8) -> Option<Term> {
+        self.remove_term(id).ok()
     }
 
     pub fn put(&mut self, term: Term, idx: usize) {
@@ -528,45 +835,62 @@ impl ComponentTree {
     /// Term in this tree
     pub fn term(&mut self, id: u8) -> Result<(), ComponentTreeError> {
         if self.has_term(id) {
-            eprintln!("bad id");
-            return Err(ComponentTreeError::IdError(IdError::IdAlreadyTaken));
+            return Err(IdError::IdAlreadyTaken { id: vec![id] }.into());
         }
         self.terms
-            .push(Term::new(id, self.ws.cols(), self.ws.rows()));
+            .push(Term::try_new(id, self.ws.cols(), self.ws.rows())?);
 
         Ok(())
     }
 
-    /// returns the active Term object id
-    pub fn active(&self) -> u8 {
+    /// returns the active Term object id, None if the active term was removed
+    pub fn active_id(&self) -> Option<u8> {
         self.term
     }
 
+    /// returns a reference to the active Term, see ComponentTree::set_active
+    pub fn active(&self) -> Option<&Term> {
+        self.term_ref(self.term?)
+    }
+
+    /// returns a mutable reference to the active Term
+    pub fn active_mut(&mut self) -> Option<&mut Term> {
+        self.term_mut(self.term?)
+    }
+
     /// changes the active Term of this tree
-    /// the active term is the term that gets rendered
+    /// the active term is the term that gets rendered, see ComponentTree::render
+    /// every term keeps its own focus and cursor, switching back to a term gives them back
     ///
     /// # Errors
     ///
     /// returns an error if a Term with the provided id does not exist in this tree
-    pub fn focus(&mut self, id: u8) -> Result<(), ComponentTreeError> {
+    pub fn set_active(&mut self, id: u8) -> Result<(), ComponentTreeError> {
         if self.has_term(id) {
-            self.term = id;
+            self.term = Some(id);
 
             return Ok(());
         }
 
-        Err(ComponentTreeError::BadID)
+        Err(ComponentTreeError::BadID { id: vec![id] })
+    }
+
+    /// changes the active Term of this tree, see ComponentTree::set_active
+    #[deprecated(note = "use ComponentTree::set_active")]
+    pub fn focus(&mut self, id: u8) -> Result<(), ComponentTreeError> {
+        self.set_active(id)
     }
 
     /// takes no id and automatically assigns an id while adding a new Term
-    /// returns the new term id
-    pub fn term_auto(&mut self) -> u8 {
-        let id = self.assign_term_id();
+    /// returns the new term id, the smallest one that is free,
+    /// or None without adding a Term when all 256 term ids are taken or the window has no cells
+    pub fn term_auto(&mut self) -> Option<u8> {
+        let id = self.assign_term_id()?;
 
         self.terms
-            .push(Term::new(id, self.ws.cols(), self.ws.rows()));
+            .push(Term::try_new(id, self.ws.cols(), self.ws.rows()).ok()?);
 
-        id
+        Some(id)
     }
 
     pub fn term_from_meta(&mut self, meta: &mut TermMeta) {
@@ -583,6 +907,68 @@ impl ComponentTree {
         self.terms.iter_mut().find(|t| t.id == id)
     }
 
+    /// returns an iterator over the terms of this tree, in the order they were added
+    pub fn terms(&self) -> impl Iterator<Item = &Term> {
+        self.terms.iter()
+    }
+
+    /// returns an iterator over mutable references to the terms of this tree
+    pub fn terms_mut(&mut self) -> impl Iterator<Item = &mut Term> {
+        self.terms.iter_mut()
+    }
+
+    /// returns the number of terms inside this tree
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// returns whether this tree has no terms left
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// returns a reference to the container with the given id in whichever term of this tree
+    /// has it, the first byte of a component id is the id of its term
+    pub fn find_container(&self, id: &[u8; 2]) -> Option<&Container> {
+        self.term_ref(id[0])?.container_ref(id)
+    }
+
+    /// returns a mutable reference to the container with the given id, see find_container
+    pub fn find_container_mut(&mut self, id: &[u8; 2]) -> Option<&mut Container> {
+        self.term_mut(id[0])?.container_mut(id)
+    }
+
+    /// returns a reference to the input or nonedit with the given id in whichever term of this
+    /// tree has it
+    pub fn find_text(&self, id: &[u8; 3]) -> Option<&Text> {
+        self.term_ref(id[0])?.text_ref(id)
+    }
+
+    /// returns a mutable reference to the input or nonedit with the given id, see find_text
+    pub fn find_text_mut(&mut self, id: &[u8; 3]) -> Option<&mut Text> {
+        self.term_mut(id[0])?.text_mut(id)
+    }
+
+    /// returns the number of containers inside all the terms of this tree
+    pub fn clen(&self) -> usize {
+        self.terms.iter().map(|t| t.clen()).sum()
+    }
+
+    /// returns the number of text objects inside all the terms of this tree
+    pub fn tlen(&self) -> usize {
+        self.terms.iter().map(|t| t.tlen()).sum()
+    }
+
+    /// returns the number of inputs inside all the terms of this tree
+    pub fn ilen(&self) -> usize {
+        self.terms.iter().map(|t| t.ilen()).sum()
+    }
+
+    /// returns the number of noneditables inside all the terms of this tree
+    pub fn nelen(&self) -> usize {
+        self.terms.iter().map(|t| t.nelen()).sum()
+    }
+
     // methods of the has_object series do not check for duplicate ids
     // because those are already being screened by earlier id assignment methods
     // and there is no way in the api to bypass those checks and push an object to the tree
@@ -592,17 +978,10 @@ impl ComponentTree {
         self.terms.iter().find(|t| t.id == term).is_some()
     }
 
-    fn assign_term_id(&self) -> u8 {
-        let mut id = 0;
-        for term in &self.terms {
-            if term.id == id {
-                id += 1;
-            } else {
-                break;
-            }
-        }
-
-        id
+    /// returns the smallest term id that is free in this tree, the terms don't have to be in
+    /// order of id, None once all 256 term ids are taken
+    pub fn assign_term_id(&self) -> Option<u8> {
+        checks::smallest_free(self.terms.iter().map(|t| t.id), 0..=u8::MAX)
     }
 
     // FIXME: this has to also resize all children
@@ -612,22 +991,32 @@ impl ComponentTree {
         let [cols, rows] = [ws.cols(), ws.rows()];
 
         self.ws = ws;
-        self.terms.iter_mut().for_each(|t| {
-            t.w = cols;
-            t.h = rows;
-        });
+        self.terms.iter_mut().for_each(|t| t.resize(cols, rows));
     }
 }
 
-#[derive(Debug)]
+/// errors about where a component goes
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SpaceError {
-    AreaOutOfBounds,
-    OriginOutOfBounds,
+    /// the [w, h] area that was asked for does not fit in the parent's available [w, h]
+    AreaOutOfBounds {
+        requested: [u16; 2],
+        available: [u16; 2],
+    },
+    /// the [x, y] origin puts the component, or part of it, outside of the parent's
+    /// available [w, h]
+    OriginOutOfBounds {
+        origin: [u16; 2],
+        available: [u16; 2],
+    },
+    /// the component takes some of the area of the component with id, on the same layer
+    AreaTaken { id: Vec<u8> },
 }
 
 #[cfg(test)]
-mod tree {
-    use super::{ComponentTree, ComponentTreeError, Term};
+mod component_tree {
+    use super::{ComponentTree, ComponentTreeError, Container, Term, Text};
+    use crate::space::{border::Border, padding::Padding};
 
     #[test]
     fn active() {
@@ -639,11 +1028,27 @@ mod tree {
 
         assert_eq!(tree.terms.len(), 2);
 
-        assert_eq!(tree.active(), 0);
-        tree.focus(3);
-        assert_eq!(tree.active(), 0);
-        tree.focus(7);
-        assert_eq!(tree.active(), 7);
+        assert_eq!(tree.active_id(), Some(0));
+        assert_eq!(
+            tree.set_active(3),
+            Err(ComponentTreeError::BadID { id: vec![3] })
+        );
+        assert_eq!(tree.active_id(), Some(0));
+        tree.set_active(7);
+        assert_eq!(tree.active_id(), Some(7));
+        assert_eq!(tree.active().unwrap().id, 7);
+        tree.active_mut().unwrap().focused = Some([7, 0, 0]);
+        assert_eq!(tree.term_ref(7).unwrap().focused, Some([7, 0, 0]));
+
+        // removing the active term leaves the tree with none
+        assert_eq!(tree.remove_term(7).unwrap().id, 7);
+        assert_eq!(
+            tree.remove_term(7).unwrap_err(),
+            ComponentTreeError::BadID { id: vec![7] }
+        );
+        assert_eq!(tree.active_id(), None);
+        assert!(tree.active().is_none());
+        assert_eq!(tree.terms.len(), 1);
     }
 
     #[test]
@@ -657,7 +1062,104 @@ mod tree {
         tree.term(1);
         tree.term(2);
         tree.term(4);
-        assert_eq!(tree.assign_term_id(), 3);
+        assert_eq!(tree.assign_term_id(), Some(3));
+        assert_eq!(tree.term_auto(), Some(3));
+        assert_eq!(tree.term_auto(), Some(5));
+    }
+
+    // the terms 0, 2 and 5, term 2 with a container holding an input and a nonedit
+    // and term 5 with an empty container
+    fn gaps() -> ComponentTree {
+        let mut tree = ComponentTree::new();
+        tree.term(5).unwrap();
+        tree.term(2).unwrap();
+        let mut c = Container::new([2, 1], 0, 0, 4, 2, Border::None, Padding::None);
+        c.items
+            .push(Text::input([2, 1, 4], 0, 0, 1, 1, 2, 1, &[], Border::None, Padding::None));
+        c.items
+            .push(Text::nonedit([2, 1, 7], 0, 1, 1, 2, 2, 1, &[], Border::None, Padding::None));
+        tree.term_mut(2).unwrap().containers.push(c);
+        let c = Container::new([5, 0], 0, 0, 4, 2, Border::None, Padding::None);
+        tree.term_mut(5).unwrap().containers.push(c);
+
+        tree
+    }
+
+    #[test]
+    fn queries() {
+        let mut tree = gaps();
+        assert_eq!(tree.len(), 3);
+        assert!(!tree.is_empty());
+        assert_eq!(tree.terms().map(|t| t.id).collect::<Vec<u8>>(), [0, 5, 2]);
+        assert!(tree.has_term(5));
+        assert!(!tree.has_term(1));
+        assert_eq!(tree.assign_term_id(), Some(1));
+
+        assert_eq!(
+            [tree.clen(), tree.tlen(), tree.ilen(), tree.nelen()],
+            [2, 2, 1, 1]
+        );
+        assert_eq!(tree.find_container(&[5, 0]).unwrap().id, [5, 0]);
+        assert!(tree.find_container(&[2, 0]).is_none());
+        assert!(tree.find_container(&[3, 0]).is_none());
+        assert!(tree.find_text(&[2, 1, 4]).unwrap().is_input());
+        assert!(!tree.find_text(&[2, 1, 7]).unwrap().is_input());
+        // the first byte picks the term
+        assert!(tree.find_text(&[5, 1, 4]).is_none());
+        assert!(tree.find_text(&[1, 1, 4]).is_none());
+        tree.find_text_mut(&[2, 1, 7]).unwrap().replace_value("ok");
+        assert_eq!(
+            tree.term_ref(2).unwrap().nonedit_ref(&[2, 1, 7]).unwrap().value_string(),
+            "ok"
+        );
+        tree.find_container_mut(&[5, 0]).unwrap().hide();
+        assert!(tree.find_container(&[5, 0]).unwrap().is_hidden());
+
+        tree.terms_mut().for_each(|t| t.focused = Some([t.id, 0, 0]));
+        assert!(tree.terms().all(|t| t.focused == Some([t.id, 0, 0])));
+        tree.remove_term(0).unwrap();
+        assert_eq!(tree.assign_term_id(), Some(0));
+        assert_eq!(tree.term_auto(), Some(0));
+        assert_eq!(tree.assign_term_id(), Some(1));
+        tree.terms = vec![];
+        assert!(tree.is_empty());
+        assert_eq!([tree.clen(), tree.tlen()], [0, 0]);
+    }
+
+    // the ids that are handed out are the smallest free ones, whatever the order of the items
+    #[test]
+    fn free_ids() {
+        let mut term = Term::new(0, 40, 10);
+        for cid in [2, 0, 1] {
+            term.containers.push(Container::new([0, cid], 0, 0, 1, 1, Border::None, Padding::None));
+        }
+        assert_eq!(term.free_container_id(), Some([0, 3]));
+        term.containers.retain(|c| c.id != [0, 0]);
+        assert_eq!(term.free_container_id(), Some([0, 0]));
+
+        let cont = term.container_mut(&[0, 2]).unwrap();
+        let text = |id| Text::input(id, 0, 0, 1, 0, 1, 1, &[], Border::None, Padding::None);
+        for iid in [4, 0, 3, 1] {
+            cont.items.push(text([0, 2, iid]));
+        }
+        assert_eq!(cont.free_text_id(), Some([0, 2, 2]));
+        cont.items.retain(|t| t.id != [0, 2, 1]);
+        assert_eq!(cont.free_text_id(), Some([0, 2, 1]));
+
+        // the kind of the texts doesn't matter
+        cont.items = (0..u8::MAX).map(|iid| text([0, 2, iid])).collect();
+        assert_eq!(cont.free_text_id(), Some([0, 2, u8::MAX]));
+        cont.items.push(text([0, 2, u8::MAX]));
+        assert_eq!(cont.free_text_id(), None);
+        term.containers = (0..=u8::MAX)
+            .rev()
+            .map(|cid| Container::new([0, cid], 0, 0, 1, 1, Border::None, Padding::None))
+            .collect();
+        assert_eq!(term.free_container_id(), None);
+        let mut tree = ComponentTree::new();
+        while tree.term_auto().is_some() {}
+        assert_eq!(tree.terms.len(), 256);
+        assert_eq!(tree.term_auto(), None);
     }
 }
 
@@ -728,7 +1230,7 @@ mod test_term {
         assert_eq!(term.focused().unwrap(), [11, 9]);
     }
 
-    use crate::space::{border::Border, padding::Padding, Area, Pos};
+    use crate::space::{border::Border, padding::Padding, Area, Polygon, Pos};
 
     #[test]
     fn cursor() {
@@ -737,6 +1239,7 @@ mod test_term {
             &[0, 0],
             Pos::Value(56),
             Pos::Value(15),
+            Polygon::Free { vertices: vec![] },
             Area::Values { w: 35, h: 8 },
             Border::None,
             Padding::None,
@@ -745,6 +1248,7 @@ mod test_term {
             &[0, 0, 0],
             Pos::Value(1),
             Pos::Value(1),
+            Polygon::Free { vertices: vec![] },
             Area::Values { w: 23, h: 2 },
             Border::None,
             Padding::None,
@@ -762,6 +1266,7 @@ mod test_term {
             &[0, 1],
             Pos::Value(56),
             Pos::Value(15),
+            Polygon::Free { vertices: vec![] },
             Area::Values { w: 35, h: 18 },
             Border::None,
             Padding::None,
@@ -772,6 +1277,7 @@ mod test_term {
             &[0, 1, 1],
             Pos::Value(12),
             Pos::Value(12),
+            Polygon::Free { vertices: vec![] },
             Area::Values { w: 2, h: 2 },
             Border::None,
             Padding::None,
@@ -791,6 +1297,7 @@ mod test_term {
             &[0, 1],
             Pos::Value(56),
             Pos::Value(15),
+            Polygon::Free { vertices: vec![] },
             Area::Values { w: 35, h: 18 },
             Border::None,
             Padding::None,
@@ -801,6 +1308,7 @@ mod test_term {
             &[0, 1, 1],
             Pos::Value(12),
             Pos::Value(12),
+            Polygon::Free { vertices: vec![] },
             Area::Values { w: 2, h: 2 },
             Border::None,
             Padding::None,
@@ -818,6 +1326,7 @@ mod test_term {
             &[0, 0],
             Pos::Value(5),
             Pos::Value(5),
+            Polygon::Free { vertices: vec![] },
             Area::Values { w: 10, h: 10 },
             Border::None,
             Padding::None,
@@ -826,6 +1335,7 @@ mod test_term {
             &[0, 1],
             Pos::Value(15),
             Pos::Value(15),
+            Polygon::Free { vertices: vec![] },
             Area::Values { w: 10, h: 10 },
             Border::None,
             Padding::None,
@@ -834,6 +1344,7 @@ mod test_term {
             &[0, 2],
             Pos::Value(25),
             Pos::Value(25),
+            Polygon::Free { vertices: vec![] },
             Area::Values { w: 10, h: 10 },
             Border::None,
             Padding::None,
@@ -843,6 +1354,7 @@ mod test_term {
             &[0, 2, 0],
             Pos::Value(1),
             Pos::Value(2),
+            Polygon::Free { vertices: vec![] },
             Area::Values { w: 2, h: 2 },
             Border::None,
             Padding::None,
@@ -851,6 +1363,7 @@ mod test_term {
             &[0, 1, 1],
             Pos::Value(2),
             Pos::Value(2),
+            Polygon::Free { vertices: vec![] },
             Area::Values { w: 2, h: 2 },
             Border::None,
             Padding::None,
@@ -861,6 +1374,7 @@ mod test_term {
             &[0, 0, 1],
             Pos::Value(1),
             Pos::Value(1),
+            Polygon::Free { vertices: vec![] },
             Area::Values { w: 2, h: 2 },
             Border::None,
             Padding::None,
@@ -884,4 +1398,1213 @@ mod test_term {
 mod test_container {}
 
 #[cfg(test)]
-mod test_text {}
+mod test_text {
+    use super::{Container, SpaceError, Term, Text};
+    use crate::space::{border::Border, padding::Padding};
+
+    // a focused 10x4 input at [3, 2] inside a bordered container
+    fn term() -> Term {
+        let mut term = Term::new(0, 30, 10);
+        let mut c = Container::new([0, 0], 2, 1, 12, 6, Border::Uniform('#'), Padding::None);
+        c.items.push(Text::input([0, 0, 0], 0, 0, 3, 2, 10, 4, &[], Border::None, Padding::None));
+        term.containers.push(c);
+        _ = term.focus(&[0, 0, 0]);
+
+        term
+    }
+
+    // whether the terminal cursor is on a cell of the focused text
+    fn inside(term: &Term) -> bool {
+        let text = term.input_ref(&[0, 0, 0]).unwrap();
+        (text.ax0..text.ax0 + text.w).contains(&term.cx)
+            && (text.ay0..text.ay0 + text.h).contains(&term.cy)
+    }
+
+    #[test]
+    fn cursor() {
+        let mut term = term();
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        assert_eq!(
+            text.set_cursor(10, 0),
+            Err(SpaceError::OriginOutOfBounds {
+                origin: [10, 0],
+                available: [10, 4]
+            })
+        );
+        text.set_cursor(9, 3).unwrap();
+        assert!(!text.move_cursor(1, 1));
+        assert!(text.move_cursor(-20, -1));
+        assert_eq!([text.cx, text.cy], [0, 2]);
+        assert!(text.move_up());
+        assert_eq!(text.cy, 1);
+        // a cursor left outside of the area is brought back in first
+        text.cx = 40;
+        assert!(text.move_home());
+        assert_eq!(text.cx, 0);
+    }
+
+    #[test]
+    fn clamped() {
+        let mut term = term();
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        [text.cx, text.cy] = [25, 7];
+        term.sync_cursor().unwrap();
+        assert!(inside(&term));
+        assert_eq!([term.cx, term.cy], [3 + 9, 2 + 3]);
+
+        // the text shrinks under its cursor
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        text.set_cursor(8, 3).unwrap();
+        [text.w, text.h] = [4, 2];
+        term.resize(20, 8);
+        let text = term.input_ref(&[0, 0, 0]).unwrap();
+        assert_eq!([text.cx, text.cy], [3, 1]);
+        term.sync_cursor().unwrap();
+        assert!(inside(&term));
+
+        // scrolled, the cursor is shown where its line is
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        [text.h, text.cy, text.scroll] = [4, 3, 2];
+        term.sync_cursor().unwrap();
+        assert_eq!(term.cy, 2 + 1);
+        assert!(inside(&term));
+    }
+}
+
+#[cfg(test)]
+mod hit_test {
+    use super::{Container, Scrollbar, Term, Text};
+    use crate::inputs::keyboard::{Modifiers, SHIFT};
+    use crate::inputs::mouse::{MouseButton, MouseEvent, MouseKind};
+    use crate::space::{border::Border, padding::Padding};
+
+    // a bordered container at [2, 1] holding a 4x2 input and a bordered 4x1 nonedit next to it
+    fn term() -> Term {
+        let mut term = Term::new(0, 30, 8);
+        let mut c = Container::new([0, 0], 2, 1, 12, 4, Border::Uniform('#'), Padding::None);
+        c.items.push(Text::input([0, 0, 0], 0, 0, 0, 0, 4, 2, &[], Border::None, Padding::None));
+        c.items.push(Text::nonedit(
+            [0, 0, 1],
+            5,
+            0,
+            0,
+            0,
+            4,
+            1,
+            &[],
+            Border::Uniform('*'),
+            Padding::None,
+        ));
+        term.containers.push(c);
+
+        term
+    }
+
+    fn event(kind: MouseKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            button: MouseButton::Left,
+            column,
+            row,
+            modifiers: Modifiers(0),
+        }
+    }
+
+    #[test]
+    fn component_at() {
+        let term = term();
+        // the container value area starts at [3, 2]
+        assert_eq!(term.component_at(3, 2), Some([0, 0, 0]));
+        assert_eq!(term.component_at(6, 3), Some([0, 0, 0]));
+        assert_eq!(term.component_at(7, 2), None);
+        // the nonedit border counts as part of it
+        assert_eq!(term.component_at(8, 2), Some([0, 0, 1]));
+        assert_eq!(term.component_at(14, 4), None);
+        // the container border
+        assert_eq!(term.component_at(2, 1), None);
+    }
+
+    #[test]
+    fn click_to_focus() {
+        let mut term = term();
+        assert_eq!(term.mouse(&event(MouseKind::Down, 4, 3)), Some([0, 0, 0]));
+        assert_eq!(term.focused, Some([0, 0, 0]));
+
+        // nonedits don't take the focus
+        term.mouse(&event(MouseKind::Down, 9, 3));
+        assert_eq!(term.focused, Some([0, 0, 0]));
+    }
+
+    #[test]
+    fn wheel() {
+        let mut term = term();
+        term.input_mut(&[0, 0, 0]).unwrap().value = vec![Some('x'); 4 * 5].into();
+
+        term.wheel_lines(1);
+        term.mouse(&event(MouseKind::ScrollDown, 3, 2));
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().scroll, 1);
+        assert!(term.input_ref(&[0, 0, 0]).unwrap().attributes.contains("dirty"));
+        for _ in 0..5 {
+            term.mouse(&event(MouseKind::ScrollDown, 3, 2));
+        }
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().scroll, 3);
+        term.mouse(&event(MouseKind::ScrollUp, 3, 2));
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().scroll, 2);
+
+        // 3 lines a notch by default, clamped at the top
+        term.wheel_lines = None;
+        term.mouse(&event(MouseKind::ScrollUp, 3, 2));
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().scroll, 0);
+    }
+
+    #[test]
+    fn wheel_fallback() {
+        let mut term = term();
+        term.input_mut(&[0, 0, 0]).unwrap().value = vec![Some('x'); 4 * 5].into();
+        // over the nonedit, nothing to scroll there, nothing focused either
+        term.mouse(&event(MouseKind::ScrollDown, 9, 3));
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().scroll, 0);
+
+        term.focused = Some([0, 0, 0]);
+        term.mouse(&event(MouseKind::ScrollDown, 9, 3));
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().scroll, 3);
+        // over no text at all
+        term.mouse(&event(MouseKind::ScrollUp, 20, 7));
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().scroll, 0);
+    }
+
+    #[test]
+    fn shift_wheel() {
+        let mut term = term();
+        let input = term.input_mut(&[0, 0, 0]).unwrap();
+        input.set_scrollbar(Scrollbar::horizontal(10));
+        input.value = vec![Some('x'); 10].into();
+        let max = input.max_hscroll();
+        assert!(max > 3);
+
+        let mut scroll = event(MouseKind::ScrollDown, 3, 2);
+        scroll.modifiers = Modifiers(SHIFT);
+        term.mouse(&scroll);
+        let input = term.input_ref(&[0, 0, 0]).unwrap();
+        assert_eq!([input.hscroll, input.scroll], [3, 0]);
+        for _ in 0..5 {
+            term.mouse(&scroll);
+        }
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().hscroll, max);
+        term.mouse(&event(MouseKind::ScrollLeft, 3, 2));
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().hscroll, max - 3);
+    }
+
+    #[test]
+    fn resize() {
+        let mut term = term();
+        assert!(!term.is_dirty());
+        term.resize(40, 12);
+        assert_eq!([term.w, term.h], [40, 12]);
+        assert!(term.is_dirty());
+    }
+}
+
+#[cfg(test)]
+mod visibility {
+    use super::{ComponentTreeError, Container, Term, Text};
+    use crate::space::{border::Border, padding::Padding};
+
+    // a bordered container at [2, 1] holding 2 inputs, one next to the other
+    fn term() -> Term {
+        let mut term = Term::new(0, 30, 8);
+        let mut c = Container::new([0, 0], 2, 1, 12, 4, Border::Uniform('#'), Padding::None);
+        c.items.push(Text::input([0, 0, 0], 0, 0, 4, 2, 4, 1, &[], Border::None, Padding::None));
+        c.items.push(Text::input([0, 0, 1], 5, 0, 9, 2, 4, 1, &[], Border::None, Padding::None));
+        term.containers.push(c);
+        term.focused = Some([0, 0, 0]);
+
+        term
+    }
+
+    // a bordered container over the one of term
+    fn over() -> Container {
+        Container::new([0, 1], 4, 2, 4, 1, Border::Uniform('+'), Padding::None)
+    }
+
+    #[test]
+    fn hidden() {
+        let mut term = term();
+        let area = term.containers[0].outer_area();
+        assert_eq!(term.hide(&[0, 0]), Ok(()));
+        assert!(term.containers[0].is_hidden());
+        assert_eq!(term.uncovered, [area]);
+        assert_eq!(term.focused, None);
+        // hiding it again blanks nothing more
+        assert_eq!(term.hide(&[0, 0]), Ok(()));
+        assert_eq!(term.uncovered.len(), 1);
+
+        // the hidden container keeps its area
+        let pushed = term.push_container(over()).unwrap_err().1;
+        assert_eq!(pushed, ComponentTreeError::AreaTaken { id: vec![0, 0] });
+        let mut frame = vec![];
+        term.render_dirty_to(&mut frame).unwrap();
+        let frame = String::from_utf8(frame).unwrap();
+        assert!(frame.contains("\x1b[2;3f\x1b[14X"));
+        assert!(!frame.contains('#'));
+
+        assert_eq!(term.show(&[0, 0]), Ok(()));
+        assert!(!term.containers[0].is_hidden());
+        assert!(term.is_dirty());
+        assert_eq!(term.focus(&[0, 0, 1]), Ok(()));
+    }
+
+    #[test]
+    fn collapsed() {
+        let mut term = term();
+        assert_eq!(term.hide_collapsed(&[0, 0]), Ok(()));
+        assert!(term.containers[0].is_collapsed());
+        assert!(term.push_container(over()).is_ok());
+        term.render_dirty_to(&mut vec![]).unwrap();
+
+        // shown again under the container that took its area, both are drawn again
+        assert_eq!(term.show(&[0, 0]), Ok(()));
+        assert!(!term.containers[0].is_collapsed());
+        assert!(term.containers.iter().all(|c| c.attributes.contains("dirty")));
+    }
+
+    #[test]
+    fn texts() {
+        let mut term = term();
+        assert_eq!(term.hide(&[0, 0, 1]), Ok(()));
+        assert_eq!(term.focused, Some([0, 0, 0]));
+        assert_eq!(
+            term.focus(&[0, 0, 1]),
+            Err(ComponentTreeError::NotFocusable { id: [0, 0, 1] })
+        );
+        let text = Text::input([0, 0, 2], 5, 0, 9, 2, 4, 1, &[], Border::None, Padding::None);
+        assert!(term.push_input(text.clone()).is_err());
+        assert_eq!(term.hide_collapsed(&[0, 0, 1]), Ok(()));
+        assert!(term.push_input(text).is_ok());
+
+        assert_eq!(term.hide(&[0, 0, 0]), Ok(()));
+        assert_eq!(term.focused, None);
+        assert_eq!(term.show(&[0, 0, 1]), Ok(()));
+        let shown = term.text_ref(&[0, 0, 1]).unwrap();
+        assert!(!shown.is_hidden() && shown.attributes.contains("dirty"));
+        assert!(term.text_ref(&[0, 0, 2]).unwrap().attributes.contains("dirty"));
+
+        for id in [&[0][..], &[0, 4], &[0, 0, 9], &[0, 0, 0, 0]] {
+            let bad = ComponentTreeError::BadID { id: id.to_vec() };
+            assert_eq!(term.hide(id), Err(bad.clone()));
+            assert_eq!(term.show(id), Err(bad));
+        }
+    }
+}
+
+#[cfg(test)]
+mod errors {
+    use super::{
+        ComponentTreeError, Container, ContainerMeta, IdError, InputMeta, NonEditMeta, SpaceError,
+        Term, Text,
+    };
+    use crate::space::{border::Border, padding::Padding, Area, Polygon, Pos};
+    use std::error::Error;
+
+    fn container(term: &mut Term, id: [u8; 2], area: Area) -> Result<(), ComponentTreeError> {
+        term.container(
+            &id,
+            Pos::Value(0),
+            Pos::Value(0),
+            Polygon::Free { vertices: vec![] },
+            area,
+            Border::None,
+            Padding::None,
+        )
+    }
+
+    #[test]
+    fn payloads() {
+        let mut term = Term::new(0, 40, 20);
+        assert!(container(&mut term, [0, 0], Area::Values { w: 10, h: 5 }).is_ok());
+        let taken = container(&mut term, [0, 0], Area::Values { w: 10, h: 5 }).unwrap_err();
+        assert_eq!(taken, ComponentTreeError::DuplicateId { id: vec![0, 0] });
+        assert_eq!(taken.to_string(), "the id [0, 0] is already taken");
+        assert!(taken.source().is_none());
+
+        let input = term.input(
+            &[0, 0, 1],
+            Pos::Value(0),
+            Pos::Value(0),
+            Polygon::Free { vertices: vec![] },
+            Area::Values { w: 2, h: 1 },
+            Border::None,
+            Padding::None,
+        );
+        // an odd id makes an input as well as an even one
+        assert_eq!(input, Ok(()));
+        assert!(term.input_ref(&[0, 0, 1]).is_some());
+        assert!(term.nonedit_ref(&[0, 0, 1]).is_none());
+        assert_eq!(
+            term.push_container(Container::default()).unwrap_err().1,
+            ComponentTreeError::DuplicateId { id: vec![0, 0] }
+        );
+        assert_eq!(term.focused(), Err(ComponentTreeError::NothingFocused));
+    }
+
+    #[test]
+    fn messages() {
+        let area = SpaceError::AreaOutOfBounds {
+            requested: [30, 4],
+            available: [20, 10],
+        };
+        assert_eq!(
+            area.to_string(),
+            "a 30x4 area does not fit in the available 20x10"
+        );
+        let flat = ComponentTreeError::from(area.clone());
+        assert_eq!(
+            flat,
+            ComponentTreeError::AreaOutOfBounds {
+                requested: [30, 4],
+                available: [20, 10]
+            }
+        );
+        assert_eq!(flat.to_string(), area.to_string());
+        assert!(flat.source().is_none());
+        assert_eq!(
+            ComponentTreeError::WrongKindForId {
+                id: [0, 0, 2],
+                input: false
+            }
+            .to_string(),
+            "[0, 0, 2] is not a nonedit"
+        );
+        let value = ComponentTreeError::BadValue {
+            len: 12,
+            available: 10,
+        };
+        assert!(value.source().is_none());
+        assert_eq!(
+            value.to_string(),
+            "a value of 12 cells does not fit in the 10 cells of the text"
+        );
+        // boxes into dyn Error chains
+        let boxed: Box<dyn Error> = Box::new(value);
+        assert!(boxed.to_string().starts_with("a value"));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated() {
+        // the old variants are still there to match on, they keep their messages and sources
+        let nested = ComponentTreeError::IdError(IdError::IdAlreadyTaken { id: vec![0, 0] });
+        assert_eq!(nested.to_string(), "bad component id");
+        assert_eq!(
+            nested.source().unwrap().to_string(),
+            "the id [0, 0] is already taken"
+        );
+        assert_eq!(
+            ComponentTreeError::IDAlreadyExists { id: vec![0, 0] }.to_string(),
+            "the id [0, 0] already exists"
+        );
+    }
+
+    // every way of adding a component fails with the same variant for the same cause
+    #[test]
+    fn insertion() {
+        let mut term = Term::new(0, 20, 10);
+        assert!(container(&mut term, [0, 0], Area::Values { w: 10, h: 5 }).is_ok());
+        let duplicate = ComponentTreeError::DuplicateId { id: vec![0, 0] };
+        let wide = Area::Values { w: 30, h: 5 };
+
+        // containers
+        assert_eq!(
+            container(&mut term, [0, 0], Area::Values { w: 1, h: 1 }),
+            Err(duplicate.clone())
+        );
+        assert!(matches!(
+            container(&mut term, [0, 1], wide.clone()),
+            Err(ComponentTreeError::AreaOutOfBounds { .. })
+        ));
+        assert_eq!(
+            container(&mut term, [0, 1], Area::Values { w: 4, h: 4 }),
+            Err(ComponentTreeError::AreaTaken { id: vec![0, 0] })
+        );
+        let boxed = term.container(
+            &[0, 1],
+            Pos::End,
+            Pos::End,
+            Polygon::Free { vertices: vec![] },
+            Area::Values { w: 1, h: 1 },
+            Border::Uniform('*'),
+            Padding::None,
+        );
+        assert_eq!(
+            boxed,
+            Err(ComponentTreeError::DecorationDoesNotFit { id: vec![0, 1] })
+        );
+        let pushed = |term: &mut Term, c| term.push_container(c).unwrap_err().1;
+        assert_eq!(pushed(&mut term, Container::default()), duplicate);
+        let outside = Container::new([0, 1], 18, 0, 5, 5, Border::None, Padding::None);
+        assert!(matches!(
+            pushed(&mut term, outside),
+            ComponentTreeError::OriginOutOfBounds { .. }
+        ));
+        let over = Container::new([0, 1], 2, 2, 2, 2, Border::None, Padding::None);
+        assert_eq!(
+            pushed(&mut term, over),
+            ComponentTreeError::AreaTaken { id: vec![0, 0] }
+        );
+        let meta = ContainerMeta::new().area(wide.clone());
+        assert!(matches!(
+            term.container_from_meta(&meta),
+            Err(ComponentTreeError::AreaOutOfBounds { .. })
+        ));
+
+        // texts
+        let text = |term: &mut Term, id: [u8; 3], area: Area| {
+            let [hpos, vpos] = [Pos::Value(0), Pos::Value(0)];
+            let shape = Polygon::Free { vertices: vec![] };
+            if id[2] % 2 == 0 {
+                term.input(&id, hpos, vpos, shape, area, Border::None, Padding::None)
+            } else {
+                term.nonedit(&id, hpos, vpos, shape, area, Border::None, Padding::None, &[])
+            }
+        };
+        let one = Area::Values { w: 1, h: 1 };
+        assert_eq!(
+            text(&mut term, [0, 5, 0], one.clone()),
+            Err(ComponentTreeError::ParentNotFound { id: vec![0, 5] })
+        );
+        assert!(matches!(
+            text(&mut term, [0, 0, 1], wide.clone()),
+            Err(ComponentTreeError::AreaOutOfBounds { .. })
+        ));
+        assert!(text(&mut term, [0, 0, 0], one.clone()).is_ok());
+        assert_eq!(
+            text(&mut term, [0, 0, 0], one.clone()),
+            Err(ComponentTreeError::DuplicateId {
+                id: vec![0, 0, 0]
+            })
+        );
+        assert_eq!(
+            text(&mut term, [0, 0, 1], one.clone()),
+            Err(ComponentTreeError::AreaTaken {
+                id: vec![0, 0, 0]
+            })
+        );
+        // the container is at the term's origin, whose columns start at 1
+        let input_at =
+            |id, x0| Text::input(id, x0, 0, x0 + 1, 0, 1, 1, &[], Border::None, Padding::None);
+        let nonedit_at =
+            |id, x0| Text::nonedit(id, x0, 0, x0 + 1, 0, 1, 1, &[], Border::None, Padding::None);
+        let pushed = |res: Result<(), (Text, ComponentTreeError)>| res.unwrap_err().1;
+        assert_eq!(
+            pushed(term.push_input(input_at([0, 0, 0], 4))),
+            ComponentTreeError::DuplicateId {
+                id: vec![0, 0, 0]
+            }
+        );
+        assert_eq!(
+            pushed(term.push_input(nonedit_at([0, 0, 3], 4))),
+            ComponentTreeError::WrongKindForId {
+                id: [0, 0, 3],
+                input: true
+            }
+        );
+        assert_eq!(
+            pushed(term.push_nonedit(input_at([0, 0, 2], 4))),
+            ComponentTreeError::WrongKindForId {
+                id: [0, 0, 2],
+                input: false
+            }
+        );
+        assert_eq!(
+            pushed(term.push_nonedit(nonedit_at([0, 5, 1], 4))),
+            ComponentTreeError::ParentNotFound { id: vec![0, 5] }
+        );
+        assert!(matches!(
+            pushed(term.push_nonedit(nonedit_at([0, 0, 1], 12))),
+            ComponentTreeError::OriginOutOfBounds { .. }
+        ));
+        assert_eq!(
+            pushed(term.push_nonedit(nonedit_at([0, 0, 1], 0))),
+            ComponentTreeError::AreaTaken {
+                id: vec![0, 0, 0]
+            }
+        );
+        let meta = InputMeta::new().parent([0, 5]);
+        assert_eq!(
+            term.input_from_meta(&meta),
+            Err(ComponentTreeError::ParentNotFound { id: vec![0, 5] })
+        );
+        let meta = NonEditMeta::new().parent([0, 0]).area(wide);
+        assert!(matches!(
+            term.nonedit_from_meta(&meta),
+            Err(ComponentTreeError::AreaOutOfBounds { .. })
+        ));
+    }
+
+    fn borders() -> Vec<Border> {
+        vec![
+            Border::None,
+            Border::Uniform('*'),
+            Border::rounded(),
+            Border::manual(
+                '+', '+', '+', '+', "ab", '-', "", "", '|', "f", "e", '|', "", "", '-', "cd",
+            ),
+        ]
+    }
+
+    fn paddings() -> Vec<Padding> {
+        vec![
+            Padding::None,
+            Padding::inner(1, 0, 2, 0),
+            Padding::outer(0, 1, 0, 2),
+            Padding::in_out(1, 1, 1, 1, 1, 1, 1, 1),
+            Padding::inner(u16::MAX, u16::MAX, u16::MAX, u16::MAX),
+        ]
+    }
+
+    fn positions() -> Vec<Pos> {
+        vec![Pos::Start, Pos::Center, Pos::End, Pos::Value(3), Pos::Value(u16::MAX)]
+    }
+
+    fn areas(w: u16, h: u16) -> Vec<Area> {
+        vec![
+            Area::Values { w, h },
+            Area::Values { w: w + 5, h },
+            Area::Values { w: u16::MAX, h: u16::MAX },
+            Area::Fill,
+            Area::Percent { w: 50, h: 100 },
+            Area::Zero,
+        ]
+    }
+
+    // no size, place or decoration of a component panics, too big ones are errors
+    #[test]
+    fn fuzzed() {
+        let free = || Polygon::Free { vertices: vec![] };
+        for [w, h] in (0..6).flat_map(|w| (0..4).map(move |h| [w, h])) {
+            if w == 0 || h == 0 {
+                let empty = ComponentTreeError::EmptyArea { area: [w, h] };
+                assert_eq!(Term::try_new(0, w, h).unwrap_err(), empty);
+                continue;
+            }
+            for area in areas(w, h) {
+                for border in borders() {
+                    for padding in paddings() {
+                        for pos in positions() {
+                            let mut term = Term::new(0, w, h);
+                            let made = term.container(
+                                &[0, 0],
+                                pos.clone(),
+                                pos.clone(),
+                                free(),
+                                area.clone(),
+                                border,
+                                padding,
+                            );
+                            if made.is_err() {
+                                continue;
+                            }
+                            // the same sizes inside a container
+                            let mut term = Term::new(0, 12, 8);
+                            let built = Container::builder([0, 0])
+                                .pos(Pos::Start, Pos::Start)
+                                .area(Area::Values { w: w + 2, h: h + 2 })
+                                .border(border)
+                                .padding(padding)
+                                .build(&mut term);
+                            if built.is_err() {
+                                continue;
+                            }
+                            _ = term.input(
+                                &[0, 0, 0],
+                                pos.clone(),
+                                pos.clone(),
+                                free(),
+                                area.clone(),
+                                border,
+                                padding,
+                            );
+                            _ = term.nonedit(
+                                &[0, 0, 1],
+                                pos.clone(),
+                                Pos::End,
+                                free(),
+                                area.clone(),
+                                border,
+                                padding,
+                                &[Some('a'); 3],
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod cursor {
+    use super::{ComponentTreeError, Container, SpaceError, Term, Text};
+    use crate::inputs::keyboard::Modifiers;
+    use crate::inputs::mouse::{MouseButton, MouseEvent, MouseKind};
+    use crate::space::{Area, Pos};
+
+    // a term with a 6 x 2 input at [3, 1], the cursor of the input at its origin
+    fn focusable() -> Term {
+        let mut term = Term::new(0, 20, 10);
+        Container::builder([0, 0])
+            .pos(Pos::Value(2), Pos::Value(1))
+            .area(Area::Values { w: 10, h: 4 })
+            .build(&mut term)
+            .unwrap();
+        Text::input_builder([0, 0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 6, h: 2 })
+            .build(&mut term)
+            .unwrap();
+
+        term
+    }
+
+    #[test]
+    fn move_cursor_to() {
+        let mut term = Term::new(0, 20, 10);
+        assert!(term.move_cursor_to(5, 3).is_ok());
+        assert_eq!([term.cx, term.cy], [5, 3]);
+
+        assert_eq!(
+            term.move_cursor_to(21, 3),
+            Err(SpaceError::OriginOutOfBounds {
+                origin: [21, 3],
+                available: [20, 10],
+            })
+        );
+        assert!(term.move_cursor_to(5, 11).is_err());
+        assert_eq!([term.cx, term.cy], [5, 3]);
+        // x is 1 based, the last column is w
+        assert!(term.move_cursor_to(20, 10).is_ok());
+    }
+
+    #[test]
+    fn save_restore() {
+        let mut term = Term::new(0, 20, 10);
+        _ = term.move_cursor_to(5, 3);
+        term.save_cursor();
+        _ = term.move_cursor_to(15, 8);
+        term.restore_cursor();
+        assert_eq!([term.cx, term.cy], [5, 3]);
+
+        // nothing saved anymore
+        _ = term.move_cursor_to(15, 8);
+        term.restore_cursor();
+        assert_eq!([term.cx, term.cy], [15, 8]);
+
+        // the term shrank under the saved cursor, it goes to the last column and row
+        term.save_cursor();
+        term.resize(10, 5);
+        term.restore_cursor();
+        assert_eq!([term.cx, term.cy], [10, 5]);
+    }
+
+    #[test]
+    fn sync_unfocused() {
+        let mut term = focusable();
+        assert_eq!(term.sync_cursor(), Err(ComponentTreeError::NothingFocused));
+        assert_eq!([term.cx, term.cy], [0, 0]);
+
+        assert!(term.focus(&[0, 0, 0]).is_ok());
+        let text = term.input_ref(&[0, 0, 0]).unwrap();
+        assert_eq!([term.cx, term.cy], [text.ax0, text.ay0]);
+    }
+
+    #[test]
+    fn sync_dangling() {
+        let mut term = focusable();
+        term.focus(&[0, 0, 0]).unwrap();
+        let before = [term.cx, term.cy];
+        term.containers[0].items.clear();
+        assert_eq!(
+            term.sync_cursor(),
+            Err(ComponentTreeError::BadID { id: vec![0, 0, 0] })
+        );
+        assert_eq!([term.cx, term.cy], before);
+    }
+
+    #[test]
+    fn sync_clamped() {
+        let mut term = focusable();
+        term.focus(&[0, 0, 0]).unwrap();
+        // a cursor left past the edges of the text, it is shown on the text's last cell
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        [text.cx, text.cy] = [40, 30];
+        let [ax0, ay0] = [text.ax0, text.ay0];
+        assert!(term.sync_cursor().is_ok());
+        assert_eq!([term.cx, term.cy], [ax0 + 5, ay0 + 1]);
+
+        // and past the edges of the term after a shrink, it is shown on the last column
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        text.cx = u16::MAX;
+        term.resize(7, 5);
+        assert!(term.sync_cursor().is_ok());
+        assert_eq!([term.cx, term.cy], [7, ay0 + 1]);
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().cx, 5);
+    }
+
+    // focus, Tab and a click agree on what can be focused
+    #[test]
+    fn focus_rules() {
+        let mut term = focusable();
+        let label = Text::nonedit_builder([0, 0, 1])
+            .pos(Pos::Start, Pos::Value(2))
+            .area(Area::Values { w: 6, h: 1 })
+            .value_str("label")
+            .build(&mut term)
+            .unwrap();
+        let not = ComponentTreeError::NotFocusable { id: label };
+        assert_eq!(term.focus(&label), Err(not.clone()));
+        let click = MouseEvent {
+            column: 2,
+            row: 3,
+            kind: MouseKind::Down,
+            button: MouseButton::Left,
+            modifiers: Modifiers(0),
+        };
+        assert_eq!(term.mouse(&click), Some(label));
+        assert_eq!(term.focused, None);
+        term.focus_next();
+        term.focus_next();
+        assert_eq!(term.focused, Some([0, 0, 0]));
+
+        // a scrollable pane that wants the keys
+        term.nonedit_mut(&label).unwrap().set_focusable(true);
+        term.mouse(&click);
+        assert_eq!(term.focused, Some(label));
+        term.focus_next();
+        assert_eq!(term.focused, Some([0, 0, 0]));
+
+        let input = term.input_mut(&[0, 0, 0]).unwrap();
+        input.disable();
+        assert_eq!(
+            term.focus(&[0, 0, 0]),
+            Err(ComponentTreeError::Disabled { id: [0, 0, 0] })
+        );
+        term.focus_next();
+        term.focus_next();
+        assert_eq!(term.focused, Some(label));
+        term.input_mut(&[0, 0, 0]).unwrap().enable();
+        assert!(term.focus(&[0, 0, 0]).is_ok());
+
+        term.nonedit_mut(&label).unwrap().hide();
+        assert_eq!(term.focus(&label), Err(not));
+        term.containers[0].hide();
+        assert_eq!(
+            term.focus(&[0, 0, 0]),
+            Err(ComponentTreeError::NotFocusable { id: [0, 0, 0] })
+        );
+        assert_eq!(
+            term.focus(&[0, 0, 9]),
+            Err(ComponentTreeError::BadID { id: vec![0, 0, 9] })
+        );
+    }
+}
+
+#[cfg(test)]
+mod editing {
+    use super::{ComponentTreeError, Container, Property, Term, Text, Value};
+    use crate::console::Capabilities;
+    use crate::events::Event;
+    use crate::inputs::actions::ActionResult;
+    use crate::inputs::keyboard::{Char, KbdEvent, KeyKind, Modifiers, CC, CONTROL, SHIFT};
+    use crate::inputs::keymap::InputAction;
+    use crate::space::{border::Border, padding::Padding};
+    use crate::themes::Style;
+
+    // a container holding two 4x2 inputs and a nonedit between them
+    fn term() -> Term {
+        let mut term = Term::new(0, 30, 8);
+        let mut c = Container::new([0, 0], 0, 0, 20, 4, Border::None, Padding::None);
+        c.items.push(Text::input([0, 0, 0], 0, 0, 0, 0, 4, 2, &[], Border::None, Padding::None));
+        c.items.push(Text::nonedit([0, 0, 1], 5, 0, 0, 0, 4, 2, &[], Border::None, Padding::None));
+        c.items.push(Text::input([0, 0, 2], 10, 0, 0, 0, 4, 2, &[], Border::None, Padding::None));
+        term.containers.push(c);
+        term.focused = Some([0, 0, 0]);
+
+        term
+    }
+
+    fn cc(cc: CC) -> KbdEvent {
+        KbdEvent {
+            char: Char::CC(cc),
+            modifiers: Modifiers(0),
+            kind: KeyKind::Press,
+        }
+    }
+
+    fn typed(term: &Term) -> String {
+        term.input_ref(&[0, 0, 0])
+            .unwrap()
+            .value
+            .iter()
+            .flatten()
+            .collect()
+    }
+
+    #[test]
+    fn typing() {
+        let mut term = term();
+        for c in "abc".chars() {
+            assert_eq!(term.key(&c.into()), None);
+        }
+        assert_eq!(typed(&term), "abc");
+
+        assert_eq!(term.key(&cc(CC::Left)), Some(InputAction::Left));
+        assert_eq!(term.key(&cc(CC::BS)), Some(InputAction::Backspace));
+        assert_eq!(typed(&term), "ac");
+        assert_eq!(term.key(&cc(CC::Delete)), Some(InputAction::Delete));
+        assert_eq!(typed(&term), "a");
+
+        // shifted chars are typed as they are, other modifiers are not
+        term.key(&KbdEvent {
+            char: Char::Char('B'),
+            modifiers: Modifiers(SHIFT),
+            kind: KeyKind::Press,
+        });
+        term.key(&KbdEvent {
+            char: Char::Char('x'),
+            modifiers: Modifiers(2),
+            kind: KeyKind::Press,
+        });
+        assert_eq!(typed(&term), "aB");
+        let text = term.input_ref(&[0, 0, 0]).unwrap();
+        assert_eq!([text.cx, text.cy], [2, 0]);
+        assert_eq!(text.value.len(), 8);
+    }
+
+    #[test]
+    fn rebound() {
+        let mut term = term();
+        term.key(&'a'.into());
+        term.keymap
+            .bind(cc(CC::BS), InputAction::Named("ask".into()))
+            .unwrap();
+        assert_eq!(term.key(&cc(CC::BS)), Some(InputAction::Named("ask".into())));
+        assert_eq!(typed(&term), "a");
+
+        // the default binding comes back once the app's binding is gone
+        term.keymap.unbind(&cc(CC::BS));
+        term.key(&cc(CC::BS));
+        assert_eq!(typed(&term), "");
+    }
+
+    #[test]
+    fn paste() {
+        let mut term = term();
+        term.paste("ab\ncd");
+        assert_eq!(typed(&term), "ab cd");
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        assert_eq!([text.cx, text.cy], [1, 1]);
+
+        text.value = Value::blank(8);
+        text.cx = 0;
+        text.cy = 0;
+        text.attributes.insert("multiline");
+        term.paste("ab\ncd");
+        let text = term.input_ref(&[0, 0, 0]).unwrap();
+        assert_eq!(
+            text.value,
+            [Some('a'), Some('b'), None, None, Some('c'), Some('d'), None, None]
+        );
+    }
+
+    #[test]
+    fn focus_cycle() {
+        let mut term = term();
+        let tab = cc(CC::TAB);
+        assert_eq!(term.key(&tab), Some(InputAction::FocusNext));
+        assert_eq!(term.focused, Some([0, 0, 2]));
+        term.key(&tab);
+        assert_eq!(term.focused, Some([0, 0, 0]));
+        term.apply(&InputAction::FocusPrev, &Event::Tick);
+        assert_eq!(term.focused, Some([0, 0, 2]));
+    }
+
+    #[test]
+    fn registered_action() {
+        let mut term = term();
+        term.keymap
+            .bind(cc(CC::CR), InputAction::Named("shout".into()))
+            .unwrap();
+        term.actions.register("shout", |term: &mut Term, _: &Event| {
+            term.paste("!");
+            ActionResult::submit()
+        });
+
+        let enter = Event::Key(cc(CC::CR));
+        assert_eq!(term.dispatch(&enter), ActionResult::submit());
+        assert_eq!(typed(&term), "!");
+        // the action is still registered after it ran
+        assert!(term.actions.contains("shout"));
+
+        // a name with no registered action does nothing
+        term.actions.unregister("shout");
+        assert_eq!(term.dispatch(&enter), ActionResult::default());
+        assert_eq!(term.dispatch(&Event::Key('a'.into())), ActionResult::redraw());
+        assert_eq!(typed(&term), "!a");
+    }
+
+    #[test]
+    fn layered_keymaps() {
+        let mut term = term();
+        let ctrl = |c: char| KbdEvent {
+            char: Char::Char(c),
+            modifiers: Modifiers(CONTROL),
+            kind: KeyKind::Press,
+        };
+        term.keymap
+            .bind(cc(CC::CR), InputAction::Named("term".into()))
+            .unwrap();
+        term.keymap
+            .bind(ctrl('t'), InputAction::Named("term".into()))
+            .unwrap();
+        let input = term.input_mut(&[0, 0, 0]).unwrap();
+        input.keymap.bind(cc(CC::CR), InputAction::Newline).unwrap();
+        input.keymap.bind(cc(CC::TAB), InputAction::NoOp).unwrap();
+
+        // the focused component's binding wins over the term's
+        assert_eq!(term.key(&cc(CC::CR)), Some(InputAction::Newline));
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().cy, 1);
+        // the term's binding wins over the defaults
+        assert_eq!(term.key(&ctrl('t')), Some(InputAction::Named("term".into())));
+        // the component unbinds a default
+        assert_eq!(term.key(&cc(CC::TAB)), Some(InputAction::NoOp));
+        assert_eq!(term.focused, Some([0, 0, 0]));
+        // the defaults come last
+        assert_eq!(term.key(&cc(CC::Left)), Some(InputAction::Left));
+
+        // another input has only the term's and the default bindings
+        term.focused = Some([0, 0, 2]);
+        assert_eq!(term.key(&cc(CC::CR)), Some(InputAction::Named("term".into())));
+        assert_eq!(term.key(&cc(CC::TAB)), Some(InputAction::FocusNext));
+        assert_eq!(term.focused, Some([0, 0, 0]));
+    }
+
+    #[test]
+    fn history() {
+        let mut term = term();
+        let ctrl = |c: char| KbdEvent {
+            char: Char::Char(c),
+            modifiers: Modifiers(CONTROL),
+            kind: KeyKind::Press,
+        };
+        for entry in ["ls", "git push", "cargo"] {
+            term.paste(entry);
+            assert_eq!(term.key(&cc(CC::CR)), Some(InputAction::Submit));
+            term.input_mut(&[0, 0, 0]).unwrap().replace_value("");
+        }
+        assert_eq!(term.history(&[0, 0, 0]).unwrap().get(0), Some("cargo"));
+
+        term.paste("x");
+        term.key(&ctrl('p'));
+        term.key(&ctrl('p'));
+        assert_eq!(typed(&term), "git push");
+        term.key(&ctrl('n'));
+        term.key(&ctrl('n'));
+        // past the newest entry, what was typed comes back
+        assert_eq!(typed(&term), "x");
+        term.key(&ctrl('n'));
+        assert_eq!(typed(&term), "x");
+
+        // the search shows in the input, esc gives back what was typed
+        term.key(&ctrl('r'));
+        term.key(&'s'.into());
+        assert_eq!(
+            term.history(&[0, 0, 0]).unwrap().search_prompt().unwrap(),
+            "(reverse-i-search)`s': git push"
+        );
+        term.key(&ctrl('r'));
+        assert_eq!(term.history(&[0, 0, 0]).unwrap().search_match(), Some("ls"));
+        term.key(&cc(CC::ESC));
+        assert_eq!(typed(&term), "x");
+
+        // enter takes the match
+        term.key(&ctrl('r'));
+        term.key(&'c'.into());
+        term.key(&cc(CC::CR));
+        assert!(!term.history(&[0, 0, 0]).unwrap().is_searching());
+        assert_eq!(typed(&term), "cargo");
+    }
+
+    #[test]
+    fn history_per_input() {
+        let mut term = term();
+        let text = Text::input([0, 0, 4], 15, 0, 0, 0, 4, 2, &[], Border::None, Padding::None);
+        term.containers[0].items.push(text);
+        let ctrl_p = KbdEvent {
+            char: Char::Char('p'),
+            modifiers: Modifiers(CONTROL),
+            kind: KeyKind::Press,
+        };
+        let submit = |term: &mut Term, id: [u8; 3], entry: &str| {
+            term.focus(&id).unwrap();
+            term.input_mut(&id).unwrap().replace_value(entry);
+            term.key(&cc(CC::CR));
+        };
+        submit(&mut term, [0, 0, 0], "search");
+        submit(&mut term, [0, 0, 2], "command");
+        assert_eq!(term.history(&[0, 0, 0]).unwrap().len(), 1);
+        assert!(term.history(&[0, 0, 4]).is_none());
+
+        // half way through the history when the focus moves on
+        term.focus(&[0, 0, 0]).unwrap();
+        term.input_mut(&[0, 0, 0]).unwrap().replace_value("");
+        term.key(&ctrl_p);
+        assert_eq!(typed(&term), "search");
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().hicu, 1);
+        term.focus(&[0, 0, 2]).unwrap();
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().hicu, 0);
+
+        // grouped inputs share one history
+        for id in [[0, 0, 2], [0, 0, 4]] {
+            let text = term.input_mut(&id).unwrap();
+            text.properties
+                .insert("history-group", Property::String("shell".into()));
+        }
+        submit(&mut term, [0, 0, 2], "make");
+        term.focus(&[0, 0, 4]).unwrap();
+        term.key(&ctrl_p);
+        let shared = term.input_ref(&[0, 0, 4]).unwrap().value_string();
+        assert_eq!(shared, "make");
+        assert_eq!(term.history(&[0, 0, 4]).unwrap().len(), 1);
+
+        // preloaded entries come up right away
+        term.history_mut(&[0, 0, 0]).extend(["saved".to_string()]);
+        term.focus(&[0, 0, 0]).unwrap();
+        term.key(&ctrl_p);
+        assert_eq!(typed(&term), "saved");
+    }
+    #[test]
+    fn disabled() {
+        let mut term = term();
+        // disabled under the focus, it takes no edits but Tab still leaves it
+        term.input_mut(&[0, 0, 0]).unwrap().disable();
+        term.key(&'a'.into());
+        assert!(!term.paste("pasted"));
+        assert_eq!(typed(&term), "");
+        assert_eq!(term.key(&cc(CC::TAB)), Some(InputAction::FocusNext));
+        assert_eq!(term.focused, Some([0, 0, 2]));
+
+        assert_eq!(term.set_disabled(&[0, 0, 0], false), Ok(()));
+        assert!(term.input_ref(&[0, 0, 0]).unwrap().attributes.contains("dirty"));
+        term.focus(&[0, 0, 0]).unwrap();
+        assert_eq!(term.set_disabled(&[0, 0, 0], true), Ok(()));
+        assert_eq!(term.focused, Some([0, 0, 2]));
+
+        // the last text that could be focused is blurred
+        term.theme.set("input.disabled", &Style::new().italic());
+        assert_eq!(term.set_disabled(&[0, 0, 2], true), Ok(()));
+        assert_eq!(term.focused, None);
+        assert_eq!(
+            term.focus(&[0, 0, 2]),
+            Err(ComponentTreeError::Disabled { id: [0, 0, 2] })
+        );
+        let mut out = vec![];
+        let text = term.input_ref(&[0, 0, 2]).unwrap();
+        text.render_value_with(&mut out, &Capabilities::full());
+        assert!(String::from_utf8(out).unwrap().starts_with("\x1b[3m"));
+
+        assert_eq!(
+            term.set_disabled(&[0, 0, 9], true),
+            Err(ComponentTreeError::BadID { id: vec![0, 0, 9] })
+        );
+    }
+
+    #[test]
+    fn editable() {
+        let mut term = term();
+        assert_eq!(term.set_editable(&[0, 0, 1], true), Ok(()));
+        assert_eq!([term.ilen(), term.nelen()], [3, 0]);
+        term.focus(&[0, 0, 1]).unwrap();
+        term.key(&'a'.into());
+        assert_eq!(term.input_ref(&[0, 0, 1]).unwrap().value_string(), "a");
+
+        // read-only under the focus, it keeps the focus, its value and its cursor
+        assert_eq!(term.set_editable(&[0, 0, 1], false), Ok(()));
+        assert_eq!(term.focused, Some([0, 0, 1]));
+        term.key(&'b'.into());
+        let text = term.nonedit_ref(&[0, 0, 1]).unwrap();
+        assert_eq!(text.value_string(), "a");
+        assert_eq!(text.cx, 1);
+        assert_eq!([term.ilen(), term.nelen()], [2, 1]);
+        assert_eq!(term.container_ref(&[0, 0]).unwrap().items[1].id, [0, 0, 1]);
+
+        assert_eq!(
+            term.set_editable(&[0, 0, 9], true),
+            Err(ComponentTreeError::BadID { id: vec![0, 0, 9] })
+        );
+    }
+}
+
+#[cfg(test)]
+mod snapshot {
+    use super::{Container, Property, Term, Text};
+    use crate::events::Event;
+    use crate::inputs::actions::ActionResult;
+    use crate::space::{border::Border, Area, Pos};
+    use std::rc::Rc;
+
+    fn term() -> Term {
+        let mut term = Term::new(0, 20, 6);
+        Container::builder([0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 12, h: 4 })
+            .border(Border::rounded())
+            .property("title", Property::String("form".into()))
+            .build(&mut term)
+            .unwrap();
+        Text::input_builder([0, 0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 8, h: 1 })
+            .build(&mut term)
+            .unwrap();
+
+        term
+    }
+
+    #[test]
+    fn clone() {
+        let mut term = term();
+        let before = term.clone();
+        assert_eq!(before, term);
+
+        // a snapshot of the container outlives a change to it
+        let cont = term.container_ref(&[0, 0]).unwrap().clone();
+        term.input_mut(&[0, 0, 0]).unwrap().value.set(0, Some('x'));
+        assert_ne!(before, term);
+        assert_ne!(term.container_ref(&[0, 0]).unwrap(), &cont);
+        *term.container_mut(&[0, 0]).unwrap() = cont;
+        assert_eq!(before, term);
+    }
+
+    #[test]
+    fn callbacks() {
+        let mut term = term();
+        let f: Rc<dyn Fn() -> Property> = Rc::new(|| Property::Bool(true));
+        let fp = Property::Fn(f.clone());
+        assert_eq!(fp.clone(), fp);
+        assert_ne!(fp, Property::Fn(Rc::new(|| Property::Bool(true))));
+        assert_eq!(
+            Property::Vec(vec![Property::Int(1), Property::Float(0.5)]),
+            Property::Vec(vec![Property::Int(1), Property::Float(0.5)])
+        );
+
+        // the actions are shared, the hooks are left out
+        term.actions.register("noop", |_: &mut Term, _: &Event| ActionResult::default());
+        term.on_select(|_, _, _| ());
+        let clone = term.clone();
+        assert!(clone.actions.contains("noop"));
+        assert!(clone.list_hooks.select.is_none());
+        assert_eq!(clone, term);
+        term.actions.unregister("noop");
+        assert_ne!(clone, term);
+    }
+}
@@ -0,0 +1,323 @@
+use std::time::{Duration, Instant};
+
+use crate::space::{fit, Area};
+
+use super::{ComponentTreeError, Container, Term};
+
+/// how an animation goes from its start to its end, see Term::animate_move
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Easing {
+    /// at the same pace all along
+    #[default]
+    Linear,
+    /// fast at first, slowing down as it gets to the end
+    EaseOut,
+}
+
+impl Easing {
+    // how far along the animation is once the part t of its duration went by
+    fn ease(self, t: f64) -> f64 {
+        match self {
+            Self::Linear => t,
+            Self::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+// a container going from one [x0, y0, w, h] geometry to another, see Term::animate_move
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Animation {
+    id: [u8; 2],
+    from: [u16; 4],
+    to: [u16; 4],
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Animation {
+    // the geometry the part t of the duration gets to, the ends of the area are eased
+    // like its origin so that it never goes past both of its geometries
+    fn at(&self, t: f64) -> [u16; 4] {
+        let p = self.easing.ease(t.clamp(0.0, 1.0));
+        let lerp = |a: u16, b: u16| (a as f64 + (b as f64 - a as f64) * p).round() as u16;
+        let [x0, y0] = [0, 1].map(|i| lerp(self.from[i], self.to[i]));
+        let [x1, y1] =
+            [0, 1].map(|i| lerp(self.from[i] + self.from[i + 2], self.to[i] + self.to[i + 2]));
+
+        [x0, y0, x1 - x0, y1 - y0]
+    }
+
+    // the part of the duration that went by at now
+    fn progress(&self, now: Instant) -> f64 {
+        match self.duration.is_zero() {
+            true => 1.0,
+            false => now.duration_since(self.start).as_secs_f64() / self.duration.as_secs_f64(),
+        }
+    }
+}
+
+fn geometry(cont: &Container) -> [u16; 4] {
+    [cont.x0, cont.y0, cont.w, cont.h]
+}
+
+// animations
+impl Term {
+    /// moves the container with id to the [x0, y0] origin over duration, a bit more
+    /// on every Tick, see Term::tick, its texts move with it
+    /// a container that is animating is drawn over the others and is not checked against
+    /// them on the way, only where it ends up is, the animation ends exactly there
+    /// it takes over the animation of the container that is in flight,
+    /// from where the container is, a resize going on keeps its size to get to
+    /// returns BadID if there is no container with id, and the same errors as
+    /// Term::push_container if the container would not fit there, nothing moves then
+    pub fn animate_move(
+        &mut self,
+        id: &[u8; 2],
+        to: [u16; 2],
+        duration: Duration,
+        easing: Easing,
+    ) -> Result<(), ComponentTreeError> {
+        let [_, _, w, h] = self.animation_end(id)?;
+
+        self.animate(id, [to[0], to[1], w, h], duration, easing)
+    }
+
+    /// resizes the container with id to area over duration, a percent or fill area is
+    /// worked out from the size of the term, see Term::animate_move
+    /// returns BadID if there is no container with id, and the same errors as
+    /// Term::push_container if the container would not fit, its texts included,
+    /// nothing changes then
+    pub fn animate_resize(
+        &mut self,
+        id: &[u8; 2],
+        area: Area,
+        duration: Duration,
+        easing: Easing,
+    ) -> Result<(), ComponentTreeError> {
+        let [x0, y0, _, _] = self.animation_end(id)?;
+        let [w, h] = area.unwrap([self.w, self.h]);
+
+        self.animate(id, [x0, y0, w, h], duration, easing)
+    }
+
+    /// ends the animation of the container with id, it snaps to where it was going
+    /// returns whether there was one
+    pub fn finish_animation(&mut self, id: &[u8; 2]) -> bool {
+        let Some(idx) = self.animations.iter().position(|a| a.id == *id) else {
+            return false;
+        };
+        let animation = self.animations.remove(idx);
+        self.set_geometry(id, animation.to);
+
+        true
+    }
+
+    /// whether the container with id is animating, see Term::animate_move
+    pub fn is_animating(&self, id: &[u8; 2]) -> bool {
+        self.animations.iter().any(|a| a.id == *id)
+    }
+
+    // the geometry the container with id ends up at, once its animation is done
+    fn animation_end(&self, id: &[u8; 2]) -> Result<[u16; 4], ComponentTreeError> {
+        if let Some(animation) = self.animations.iter().find(|a| a.id == *id) {
+            return Ok(animation.to);
+        }
+        let cont = self
+            .container_ref(id)
+            .ok_or_else(|| ComponentTreeError::BadID { id: id.to_vec() })?;
+
+        Ok(geometry(cont))
+    }
+
+    fn animate(
+        &mut self,
+        id: &[u8; 2],
+        to: [u16; 4],
+        duration: Duration,
+        easing: Easing,
+    ) -> Result<(), ComponentTreeError> {
+        let Some(idx) = self.containers.iter().position(|c| c.id == *id) else {
+            return Err(ComponentTreeError::BadID { id: id.to_vec() });
+        };
+        let mut end = self.containers[idx].clone();
+        [end.x0, end.y0, end.w, end.h] = to;
+        end.invalidate();
+        for text in &end.items {
+            fit([text.x0, text.y0], text.decorate(), [end.w, end.h])?;
+        }
+        // the container's own area isn't in the way of the one it moves to
+        self.assign_valid_container_area(&end)?;
+        let from = geometry(&self.containers[idx]);
+
+        self.animations.retain(|a| a.id != *id);
+        self.animations.push(Animation {
+            id: *id,
+            from,
+            to,
+            start: Instant::now(),
+            duration,
+            easing,
+        });
+        if duration.is_zero() {
+            self.finish_animation(id);
+        }
+
+        Ok(())
+    }
+
+    // puts the container with id at the geometry, the cells it leaves are blanked
+    // and what is under them drawn again by the next Term::render_dirty call
+    fn set_geometry(&mut self, id: &[u8; 2], [x0, y0, w, h]: [u16; 4]) {
+        let Some(cont) = self.container_mut(id) else {
+            return;
+        };
+        if geometry(cont) == [x0, y0, w, h] {
+            return;
+        }
+        let before = cont.outer_area();
+        [cont.x0, cont.y0, cont.w, cont.h] = [x0, y0, w, h];
+        cont.invalidate();
+        cont.mark_dirty();
+        self.uncover(before);
+    }
+
+    // moves every animating container to where its animation is at now,
+    // the ones that got to their end are done
+    pub(crate) fn step_animations(&mut self, now: Instant) {
+        let animations = std::mem::take(&mut self.animations);
+        for animation in animations {
+            let t = animation.progress(now);
+            self.set_geometry(&animation.id, animation.at(t));
+            if t < 1.0 && self.container_ref(&animation.id).is_some() {
+                self.animations.push(animation);
+            }
+        }
+    }
+
+    // the shown containers and their index, in the order they are drawn,
+    // the animating ones last so that they go over the others
+    pub(crate) fn draw_order(&self) -> impl Iterator<Item = (usize, &Container)> {
+        let shown = || {
+            let shown = self.containers.iter().enumerate();
+            shown.filter(|(_, c)| !c.is_hidden())
+        };
+        let still = shown().filter(|(_, c)| !self.is_animating(&c.id));
+
+        still.chain(shown().filter(|(_, c)| self.is_animating(&c.id)))
+    }
+}
+
+#[cfg(test)]
+mod animate {
+    use super::*;
+    use crate::space::{border::Border, padding::Padding};
+
+    // a 30x10 term with a bordered 6x2 container at [0, 0] holding a 4x1 input,
+    // and a 6x2 container at [20, 0]
+    fn term() -> Term {
+        let mut term = Term::new(0, 30, 10);
+        let mut c = Container::new([0, 0], 0, 0, 6, 2, Border::Uniform('#'), Padding::None);
+        c.items.push(super::super::Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            4,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        ));
+        c.invalidate();
+        term.containers.push(c);
+        let other = Container::new([0, 1], 20, 0, 6, 2, Border::None, Padding::None);
+        term.containers.push(other);
+
+        term
+    }
+
+    fn started(term: &Term) -> Instant {
+        term.animations[0].start
+    }
+
+    #[test]
+    fn moved() {
+        let mut term = term();
+        let second = Duration::from_secs(1);
+        term.animate_move(&[0, 0], [10, 4], second, Easing::Linear)
+            .unwrap();
+        let start = started(&term);
+        term.step_animations(start + second / 2);
+        let cont = term.container_ref(&[0, 0]).unwrap();
+        assert_eq!([cont.x0, cont.y0], [5, 2]);
+        assert_eq!(cont.items[0].ay0, 3);
+        assert!(term.is_animating(&[0, 0]));
+        assert!(term.is_dirty());
+
+        term.step_animations(start + second * 2);
+        let cont = term.container_ref(&[0, 0]).unwrap();
+        assert_eq!([cont.x0, cont.y0], [10, 4]);
+        assert!(!term.is_animating(&[0, 0]));
+
+        // the other container is where the first one wants to go
+        let err = term.animate_move(&[0, 0], [19, 0], second, Easing::Linear);
+        assert!(err.is_err());
+        assert!(!term.is_animating(&[0, 0]));
+        let err = term.animate_move(&[0, 9], [0, 0], second, Easing::Linear);
+        assert!(matches!(err, Err(ComponentTreeError::BadID { .. })));
+    }
+
+    #[test]
+    fn resized() {
+        let mut term = term();
+        let second = Duration::from_secs(1);
+        let area = Area::Values { w: 12, h: 4 };
+        term.animate_resize(&[0, 0], area, second, Easing::EaseOut)
+            .unwrap();
+        // both at once, the move keeps the size the resize goes to
+        term.animate_move(&[0, 0], [2, 0], second, Easing::EaseOut)
+            .unwrap();
+        let start = started(&term);
+        term.step_animations(start + second / 2);
+        let cont = term.container_ref(&[0, 0]).unwrap();
+        assert_eq!(geometry(cont), [2, 0, 10, 4]);
+
+        // its texts would not fit
+        let small = Area::Values { w: 2, h: 1 };
+        assert!(term
+            .animate_resize(&[0, 0], small, second, Easing::Linear)
+            .is_err());
+
+        assert!(term.finish_animation(&[0, 0]));
+        let cont = term.container_ref(&[0, 0]).unwrap();
+        assert_eq!(geometry(cont), [2, 0, 12, 4]);
+        assert!(!term.finish_animation(&[0, 0]));
+    }
+
+    #[test]
+    fn together() {
+        let mut term = term();
+        let second = Duration::from_secs(1);
+        term.animate_move(&[0, 0], [0, 4], second, Easing::Linear)
+            .unwrap();
+        term.animate_move(&[0, 1], [20, 6], second, Easing::Linear)
+            .unwrap();
+        let order = |term: &Term| {
+            term.draw_order()
+                .map(|(idx, _)| idx)
+                .collect::<Vec<usize>>()
+        };
+        assert_eq!(order(&term), [0, 1]);
+        term.step_animations(started(&term) + second / 2);
+        let ys = term.containers.iter().map(|c| c.y0).collect::<Vec<u16>>();
+        assert_eq!(ys, [2, 3]);
+
+        // the one still animating goes over the other
+        term.finish_animation(&[0, 1]);
+        assert_eq!(order(&term), [1, 0]);
+        term.finish_animation(&[0, 0]);
+        assert!(term.animations.is_empty());
+    }
+}
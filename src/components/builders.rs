@@ -1,11 +1,22 @@
-use crate::components::{Container, Term, Text};
+use std::collections::{HashMap, HashSet};
+
+use crate::components::{
+    ComponentTreeError, Container, IdError, Property, SpaceError, Term, Text, TextKind,
+};
+use crate::inputs::keymap::Keymap;
+#[cfg(feature = "layout")]
 use crate::layout::Layout;
-use crate::space::{border::Border, padding::Padding, Area, Pos};
+use crate::space::{
+    border::Border, border_fit, calc_text_abs_ori, fit, padding::Padding, place, resolve_wh, Area,
+    Overflow, Polygon, Pos,
+};
+use crate::themes::Style;
 
 // pass the meta series to the component making methods in tree and term
 
 #[derive(Debug, Clone)]
 pub struct TermMeta {
+    #[cfg(feature = "layout")]
     layout: Layout,
     area: Area,
     id: u8,
@@ -14,6 +25,7 @@ pub struct TermMeta {
 impl TermMeta {
     fn new() -> Self {
         Self {
+            #[cfg(feature = "layout")]
             layout: Layout::Flex { direction: 'r' },
             area: Area::Zero,
             id: 0,
@@ -25,6 +37,7 @@ impl TermMeta {
         self
     }
 
+    #[cfg(feature = "layout")]
     fn layout(mut self, layout: Layout) -> Self {
         self.layout = layout;
         self
@@ -46,6 +59,7 @@ impl TermMeta {
                 self.bump_id();
                 id
             },
+            #[cfg(feature = "layout")]
             layout: self.layout.clone(),
             w: self.area.width().unwrap(),
             h: self.area.height().unwrap(),
@@ -58,31 +72,43 @@ impl TermMeta {
     }
 }
 
+/// the options of containers made with Term::container_from_meta, a meta can make any number
+/// of them, each one gets the first container id of the term that is free
 #[derive(Debug, Clone)]
 pub struct ContainerMeta {
     layer: u8,
-    tid: u8,
-    cid: u8,
     border: Border,
     padding: Padding,
     area: Area,
+    #[cfg(feature = "layout")]
     layout: Layout,
     hpos: Pos,
     vpos: Pos,
+    dim: bool,
+    style: Option<Style>,
+}
+
+impl Default for ContainerMeta {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ContainerMeta {
-    fn new() -> Self {
+    /// makes a new ContainerMeta, filling its parent and centered in it, with no border
+    /// and no padding, on layer 0 with a row flex layout
+    pub fn new() -> Self {
         Self {
             layer: 0,
-            tid: 0,
-            cid: 0,
             padding: Padding::None,
             border: Border::None,
             area: Area::Fill,
             hpos: Pos::Center,
             vpos: Pos::Center,
+            #[cfg(feature = "layout")]
             layout: Layout::Flex { direction: 'r' },
+            dim: false,
+            style: None,
         }
     }
 
@@ -90,252 +116,1029 @@ impl ContainerMeta {
         self
     }
 
-    fn layer(mut self, layer: u8) -> Self {
+    /// whether a popup made from this meta dims everything under it, see Term::popup
+    pub fn dim(mut self, dim: bool) -> Self {
+        self.dim = dim;
+        self
+    }
+
+    /// the style of the container's area, see Container::set_style
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// the layer of the container
+    pub fn layer(mut self, layer: u8) -> Self {
         self.layer = layer;
         self
     }
 
-    fn border(mut self, border: Border) -> Self {
+    /// the border of the container
+    pub fn border(mut self, border: Border) -> Self {
         self.border = border;
         self
     }
 
-    fn padding(mut self, padding: Padding) -> Self {
+    /// the padding of the container
+    pub fn padding(mut self, padding: Padding) -> Self {
         self.padding = padding;
         self
     }
 
-    fn area(mut self, area: Area) -> Self {
+    /// the area of the container's value
+    pub fn area(mut self, area: Area) -> Self {
         self.area = area;
         self
     }
 
-    fn hpos(mut self, hpos: Pos) -> Self {
+    /// where the container goes horizontally inside its parent
+    pub fn hpos(mut self, hpos: Pos) -> Self {
         self.hpos = hpos;
         self
     }
 
-    fn vpos(mut self, vpos: Pos) -> Self {
+    /// where the container goes vertically inside its parent
+    pub fn vpos(mut self, vpos: Pos) -> Self {
         self.vpos = vpos;
         self
     }
 
-    fn layout(mut self, layout: Layout) -> Self {
+    /// how the container lays out its texts
+    #[cfg(feature = "layout")]
+    pub fn layout(mut self, layout: Layout) -> Self {
         self.layout = layout;
         self
     }
 
-    fn bump_tid(&mut self, id: u8) {
-        self.tid = id;
-    }
+    // a container with id placed inside a [w, h] term by hpos and vpos,
+    // on top of a term that has layer as its greatest layer
+    pub(super) fn floating(&self, id: [u8; 2], [w, h]: [u16; 2], layer: u8) -> Container {
+        let [cw, ch] = self.area.clone().unwrap([w, h]);
+        let mut c = Container::new(id, 0, 0, cw, ch, self.border, self.padding);
+        let [ow, oh] = c.decorate();
+        [c.x0, c.y0] = place(&self.hpos, &self.vpos, [w, h], [ow, oh]);
+        c.layer = layer.saturating_add(1);
+        c.invalidate();
 
-    fn bump_cid(&mut self, id: u8) {
-        self.cid = id
+        c
     }
 
-    fn id(&self) -> [u8; 2] {
-        [self.tid, self.cid]
+    pub(super) fn dims(&self) -> bool {
+        self.dim
     }
 
-    pub(super) fn container(&mut self) -> Container {
-        Container {
-            id: self.id(),
-            layout: self.layout.clone(),
-            w: self.area.width().unwrap(),
-            h: self.area.height().unwrap(),
-            ..Container::default()
-        }
+    /// checks that a container made from this meta can be pushed to term,
+    /// with the same checks as ContainerBuilder::build
+    pub fn validate(&self, term: &Term) -> Result<(), ComponentTreeError> {
+        self.make(term).map(|_| ())
     }
 
-    fn clear(self) -> Self {
-        Self::new()
+    // the container made from this meta with the first free container id of term
+    pub(super) fn make(&self, term: &Term) -> Result<Container, ComponentTreeError> {
+        let Some(id) = term.free_container_id() else {
+            return Err(IdError::IdAlreadyTaken { id: vec![term.id] }.into());
+        };
+
+        let builder = Container::builder(id)
+            .pos(self.hpos.clone(), self.vpos.clone())
+            .area(self.area.clone())
+            .border(self.border)
+            .padding(self.padding)
+            .layer(self.layer);
+        #[cfg(feature = "layout")]
+        let builder = builder.layout(self.layout.clone());
+
+        let mut cont = builder.container(term)?;
+        cont.style = self.style.clone();
+
+        Ok(cont)
     }
 }
 
+/// the options of inputs made with Term::input_from_meta, a meta can make any number of them,
+/// each one gets the first input id of its container that is free
 #[derive(Debug, Clone)]
 pub struct InputMeta {
     layer: u8,
-    tid: u8,
-    cid: u8,
-    iid: u8,
+    parent: [u8; 2],
     border: Border,
     padding: Padding,
     area: Area,
     hpos: Pos,
     vpos: Pos,
+    keymap: Keymap,
+    style: Option<Style>,
+}
+
+impl Default for InputMeta {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl InputMeta {
-    fn new() -> Self {
+    /// makes a new InputMeta, for inputs in the container [0, 0], filling it and centered in
+    /// it, with no border and no padding, on layer 0 with the default keymap
+    pub fn new() -> Self {
         Self {
             layer: 0,
-            tid: 0,
-            cid: 0,
-            iid: 0,
+            parent: [0, 0],
             padding: Padding::None,
             border: Border::None,
             area: Area::Fill,
             hpos: Pos::Center,
             vpos: Pos::Center,
+            keymap: Keymap::default(),
+            style: None,
         }
     }
 
-    fn layer(mut self, layer: u8) -> Self {
+    /// the id of the container the inputs go to
+    pub fn parent(mut self, id: [u8; 2]) -> Self {
+        self.parent = id;
+        self
+    }
+
+    /// the layer of the input
+    pub fn layer(mut self, layer: u8) -> Self {
         self.layer = layer;
         self
     }
 
-    fn border(mut self, border: Border) -> Self {
+    /// the border of the input
+    pub fn border(mut self, border: Border) -> Self {
         self.border = border;
         self
     }
 
-    fn padding(mut self, padding: Padding) -> Self {
+    /// the padding of the input
+    pub fn padding(mut self, padding: Padding) -> Self {
         self.padding = padding;
         self
     }
 
-    fn area(mut self, area: Area) -> Self {
+    /// the area the input takes, its border and padding included
+    pub fn area(mut self, area: Area) -> Self {
         self.area = area;
         self
     }
 
-    fn hpos(mut self, hpos: Pos) -> Self {
+    /// where the input goes horizontally inside its container
+    pub fn hpos(mut self, hpos: Pos) -> Self {
         self.hpos = hpos;
         self
     }
 
-    fn vpos(mut self, vpos: Pos) -> Self {
+    /// where the input goes vertically inside its container
+    pub fn vpos(mut self, vpos: Pos) -> Self {
         self.vpos = vpos;
         self
     }
 
-    fn clear(self) -> Self {
-        Self::new()
-    }
-
-    pub fn input(&mut self) -> Text {
-        Text {
-            id: {
-                let id = self.iid();
-                self.bump_iid();
-                id
-            },
-            w: self.area.width().unwrap_or(0),
-            h: self.area.height().unwrap_or(0),
-            ..Text::default()
-        }
+    /// the keymap of the input
+    pub fn keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = keymap;
+        self
     }
 
-    fn bump_tid(&mut self) {
-        self.tid += 1;
+    /// the style of the input's value, see Text::set_style
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
     }
 
-    fn bump_cid(&mut self) {
-        self.cid += 1;
+    pub(super) fn cid(&self) -> [u8; 2] {
+        self.parent
     }
 
-    fn bump_iid(&mut self) {
-        self.iid += 2;
+    /// checks that an input made from this meta can be pushed to cont,
+    /// with the same checks as TextBuilder::build
+    pub fn validate(&self, cont: &Container) -> Result<(), ComponentTreeError> {
+        self.make(cont).map(|_| ())
     }
 
-    pub(super) fn cid(&self) -> [u8; 2] {
-        [self.tid, self.cid]
-    }
+    // the input made from this meta with the first free text id of cont
+    pub(super) fn make(&self, cont: &Container) -> Result<Text, ComponentTreeError> {
+        let Some(id) = cont.free_text_id() else {
+            return Err(IdError::IdAlreadyTaken {
+                id: cont.id.to_vec(),
+            }
+            .into());
+        };
 
-    pub(super) fn iid(&self) -> [u8; 3] {
-        [self.tid, self.cid, self.iid]
+        Text::input_builder(id)
+            .pos(self.hpos.clone(), self.vpos.clone())
+            .area(self.area.clone())
+            .border(self.border)
+            .padding(self.padding)
+            .layer(self.layer)
+            .keymap(self.keymap.clone())
+            .text(cont)
+            .map(|mut text| {
+                text.set_style(self.style.clone());
+                text
+            })
     }
 }
 
+/// the options of nonedits made with Term::nonedit_from_meta, a meta can make any number of
+/// them, each one gets the first nonedit id of its container that is free
 #[derive(Debug, Clone)]
 pub struct NonEditMeta {
     layer: u8,
-    tid: u8,
-    cid: u8,
-    neid: u8,
+    parent: [u8; 2],
     border: Border,
     padding: Padding,
     area: Area,
     hpos: Pos,
     vpos: Pos,
+    value: Vec<Option<char>>,
+    style: Option<Style>,
+}
+
+impl Default for NonEditMeta {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl NonEditMeta {
-    fn new() -> Self {
+    /// makes a new NonEditMeta, for empty nonedits in the container [0, 0], filling it and
+    /// centered in it, with no border and no padding, on layer 0
+    pub fn new() -> Self {
         Self {
             layer: 0,
-            tid: 0,
-            cid: 0,
-            neid: 0,
+            parent: [0, 0],
             padding: Padding::None,
             border: Border::None,
             area: Area::Fill,
             hpos: Pos::Center,
             vpos: Pos::Center,
+            value: vec![],
+            style: None,
         }
     }
 
-    fn border(mut self, border: Border) -> Self {
+    /// the id of the container the nonedits go to
+    pub fn parent(mut self, id: [u8; 2]) -> Self {
+        self.parent = id;
+        self
+    }
+
+    /// the layer of the nonedit
+    pub fn layer(mut self, layer: u8) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    /// the border of the nonedit
+    pub fn border(mut self, border: Border) -> Self {
         self.border = border;
         self
     }
 
-    fn padding(mut self, padding: Padding) -> Self {
+    /// the padding of the nonedit
+    pub fn padding(mut self, padding: Padding) -> Self {
         self.padding = padding;
         self
     }
 
-    fn area(mut self, area: Area) -> Self {
+    /// the area the nonedit takes, its border and padding included
+    pub fn area(mut self, area: Area) -> Self {
         self.area = area;
         self
     }
 
-    fn hpos(mut self, hpos: Pos) -> Self {
+    /// where the nonedit goes horizontally inside its container
+    pub fn hpos(mut self, hpos: Pos) -> Self {
         self.hpos = hpos;
         self
     }
 
-    fn vpos(mut self, vpos: Pos) -> Self {
+    /// where the nonedit goes vertically inside its container
+    pub fn vpos(mut self, vpos: Pos) -> Self {
         self.vpos = vpos;
         self
     }
 
-    fn clear(self) -> Self {
-        Self::new()
+    /// the value of the nonedit
+    pub fn value(mut self, value: &[Option<char>]) -> Self {
+        self.value = value.to_vec();
+        self
     }
 
-    pub fn nonedit(&mut self, value: Vec<Option<char>>) -> Text {
-        Text {
-            value,
-            id: {
-                let id = self.neid();
-                self.bump_neid();
-                id
-            },
-            w: self.area.width().unwrap_or(0),
-            h: self.area.height().unwrap_or(0),
-            ..Default::default()
+    /// the value of the nonedit, a cell per char of value
+    pub fn value_str(mut self, value: &str) -> Self {
+        self.value = value.chars().map(Some).collect();
+        self
+    }
+
+    /// the style of the nonedit's value, see Text::set_style
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub(super) fn cid(&self) -> [u8; 2] {
+        self.parent
+    }
+
+    /// checks that a nonedit made from this meta can be pushed to cont,
+    /// with the same checks as TextBuilder::build
+    pub fn validate(&self, cont: &Container) -> Result<(), ComponentTreeError> {
+        self.make(cont).map(|_| ())
+    }
+
+    // the nonedit made from this meta with the first free text id of cont
+    pub(super) fn make(&self, cont: &Container) -> Result<Text, ComponentTreeError> {
+        let Some(id) = cont.free_text_id() else {
+            return Err(IdError::IdAlreadyTaken {
+                id: cont.id.to_vec(),
+            }
+            .into());
+        };
+
+        Text::nonedit_builder(id)
+            .pos(self.hpos.clone(), self.vpos.clone())
+            .area(self.area.clone())
+            .border(self.border)
+            .padding(self.padding)
+            .layer(self.layer)
+            .value(&self.value)
+            .text(cont)
+            .map(|mut text| {
+                text.set_style(self.style.clone());
+                text
+            })
+    }
+}
+
+/// makes a Container and pushes it to a Term, see Container::builder
+/// every option that is not given keeps its default:
+/// centered in the term, filling it, no border, no padding, layer 0, the canvas layout
+#[derive(Debug)]
+pub struct ContainerBuilder {
+    id: [u8; 2],
+    hpos: Pos,
+    vpos: Pos,
+    // the shape is not used yet
+    shape: Polygon,
+    area: Area,
+    border: Border,
+    padding: Padding,
+    layer: u8,
+    #[cfg(feature = "layout")]
+    layout: Layout,
+    bstyle: String,
+    properties: HashMap<&'static str, Property>,
+    attributes: HashSet<&'static str>,
+}
+
+impl Container {
+    /// starts a ContainerBuilder for the container with id
+    ///
+    /// # Examples
+    /// ```
+    /// # use ragout::components::{Container, Term};
+    /// # use ragout::space::{border::Border, padding::Padding, Area, Pos};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut term = Term::new(0, 80, 24);
+    /// let id = Container::builder([0, 0])
+    ///     .pos(Pos::Center, Pos::Start)
+    ///     .area(Area::Percent { w: 50, h: 100 })
+    ///     .border(Border::rounded())
+    ///     .padding(Padding::None)
+    ///     .build(&mut term)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder(id: [u8; 2]) -> ContainerBuilder {
+        ContainerBuilder {
+            id,
+            hpos: Pos::Center,
+            vpos: Pos::Center,
+            shape: Polygon::Free { vertices: vec![] },
+            area: Area::Fill,
+            border: Border::None,
+            padding: Padding::None,
+            layer: 0,
+            #[cfg(feature = "layout")]
+            layout: Layout::Canvas,
+            bstyle: String::new(),
+            properties: HashMap::new(),
+            attributes: HashSet::new(),
         }
     }
+}
+
+impl ContainerBuilder {
+    /// where the container goes horizontally and vertically inside the term
+    pub fn pos(mut self, hpos: Pos, vpos: Pos) -> Self {
+        self.hpos = hpos;
+        self.vpos = vpos;
+        self
+    }
 
-    fn bump_tid(&mut self) {
-        self.tid += 1;
+    /// where the container goes horizontally inside the term
+    pub fn hpos(mut self, hpos: Pos) -> Self {
+        self.hpos = hpos;
+        self
     }
 
-    fn bump_cid(&mut self) {
-        self.cid += 1;
+    /// where the container goes vertically inside the term
+    pub fn vpos(mut self, vpos: Pos) -> Self {
+        self.vpos = vpos;
+        self
     }
 
-    fn bump_neid(&mut self) {
-        self.neid += 2;
+    /// the shape of the container
+    pub fn shape(mut self, shape: Polygon) -> Self {
+        self.shape = shape;
+        self
     }
 
-    pub(super) fn cid(&self) -> [u8; 2] {
-        [self.tid, self.cid]
+    /// the area the container takes, its border and padding included
+    pub fn area(mut self, area: Area) -> Self {
+        self.area = area;
+        self
+    }
+
+    /// the border of the container
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = border;
+        self
+    }
+
+    /// the padding of the container
+    pub fn padding(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
     }
 
-    pub(super) fn neid(&self) -> [u8; 3] {
-        [self.tid, self.cid, self.neid]
+    /// the layer of the container, the greater one is rendered over the others
+    pub fn layer(mut self, layer: u8) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    /// how the container lays out its texts
+    #[cfg(feature = "layout")]
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// the style of the container's border
+    pub fn bstyle(mut self, style: &Style) -> Self {
+        self.bstyle = style.style();
+        self
+    }
+
+    /// adds a property to the container
+    pub fn property(mut self, name: &'static str, value: Property) -> Self {
+        self.properties.insert(name, value);
+        self
+    }
+
+    /// adds an attribute to the container
+    pub fn attribute(mut self, attribute: &'static str) -> Self {
+        self.attributes.insert(attribute);
+        self
+    }
+
+    /// makes the container and pushes it to term
+    /// returns the id of the container
+    ///
+    /// # Errors
+    /// returns an error if any of the following conditions are met
+    /// - the id is already taken by a container of term
+    /// - the area can't hold the border and padding
+    /// - a manual border doesn't fit the container
+    /// - the container doesn't fit inside term or takes some of the area of another container
+    pub fn build(self, term: &mut Term) -> Result<[u8; 2], ComponentTreeError> {
+        let cont = self.container(term)?;
+        let id = cont.id;
+        term.containers.push(cont);
+
+        Ok(id)
+    }
+
+    // the container, once it is known to fit in term
+    pub(super) fn container(self, term: &Term) -> Result<Container, ComponentTreeError> {
+        let id = self.id;
+        if !term.is_valid_container_id(&id) {
+            return Err(IdError::IdAlreadyTaken { id: id.to_vec() }.into());
+        }
+
+        let [ow, oh] = self.area.unwrap([term.w, term.h]);
+        let fitted = resolve_wh(&self.border, &self.padding)
+            .and_then(|[wextra, hextra]| Some([ow.checked_sub(wextra)?, oh.checked_sub(hextra)?]));
+        let Some([w, h]) = fitted else {
+            return Err(ComponentTreeError::DecorationDoesNotFit { id: id.to_vec() });
+        };
+
+        let [x0, y0] = place(&self.hpos, &self.vpos, [term.w, term.h], [ow, oh]);
+
+        if let Border::Manual { .. } = self.border {
+            if !border_fit(&self.border, &self.padding, term.w, term.h) {
+                return Err(ComponentTreeError::DecorationDoesNotFit { id: id.to_vec() });
+            }
+        }
+
+        let mut cont = Container::new(id, x0, y0, w, h, self.border, self.padding);
+        cont.anchor = Some([self.hpos, self.vpos]);
+        cont.layer = self.layer;
+        #[cfg(feature = "layout")]
+        {
+            cont.layout = self.layout;
+        }
+        cont.bstyle = self.bstyle;
+        cont.properties = self.properties;
+        cont.attributes = self.attributes;
+
+        term.assign_valid_container_area(&cont)?;
+
+        Ok(cont)
+    }
+}
+
+/// makes an input or a nonedit Text and pushes it to its container,
+/// see Text::input_builder and Text::nonedit_builder
+/// every option that is not given keeps its default:
+/// centered in the container, filling it, no border, no padding, an empty value, layer 0,
+/// the default keymap and clipped overflow
+#[derive(Debug)]
+pub struct TextBuilder {
+    id: [u8; 3],
+    kind: TextKind,
+    hpos: Pos,
+    vpos: Pos,
+    // the shape is not used yet
+    shape: Polygon,
+    area: Area,
+    border: Border,
+    padding: Padding,
+    value: Vec<Option<char>>,
+    layer: u8,
+    keymap: Keymap,
+    overflow: Overflow,
+    vstyle: String,
+    bstyle: String,
+    properties: HashMap<&'static str, Property>,
+    attributes: HashSet<&'static str>,
+}
+
+impl Text {
+    /// starts a TextBuilder for the text with id,
+    /// an input if the last byte of id is even, a nonedit if it is odd
+    #[deprecated(note = "use Text::input_builder or Text::nonedit_builder")]
+    #[allow(deprecated)]
+    pub fn builder(id: [u8; 3]) -> TextBuilder {
+        Self::builder_of(TextKind::of(&id), id)
+    }
+
+    /// starts a TextBuilder for the input with id
+    ///
+    /// # Examples
+    /// ```
+    /// # use ragout::components::{Container, Term, Text};
+    /// # use ragout::space::{border::Border, Area, Pos};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let mut term = Term::new(0, 80, 24);
+    /// # Container::builder([0, 0]).build(&mut term)?;
+    /// let id = Text::input_builder([0, 0, 0])
+    ///     .pos(Pos::Start, Pos::Start)
+    ///     .area(Area::Values { w: 20, h: 3 })
+    ///     .border(Border::rounded())
+    ///     .build(&mut term)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn input_builder(id: [u8; 3]) -> TextBuilder {
+        Self::builder_of(TextKind::Input, id)
+    }
+
+    /// starts a TextBuilder for the nonedit with id
+    pub fn nonedit_builder(id: [u8; 3]) -> TextBuilder {
+        Self::builder_of(TextKind::NonEdit, id)
+    }
+
+    /// starts a TextBuilder for the text of kind with id
+    pub fn builder_of(kind: TextKind, id: [u8; 3]) -> TextBuilder {
+        TextBuilder {
+            id,
+            kind,
+            hpos: Pos::Center,
+            vpos: Pos::Center,
+            shape: Polygon::Free { vertices: vec![] },
+            area: Area::Fill,
+            border: Border::None,
+            padding: Padding::None,
+            value: vec![],
+            layer: 0,
+            keymap: Keymap::default(),
+            overflow: Overflow::Clip,
+            vstyle: String::new(),
+            bstyle: String::new(),
+            properties: HashMap::new(),
+            attributes: HashSet::new(),
+        }
+    }
+}
+
+impl TextBuilder {
+    /// where the text goes horizontally and vertically inside its container
+    pub fn pos(mut self, hpos: Pos, vpos: Pos) -> Self {
+        self.hpos = hpos;
+        self.vpos = vpos;
+        self
+    }
+
+    /// where the text goes horizontally inside its container
+    pub fn hpos(mut self, hpos: Pos) -> Self {
+        self.hpos = hpos;
+        self
+    }
+
+    /// where the text goes vertically inside its container
+    pub fn vpos(mut self, vpos: Pos) -> Self {
+        self.vpos = vpos;
+        self
+    }
+
+    /// the shape of the text
+    pub fn shape(mut self, shape: Polygon) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// the area the text takes, its border and padding included
+    pub fn area(mut self, area: Area) -> Self {
+        self.area = area;
+        self
+    }
+
+    /// the border of the text
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = border;
+        self
+    }
+
+    /// the padding of the text
+    pub fn padding(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// the value the text starts with
+    pub fn value(mut self, value: &[Option<char>]) -> Self {
+        self.value = value.to_vec();
+        self
+    }
+
+    /// the value the text starts with, a cell per char of value
+    pub fn value_str(mut self, value: &str) -> Self {
+        self.value = value.chars().map(Some).collect();
+        self
+    }
+
+    /// the layer of the text
+    pub fn layer(mut self, layer: u8) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    /// the keymap of the text, see Term::key
+    pub fn keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    /// what happens to the part of the value that doesn't fit inside the text
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// the style of the text's value
+    pub fn vstyle(mut self, style: &Style) -> Self {
+        self.vstyle = style.style();
+        self
+    }
+
+    /// the style of the text's border
+    pub fn bstyle(mut self, style: &Style) -> Self {
+        self.bstyle = style.style();
+        self
+    }
+
+    /// adds a property to the text
+    pub fn property(mut self, name: &'static str, value: Property) -> Self {
+        self.properties.insert(name, value);
+        self
+    }
+
+    /// adds an attribute to the text
+    pub fn attribute(mut self, attribute: &'static str) -> Self {
+        self.attributes.insert(attribute);
+        self
+    }
+
+    /// makes the text and pushes it to its container in term
+    /// returns the id of the text
+    ///
+    /// # Errors
+    /// returns an error if any of the following conditions are met
+    /// - the container of the text is not in term or the id is already taken
+    /// - the area can't hold the border and padding, or the text doesn't fit inside its container
+    /// - a manual border doesn't fit the text
+    /// - the value is longer than the text's area
+    /// - the text takes some of the area of another text of the container
+    pub fn build(self, term: &mut Term) -> Result<[u8; 3], ComponentTreeError> {
+        let id = self.id;
+        term.text_id_check(&id)?;
+
+        let cont = term.container_mut(&[id[0], id[1]]).unwrap();
+        let text = self.text(cont)?;
+        cont.items.push(text);
+
+        Ok(id)
+    }
+
+    // the text, once it is known to fit in cont
+    pub(super) fn text(self, cont: &Container) -> Result<Text, ComponentTreeError> {
+        let id = self.id;
+        if cont.items.iter().any(|t| t.id == id) {
+            return Err(IdError::IdAlreadyTaken { id: id.to_vec() }.into());
+        }
+
+        let contwh = [cont.w, cont.h];
+
+        let [ow, oh] = self.area.unwrap(contwh);
+        let fitted = resolve_wh(&self.border, &self.padding)
+            .and_then(|[wextra, hextra]| Some([ow.checked_sub(wextra)?, oh.checked_sub(hextra)?]));
+        let Some([w, h]) = fitted else {
+            return Err(ComponentTreeError::DecorationDoesNotFit { id: id.to_vec() });
+        };
+
+        let [x0, y0] = place(&self.hpos, &self.vpos, contwh, [ow, oh]);
+        // the same check Container::assign_valid_text_area makes, before the value is checked
+        fit([x0, y0], [ow, oh], contwh)?;
+
+        if let Border::Manual { .. } = self.border {
+            if !border_fit(&self.border, &self.padding, w, h) {
+                return Err(ComponentTreeError::DecorationDoesNotFit { id: id.to_vec() });
+            }
+        }
+
+        if self.value.len() > w as usize * h as usize {
+            return Err(ComponentTreeError::BadValue {
+                len: self.value.len(),
+                available: w.saturating_mul(h),
+            });
+        }
+
+        let [ax0, ay0] = calc_text_abs_ori(
+            &[id[0], id[1]],
+            &[x0, y0],
+            &self.border,
+            &self.padding,
+            cont,
+        );
+
+        let mut text = Text::of_kind(
+            self.kind,
+            id,
+            x0,
+            y0,
+            ax0,
+            ay0,
+            w,
+            h,
+            &self.value,
+            self.border,
+            self.padding,
+        );
+        text.layer = self.layer;
+        text.keymap = self.keymap;
+        text.overflow = self.overflow;
+        text.vstyle = self.vstyle;
+        text.bstyle = self.bstyle;
+        text.properties = self.properties;
+        text.attributes.extend(self.attributes);
+
+        cont.assign_valid_text_area(&text)?;
+
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod builders {
+    use super::*;
+
+    fn term() -> Term {
+        let mut term = Term::new(0, 40, 10);
+        Container::builder([0, 0])
+            .pos(Pos::Center, Pos::Start)
+            .area(Area::Percent { w: 50, h: 100 })
+            .border(Border::rounded())
+            .layer(2)
+            .attribute("sidebar")
+            .property("title", Property::String("files".into()))
+            .build(&mut term)
+            .unwrap();
+
+        term
+    }
+
+    #[test]
+    fn container() {
+        let mut term = term();
+        let c = term.container_ref(&[0, 0]).unwrap();
+        assert_eq!([c.x0, c.y0, c.w, c.h], [10, 0, 18, 8]);
+        assert_eq!(c.layer, 2);
+        assert!(c.attributes.contains("sidebar"));
+        assert!(c.properties.contains_key("title"));
+
+        let taken = Container::builder([0, 0]).build(&mut term);
+        assert_eq!(
+            taken,
+            Err(IdError::IdAlreadyTaken { id: vec![0, 0] }.into())
+        );
+        // a 1x1 area has no room for a border
+        let small = Container::builder([0, 1])
+            .area(Area::Values { w: 1, h: 1 })
+            .border(Border::rounded())
+            .build(&mut term);
+        assert_eq!(
+            small,
+            Err(ComponentTreeError::DecorationDoesNotFit { id: vec![0, 1] })
+        );
+    }
+
+    #[test]
+    fn texts() {
+        let mut term = term();
+        let id = Text::input_builder([0, 0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 10, h: 1 })
+            .overflow(Overflow::Ellipsis)
+            .build(&mut term);
+        assert_eq!(id, Ok([0, 0, 0]));
+        let input = term.input_ref(&[0, 0, 0]).unwrap();
+        assert_eq!([input.ax0, input.ay0, input.w, input.h], [12, 1, 10, 1]);
+        assert_eq!(input.overflow, Overflow::Ellipsis);
+
+        let value = "too long!".chars().map(Some).collect::<Vec<Option<char>>>();
+        let long = Text::nonedit_builder([0, 0, 1])
+            .pos(Pos::End, Pos::End)
+            .area(Area::Values { w: 4, h: 2 })
+            .value_str("too long!")
+            .build(&mut term);
+        assert_eq!(
+            long,
+            Err(ComponentTreeError::BadValue {
+                len: 9,
+                available: 8
+            })
+        );
+        Text::nonedit_builder([0, 0, 1])
+            .pos(Pos::End, Pos::End)
+            .area(Area::Values { w: 4, h: 2 })
+            .value(&value[..2])
+            .build(&mut term)
+            .unwrap();
+        let nonedit = term.nonedit_ref(&[0, 0, 1]).unwrap();
+        assert_eq!([nonedit.x0, nonedit.y0], [14, 6]);
+        assert_eq!(nonedit.value_string(), "to");
+
+        // the deprecated Text::builder still takes the kind from the parity of the id
+        let shim = Text::builder([0, 0, 2])
+            .pos(Pos::Start, Pos::End)
+            .area(Area::Values { w: 1, h: 1 })
+            .build(&mut term);
+        assert_eq!(shim, Ok([0, 0, 2]));
+        assert!(term.input_ref(&[0, 0, 2]).is_some());
+        assert_eq!(TextKind::of(&[0, 0, 3]), TextKind::NonEdit);
+        let odd = Text::input_builder([0, 0, 3])
+            .pos(Pos::Value(2), Pos::End)
+            .area(Area::Values { w: 1, h: 1 })
+            .build(&mut term);
+        assert_eq!(odd, Ok([0, 0, 3]));
+        assert_eq!(term.text_ref(&[0, 0, 3]).unwrap().kind(), TextKind::Input);
+    }
+
+    #[test]
+    fn metas() {
+        let mut term = term();
+        let meta = ContainerMeta::new()
+            .area(Area::Values { w: 5, h: 5 })
+            .hpos(Pos::Start)
+            .vpos(Pos::Start);
+        assert_eq!(term.container_from_meta(&meta), Ok([0, 1]));
+        // the same place is taken now
+        assert!(meta.validate(&term).is_err());
+        let below = meta.clone().vpos(Pos::End).style(Style::new().bold());
+        assert_eq!(term.container_from_meta(&below), Ok([0, 2]));
+        let cont = term.container_ref(&[0, 2]).unwrap();
+        assert_eq!(cont.y0, 5);
+        assert_eq!(cont.style, Some(Style::new().bold()));
+
+        let meta = InputMeta::new()
+            .parent([0, 0])
+            .area(Area::Values { w: 18, h: 1 })
+            .hpos(Pos::Start);
+        let ids =
+            [Pos::Start, Pos::Value(1)].map(|vpos| term.input_from_meta(&meta.clone().vpos(vpos)));
+        assert_eq!(ids, [Ok([0, 0, 0]), Ok([0, 0, 1])]);
+        let too_tall = meta.clone().area(Area::Values { w: 18, h: 9 });
+        let cont = term.container_ref(&[0, 0]).unwrap();
+        assert!(matches!(
+            too_tall.validate(cont),
+            Err(ComponentTreeError::AreaOutOfBounds { .. })
+        ));
+
+        let value = "files".chars().map(Some).collect::<Vec<Option<char>>>();
+        let meta = NonEditMeta::new()
+            .parent([0, 1])
+            .value(&value)
+            .style(Style::new().italic());
+        assert_eq!(term.nonedit_from_meta(&meta), Ok([0, 1, 0]));
+        let text = term.nonedit_ref(&[0, 1, 0]).unwrap();
+        assert_eq!(text.value_string(), "files");
+        assert_eq!(text.vstyle, "\x1b[3m");
+        let orphan = meta.parent([0, 9]);
+        assert_eq!(
+            term.nonedit_from_meta(&orphan),
+            Err(IdError::ParentIdNotFound { id: vec![0, 9] }.into())
+        );
+    }
+
+    // the origin a text is checked at is the one it gets
+    #[test]
+    fn placed() {
+        let placed = |hpos, vpos, [w, h]: [u16; 2], border| {
+            let mut term = term();
+            Text::input_builder([0, 0, 0])
+                .pos(hpos, vpos)
+                .area(Area::Values { w, h })
+                .border(border)
+                .build(&mut term)?;
+            let input = term.input_ref(&[0, 0, 0]).unwrap();
+
+            Ok::<_, ComponentTreeError>([input.x0, input.y0])
+        };
+        let none = Border::None;
+        assert_eq!(placed(Pos::End, Pos::End, [4, 2], none), Ok([14, 6]));
+        assert_eq!(
+            placed(Pos::Center, Pos::Center, [5, 3], Border::rounded()),
+            Ok([6, 2])
+        );
+        assert_eq!(
+            placed(Pos::End, Pos::End, [18, 8], Border::rounded()),
+            Ok([0, 0])
+        );
+        assert_eq!(placed(Pos::Center, Pos::End, [18, 1], none), Ok([0, 7]));
+        assert_eq!(placed(Pos::Value(14), Pos::Start, [4, 1], none), Ok([14, 0]));
+        assert_eq!(
+            placed(Pos::Value(15), Pos::Start, [4, 1], none),
+            Err(SpaceError::OriginOutOfBounds {
+                origin: [15, 0],
+                available: [18, 8]
+            }
+            .into())
+        );
+        assert_eq!(
+            placed(Pos::End, Pos::Center, [19, 1], none),
+            Err(SpaceError::AreaOutOfBounds {
+                requested: [19, 1],
+                available: [18, 8]
+            }
+            .into())
+        );
+
+        // containers at the end of the term
+        let mut term = term();
+        let corner = Container::builder([0, 1])
+            .pos(Pos::End, Pos::End)
+            .area(Area::Values { w: 10, h: 2 })
+            .build(&mut term);
+        assert_eq!(corner, Ok([0, 1]));
+        let c = term.container_ref(&[0, 1]).unwrap();
+        assert_eq!([c.x0, c.y0], [30, 8]);
+        let past = Container::builder([0, 2])
+            .pos(Pos::Value(31), Pos::Start)
+            .area(Area::Values { w: 10, h: 2 })
+            .build(&mut term);
+        assert_eq!(
+            past,
+            Err(SpaceError::OriginOutOfBounds {
+                origin: [31, 0],
+                available: [40, 10]
+            }
+            .into())
+        );
     }
 }
@@ -0,0 +1,327 @@
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use super::{Container, Property, Term, Text};
+use crate::space::{calc_text_abs_ori, AreaIndex};
+
+// a value derived from the geometry of a container or a text, e.g. its decorated size,
+// kept with the hash of the geometry it was worked out from, see Container::geometry
+// the value is worked out again once the geometry it is asked for hashes to another one
+#[derive(Default, Clone)]
+pub(crate) struct Cached<T: Copy>(Cell<Option<(u64, T)>>);
+
+impl<T: Copy> Cached<T> {
+    // the value for the geometry hash, f works it out if it isn't kept yet
+    pub(crate) fn get(&self, geometry: u64, f: impl FnOnce() -> T) -> T {
+        match self.0.get() {
+            Some((kept, value)) if kept == geometry => value,
+            _ => {
+                let value = f();
+                self.0.set(Some((geometry, value)));
+
+                value
+            }
+        }
+    }
+}
+
+// an unchanged geometry is the same no matter what is kept
+impl<T: Copy> PartialEq for Cached<T> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<T: Copy> std::fmt::Debug for Cached<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("Cached")
+    }
+}
+
+// the ids of the texts in the order Tab goes through them, see Term::tab_order
+// kept with the hash of the ids and "tab-index" properties they were sorted from,
+// they are sorted again once a text or its index changed
+#[derive(Default, Clone)]
+pub(crate) struct TabOrder(RefCell<Option<(u64, Vec<[u8; 3]>)>>);
+
+// the order is worked out from the term, like the other caches
+impl PartialEq for TabOrder {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for TabOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("TabOrder")
+    }
+}
+
+impl Term {
+    /// the ids of the texts in the order Tab goes through them, focusable or not
+    /// the texts with a "tab-index" Property::Int or Property::UInt come first by index then id,
+    /// the texts without one follow in the order of their containers, the ones with a negative
+    /// index are left out, they can only be focused by Term::focus or a click
+    pub fn tab_order(&self) -> Vec<[u8; 3]> {
+        let texts = self.containers.iter().flat_map(|c| c.items.iter());
+        let indexed = texts.map(|t| (t.id, tab_index(t))).collect::<Vec<_>>();
+        let hash = hashed(&indexed);
+
+        let mut kept = self.tab_order.0.borrow_mut();
+        match &*kept {
+            Some((sorted, order)) if *sorted == hash => order.clone(),
+            _ => {
+                let mut order = indexed
+                    .into_iter()
+                    .filter(|(_, idx)| idx.map_or(true, |idx| idx >= 0))
+                    .collect::<Vec<_>>();
+                // a stable sort, the texts without an index keep their order
+                order.sort_by_key(|(id, idx)| match idx {
+                    Some(idx) => (false, *idx, *id),
+                    None => (true, 0, [0; 3]),
+                });
+                let order = order.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+                *kept = Some((hash, order.clone()));
+
+                order
+            }
+        }
+    }
+}
+
+// what the area of a container is kept with, its id, its layer, whether it is collapsed
+// and the hash of its geometry
+type Shape = ([u8; 2], u8, bool, u64);
+
+// the areas of the containers as Term::area_taken looks them up, kept with the shape
+// of each container they were worked out from and the size of the term,
+// an area is only worked out again once one of those changed
+#[derive(Default, Clone)]
+pub(crate) struct Areas(RefCell<Option<(Vec<Shape>, [u16; 2], AreaIndex)>>);
+
+// the areas are worked out from the term, like the other caches
+impl PartialEq for Areas {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for Areas {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("Areas")
+    }
+}
+
+// what the area of c is kept with
+fn shape(c: &Container) -> Shape {
+    (c.id, c.layer, c.is_collapsed(), c.geometry())
+}
+
+// the layer and the area c takes, none when it is collapsed
+fn entry(c: &Container) -> Option<(u8, [u16; 4])> {
+    (!c.is_collapsed()).then(|| (c.layer, c.outer_area()))
+}
+
+impl Term {
+    // the first container other than the one with id on layer that takes some of area
+    // only the containers whose areas changed since the last call are looked at,
+    // the other ones are only looked at when they are near area
+    pub(crate) fn area_taken(&self, id: [u8; 2], layer: u8, area: [u16; 4]) -> Option<&Container> {
+        let mut kept = self.areas.0.borrow_mut();
+        let size = [self.w, self.h];
+        let fresh = match &*kept {
+            Some((shapes, at, _)) => *at != size || shapes.len() > self.containers.len(),
+            None => true,
+        };
+        if fresh {
+            *kept = Some((vec![], size, AreaIndex::new(size)));
+        }
+        let (shapes, _, index) = kept.as_mut().unwrap();
+        for (n, c) in self.containers.iter().enumerate() {
+            match shapes.get(n) {
+                Some(kept) if *kept == shape(c) => (),
+                Some(_) => {
+                    shapes[n] = shape(c);
+                    index.set(n, entry(c));
+                }
+                None => {
+                    shapes.push(shape(c));
+                    index.push(entry(c));
+                }
+            }
+        }
+        let n = index.find(layer, area, |n| self.containers[n].id == id)?;
+
+        self.containers.get(n)
+    }
+}
+
+// the "tab-index" property of text
+fn tab_index(text: &Text) -> Option<i64> {
+    match text.properties.get("tab-index") {
+        Some(Property::Int(idx)) => Some(*idx),
+        Some(Property::UInt(idx)) => Some(i64::try_from(*idx).unwrap_or(i64::MAX)),
+        _ => None,
+    }
+}
+
+// the hash of the fields a cached value is worked out from
+fn hashed(fields: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    fields.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+impl Container {
+    // the hash of this container's origin, size, border and padding,
+    // its decorated size and area are worked out again once it changed
+    pub(crate) fn geometry(&self) -> u64 {
+        hashed((self.x0, self.y0, self.w, self.h, self.border, self.padding))
+    }
+
+    /// tells this container that its border, padding, origin or size changed,
+    /// the absolute origins of its texts follow it and Term::hit_test sees it
+    /// the methods that change the geometry call it, code that sets those fields directly
+    /// has to call it too, the decorated sizes follow the fields on their own
+    pub fn invalidate(&mut self) {
+        self.changes.bump();
+        let items = std::mem::take(&mut self.items);
+        self.items = items
+            .into_iter()
+            .map(|mut text| {
+                [text.ax0, text.ay0] = calc_text_abs_ori(
+                    &[text.id[0], text.id[1]],
+                    &[text.x0, text.y0],
+                    &text.border,
+                    &text.padding,
+                    self,
+                );
+                text.invalidate();
+
+                text
+            })
+            .collect();
+    }
+}
+
+impl Text {
+    // the hash of this text's origins, size, border and padding,
+    // its decorated size and area are worked out again once it changed
+    pub(crate) fn geometry(&self) -> u64 {
+        let origins = [self.x0, self.y0, self.ax0, self.ay0];
+
+        hashed((origins, self.w, self.h, self.border, self.padding))
+    }
+
+    /// tells this text that its border, padding, origin or size changed,
+    /// Term::hit_test sees it, see Container::invalidate
+    pub fn invalidate(&mut self) {
+        self.changes.bump();
+    }
+}
+
+#[cfg(test)]
+mod cache {
+    use super::*;
+    use crate::space::{border::Border, padding::Padding, Area, Pos};
+
+    #[test]
+    fn kept() {
+        let cached = Cached::default();
+        let mut runs = 0;
+        let mut get = |geometry| {
+            cached.get(geometry, || {
+                runs += 1;
+                [runs, 0]
+            })
+        };
+        assert_eq!(get(0), [1, 0]);
+        assert_eq!(get(0), [1, 0]);
+        assert_eq!(get(1), [2, 0]);
+    }
+
+    #[test]
+    fn geometry() {
+        let mut term = Term::new(0, 20, 10);
+        Container::builder([0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 10, h: 6 })
+            .build(&mut term)
+            .unwrap();
+        Text::nonedit_builder([0, 0, 1])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 4, h: 2 })
+            .build(&mut term)
+            .unwrap();
+        let cont = term.container_mut(&[0, 0]).unwrap();
+        assert_eq!(cont.decorate(), [10, 6]);
+
+        // a field set directly is seen by the caches, the texts follow once the container is told
+        cont.border = Border::Uniform('*');
+        cont.x0 = 5;
+        assert_eq!(cont.decorate(), [12, 8]);
+        cont.invalidate();
+        let text = &cont.items[0];
+        assert_eq!([text.ax0, text.ay0], [7, 1]);
+
+        let text = &mut cont.items[0];
+        assert_eq!(text.decorate(), [4, 2]);
+        text.padding = Padding::Inner {
+            top: 1,
+            bottom: 0,
+            right: 0,
+            left: 1,
+        };
+        assert_eq!(text.decorate(), [5, 3]);
+    }
+    #[test]
+    fn tab_order() {
+        let mut term = Term::new(0, 40, 4);
+        let mut c = Container::new([0, 0], 0, 0, 40, 4, Border::None, Padding::None);
+        for idx in 0..5 {
+            let x = idx as u16 * 5;
+            let (border, padding) = (Border::None, Padding::None);
+            c.items.push(Text::input(
+                [0, 0, idx],
+                x,
+                0,
+                0,
+                0,
+                4,
+                1,
+                &[],
+                border,
+                padding,
+            ));
+        }
+        term.containers.push(c);
+        assert_eq!(term.tab_order(), [0, 1, 2, 3, 4].map(|i| [0, 0, i]));
+
+        // the indexed texts come first, by index then id, the others keep their order
+        let index = |term: &mut Term, id: u8, idx: i64| {
+            let text = term.text_mut(&[0, 0, id]).unwrap();
+            text.properties.insert("tab-index", Property::Int(idx));
+        };
+        index(&mut term, 3, 1);
+        index(&mut term, 4, 0);
+        index(&mut term, 1, 1);
+        assert_eq!(term.tab_order(), [4, 1, 3, 0, 2].map(|i| [0, 0, i]));
+
+        // a negative index is left out of the order but can still be focused
+        index(&mut term, 0, -1);
+        assert_eq!(term.tab_order(), [4, 1, 3, 2].map(|i| [0, 0, i]));
+        term.focus(&[0, 0, 0]).unwrap();
+        term.focus_next();
+        assert_eq!(term.focused, Some([0, 0, 4]));
+        term.focus_next();
+        term.focus_prev();
+        assert_eq!(term.focused, Some([0, 0, 4]));
+        term.focus_prev();
+        assert_eq!(term.focused, Some([0, 0, 2]));
+
+        term.containers[0].items.remove(1);
+        assert_eq!(term.tab_order(), [4, 3, 2].map(|i| [0, 0, i]));
+    }
+}
@@ -0,0 +1,351 @@
+use crate::inputs::keyboard::{Char, KbdEvent, CC};
+use crate::themes::Style;
+
+use super::Property;
+use super::{Term, Text};
+
+/// what a Checkbox looks like
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Look {
+    /// "[x] label" when checked, "[ ] label" when not
+    #[default]
+    Box,
+    /// a switch, "(    ●) label" when checked, "(●    ) label" when not
+    Switch,
+}
+
+/// a boolean input with a label, shown in the first row of a Text, see Text::set_checkbox
+/// Space or Enter toggles a focused checkbox, see Term::on_toggle
+/// a text showing a checkbox is gone through by Tab like the inputs are
+///
+/// checking one of the checkboxes of a container with the same "radio-group" property
+/// unchecks the others, and checking a checked one does nothing
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Checkbox {
+    label: String,
+    checked: bool,
+    look: Look,
+    // whether the text showing it is focused, see Term::focus
+    focused: bool,
+    // the style escape sequence of the brackets while focused
+    focus_style: String,
+}
+
+impl Checkbox {
+    /// makes a new unchecked Checkbox with label
+    /// its brackets are shown in bold while it is focused unless told otherwise, see
+    /// Checkbox::focus_style
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            checked: false,
+            look: Look::Box,
+            focused: false,
+            focus_style: "\x1b[1m".to_string(),
+        }
+    }
+
+    /// makes a new unchecked Checkbox with label that looks like a switch
+    pub fn switch(label: &str) -> Self {
+        Self {
+            look: Look::Switch,
+            ..Self::new(label)
+        }
+    }
+
+    /// changes the style of the brackets while the checkbox is focused
+    pub fn focus_style(&mut self, style: &Style) {
+        self.focus_style = style.style();
+    }
+
+    /// the label
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// what the checkbox looks like
+    pub fn look(&self) -> Look {
+        self.look
+    }
+
+    /// whether the checkbox is checked
+    pub fn checked(&self) -> bool {
+        self.checked
+    }
+
+    // the chars of the brackets and what is between them
+    fn brackets(&self) -> &'static str {
+        match (self.look, self.checked) {
+            (Look::Box, true) => "[x]",
+            (Look::Box, false) => "[ ]",
+            (Look::Switch, true) => "(    ●)",
+            (Look::Switch, false) => "(●    )",
+        }
+    }
+
+    // the cells of the first row the brackets take and their style, while focused
+    pub(crate) fn focused(&self) -> Option<([u16; 2], &str)> {
+        if !self.focused {
+            return None;
+        }
+        let end = self.brackets().chars().count() as u16;
+
+        Some(([0, end], &self.focus_style))
+    }
+}
+
+// checkboxes
+impl Text {
+    /// shows checkbox in the first row of this text, replacing its value
+    pub fn set_checkbox(&mut self, checkbox: Checkbox) {
+        self.checkbox = Some(checkbox);
+        self.set_focusable(true);
+        self.sync_checkbox();
+    }
+
+    /// the checkbox this text shows, if it shows one
+    pub fn checkbox(&self) -> Option<&Checkbox> {
+        self.checkbox.as_ref()
+    }
+
+    /// whether the checkbox this text shows is checked, false if it shows none
+    pub fn checked(&self) -> bool {
+        self.checkbox.as_ref().is_some_and(Checkbox::checked)
+    }
+
+    /// checks or unchecks the checkbox this text shows, the other checkboxes of its radio group
+    /// are left as they are, see Term::set_checked
+    /// returns whether the checkbox changed
+    pub fn set_checked(&mut self, checked: bool) -> bool {
+        let Some(checkbox) = &mut self.checkbox else {
+            return false;
+        };
+        let changed = checkbox.checked != checked;
+        checkbox.checked = checked;
+        if changed {
+            self.sync_checkbox();
+        }
+
+        changed
+    }
+
+    // writes the brackets then the label to the first row
+    fn sync_checkbox(&mut self) {
+        let Some(checkbox) = &self.checkbox else {
+            return;
+        };
+        let w = self.w as usize;
+        let mut cells = vec![None; w * self.h as usize];
+        let shown = format!("{} {}", checkbox.brackets(), checkbox.label);
+        cells
+            .iter_mut()
+            .take(w)
+            .zip(shown.chars())
+            .for_each(|(cell, c)| *cell = Some(c));
+        self.value = cells.into();
+        self.scroll = 0;
+        self.mark_dirty();
+    }
+
+    // tells the checkbox whether this text is focused
+    pub(crate) fn focus_checkbox(&mut self, focused: bool) {
+        let Some(checkbox) = &mut self.checkbox else {
+            return;
+        };
+        if checkbox.focused != focused {
+            checkbox.focused = focused;
+            self.mark_dirty();
+        }
+    }
+
+    // the "radio-group" property, if this text shows a checkbox
+    fn radio_group(&self) -> Option<&str> {
+        self.checkbox.as_ref()?;
+        match self.properties.get("radio-group") {
+            Some(Property::String(group)) => Some(group),
+            _ => None,
+        }
+    }
+}
+
+impl Term {
+    /// calls f with the checkbox's id and whether it is checked whenever a focused checkbox
+    /// is toggled, the radio group checkboxes that were unchecked by it are reported too,
+    /// replaces the previous callback
+    pub fn on_toggle(&mut self, f: impl FnMut([u8; 3], bool) + 'static) {
+        self.list_hooks.toggle = Some(Box::new(f));
+    }
+
+    /// checks or unchecks the checkbox the text with id shows, checking it unchecks
+    /// the other checkboxes of its radio group
+    /// returns the ids of the checkboxes that changed
+    pub fn set_checked(&mut self, id: &[u8; 3], checked: bool) -> Vec<[u8; 3]> {
+        let Some(text) = self.text_mut(id) else {
+            return vec![];
+        };
+        if !text.set_checked(checked) {
+            return vec![];
+        }
+        let group = text.radio_group().map(str::to_string);
+        let mut changed = vec![*id];
+        let (Some(group), true) = (group, checked) else {
+            return changed;
+        };
+
+        let Some(c) = self.container_mut(&[id[0], id[1]]) else {
+            return changed;
+        };
+        for text in c.items.iter_mut().filter(|t| t.id != *id) {
+            if text.radio_group() == Some(&group) && text.set_checked(false) {
+                changed.push(text.id);
+            }
+        }
+
+        changed
+    }
+
+    // toggles the focused checkbox for Space and Enter
+    // returns None if the key is left to the keymaps
+    pub(crate) fn checkbox_key(&mut self, event: &KbdEvent) -> Option<bool> {
+        let id = self.focused?;
+        let text = self.text_ref(&id)?;
+        text.checkbox.as_ref()?;
+        if !event.modifiers.is_empty() || !matches!(event.char, Char::CC(CC::CR) | Char::Char(' '))
+        {
+            return None;
+        }
+        // a checked radio stays checked
+        let checked = !text.checked();
+        if !checked && text.radio_group().is_some() {
+            return Some(false);
+        }
+
+        let changed = self.set_checked(&id, checked);
+        if let Some(hook) = &mut self.list_hooks.toggle {
+            // only the first one can have been checked
+            for (idx, id) in changed.iter().enumerate() {
+                hook(*id, idx == 0 && checked);
+            }
+        }
+
+        Some(!changed.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod checkbox {
+    use super::*;
+    use crate::components::Container;
+    use crate::inputs::keyboard::{KeyKind, Modifiers};
+    use crate::space::{border::Border, padding::Padding};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // 3 checkboxes, the last 2 in the "size" radio group
+    fn form() -> Term {
+        let mut term = Term::new(0, 30, 8);
+        let mut c = Container::new([0, 0], 0, 0, 20, 4, Border::None, Padding::None);
+        let boxes = [
+            Checkbox::new("agree"),
+            Checkbox::switch("small"),
+            Checkbox::switch("large"),
+        ];
+        for (idx, checkbox) in boxes.into_iter().enumerate() {
+            let id = [0, 0, 2 * idx as u8];
+            let mut text = Text::input(
+                id,
+                0,
+                idx as u16,
+                1,
+                idx as u16 + 1,
+                14,
+                1,
+                &[],
+                Border::None,
+                Padding::None,
+            );
+            text.set_checkbox(checkbox);
+            if idx > 0 {
+                text.properties
+                    .insert("radio-group", Property::String("size".into()));
+            }
+            c.items.push(text);
+        }
+        term.containers.push(c);
+        _ = term.focus(&[0, 0, 0]);
+
+        term
+    }
+
+    fn key(char: Char) -> KbdEvent {
+        KbdEvent {
+            char,
+            modifiers: Modifiers(0),
+            kind: KeyKind::Press,
+        }
+    }
+
+    fn shown(term: &Term, id: u8) -> String {
+        term.input_ref(&[0, 0, id]).unwrap().value_string()
+    }
+
+    #[test]
+    fn toggle() {
+        let mut term = form();
+        let toggled = Rc::new(RefCell::new(vec![]));
+        let seen = toggled.clone();
+        term.on_toggle(move |id, checked| seen.borrow_mut().push((id[2], checked)));
+
+        assert_eq!(shown(&term, 0), "[ ] agree");
+        term.key(&key(Char::Char(' ')));
+        assert!(term.input_ref(&[0, 0, 0]).unwrap().checked());
+        assert_eq!(shown(&term, 0), "[x] agree");
+        term.key(&key(Char::CC(CC::CR)));
+        assert!(!term.input_ref(&[0, 0, 0]).unwrap().checked());
+        assert_eq!(*toggled.borrow(), [(0, true), (0, false)]);
+
+        // the focused brackets get the focus style, the others lose it
+        let focused = |term: &Term, id| {
+            let text = term.input_ref(&[0, 0, id]).unwrap();
+            text.checkbox().unwrap().focused().is_some()
+        };
+        assert!(focused(&term, 0));
+        term.focus_next();
+        assert_eq!([focused(&term, 0), focused(&term, 2)], [false, true]);
+        assert_eq!(
+            term.input_ref(&[0, 0, 2])
+                .unwrap()
+                .checkbox()
+                .unwrap()
+                .focused(),
+            Some(([0, 7], "\x1b[1m"))
+        );
+        let mut out = vec![];
+        let text = term.input_ref(&[0, 0, 2]).unwrap();
+        text.render_value_with(&mut out, &term.capabilities);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("\x1b[1m(●    )\x1b[0m small"));
+    }
+
+    #[test]
+    fn radio_group() {
+        let mut term = form();
+        let toggled = Rc::new(RefCell::new(vec![]));
+        let seen = toggled.clone();
+        term.on_toggle(move |id, checked| seen.borrow_mut().push((id[2], checked)));
+
+        assert_eq!(term.set_checked(&[0, 0, 2], true), [[0, 0, 2]]);
+        assert_eq!(shown(&term, 2), "(    ●) small");
+        _ = term.focus(&[0, 0, 4]);
+        term.key(&key(Char::Char(' ')));
+        assert_eq!(*toggled.borrow(), [(4, true), (2, false)]);
+        assert_eq!(shown(&term, 2), "(●    ) small");
+
+        // the checked radio can't be unchecked by toggling it
+        term.key(&key(Char::Char(' ')));
+        assert!(term.input_ref(&[0, 0, 4]).unwrap().checked());
+        // the checkbox outside the group is left alone
+        term.set_checked(&[0, 0, 0], true);
+        assert!(term.input_ref(&[0, 0, 4]).unwrap().checked());
+    }
+}
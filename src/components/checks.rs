@@ -0,0 +1,67 @@
+use std::collections::HashSet;
+
+use crate::space::ends_past;
+
+use super::{Container, IdError, Term};
+
+impl Term {
+    pub(crate) fn is_valid_container_id(&self, id: &[u8; 2]) -> bool {
+        !self.has_container(&id)
+    }
+
+    // the first container id of this term that is free, None once all 256 are taken
+    pub(crate) fn free_container_id(&self) -> Option<[u8; 2]> {
+        let used = self.containers.iter().map(|c| c.id[1]);
+
+        smallest_free(used, 0..=u8::MAX).map(|cid| [self.id, cid])
+    }
+
+    // why id can't be given to a new input, or a new nonedit, if it can't
+    pub(crate) fn text_id_check(&self, id: &[u8; 3]) -> Result<(), IdError> {
+        if !self.has_container(&[id[0], id[1]]) {
+            return Err(IdError::ParentIdNotFound {
+                id: id[..2].to_vec(),
+            });
+        }
+        if self.text_ref(id).is_some() {
+            return Err(IdError::IdAlreadyTaken { id: id.to_vec() });
+        }
+
+        Ok(())
+    }
+}
+
+impl Container {
+    // the first text id of this container that is free, None once all 256 are taken
+    pub(crate) fn free_text_id(&self) -> Option<[u8; 3]> {
+        let used = self.items.iter().map(|t| t.id[2]);
+
+        smallest_free(used, 0..=u8::MAX).map(|tid| [self.id[0], self.id[1], tid])
+    }
+
+    pub(crate) fn area_out_of_bounds(&self, wh: &[u16; 2]) -> bool {
+        let [w, h] = *wh;
+        if w > self.w || h > self.h {
+            return true;
+        }
+
+        false
+    }
+
+    // flex and grid dont respect origins
+    pub(crate) fn origin_out_of_bounds(&self, xy: &[u16; 2], wh: &[u16; 2]) -> bool {
+        let [x0, y0] = *xy;
+        let [w, h] = *wh;
+        ends_past(x0, w, self.w) || ends_past(y0, h, self.h)
+    }
+}
+
+// the smallest of candidates that is not in used, used doesn't have to be in any order
+pub(crate) fn smallest_free(
+    used: impl Iterator<Item = u8>,
+    mut candidates: impl Iterator<Item = u8>,
+) -> Option<u8> {
+    let used = used.collect::<HashSet<u8>>();
+
+    candidates.find(|id| !used.contains(id))
+}
@@ -3,20 +3,22 @@ use std::io::StdoutLock;
 use std::io::Write;
 
 use crate::console::winsize::winsize;
+#[cfg(feature = "layout")]
 use crate::layout::Layout;
 use crate::render_pipeline;
 use crate::space::{
-    area_conflicts, between, border::Border, border_fit, padding::Padding, Area, Pos,
+    area_conflicts, between, border::Border, border_fit, fit, overlap, padding::Padding,
+    Area, Pos,
 };
 use crate::themes::Style;
 
 use super::Property;
 use super::{ComponentTreeError, SpaceError};
-use super::{Term, Text};
+use super::{Cached, Term, Text};
 
 /// Container objects are direct children of the Term object
 /// and direct parents of the Text objects
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Container {
     /// the layer of this container in terminal
     /// decide which container takes render priority in case of conflict
@@ -41,9 +43,22 @@ pub struct Container {
     // the following field has now become part of properties
     /// border style
     pub bstyle: String,
+    /// the style of this container's area, see Container::set_style
+    pub style: Option<Style>,
+    // the style of this container's border while the focused text is one of its borderless
+    // items, see Term::mark_focused
+    pub(crate) focus: Option<String>,
+    #[cfg(feature = "layout")]
     pub layout: Layout,
     pub properties: HashMap<&'static str, Property>,
     pub attributes: HashSet<&'static str>,
+    // the hpos and vpos the container was placed by, see Term::fit_container
+    pub(crate) anchor: Option<[Pos; 2]>,
+    // the decorated size of this container and the area it takes, see Container::decorate
+    pub(crate) decoration: Cached<[u16; 2]>,
+    pub(crate) outer: Cached<[u16; 4]>,
+    // the count of the changes to the geometry of the components of its term
+    pub(crate) changes: super::hit::Changes,
 }
 
 impl std::fmt::Display for Container {
@@ -71,13 +86,20 @@ impl Container {
             h,
             x0,
             layer: 0,
+            #[cfg(feature = "layout")]
             layout: Layout::Canvas,
             y0,
             border,
             padding,
             bstyle: "".to_string(),
+            style: None,
+            focus: None,
             properties: HashMap::new(),
             attributes: HashSet::new(),
+            anchor: None,
+            decoration: Cached::default(),
+            outer: Cached::default(),
+            changes: Default::default(),
         }
     }
 
@@ -100,49 +122,149 @@ impl Container {
         self.bstyle = style.style();
     }
 
+    /// gives this container the style its area is rendered in before its texts,
+    /// None leaves the area alone, the container is marked dirty
+    pub fn set_style(&mut self, style: Option<Style>) {
+        self.style = style;
+        self.mark_dirty();
+    }
+
+    /// whether the padding inside this container's border is rendered in its style too,
+    /// it is left alone by default, the container is marked dirty
+    pub fn style_padding(&mut self, styled: bool) {
+        match styled {
+            true => self.attributes.insert("style-padding"),
+            false => self.attributes.remove("style-padding"),
+        };
+        self.mark_dirty();
+    }
+
+    /// marks this container to be rendered again by the next Term::render_dirty call
+    pub fn mark_dirty(&mut self) {
+        self.attributes.insert("dirty");
+    }
+
+    /// hides this container, a hidden container and its items are not rendered, hit by the
+    /// mouse or focused by Tab
+    /// the cells it took stay on the display until something is rendered over them,
+    /// see Term::hide, and no other container can take them, see Container::collapse
+    pub fn hide(&mut self) {
+        if self.attributes.insert("hidden") {
+            self.changes.bump();
+        }
+    }
+
+    /// hides this container and gives up its area, the other containers can be placed over it
+    /// until it is shown again
+    pub fn collapse(&mut self) {
+        self.hide();
+        // the area it takes changed
+        if self.attributes.insert("collapse") {
+            self.invalidate();
+        }
+    }
+
+    /// shows this container again, hidden or collapsed, it is marked dirty
+    pub fn show(&mut self) {
+        if self.attributes.remove("collapse") {
+            self.invalidate();
+        }
+        if self.attributes.remove("hidden") {
+            self.changes.bump();
+            self.mark_dirty();
+        }
+    }
+
+    /// whether this container is hidden
+    pub fn is_hidden(&self) -> bool {
+        self.attributes.contains("hidden")
+    }
+
+    /// whether this container is collapsed, hidden without taking its area
+    pub fn is_collapsed(&self) -> bool {
+        self.is_hidden() && self.attributes.contains("collapse")
+    }
+
+    // the area this container takes on the display, border included, as [x, y, w, h]
+    pub(crate) fn outer_area(&self) -> [u16; 4] {
+        self.outer.get(self.geometry(), || {
+            let [_, pol, pot, _, pir, pil, pit, pib] =
+                render_pipeline::spread_padding(&self.padding);
+            let b = if let Border::None = self.border { 0 } else { 1 };
+
+            [
+                self.x0 + pol + 1,
+                self.y0 + pot,
+                pil + self.w + pir + 2 * b,
+                pit + self.h + pib + 2 * b,
+            ]
+        })
+    }
+
     /// returns the id of the parent term of this container
     pub fn parent(&self) -> u8 {
         self.id[0]
     }
 
+    /// shrinks this container to the smallest area holding all of its texts, their borders and
+    /// paddings included, the border and padding of the container stay around that area
+    /// the texts stay where they are on the display, the origin of the container moves to them
+    /// a container with no texts is left as it is, and a container is never made smaller
+    /// than 1x1
+    /// returns whether the container changed, it is marked dirty if it did
+    pub fn fit_to_children(&mut self) -> bool {
+        let Some([x0, y0, x1, y1]) = self.children_bounds() else {
+            return false;
+        };
+        let [w, h] = [(x1 - x0).max(1), (y1 - y0).max(1)];
+        if [x0, y0, w, h] == [0, 0, self.w, self.h] {
+            return false;
+        }
+
+        self.items.iter_mut().for_each(|t| {
+            t.x0 -= x0;
+            t.y0 -= y0;
+        });
+        self.x0 += x0;
+        self.y0 += y0;
+        [self.w, self.h] = [w, h];
+        self.invalidate();
+        self.mark_dirty();
+
+        true
+    }
+
+    // the [x0, y0, x1, y1] cells the texts of this container take, relative to its inner area
+    fn children_bounds(&self) -> Option<[u16; 4]> {
+        self.items
+            .iter()
+            .map(|t| {
+                let [w, h] = t.decorate();
+                [t.x0, t.y0, t.x0.saturating_add(w), t.y0.saturating_add(h)]
+            })
+            .reduce(|[ax0, ay0, ax1, ay1], [bx0, by0, bx1, by1]| {
+                [ax0.min(bx0), ay0.min(by0), ax1.max(bx1), ay1.max(by1)]
+            })
+    }
+
     // called on auto and base input/nonedit initializers
     /// checks for the validity of a text object's area before creating it
     pub(super) fn assign_valid_text_area(
         &self, // container
         text: &Text,
     ) -> Result<(), SpaceError> {
-        let [x0, y0] = [text.x0, text.y0];
-        let [w, h] = text.decorate();
-
-        // check if new area is bigger than parent container area
-        if self.w * self.h < w * h
-            || x0 > self.w
-            || y0 > self.h
-            || w > self.w
-            || h > self.h
-            || x0 + w > self.w
-            || y0 + h > self.h
-        {
-            // println!("0\r\n{x0} + {w} > {}\r\n{y0} + {h} > {}", self.w, self.h);
-            return Err(SpaceError::AreaOutOfBounds);
-        }
+        fit([text.x0, text.y0], text.decorate(), [self.w, self.h])?;
 
-        let mut e = 0;
-
-        self.items.iter().for_each(|t| {
-            let [top, right, bottom, left] =
-                area_conflicts(x0, y0, text.w, text.h, t.x0, t.y0, t.w, t.h);
-            // conflict case
-            if (left > 0 || right < 0) && (top > 0 || bottom < 0) {
-                // TODO: actually handle overlay logic
-                let e = self.shift_no_overlay(top, right, bottom, left);
-                if e != 0 {}
-            }
-        });
-
-        if e == 1 {
-            // println!("1");
-            return Err(SpaceError::AreaOutOfBounds);
+        let area = text.outer_area();
+        if let Some(other) = self
+            .items
+            .iter()
+            .filter(|t| !t.is_collapsed())
+            .find(|t| t.layer == text.layer && overlap(t.outer_area(), area))
+        {
+            return Err(SpaceError::AreaTaken {
+                id: other.id.to_vec(),
+            });
         }
 
         Ok(())
@@ -153,3 +275,96 @@ impl Container {
     //     Ok(())
     // }
 }
+
+#[cfg(test)]
+mod container {
+    use super::*;
+
+    // a 30x10 term with a bordered container in the middle, 2 texts in the top left corner
+    // of the container, one over the other, and a container in the top right corner
+    fn term() -> Term {
+        let mut term = Term::new(0, 30, 10);
+        Container::builder([0, 0])
+            .pos(Pos::Center, Pos::Center)
+            .area(Area::Values { w: 14, h: 10 })
+            .border(Border::Uniform('#'))
+            .build(&mut term)
+            .unwrap();
+        for (id, y) in [(0, 1), (1, 4)] {
+            Text::input_builder([0, 0, id])
+                .pos(Pos::Value(2), Pos::Value(y))
+                .area(Area::Values { w: 6, h: 3 })
+                .border(Border::Uniform('*'))
+                .build(&mut term)
+                .unwrap();
+        }
+        Container::builder([0, 1])
+            .pos(Pos::End, Pos::Start)
+            .area(Area::Values { w: 8, h: 3 })
+            .build(&mut term)
+            .unwrap();
+
+        term
+    }
+
+    fn ax0ay0(term: &Term) -> Vec<[u16; 2]> {
+        term.containers[0].items.iter().map(|t| [t.ax0, t.ay0]).collect()
+    }
+
+    #[test]
+    fn fitted() {
+        let mut term = term();
+        let before = ax0ay0(&term);
+        let cont = term.container_mut(&[0, 0]).unwrap();
+        assert_eq!([cont.x0, cont.y0, cont.w, cont.h], [8, 0, 12, 8]);
+        assert!(cont.fit_to_children());
+        // the 2 texts take 6x3 cells each, one over the other
+        assert_eq!([cont.x0, cont.y0, cont.w, cont.h], [10, 1, 6, 6]);
+        assert_eq!(cont.decorate(), [8, 8]);
+        assert_eq!([cont.items[1].x0, cont.items[1].y0], [0, 3]);
+        assert!(cont.attributes.contains("dirty"));
+        assert!(!cont.fit_to_children());
+        assert_eq!(ax0ay0(&term), before);
+
+        let mut empty = Container::new([0, 5], 1, 1, 4, 4, Border::None, Padding::None);
+        assert!(!empty.fit_to_children());
+        assert_eq!([empty.w, empty.h], [4, 4]);
+    }
+
+    #[test]
+    fn reanchored() {
+        let mut term = term();
+        assert_eq!(term.fit_container(&[0, 0], false), Ok(true));
+        assert_eq!(term.container_ref(&[0, 0]).unwrap().x0, 10);
+        assert_eq!(term.uncovered, [[9, 0, 14, 10]]);
+        assert_eq!(term.fit_container(&[0, 0], false), Ok(false));
+
+        // placed again by Pos::Center, Pos::Center, the texts follow it
+        assert_eq!(term.fit_container(&[0, 0], true), Ok(true));
+        let cont = term.container_ref(&[0, 0]).unwrap();
+        assert_eq!([cont.x0, cont.y0], [11, 1]);
+        let [x, y] = [cont.x0, cont.y0];
+        let text = &cont.items[0];
+        assert_eq!([text.ax0, text.ay0], [x + 1 + 1 + 1, y + 1 + 1]);
+        assert_eq!(
+            term.fit_container(&[0, 7], true),
+            Err(ComponentTreeError::BadID { id: vec![0, 7] })
+        );
+    }
+
+    #[test]
+    fn taken() {
+        let mut term = term();
+        term.container_mut(&[0, 0]).unwrap().anchor = Some([Pos::End, Pos::Start]);
+        let before = term.container_ref(&[0, 0]).unwrap().clone();
+        assert_eq!(
+            term.fit_container(&[0, 0], true),
+            Err(ComponentTreeError::AreaTaken { id: vec![0, 1] })
+        );
+        // nothing changed
+        let cont = term.container_ref(&[0, 0]).unwrap();
+        assert_eq!([cont.x0, cont.y0, cont.w, cont.h], [before.x0, before.y0, before.w, before.h]);
+        assert_eq!(term.containers.last().unwrap().id, [0, 1]);
+        assert!(term.uncovered.is_empty());
+    }
+}
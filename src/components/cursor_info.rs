@@ -0,0 +1,167 @@
+use super::tab_stop::FILL;
+use super::{Term, Text};
+
+/// where the cursor of an input is and how long its value is, e.g. for a "Ln 3, Col 14" status
+/// see Text::cursor_info and Term::on_cursor_move
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CursorInfo {
+    /// the line the cursor is on, from 1, a row whose last cell is filled goes on
+    /// on the next row, they are one line
+    pub line: usize,
+    /// the column the cursor is on in its line, from 1
+    pub column: usize,
+    /// the number of chars the value holds
+    pub len: usize,
+    /// the number of selected chars, None while nothing is selected
+    pub selection: Option<usize>,
+}
+
+// what Term::on_cursor_move was given and what it was last called with
+#[derive(Default)]
+pub(crate) struct CursorHook {
+    hook: Option<Box<dyn FnMut([u8; 3], CursorInfo)>>,
+    last: Option<([u8; 3], CursorInfo)>,
+}
+
+impl Text {
+    /// the line and column of this text's cursor and the length of its value
+    /// a tab is one char, see Text::tab_width
+    pub fn cursor_info(&self) -> CursorInfo {
+        let w = self.w as usize;
+        let goes_on = |row: usize| row > 0 && self.value.get(row * w - 1).flatten().is_some();
+        let cy = self.cy as usize;
+        let line = 1 + (1..=cy).filter(|row| !goes_on(*row)).count();
+        let head = (0..=cy).rev().find(|row| !goes_on(*row)).unwrap_or(0);
+        let before = self.value.range(head * w..self.cursor_index());
+        let fills = before.filter(|c| *c == Some(FILL)).count();
+
+        CursorInfo {
+            line,
+            column: (cy - head) * w + self.cx as usize + 1 - fills,
+            len: self.value.chars().filter(|c| *c != FILL).count(),
+            selection: None,
+        }
+    }
+}
+
+impl Term {
+    /// the cursor info of the focused input, None if no input is focused
+    /// see Term::on_cursor_move to be told when it changes
+    pub fn focused_cursor_info(&self) -> Option<CursorInfo> {
+        let id = self.focused.filter(|id| self.has_input(id))?;
+
+        self.input_ref(&id).map(Text::cursor_info)
+    }
+
+    /// calls f with the focused input's id and its cursor info after a key, a paste
+    /// or a Term::edit moved its cursor or changed the length of its value,
+    /// or after another input got the focus, it isn't called when they are left as they were,
+    /// replaces the previous callback
+    pub fn on_cursor_move(&mut self, f: impl FnMut([u8; 3], CursorInfo) + 'static) {
+        self.cursor_hook.hook = Some(Box::new(f));
+        self.cursor_hook.last = self.focused_cursor();
+    }
+
+    fn focused_cursor(&self) -> Option<([u8; 3], CursorInfo)> {
+        let id = self.focused?;
+
+        Some((id, self.focused_cursor_info()?))
+    }
+
+    // calls the on_cursor_move hook if the focused input's cursor info is not the one
+    // it was last called with
+    pub(crate) fn report_cursor(&mut self) {
+        if self.cursor_hook.hook.is_none() {
+            return;
+        }
+        let now = self.focused_cursor();
+        if now.is_none() || now == self.cursor_hook.last {
+            return;
+        }
+        self.cursor_hook.last = now;
+        let (id, info) = now.unwrap();
+        if let Some(hook) = self.cursor_hook.hook.as_mut() {
+            hook(id, info);
+        }
+    }
+}
+
+#[cfg(test)]
+mod cursor_info {
+    use super::*;
+    use crate::components::Container;
+    use crate::inputs::keyboard::{Char, Modifiers, CC};
+    use crate::inputs::keyboard::{KbdEvent, KeyKind};
+    use crate::space::{border::Border, padding::Padding};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // a focused multiline 4x3 input
+    fn term() -> Term {
+        let mut term = Term::new(0, 20, 6);
+        let mut c = Container::new([0, 0], 0, 0, 10, 4, Border::None, Padding::None);
+        let mut input = Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            4,
+            3,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        input.attributes.insert("multiline");
+        c.items.push(input);
+        term.containers.push(c);
+        term.focused = Some([0, 0, 0]);
+
+        term
+    }
+
+    #[test]
+    fn info() {
+        let mut term = term();
+        term.paste("abcdef\ngh");
+        let info = term.focused_cursor_info().unwrap();
+        let expected = CursorInfo {
+            line: 2,
+            column: 3,
+            len: 8,
+            selection: None,
+        };
+        assert_eq!(info, expected);
+
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        [text.cx, text.cy] = [1, 1];
+        assert_eq!([text.cursor_info().line, text.cursor_info().column], [1, 6]);
+
+        term.focused = None;
+        assert_eq!(term.focused_cursor_info(), None);
+    }
+
+    #[test]
+    fn reported() {
+        let mut term = term();
+        let seen = Rc::new(RefCell::new(vec![]));
+        let reports = seen.clone();
+        term.on_cursor_move(move |id, info| reports.borrow_mut().push((id, info.column)));
+
+        let key = |c| KbdEvent {
+            char: c,
+            modifiers: Modifiers(0),
+            kind: KeyKind::Press,
+        };
+        // left at the start of the value moves nothing
+        term.key(&key(Char::CC(CC::Left)));
+        assert!(seen.borrow().is_empty());
+        term.key(&key(Char::Char('a')));
+        term.paste("bc");
+        term.key(&key(Char::CC(CC::Left)));
+        assert_eq!(
+            *seen.borrow(),
+            [([0, 0, 0], 2), ([0, 0, 0], 4), ([0, 0, 0], 3)]
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use std::ops::Range;
+
+use super::grapheme::{Clusters, WIDE};
+use super::Text;
+
+/// the direction the lines of a text are read in, see Text::set_direction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// left to right, from the left edge of the area
+    #[default]
+    Ltr,
+    /// right to left, from the right edge of the area, e.g. hebrew or arabic
+    Rtl,
+}
+
+// the cells of row put together in the chars they show, a wide char and its right half
+// are one
+fn units(row: &[Option<char>]) -> Vec<Range<usize>> {
+    let mut units: Vec<Range<usize>> = vec![];
+    for (idx, c) in row.iter().enumerate() {
+        match (c, units.last_mut()) {
+            (Some(WIDE), Some(unit)) => unit.end = idx + 1,
+            _ => units.push(idx..idx + 1),
+        }
+    }
+
+    units
+}
+
+// the units in the order they are shown in, left to right, the runs of left to right chars
+// keep their order, see the bidi feature
+#[cfg(feature = "bidi")]
+fn reorder(
+    row: &[Option<char>],
+    units: Vec<Range<usize>>,
+    clusters: &Clusters,
+) -> Vec<Range<usize>> {
+    use unicode_bidi::{BidiInfo, Level};
+
+    // the char the bidi class of a unit is taken from
+    let class = |c: Option<char>| match c.and_then(|c| clusters.get(c)) {
+        Some(chars) => chars.chars().next().unwrap_or(' '),
+        None => c.filter(|c| *c != WIDE).unwrap_or(' '),
+    };
+    let s = units
+        .iter()
+        .map(|unit| class(row[unit.start]))
+        .collect::<String>();
+    // the byte each unit starts at
+    let starts = s
+        .char_indices()
+        .map(|(byte, _)| byte)
+        .collect::<Vec<usize>>();
+    let info = BidiInfo::new(&s, Some(Level::rtl()));
+    let Some(para) = info.paragraphs.first() else {
+        return units;
+    };
+    let (levels, runs) = info.visual_runs(para, para.range.clone());
+
+    let mut visual = vec![];
+    for run in runs {
+        let [start, end] = [run.start, run.end].map(|byte| starts.partition_point(|b| *b < byte));
+        let shown = units[start..end].iter().cloned();
+        match levels[run.start].is_rtl() {
+            true => visual.extend(shown.rev()),
+            false => visual.extend(shown),
+        }
+    }
+
+    visual
+}
+
+#[cfg(not(feature = "bidi"))]
+fn reorder(_: &[Option<char>], mut units: Vec<Range<usize>>, _: &Clusters) -> Vec<Range<usize>> {
+    units.reverse();
+
+    units
+}
+
+// direction
+impl Text {
+    /// sets the direction the lines of this text are read in
+    /// an rtl line is shown right to left from the right edge of the area, the cursor of an
+    /// empty input starts there and the arrow keys move it the way they point, see
+    /// Text::arrow_left, the ellipsis of an overflowing value is at the left end of its line
+    /// with the "bidi" feature, the runs of left to right chars in an rtl line, such as
+    /// numbers and latin words, keep their order as the unicode bidi algorithm has it,
+    /// without it an rtl line is reversed as a whole
+    /// the value is kept in the order it was typed, Text::value_string gives it back as such
+    pub fn set_direction(&mut self, direction: Direction) {
+        self.direction = direction;
+        self.mark_dirty();
+    }
+
+    /// the direction the lines of this text are read in, see Text::set_direction
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// moves the cursor as the left arrow does, Text::move_left in an ltr text,
+    /// Text::move_right in an rtl one, where the chars after it are on its left
+    pub fn arrow_left(&mut self) -> bool {
+        match self.direction {
+            Direction::Ltr => self.move_left(),
+            Direction::Rtl => self.move_right(),
+        }
+    }
+
+    /// moves the cursor as the right arrow does, see Text::arrow_left
+    pub fn arrow_right(&mut self) -> bool {
+        match self.direction {
+            Direction::Ltr => self.move_right(),
+            Direction::Rtl => self.move_left(),
+        }
+    }
+
+    // the cells of the row, in the order they are shown in,
+    // left as they are in an ltr text
+    pub(crate) fn in_direction(&self, row: &mut [Option<char>]) {
+        if self.direction == Direction::Ltr {
+            return;
+        }
+        let order = self.visual_order(row);
+        let shown = order
+            .iter()
+            .map(|idx| row[*idx])
+            .collect::<Vec<Option<char>>>();
+        row.copy_from_slice(&shown);
+    }
+
+    // the cell of row shown in each column, from the left
+    fn visual_order(&self, row: &[Option<char>]) -> Vec<usize> {
+        match self.direction {
+            Direction::Ltr => (0..row.len()).collect(),
+            Direction::Rtl => reorder(row, units(row), &self.clusters)
+                .into_iter()
+                .flatten()
+                .collect(),
+        }
+    }
+
+    // the column of the area the cursor is shown in, the cell it is on is shown there
+    pub(crate) fn cursor_column(&self) -> u16 {
+        let vw = self.view()[0] as usize;
+        if self.direction == Direction::Ltr || self.cx as usize >= vw {
+            return self.cx;
+        }
+        let start = self.cy as usize * self.stride() as usize;
+        let row = self
+            .shown_cells(start..start + vw)
+            .collect::<Vec<Option<char>>>();
+        let order = self.visual_order(&row);
+
+        order
+            .iter()
+            .position(|idx| *idx == self.cx as usize)
+            .unwrap_or(0) as u16
+    }
+}
+
+#[cfg(test)]
+mod direction {
+    use super::*;
+    use crate::components::{Container, Term};
+    use crate::space::Overflow;
+    use crate::space::{border::Border, padding::Padding};
+    use crate::test::Frame;
+
+    // a 10x2 term holding a focused 8x1 rtl input
+    fn term() -> Term {
+        let mut term = Term::new(0, 10, 2);
+        let mut c = Container::new([0, 0], 0, 0, 8, 1, Border::None, Padding::None);
+        let mut input = Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            8,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        input.set_direction(Direction::Rtl);
+        c.items.push(input);
+        term.containers.push(c);
+        term.focused = Some([0, 0, 0]);
+
+        term
+    }
+
+    #[test]
+    fn label() {
+        let mut term = term();
+        // the cursor of the empty input is at the right edge
+        _ = term.sync_cursor();
+        assert_eq!(term.cx, 7);
+
+        term.paste("שלום");
+        assert_eq!(Frame::of(&term).rows()[0], "    םולש  ");
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().value_string(), "שלום");
+        _ = term.sync_cursor();
+        assert_eq!(term.cx, 3);
+
+        // the arrows go the way they point
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        assert!(text.arrow_right());
+        assert_eq!(text.cx, 3);
+        assert!(text.arrow_left());
+        assert_eq!(text.cx, 4);
+        text.arrow_right();
+        text.arrow_right();
+        _ = term.sync_cursor();
+        assert_eq!(term.cx, 5);
+        term.input_mut(&[0, 0, 0]).unwrap().move_home();
+        _ = term.sync_cursor();
+        assert_eq!(term.cx, 7);
+    }
+
+    #[test]
+    fn overflow() {
+        let mut pane = Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            0,
+            0,
+            4,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        pane.set_direction(Direction::Rtl);
+        pane.overflow(Overflow::Ellipsis);
+        pane.replace_value("אבגדהו");
+        // the end of the line is cut on the left
+        let row = (0..4).map(|x| pane.cell(x, 0).unwrap()).collect::<String>();
+        assert_eq!(row, "…גבא");
+    }
+
+    #[cfg(feature = "bidi")]
+    #[test]
+    fn embedded() {
+        let mut term = term();
+        term.paste("אב 12 ג");
+        assert_eq!(Frame::of(&term).rows()[0], " ג 12 בא  ");
+    }
+}
@@ -0,0 +1,444 @@
+use crate::console::Capabilities;
+use crate::inputs::keyboard::{Char, KbdEvent, CC, SHIFT};
+use crate::render_pipeline::cup;
+use crate::space::{border::Border, overlap};
+use crate::themes::Style;
+
+use super::list::{moved_to, shown_from};
+use super::{Term, Text};
+
+/// a single line field showing the chosen option, shown in a Text, see Text::set_dropdown
+/// Enter or Space opens a list of the options over what is below the field, or above it if
+/// there is no room below, Up and Down move through it, Enter chooses and Esc closes it
+/// typing a char while it is open goes to the first option starting with it
+/// see Term::on_change
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dropdown {
+    options: Vec<String>,
+    selected: usize,
+    // the option the open list is at, None while it is closed
+    open: Option<usize>,
+    // the index of the option in the first row of the open list
+    offset: usize,
+    // how many options the open list shows at most
+    rows: u16,
+    // the style escape sequences of the open list and its current row
+    style: String,
+    highlight: String,
+}
+
+impl Dropdown {
+    /// makes a new closed Dropdown of options, the first option is chosen
+    /// the open list shows 6 rows at most, its current row in reverse video
+    pub fn new(options: Vec<String>) -> Self {
+        Self {
+            options,
+            selected: 0,
+            open: None,
+            offset: 0,
+            rows: 6,
+            style: String::new(),
+            highlight: "\x1b[7m".to_string(),
+        }
+    }
+
+    /// replaces the options, the chosen one stays where it was if it can
+    /// an open list is closed
+    pub fn options(&mut self, options: Vec<String>) {
+        self.options = options;
+        self.selected = self.selected.min(self.options.len().saturating_sub(1));
+        self.open = None;
+    }
+
+    /// changes how many options the open list shows at most
+    pub fn rows(&mut self, rows: u16) {
+        self.rows = rows.max(1);
+    }
+
+    /// changes the style of the open list
+    pub fn style(&mut self, style: &Style) {
+        self.style = style.style();
+    }
+
+    /// changes the style of the open list's current row
+    pub fn highlight(&mut self, style: &Style) {
+        self.highlight = style.style();
+    }
+
+    /// the index of the chosen option
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// the chosen option, None if there are no options
+    pub fn selected_option(&self) -> Option<&str> {
+        self.options.get(self.selected).map(String::as_str)
+    }
+
+    /// whether the list of options is open
+    pub fn is_open(&self) -> bool {
+        self.open.is_some()
+    }
+
+    /// the index of the option the open list is at, None while it is closed
+    pub fn highlighted(&self) -> Option<usize> {
+        self.open
+    }
+
+    // opens the list at the chosen option
+    fn open(&mut self) {
+        self.open = Some(self.selected);
+        self.offset = shown_from(self.selected, 0, self.rows as usize);
+    }
+
+    // moves the open list to the option at idx, the last one if idx is past it
+    fn go(&mut self, idx: usize) {
+        let idx = idx.min(self.options.len().saturating_sub(1));
+        self.open = Some(idx);
+        self.offset = shown_from(idx, self.offset, self.rows as usize);
+    }
+
+    // the first option after the current one that starts with c, from the top if there is none
+    fn jump(&mut self, c: char) {
+        let at = self.open.unwrap_or(self.selected);
+        let starts = |idx: &usize| {
+            let option = self.options[*idx].chars().next();
+            option.is_some_and(|o| o.to_lowercase().eq(c.to_lowercase()))
+        };
+        let len = self.options.len();
+        if let Some(idx) = (1..=len).map(|n| (at + n) % len).find(starts) {
+            self.go(idx);
+        }
+    }
+
+    // closes the list, choosing the option it was at if commit
+    // returns whether the chosen option changed
+    fn close(&mut self, commit: bool) -> bool {
+        let Some(at) = self.open.take() else {
+            return false;
+        };
+        let changed = commit && at != self.selected;
+        if commit {
+            self.selected = at;
+        }
+
+        changed
+    }
+
+    // where the open list goes for a field at [x, y] w cells wide and h rows high,
+    // in a terminal term_h rows high, as [x, y, w, h]
+    fn area(&self, [x, y, w, h]: [u16; 4], term_h: u16) -> Option<[u16; 4]> {
+        self.open?;
+        let rows = (self.options.len() as u16).min(self.rows);
+        let below = y + h;
+        // the rows are 1 based
+        let y = match below + rows <= term_h + 1 || y <= rows {
+            true => below,
+            false => y - rows,
+        };
+
+        Some([x, y, w, rows])
+    }
+
+    // the bytes that draw the open list over the area
+    fn overlay(&self, [x, y, w, h]: [u16; 4], caps: &Capabilities) -> String {
+        let mut s = String::new();
+        let visible = self.options.iter().enumerate().skip(self.offset);
+        for (row, (idx, option)) in visible.take(h as usize).enumerate() {
+            s += &cup(x, y + row as u16);
+            s += &caps.adapt_sgr(&self.style);
+            if self.open == Some(idx) {
+                s += &caps.adapt_sgr(&self.highlight);
+            }
+            let mut chars = option.chars().collect::<Vec<char>>();
+            if chars.len() > w as usize && w > 0 {
+                chars.truncate(w as usize - 1);
+                chars.push('…');
+            }
+            chars.resize(w as usize, ' ');
+            s.extend(chars.into_iter().map(|c| caps.adapt_char(c)));
+            s += "\x1b[0m";
+        }
+
+        s
+    }
+}
+
+// dropdowns
+impl Text {
+    /// shows dropdown in the first row of this text, replacing its value
+    pub fn set_dropdown(&mut self, dropdown: Dropdown) {
+        self.dropdown = Some(dropdown);
+        self.set_focusable(true);
+        self.sync_dropdown();
+    }
+
+    /// the dropdown this text shows, if it shows one
+    pub fn dropdown(&self) -> Option<&Dropdown> {
+        self.dropdown.as_ref()
+    }
+
+    /// replaces the options of the dropdown this text shows, see Dropdown::options
+    /// returns false if this text shows no dropdown
+    pub fn set_options(&mut self, options: Vec<String>) -> bool {
+        let Some(dropdown) = &mut self.dropdown else {
+            return false;
+        };
+        dropdown.options(options);
+        self.sync_dropdown();
+
+        true
+    }
+
+    // writes the chosen option to the first row, with an arrow in the last cell
+    fn sync_dropdown(&mut self) {
+        let Some(dropdown) = &self.dropdown else {
+            return;
+        };
+        if self.w == 0 || self.h == 0 {
+            return;
+        }
+        let w = self.w as usize;
+        let mut cells = vec![None; w * self.h as usize];
+        let option = dropdown.selected_option().unwrap_or("");
+        let shown = option.chars().take(w.saturating_sub(2)).map(Some);
+        let shown = shown.collect::<Vec<Option<char>>>();
+        cells.splice(..shown.len(), shown);
+        cells[w - 1] = Some('▾');
+        self.value = cells.into();
+        self.scroll = 0;
+        self.mark_dirty();
+    }
+}
+
+impl Term {
+    /// calls f with the dropdown's id, the chosen index and option whenever an option
+    /// other than the chosen one is chosen in a focused dropdown, replaces the previous callback
+    pub fn on_change(&mut self, f: impl FnMut([u8; 3], usize, &str) + 'static) {
+        self.list_hooks.change = Some(Box::new(f));
+    }
+
+    // the area of the open list of the dropdown the text with id shows
+    pub(crate) fn dropdown_area(&self, id: &[u8; 3]) -> Option<[u16; 4]> {
+        let text = self.text_ref(id)?;
+        let field = [text.ax0, text.ay0, text.w, text.h];
+
+        text.dropdown.as_ref()?.area(field, self.h)
+    }
+
+    // hands the key to the focused dropdown, if the focused text shows one
+    // an open dropdown takes every key, a closed one only Enter and Space
+    // returns None if the key is left to the keymaps
+    pub(crate) fn dropdown_key(&mut self, event: &KbdEvent) -> Option<bool> {
+        let id = self.focused?;
+        let area = self.dropdown_area(&id);
+        let text = self.text_mut(&id)?;
+        let dropdown = text.dropdown.as_mut()?;
+        let plain = event.modifiers.is_empty();
+
+        let Some(at) = dropdown.open else {
+            if !plain || !matches!(event.char, Char::CC(CC::CR) | Char::Char(' ')) {
+                return None;
+            }
+            dropdown.open();
+            text.mark_dirty();
+            return Some(true);
+        };
+
+        if let Some(idx) = moved_to(event, at, dropdown.rows as usize) {
+            dropdown.go(idx);
+            text.mark_dirty();
+            return Some(true);
+        }
+        let commit = match event.char {
+            Char::CC(CC::CR) if plain => true,
+            Char::CC(CC::ESC) => false,
+            Char::Char(c) if event.modifiers.0 & !SHIFT == 0 => {
+                dropdown.jump(c);
+                text.mark_dirty();
+                return Some(true);
+            }
+            _ => return Some(false),
+        };
+
+        let changed = dropdown.close(commit);
+        let (idx, option) = (dropdown.selected, dropdown.selected_option()?.to_string());
+        text.sync_dropdown();
+        if let Some(area) = area {
+            self.uncover(area);
+        }
+        if let Some(hook) = self.list_hooks.change.as_mut().filter(|_| changed) {
+            hook(id, idx, &option);
+        }
+
+        Some(true)
+    }
+
+    // the next render_dirty call blanks area and renders again what is under it
+    pub(crate) fn uncover(&mut self, area: [u16; 4]) {
+        self.uncovered.push(area);
+        self.mark_dirty_over(area);
+    }
+
+    // marks what takes some of area dirty, the containers without a border render nothing
+    // of their own
+    pub(crate) fn mark_dirty_over(&mut self, area: [u16; 4]) {
+        for c in &mut self.containers {
+            if !matches!(c.border, Border::None) && overlap(c.outer_area(), area) {
+                c.mark_dirty();
+            }
+            c.items
+                .iter_mut()
+                .filter(|t| overlap(t.outer_area(), area))
+                .for_each(Text::mark_dirty);
+        }
+    }
+
+    // the bytes that blank the areas closed dropdowns left
+    pub(crate) fn uncovered_frame(&mut self) -> String {
+        self.uncovered
+            .drain(..)
+            .flat_map(|[x, y, w, h]| (y..y + h).map(move |y| format!("{}\x1b[{}X", cup(x, y), w)))
+            .collect()
+    }
+
+    // the bytes that draw the open dropdowns over everything else
+    pub(crate) fn overlays_frame(&self) -> String {
+        self.overlays_frame_with(&self.capabilities)
+    }
+
+    // the open dropdowns drawn as they are with caps
+    pub(crate) fn overlays_frame_with(&self, caps: &Capabilities) -> String {
+        let mut s = String::new();
+        let shown = self.containers.iter().filter(|c| !c.is_hidden());
+        for text in shown.flat_map(|c| c.items.iter()).filter(|t| !t.is_hidden()) {
+            let Some(dropdown) = &text.dropdown else {
+                continue;
+            };
+            if let Some(area) = self.dropdown_area(&text.id) {
+                s += &dropdown.overlay(area, caps);
+            }
+        }
+
+        s
+    }
+}
+
+#[cfg(test)]
+mod dropdown {
+    use super::*;
+    use crate::components::Container;
+    use crate::inputs::keyboard::{KeyKind, Modifiers};
+    use crate::space::padding::Padding;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // a dropdown at the 3rd row of an 8 rows high term, over a label
+    fn form() -> Term {
+        let mut term = Term::new(0, 30, 8);
+        let mut c = Container::new([0, 0], 0, 0, 20, 6, Border::None, Padding::None);
+        let mut field = Text::input(
+            [0, 0, 2],
+            0,
+            2,
+            1,
+            3,
+            8,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        let colors = ["red", "green", "blue", "black"];
+        field.set_dropdown(Dropdown::new(colors.map(String::from).to_vec()));
+        let label = Text::nonedit(
+            [0, 0, 1],
+            0,
+            3,
+            1,
+            4,
+            8,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        c.items.extend([field, label]);
+        term.containers.push(c);
+        term.focused = Some([0, 0, 2]);
+
+        term
+    }
+
+    fn key(char: Char) -> KbdEvent {
+        KbdEvent {
+            char,
+            modifiers: Modifiers(0),
+            kind: KeyKind::Press,
+        }
+    }
+
+    fn field(term: &Term) -> &Dropdown {
+        term.input_ref(&[0, 0, 2]).unwrap().dropdown().unwrap()
+    }
+
+    #[test]
+    fn choose() {
+        let mut term = form();
+        let changed = Rc::new(RefCell::new(vec![]));
+        let seen = changed.clone();
+        term.on_change(move |_, idx, option| seen.borrow_mut().push((idx, option.to_string())));
+
+        term.key(&key(Char::Char(' ')));
+        assert!(field(&term).is_open());
+        assert_eq!(term.dropdown_area(&[0, 0, 2]), Some([1, 4, 8, 4]));
+        term.key(&key(Char::CC(CC::Down)));
+        term.key(&key(Char::CC(CC::Down)));
+        assert_eq!(field(&term).highlighted(), Some(2));
+        // Esc closes without choosing
+        term.key(&key(Char::CC(CC::ESC)));
+        assert_eq!(
+            [field(&term).is_open(), field(&term).selected() == 0],
+            [false, true]
+        );
+        assert!(changed.borrow().is_empty());
+
+        term.key(&key(Char::CC(CC::CR)));
+        // b goes to blue then black
+        term.key(&key(Char::Char('b')));
+        term.key(&key(Char::Char('b')));
+        term.key(&key(Char::CC(CC::CR)));
+        assert_eq!(field(&term).selected_option(), Some("black"));
+        assert_eq!(*changed.borrow(), [(3, "black".into())]);
+        let shown = term.input_ref(&[0, 0, 2]).unwrap().value_string();
+        assert_eq!(shown, "black▾");
+    }
+
+    #[test]
+    fn overlay() {
+        let mut term = form();
+        term.input_mut(&[0, 0, 2])
+            .unwrap()
+            .set_options(vec!["a".into(), "b".into()]);
+        term.key(&key(Char::CC(CC::CR)));
+        let overlay = term.overlays_frame();
+        assert!(overlay.starts_with("\x1b[4;1f\x1b[7ma       \x1b[0m"));
+        assert!(overlay.ends_with("\x1b[5;1fb       \x1b[0m"));
+
+        // the list goes above the field when there is no room below
+        term.input_mut(&[0, 0, 2]).unwrap().ay0 = 7;
+        assert_eq!(term.dropdown_area(&[0, 0, 2]), Some([1, 5, 8, 2]));
+        term.input_mut(&[0, 0, 2]).unwrap().ay0 = 3;
+
+        term.containers[0]
+            .items
+            .iter_mut()
+            .for_each(|t| _ = t.attributes.remove("dirty"));
+        term.key(&key(Char::CC(CC::ESC)));
+        // the label under the list is rendered again over the blanked cells
+        let label = term.nonedit_ref(&[0, 0, 1]).unwrap();
+        assert!(label.attributes.contains("dirty"));
+        assert_eq!(term.uncovered_frame(), "\x1b[4;1f\x1b[8X\x1b[5;1f\x1b[8X");
+        assert_eq!(term.overlays_frame(), "");
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::space::{border::Border, padding::Padding};
+
+use super::{Property, Term};
+
+impl Term {
+    /// an outline of this term's component tree, a line for each container and text with its
+    /// id, origin, size, border and padding kinds, layer, value length, attributes and
+    /// properties, the focused text is marked
+    /// the attributes and properties are sorted so that dumps of the same tree are the same
+    pub fn dump(&self) -> String {
+        let mut dump = format!("term {} {}x{}\n", self.id, self.w, self.h);
+        for c in &self.containers {
+            _ = write!(
+                dump,
+                "  container {:?} x0 {} y0 {} {}x{} border {} padding {} layer {}",
+                c.id,
+                c.x0,
+                c.y0,
+                c.w,
+                c.h,
+                border_kind(&c.border),
+                padding_kind(&c.padding),
+                c.layer,
+            );
+            extras(&mut dump, &c.attributes, &c.properties);
+            for t in &c.items {
+                _ = write!(
+                    dump,
+                    "    {} {:?} x0 {} y0 {} ax0 {} ay0 {} {}x{} border {} padding {} layer {} value {}",
+                    if t.is_input() { "input" } else { "nonedit" },
+                    t.id,
+                    t.x0,
+                    t.y0,
+                    t.ax0,
+                    t.ay0,
+                    t.w,
+                    t.h,
+                    border_kind(&t.border),
+                    padding_kind(&t.padding),
+                    t.layer,
+                    t.value.len(),
+                );
+                if self.focused == Some(t.id) {
+                    dump.push_str(" focused");
+                }
+                extras(&mut dump, &t.attributes, &t.properties);
+            }
+        }
+
+        dump
+    }
+
+    /// this term's w x h cells with the box each container and text takes drawn over them,
+    /// its border and padding included, and its id written at the start of its top side
+    /// a cell where the boxes of 2 components cross is a '#'
+    pub fn blueprint(&self) -> String {
+        let [w, h] = [self.w as usize, self.h as usize];
+        let mut grid = vec![vec!['.'; w]; h];
+        // which component drew each cell
+        let mut owners = vec![vec![None; w]; h];
+        let boxes = self.containers.iter().flat_map(|c| {
+            let label = format!("{}.{}", c.id[0], c.id[1]);
+            let text_boxes = c.items.iter().map(|t| {
+                let label = format!("{}.{}.{}", t.id[0], t.id[1], t.id[2]);
+                (t.id.to_vec(), t.outer_area(), label)
+            });
+
+            std::iter::once((c.id.to_vec(), c.outer_area(), label)).chain(text_boxes)
+        });
+        for (id, [x, y, bw, bh], label) in boxes.collect::<Vec<_>>() {
+            if bw == 0 || bh == 0 {
+                continue;
+            }
+            // the areas' columns start at 1
+            let [x0, y0] = [x.saturating_sub(1) as usize, y as usize];
+            let [x1, y1] = [x0 + bw as usize - 1, y0 + bh as usize - 1];
+            let mut draw = |cx: usize, cy: usize, c: char| {
+                if cx >= w || cy >= h {
+                    return;
+                }
+                grid[cy][cx] = match &owners[cy][cx] {
+                    Some(owner) if *owner != id => '#',
+                    // the crossing stays shown when the side of a 1 cell tall box is drawn twice
+                    _ if grid[cy][cx] == '#' => '#',
+                    _ => c,
+                };
+                owners[cy][cx] = Some(id.clone());
+            };
+            for cx in x0..=x1 {
+                let side = if cx == x0 || cx == x1 { '+' } else { '-' };
+                draw(cx, y0, side);
+                draw(cx, y1, side);
+            }
+            for cy in y0 + 1..y1 {
+                draw(x0, cy, '|');
+                draw(x1, cy, '|');
+            }
+            // the id goes between the corners when it fits
+            if label.chars().count() + 2 <= bw as usize {
+                label
+                    .chars()
+                    .enumerate()
+                    .for_each(|(idx, c)| draw(x0 + 1 + idx, y0, c));
+            }
+        }
+
+        grid.into_iter()
+            .map(|row| row.into_iter().collect::<String>() + "\n")
+            .collect()
+    }
+}
+
+// the name of the kind of border
+fn border_kind(border: &Border) -> &'static str {
+    match border {
+        Border::None => "none",
+        Border::Uniform(_) => "uniform",
+        Border::Polyform { .. } => "polyform",
+        Border::Manual { .. } => "manual",
+    }
+}
+
+// the name of the kind of padding
+fn padding_kind(padding: &Padding) -> &'static str {
+    match padding {
+        Padding::None => "none",
+        Padding::Inner { .. } => "inner",
+        Padding::Outer { .. } => "outer",
+        Padding::InOut { .. } => "inout",
+    }
+}
+
+// writes the sorted attributes and properties of a component, then ends its line
+fn extras(
+    dump: &mut String,
+    attributes: &HashSet<&'static str>,
+    properties: &HashMap<&'static str, Property>,
+) {
+    if !attributes.is_empty() {
+        let mut attributes = attributes.iter().collect::<Vec<_>>();
+        attributes.sort();
+        _ = write!(dump, " attributes {:?}", attributes);
+    }
+    if !properties.is_empty() {
+        let mut properties = properties.iter().collect::<Vec<_>>();
+        properties.sort_by_key(|(name, _)| **name);
+        let properties = properties
+            .iter()
+            .map(|(name, value)| format!("{}: {:?}", name, value))
+            .collect::<Vec<String>>();
+        _ = write!(dump, " properties {{{}}}", properties.join(", "));
+    }
+    dump.push('\n');
+}
+
+#[cfg(test)]
+mod dump {
+    use super::*;
+    use crate::components::{Container, Text};
+    use crate::space::{Area, Pos};
+
+    fn term() -> Term {
+        let mut term = Term::new(0, 20, 6);
+        Container::builder([0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 12, h: 5 })
+            .border(Border::rounded())
+            .property("title", Property::String("form".into()))
+            .build(&mut term)
+            .unwrap();
+        Text::input_builder([0, 0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 8, h: 3 })
+            .border(Border::Uniform('*'))
+            .attribute("search")
+            .build(&mut term)
+            .unwrap();
+        _ = term.focus(&[0, 0, 0]);
+
+        term
+    }
+
+    #[test]
+    fn outline() {
+        let term = term();
+        assert_eq!(
+            term.dump(),
+            "term 0 20x6\n  \
+             container [0, 0] x0 0 y0 0 10x3 border polyform padding none layer 0 \
+             properties {title: form}\n    \
+             input [0, 0, 0] x0 0 y0 0 ax0 3 ay0 2 6x1 border uniform padding none layer 0 \
+             value 6 focused attributes [\"focusable\", \"search\"]\n"
+        );
+    }
+
+    #[test]
+    fn blueprint() {
+        let mut term = term();
+        assert_eq!(
+            term.blueprint(),
+            "+0.0-------+........\n\
+             |+0.0.0-+..|........\n\
+             ||......|..|........\n\
+             |+------+..|........\n\
+             +----------+........\n\
+             ....................\n"
+        );
+
+        // a container over the first one
+        let over = Container::new([0, 1], 8, 3, 4, 1, Border::None, Padding::None);
+        term.containers.push(over);
+        assert_eq!(
+            term.blueprint().lines().nth(3),
+            Some("|+------#--#........")
+        );
+    }
+}
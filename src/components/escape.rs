@@ -0,0 +1,163 @@
+use std::ops::Range;
+
+use super::grapheme::WIDE;
+use super::tab_stop::{untab, FILL};
+use super::{Text, TextKind};
+
+// the cell a NUL is kept in, an empty cell is a NUL to the value, see Value
+pub(crate) const NUL: char = '\u{e001}';
+
+/// how a nonedit shows the control chars of its value, see Text::set_control_display
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ControlDisplay {
+    /// in caret notation, ^C for ETX, ^[ for ESC and ^? for DEL, over 2 cells
+    #[default]
+    Caret,
+    /// as the unicode control pictures, ␃ for ETX, ␛ for ESC and ␡ for DEL, in 1 cell
+    Pictures,
+}
+
+// the chars the control char c is shown as and how many of them there are,
+// a C1 char has no picture, it is shown as the ESC sequence standing for it, e.g. ^[[ for CSI
+fn escaped(c: char, display: ControlDisplay) -> Option<([char; 3], usize)> {
+    let code = match c {
+        NUL => 0,
+        c => c as u32,
+    };
+    let glyph = |code: u32| char::from_u32(code).unwrap_or('?');
+    match (code, display) {
+        (0x00..=0x1f, ControlDisplay::Caret) => Some((['^', glyph(code + 0x40), ' '], 2)),
+        (0x00..=0x1f, ControlDisplay::Pictures) => Some(([glyph(0x2400 + code), ' ', ' '], 1)),
+        (0x7f, ControlDisplay::Caret) => Some((['^', '?', ' '], 2)),
+        (0x7f, ControlDisplay::Pictures) => Some((['␡', ' ', ' '], 1)),
+        (0x80..=0x9f, _) => Some((['^', '[', glyph(code - 0x40)], 3)),
+        _ => None,
+    }
+}
+
+// control chars
+impl Text {
+    /// makes this nonedit keep the control chars that come into its value, e.g. the output
+    /// of a subprocess or a file preview, and show them as display, the C0 chars, DEL and the
+    /// C1 chars, a tab and a newline are still laid out as such
+    /// the value holds the chars as they came, Text::value_string gives them back,
+    /// only the cells they are shown in change, they take as many cells as they are
+    /// shown with, in the rows and the columns like any other char
+    /// None, the default, leaves the control chars out of the value,
+    /// the ones it already holds are shown as spaces
+    pub fn set_control_display(&mut self, display: Option<ControlDisplay>) {
+        self.control_display = display;
+        self.fill();
+        self.mark_dirty();
+    }
+
+    /// how this text shows the control chars of its value, see Text::set_control_display
+    pub fn control_display(&self) -> Option<ControlDisplay> {
+        self.control_display
+    }
+
+    // the chars the cell c is shown as and how many of them there are,
+    // None when c is no control char this text escapes
+    pub(crate) fn escape(&self, c: char) -> Option<([char; 3], usize)> {
+        let display = self
+            .control_display
+            .filter(|_| self.kind == TextKind::NonEdit)?;
+        if c == '\t' || c == '\n' {
+            return None;
+        }
+
+        escaped(c, display)
+    }
+
+    // the chars the value cells of range are shown as, a tab and its fill cells are blank,
+    // an escaped control char is shown over its fill cells, see Text::set_control_display,
+    // and the fill cell of a wide char is WIDE
+    pub(crate) fn shown_cells(
+        &self,
+        range: Range<usize>,
+    ) -> impl Iterator<Item = Option<char>> + '_ {
+        // the char the fill cells at the start of range are part of
+        let mut at = range.start;
+        while at > 0 && self.value.get(at).flatten() == Some(FILL) {
+            at -= 1;
+        }
+        let mut owner = self.value.get(at).flatten().map(|c| (c, range.start - at));
+
+        self.value.range(range).map(move |c| {
+            let c = c?;
+            let (shown, k) = match (c, owner) {
+                (FILL, Some(of)) => of,
+                (c, _) => (c, 0),
+            };
+            owner = Some((shown, k + 1)).filter(|_| shown != FILL);
+            let escaped = self.escape(shown).filter(|(_, len)| k < *len);
+
+            Some(match escaped {
+                Some((chars, _)) => chars[k],
+                None if k > 0 && k < self.clusters.cells(shown) => WIDE,
+                None if k > 0 || shown.is_control() || shown == NUL => ' ',
+                None => untab(shown),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod escape {
+    use super::*;
+    use crate::space::{border::Border, padding::Padding};
+
+    // a 6x2 nonedit
+    fn pane() -> Text {
+        Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            0,
+            0,
+            6,
+            2,
+            &[],
+            Border::None,
+            Padding::None,
+        )
+    }
+
+    fn row(text: &Text, y: u16) -> String {
+        (0..text.w)
+            .map(|x| text.cell(x, y).unwrap_or('.'))
+            .collect()
+    }
+
+    #[test]
+    fn caret() {
+        let mut text = pane();
+        text.replace_value("a\x03b");
+        // left out by default
+        assert_eq!(text.value_string(), "ab");
+
+        text.set_control_display(Some(ControlDisplay::Caret));
+        text.replace_value("a\x03b\x7f\u{9b}\0");
+        assert_eq!(row(&text, 0), "a^Cb^?");
+        // the CSI goes on on the next row
+        assert_eq!(row(&text, 1), "^[[^@.");
+        assert_eq!(text.value_string(), "a\x03b\x7f\u{9b}\0");
+        assert_eq!(text.cursor_info().len, 6);
+    }
+
+    #[test]
+    fn pictures() {
+        let mut text = pane();
+        text.set_control_display(Some(ControlDisplay::Pictures));
+        text.replace_value("\x1b[1m\0\t");
+        assert_eq!(row(&text, 0), "␛[1m␀ ");
+        text.set_control_display(Some(ControlDisplay::Caret));
+        assert_eq!(row(&text, 0), "^[[1m^");
+        assert_eq!(row(&text, 1), "@     ");
+
+        // turned off, the chars are still in the value
+        text.set_control_display(None);
+        assert_eq!(row(&text, 0), " [1m  ");
+        assert_eq!(text.value_string(), "\x1b[1m\0\t");
+    }
+}
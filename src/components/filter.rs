@@ -0,0 +1,331 @@
+use super::{Hook, Term, Text};
+
+/// the blank cells of a mask, see Text::set_mask
+pub const MASK_BLANK: char = '_';
+
+/// what the chars typed into an input have to be, see Text::set_filter
+/// the chars a filter rejects are not typed, see Term::on_reject
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFilter {
+    /// a number made of ascii digits, with a leading '-' if allow_negative
+    /// and a single '.' if allow_decimal
+    Numeric {
+        allow_negative: bool,
+        allow_decimal: bool,
+    },
+    /// only the chars of the str
+    Charset(&'static str),
+}
+
+impl InputFilter {
+    /// whether the whole value s passes this filter, a number that is still being typed,
+    /// like "-" or "1.", passes
+    pub fn accepts(&self, s: &str) -> bool {
+        match *self {
+            Self::Numeric {
+                allow_negative,
+                allow_decimal,
+            } => {
+                let body = match allow_negative {
+                    true => s.strip_prefix('-').unwrap_or(s),
+                    false => s,
+                };
+                let digits = body
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || (allow_decimal && c == '.'));
+
+                digits && body.matches('.').count() <= 1
+            }
+            Self::Charset(set) => s.chars().all(|c| set.contains(c)),
+        }
+    }
+}
+
+// filters and masks
+impl Text {
+    /// makes every char typed or pasted into this text go through filter first,
+    /// None lets any char in, the value this text already holds is left as it is
+    pub fn set_filter(&mut self, filter: Option<InputFilter>) {
+        self.filter = filter;
+    }
+
+    /// the filter of this text, see Text::set_filter
+    pub fn filter(&self) -> Option<InputFilter> {
+        self.filter
+    }
+
+    /// gives this input a fixed format, e.g. "____-__-__" for a date, the MASK_BLANK cells
+    /// are filled by typing, over what they held, and the other chars of mask are literals
+    /// that the cursor skips,
+    /// backspace goes back over them and empties the blank before them
+    /// the value becomes the mask and the cursor goes to the first blank,
+    /// the filter of this text checks the chars the blanks are filled with, see Text::raw_value
+    /// None removes the mask and keeps the value as it is
+    pub fn set_mask(&mut self, mask: Option<&'static str>) {
+        self.mask = mask;
+        if let Some(mask) = mask {
+            self.value = mask.into();
+            self.fill();
+            self.set_cursor_index(0);
+            self.skip_literals(false);
+        }
+        self.mark_dirty();
+    }
+
+    /// the mask of this text, see Text::set_mask
+    pub fn mask(&self) -> Option<&'static str> {
+        self.mask
+    }
+
+    /// the chars typed into the blanks of the mask, e.g. "20240131" for "2024-01-31",
+    /// the value as Text::value_string gives it when there is no mask
+    pub fn raw_value(&self) -> String {
+        let Some(mask) = self.mask else {
+            return self.value_string();
+        };
+
+        let mut raw = String::new();
+        self.blanks(mask)
+            .filter_map(|idx| self.value.get(idx).flatten())
+            .filter(|c| *c != MASK_BLANK)
+            .for_each(|c| self.clusters.push(&mut raw, c));
+
+        raw
+    }
+
+    // the indexes of the blank cells of mask
+    fn blanks(&self, mask: &'static str) -> impl Iterator<Item = usize> {
+        mask.chars()
+            .enumerate()
+            .filter(|(_, c)| *c == MASK_BLANK)
+            .map(|(idx, _)| idx)
+    }
+
+    // the literal of the mask in the cell idx, None for a blank or a cell past the mask
+    fn literal(&self, idx: usize) -> Option<char> {
+        let c = self.mask?.chars().nth(idx)?;
+
+        (c != MASK_BLANK).then_some(c)
+    }
+
+    // moves the cursor off the literals of the mask, backwards first if back,
+    // forwards when there is no blank behind it
+    pub(crate) fn skip_literals(&mut self, back: bool) {
+        let Some(mask) = self.mask else {
+            return;
+        };
+        let len = mask.chars().count();
+        let idx = self.cursor_index().min(len);
+        let blanks = self.blanks(mask).collect::<Vec<usize>>();
+        let before = blanks.iter().rev().find(|b| **b <= idx).copied();
+        let after = blanks.iter().find(|b| **b >= idx).copied();
+        let to = match (self.literal(idx).is_some(), back) {
+            (false, _) => idx,
+            (true, true) => before.or(after).unwrap_or(len),
+            (true, false) => after.unwrap_or(len),
+        };
+        self.set_cursor_index(to);
+    }
+
+    // whether c can be typed at the cursor, the value it would make goes through the filter
+    pub(crate) fn accepts(&self, c: char) -> bool {
+        // the chars the cells stand for, see grapheme
+        let chars = |cells: Vec<Option<char>>| {
+            let mut s = String::new();
+            cells.into_iter().flatten().for_each(|c| self.clusters.push(&mut s, c));
+            s
+        };
+        let raw = match self.mask {
+            Some(_) => self.raw_value() + &chars(vec![Some(c)]),
+            None => {
+                let idx = self.cursor_index().min(self.value.len());
+                let mut cells = self.value.to_vec();
+                cells.insert(idx, Some(c));
+                chars(cells)
+            }
+        };
+
+        self.filter.map_or(true, |filter| filter.accepts(&raw))
+    }
+
+    // types c into the blank under the cursor, the cursor goes to the next blank
+    // a literal of the mask just behind the cursor, or ahead of it, is stepped over instead
+    // returns false when there is no blank left to fill
+    pub(crate) fn put_masked(&mut self, mask: &'static str, c: char) -> bool {
+        let idx = self.cursor_index();
+        if idx > 0 && self.literal(idx - 1) == Some(c) {
+            return true;
+        }
+        let len = mask.chars().count();
+        let ahead = (idx..len).find(|i| self.literal(*i).is_some());
+        if let Some(at) = ahead.filter(|at| self.literal(*at) == Some(c)) {
+            self.set_cursor_index(at + 1);
+            self.skip_literals(false);
+            return true;
+        }
+        if idx >= len || c == MASK_BLANK || !self.accepts(c) {
+            return false;
+        }
+        self.value.set(idx, Some(c));
+        self.set_cursor_index(idx + 1);
+        self.skip_literals(false);
+
+        true
+    }
+
+    // empties the blank before the cursor, or under it when back is false
+    // returns false if there is no such blank
+    pub(crate) fn clear_masked(&mut self, mask: &'static str, back: bool) -> bool {
+        let idx = self.cursor_index();
+        let blanks = self.blanks(mask).collect::<Vec<usize>>();
+        let blank = match back {
+            true => blanks.iter().rev().find(|b| **b < idx),
+            false => blanks.iter().find(|b| **b >= idx),
+        };
+        let Some(&blank) = blank else {
+            return false;
+        };
+        self.value.set(blank, Some(MASK_BLANK));
+        if back {
+            self.set_cursor_index(blank);
+        }
+        self.mark_dirty();
+
+        true
+    }
+
+    // the chars typed into this text that its filter or mask rejected since the last call
+    pub(crate) fn take_rejected(&mut self) -> String {
+        std::mem::take(&mut self.rejected)
+    }
+}
+
+impl Term {
+    /// calls f with the input's id and the char every time a typed or pasted char is rejected
+    /// by its filter or mask, e.g. to ring the bell, replaces the previous callback
+    pub fn on_reject(&mut self, f: impl FnMut([u8; 3], char) + 'static) {
+        self.reject_hook = Hook(Some(Box::new(f)));
+    }
+
+    // calls the on_reject hook for the chars the input with id rejected
+    pub(crate) fn report_rejected(&mut self, id: &[u8; 3]) {
+        let Some(rejected) = self.text_mut(id).map(Text::take_rejected) else {
+            return;
+        };
+        if let Some(hook) = self.reject_hook.0.as_mut() {
+            rejected.chars().for_each(|c| hook(*id, c));
+        }
+    }
+}
+
+// what Term::on_reject was given
+pub(crate) type RejectHook = Hook<Option<Box<dyn FnMut([u8; 3], char)>>>;
+
+#[cfg(test)]
+mod filter {
+    use super::*;
+    use crate::components::Container;
+    use crate::space::{border::Border, padding::Padding};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // a focused 12x1 input
+    fn term() -> Term {
+        let mut term = Term::new(0, 20, 4);
+        let mut c = Container::new([0, 0], 0, 0, 12, 2, Border::None, Padding::None);
+        c.items.push(Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            12,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        ));
+        term.containers.push(c);
+        term.focused = Some([0, 0, 0]);
+
+        term
+    }
+
+    fn input(term: &mut Term) -> &mut Text {
+        term.input_mut(&[0, 0, 0]).unwrap()
+    }
+
+    #[test]
+    fn numeric() {
+        let filter = InputFilter::Numeric {
+            allow_negative: true,
+            allow_decimal: false,
+        };
+        assert!(filter.accepts("-12"));
+        assert!(filter.accepts("-"));
+        assert!(!filter.accepts("1-2"));
+        assert!(!filter.accepts("1.5"));
+        let decimal = InputFilter::Numeric {
+            allow_negative: false,
+            allow_decimal: true,
+        };
+        assert!(decimal.accepts("1."));
+        assert!(!decimal.accepts("1.5."));
+        assert!(!decimal.accepts("-1"));
+        assert!(InputFilter::Charset("abc").accepts("cab"));
+        assert!(!InputFilter::Charset("abc").accepts("abd"));
+    }
+
+    #[test]
+    fn rejected() {
+        let mut term = term();
+        let seen = Rc::new(RefCell::new(String::new()));
+        let rejects = seen.clone();
+        term.on_reject(move |_, c| rejects.borrow_mut().push(c));
+        input(&mut term).set_filter(Some(InputFilter::Numeric {
+            allow_negative: false,
+            allow_decimal: true,
+        }));
+
+        term.paste("1a2.3.4");
+        assert_eq!(input(&mut term).value_string(), "12.34");
+        assert_eq!(*seen.borrow(), "a.");
+        assert!(!input(&mut term).insert('x'));
+        assert!(input(&mut term).insert('5'));
+    }
+
+    #[test]
+    fn masked() {
+        let mut term = term();
+        let text = input(&mut term);
+        text.set_filter(Some(InputFilter::Charset("0123456789")));
+        text.set_mask(Some("____-__-__"));
+        assert_eq!(text.value_string(), "____-__-__");
+
+        text.insert_str("20240");
+        assert_eq!(text.value_string(), "2024-0_-__");
+        assert_eq!(text.cx, 6);
+        assert_eq!(text.raw_value(), "20240");
+        // backspace goes back over the dash
+        text.backspace();
+        text.backspace();
+        assert_eq!(text.value_string(), "202_-__-__");
+        assert_eq!(text.cx, 3);
+        // the dash forwards the cursor to the next part
+        text.insert_str("4-0131");
+        assert_eq!(text.value_string(), "2024-01-31");
+        assert!(!text.insert('1'));
+
+        text.set_cursor_index(5);
+        assert!(text.move_left());
+        assert_eq!(text.cx, 3);
+        assert!(text.move_right());
+        assert_eq!(text.cx, 5);
+
+        text.replace_value("1999-12-31");
+        assert_eq!(text.value_string(), "1999-12-31");
+        text.replace_value("20000101");
+        assert_eq!(text.value_string(), "2000-01-01");
+        assert_eq!(text.raw_value(), "20000101");
+    }
+}
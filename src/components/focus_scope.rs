@@ -0,0 +1,160 @@
+use super::{ComponentTreeError, Term};
+
+impl Term {
+    /// confines the focus to the texts of the container with id, e.g. while a wizard step is
+    /// shown, until Term::pop_focus_scope, Term::focus_next and Term::focus_prev, Tab and the
+    /// clicks only go through its texts and Term::focus returns OutOfFocusScope for the others
+    /// scopes nest, the last one pushed is the one in use, a popup opened on top of it still
+    /// takes the focus, see Term::popup
+    /// if the focused text is outside of the container, the focus goes to its first text in
+    /// tab order that can be focused, or nowhere if it has none
+    ///
+    /// # Errors
+    /// returns BadID if there is no container with id
+    pub fn push_focus_scope(&mut self, id: &[u8; 2]) -> Result<(), ComponentTreeError> {
+        if self.container_ref(id).is_none() {
+            return Err(ComponentTreeError::BadID { id: id.to_vec() });
+        }
+        self.prune_focus_scopes();
+        self.focus_scopes.push(*id);
+        self.enter_focus_scope();
+
+        Ok(())
+    }
+
+    /// ends the focus scope pushed last, the one pushed before it is used again
+    /// the scopes whose container was removed are dropped first
+    /// returns the container id of the scope that ended, None if there was none
+    pub fn pop_focus_scope(&mut self) -> Option<[u8; 2]> {
+        self.prune_focus_scopes();
+        let id = self.focus_scopes.pop();
+        self.enter_focus_scope();
+
+        id
+    }
+
+    /// the container id of the focus scope in use, see Term::push_focus_scope
+    /// a scope whose container was removed is skipped
+    pub fn focus_scope(&self) -> Option<[u8; 2]> {
+        self.focus_scopes
+            .iter()
+            .rev()
+            .find(|id| self.container_ref(id).is_some())
+            .copied()
+    }
+
+    // whether the focus scope in use lets the text with id be focused,
+    // everything does while a popup is open, the popup decides what can be
+    pub(crate) fn in_focus_scope(&self, id: &[u8; 3]) -> bool {
+        self.top_popup().is_some() || self.focus_scope().map_or(true, |s| [id[0], id[1]] == s)
+    }
+
+    // drops the scopes whose container is gone
+    fn prune_focus_scopes(&mut self) {
+        let scopes = std::mem::take(&mut self.focus_scopes);
+        self.focus_scopes = scopes
+            .into_iter()
+            .filter(|id| self.container_ref(id).is_some())
+            .collect();
+    }
+
+    // moves the focus inside the scope in use, when it is outside of it
+    fn enter_focus_scope(&mut self) {
+        if self.focused.map_or(true, |id| self.in_focus_scope(&id)) {
+            return;
+        }
+        self.leave_history();
+        if let Some(text) = self.focused.and_then(|id| self.text_mut(&id)) {
+            text.focus_checkbox(false);
+        }
+        self.focused = None;
+        self.focus_next();
+    }
+}
+
+#[cfg(test)]
+mod focus_scope {
+    use super::*;
+    use crate::components::{Container, Text};
+    use crate::space::{border::Border, padding::Padding};
+
+    // 3 containers, one above the other, with 2 inputs each
+    fn steps() -> Term {
+        let mut term = Term::new(0, 20, 6);
+        for cid in 0..3u8 {
+            let y = cid as u16 * 2;
+            let mut c = Container::new([0, cid], 0, y, 20, 2, Border::None, Padding::None);
+            for iid in [0, 2] {
+                let input = Text::input(
+                    [0, cid, iid],
+                    0,
+                    iid as u16 / 2,
+                    0,
+                    y + iid as u16 / 2,
+                    20,
+                    1,
+                    &[],
+                    Border::None,
+                    Padding::None,
+                );
+                c.items.push(input);
+            }
+            term.containers.push(c);
+        }
+        _ = term.focus(&[0, 0, 0]);
+
+        term
+    }
+
+    #[test]
+    fn confined() {
+        let mut term = steps();
+        assert!(term.push_focus_scope(&[0, 9]).is_err());
+        term.push_focus_scope(&[0, 1]).unwrap();
+        // the focus was outside, it lands on the first input of the scope
+        assert_eq!(term.focused, Some([0, 1, 0]));
+
+        term.focus_next();
+        term.focus_next();
+        assert_eq!(term.focused, Some([0, 1, 0]));
+        term.focus_prev();
+        assert_eq!(term.focused, Some([0, 1, 2]));
+        assert_eq!(
+            term.focus(&[0, 2, 0]),
+            Err(ComponentTreeError::OutOfFocusScope {
+                id: [0, 2, 0],
+                scope: [0, 1]
+            })
+        );
+    }
+
+    #[test]
+    fn nested() {
+        let mut term = steps();
+        term.push_focus_scope(&[0, 0]).unwrap();
+        assert_eq!(term.focused, Some([0, 0, 0]));
+        term.push_focus_scope(&[0, 2]).unwrap();
+        assert_eq!(term.focused, Some([0, 2, 0]));
+
+        assert_eq!(term.pop_focus_scope(), Some([0, 2]));
+        assert_eq!(term.focus_scope(), Some([0, 0]));
+        assert_eq!(term.focused, Some([0, 0, 0]));
+        assert_eq!(term.pop_focus_scope(), Some([0, 0]));
+        assert_eq!(term.pop_focus_scope(), None);
+        assert!(term.focus(&[0, 2, 2]).is_ok());
+    }
+
+    #[test]
+    fn removed() {
+        let mut term = steps();
+        term.push_focus_scope(&[0, 0]).unwrap();
+        term.push_focus_scope(&[0, 1]).unwrap();
+        term.containers.retain(|c| c.id != [0, 1]);
+        term.focused = None;
+
+        assert_eq!(term.focus_scope(), Some([0, 0]));
+        assert!(term.focus(&[0, 2, 0]).is_err());
+        assert_eq!(term.pop_focus_scope(), Some([0, 0]));
+        assert!(term.focus_scopes.is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+use super::Text;
+
+// the blocks of 1 to 7 eighths of a cell, from the left
+const EIGHTHS: &[char] = &['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// a bar filling a Text from the left as far as a ratio goes, with a label in its middle row,
+/// see Text::set_gauge
+/// the label is the ratio as a percentage unless told otherwise, see Gauge::label
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Gauge {
+    ratio: f64,
+    label: Option<String>,
+}
+
+impl Gauge {
+    /// makes a new Gauge filled as far as ratio goes, see Gauge::ratio
+    pub fn new(ratio: f64) -> Self {
+        Self {
+            ratio: clamped(ratio),
+            label: None,
+        }
+    }
+
+    /// shows label in place of the percentage
+    pub fn label(&mut self, label: &str) {
+        self.label = Some(label.to_string());
+    }
+
+    /// how far the gauge is filled, from 0 to 1
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    // the label that is shown
+    fn shown_label(&self) -> String {
+        match &self.label {
+            Some(label) => label.clone(),
+            None => format!("{}%", (self.ratio * 100.0).round()),
+        }
+    }
+
+    // the cells of a w * h text
+    fn cells(&self, w: u16, h: u16) -> Vec<Option<char>> {
+        let w = w as usize;
+        let eighths = (self.ratio * w as f64 * 8.0).round() as usize;
+        let mut row = vec![Some('█'); eighths / 8];
+        if eighths % 8 > 0 {
+            row.push(Some(EIGHTHS[eighths % 8 - 1]));
+        }
+        row.resize(w, None);
+
+        let mut cells = row.repeat(h as usize);
+        let label = self.shown_label().chars().take(w).collect::<Vec<char>>();
+        let start = (h as usize / 2) * w + (w - label.len()) / 2;
+        cells[start..start + label.len()]
+            .iter_mut()
+            .zip(label)
+            .for_each(|(cell, c)| *cell = Some(c));
+
+        cells
+    }
+}
+
+// a ratio between 0 and 1, NaN is 0
+fn clamped(ratio: f64) -> f64 {
+    match ratio.is_nan() {
+        true => 0.0,
+        false => ratio.clamp(0.0, 1.0),
+    }
+}
+
+// gauges
+impl Text {
+    /// shows gauge in this text, replacing its value
+    pub fn set_gauge(&mut self, gauge: Gauge) {
+        self.gauge = Some(gauge);
+        self.sync_gauge();
+    }
+
+    /// the gauge this text shows, if it shows one
+    pub fn gauge(&self) -> Option<&Gauge> {
+        self.gauge.as_ref()
+    }
+
+    /// fills the gauge this text shows as far as ratio goes, ratio is clamped between 0 and 1
+    /// and NaN empties the gauge
+    /// returns false if the text shows no gauge
+    pub fn set_ratio(&mut self, ratio: f64) -> bool {
+        let Some(gauge) = &mut self.gauge else {
+            return false;
+        };
+        gauge.ratio = clamped(ratio);
+        self.sync_gauge();
+
+        true
+    }
+
+    // writes the bar and the label to the value
+    fn sync_gauge(&mut self) {
+        let Some(gauge) = &self.gauge else {
+            return;
+        };
+        self.value = gauge.cells(self.w, self.h).into();
+        self.scroll = 0;
+        self.mark_dirty();
+    }
+}
+
+#[cfg(test)]
+mod gauge {
+    use super::*;
+    use crate::console::Capabilities;
+    use crate::space::{border::Border, padding::Padding};
+
+    fn shown(text: &Text) -> String {
+        text.value.iter().map(|c| c.unwrap_or(' ')).collect()
+    }
+
+    #[test]
+    fn fill() {
+        let mut text = Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            1,
+            1,
+            10,
+            3,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        text.set_gauge(Gauge::new(0.45));
+        assert_eq!(
+            shown(&text),
+            ["████▌     ", "███45%    ", "████▌     "].concat()
+        );
+        assert!(text.attributes.contains("dirty"));
+
+        text.set_ratio(f64::NAN);
+        assert_eq!(text.gauge().unwrap().ratio(), 0.0);
+        text.set_ratio(2.0);
+        let mut gauge = Gauge::new(1.0);
+        gauge.label("done");
+        text.set_gauge(gauge);
+        assert_eq!(
+            shown(&text),
+            ["██████████", "███done███", "██████████"].concat()
+        );
+
+        let ascii = ['▍', '▌', '█'].map(|c| Capabilities::default().adapt_char(c));
+        assert_eq!(ascii, [' ', '#', '#']);
+    }
+}
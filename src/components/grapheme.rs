@@ -0,0 +1,413 @@
+//! grapheme clusters, the chars that are drawn as one, e.g. an emoji and its skin tone,
+//! a family of emoji joined by zero width joiners, a flag or a letter and its accents
+//!
+//! a cluster is one char of the value of a text, the cursor, backspace and delete go over it
+//! at once, and it takes the cells it is drawn over, 2 for an emoji or a wide east asian char
+
+use super::escape::NUL;
+use super::tab_stop::FILL;
+use super::Text;
+
+// the cell on the right of a wide char as it is shown, the terminal draws the char over both
+pub(crate) const WIDE: char = '\u{e002}';
+
+// the char standing for the first cluster of a text, in the supplementary private use area A
+const FIRST: u32 = 0xf0000;
+
+// the number of clusters a table can tell apart, one for each char from FIRST to char::MAX
+const CAPACITY: usize = (char::MAX as u32 - FIRST + 1) as usize;
+
+const ZWJ: char = '\u{200d}';
+
+// the clusters of more than one char of a text's value, the FIRST + nth char of the value
+// stands for the nth one, and so do the chars past FIRST and the chars FILL, NUL and WIDE
+// stand for that came into the value, so that they aren't taken for a cluster or a fill cell
+// the clusters are only ever added, the ones no cell stands for anymore are dropped
+// once the table is full, see Clusters::reclaim
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Clusters(Vec<String>);
+
+impl Clusters {
+    // the chars of the cluster c stands for, None when c is a char of its own
+    pub(crate) fn get(&self, c: char) -> Option<&str> {
+        let n = (c as u32).checked_sub(FIRST)?;
+
+        self.0.get(n as usize).map(String::as_str)
+    }
+
+    // the char standing for the cluster s, s itself when it is one char the value can keep
+    pub(crate) fn intern(&mut self, s: &str) -> char {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if !reserved(c) {
+                return c;
+            }
+        }
+        let n = match self.0.iter().position(|cluster| cluster == s) {
+            Some(n) => n,
+            None => {
+                self.0.push(s.to_string());
+                self.0.len() - 1
+            }
+        };
+
+        char::from_u32(FIRST + n as u32).unwrap_or(char::REPLACEMENT_CHARACTER)
+    }
+
+    // whether the next new cluster would not get a char of its own
+    pub(crate) fn is_full(&self) -> bool {
+        self.0.len() >= CAPACITY
+    }
+
+    // drops the clusters none of cells stands for and numbers the others again,
+    // the cells get the chars that stand for them now
+    pub(crate) fn reclaim<'a>(&mut self, cells: impl Iterator<Item = &'a mut Option<char>>) {
+        let old = std::mem::take(self);
+        cells.flatten().for_each(|c| *c = self.take(&old, *c));
+    }
+
+    // the char standing for c in the value, see Clusters::intern
+    pub(crate) fn own(&mut self, c: char) -> char {
+        self.intern(c.encode_utf8(&mut [0; 4]))
+    }
+
+    // the char standing in this table for what c stands for in from
+    pub(crate) fn take(&mut self, from: &Clusters, c: char) -> char {
+        match from.get(c) {
+            Some(chars) => self.intern(chars),
+            None if (c as u32) >= FIRST => self.own(c),
+            None => c,
+        }
+    }
+
+    // pushes the chars c stands for at the end of s
+    pub(crate) fn push(&self, s: &mut String, c: char) {
+        match self.get(c) {
+            Some(chars) => s.push_str(chars),
+            None => s.push(c),
+        }
+    }
+
+    // whether c goes into the cluster base stands for
+    pub(crate) fn joins(&self, base: char, c: char) -> bool {
+        if [FILL, NUL, WIDE].contains(&base) {
+            return false;
+        }
+
+        match self.get(base) {
+            Some(chars) => joins(chars, c),
+            None => joins(base.encode_utf8(&mut [0; 4]), c),
+        }
+    }
+
+    // the char standing for the cluster base stands for with c at its end
+    pub(crate) fn joined(&mut self, base: char, c: char) -> char {
+        let mut s = String::new();
+        self.push(&mut s, base);
+        self.push(&mut s, c);
+
+        self.intern(&s)
+    }
+
+    // the number of cells c takes, see width
+    pub(crate) fn cells(&self, c: char) -> usize {
+        match self.get(c) {
+            Some(chars) => cells(chars),
+            None => cells(c.encode_utf8(&mut [0; 4])),
+        }
+    }
+}
+
+// whether the value keeps c for something else than the char it is
+fn reserved(c: char) -> bool {
+    (c as u32) >= FIRST || [FILL, NUL, WIDE].contains(&c)
+}
+
+/// the number of cells s takes on the display, an emoji, a flag and a wide east asian char
+/// take 2, a mark or a joiner goes with the char before it
+pub fn width(s: &str) -> usize {
+    split(s).into_iter().map(cells).sum()
+}
+
+// the chars of s put together in clusters
+pub(crate) fn split(s: &str) -> Vec<&str> {
+    let mut clusters = vec![];
+    let mut start = 0;
+    for (idx, c) in s.char_indices() {
+        if idx > 0 && !joins(&s[start..idx], c) {
+            clusters.push(&s[start..idx]);
+            start = idx;
+        }
+    }
+    if start < s.len() {
+        clusters.push(&s[start..]);
+    }
+
+    clusters
+}
+
+// whether c goes into the cluster of chars
+pub(crate) fn joins(chars: &str, c: char) -> bool {
+    let mut all = chars.chars();
+    let Some(first) = all.next() else {
+        return false;
+    };
+    if first.is_control() {
+        return false;
+    }
+    let alone = all.next().is_none();
+    let last = chars.chars().last().unwrap_or(first);
+
+    extends(c) || last == ZWJ || regional(c) && regional(first) && alone
+}
+
+// the number of cells the cluster of chars takes, see width
+pub(crate) fn cells(chars: &str) -> usize {
+    let mut all = chars.chars();
+    let wide = match (all.next(), all.next()) {
+        (Some(c), None) => wide(c),
+        (first, _) => {
+            let flag = chars.chars().filter(|c| regional(*c)).count() == 2;
+            flag || chars.contains('\u{fe0f}') || first.is_some_and(wide)
+        }
+    };
+
+    1 + wide as usize
+}
+
+// a mark, a variation selector, a skin tone, a tag or a joiner, it goes with the char before it
+fn extends(c: char) -> bool {
+    matches!(c as u32,
+        0x300..=0x36f | 0x483..=0x489 | 0x591..=0x5bd | 0x610..=0x61a | 0x64b..=0x65f
+        | 0x1ab0..=0x1aff | 0x1dc0..=0x1dff | 0x200c..=0x200d | 0x20d0..=0x20ff
+        | 0xfe00..=0xfe0f | 0xfe20..=0xfe2f | 0x1f3fb..=0x1f3ff | 0xe0020..=0xe007f
+        | 0xe0100..=0xe01ef)
+}
+
+// the letters two of which make a flag
+fn regional(c: char) -> bool {
+    ('\u{1f1e6}'..='\u{1f1ff}').contains(&c)
+}
+
+// the emoji shown as such by default and the wide and fullwidth east asian chars
+fn wide(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115f | 0x231a..=0x231b | 0x2329..=0x232a | 0x23e9..=0x23ec | 0x23f0
+        | 0x23f3 | 0x25fd..=0x25fe | 0x2614..=0x2615 | 0x2648..=0x2653 | 0x267f | 0x2693
+        | 0x26a1 | 0x26aa..=0x26ab | 0x26bd..=0x26be | 0x26c4..=0x26c5 | 0x26ce | 0x26d4
+        | 0x26ea | 0x26f2..=0x26f3 | 0x26f5 | 0x26fa | 0x26fd | 0x2705 | 0x270a..=0x270b
+        | 0x2728 | 0x274c | 0x274e | 0x2753..=0x2755 | 0x2757 | 0x2795..=0x2797 | 0x27b0
+        | 0x27bf | 0x2b1b..=0x2b1c | 0x2b50 | 0x2b55 | 0x2e80..=0x303e | 0x3041..=0x33ff
+        | 0x3400..=0x4dbf | 0x4e00..=0x9fff | 0xa000..=0xa4cf | 0xa960..=0xa97f
+        | 0xac00..=0xd7a3 | 0xf900..=0xfaff | 0xfe10..=0xfe19 | 0xfe30..=0xfe6f
+        | 0xff00..=0xff60 | 0xffe0..=0xffe6 | 0x1f004 | 0x1f0cf | 0x1f18e
+        | 0x1f191..=0x1f19a | 0x1f200..=0x1f2ff | 0x1f300..=0x1f64f | 0x1f680..=0x1f6ff
+        | 0x1f7e0..=0x1f7ff | 0x1f900..=0x1f9ff | 0x1fa70..=0x1faff | 0x20000..=0x2fffd
+        | 0x30000..=0x3fffd)
+}
+
+// clusters
+impl Text {
+    // the char standing for c in the value, the clusters the value no longer holds
+    // are dropped first when there is no room for another one
+    pub(crate) fn own(&mut self, c: char) -> char {
+        self.reclaim_clusters();
+
+        self.clusters.own(c)
+    }
+
+    // drops the clusters the value no longer holds once the table is full, see Clusters::reclaim
+    pub(crate) fn reclaim_clusters(&mut self) {
+        if self.clusters.is_full() {
+            let mut cells = self.value.to_vec();
+            self.clusters.reclaim(cells.iter_mut());
+            self.value = cells.into();
+        }
+    }
+
+    // the cell of the cluster that ends right before the cell idx, when c goes into it
+    pub(crate) fn cluster_before(&self, idx: usize, c: char) -> Option<usize> {
+        let mut at = idx.checked_sub(1)?;
+        while at > 0 && self.value.get(at).flatten() == Some(FILL) {
+            at -= 1;
+        }
+        let base = self.value.get(at).flatten()?;
+
+        (self.clusters.joins(base, c) && self.escape(base).is_none()).then_some(at)
+    }
+
+    // puts c into the cluster that ends right before the cell idx when it goes with it,
+    // returns the cell of the cluster
+    pub(crate) fn join_at(&mut self, idx: usize, c: char) -> Option<usize> {
+        let at = self.cluster_before(idx, c)?;
+        // the cell is numbered again when there is no room for the joined cluster
+        self.reclaim_clusters();
+        let base = self.value.get(at).flatten()?;
+        let joined = self.clusters.joined(base, c);
+        self.value.set(at, Some(joined));
+
+        Some(at)
+    }
+}
+
+#[cfg(test)]
+mod grapheme {
+    use super::*;
+    use crate::components::{Container, Term};
+    use crate::space::{border::Border, padding::Padding};
+    use crate::test::Frame;
+
+    const FAMILY: &str = "👨\u{200d}👩\u{200d}👧";
+    const THUMB: &str = "👍\u{1f3fd}";
+    const HEART: &str = "\u{2764}\u{fe0f}";
+
+    // a 10x2 term holding a focused 8x1 input
+    fn term() -> Term {
+        let mut term = Term::new(0, 10, 2);
+        let mut c = Container::new([0, 0], 0, 0, 8, 2, Border::None, Padding::None);
+        c.items.push(Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            8,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        ));
+        term.containers.push(c);
+        term.focused = Some([0, 0, 0]);
+
+        term
+    }
+
+    #[test]
+    fn widths() {
+        assert_eq!(width(FAMILY), 2);
+        assert_eq!(width(THUMB), 2);
+        // the variation selector makes the heart an emoji
+        assert_eq!([width("\u{2764}"), width(HEART)], [1, 2]);
+        assert_eq!(width("🇯🇵🇫🇷"), 4);
+        assert_eq!(width("e\u{301}漢"), 3);
+        assert_eq!(split(FAMILY), [FAMILY]);
+    }
+
+    #[test]
+    fn fixture() {
+        let mut term = term();
+        term.paste(&format!("{THUMB}{FAMILY}a{HEART}"));
+        let text = term.input_ref(&[0, 0, 0]).unwrap();
+        let value = format!("{THUMB}{FAMILY}a{HEART}");
+        assert_eq!(text.value_string(), value);
+        // the buffer, the cursor and the frame agree on the width
+        assert_eq!(text.cx as usize, width(&value));
+        assert_eq!(text.cursor_info().len, 4);
+        let rows = Frame::of(&term).rows();
+        assert_eq!(rows[0], format!("{value}   "));
+        assert_eq!(width(&rows[0]), 10);
+
+        // the family goes at once, the cursor stays on the cell boundaries
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        text.move_left();
+        text.move_left();
+        assert_eq!(text.cx, 4);
+        text.move_left();
+        assert_eq!(text.cx, 2);
+        assert!(text.backspace());
+        assert_eq!(text.value_string(), format!("{FAMILY}a{HEART}"));
+        assert_eq!(text.cx, 0);
+        assert!(text.delete());
+        assert_eq!(text.value_string(), format!("a{HEART}"));
+    }
+
+    #[test]
+    fn edge() {
+        // the emoji that doesn't fit in the last cell of the row goes on on the next one
+        let mut term = term();
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        text.h = 2;
+        text.attributes.insert("multiline");
+        text.replace_value(&format!("abcdefg{THUMB}"));
+        assert_eq!(text.cell(7, 0), Some(' '));
+        let thumb = text.cell(0, 1).unwrap();
+        assert_eq!(text.clusters.get(thumb), Some(THUMB));
+        assert_eq!(text.cell(1, 1), Some(WIDE));
+
+        // and so does an appended one, its skin tone comes with it
+        let mut log = Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            0,
+            0,
+            4,
+            2,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        log.append_str(&format!("abc{THUMB}d"));
+        let thumb = log.cell(0, 1).unwrap();
+        assert_eq!(log.clusters.get(thumb), Some(THUMB));
+        assert_eq!(log.cell(2, 1), Some('d'));
+        assert_eq!(log.value_string(), format!("abc{THUMB}d"));
+
+        // scrolled into the middle of an emoji, the half of it in the view is blank
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        text.replace_value(&format!("{FAMILY}abc"));
+        text.hscroll = 1;
+        assert_eq!(text.cell(0, 0), Some(' '));
+        assert_eq!(text.cell(1, 0), Some('a'));
+    }
+
+    #[test]
+    fn reclaimed() {
+        // a table with no room left, the value only holds its first cluster
+        let mut term = term();
+        term.paste(&format!("a{THUMB}"));
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        let left = CAPACITY - text.clusters.0.len();
+        text.clusters.0.extend((0..left).map(|n| n.to_string()));
+        assert!(text.clusters.is_full());
+
+        // the clusters no cell stands for make room for the new one
+        term.paste(HEART);
+        let text = term.input_ref(&[0, 0, 0]).unwrap();
+        assert_eq!(text.value_string(), format!("a{THUMB}{HEART}"));
+        assert_eq!(text.clusters.0, [THUMB, HEART]);
+        let rows = Frame::of(&term).rows();
+        assert_eq!(rows[0].trim_end(), format!("a{THUMB}{HEART}"));
+    }
+
+    #[test]
+    fn private_use() {
+        // a char of the private use area the clusters are numbered from is no cluster
+        let mut term = term();
+        let icon = '\u{f0001}';
+        term.paste(&format!("{THUMB}{icon}"));
+        let text = term.input_ref(&[0, 0, 0]).unwrap();
+        assert_eq!(text.value_string(), format!("{THUMB}{icon}"));
+
+        // each text numbers its own clusters, the frame tells them apart
+        let mut log = Text::nonedit(
+            [0, 0, 1],
+            0,
+            1,
+            0,
+            1,
+            8,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        log.append_str(&format!("{icon}{FAMILY}"));
+        assert_eq!(log.value_string(), format!("{icon}{FAMILY}"));
+        term.containers[0].items.push(log);
+        let rows = Frame::of(&term).rows();
+        assert_eq!(rows[0].trim_end(), format!("{THUMB}{icon}"));
+        assert_eq!(rows[1].trim_end(), format!("{icon}{FAMILY}"));
+    }
+}
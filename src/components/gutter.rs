@@ -0,0 +1,392 @@
+use crate::console::Capabilities;
+use crate::render_pipeline::cup;
+use crate::space::padding::Padding;
+use crate::themes::Style;
+
+use super::{SpaceError, Text};
+
+// the line numbers on the left of a text, see Text::set_line_numbers
+// the gutter takes its columns from the text's width and lies in its inner left padding,
+// so the value, the cursor and the scrolling only see the columns left to the value
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Gutter {
+    // the number of columns taken, 0 while the line numbers are off
+    w: u16,
+    // what the rows that go on with the line of the row above them show
+    marker: Option<char>,
+    style: String,
+}
+
+impl Default for Gutter {
+    fn default() -> Self {
+        Self {
+            w: 0,
+            marker: None,
+            style: Style::new().faint().style(),
+        }
+    }
+}
+
+// the number of digits of n
+fn digits(n: usize) -> u16 {
+    n.max(1).ilog10() as u16 + 1
+}
+
+// padding with columns more, or less, on the inner left side
+fn widen(padding: &Padding, columns: i32) -> Padding {
+    let add = |left: u16| (left as i32 + columns).max(0) as u16;
+    match *padding {
+        Padding::None => Padding::Inner {
+            top: 0,
+            bottom: 0,
+            right: 0,
+            left: add(0),
+        },
+        Padding::Inner {
+            top,
+            bottom,
+            right,
+            left,
+        } => Padding::Inner {
+            top,
+            bottom,
+            right,
+            left: add(left),
+        },
+        Padding::Outer {
+            top,
+            bottom,
+            right,
+            left,
+        } => Padding::InOut {
+            inner_top: 0,
+            inner_bottom: 0,
+            inner_right: 0,
+            inner_left: add(0),
+            outer_top: top,
+            outer_bottom: bottom,
+            outer_right: right,
+            outer_left: left,
+        },
+        Padding::InOut {
+            inner_top,
+            inner_bottom,
+            inner_right,
+            inner_left,
+            outer_top,
+            outer_bottom,
+            outer_right,
+            outer_left,
+        } => Padding::InOut {
+            inner_top,
+            inner_bottom,
+            inner_right,
+            inner_left: add(inner_left),
+            outer_top,
+            outer_bottom,
+            outer_right,
+            outer_left,
+        },
+    }
+}
+
+// the lines of cells, rows of stride cells that go on with the next row when their last cell
+// is filled, without their trailing empty cells, and the number of rows they took
+pub(super) fn split_lines(cells: &[Option<char>], stride: usize) -> Vec<(Vec<Option<char>>, usize)> {
+    let mut lines = vec![(vec![], 0)];
+    for row in cells.chunks(stride) {
+        let (line, rows) = lines.last_mut().unwrap();
+        line.extend_from_slice(row);
+        *rows += 1;
+        if row.len() < stride || row[stride - 1].is_none() {
+            lines.push((vec![], 0));
+        }
+    }
+    lines.iter_mut().for_each(|(line, _)| {
+        while line.last() == Some(&None) {
+            line.pop();
+        }
+    });
+    // the empty rows under the last line
+    while lines.len() > 1 && lines.last().is_some_and(|(line, _)| line.is_empty()) {
+        lines.pop();
+    }
+
+    lines
+}
+
+// line numbers
+impl Text {
+    /// shows the numbers of the lines of the value in a gutter on the left of this text,
+    /// meant for multiline texts, numbers are right-aligned and dimmed, see Text::set_gutter_style
+    /// the gutter is as wide as the number of the last row the value holds, it takes its columns
+    /// from the width of this text, the value is wrapped again on the columns left to it
+    /// and the cursor stays on the char it was on
+    /// a row that goes on with the line above it shows no number, see Text::set_wrap_marker
+    /// the gutter is neither edited nor selected, it isn't part of the value
+    /// turning the line numbers on again fits the gutter to the value as it is now
+    /// returns an AreaOutOfBounds error when no column would be left to the value
+    pub fn set_line_numbers(&mut self, on: bool) -> Result<(), SpaceError> {
+        let rows = match self.w {
+            0 => 0,
+            w => self.value.len().div_ceil(w as usize),
+        };
+        let w = match on {
+            true => digits(rows.max(self.h as usize)),
+            false => 0,
+        };
+        let total = self.w + self.gutter.w;
+        if w >= total {
+            return Err(SpaceError::AreaOutOfBounds {
+                requested: [w + 1, self.h],
+                available: [total, self.h],
+            });
+        }
+        let delta = w as i32 - self.gutter.w as i32;
+        if delta == 0 {
+            return Ok(());
+        }
+
+        self.padding = widen(&self.padding, delta);
+        self.ax0 = (self.ax0 as i32 + delta) as u16;
+        self.gutter.w = w;
+        self.rewrap(total - w);
+        self.invalidate();
+        self.mark_dirty();
+
+        Ok(())
+    }
+
+    /// whether this text shows its line numbers, see Text::set_line_numbers
+    pub fn line_numbers(&self) -> bool {
+        self.gutter.w > 0
+    }
+
+    /// changes what the rows that go on with the line above them show in the gutter,
+    /// nothing by default
+    pub fn set_wrap_marker(&mut self, marker: Option<char>) {
+        self.gutter.marker = marker;
+        self.mark_dirty();
+    }
+
+    /// changes the style of the line numbers, they are dimmed by default
+    pub fn set_gutter_style(&mut self, style: &Style) {
+        self.gutter.style = style.style();
+        self.mark_dirty();
+    }
+
+    // gives this text the width w, its value is wrapped again on it
+    fn rewrap(&mut self, w: u16) {
+        let [cx, cy] = [self.cx as usize, self.cy as usize];
+        let old = self.stride() as usize;
+        self.w = w;
+        let stride = self.stride() as usize;
+        if stride == old || old == 0 || stride == 0 {
+            self.fill();
+            self.clamp_cursor();
+            return;
+        }
+
+        // the line the cursor is on and how far into it
+        let lines = split_lines(&self.value.to_vec(), old);
+        let (mut row, mut at) = (0, None);
+        for (idx, (line, rows)) in lines.iter().enumerate() {
+            if at.is_none() && (row..row + rows).contains(&cy) {
+                at = Some((idx, ((cy - row) * old + cx).min(line.len())));
+            }
+            row += rows;
+        }
+        let at = at.unwrap_or((lines.len(), 0));
+
+        let mut value = vec![];
+        let mut cursor = None;
+        for (idx, (line, _)) in lines.iter().enumerate() {
+            if idx == at.0 {
+                cursor = Some(value.len() + at.1);
+            }
+            let rows = line.len().div_ceil(stride).max(1);
+            let start = value.len();
+            value.extend_from_slice(line);
+            value.resize(start + rows * stride, None);
+        }
+        let cursor = cursor.unwrap_or(value.len());
+        self.value = value.into();
+        self.fill();
+        self.set_cursor_index(cursor);
+    }
+
+    // the number shown in the gutter for each of the h rows from the row scroll,
+    // None for the rows that go on with the line above them and the rows under the value
+    pub(crate) fn line_heads(&self) -> Vec<Option<usize>> {
+        let stride = self.stride() as usize;
+        if stride == 0 {
+            return vec![None; self.h as usize];
+        }
+        let last = self.value.last_filled().map_or(0, |idx| idx / stride);
+        let last = last.max(self.cy as usize);
+        let mut heads = vec![];
+        let mut line = 0;
+        for row in 0..self.scroll as usize + self.h as usize {
+            let goes_on = row > 0 && self.value.get(row * stride - 1).flatten().is_some();
+            if !goes_on {
+                line += 1;
+            }
+            heads.push((row <= last && !goes_on).then_some(line));
+        }
+
+        heads.split_off(self.scroll as usize)
+    }
+
+    // the gutter cells of the h rows, w of them each, e.g. " 9" and "10"
+    pub(crate) fn gutter_rows(&self) -> Vec<String> {
+        let w = self.gutter.w as usize;
+        let rows = self.line_heads().into_iter().enumerate();
+
+        rows.map(|(row, head)| match (head, self.gutter.marker) {
+            (Some(line), _) => format!("{:>w$}", line),
+            (None, Some(marker)) if self.goes_on(row) => format!("{:>w$}", marker),
+            (None, _) => " ".repeat(w),
+        })
+        .collect()
+    }
+
+    // whether the shown row goes on with the line above it
+    fn goes_on(&self, row: usize) -> bool {
+        let [stride, row] = [self.stride() as usize, row + self.scroll as usize];
+        let last = self.value.last_filled().map_or(0, |idx| idx / stride);
+
+        row > 0 && row <= last && self.value.get(row * stride - 1).flatten().is_some()
+    }
+
+    // writes the gutter in front of the value rows, with its style
+    pub(crate) fn render_gutter(&self, s: &mut String, caps: &Capabilities) {
+        let w = self.gutter.w;
+        if w == 0 {
+            return;
+        }
+        *s += &caps.adapt_sgr(&self.gutter.style);
+        for (y, row) in self.gutter_rows().iter().enumerate() {
+            *s += &cup(self.ax0 - w, self.ay0 + y as u16);
+            *s += row;
+        }
+        *s += "\x1b[0m";
+    }
+
+    // puts the gutter cells in the lines of the decorated text, before the value that starts
+    // at the column x and the row y
+    pub(crate) fn put_gutter(&self, lines: &mut [Option<char>], wx: u16, [x, y]: [u16; 2]) {
+        let w = self.gutter.w;
+        for (row, cells) in self.gutter_rows().iter().enumerate() {
+            let start = ((y + row as u16) * wx + x - w) as usize;
+            for (idx, c) in cells.chars().enumerate() {
+                lines[start + idx] = (c != ' ').then_some(c);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod gutter {
+    use super::*;
+    use crate::space::border::Border;
+
+    // a multiline 6x3 input holding value
+    fn text(value: &str) -> Text {
+        let mut text = Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            1,
+            0,
+            6,
+            3,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        text.attributes.insert("multiline");
+        text.replace_value(value);
+
+        text
+    }
+
+    fn shown(text: &Text) -> Vec<String> {
+        (0..text.h)
+            .map(|y| {
+                let row = (0..text.w).map(|x| text.cell(x, y).unwrap_or('_'));
+                row.collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn numbered() {
+        let mut text = text("abcdefgh\nij");
+        assert_eq!(shown(&text), ["abcdef", "gh____", "ij____"]);
+        text.set_line_numbers(true).unwrap();
+        assert!(text.line_numbers());
+        assert_eq!(text.w, 5);
+        assert_eq!(text.ax0, 2);
+        assert_eq!(text.padding, Padding::inner(0, 0, 0, 1));
+        assert_eq!(text.decorate(), [6, 3]);
+        // wrapped again on the 5 columns left
+        assert_eq!(shown(&text), ["abcde", "fgh__", "ij___"]);
+        assert_eq!(text.gutter_rows(), ["1", " ", "2"]);
+        assert_eq!([text.cx, text.cy], [2, 2]);
+        assert!(text.attributes.contains("dirty"));
+
+        text.set_wrap_marker(Some('↪'));
+        assert_eq!(text.gutter_rows(), ["1", "↪", "2"]);
+
+        text.set_line_numbers(false).unwrap();
+        assert_eq!([text.w, text.ax0], [6, 1]);
+        assert_eq!(shown(&text), ["abcdef", "gh____", "ij____"]);
+        assert_eq!([text.cx, text.cy], [2, 2]);
+    }
+
+    #[test]
+    fn wide() {
+        let mut text = text("");
+        text.h = 12;
+        text.fill();
+        text.set_line_numbers(true).unwrap();
+        assert_eq!(text.w, 4);
+        text.insert_str("a\nb");
+        assert_eq!(text.gutter_rows()[..3], [" 1", " 2", "  "]);
+
+        let mut narrow = Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            1,
+            0,
+            1,
+            3,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        let err = narrow.set_line_numbers(true);
+        assert!(matches!(err, Err(SpaceError::AreaOutOfBounds { .. })));
+        assert!(!narrow.line_numbers());
+    }
+
+    #[test]
+    fn rendered() {
+        let mut text = text("ab");
+        text.set_line_numbers(true).unwrap();
+        let mut out = vec![];
+        text.render_value_with(&mut out, &Capabilities::default());
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.ends_with("\x1b[2m\x1b[0;1f1\x1b[1;1f \x1b[2;1f \x1b[0m\x1b[0m"));
+
+        let mut term = crate::components::Term::new(0, 8, 3);
+        let mut c =
+            crate::components::Container::new([0, 0], 0, 0, 8, 3, Border::None, Padding::None);
+        text.x0 = 0;
+        c.items.push(text);
+        term.containers.push(c);
+        let frame = term.frame_with(&Capabilities::default());
+        assert!(frame.starts_with("1ab"));
+    }
+}
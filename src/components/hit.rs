@@ -0,0 +1,268 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use super::{Container, Term, Text};
+use crate::render_pipeline;
+use crate::space::{border::Border, AreaIndex};
+
+// goes up every time a container or a text of a term is moved, resized, hidden or shown,
+// a hit index built before it went up is built again
+// the term, its containers and their texts share it, a component in no term has its own,
+// it takes the one of its term once the index of the term is built with it
+#[derive(Default, Clone)]
+pub(crate) struct Changes(RefCell<Rc<Cell<u64>>>);
+
+impl Changes {
+    // tells the hit index of the term that the geometry of some component changed
+    pub(crate) fn bump(&self) {
+        let count = self.0.borrow();
+        count.set(count.get() + 1);
+    }
+
+    fn count(&self) -> u64 {
+        self.0.borrow().get()
+    }
+
+    // counts the changes bumped here in the ones of term
+    fn share(&self, term: &Changes) {
+        if !Rc::ptr_eq(&self.0.borrow(), &term.0.borrow()) {
+            *self.0.borrow_mut() = term.0.borrow().clone();
+        }
+    }
+}
+
+// the changes are worked out from the components, like the other caches
+impl PartialEq for Changes {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for Changes {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("Changes")
+    }
+}
+
+// what a hit index was built from, the changes, the size of the term
+// and the numbers of containers and texts
+type Built = (u64, [u16; 2], usize, usize);
+
+// the areas the texts of the term are hit in, see Term::hit_test
+// kept with what they were built from, the ids are in the order the texts are drawn in
+#[derive(Default, Clone)]
+pub(crate) struct Hits(RefCell<Option<(Built, Vec<[u8; 3]>, AreaIndex)>>);
+
+// the index is worked out from the term, like the other caches
+impl PartialEq for Hits {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for Hits {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("Hits")
+    }
+}
+
+// the cells t is hit in, its area, border included, clipped by the value area of c
+fn hit_area(c: &Container, t: &Text) -> Option<[u16; 4]> {
+    let [_, cpol, cpot, _, _, cpil, cpit, _] = render_pipeline::spread_padding(&c.padding);
+    let cb = if let Border::None = c.border { 0 } else { 1 };
+    let [vx, vy] = [c.x0 + cpol + cb + cpil, c.y0 + cpot + cb + cpit];
+
+    let [tpor, tpol, tpot, tpob, ..] = render_pipeline::spread_padding(&t.padding);
+    let [tw, th] = t.decorate();
+    let [tx, ty] = [vx + t.x0 + tpol, vy + t.y0 + tpot];
+    let [tw, th] = [tw - tpol - tpor, th - tpot - tpob];
+
+    let [x0, y0] = [tx.max(vx), ty.max(vy)];
+    let [x1, y1] = [(tx + tw).min(vx + c.w), (ty + th).min(vy + c.h)];
+
+    (x0 < x1 && y0 < y1).then_some([x0, y0, x1 - x0, y1 - y0])
+}
+
+impl Term {
+    /// returns the id of the topmost text whose area, border included, holds the [x, y] cell,
+    /// the one of the last container that is drawn over the others and its last text
+    /// only the texts near the cell are looked at, the index of their areas is built again
+    /// once a component was added, removed, moved, resized, hidden or shown, or the term resized
+    /// the coordinates are 0 based, like the ones of MouseEvent
+    pub fn hit_test(&self, x: u16, y: u16) -> Option<[u8; 3]> {
+        self.with_hits(|ids, index| {
+            let top = index.overlapping([x, y, 1, 1]).into_iter().max()?;
+
+            Some(ids[top])
+        })
+    }
+
+    /// the ids of the texts whose areas, as Term::hit_test has them, take some of the
+    /// [x, y, w, h] rect, in the order they are drawn, the topmost last
+    pub fn query_rect(&self, rect: [u16; 4]) -> Vec<[u8; 3]> {
+        if rect[2] == 0 || rect[3] == 0 {
+            return vec![];
+        }
+
+        self.with_hits(|ids, index| {
+            let hit = index.overlapping(rect).into_iter();
+
+            hit.map(|idx| ids[idx]).collect()
+        })
+    }
+
+    // calls f with the ids and the index of the hit areas of the texts, built again first
+    // when the term changed since they were
+    fn with_hits<T>(&self, f: impl FnOnce(&[[u8; 3]], &AreaIndex) -> T) -> T {
+        let texts = self.containers.iter().map(|c| c.items.len()).sum();
+        let built = (
+            self.changes.count(),
+            [self.w, self.h],
+            self.containers.len(),
+            texts,
+        );
+        let mut kept = self.hits.0.borrow_mut();
+        if kept.as_ref().map_or(true, |(at, ..)| *at != built) {
+            let mut ids = vec![];
+            let mut index = AreaIndex::new([self.w, self.h]);
+            for c in &self.containers {
+                c.changes.share(&self.changes);
+                c.items.iter().for_each(|t| t.changes.share(&self.changes));
+            }
+            let shown = self.containers.iter().filter(|c| !c.is_hidden());
+            for c in shown {
+                for t in c.items.iter().filter(|t| !t.is_hidden()) {
+                    let Some(area) = hit_area(c, t) else {
+                        continue;
+                    };
+                    ids.push(t.id);
+                    index.push(Some((0, area)));
+                }
+            }
+            *kept = Some((built, ids, index));
+        }
+        let (_, ids, index) = kept.as_ref().unwrap();
+
+        f(ids, index)
+    }
+}
+
+#[cfg(test)]
+mod hit {
+    use super::*;
+    use crate::space::padding::Padding;
+
+    // the topmost text holding [x, y], every text checked in drawn order
+    fn brute(term: &Term, x: u16, y: u16) -> Option<[u8; 3]> {
+        let shown = term.containers.iter().filter(|c| !c.is_hidden());
+        let texts = shown.flat_map(|c| {
+            let texts = c.items.iter().filter(|t| !t.is_hidden());
+            texts.filter_map(move |t| Some((t.id, hit_area(c, t)?)))
+        });
+
+        texts
+            .filter(|(_, [ax, ay, aw, ah])| {
+                (*ax..ax + aw).contains(&x) && (*ay..ay + ah).contains(&y)
+            })
+            .last()
+            .map(|(id, _)| id)
+    }
+
+    // a 200x60 term of 8 bordered containers of 50 bordered 3x1 texts each, 400 texts
+    // in all, the ones of the last container are drawn over the ones of the first
+    fn stress() -> Term {
+        let mut term = Term::new(0, 200, 60);
+        for n in 0..8u16 {
+            let [x, y] = [n % 4 * 45, n / 4 * 22];
+            let mut c = Container::new(
+                [0, n as u8],
+                x,
+                y,
+                60,
+                25,
+                Border::Uniform('#'),
+                Padding::None,
+            );
+            for idx in 0..50u16 {
+                let [tx, ty] = [idx % 10 * 6, idx / 10 * 5];
+                let t = Text::nonedit(
+                    [0, n as u8, idx as u8],
+                    tx,
+                    ty,
+                    0,
+                    0,
+                    3,
+                    1,
+                    &[],
+                    Border::Uniform('+'),
+                    Padding::None,
+                );
+                c.items.push(t);
+            }
+            c.invalidate();
+            term.containers.push(c);
+        }
+
+        term
+    }
+
+    #[test]
+    fn stressed() {
+        let mut term = stress();
+        let cells = |term: &Term| {
+            for [x, y] in (0..term.w).flat_map(|x| (0..term.h).map(move |y| [x, y])) {
+                assert_eq!(term.hit_test(x, y), brute(term, x, y), "{x} {y}");
+            }
+        };
+        cells(&term);
+        assert_eq!(term.hit_test(1, 1), Some([0, 0, 0]));
+
+        // moved, hidden and removed components and a smaller term
+        term.containers[5].x0 = 3;
+        term.containers[5].invalidate();
+        term.containers[2].hide();
+        term.containers[0].items[0].hide();
+        term.containers[7].items.truncate(20);
+        cells(&term);
+        assert_eq!(term.hit_test(1, 1), None);
+        term.resize(120, 40);
+        cells(&term);
+        term.containers[2].show();
+        cells(&term);
+    }
+
+    #[test]
+    fn rect() {
+        let term = stress();
+        // the texts of a container overlapping the next one's come first
+        let ids = term.query_rect([45, 0, 8, 4]);
+        assert_eq!(ids, vec![[0, 0, 7], [0, 0, 8], [0, 1, 0], [0, 1, 1]]);
+        assert!(term.query_rect([1, 1, 0, 5]).is_empty());
+        assert_eq!(term.query_rect([0, 0, 200, 60]).len(), 400);
+    }
+
+    #[test]
+    fn terms() {
+        let [mut a, mut b] = [stress(), stress()];
+        let built = |term: &Term| term.hits.0.borrow().as_ref().map(|(at, ..)| *at);
+        assert_eq!(a.hit_test(1, 1), Some([0, 0, 0]));
+        assert_eq!(b.hit_test(1, 1), Some([0, 0, 0]));
+
+        // the changes to the components of b leave the index of a alone
+        let kept = built(&a);
+        b.containers[0].hide();
+        assert_eq!(a.hit_test(1, 1), Some([0, 0, 0]));
+        assert_eq!(built(&a), kept);
+        assert_eq!(b.hit_test(1, 1), None);
+
+        // a container of b moved over to a counts its changes in a
+        a.containers.remove(0);
+        assert_eq!(a.hit_test(1, 1), None);
+        let mut c = b.containers.remove(0);
+        c.show();
+        a.containers.insert(0, c);
+        assert_eq!(a.hit_test(1, 1), Some([0, 0, 0]));
+        a.containers[0].hide();
+        assert_eq!(a.hit_test(1, 1), None);
+    }
+}
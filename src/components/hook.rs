@@ -0,0 +1,39 @@
+use std::ops::{Deref, DerefMut};
+
+// the callbacks a term was given through its on_* methods, with whatever they keep track of
+// the callbacks may hold state they change, so they aren't shared with a clone, the clone gets
+// none, and they are left out of the comparisons of terms
+#[derive(Default)]
+pub(crate) struct Hook<T: Default>(pub(crate) T);
+
+impl<T: Default> Clone for Hook<T> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<T: Default> PartialEq for Hook<T> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<T: Default> std::fmt::Debug for Hook<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Hook")
+    }
+}
+
+impl<T: Default> Deref for Hook<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Default> DerefMut for Hook<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
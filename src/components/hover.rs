@@ -0,0 +1,178 @@
+use crate::inputs::mouse::MouseEvent;
+use crate::themes::Style;
+
+use super::{Hook, Term, Text};
+
+// the text under the pointer, the cell the last motion report was in
+// and what Term::on_hover_change was given
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Hover {
+    id: Option<[u8; 3]>,
+    cell: Option<[u16; 2]>,
+    hook: Hook<Option<Box<dyn FnMut(Option<[u8; 3]>, Option<[u8; 3]>)>>>,
+}
+
+// left out of the comparisons of terms
+impl PartialEq for Hover {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Text {
+    /// gives this text the style its value is rendered in while the pointer is over it,
+    /// it wins over the theme's "hover" style, the focus and disabled styles win over it,
+    /// it is used from the next time the pointer gets over this text
+    pub fn set_hover_style(&mut self, style: Option<Style>) {
+        self.hover_style = style;
+    }
+}
+
+impl Term {
+    /// the id of the text under the pointer, as of the last motion report
+    /// the motion is only reported once the any motion mouse tracking is on
+    pub fn hovered(&self) -> Option<[u8; 3]> {
+        self.hover.id
+    }
+
+    /// calls f with the previously hovered text's id and the newly hovered one's every time
+    /// the pointer moves from a text to another one, e.g. to show a tooltip in the status bar,
+    /// replaces the previous callback
+    pub fn on_hover_change(&mut self, f: impl FnMut(Option<[u8; 3]>, Option<[u8; 3]>) + 'static) {
+        self.hover.hook = Hook(Some(Box::new(f)));
+    }
+
+    // hit tests a motion report, the motion reports inside the same cell as the last one are
+    // left as they are, the styles change and the hook is called only when another text is
+    // hovered
+    pub(crate) fn hover_mouse(&mut self, event: &MouseEvent) -> Option<[u8; 3]> {
+        let cell = [event.column, event.row];
+        if self.hover.cell == Some(cell) {
+            return self.hover.id;
+        }
+        self.hover.cell = Some(cell);
+        let id = self
+            .component_at(event.column, event.row)
+            .filter(|id| self.reachable(id));
+        if id != self.hover.id {
+            self.hover_to(id);
+        }
+
+        id
+    }
+
+    // moves the hover to the text with id, the texts whose style changed are marked dirty
+    fn hover_to(&mut self, id: Option<[u8; 3]>) {
+        let previous = std::mem::replace(&mut self.hover.id, id);
+        if let Some(text) = previous.and_then(|id| self.text_mut(&id)) {
+            if text.hover.take().is_some() {
+                text.mark_dirty();
+            }
+        }
+        let theme = self.theme_style("hover");
+        if let Some(text) = id.and_then(|id| self.text_mut(&id)) {
+            text.hover = text.hover_style.as_ref().map(Style::style).or(theme);
+            if text.hover.is_some() {
+                text.mark_dirty();
+            }
+        }
+
+        if let Some(mut hook) = self.hover.hook.take() {
+            hook(previous, id);
+            self.hover.hook.get_or_insert(hook);
+        }
+    }
+
+    // the pointer is in another place once the components or the term moved
+    pub(crate) fn forget_hover_cell(&mut self) {
+        self.hover.cell = None;
+    }
+}
+
+#[cfg(test)]
+mod hover {
+    use super::*;
+    use crate::components::Container;
+    use crate::inputs::keyboard::Modifiers;
+    use crate::inputs::mouse::{MouseButton, MouseKind};
+    use crate::space::{border::Border, padding::Padding};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // a container at [0, 0] holding 2 4x1 inputs next to each other
+    fn term() -> Term {
+        let mut term = Term::new(0, 30, 8);
+        let mut c = Container::new([0, 0], 0, 0, 12, 4, Border::None, Padding::None);
+        for (idx, x) in [(0, 0), (1, 5)] {
+            let (border, padding) = (Border::None, Padding::None);
+            c.items.push(Text::input(
+                [0, 0, idx],
+                x,
+                0,
+                0,
+                0,
+                4,
+                1,
+                &[],
+                border,
+                padding,
+            ));
+        }
+        term.containers.push(c);
+
+        term
+    }
+
+    fn moved(term: &mut Term, column: u16, row: u16) -> Option<[u8; 3]> {
+        term.mouse(&MouseEvent {
+            kind: MouseKind::Move,
+            button: MouseButton::None,
+            column,
+            row,
+            modifiers: Modifiers(0),
+        })
+    }
+
+    #[test]
+    fn hovered() {
+        let mut term = term();
+        let changes = Rc::new(RefCell::new(vec![]));
+        let seen = changes.clone();
+        term.on_hover_change(move |from, to| seen.borrow_mut().push((from, to)));
+        term.text_mut(&[0, 0, 1])
+            .unwrap()
+            .set_hover_style(Some(Style::new().reverse()));
+
+        assert_eq!(moved(&mut term, 1, 0), Some([0, 0, 0]));
+        // no theme style and no style of its own, it is not drawn again
+        assert!(!term.is_dirty());
+        assert_eq!(term.hovered(), Some([0, 0, 0]));
+        moved(&mut term, 2, 0);
+        assert_eq!(changes.borrow().len(), 1);
+
+        assert_eq!(moved(&mut term, 6, 0), Some([0, 0, 1]));
+        let text = term.text_ref(&[0, 0, 1]).unwrap();
+        assert_eq!(text.hover.as_deref(), Some("\x1b[7m"));
+        assert!(text.attributes.contains("dirty"));
+        term.text_mut(&[0, 0, 1])
+            .unwrap()
+            .attributes
+            .remove("dirty");
+
+        term.theme.set("hover", &Style::new().bold());
+        assert_eq!(moved(&mut term, 1, 3), None);
+        assert!(term.is_dirty());
+        assert_eq!(term.text_ref(&[0, 0, 1]).unwrap().hover, None);
+        moved(&mut term, 0, 0);
+        let text = term.text_ref(&[0, 0, 0]).unwrap();
+        assert_eq!(text.hover.as_deref(), Some("\x1b[1m"));
+
+        let expected = vec![
+            (None, Some([0, 0, 0])),
+            (Some([0, 0, 0]), Some([0, 0, 1])),
+            (Some([0, 0, 1]), None),
+            (None, Some([0, 0, 0])),
+        ];
+        assert_eq!(*changes.borrow(), expected);
+    }
+}
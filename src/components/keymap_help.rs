@@ -0,0 +1,250 @@
+use crate::inputs::keyboard::{Char, KbdEvent, CC, SHIFT};
+use crate::inputs::keymap::{key_label, InputAction, Keymap};
+use crate::space::{border::Border, Area};
+
+use super::{ContainerMeta, NonEditMeta, PopupHandle, Term};
+
+// the gap between the key and the description columns
+const GAP: usize = 2;
+
+impl Term {
+    /// the bindings the focused text's keys go through, the focused text's keymap first,
+    /// then this Term's keymap and Keymap::defaults, a key bound in more than one keymap
+    /// is only given for the first one, and the keys bound to NoOp are left out
+    /// each binding comes with its category and description, see Keymap::describe,
+    /// a binding with none is in the "other" category and described by its action's name
+    pub fn active_bindings(&self) -> Vec<(KbdEvent, InputAction, String, String)> {
+        let focused = self.focused.and_then(|id| self.text_ref(&id));
+        let mut layers = vec![];
+        if let Some(text) = focused {
+            layers.push(&text.keymap);
+        }
+        layers.extend([&self.keymap, Keymap::defaults()]);
+
+        let mut bindings: Vec<(KbdEvent, InputAction, String, String)> = vec![];
+        for keymap in layers {
+            for (key, action) in keymap.iter() {
+                if bindings.iter().any(|(k, ..)| k.same_key(key)) {
+                    continue;
+                }
+                let (category, description) = match keymap.help(key) {
+                    Some(help) => (help.category.clone(), help.description.clone()),
+                    None => ("other".to_string(), action.name().to_string()),
+                };
+                bindings.push((key.clone(), action.clone(), category, description));
+            }
+        }
+        bindings.retain(|(_, action, ..)| *action != InputAction::NoOp);
+
+        bindings
+    }
+
+    /// opens a popup listing the active bindings, see Term::active_bindings, grouped by
+    /// category in the order the categories first come in, a key and its description a line
+    /// the popup is centered and as large as the list, up to the size of the term, the arrows,
+    /// PageUp, PageDown, Home and End scroll a list that doesn't fit, Esc or ? close it
+    /// while it is open no other component gets the keys, see Term::popup
+    /// returns the popup that is already open if there is one
+    pub fn show_keymap_help(&mut self) -> PopupHandle {
+        if let Some(id) = self.keymap_help() {
+            return PopupHandle::new([id[0], id[1]]);
+        }
+        let lines = help_lines(&self.active_bindings());
+        let w = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16;
+        let h = lines.len() as u16;
+        // the border takes a cell on each side
+        let area = Area::Values {
+            w: w.clamp(1, self.w.saturating_sub(2).max(1)),
+            h: h.clamp(1, self.h.saturating_sub(2).max(1)),
+        };
+        let popup = self.popup(
+            ContainerMeta::new()
+                .area(area.clone())
+                .border(Border::Uniform('#')),
+        );
+        let meta = NonEditMeta::new().parent(popup.id()).area(area);
+        let id = self.nonedit_from_meta(&meta).unwrap();
+        let text = self.nonedit_mut(&id).unwrap();
+        text.attributes.insert("multiline");
+        for (idx, line) in lines.iter().enumerate() {
+            match idx {
+                0 => text.append_str(line),
+                _ => text.append_line(line),
+            }
+        }
+        self.keymap_help = Some(id);
+
+        popup
+    }
+
+    // the id of the text of the open keymap help, while it is the topmost popup
+    fn keymap_help(&self) -> Option<[u8; 3]> {
+        self.keymap_help
+            .filter(|id| self.top_popup() == Some([id[0], id[1]]))
+    }
+
+    // scrolls and closes the keymap help, every other key is swallowed while it is open
+    // returns None if it is not open
+    pub(crate) fn keymap_help_key(&mut self, event: &KbdEvent) -> Option<bool> {
+        let id = self.keymap_help()?;
+        // a ? may come with the shift it was typed with
+        let close = match event.char {
+            Char::CC(CC::ESC) => event.modifiers.is_empty(),
+            Char::Char('?') => event.modifiers.0 & !SHIFT == 0,
+            _ => false,
+        };
+        if close {
+            self.keymap_help = None;
+            return Some(self.close_popup(&[id[0], id[1]]));
+        }
+        if !event.modifiers.is_empty() {
+            return Some(false);
+        }
+        let text = self.nonedit_mut(&id)?;
+        let page = text.h.max(1) as i16;
+        let lines = match event.char {
+            Char::CC(CC::Up) => -1,
+            Char::CC(CC::Down) => 1,
+            Char::CC(CC::PageUp) => -page,
+            Char::CC(CC::PageDown) => page,
+            Char::CC(CC::Home) => i16::MIN,
+            Char::CC(CC::End) => i16::MAX,
+            _ => return Some(false),
+        };
+
+        Some(text.scroll_by(lines))
+    }
+}
+
+// the lines of the help, a line with the name of each category, then its bindings,
+// the descriptions start in the same column, a blank line between the categories
+fn help_lines(bindings: &[(KbdEvent, InputAction, String, String)]) -> Vec<String> {
+    let labels = bindings
+        .iter()
+        .map(|(key, ..)| key_label(key))
+        .collect::<Vec<_>>();
+    let kw = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let mut categories: Vec<&str> = vec![];
+    for (_, _, category, _) in bindings {
+        if !categories.contains(&category.as_str()) {
+            categories.push(category);
+        }
+    }
+
+    let mut lines = vec![];
+    for category in categories {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(category.to_string());
+        let entries = bindings.iter().zip(&labels);
+        for ((_, _, c, description), label) in entries.filter(|((_, _, c, _), _)| c == category) {
+            let pad = kw - label.chars().count() + GAP;
+            lines.push(format!("{}{}{}", label, " ".repeat(pad), description));
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod keymap_help {
+    use super::*;
+    use crate::components::{Container, Text};
+    use crate::inputs::keyboard::{KeyKind, Modifiers, CONTROL};
+    use crate::space::padding::Padding;
+
+    fn key(char: Char, modifiers: u8) -> KbdEvent {
+        KbdEvent {
+            char,
+            modifiers: Modifiers(modifiers),
+            kind: KeyKind::Press,
+        }
+    }
+
+    // an input with a keymap of its own, in a term with one too
+    fn app(h: u16) -> Term {
+        let mut term = Term::new(0, 60, h);
+        let mut c = Container::new([0, 0], 0, 0, 60, 1, Border::None, Padding::None);
+        let mut input = Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            60,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        input
+            .keymap
+            .bind_described(
+                key(Char::Char('s'), CONTROL),
+                InputAction::Named("save".into()),
+                "file",
+                "save the file",
+            )
+            .unwrap();
+        input
+            .keymap
+            .bind(key(Char::CC(CC::BS), 0), InputAction::NoOp)
+            .unwrap();
+        c.items.push(input);
+        term.containers.push(c);
+        term.keymap
+            .bind_described(
+                key(Char::Char('q'), CONTROL),
+                InputAction::Named("quit".into()),
+                "file",
+                "quit",
+            )
+            .unwrap();
+        _ = term.focus(&[0, 0, 0]);
+
+        term
+    }
+
+    #[test]
+    fn bindings() {
+        let term = app(30);
+        let bindings = term.active_bindings();
+        assert_eq!(bindings[0].3, "save the file");
+        assert_eq!(bindings[1].3, "quit");
+        // the input's NoOp hides the default backspace
+        assert!(bindings
+            .iter()
+            .all(|(k, ..)| !k.same_key(&key(Char::CC(CC::BS), 0))));
+
+        let lines = help_lines(&bindings);
+        assert_eq!(lines[..3], ["file", "Ctrl+s     save the file", "Ctrl+q     quit"]);
+        assert_eq!(lines[3..5], ["", "editing"]);
+        assert!(lines.contains(&"Shift+Tab  focus the previous input".to_string()));
+    }
+
+    #[test]
+    fn overlay() {
+        let mut term = app(10);
+        let popup = term.show_keymap_help();
+        assert_eq!(term.show_keymap_help(), popup);
+        let id = term.keymap_help().unwrap();
+        let text = term.nonedit_ref(&id).unwrap();
+        // 8 rows inside the border, the list is longer
+        assert_eq!(text.h, 8);
+        assert!(text.max_scroll() > 0);
+        assert!(text.value_string().starts_with("file\nCtrl+s"));
+
+        // the input behind gets nothing
+        term.key(&key(Char::Char('x'), 0));
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().value_string(), "");
+        term.key(&key(Char::CC(CC::PageDown), 0));
+        assert_eq!(term.nonedit_ref(&id).unwrap().scroll, 8);
+        term.key(&key(Char::CC(CC::Home), 0));
+        assert_eq!(term.nonedit_ref(&id).unwrap().scroll, 0);
+
+        term.key(&key(Char::Char('?'), 0));
+        assert!(!term.is_popup_open());
+        assert_eq!(term.focused, Some([0, 0, 0]));
+    }
+}
@@ -0,0 +1,309 @@
+use crate::inputs::keyboard::{Char, KbdEvent, CC};
+use crate::space::Overflow;
+use crate::themes::Style;
+
+use super::tree::Node;
+use super::Text;
+
+/// a scrollable list of items with one selected item, shown in a Text, see Text::set_list
+/// a focused list moves its selection with Up, Down, Home, End, PageUp and PageDown
+/// and is activated with Enter, see Term::on_select and Term::on_activate
+///
+/// an item takes one row of the text, the rows past the text's height are scrolled to
+/// items wider than the text are cut, with an ellipsis if the text's overflow is Ellipsis
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct List {
+    items: Vec<String>,
+    selected: usize,
+    // the index of the item in the first row
+    offset: usize,
+    // the style escape sequence of the selected row
+    highlight: String,
+}
+
+impl List {
+    /// makes a new List of items, the first item is selected
+    /// the selected row is shown in reverse video unless told otherwise, see List::highlight
+    pub fn new(items: Vec<String>) -> Self {
+        Self {
+            items,
+            selected: 0,
+            offset: 0,
+            highlight: "\x1b[7m".to_string(),
+        }
+    }
+
+    /// changes the style of the selected row
+    pub fn highlight(&mut self, style: &Style) {
+        self.highlight = style.style();
+    }
+
+    /// the items
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    /// the index of the selected item
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// the selected item, None if the list is empty
+    pub fn selected_item(&self) -> Option<&str> {
+        self.items.get(self.selected).map(String::as_str)
+    }
+
+    /// the index of the item shown in the first row
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    // the row of the text the selected item is shown in, if it is shown
+    pub(crate) fn highlighted(&self, h: u16) -> Option<(u16, &str)> {
+        let row = self.selected.checked_sub(self.offset)?;
+        if row >= h as usize || self.items.is_empty() {
+            return None;
+        }
+
+        Some((row as u16, &self.highlight))
+    }
+
+    // selects the item at idx, the last one if idx is past it,
+    // and scrolls the h rows just enough for it to be shown
+    fn select(&mut self, idx: usize, h: u16) {
+        self.selected = idx.min(self.items.len().saturating_sub(1));
+        self.offset = shown_from(self.selected, self.offset, h as usize);
+    }
+
+    // the value cells of the visible items, one item per row of w cells
+    fn cells(&self, w: u16, h: u16, overflow: Overflow) -> Vec<Option<char>> {
+        let w = w as usize;
+        let mut cells = vec![None; w * h as usize];
+        let visible = self.items.iter().skip(self.offset).take(h as usize);
+        for (row, item) in visible.enumerate() {
+            let line = &mut cells[row * w..(row + 1) * w];
+            let mut chars = item.chars().collect::<Vec<char>>();
+            if let (Overflow::Ellipsis, true) = (overflow, chars.len() > w && w > 0) {
+                chars.truncate(w - 1);
+                chars.push('…');
+            }
+            line.iter_mut()
+                .zip(chars)
+                .for_each(|(cell, c)| *cell = Some(c));
+        }
+
+        cells
+    }
+}
+
+// lists
+impl Text {
+    /// shows list in this text, replacing its value
+    pub fn set_list(&mut self, list: List) {
+        self.list = Some(list);
+        self.set_focusable(true);
+        self.sync_list();
+    }
+
+    /// the list this text shows, if it shows one
+    pub fn list(&self) -> Option<&List> {
+        self.list.as_ref()
+    }
+
+    /// replaces the items of the list this text shows, the selection stays where it was if it can
+    /// returns false if this text shows no list
+    pub fn set_items(&mut self, items: Vec<String>) -> bool {
+        let Some(list) = &mut self.list else {
+            return false;
+        };
+        list.items = items;
+        list.select(list.selected, self.h);
+        self.sync_list();
+
+        true
+    }
+
+    /// selects the item at idx of the list this text shows, the last item if idx is past it
+    /// the list scrolls for the item to be shown
+    /// returns whether the selection changed
+    pub fn select(&mut self, idx: usize) -> bool {
+        let Some(list) = &mut self.list else {
+            return false;
+        };
+        let before = list.selected;
+        list.select(idx, self.h);
+        let changed = list.selected != before;
+        self.sync_list();
+
+        changed
+    }
+
+    // writes the visible items to the value
+    fn sync_list(&mut self) {
+        let Some(list) = &self.list else {
+            return;
+        };
+        self.value = list.cells(self.w, self.h, self.overflow).into();
+        self.scroll = 0;
+        self.mark_dirty();
+    }
+
+    // moves the selection of the list this text shows for a key press
+    // returns whether the selection changed, or None if the key is not a list key
+    pub(crate) fn list_key(&mut self, event: &KbdEvent) -> Option<bool> {
+        let list = self.list.as_ref()?;
+        let idx = moved_to(event, list.selected, self.h as usize)?;
+
+        Some(self.select(idx))
+    }
+}
+
+// the first row to show out of rows rows, so that the selected one is shown
+// the rows scroll no more than they have to
+pub(crate) fn shown_from(selected: usize, offset: usize, rows: usize) -> usize {
+    let rows = rows.max(1);
+    if selected < offset {
+        selected
+    } else if selected >= offset + rows {
+        selected + 1 - rows
+    } else {
+        offset
+    }
+}
+
+// where a selection key moves the selection to, page rows are shown at a time
+// the index may be past the last row
+pub(crate) fn moved_to(event: &KbdEvent, selected: usize, page: usize) -> Option<usize> {
+    if !event.modifiers.is_empty() {
+        return None;
+    }
+    let page = page.max(1);
+
+    Some(match event.char {
+        Char::CC(CC::Up) => selected.saturating_sub(1),
+        Char::CC(CC::Down) => selected + 1,
+        Char::CC(CC::PageUp) => selected.saturating_sub(page),
+        Char::CC(CC::PageDown) => selected + page,
+        Char::CC(CC::Home) => 0,
+        Char::CC(CC::End) => usize::MAX,
+        _ => return None,
+    })
+}
+
+/// what Term::on_select, Term::on_activate, Term::on_row_select, Term::on_change
+/// Term::on_toggle and Term::on_expand were given
+#[derive(Default)]
+pub(crate) struct ListHooks {
+    pub(crate) select: Option<Box<dyn FnMut([u8; 3], usize, &str)>>,
+    pub(crate) activate: Option<Box<dyn FnMut([u8; 3], usize, &str)>>,
+    pub(crate) row: Option<Box<dyn FnMut([u8; 3], usize)>>,
+    pub(crate) change: Option<Box<dyn FnMut([u8; 3], usize, &str)>>,
+    pub(crate) toggle: Option<Box<dyn FnMut([u8; 3], bool)>>,
+    pub(crate) expand: Option<Box<dyn FnMut([u8; 3], &[usize], &str) -> Vec<Node>>>,
+}
+
+#[cfg(test)]
+mod list {
+    use super::*;
+    use crate::components::{Container, Term};
+    use crate::inputs::keyboard::{KeyKind, Modifiers};
+    use crate::space::{border::Border, padding::Padding};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // a 6x3 nonedit showing 5 items
+    fn term() -> Term {
+        let mut term = Term::new(0, 30, 8);
+        let mut c = Container::new([0, 0], 0, 0, 20, 4, Border::None, Padding::None);
+        let mut text = Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            0,
+            0,
+            6,
+            3,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        let items = ["one", "two", "three", "four", "a long item"];
+        text.set_list(List::new(items.map(String::from).to_vec()));
+        c.items.push(text);
+        term.containers.push(c);
+        term.focused = Some([0, 0, 1]);
+
+        term
+    }
+
+    fn cc(cc: CC) -> KbdEvent {
+        KbdEvent {
+            char: Char::CC(cc),
+            modifiers: Modifiers(0),
+            kind: KeyKind::Press,
+        }
+    }
+
+    fn rows(text: &Text) -> Vec<String> {
+        text.value
+            .to_vec()
+            .chunks(text.w as usize)
+            .map(|row| row.iter().flatten().collect())
+            .collect()
+    }
+
+    #[test]
+    fn scrolling() {
+        let mut term = term();
+        let selected = Rc::new(RefCell::new(vec![]));
+        let seen = selected.clone();
+        term.on_select(move |id, idx, item| seen.borrow_mut().push((id, idx, item.to_string())));
+
+        for _ in 0..3 {
+            term.key(&cc(CC::Down));
+        }
+        let text = term.nonedit_ref(&[0, 0, 1]).unwrap();
+        let list = text.list().unwrap();
+        assert_eq!([list.selected(), list.offset()], [3, 1]);
+        assert_eq!(rows(text), ["two", "three", "four"]);
+        assert_eq!(list.highlighted(text.h).unwrap().0, 2);
+        assert_eq!(
+            selected.borrow().last().unwrap(),
+            &([0, 0, 1], 3, "four".into())
+        );
+
+        term.key(&cc(CC::End));
+        let text = term.nonedit_ref(&[0, 0, 1]).unwrap();
+        // the long item is cut at the text's width
+        assert_eq!(rows(text)[2], "a long");
+        // the selection doesn't go past the last item, and reports nothing when it doesn't move
+        term.key(&cc(CC::Down));
+        assert_eq!(selected.borrow().len(), 4);
+
+        term.key(&cc(CC::Home));
+        let text = term.nonedit_ref(&[0, 0, 1]).unwrap();
+        assert_eq!([text.list().unwrap().offset(), 0], [0, 0]);
+        assert_eq!(rows(text)[0], "one");
+    }
+
+    #[test]
+    fn activate_and_overflow() {
+        let mut term = term();
+        let activated = Rc::new(RefCell::new(None));
+        let seen = activated.clone();
+        term.on_activate(move |_, idx, item| *seen.borrow_mut() = Some((idx, item.to_string())));
+
+        term.key(&cc(CC::Down));
+        term.key(&cc(CC::CR));
+        assert_eq!(*activated.borrow(), Some((1, "two".into())));
+
+        let text = term.nonedit_mut(&[0, 0, 1]).unwrap();
+        text.overflow(Overflow::Ellipsis);
+        text.select(4);
+        assert_eq!(rows(text)[2], "a lon…");
+
+        text.set_items(vec!["x".into()]);
+        assert_eq!(text.list().unwrap().selected_item(), Some("x"));
+        assert_eq!(rows(text), ["x", "", ""]);
+    }
+}
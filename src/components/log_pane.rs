@@ -0,0 +1,456 @@
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::diagnostics::Level;
+use crate::themes::Style;
+
+use super::{Term, Text};
+
+/// how many lines a LogPane keeps, unless told otherwise
+pub const DEFAULT_LOG_CAPACITY: usize = 1000;
+
+// the width of the level names, and of the prefix with the space after it
+const LEVEL_W: usize = 5;
+const PREFIX_W: usize = LEVEL_W + 1;
+
+/// a logged line, as a LogPane keeps it
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub level: Level,
+    /// where the line was logged from, e.g. the module path given by the log crate
+    pub target: String,
+    pub message: String,
+}
+
+// what the queue of a log pane shares between the threads that log and the event loop
+#[derive(Debug)]
+struct Shared {
+    lines: Mutex<VecDeque<LogLine>>,
+    capacity: AtomicUsize,
+    // the least level that is kept, as its index in LEVELS
+    level: AtomicU8,
+}
+
+const LEVELS: [Level; 4] = [Level::Debug, Level::Info, Level::Warn, Level::Error];
+
+fn level_idx(level: Level) -> u8 {
+    LEVELS.iter().position(|l| *l == level).unwrap() as u8
+}
+
+/// the sending end of a LogPane, it can be cloned and moved to other threads
+/// the lines wait in the queue until the event loop drains them into the pane,
+/// see Term::drain_logs, past the pane's capacity the oldest ones are dropped
+///
+/// with the log feature the queue is a log::Log, see LogQueue::install
+#[derive(Debug, Clone)]
+pub struct LogQueue(Arc<Shared>);
+
+// two queues are the same when they send to the same pane
+impl PartialEq for LogQueue {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl LogQueue {
+    fn new() -> Self {
+        Self(Arc::new(Shared {
+            lines: Mutex::new(VecDeque::new()),
+            capacity: AtomicUsize::new(DEFAULT_LOG_CAPACITY),
+            level: AtomicU8::new(0),
+        }))
+    }
+
+    /// whether lines of level are kept, see LogPane::level
+    pub fn enabled(&self, level: Level) -> bool {
+        level_idx(level) >= self.0.level.load(Ordering::Relaxed)
+    }
+
+    /// queues a line for the pane, lines under the pane's level are dropped
+    pub fn push(&self, level: Level, target: &str, message: &str) {
+        if !self.enabled(level) {
+            return;
+        }
+        let mut lines = self.0.lines.lock().unwrap_or_else(|e| e.into_inner());
+        lines.push_back(LogLine {
+            level,
+            target: target.to_string(),
+            message: message.to_string(),
+        });
+        let capacity = self.0.capacity.load(Ordering::Relaxed);
+        while lines.len() > capacity {
+            lines.pop_front();
+        }
+    }
+
+    // takes the queued lines out
+    fn take(&self) -> VecDeque<LogLine> {
+        std::mem::take(&mut *self.0.lines.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// the lines of a log, shown in a Text with a prefix in the color of their level,
+/// see Text::set_log_pane
+/// the text follows the last line as lines come in, unless it was scrolled up from it,
+/// e.g. with the mouse wheel, then it stays on the lines it shows until it is scrolled back down
+/// a line takes as many rows as it has lines, the rows wider than the text are cut
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogPane {
+    lines: VecDeque<LogLine>,
+    queue: LogQueue,
+    // the style escape sequences of the level prefixes, in the order of LEVELS
+    styles: [String; 4],
+    // the level of each row the lines take, for the rows that start a line
+    heads: Vec<Option<Level>>,
+}
+
+impl Default for LogPane {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogPane {
+    /// makes a new empty LogPane keeping up to DEFAULT_LOG_CAPACITY lines of every level
+    /// debug lines are dim, info green, warn yellow and error red
+    pub fn new() -> Self {
+        Self {
+            lines: VecDeque::new(),
+            queue: LogQueue::new(),
+            styles: ["\x1b[2m", "\x1b[32m", "\x1b[33m", "\x1b[31m"].map(String::from),
+            heads: vec![],
+        }
+    }
+
+    /// the queue that sends lines to this pane, see LogQueue
+    pub fn queue(&self) -> LogQueue {
+        self.queue.clone()
+    }
+
+    /// sets how many lines are kept at most, the oldest ones are dropped past that
+    pub fn capacity(&mut self, capacity: usize) {
+        self.queue.0.capacity.store(capacity, Ordering::Relaxed);
+    }
+
+    /// sets the least level of the lines that are kept, the lower ones are dropped as they come in
+    pub fn level(&mut self, level: Level) {
+        self.queue
+            .0
+            .level
+            .store(level_idx(level), Ordering::Relaxed);
+    }
+
+    /// changes the style of the prefix of the lines of level
+    pub fn style(&mut self, level: Level, style: &Style) {
+        self.styles[level_idx(level) as usize] = style.style();
+    }
+
+    /// the kept lines, the oldest first
+    pub fn lines(&self) -> impl Iterator<Item = &LogLine> {
+        self.lines.iter()
+    }
+
+    /// writes the kept lines to the file at path, one 'LEVEL target: message' line each
+    /// the lines still in the queue are not written, see Term::drain_logs
+    pub fn dump(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        for line in &self.lines {
+            writeln!(
+                file,
+                "{} {}: {}",
+                name(line.level),
+                line.target,
+                line.message
+            )?;
+        }
+
+        file.flush()
+    }
+
+    // moves the queued lines to the kept ones
+    // returns how many kept lines were dropped to make room, None if no line came in
+    fn drain(&mut self) -> Option<usize> {
+        let came = self.queue.take();
+        if came.is_empty() {
+            return None;
+        }
+        let before = self.lines.len();
+        self.lines.extend(came);
+        let capacity = self.queue.0.capacity.load(Ordering::Relaxed);
+        let dropped = self.lines.len().saturating_sub(capacity);
+        self.lines.drain(..dropped);
+
+        // the dropped lines may have come in through this drain
+        Some(dropped.min(before))
+    }
+
+    // the value cells of the lines, rows of stride cells, and the heads of the rows
+    fn cells(&self, stride: u16) -> (Vec<Option<char>>, Vec<Option<Level>>) {
+        let stride = stride as usize;
+        let mut cells = vec![];
+        let mut heads = vec![];
+        for line in &self.lines {
+            for (idx, part) in line.message.split('\n').enumerate() {
+                let prefix = match idx {
+                    0 => format!("{:<w$} ", name(line.level), w = LEVEL_W),
+                    _ => " ".repeat(PREFIX_W),
+                };
+                let row = prefix.chars().chain(part.chars()).map(Some);
+                let start = cells.len();
+                cells.extend(row.take(stride));
+                cells.resize(start + stride, None);
+                heads.push((idx == 0).then_some(line.level));
+            }
+        }
+
+        (cells, heads)
+    }
+
+    // the level prefixes of the h rows shown from the row scroll, as highlights of the text
+    pub(crate) fn prefixes(&self, scroll: u16, h: u16) -> Vec<(u16, [u16; 2], &str)> {
+        let shown = self.heads.iter().skip(scroll as usize).take(h as usize);
+
+        shown
+            .enumerate()
+            .filter_map(|(row, head)| {
+                let level = (*head)?;
+                let style = &self.styles[level_idx(level) as usize];
+
+                Some((row as u16, [0, LEVEL_W as u16], style.as_str()))
+            })
+            .collect()
+    }
+}
+
+// the name of a level in the prefixes and the dumps
+fn name(level: Level) -> &'static str {
+    match level {
+        Level::Debug => "DEBUG",
+        Level::Info => "INFO",
+        Level::Warn => "WARN",
+        Level::Error => "ERROR",
+    }
+}
+
+// log panes
+impl Text {
+    /// shows pane in this text, replacing its value, the text is scrolled to the last line
+    pub fn set_log_pane(&mut self, pane: LogPane) {
+        self.log = Some(pane);
+        self.sync_log(true, 0);
+    }
+
+    /// the log pane this text shows, if it shows one
+    pub fn log_pane(&self) -> Option<&LogPane> {
+        self.log.as_ref()
+    }
+
+    /// the log pane this text shows, to change its capacity, level or styles
+    pub fn log_pane_mut(&mut self) -> Option<&mut LogPane> {
+        self.log.as_mut()
+    }
+
+    /// moves the lines queued for the log pane this text shows into it
+    /// returns whether any line came in, false if the text shows no log pane
+    pub fn drain_log(&mut self) -> bool {
+        let tail = self.scroll >= self.max_scroll();
+        let Some(dropped) = self.log.as_mut().and_then(LogPane::drain) else {
+            return false;
+        };
+        // the rows of the dropped lines end at the head of the first line that was kept,
+        // the heads are still the ones of the rows shown so far
+        let heads = &self.log.as_ref().unwrap().heads;
+        let kept = heads
+            .iter()
+            .enumerate()
+            .filter(|(_, h)| h.is_some())
+            .nth(dropped);
+        let gone = kept.map_or(heads.len(), |(row, _)| row);
+        self.sync_log(tail, gone);
+
+        true
+    }
+
+    // writes the lines to the value, the text follows the last line if tail,
+    // otherwise it stays on the lines it showed, which moved up by gone rows
+    fn sync_log(&mut self, tail: bool, gone: usize) {
+        let stride = self.stride();
+        let Some(pane) = &mut self.log else {
+            return;
+        };
+        let (cells, heads) = pane.cells(stride);
+        pane.heads = heads;
+        self.value = cells.into();
+        self.scroll = match tail {
+            true => self.max_scroll(),
+            false => self
+                .scroll
+                .saturating_sub(gone.min(u16::MAX as usize) as u16),
+        };
+        self.mark_dirty();
+    }
+}
+
+impl Term {
+    /// moves the lines queued for every log pane into it, see LogQueue
+    /// EventLoop::run calls it after every event, apps running their own loop call it
+    /// before rendering
+    /// returns whether any line came in
+    pub fn drain_logs(&mut self) -> bool {
+        let texts = self.containers.iter_mut().flat_map(|c| c.items.iter_mut());
+
+        texts
+            .filter(|t| t.log.is_some())
+            .fold(false, |came, t| t.drain_log() || came)
+    }
+}
+
+#[cfg(feature = "log")]
+impl log::Log for LogQueue {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.enabled(from_log(metadata.level()))
+    }
+
+    fn log(&self, record: &log::Record) {
+        let level = from_log(record.level());
+        if self.enabled(level) {
+            self.push(level, record.target(), &record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+// trace is as much of a detail as debug
+#[cfg(feature = "log")]
+fn from_log(level: log::Level) -> Level {
+    match level {
+        log::Level::Error => Level::Error,
+        log::Level::Warn => Level::Warn,
+        log::Level::Info => Level::Info,
+        log::Level::Debug | log::Level::Trace => Level::Debug,
+    }
+}
+
+#[cfg(feature = "log")]
+impl LogQueue {
+    /// makes this queue the log crate's logger, the records of every level are let through
+    /// to the queue, which drops the ones under its pane's level
+    ///
+    /// # Errors
+    ///
+    /// returns an error if a logger was already set
+    pub fn install(self) -> Result<(), log::SetLoggerError> {
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(log::LevelFilter::Trace);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod log_pane {
+    use super::*;
+    use crate::space::{border::Border, padding::Padding};
+
+    fn text(h: u16) -> Text {
+        Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            1,
+            1,
+            16,
+            h,
+            &[],
+            Border::None,
+            Padding::None,
+        )
+    }
+
+    fn rows(text: &Text) -> Vec<String> {
+        let cells = text
+            .value
+            .iter()
+            .map(|c| c.unwrap_or(' '))
+            .collect::<Vec<char>>();
+
+        cells
+            .chunks(text.w as usize)
+            .map(|row| row.iter().collect::<String>().trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn drained() {
+        let mut text = text(2);
+        text.set_log_pane(LogPane::new());
+        let queue = text.log_pane().unwrap().queue();
+        assert!(!text.drain_log());
+
+        let sender = queue.clone();
+        std::thread::spawn(move || sender.push(Level::Info, "app", "started"))
+            .join()
+            .unwrap();
+        queue.push(Level::Error, "app", "two\nrows");
+        assert!(text.drain_log());
+        assert_eq!(rows(&text), ["INFO  started", "ERROR two", "      rows"]);
+        // the last line is followed
+        assert_eq!(text.scroll, 1);
+        assert_eq!(
+            text.log_pane().unwrap().prefixes(text.scroll, text.h),
+            [(0, [0, 5], "\x1b[31m")]
+        );
+    }
+
+    #[test]
+    fn scrolled_up() {
+        let mut text = text(2);
+        let mut pane = LogPane::new();
+        pane.capacity(4);
+        text.set_log_pane(pane);
+        let queue = text.log_pane().unwrap().queue();
+        (0..4).for_each(|n| queue.push(Level::Info, "app", &n.to_string()));
+        text.drain_log();
+        assert_eq!(text.scroll, 2);
+
+        // scrolled up, the shown lines stay shown as the oldest ones are dropped
+        text.scroll_by(-1);
+        assert_eq!(rows(&text)[text.scroll as usize], "INFO  1");
+        queue.push(Level::Info, "app", "4");
+        text.drain_log();
+        assert_eq!(text.log_pane().unwrap().lines().count(), 4);
+        assert_eq!(text.scroll, 0);
+        assert_eq!(rows(&text)[text.scroll as usize], "INFO  1");
+
+        // back at the bottom it follows again
+        text.scroll_by(10);
+        queue.push(Level::Warn, "app", "5");
+        text.drain_log();
+        assert_eq!(rows(&text)[text.scroll as usize + 1], "WARN  5");
+    }
+
+    #[test]
+    fn filtered_and_dumped() {
+        let mut text = text(3);
+        let mut pane = LogPane::new();
+        pane.level(Level::Warn);
+        let queue = pane.queue();
+        text.set_log_pane(pane);
+        queue.push(Level::Debug, "app", "dropped");
+        queue.push(Level::Warn, "app::net", "slow");
+        assert!(!queue.enabled(Level::Info));
+        text.drain_log();
+        assert_eq!(rows(&text), ["WARN  slow"]);
+
+        let path = std::env::temp_dir().join(format!("ragout-log-{}", std::process::id()));
+        text.log_pane().unwrap().dump(&path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "WARN app::net: slow\n"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
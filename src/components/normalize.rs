@@ -0,0 +1,158 @@
+use unicode_normalization::UnicodeNormalization;
+
+use super::Text;
+
+/// the unicode normalization form the chars typed and pasted into a text are put in,
+/// see Text::set_normalization
+/// e.g. a terminal that sends e and a combining acute accent for é types the same
+/// value as one that sends the precomposed é, under Nfc
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Normalization {
+    /// canonical composition, é is one char
+    #[default]
+    Nfc,
+    /// canonical decomposition, é is an e and a combining acute accent
+    Nfd,
+    /// compatibility composition, like Nfc but the ligature ﬁ becomes f and i
+    Nfkc,
+    /// compatibility decomposition
+    Nfkd,
+}
+
+impl Normalization {
+    /// s in this normalization form
+    pub fn apply(self, s: &str) -> String {
+        match self {
+            Self::Nfc => s.nfc().collect(),
+            Self::Nfd => s.nfd().collect(),
+            Self::Nfkc => s.nfkc().collect(),
+            Self::Nfkd => s.nfkd().collect(),
+        }
+    }
+}
+
+// normalization
+impl Text {
+    /// puts the chars typed or pasted into this text in the normalization form before they
+    /// go through its filter and are laid out, with the char before the cursor when they
+    /// combine with it, the value this text already holds is left as it is
+    /// every text starts with Nfc, None turns it off, e.g. for a field whose chars have to
+    /// stay as they were typed
+    pub fn set_normalization(&mut self, normalization: Option<Normalization>) {
+        self.normalization = normalization;
+    }
+
+    /// the normalization form of this text, see Text::set_normalization
+    pub fn normalization(&self) -> Option<Normalization> {
+        self.normalization
+    }
+
+    // s in the normalization form of this text, the char before the cursor is taken out
+    // of the value when s combines with it and comes back at the start of the chars returned,
+    // unless the filter rejects what it combined into
+    // a masked text only normalizes s, the char before the cursor may be a literal
+    pub(crate) fn normalize_typed(&mut self, s: &str) -> String {
+        let Some(form) = self.normalization else {
+            return s.to_string();
+        };
+        let idx = self.cursor_index();
+        let prev = match (self.mask, idx) {
+            (None, 1..) => self.value.get(idx - 1).flatten(),
+            _ => None,
+        };
+        let Some(prev) = prev else {
+            return form.apply(s);
+        };
+        let joined = form.apply(&format!("{prev}{s}"));
+        if let Some(rest) = joined.strip_prefix(prev) {
+            return rest.to_string();
+        }
+
+        self.value.remove(idx - 1);
+        self.fill();
+        self.set_cursor_index(idx - 1);
+        match joined.chars().next().is_some_and(|c| self.accepts(c)) {
+            true => joined,
+            false => {
+                self.value.insert(idx - 1, Some(prev));
+                self.fill();
+                self.set_cursor_index(idx);
+                form.apply(s)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod normalize {
+    use super::*;
+    use crate::components::{Container, InputFilter, Term};
+    use crate::space::{border::Border, padding::Padding};
+
+    // a focused 8x1 input
+    fn term() -> Term {
+        let mut term = Term::new(0, 10, 3);
+        let mut c = Container::new([0, 0], 0, 0, 10, 3, Border::None, Padding::None);
+        c.items.push(Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            8,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        ));
+        term.containers.push(c);
+        term.focused = Some([0, 0, 0]);
+
+        term
+    }
+
+    fn input(term: &mut Term) -> &mut Text {
+        term.input_mut(&[0, 0, 0]).unwrap()
+    }
+
+    #[test]
+    fn composed() {
+        let mut term = term();
+        assert_eq!(input(&mut term).normalization(), Some(Normalization::Nfc));
+        // typed one char after the other, the accent goes onto the e
+        input(&mut term).insert('e');
+        input(&mut term).insert('\u{301}');
+        assert_eq!(input(&mut term).value_string(), "é");
+        assert_eq!(input(&mut term).cx, 1);
+
+        // the cursor ends after the chars the paste normalized into
+        term.paste("u\u{308}e\u{301}x");
+        let text = input(&mut term);
+        assert_eq!(text.value_string(), "éüéx");
+        assert_eq!(text.cx, 4);
+
+        text.set_normalization(Some(Normalization::Nfd));
+        text.insert('ü');
+        assert_eq!(text.value_string(), "éüéxu\u{308}");
+        // the u and its mark are one cluster
+        assert_eq!(text.cx, 5);
+    }
+
+    #[test]
+    fn exact() {
+        let mut term = term();
+        input(&mut term).set_normalization(None);
+        term.paste("e\u{301}");
+        assert_eq!(input(&mut term).value_string(), "e\u{301}");
+
+        // the filter rejects the composed char, the e stays and the accent is typed as it came
+        let text = input(&mut term);
+        text.replace_value("e");
+        text.cx = 1;
+        text.set_normalization(Some(Normalization::Nfc));
+        text.set_filter(Some(InputFilter::Charset("e\u{301}")));
+        assert!(text.insert('\u{301}'));
+        assert_eq!(text.value_string(), "e\u{301}");
+        assert_eq!(text.cx, 1);
+    }
+}
@@ -0,0 +1,238 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use super::{Term, Text};
+
+// the part of bytes that goes to the value, all of them when flushed, otherwise up to the
+// last newline, an unfinished utf-8 char at the end waits for the rest of it either way
+// the invalid utf-8 is replaced
+fn take_text(bytes: &mut Vec<u8>, flushed: bool) -> Option<String> {
+    let end = match flushed {
+        true => match std::str::from_utf8(bytes) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => bytes.len(),
+        },
+        false => bytes.iter().rposition(|b| *b == b'\n')? + 1,
+    };
+    if end == 0 {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&bytes[..end]).into_owned();
+    bytes.drain(..end);
+
+    Some(text)
+}
+
+/// an io::Write that appends what is written to it to a text, see Text::writer
+/// the bytes are kept until a newline comes, the lines go to the text through
+/// Text::append_str, Write::flush puts the unfinished line in too and so does dropping
+/// the writer, the invalid utf-8 is shown as U+FFFD
+#[derive(Debug)]
+pub struct TextWriter<'a> {
+    text: &'a mut Text,
+    pending: Vec<u8>,
+}
+
+impl Write for TextWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        if let Some(text) = take_text(&mut self.pending, false) {
+            self.text.append_str(&text);
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if let Some(text) = take_text(&mut self.pending, true) {
+            self.text.append_str(&text);
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for TextWriter<'_> {
+    fn drop(&mut self) {
+        if !self.pending.is_empty() {
+            self.text
+                .append_str(&String::from_utf8_lossy(&self.pending));
+        }
+    }
+}
+
+// what a sender shares with its text, the bytes and how many of them were flushed
+#[derive(Debug, Default)]
+struct Queue {
+    bytes: Vec<u8>,
+    flushed: usize,
+}
+
+/// an io::Write that can be cloned and moved to other threads, the bytes written to it wait
+/// until the event loop drains them into the text it came from, see Text::sender and
+/// Term::drain_senders, they go in like the ones written to a TextWriter
+#[derive(Debug, Clone, Default)]
+pub struct PaneSender(Arc<Mutex<Queue>>);
+
+// two senders are the same when they send to the same text
+impl PartialEq for PaneSender {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl PaneSender {
+    // takes the part of the queued bytes that goes to the text out
+    fn take(&self) -> Option<String> {
+        let mut queue = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let flushed = std::mem::take(&mut queue.flushed);
+        let mut rest = queue.bytes.split_off(flushed);
+        let mut text = take_text(&mut queue.bytes, true).unwrap_or_default();
+        // what is left of the flushed bytes is the start of an unfinished char
+        queue.bytes.append(&mut rest);
+        text += &take_text(&mut queue.bytes, false).unwrap_or_default();
+
+        (!text.is_empty()).then_some(text)
+    }
+}
+
+impl Write for PaneSender {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut queue = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        queue.bytes.extend_from_slice(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        let mut queue = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        queue.flushed = queue.bytes.len();
+
+        Ok(())
+    }
+}
+
+// writers
+impl Text {
+    /// an io::Write appending to the value of this text, e.g. for the output of a subprocess,
+    /// a formatter or serde_json::to_writer, see TextWriter
+    pub fn writer(&mut self) -> TextWriter<'_> {
+        TextWriter {
+            text: self,
+            pending: vec![],
+        }
+    }
+
+    /// the io::Write for the threads that write to this text, see PaneSender,
+    /// every call gives a sender to the same queue
+    pub fn sender(&mut self) -> PaneSender {
+        self.sender.get_or_insert_with(PaneSender::default).clone()
+    }
+
+    /// appends the bytes the senders of this text sent since the last call to it,
+    /// see Text::sender, returns whether any of them went in
+    pub fn drain_sender(&mut self) -> bool {
+        let Some(text) = self.sender.as_ref().and_then(PaneSender::take) else {
+            return false;
+        };
+        self.append_str(&text);
+
+        true
+    }
+}
+
+impl Term {
+    /// the io::Write appending to the value of the text with id, see Text::writer,
+    /// None when there is no such text
+    pub fn pane_writer(&mut self, id: &[u8; 3]) -> Option<TextWriter<'_>> {
+        self.text_mut(id).map(Text::writer)
+    }
+
+    /// appends what the senders of every text sent to it, see Text::sender
+    /// EventLoop::run calls it after every event, apps running their own loop call it
+    /// before rendering
+    /// returns whether anything came in
+    pub fn drain_senders(&mut self) -> bool {
+        let texts = self.containers.iter_mut().flat_map(|c| c.items.iter_mut());
+
+        texts
+            .filter(|t| t.sender.is_some())
+            .fold(false, |came, t| t.drain_sender() || came)
+    }
+}
+
+#[cfg(test)]
+mod pane_writer {
+    use super::*;
+    use crate::space::{border::Border, padding::Padding};
+
+    // a 6x3 multiline nonedit
+    fn pane() -> Text {
+        let mut text = Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            0,
+            0,
+            6,
+            3,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        text.attributes.insert("multiline");
+
+        text
+    }
+
+    #[test]
+    fn lines() {
+        let mut text = pane();
+        let mut writer = text.writer();
+        write!(writer, "one\ntw").unwrap();
+        // the unfinished line waits for the flush
+        assert_eq!(writer.text.value_string(), "one");
+        writer.flush().unwrap();
+        assert_eq!(writer.text.value_string(), "one\ntw");
+        // the é is split over two writes, the invalid byte is replaced
+        writer.write_all(b"o \xc3").unwrap();
+        writer.write_all(b"\xa9\xff\n").unwrap();
+        drop(writer);
+        assert_eq!(text.value_string(), "one\ntwo é\u{fffd}");
+
+        // what is left goes in when the writer is dropped
+        write!(text.writer(), "end").unwrap();
+        assert_eq!(text.value_string(), "one\ntwo é\u{fffd}\nend");
+    }
+
+    #[test]
+    fn sent() {
+        let mut term = Term::new(0, 10, 4);
+        let mut c = super::super::Container::new([0, 0], 0, 0, 6, 3, Border::None, Padding::None);
+        c.items.push(pane());
+        term.containers.push(c);
+        let sender = term.text_mut(&[0, 0, 1]).unwrap().sender();
+        assert!(!term.drain_senders());
+
+        let mut moved = sender.clone();
+        std::thread::spawn(move || {
+            moved.write_all(b"ab\ncd").unwrap();
+            moved.write_all(b" \xe2\x82").unwrap();
+            moved.flush().unwrap();
+        })
+        .join()
+        .unwrap();
+        assert!(term.drain_senders());
+        // the flushed line is shown, but not the start of the unfinished char
+        let text = term.text_ref(&[0, 0, 1]).unwrap();
+        assert_eq!(text.value_string(), "ab\ncd ");
+        let mut sender = sender;
+        sender.write_all(b"\xac\n").unwrap();
+        term.drain_senders();
+        let text = term.text_ref(&[0, 0, 1]).unwrap();
+        assert_eq!(text.value_string(), "ab\ncd €");
+
+        write!(term.pane_writer(&[0, 0, 1]).unwrap(), "x").unwrap();
+        assert!(term.pane_writer(&[0, 0, 3]).is_none());
+    }
+}
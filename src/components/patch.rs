@@ -0,0 +1,324 @@
+use std::ops::Range;
+
+use super::gutter::split_lines;
+use super::{ComponentTreeError, Text};
+
+/// an edit of the value of a text, see Text::apply_patch
+/// the offsets count the chars of the value's lines joined with newlines, from 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchOp {
+    /// puts text before the char at at, or after the last one when at is the length
+    Insert { at: usize, text: String },
+    /// removes the chars of range
+    Delete { range: Range<usize> },
+    /// puts text in place of the chars of range
+    Replace { range: Range<usize>, text: String },
+}
+
+impl PatchOp {
+    // the chars the op takes out and the ones it puts in their place
+    fn parts(&self) -> (Range<usize>, &str) {
+        match self {
+            Self::Insert { at, text } => (*at..*at, text),
+            Self::Delete { range } => (range.clone(), ""),
+            Self::Replace { range, text } => (range.clone(), text),
+        }
+    }
+}
+
+// a line of a value, its cells, the rows it takes from the row it starts on
+// and the offset of its first char
+struct Line {
+    cells: Vec<Option<char>>,
+    row: usize,
+    rows: usize,
+    start: usize,
+}
+
+// the lines of cells, rows of stride cells, see Text::apply_patch
+fn lay(cells: &[Option<char>], stride: usize) -> Vec<Line> {
+    let (mut row, mut start) = (0, 0);
+    let lines = split_lines(cells, stride).into_iter();
+
+    lines
+        .map(|(cells, rows)| {
+            let line = Line {
+                row,
+                rows,
+                start,
+                cells,
+            };
+            row += rows;
+            start += line.cells.len() + 1;
+            line
+        })
+        .collect()
+}
+
+// the last line starting at or before the row, or the offset
+fn line_of(lines: &[Line], at: impl Fn(&Line) -> bool) -> &Line {
+    lines.iter().rev().find(|l| at(l)).unwrap_or(&lines[0])
+}
+
+// the offset of the value cell idx, a cell past the end of its line is its end
+fn offset(lines: &[Line], stride: usize, idx: usize) -> usize {
+    let line = line_of(lines, |l| l.row <= idx / stride);
+
+    line.start + (idx - line.row * stride).min(line.cells.len())
+}
+
+// the value cell of the offset
+fn cell(lines: &[Line], stride: usize, offset: usize) -> usize {
+    let line = line_of(lines, |l| l.start <= offset);
+
+    line.row * stride + (offset - line.start).min(line.cells.len())
+}
+
+// where the offset p goes once the chars of range are replaced by n chars,
+// an offset inside them goes to their start and one right where n chars are inserted after them
+fn shift(p: usize, range: &Range<usize>, n: usize) -> usize {
+    match p {
+        p if p < range.start => p,
+        p if p >= range.end => p - range.len() + n,
+        _ => range.start,
+    }
+}
+
+// the rows of both ranges, an empty range holds none
+fn union(a: Range<u16>, b: Range<u16>) -> Range<u16> {
+    match (a.is_empty(), b.is_empty()) {
+        (true, _) => b,
+        (_, true) => a,
+        _ => a.start.min(b.start)..a.end.max(b.end),
+    }
+}
+
+// patches
+impl Text {
+    /// applies the ops, one after the other, to the value of this text, e.g. the changes
+    /// to a watched file or an updated table row, without replacing the whole value
+    /// the offsets of an op count the chars of the value as the ops before it left it,
+    /// with the lines joined with newlines, a row whose last cell is filled goes on
+    /// on the next row, they are one line
+    /// a newline put in by an op starts a new line and the value is wrapped again
+    /// the line on the top row stays there, the rows shown don't move when lines are put in
+    /// or taken out above them, the cursor and the search matches stay on their chars
+    /// and a match an op changed is dropped
+    /// only the rows whose cells changed are rendered again by the next Term::render_dirty
+    /// a text without columns is left as it is
+    ///
+    /// # Errors
+    /// returns PatchOutOfRange if an op reaches past the end of the value,
+    /// none of the ops is applied then
+    pub fn apply_patch(&mut self, ops: &[PatchOp]) -> Result<(), ComponentTreeError> {
+        let stride = self.stride() as usize;
+        if stride == 0 {
+            return Ok(());
+        }
+        let before = lay(&self.value.to_vec(), stride);
+        let mut buffer = before
+            .iter()
+            .map(|l| &l.cells[..])
+            .collect::<Vec<&[Option<char>]>>()
+            .join(&Some('\n'));
+        let mut edits = vec![];
+        for (idx, op) in ops.iter().enumerate() {
+            let (range, text) = op.parts();
+            if range.start > range.end || range.end > buffer.len() {
+                return Err(ComponentTreeError::PatchOutOfRange {
+                    op: idx,
+                    len: buffer.len(),
+                });
+            }
+            let mut put = vec![];
+            for c in text.chars().filter(|c| *c == '\n' || !c.is_control()) {
+                // the value is the buffer until the ops are all applied
+                if self.clusters.is_full() {
+                    self.clusters.reclaim(buffer.iter_mut().chain(&mut put));
+                }
+                put.push(Some(self.clusters.own(c)));
+            }
+            let text = put;
+            edits.push((range.clone(), text.len()));
+            buffer.splice(range, text);
+        }
+        let shifted = |p: usize| edits.iter().fold(p, |p, (range, n)| shift(p, range, *n));
+
+        // the line on the top row and how many of its rows are scrolled past
+        let scroll = self.scroll as usize;
+        let top = line_of(&before, |l| l.row <= scroll);
+        let [anchor, down] = [shifted(top.start), scroll - top.row];
+        let cursor = shifted(offset(&before, stride, self.cursor_index()));
+        let (shown, lines) = (self.cells(), self.lines());
+
+        let mut cells = vec![];
+        for line in buffer.split(|c| *c == Some('\n')) {
+            let start = cells.len();
+            cells.extend_from_slice(line);
+            cells.resize(start + line.len().div_ceil(stride).max(1) * stride, None);
+        }
+        self.value = cells.into();
+        self.fill();
+        let after = lay(&self.value.to_vec(), stride);
+
+        let row = cell(&after, stride, anchor) / stride;
+        let line = line_of(&after, |l| l.row <= row);
+        let scroll = (row + down).min(line.row + line.rows - 1);
+        self.scroll = scroll.min(self.max_scroll() as usize) as u16;
+        self.set_cursor_index(cell(&after, stride, cursor));
+        self.remap_matches(|found| {
+            let ends = [found.start, found.end].map(|idx| offset(&before, stride, idx));
+            let [start, end] = edits.iter().try_fold(ends, |[start, end], (range, n)| {
+                match (end <= range.start, start >= range.end) {
+                    (true, _) => Some([start, end]),
+                    (_, true) => Some([start, end].map(|p| p - range.len() + n)),
+                    _ => None,
+                }
+            })?;
+
+            Some(cell(&after, stride, start)..cell(&after, stride, end))
+        });
+
+        // the rows whose cells changed, the scrollbars and the gutter are rendered with them
+        let now = self.cells();
+        let w = self.w as usize;
+        let changed = (0..self.h)
+            .filter(|y| {
+                let row = *y as usize * w..(*y as usize + 1) * w;
+                shown[row.clone()] != now[row]
+            })
+            .collect::<Vec<u16>>();
+        let rows = match (changed.first(), changed.last()) {
+            (Some(first), Some(last)) => *first..*last + 1,
+            _ if lines != self.lines() => 0..0,
+            _ => return Ok(()),
+        };
+        self.mark_rows(rows);
+
+        Ok(())
+    }
+
+    // marks the rows of the area to be rendered again by the next Term::render_dirty,
+    // with the scrollbars and the gutter, unless the whole text already is
+    pub(crate) fn mark_rows(&mut self, rows: Range<u16>) {
+        self.patched = match (self.attributes.contains("dirty"), self.patched.take()) {
+            (true, None) => None,
+            (_, Some(patched)) => Some(union(patched, rows)),
+            (false, None) => Some(rows),
+        };
+        self.attributes.insert("dirty");
+    }
+}
+
+#[cfg(test)]
+mod patch {
+    use super::*;
+    use crate::components::{Container, Term};
+    use crate::console::Capabilities;
+    use crate::space::{border::Border, padding::Padding};
+
+    // a multiline 4x2 nonedit holding the lines of value, one per row
+    fn pane(value: &str) -> Text {
+        let mut text = Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            0,
+            0,
+            4,
+            2,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        text.attributes.insert("multiline");
+        text.value = value
+            .split('\n')
+            .flat_map(|line| {
+                let mut row = line.chars().map(Some).collect::<Vec<Option<char>>>();
+                row.resize(4, None);
+                row
+            })
+            .collect::<Vec<Option<char>>>()
+            .into();
+        text.fill();
+
+        text
+    }
+
+    fn insert(at: usize, text: &str) -> PatchOp {
+        PatchOp::Insert {
+            at,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn applied() {
+        let mut text = pane("ab\ncd\nef");
+        text.apply_patch(&[
+            insert(2, "x"),
+            PatchOp::Delete { range: 4..6 },
+            PatchOp::Replace {
+                range: 5..7,
+                text: "ghijk".to_string(),
+            },
+        ])
+        .unwrap();
+        assert_eq!(text.value_string(), "abx\n\nghij\nk");
+
+        let err = text.apply_patch(&[insert(0, "y"), PatchOp::Delete { range: 3..40 }]);
+        let len = "yabx\n\nghijk".len();
+        assert_eq!(err, Err(ComponentTreeError::PatchOutOfRange { op: 1, len }));
+        assert_eq!(text.value_string(), "abx\n\nghij\nk");
+    }
+
+    #[test]
+    fn anchored() {
+        let mut text = pane("a\nb\nc\nd");
+        text.scroll = 2;
+        text.search("d", false);
+        text.apply_patch(&[insert(0, "0\n1\n")]).unwrap();
+        // still showing c and d
+        assert_eq!(text.scroll, 4);
+        assert_eq!([text.cell(0, 0), text.cell(0, 1)], [Some('c'), Some('d')]);
+        assert_eq!(text.matches(), [20..21]);
+        // the search already marked the whole text
+        assert_eq!(text.patched, None);
+
+        // nothing shown changed, only the scrollbars and the gutter are rendered
+        text.attributes.remove("dirty");
+        text.apply_patch(&[PatchOp::Delete { range: 0..4 }])
+            .unwrap();
+        assert_eq!(text.scroll, 2);
+        assert_eq!(text.patched, Some(0..0));
+        text.apply_patch(&[insert(7, "e")]).unwrap();
+        assert_eq!(text.patched, Some(1..2));
+        assert_eq!(text.matches(), [12..13]);
+        let replace = PatchOp::Replace {
+            range: 6..7,
+            text: "D".to_string(),
+        };
+        text.apply_patch(&[replace]).unwrap();
+        assert!(text.matches().is_empty());
+    }
+
+    #[test]
+    fn rendered() {
+        let mut term = Term::new(0, 6, 3);
+        let mut c = Container::new([0, 0], 0, 0, 6, 3, Border::None, Padding::None);
+        c.items.push(pane("ab\ncd"));
+        term.containers.push(c);
+        term.frame_with(&Capabilities::default());
+        let text = term.nonedit_mut(&[0, 0, 1]).unwrap();
+        text.attributes.remove("dirty");
+        text.apply_patch(&[insert(5, "e")]).unwrap();
+
+        let mut out = vec![];
+        term.render_dirty_to(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("cde"));
+        assert!(!out.contains("ab"));
+        assert_eq!(term.nonedit_ref(&[0, 0, 1]).unwrap().patched, None);
+    }
+}
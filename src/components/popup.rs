@@ -0,0 +1,270 @@
+use crate::inputs::keyboard::{Char, KbdEvent, CC};
+
+use super::ContainerMeta;
+use super::Term;
+
+/// an open popup, see Term::popup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PopupHandle {
+    id: [u8; 2],
+}
+
+impl PopupHandle {
+    pub(crate) fn new(id: [u8; 2]) -> Self {
+        Self { id }
+    }
+
+    /// the id of the popup's container, to push its components to
+    pub fn id(&self) -> [u8; 2] {
+        self.id
+    }
+
+    /// closes the popup, see Term::close_popup
+    pub fn close(self, term: &mut Term) -> bool {
+        term.close_popup(&self.id)
+    }
+}
+
+// what the term keeps of an open popup
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Popup {
+    pub(crate) id: [u8; 2],
+    // whether what is under the popup is dimmed
+    pub(crate) dim: bool,
+    // the text that was focused when the popup opened
+    pub(crate) focus: Option<[u8; 3]>,
+}
+
+impl Term {
+    /// opens a modal popup, a container made from meta on top of every other one,
+    /// placed by the meta's hpos and vpos, e.g. Pos::Center for both
+    /// while it is open the keys and clicks only go to the topmost popup, Tab only goes
+    /// through its components and the focus moves to its first input once one is pushed to it
+    /// Esc or PopupHandle::close closes it, popups opened while it is open go on top of it
+    pub fn popup(&mut self, meta: ContainerMeta) -> PopupHandle {
+        let [_, cid] = self
+            .free_container_id()
+            .expect("every container id of the term is taken");
+        let layer = self.containers.iter().map(|c| c.layer).max().unwrap_or(0);
+        let id = [self.id, cid];
+        let mut c = meta.floating(id, [self.w, self.h], layer);
+        c.mark_dirty();
+        self.containers.push(c);
+
+        if meta.dims() {
+            self.containers.iter_mut().for_each(|c| c.mark_dirty());
+        }
+        self.popups.push(Popup {
+            id,
+            dim: meta.dims(),
+            focus: self.focused.take(),
+        });
+
+        PopupHandle { id }
+    }
+
+    /// closes the open popup with id and removes its container, the cells it took are
+    /// rendered again and the focus goes back to where it was when the popup opened
+    /// returns false if no popup with id is open
+    pub fn close_popup(&mut self, id: &[u8; 2]) -> bool {
+        let Some(idx) = self.popups.iter().position(|p| p.id == *id) else {
+            return false;
+        };
+        let popup = self.popups.remove(idx);
+        let Some(at) = self.containers.iter().position(|c| c.id == *id) else {
+            return true;
+        };
+        let area = self.containers.remove(at).outer_area();
+        self.uncover(area);
+        if popup.dim {
+            self.containers.iter_mut().for_each(|c| c.mark_dirty());
+        }
+
+        // the popup opened on top of this one gives the focus back to where this one would have
+        match self.popups.get_mut(idx) {
+            Some(above) if above.focus.is_some_and(|f| [f[0], f[1]] == *id) => {
+                above.focus = popup.focus
+            }
+            Some(_) => (),
+            None => {
+                self.focused = None;
+                if let Some(focus) = popup.focus {
+                    _ = self.focus(&focus);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// whether a popup is open
+    pub fn is_popup_open(&self) -> bool {
+        !self.popups.is_empty()
+    }
+
+    // the container id of the topmost popup
+    pub(crate) fn top_popup(&self) -> Option<[u8; 2]> {
+        self.popups.last().map(|p| p.id)
+    }
+
+    // whether the text with id can get the keys and clicks,
+    // the ones outside of the topmost popup can't while it is open
+    pub(crate) fn reachable(&self, id: &[u8; 3]) -> bool {
+        self.top_popup().map_or(true, |popup| [id[0], id[1]] == popup)
+    }
+
+    // closes the topmost popup for Esc
+    // returns None if the key is left to the keymaps
+    pub(crate) fn popup_key(&mut self, event: &KbdEvent) -> Option<bool> {
+        let popup = self.top_popup()?;
+        if event.char != Char::CC(CC::ESC) || !event.modifiers.is_empty() {
+            return None;
+        }
+
+        Some(self.close_popup(&popup))
+    }
+
+    // the index of the container under which everything is dimmed
+    pub(crate) fn dimmed_below(&self) -> Option<usize> {
+        let popup = self.popups.iter().rev().find(|p| p.dim)?;
+
+        self.containers.iter().position(|c| c.id == popup.id)
+    }
+
+    // renders the popups again over whatever is rendered under them
+    pub(crate) fn raise_popups(&mut self) {
+        let Some(first) = self.popups.first().map(|p| p.id) else {
+            return;
+        };
+        let Some(at) = self.containers.iter().position(|c| c.id == first) else {
+            return;
+        };
+        let under = self.containers[..at].iter().any(|c| {
+            c.attributes.contains("dirty") || c.items.iter().any(|t| t.attributes.contains("dirty"))
+        });
+        if under {
+            let popups = self.popups.iter().map(|p| p.id).collect::<Vec<[u8; 2]>>();
+            self.containers
+                .iter_mut()
+                .filter(|c| popups.contains(&c.id))
+                .for_each(|c| c.mark_dirty());
+        }
+    }
+}
+
+// the bytes of a render, faint
+pub(crate) fn dim(render: &[u8]) -> Vec<u8> {
+    let render = String::from_utf8_lossy(render).replace("\x1b[0m", "\x1b[0;2m");
+
+    format!("\x1b[2m{}\x1b[0m", render).into_bytes()
+}
+
+#[cfg(test)]
+mod popup {
+    use super::*;
+    use crate::components::{Container, Text};
+    use crate::inputs::keyboard::{KeyKind, Modifiers};
+    use crate::space::{border::Border, padding::Padding, Area};
+
+    // an input behind the popups
+    fn app() -> Term {
+        let mut term = Term::new(0, 30, 10);
+        let mut c = Container::new([0, 0], 0, 0, 30, 10, Border::None, Padding::None);
+        let input = Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            1,
+            1,
+            10,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        c.items.push(input);
+        term.containers.push(c);
+        _ = term.focus(&[0, 0, 0]);
+
+        term
+    }
+
+    // opens a 10x2 popup with 2 inputs
+    fn dialog(term: &mut Term) -> PopupHandle {
+        let meta = ContainerMeta::new()
+            .area(Area::Values { w: 10, h: 2 })
+            .border(Border::Uniform('#'))
+            .dim(true);
+        let popup = term.popup(meta);
+        for (row, iid) in [0, 2].into_iter().enumerate() {
+            let r = row as u16;
+            let input = Text::input(
+                [0, popup.id()[1], iid],
+                0,
+                r,
+                11,
+                6 + r,
+                10,
+                1,
+                &[],
+                Border::None,
+                Padding::None,
+            );
+            _ = term.push_input(input);
+        }
+
+        popup
+    }
+
+    fn key(char: Char) -> KbdEvent {
+        KbdEvent {
+            char,
+            modifiers: Modifiers(0),
+            kind: KeyKind::Press,
+        }
+    }
+
+    #[test]
+    fn focus_trap() {
+        let mut term = app();
+        let popup = dialog(&mut term);
+        let c = term.container_ref(&popup.id()).unwrap();
+        // centered, the border takes a cell on each side
+        assert_eq!([c.x0, c.y0], [9, 3]);
+        assert_eq!(c.layer, 1);
+        assert_eq!(term.focused, Some([0, 1, 0]));
+
+        term.key(&key(Char::Char('y')));
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().value_string(), "");
+        term.focus_next();
+        term.focus_next();
+        assert_eq!(term.focused, Some([0, 1, 0]));
+        assert!(term.focus(&[0, 0, 0]).is_err());
+
+        assert_eq!(term.dimmed_below(), Some(1));
+        assert!(dim(b"a\x1b[0mb").starts_with(b"\x1b[2ma\x1b[0;2mb"));
+
+        popup.close(&mut term);
+        assert!(!term.is_popup_open());
+        assert!(term.container_ref(&popup.id()).is_none());
+        assert_eq!(term.focused, Some([0, 0, 0]));
+        assert_eq!(term.uncovered, [[10, 3, 12, 4]]);
+    }
+
+    #[test]
+    fn stacked() {
+        let mut term = app();
+        let below = dialog(&mut term);
+        let top = dialog(&mut term);
+        assert_eq!(top.id(), [0, 2]);
+        assert_eq!(term.focused, Some([0, 2, 0]));
+
+        term.key(&key(Char::CC(CC::ESC)));
+        assert_eq!(term.top_popup(), Some(below.id()));
+        assert_eq!(term.focused, Some([0, 1, 0]));
+        term.key(&key(Char::CC(CC::ESC)));
+        assert_eq!(term.focused, Some([0, 0, 0]));
+        // Esc goes to the keymaps once no popup is open
+        assert!(!term.close_popup(&below.id()));
+    }
+}
@@ -0,0 +1,292 @@
+use std::io::Write;
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+
+use super::{RenderStats, ScreenMode, Term};
+
+type BeforeHook = Box<dyn FnMut(&mut Term)>;
+type AfterHook = Box<dyn FnMut(&Term, &RenderStats)>;
+
+/// what Term::on_before_render and Term::on_after_render return,
+/// the hook is removed by giving it to Term::remove_render_hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RenderHookToken(u64);
+
+// what Term::on_before_render and Term::on_after_render were given
+#[derive(Default)]
+pub(crate) struct RenderHooks {
+    next: u64,
+    before: Vec<(u64, BeforeHook)>,
+    after: Vec<(u64, AfterHook)>,
+    // the hooks taken out while they run, and the ones of them removed meanwhile
+    running: Vec<u64>,
+    removed: Vec<u64>,
+}
+
+impl RenderHooks {
+    fn token(&mut self) -> RenderHookToken {
+        self.next += 1;
+
+        RenderHookToken(self.next)
+    }
+
+    // the hooks that ran go back before the ones registered while they ran
+    fn put_back<T>(&mut self, mut ran: Vec<(u64, T)>, registered: Vec<(u64, T)>) -> Vec<(u64, T)> {
+        ran.retain(|(id, _)| !self.removed.contains(id));
+        ran.extend(registered);
+        self.running.clear();
+        self.removed.clear();
+
+        ran
+    }
+}
+
+impl Term {
+    /// calls f with this Term at the start of every frame, before anything is laid out,
+    /// e.g. to update a clock, the components it marks dirty are drawn in the same frame
+    /// the hooks run in the order they were registered, by Term::render and Term::render_dirty,
+    /// and their async counterparts, see Term::hook_partial_renders for the other renders
+    pub fn on_before_render(&mut self, f: impl FnMut(&mut Term) + 'static) -> RenderHookToken {
+        let token = self.render_hooks.token();
+        self.render_hooks.before.push((token.0, Box::new(f)));
+
+        token
+    }
+
+    /// calls f with this Term and the stats of the frame once the frame is written, e.g. to
+    /// flush an overlay drawn by something else, the stats are Term::render_stats, all zeros
+    /// unless the stats feature is on and they are collected, see Term::collect_stats
+    /// the hooks run when the before hooks do, see Term::on_before_render
+    pub fn on_after_render(
+        &mut self,
+        f: impl FnMut(&Term, &RenderStats) + 'static,
+    ) -> RenderHookToken {
+        let token = self.render_hooks.token();
+        self.render_hooks.after.push((token.0, Box::new(f)));
+
+        token
+    }
+
+    /// removes the hook token was returned for, a hook can remove itself or another one while
+    /// it runs, returns false if the hook was already removed
+    pub fn remove_render_hook(&mut self, token: RenderHookToken) -> bool {
+        let hooks = &mut self.render_hooks;
+        let len = hooks.before.len() + hooks.after.len();
+        hooks.before.retain(|(id, _)| *id != token.0);
+        hooks.after.retain(|(id, _)| *id != token.0);
+        if hooks.before.len() + hooks.after.len() < len {
+            return true;
+        }
+        if hooks.running.contains(&token.0) && !hooks.removed.contains(&token.0) {
+            hooks.removed.push(token.0);
+            return true;
+        }
+
+        false
+    }
+
+    /// lets Term::attribute_render and Term::property_render run the render hooks too,
+    /// which they don't by default since they only draw some of the components,
+    /// see Term::on_before_render
+    pub fn hook_partial_renders(&mut self, on: bool) {
+        match on {
+            true => self.attributes.insert("hooked-partial-renders"),
+            false => self.attributes.remove("hooked-partial-renders"),
+        };
+    }
+
+    // whether a partial render runs the hooks, see Term::hook_partial_renders
+    pub(crate) fn hooks_partial_renders(&self) -> bool {
+        self.attributes.contains("hooked-partial-renders")
+    }
+
+    // runs the before render hooks, the hooks are taken out of the term while they run
+    // returns what a panicking hook panicked with, once the hooks are back
+    pub(crate) fn before_render(&mut self) -> std::thread::Result<()> {
+        if self.render_hooks.before.is_empty() {
+            return Ok(());
+        }
+        let mut hooks = std::mem::take(&mut self.render_hooks.before);
+        self.render_hooks.running = hooks.iter().map(|(id, _)| *id).collect();
+        let ran = catch_unwind(AssertUnwindSafe(|| {
+            hooks.iter_mut().for_each(|(_, hook)| hook(self))
+        }));
+        let registered = std::mem::take(&mut self.render_hooks.before);
+        self.render_hooks.before = self.render_hooks.put_back(hooks, registered);
+
+        ran
+    }
+
+    // runs the after render hooks like Term::before_render does the before ones
+    pub(crate) fn after_render(&mut self) -> std::thread::Result<()> {
+        if self.render_hooks.after.is_empty() {
+            return Ok(());
+        }
+        let stats = self.frame_stats();
+        let mut hooks = std::mem::take(&mut self.render_hooks.after);
+        self.render_hooks.running = hooks.iter().map(|(id, _)| *id).collect();
+        let term = &*self;
+        let ran = catch_unwind(AssertUnwindSafe(|| {
+            hooks.iter_mut().for_each(|(_, hook)| hook(term, &stats))
+        }));
+        let registered = std::mem::take(&mut self.render_hooks.after);
+        self.render_hooks.after = self.render_hooks.put_back(hooks, registered);
+
+        ran
+    }
+
+    fn frame_stats(&self) -> RenderStats {
+        #[cfg(feature = "stats")]
+        return self.render_stats();
+        #[cfg(not(feature = "stats"))]
+        RenderStats::default()
+    }
+
+    // what ends a frame, the style reset, the end of the synchronized output and the cursor
+    // shown again, nothing for a linear term
+    pub(crate) fn frame_epilogue(&self) -> Vec<u8> {
+        if self.mode == ScreenMode::Linear {
+            return vec![];
+        }
+        let mut s = b"\x1b[0m".to_vec();
+        if self.capabilities.synchronized_output {
+            s.extend_from_slice(b"\x1b[?2026l");
+        }
+        s.extend_from_slice(b"\x1b[?25h");
+
+        s
+    }
+
+    // runs the before render hooks, frame then the after render hooks
+    pub(crate) fn with_hooks<W: Write, T>(
+        &mut self,
+        writer: &mut W,
+        frame: impl FnOnce(&mut Self, &mut W) -> T,
+    ) -> T {
+        let ran = self.before_render();
+        self.unwind_hooks(writer, ran);
+        let framed = frame(self, writer);
+        let ran = self.after_render();
+        self.unwind_hooks(writer, ran);
+
+        framed
+    }
+
+    // writes the frame epilogue and goes on panicking if a hook panicked,
+    // the terminal is left usable for whatever catches the panic
+    pub(crate) fn unwind_hooks(&self, writer: &mut impl Write, ran: std::thread::Result<()>) {
+        if let Err(panic) = ran {
+            _ = writer.write_all(&self.frame_epilogue());
+            _ = writer.flush();
+            resume_unwind(panic);
+        }
+    }
+}
+
+#[cfg(test)]
+mod render_hooks {
+    use super::*;
+    use crate::components::{Container, Text};
+    use crate::space::{border::Border, padding::Padding};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // a nonedit showing a clock
+    fn clock() -> Term {
+        let mut term = Term::new(0, 10, 1);
+        let mut c = Container::new([0, 0], 0, 0, 10, 1, Border::None, Padding::None);
+        let text = Text::nonedit(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            10,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        c.items.push(text);
+        term.containers.push(c);
+
+        term
+    }
+
+    #[test]
+    fn order() {
+        let mut term = clock();
+        let calls = Rc::new(RefCell::new(vec![]));
+        let before = |name: &'static str| {
+            let calls = calls.clone();
+            move |_: &mut Term| calls.borrow_mut().push(name)
+        };
+        let first = term.on_before_render(before("first"));
+        term.on_before_render(before("second"));
+        let after = calls.clone();
+        term.on_after_render(move |_, _| after.borrow_mut().push("after"));
+
+        term.render_to(&mut std::io::sink()).unwrap();
+        assert_eq!(*calls.borrow(), ["first", "second", "after"]);
+        assert!(term.remove_render_hook(first));
+        assert!(!term.remove_render_hook(first));
+        term.render_dirty_to(&mut std::io::sink()).unwrap();
+        assert_eq!(calls.borrow()[3..], ["second", "after"]);
+    }
+
+    #[test]
+    fn same_frame() {
+        let mut term = clock();
+        term.render_to(&mut std::io::sink()).unwrap();
+        let mut ticks = 0;
+        term.on_before_render(move |term| {
+            ticks += 1;
+            let text = term.nonedit_mut(&[0, 0, 0]).unwrap();
+            text.replace_value(&format!("12:0{}", ticks));
+        });
+
+        let mut out = vec![];
+        term.render_dirty_to(&mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("12:01"));
+        assert!(!term.is_dirty());
+    }
+
+    #[test]
+    fn partial() {
+        let mut term = clock();
+        let calls = Rc::new(RefCell::new(0));
+        let count = calls.clone();
+        term.on_before_render(move |_| *count.borrow_mut() += 1);
+
+        term.attribute_render_hooked(&mut std::io::sink(), "dirty");
+        assert_eq!(*calls.borrow(), 0);
+        term.hook_partial_renders(true);
+        term.attribute_render_hooked(&mut std::io::sink(), "dirty");
+        assert_eq!(*calls.borrow(), 1);
+    }
+
+    #[test]
+    fn self_removal() {
+        let mut term = clock();
+        let token = Rc::new(RefCell::new(None));
+        let own = token.clone();
+        *token.borrow_mut() = Some(term.on_before_render(move |term| {
+            term.remove_render_hook(own.borrow().unwrap());
+        }));
+
+        term.render_to(&mut std::io::sink()).unwrap();
+        assert!(term.render_hooks.before.is_empty());
+    }
+
+    #[test]
+    fn panicking() {
+        let mut term = clock();
+        term.on_before_render(|_| panic!("hook"));
+        let mut out = vec![];
+        let rendered = catch_unwind(AssertUnwindSafe(|| term.render_to(&mut out)));
+
+        assert!(rendered.is_err());
+        assert!(out.ends_with(b"\x1b[0m\x1b[?25h") || out.ends_with(b"\x1b[?2026l\x1b[?25h"));
+        // the hook is still registered
+        assert_eq!(term.render_hooks.before.len(), 1);
+    }
+}
@@ -0,0 +1,463 @@
+use crate::console::Capabilities;
+use crate::inputs::mouse::{MouseButton, MouseEvent, MouseKind};
+use crate::render_pipeline::cup;
+use crate::themes::Style;
+
+use super::{Term, Text};
+
+/// the edge of a Text a Scrollbar is drawn on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Axis {
+    /// the right edge, for the lines of the value
+    #[default]
+    Vertical,
+    /// the bottom edge, for the columns of the value
+    Horizontal,
+}
+
+/// a one cell wide scrollbar drawn inside a Text's border, on its right edge or on its bottom
+/// edge for a horizontal one, see Text::set_scrollbar
+/// it is only shown while the value does not fit in the text, and it then takes the last
+/// column, or the last row, of the text's area
+/// the thumb's size is how much of the value is shown and its place is how far it is scrolled,
+/// a click on the track scrolls there and dragging the thumb scrolls along with it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scrollbar {
+    axis: Axis,
+    // the width of the value's lines, only used by horizontal scrollbars
+    columns: u16,
+    track: char,
+    thumb: char,
+    // the style escape sequences of the track and the thumb
+    track_style: String,
+    thumb_style: String,
+}
+
+impl Scrollbar {
+    /// makes a new vertical Scrollbar, for the value lines past the text's height
+    /// the track is a faint '│' and the thumb a '█' unless told otherwise
+    pub fn vertical() -> Self {
+        Self {
+            axis: Axis::Vertical,
+            columns: 0,
+            track: '│',
+            thumb: '█',
+            track_style: "\x1b[2m".to_string(),
+            thumb_style: "".to_string(),
+        }
+    }
+
+    /// makes a new horizontal Scrollbar, the value of the text it is given to is then laid out
+    /// in lines of columns cells instead of lines as wide as the text, see Text::hscroll
+    /// the track is a faint '─' and the thumb a '█' unless told otherwise
+    pub fn horizontal(columns: u16) -> Self {
+        Self {
+            axis: Axis::Horizontal,
+            columns,
+            track: '─',
+            ..Self::vertical()
+        }
+    }
+
+    /// changes the char the track is drawn with
+    pub fn track(&mut self, track: char) {
+        self.track = track;
+    }
+
+    /// changes the char the thumb is drawn with
+    pub fn thumb(&mut self, thumb: char) {
+        self.thumb = thumb;
+    }
+
+    /// changes the style of the track
+    pub fn track_style(&mut self, style: &Style) {
+        self.track_style = style.style();
+    }
+
+    /// changes the style of the thumb
+    pub fn thumb_style(&mut self, style: &Style) {
+        self.thumb_style = style.style();
+    }
+
+    /// the edge the scrollbar is drawn on
+    pub fn axis(&self) -> Axis {
+        self.axis
+    }
+
+    /// the width of the value's lines, 0 for a vertical scrollbar
+    pub fn columns(&self) -> u16 {
+        self.columns
+    }
+
+    // writes a cell of the track, or of the thumb
+    fn put(&self, s: &mut String, caps: &Capabilities, thumb: bool) {
+        let (c, style) = match thumb {
+            true => (self.thumb, &self.thumb_style),
+            false => (self.track, &self.track_style),
+        };
+        *s += "\x1b[0m";
+        *s += &caps.adapt_sgr(style);
+        s.push(caps.adapt_char(c));
+    }
+}
+
+// the [start, end) cells of the thumb on a track of len cells,
+// when view out of total lines or columns are shown, scrolled by offset
+pub(crate) fn thumb(len: u16, view: u16, total: u16, offset: u16) -> [u16; 2] {
+    if total == 0 {
+        return [0, len];
+    }
+    let [len, view, total] = [len as u32, view as u32, total as u32];
+    let max = total.saturating_sub(view);
+    let size = ((len * view + total / 2) / total).clamp(1.min(len), len);
+    let start = match max {
+        0 => 0,
+        max => ((len - size) * (offset as u32).min(max) + max / 2) / max,
+    };
+
+    [start as u16, (start + size) as u16]
+}
+
+// the offset out of 0..=max that the cell at of a track of len cells stands for
+fn offset_at(len: u16, max: u16, at: u16) -> u16 {
+    if len <= 1 {
+        return 0;
+    }
+    let [len, max, at] = [len as u32 - 1, max as u32, at.min(len - 1) as u32];
+
+    ((at * max + len / 2) / len) as u16
+}
+
+// scrollbars
+impl Text {
+    /// gives this text scrollbar, replacing the one it had on the same edge
+    pub fn set_scrollbar(&mut self, scrollbar: Scrollbar) {
+        let axis = scrollbar.axis as usize;
+        self.scrollbars[axis] = Some(scrollbar);
+        self.scroll = self.scroll.min(self.max_scroll());
+        self.hscroll = self.hscroll.min(self.max_hscroll());
+        self.mark_dirty();
+    }
+
+    /// the scrollbar this text has on the edge of axis, if it has one
+    pub fn scrollbar(&self, axis: Axis) -> Option<&Scrollbar> {
+        self.scrollbars[axis as usize].as_ref()
+    }
+
+    /// the width and height of the part of this text's area that shows the value,
+    /// the scrollbars that are shown take the rest
+    pub fn view(&self) -> [u16; 2] {
+        if self.scrollbars == [None, None] {
+            return [self.w, self.h];
+        }
+        let [long, wide] = self.shown_scrollbars();
+
+        [self.w - long as u16, self.h - wide as u16]
+    }
+
+    /// returns the greatest column offset, 0 unless this text has a horizontal scrollbar
+    /// whose lines are wider than the text
+    pub fn max_hscroll(&self) -> u16 {
+        self.stride().saturating_sub(self.view()[0])
+    }
+
+    /// scrolls the value by the given number of columns, negative values scroll back left
+    /// the offset is clamped between 0 and max_hscroll
+    /// returns whether the offset changed
+    pub fn hscroll_by(&mut self, columns: i16) -> bool {
+        let max = self.max_hscroll() as i32;
+        let hscroll = (self.hscroll as i32 + columns as i32).clamp(0, max) as u16;
+        let changed = hscroll != self.hscroll;
+        self.hscroll = hscroll;
+        if changed {
+            self.mark_dirty();
+        }
+
+        changed
+    }
+
+    // the width of the value's lines
+    pub(crate) fn stride(&self) -> u16 {
+        match &self.scrollbars[1] {
+            Some(bar) => bar.columns.max(self.w),
+            None => self.w,
+        }
+    }
+
+    // the number of value lines, up to the last one holding anything
+    pub(crate) fn lines(&self) -> u16 {
+        if self.stride() == 0 {
+            return 0;
+        }
+        let Some(last) = self.value.last_filled() else {
+            return 0;
+        };
+
+        (last / self.stride() as usize + 1) as u16
+    }
+
+    // the number of lines that are scrolled through, a list's items or a table's rows
+    // and its header
+    fn total_lines(&self) -> u16 {
+        match (&self.list, &self.table) {
+            (Some(list), _) => list.items().len() as u16,
+            (None, Some(table)) => table.rows().len() as u16 + 1,
+            (None, None) => self.lines(),
+        }
+    }
+
+    // the [view, total, offset] lines or columns of the scrollbar on the edge of axis
+    fn extent(&self, axis: Axis) -> [u16; 3] {
+        let [vw, vh] = self.view();
+        match axis {
+            Axis::Horizontal => [vw, self.stride(), self.hscroll],
+            Axis::Vertical => match (&self.list, &self.table) {
+                (Some(list), _) => [vh, self.total_lines(), list.offset() as u16],
+                (None, Some(table)) => [vh, self.total_lines(), table.offset() as u16],
+                (None, None) => [vh, self.total_lines(), self.scroll],
+            },
+        }
+    }
+
+    // whether the vertical and the horizontal scrollbars are shown,
+    // each one showing takes room from the other's direction
+    fn shown_scrollbars(&self) -> [bool; 2] {
+        let [v, h] = self.scrollbars.each_ref().map(Option::is_some);
+        let wide = h && self.stride() > self.w;
+        let long = v && self.total_lines() > self.h - wide as u16;
+        let wide = h && self.stride() > self.w - long as u16;
+
+        [long, wide]
+    }
+
+    // writes the shown scrollbars inside the area
+    pub(crate) fn render_scrollbars(&self, s: &mut String, caps: &Capabilities) {
+        let [long, wide] = self.shown_scrollbars();
+        let [vw, vh] = self.view();
+        if let (true, Some(bar)) = (long, &self.scrollbars[0]) {
+            let [view, total, offset] = self.extent(Axis::Vertical);
+            let [start, end] = thumb(vh, view, total, offset);
+            for y in 0..vh {
+                *s += &cup(self.ax0 + vw, self.ay0 + y);
+                bar.put(s, caps, (start..end).contains(&y));
+            }
+        }
+        if let (true, Some(bar)) = (wide, &self.scrollbars[1]) {
+            let [view, total, offset] = self.extent(Axis::Horizontal);
+            let [start, end] = thumb(vw, view, total, offset);
+            *s += &cup(self.ax0, self.ay0 + vh);
+            for x in 0..vw {
+                bar.put(s, caps, (start..end).contains(&x));
+            }
+        }
+        if long && wide {
+            *s += &format!("\x1b[0m{} ", cup(self.ax0 + vw, self.ay0 + vh));
+        }
+    }
+
+    // the scrollbar whose track holds the 0 based [x, y] cell and the cell of the track,
+    // if one is shown there
+    fn track_at(&self, x: u16, y: u16) -> Option<(Axis, u16)> {
+        let [long, wide] = self.shown_scrollbars();
+        let [vw, vh] = self.view();
+        let [x, y] = [
+            (x + 1).checked_sub(self.ax0)?,
+            (y + 1).checked_sub(self.ay0)?,
+        ];
+        match [x, y] {
+            [x, y] if long && x == vw && y < vh => Some((Axis::Vertical, y)),
+            [x, y] if wide && y == vh && x < vw => Some((Axis::Horizontal, x)),
+            _ => None,
+        }
+    }
+
+    // scrolls to where the cell at of the track on the edge of axis stands for,
+    // a list or a table selects the item there instead
+    // returns whether anything changed
+    fn scroll_to(&mut self, axis: Axis, at: u16) -> bool {
+        let [vw, vh] = self.view();
+        if axis == Axis::Horizontal {
+            let hscroll = offset_at(vw, self.max_hscroll(), at);
+            return self.hscroll_by(hscroll as i16 - self.hscroll as i16);
+        }
+        let last = self.total_lines().saturating_sub(1);
+        match (&self.list, &self.table) {
+            (Some(_), _) => self.select(offset_at(vh, last, at) as usize),
+            // the header is not a row
+            (None, Some(_)) => self.select_row(offset_at(vh, last.saturating_sub(1), at) as usize),
+            (None, None) => {
+                let scroll = offset_at(vh, self.max_scroll(), at);
+                self.scroll_by(scroll as i16 - self.scroll as i16)
+            }
+        }
+    }
+}
+
+impl Term {
+    // a left click on a scrollbar's track scrolls there and dragging keeps scrolling until
+    // the button is released, wherever the pointer goes
+    // returns the id of the scrolled text, or None if the event is left to Term::mouse
+    pub(crate) fn scrollbar_mouse(&mut self, event: &MouseEvent) -> Option<[u8; 3]> {
+        let [x, y] = [event.column, event.row];
+        match (event.kind, event.button, self.scrollbar_drag) {
+            (MouseKind::Drag, _, Some((id, axis))) => {
+                let text = self.text_mut(&id)?;
+                let [ax, ay] = [
+                    (x + 1).saturating_sub(text.ax0),
+                    (y + 1).saturating_sub(text.ay0),
+                ];
+                let at = if axis == Axis::Vertical { ay } else { ax };
+                text.scroll_to(axis, at);
+
+                Some(id)
+            }
+            (MouseKind::Up, ..) => {
+                self.scrollbar_drag = None;
+
+                None
+            }
+            (MouseKind::Down, MouseButton::Left, _) => {
+                let id = self.component_at(x, y).filter(|id| self.reachable(id))?;
+                let text = self.text_mut(&id)?;
+                let (axis, at) = text.track_at(x, y)?;
+                text.scroll_to(axis, at);
+                self.scrollbar_drag = Some((id, axis));
+
+                Some(id)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod scrollbar {
+    use super::*;
+    use crate::components::List;
+    use crate::inputs::keyboard::Modifiers;
+    use crate::space::{border::Border, padding::Padding};
+
+    // a 6x4 nonedit at the terminal's top left, 10 lines of 6 cells in its value
+    fn text() -> Text {
+        let value = (0..60)
+            .map(|idx| Some(char::from(b'a' + (idx / 6) as u8)))
+            .collect::<Vec<Option<char>>>();
+        let mut text = Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            1,
+            1,
+            6,
+            4,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        text.value = value.into();
+
+        text
+    }
+
+    fn click(kind: MouseKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            column,
+            row,
+            kind,
+            button: MouseButton::Left,
+            modifiers: Modifiers(0),
+        }
+    }
+
+    #[test]
+    fn thumb_size() {
+        // half of the lines are shown, scrolled to the middle
+        assert_eq!(thumb(4, 4, 8, 2), [1, 3]);
+        assert_eq!(thumb(4, 4, 8, 4), [2, 4]);
+        // a thumb is never smaller than a cell
+        assert_eq!(thumb(4, 4, 400, 0), [0, 1]);
+        assert_eq!(thumb(4, 4, 4, 0), [0, 4]);
+
+        let mut text = text();
+        assert_eq!(text.view(), [6, 4]);
+        text.set_scrollbar(Scrollbar::vertical());
+        assert_eq!(text.view(), [5, 4]);
+        assert_eq!(text.max_scroll(), 6);
+        // the cells under the scrollbar are not shown
+        assert_eq!(text.cell(5, 0), None);
+        assert_eq!(text.cell(4, 0), Some('a'));
+
+        text.scroll_by(6);
+        let mut s = String::new();
+        text.render_scrollbars(&mut s, &Capabilities::default());
+        assert!(s.ends_with("\x1b[4;6f\x1b[0m#"));
+        // the track falls back to ascii
+        assert!(s.starts_with("\x1b[1;6f\x1b[0m\x1b[2m|"));
+    }
+
+    #[test]
+    fn fits() {
+        let mut text = text();
+        text.value.truncate(24);
+        text.set_scrollbar(Scrollbar::vertical());
+        text.set_scrollbar(Scrollbar::horizontal(6));
+        assert_eq!(text.view(), [6, 4]);
+        let mut s = String::new();
+        text.render_scrollbars(&mut s, &Capabilities::default());
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn horizontal() {
+        let mut text = text();
+        // 5 lines of 12 cells
+        text.set_scrollbar(Scrollbar::horizontal(12));
+        assert_eq!(text.view(), [6, 3]);
+        assert_eq!(text.max_hscroll(), 6);
+        assert_eq!(text.cell(0, 1), Some('c'));
+        text.hscroll_by(7);
+        assert_eq!(text.hscroll, 6);
+        assert_eq!(text.cell(0, 0), Some('b'));
+        assert_eq!(thumb(6, 6, 12, 6), [3, 6]);
+    }
+
+    #[test]
+    fn mouse() {
+        let mut term = Term::new(0, 20, 8);
+        let mut c = super::super::Container::new([0, 0], 0, 0, 10, 6, Border::None, Padding::None);
+        let mut scrolled = text();
+        scrolled.set_scrollbar(Scrollbar::vertical());
+        c.items.push(scrolled);
+        let mut list = Text::input(
+            [0, 0, 2],
+            0,
+            4,
+            1,
+            5,
+            6,
+            2,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        list.set_list(List::new((0..5).map(|idx| idx.to_string()).collect()));
+        list.set_scrollbar(Scrollbar::vertical());
+        c.items.push(list);
+        term.containers.push(c);
+
+        // the bottom of the track scrolls all the way down
+        assert_eq!(term.mouse(&click(MouseKind::Down, 5, 3)), Some([0, 0, 1]));
+        assert_eq!(term.nonedit_ref(&[0, 0, 1]).unwrap().scroll, 6);
+        // the drag goes on off the track
+        term.mouse(&click(MouseKind::Drag, 12, 1));
+        assert_eq!(term.nonedit_ref(&[0, 0, 1]).unwrap().scroll, 2);
+        term.mouse(&click(MouseKind::Up, 12, 1));
+        term.mouse(&click(MouseKind::Drag, 5, 0));
+        assert_eq!(term.nonedit_ref(&[0, 0, 1]).unwrap().scroll, 2);
+
+        // a list selects the item there
+        term.mouse(&click(MouseKind::Down, 5, 5));
+        let list = term.input_ref(&[0, 0, 2]).unwrap().list().unwrap();
+        assert_eq!([list.selected(), list.offset()], [4, 3]);
+    }
+}
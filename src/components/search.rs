@@ -0,0 +1,316 @@
+use std::ops::Range;
+
+use crate::themes::Style;
+
+use super::Text;
+
+// the matches of the last Text::search, as ranges of value cells, so that they are found
+// again at their new place once the value is laid out on lines of another width,
+// and the styles they are highlighted in
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Search {
+    matches: Vec<Range<usize>>,
+    // the index of the current match in matches, see Text::next_match
+    current: Option<usize>,
+    style: String,
+    current_style: String,
+}
+
+impl Search {
+    fn new(matches: Vec<Range<usize>>, styles: [String; 2]) -> Self {
+        let [style, current_style] = styles;
+        Self {
+            matches,
+            current: None,
+            style,
+            current_style,
+        }
+    }
+}
+
+// the cells of value that are equal to the chars of pattern, empty cells match nothing
+// the matches don't overlap, each one starts after the end of the previous one
+fn find(value: &[Option<char>], pattern: &[char], ignore_case: bool) -> Vec<Range<usize>> {
+    let same = |c: char, p: char| match ignore_case {
+        true => c == p || c.to_lowercase().eq(p.to_lowercase()),
+        false => c == p,
+    };
+    let mut matches = vec![];
+    if pattern.is_empty() {
+        return matches;
+    }
+    let mut start = 0;
+    while start + pattern.len() <= value.len() {
+        let window = &value[start..start + pattern.len()];
+        let found = window
+            .iter()
+            .zip(pattern)
+            .all(|(c, p)| c.is_some_and(|c| same(c, *p)));
+        if found {
+            matches.push(start..start + pattern.len());
+            start += pattern.len();
+        } else {
+            start += 1;
+        }
+    }
+
+    matches
+}
+
+// searching
+impl Text {
+    /// finds every literal occurrence of pattern in this text's value and highlights them,
+    /// with ignore_case the letters match whatever their case is
+    /// returns the matches as ranges of value cells, the first cell of the value is 0
+    /// the matches are kept as they are until the next search or Text::clear_search,
+    /// they stay on the same chars when the text is resized, but not when the value changes
+    /// no match is the current one until Text::next_match or Text::prev_match is called
+    pub fn search(&mut self, pattern: &str, ignore_case: bool) -> Vec<Range<usize>> {
+        let pattern = pattern.chars().collect::<Vec<char>>();
+        let matches = find(&self.value.to_vec(), &pattern, ignore_case);
+        let styles = match &self.search {
+            Some(search) => [search.style.clone(), search.current_style.clone()],
+            None => [Style::new().reverse(), Style::new().reverse().bold()].map(|s| s.style()),
+        };
+        self.search = Some(Search::new(matches.clone(), styles));
+        self.mark_dirty();
+
+        matches
+    }
+
+    /// changes the styles the matches and the current match are highlighted in,
+    /// they are reversed by default and the current one is bold too
+    /// the styles are kept from a search to the next one
+    pub fn set_search_styles(&mut self, matches: &Style, current: &Style) {
+        let search = self
+            .search
+            .get_or_insert_with(|| Search::new(vec![], [String::new(), String::new()]));
+        search.style = matches.style();
+        search.current_style = current.style();
+        self.mark_dirty();
+    }
+
+    /// the matches of the last search, see Text::search
+    pub fn matches(&self) -> &[Range<usize>] {
+        self.search.as_ref().map_or(&[], |search| &search.matches)
+    }
+
+    /// the current match, see Text::next_match
+    pub fn current_match(&self) -> Option<Range<usize>> {
+        let search = self.search.as_ref()?;
+
+        search.current.map(|idx| search.matches[idx].clone())
+    }
+
+    /// makes the match after the current one the current match, the first one comes
+    /// after the last one, and scrolls the text so that it is shown
+    /// returns the new current match, None if the last search found nothing
+    pub fn next_match(&mut self) -> Option<Range<usize>> {
+        self.step_match(true)
+    }
+
+    /// makes the match before the current one the current match, the last one comes
+    /// before the first one, and scrolls the text so that it is shown
+    /// returns the new current match, None if the last search found nothing
+    pub fn prev_match(&mut self) -> Option<Range<usize>> {
+        self.step_match(false)
+    }
+
+    /// removes the matches of the last search and their highlights
+    pub fn clear_search(&mut self) {
+        if let Some(search) = &mut self.search {
+            search.matches.clear();
+            search.current = None;
+            self.mark_dirty();
+        }
+    }
+
+    // moves the matches to the cells f gives them, the ones it gives None are dropped
+    // and the current match is lost if it is one of them
+    pub(crate) fn remap_matches(&mut self, f: impl Fn(&Range<usize>) -> Option<Range<usize>>) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        let current = search.current.take();
+        let mut matches = vec![];
+        for (idx, found) in search.matches.iter().enumerate() {
+            if let Some(found) = f(found) {
+                if current == Some(idx) {
+                    search.current = Some(matches.len());
+                }
+                matches.push(found);
+            }
+        }
+        search.matches = matches;
+    }
+
+    fn step_match(&mut self, forward: bool) -> Option<Range<usize>> {
+        let search = self.search.as_mut()?;
+        let len = search.matches.len();
+        if len == 0 {
+            return None;
+        }
+        let current = match (search.current, forward) {
+            (None, true) => 0,
+            (None, false) => len - 1,
+            (Some(idx), true) => (idx + 1) % len,
+            (Some(idx), false) => (idx + len - 1) % len,
+        };
+        search.current = Some(current);
+        let found = search.matches[current].clone();
+        self.scroll_to_cell(found.start);
+        self.mark_dirty();
+
+        Some(found)
+    }
+
+    // scrolls the least needed for the value cell idx to be shown
+    fn scroll_to_cell(&mut self, idx: usize) {
+        let stride = self.stride() as usize;
+        if stride == 0 {
+            return;
+        }
+        let [vw, vh] = self.view().map(usize::from);
+        let [x, y] = [idx % stride, idx / stride];
+        let [scroll, hscroll] = [self.scroll as usize, self.hscroll as usize];
+        if y < scroll {
+            self.scroll_by(y as i16 - scroll as i16);
+        } else if y >= scroll + vh {
+            self.scroll_by((y + 1 - vh - scroll) as i16);
+        }
+        if x < hscroll {
+            self.hscroll_by(x as i16 - hscroll as i16);
+        } else if x >= hscroll + vw {
+            self.hscroll_by((x + 1 - vw - hscroll) as i16);
+        }
+    }
+
+    // the matches on the h rows shown, as highlights of the text, a match that goes on
+    // past the end of a row goes on at the start of the next one
+    pub(crate) fn search_highlights(&self) -> Vec<(u16, [u16; 2], &str)> {
+        let Some(search) = &self.search else {
+            return vec![];
+        };
+        let stride = self.stride() as usize;
+        let [vw, vh] = self.view().map(usize::from);
+        let [scroll, hscroll] = [self.scroll as usize, self.hscroll as usize];
+        let mut highlights = vec![];
+        if stride == 0 {
+            return highlights;
+        }
+        for (idx, found) in search.matches.iter().enumerate() {
+            let style = match search.current == Some(idx) {
+                true => search.current_style.as_str(),
+                false => search.style.as_str(),
+            };
+            let mut start = found.start;
+            while start < found.end {
+                let [x, y] = [start % stride, start / stride];
+                let end = found.end.min(start - x + stride);
+                let [x0, x1] = [x.max(hscroll), (end - start + x).min(hscroll + vw)];
+                if (scroll..scroll + vh).contains(&y) && x0 < x1 {
+                    let cells = [x0 - hscroll, x1 - hscroll].map(|x| x as u16);
+                    highlights.push(((y - scroll) as u16, cells, style));
+                }
+                start = end;
+            }
+        }
+
+        highlights
+    }
+}
+
+#[cfg(test)]
+mod search {
+    use super::*;
+    use crate::space::{border::Border, padding::Padding};
+
+    // a 6x2 nonedit holding value, laid out on its rows
+    fn pane(value: &str) -> Text {
+        let mut text = Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            0,
+            0,
+            6,
+            2,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        text.value = value
+            .chars()
+            .map(|c| (c != '_').then_some(c))
+            .collect::<Vec<_>>()
+            .into();
+
+        text
+    }
+
+    #[test]
+    fn found() {
+        let mut text = pane("ab_Ab_abab__");
+        assert_eq!(text.search("ab", false), vec![0..2, 6..8, 8..10]);
+        assert_eq!(text.search("AB", true), vec![0..2, 3..5, 6..8, 8..10]);
+        assert_eq!(text.search("aba", false), vec![6..9]);
+        // the empty cells match nothing
+        assert!(text.search("b_A", false).is_empty());
+        assert!(text.search("", false).is_empty());
+        assert!(text.attributes.contains("dirty"));
+
+        text.search("ab", true);
+        text.clear_search();
+        assert!(text.matches().is_empty());
+        assert!(text.search_highlights().is_empty());
+    }
+
+    #[test]
+    fn cycled() {
+        // 4 rows, 2 of them shown
+        let mut text = pane("ab____cd____ab____ab____");
+        text.search("ab", false);
+        assert_eq!(text.current_match(), None);
+        let style = Style::new().reverse().style();
+        let current = Style::new().reverse().bold().style();
+        assert_eq!(text.search_highlights(), vec![(0, [0, 2], style.as_str())]);
+
+        assert_eq!(text.next_match(), Some(0..2));
+        assert_eq!(
+            text.search_highlights(),
+            vec![(0, [0, 2], current.as_str())]
+        );
+        // the third row is scrolled to
+        assert_eq!(text.next_match(), Some(12..14));
+        assert_eq!(text.scroll, 1);
+        assert_eq!(
+            text.search_highlights(),
+            vec![(1, [0, 2], current.as_str())]
+        );
+        assert_eq!(text.next_match(), Some(18..20));
+        assert_eq!(text.scroll, 2);
+        assert_eq!(text.next_match(), Some(0..2));
+        assert_eq!(text.scroll, 0);
+        assert_eq!(text.prev_match(), Some(18..20));
+        assert_eq!(text.scroll, 2);
+
+        text.search("xy", false);
+        assert_eq!(text.next_match(), None);
+    }
+
+    #[test]
+    fn rewrapped() {
+        let mut text = pane("__abcdef____");
+        text.set_search_styles(&Style::new().bold(), &Style::new().underline());
+        text.search("cdef", false);
+        let bold = Style::new().bold().style();
+        // the match goes on on the second row
+        let expected = vec![(0, [4, 6], bold.as_str()), (1, [0, 2], bold.as_str())];
+        assert_eq!(text.search_highlights(), expected);
+
+        text.w = 4;
+        let expected = vec![(1, [0, 4], bold.as_str())];
+        assert_eq!(text.search_highlights(), expected);
+        assert_eq!(text.matches(), [4..8]);
+    }
+}
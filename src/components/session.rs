@@ -0,0 +1,342 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{ComponentTreeError, Term, Text};
+
+/// whether a component is shown, see Term::hide and Term::hide_collapsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Visibility {
+    Shown,
+    Hidden,
+    Collapsed,
+}
+
+/// what a Term::save_state keeps of a container
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ContainerState {
+    pub id: [u8; 2],
+    pub visibility: Visibility,
+}
+
+/// what a Term::save_state keeps of a text
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TextState {
+    pub id: [u8; 3],
+    pub visibility: Visibility,
+    /// the value typed into an input, see Text::value_string, None for a nonedit,
+    /// whose value is the app's to set
+    pub value: Option<String>,
+    /// the [cx, cy] cell of the cursor
+    pub cursor: [u16; 2],
+    /// the lines scrolled past the top, see Text::scroll_by
+    pub scroll: u16,
+    /// the columns scrolled past the left, see Text::hscroll_by
+    pub hscroll: u16,
+    /// the active tab of a text showing tabs, see Term::switch_tab
+    pub tab: Option<usize>,
+}
+
+/// the state the user left the components of a Term in, see Term::save_state,
+/// with the serde feature it can be written to a file and read back on the next run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TermState {
+    pub containers: Vec<ContainerState>,
+    pub texts: Vec<TextState>,
+    pub focused: Option<[u8; 3]>,
+}
+
+/// what Term::restore_state could not put back
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestoreWarning {
+    /// there is no container with id anymore
+    MissingContainer { id: [u8; 2] },
+    /// there is no text with id anymore
+    MissingText { id: [u8; 3] },
+    /// the saved value of the input with id could not be put back whole, it does not fit
+    /// the input's area anymore or its filter rejects some of it, see Text::set_filter,
+    /// the input keeps the value it had
+    ValueDoesNotFit { id: [u8; 3] },
+    /// the text with id does not show tabs anymore, or not as many
+    MissingTab { id: [u8; 3], tab: usize },
+    /// the text with id could not be focused again
+    NotFocused { id: [u8; 3], error: ComponentTreeError },
+}
+
+fn visibility(hidden: bool, collapsed: bool) -> Visibility {
+    match (hidden, collapsed) {
+        (_, true) => Visibility::Collapsed,
+        (true, false) => Visibility::Hidden,
+        (false, false) => Visibility::Shown,
+    }
+}
+
+impl Text {
+    fn state(&self) -> TextState {
+        TextState {
+            id: self.id,
+            visibility: visibility(self.is_hidden(), self.is_collapsed()),
+            value: self.is_input().then(|| self.value_string()),
+            cursor: [self.cx, self.cy],
+            scroll: self.scroll,
+            hscroll: self.hscroll,
+            tab: self.tabs().map(|tabs| tabs.active()),
+        }
+    }
+}
+
+impl Term {
+    /// the state of the components of this Term, their visibility, the values typed into
+    /// the inputs, the cursors, the scroll offsets, the active tabs and the focus, by id,
+    /// to be put back by Term::restore_state, e.g. when the app is opened again
+    pub fn save_state(&self) -> TermState {
+        let containers = self.containers.iter().map(|c| ContainerState {
+            id: c.id,
+            visibility: visibility(c.is_hidden(), c.is_collapsed()),
+        });
+        let texts = self.containers.iter().flat_map(|c| c.items.iter());
+
+        TermState {
+            containers: containers.collect(),
+            texts: texts.map(Text::state).collect(),
+            focused: self.focused,
+        }
+    }
+
+    /// puts back what state has of the components this Term still has with the same ids,
+    /// through the methods the app would call, so that the components are marked dirty,
+    /// the filters and the validation see the values and the focus goes to a text that can
+    /// be focused, see Term::switch_tab, Term::hide, Term::show, Text::scroll_by and Term::focus
+    /// the tabs are switched first, the texts' own state then the focus are put back after
+    /// returns what could not be put back, in the order of state
+    pub fn restore_state(&mut self, state: TermState) -> Vec<RestoreWarning> {
+        let mut warnings = vec![];
+        for text in state.texts.iter().filter(|t| t.tab.is_some()) {
+            let tab = text.tab.unwrap();
+            let Some(tabs) = self.text_ref(&text.id).map(Text::tabs) else {
+                continue;
+            };
+            match tabs.map(|tabs| tabs.tabs().len()) {
+                Some(len) if tab < len => _ = self.switch_tab(&text.id, tab),
+                _ => warnings.push(RestoreWarning::MissingTab { id: text.id, tab }),
+            }
+        }
+        for cont in &state.containers {
+            if self.container_ref(&cont.id).is_none() {
+                warnings.push(RestoreWarning::MissingContainer { id: cont.id });
+                continue;
+            }
+            self.restore_visibility(&cont.id, cont.visibility);
+        }
+        for text in &state.texts {
+            if self.text_ref(&text.id).is_none() {
+                warnings.push(RestoreWarning::MissingText { id: text.id });
+                continue;
+            }
+            if let Some(warning) = self.restore_text(text) {
+                warnings.push(warning);
+            }
+        }
+        if let Some(id) = state.focused.filter(|id| self.text_ref(id).is_some()) {
+            if let Err(error) = self.focus(&id) {
+                warnings.push(RestoreWarning::NotFocused { id, error });
+            }
+        }
+
+        warnings
+    }
+
+    fn restore_visibility(&mut self, id: &[u8], visibility: Visibility) {
+        _ = match visibility {
+            Visibility::Shown => match *id {
+                [t, c] => match self.container_ref(&[t, c]).unwrap().is_hidden() {
+                    true => self.show(id),
+                    false => Ok(()),
+                },
+                [t, c, i] => match self.text_ref(&[t, c, i]).unwrap().is_hidden() {
+                    true => self.show(id),
+                    false => Ok(()),
+                },
+                _ => Ok(()),
+            },
+            Visibility::Hidden => self.hide(id),
+            Visibility::Collapsed => self.hide_collapsed(id),
+        };
+    }
+
+    // puts back the visibility, the value, the cursor and the scroll offsets of a text
+    fn restore_text(&mut self, state: &TextState) -> Option<RestoreWarning> {
+        let id = state.id;
+        self.restore_visibility(&id, state.visibility);
+        let before = self.validated_value(&id);
+        let text = self.text_mut(&id).unwrap();
+        let mut warning = None;
+        if let Some(value) = state.value.as_ref().filter(|_| text.is_input()) {
+            let kept = text.value.clone();
+            if text.value_string() != *value {
+                text.replace_value(value);
+            }
+            // a value that doesn't come back whole is not put back at all
+            if text.value_string() != *value {
+                text.value = kept;
+                warning = Some(RestoreWarning::ValueDoesNotFit { id });
+            }
+        }
+        let [cx, cy] = state.cursor;
+        if [text.cx, text.cy] != [cx, cy] {
+            let cx = cx.min(text.w.saturating_sub(1));
+            text.set_cursor_index(cy as usize * text.w as usize + cx as usize);
+        }
+        // a saved offset too far from the current one only scrolls as far as a step goes
+        let delta = |to: u16, from: u16| {
+            (to as i32 - from as i32).clamp(i16::MIN as i32, i16::MAX as i32) as i16
+        };
+        text.scroll_by(delta(state.scroll, text.scroll));
+        text.hscroll_by(delta(state.hscroll, text.hscroll));
+        self.revalidate(&id, before);
+
+        warning
+    }
+}
+
+#[cfg(test)]
+mod session {
+    use super::*;
+    use crate::components::{Container, Tabs, TextKind, Validation};
+    use crate::space::{border::Border, padding::Padding};
+
+    // tabs in container 0 switching between containers 1 and 2, 2 inputs in 1,
+    // a multiline nonedit in 2 and a hidden container 3
+    fn app() -> Term {
+        let mut term = Term::new(0, 40, 12);
+        for cid in 0..4u8 {
+            let y = [0, 1, 1, 8][cid as usize];
+            let mut c = Container::new([0, cid], 0, y, 20, 4, Border::None, Padding::None);
+            let texts: &[(TextKind, u16)] = match cid {
+                0 => &[(TextKind::NonEdit, 1)],
+                1 => &[(TextKind::Input, 1), (TextKind::Input, 1)],
+                2 => &[(TextKind::NonEdit, 3)],
+                _ => &[(TextKind::Input, 1)],
+            };
+            for (iid, (kind, h)) in texts.iter().enumerate() {
+                let iid = iid as u8;
+                let text = Text::of_kind(
+                    *kind,
+                    [0, cid, iid],
+                    0,
+                    iid as u16,
+                    0,
+                    y + iid as u16,
+                    20,
+                    *h,
+                    &[],
+                    Border::None,
+                    Padding::None,
+                );
+                c.items.push(text);
+            }
+            term.containers.push(c);
+        }
+        let tabs = Tabs::new(vec![("one".into(), [0, 1]), ("two".into(), [0, 2])]);
+        term.set_tabs(&[0, 0, 0], tabs);
+        let log = term.nonedit_mut(&[0, 2, 0]).unwrap();
+        log.attributes.insert("multiline");
+        (0..10).for_each(|n| log.append_line(&format!("line {}", n)));
+
+        term
+    }
+
+    // what the user did before closing the app
+    fn used() -> Term {
+        let mut term = app();
+        _ = term.focus(&[0, 1, 1]);
+        term.paste("ragout");
+        term.text_mut(&[0, 1, 0]).unwrap().replace_value("name");
+        term.nonedit_mut(&[0, 2, 0]).unwrap().scroll_by(4);
+        term.switch_tab(&[0, 0, 0], 1);
+        term.hide(&[0, 3]).unwrap();
+        _ = term.focus(&[0, 2, 0]);
+
+        term
+    }
+
+    #[test]
+    fn round_trip() {
+        let state = used().save_state();
+        assert_eq!(state.texts[1].value.as_deref(), Some("name"));
+        assert_eq!(state.texts[2].cursor, [6, 0]);
+        assert_eq!(state.texts[3].value, None);
+
+        let mut term = app();
+        term.set_validation(&[0, 1, 1], Validation::Valid).unwrap();
+        assert_eq!(term.restore_state(state.clone()), []);
+        assert_eq!(term.save_state(), state);
+        assert_eq!(term.input_ref(&[0, 1, 1]).unwrap().value_string(), "ragout");
+        assert_eq!(term.nonedit_ref(&[0, 2, 0]).unwrap().scroll, 4);
+        assert!(term.container_ref(&[0, 1]).unwrap().is_hidden());
+        assert!(term.container_ref(&[0, 3]).unwrap().is_hidden());
+        // the value went through the editing paths
+        assert_eq!(term.validation(&[0, 1, 1]), Some(&Validation::Unknown));
+        assert!(term
+            .input_ref(&[0, 1, 1])
+            .unwrap()
+            .attributes
+            .contains("dirty"));
+    }
+
+    #[test]
+    fn warnings() {
+        let mut state = used().save_state();
+        state.texts[1].value = Some("a value that is too long for 20 cells".into());
+        state.texts[0].tab = Some(5);
+        state.texts.push(TextState {
+            id: [0, 9, 0],
+            ..state.texts[1].clone()
+        });
+
+        let mut term = app();
+        term.containers.retain(|c| c.id != [0, 2]);
+        let warnings = term.restore_state(state);
+        assert_eq!(
+            warnings,
+            [
+                RestoreWarning::MissingTab { id: [0, 0, 0], tab: 5 },
+                RestoreWarning::MissingContainer { id: [0, 2] },
+                RestoreWarning::ValueDoesNotFit { id: [0, 1, 0] },
+                RestoreWarning::MissingText { id: [0, 2, 0] },
+                RestoreWarning::MissingText { id: [0, 9, 0] },
+            ]
+        );
+        assert_eq!(term.input_ref(&[0, 1, 0]).unwrap().value_string(), "");
+    }
+
+    #[test]
+    fn out_of_range() {
+        let mut state = used().save_state();
+        let scrolled = used().nonedit_ref(&[0, 2, 0]).unwrap().clone();
+        state.texts[2].cursor = [20, 0];
+        state.texts[3].scroll = 40000;
+
+        let mut term = app();
+        term.restore_state(state);
+        let input = term.input_ref(&[0, 1, 1]).unwrap();
+        assert_eq!([input.cx, input.cy], [19, 0]);
+        // the log scrolls down as far as it goes rather than wrapping around
+        let mut bottom = scrolled;
+        bottom.scroll_by(i16::MAX);
+        assert_eq!(term.nonedit_ref(&[0, 2, 0]).unwrap().scroll, bottom.scroll);
+        assert!(bottom.scroll > 4);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let state = used().save_state();
+        let json = serde_json::to_string(&state).unwrap();
+
+        assert_eq!(serde_json::from_str::<TermState>(&json).unwrap(), state);
+    }
+}
@@ -0,0 +1,232 @@
+use std::time::{Duration, Instant};
+
+use crate::render_pipeline::cup;
+
+use super::grapheme::WIDE;
+use super::{Term, Text};
+
+// the cursor the library draws, see Term::set_soft_cursor
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SoftCursor {
+    period: Option<Duration>,
+    // whether the cell is shown inverted in this phase of the blink
+    on: bool,
+    // when the phase last changed, or a key last came in
+    since: Option<Instant>,
+    // the text and the [x, y] cell of the term the cursor was last drawn on
+    drawn: Option<([u8; 3], [u16; 2])>,
+    // whether the phase changed since the cursor was last drawn
+    due: bool,
+}
+
+impl Term {
+    /// draws the cell under the focused input's cursor in reverse video, for the terminals
+    /// that don't blink their cursor or while it is hidden, the cell goes from inverted
+    /// to normal and back every period, on the Ticks, see Term::tick
+    /// it stays inverted while keys come in and goes away with the focus
+    /// None, the default, turns it off, the next render puts the cell back as it was
+    pub fn set_soft_cursor(&mut self, period: Option<Duration>) {
+        self.soft_cursor.period = period;
+        self.soft_cursor.on = period.is_some();
+        self.soft_cursor.since = Some(Instant::now());
+        // turned off, it is due if it is still drawn
+        self.soft_cursor.due = period.is_some();
+    }
+
+    /// how long the soft cursor takes to blink, None when it is off, see Term::set_soft_cursor
+    pub fn soft_cursor(&self) -> Option<Duration> {
+        self.soft_cursor.period
+    }
+
+    // inverts the soft cursor, or puts it back, once a period went by since it last changed
+    pub(crate) fn blink(&mut self) {
+        let Some(period) = self.soft_cursor.period else {
+            return;
+        };
+        let now = Instant::now();
+        let since = self.soft_cursor.since.unwrap_or(now);
+        if now.duration_since(since) < period {
+            return;
+        }
+        self.soft_cursor.on = !self.soft_cursor.on;
+        self.soft_cursor.since = Some(now);
+        self.soft_cursor.due = self.cursor_cell().is_some();
+    }
+
+    // keeps the soft cursor inverted for a period from now, it doesn't blink while keys come in
+    pub(crate) fn hold_cursor(&mut self) {
+        if self.soft_cursor.period.is_none() {
+            return;
+        }
+        self.soft_cursor.due |= !self.soft_cursor.on;
+        self.soft_cursor.on = true;
+        self.soft_cursor.since = Some(Instant::now());
+    }
+
+    // the focused input and the [x, y] term cell its cursor is shown on,
+    // None while the soft cursor is off
+    fn cursor_cell(&self) -> Option<([u8; 3], [u16; 2])> {
+        self.soft_cursor.period?;
+        let id = self.focused.filter(|id| self.has_input(id))?;
+        let text = self.text_ref(&id).filter(|t| !t.is_hidden())?;
+        self.container_ref(&text.parent())
+            .filter(|c| !c.is_hidden())?;
+        let [vw, vh] = text.view();
+        let x = text.cx.checked_sub(text.hscroll).filter(|x| *x < vw)?;
+        let y = text.cy.checked_sub(text.scroll).filter(|y| *y < vh)?;
+
+        Some((id, [text.ax0 + x, text.ay0 + y]))
+    }
+
+    // the cell the soft cursor is to be drawn on in this phase of the blink
+    fn cursor_target(&self) -> Option<([u8; 3], [u16; 2])> {
+        self.cursor_cell().filter(|_| self.soft_cursor.on)
+    }
+
+    // whether the soft cursor has to be drawn again, see Term::is_dirty
+    pub(crate) fn cursor_due(&self) -> bool {
+        self.soft_cursor.due || self.soft_cursor.drawn != self.cursor_target()
+    }
+
+    // whether the row y of the text with id is rendered by this dirty frame
+    fn redrawn(&self, id: &[u8; 3], y: u16) -> bool {
+        let container = self.container_ref(&[id[0], id[1]]);
+        let text = self.text_ref(id).filter(|t| t.attributes.contains("dirty"));
+
+        container.is_some_and(|c| c.attributes.contains("dirty"))
+            || text.is_some_and(|t| t.patched.as_ref().map_or(true, |rows| rows.contains(&y)))
+    }
+
+    // the bytes that draw the [x, y] cell of the text with id, inverted or not,
+    // nothing when the cell is not in the text's area anymore
+    fn cursor_cell_frame(&self, id: &[u8; 3], [x, y]: [u16; 2], inverted: bool) -> String {
+        let Some(text) = self.text_ref(id) else {
+            return String::new();
+        };
+        let (Some(col), Some(row)) = (x.checked_sub(text.ax0), y.checked_sub(text.ay0)) else {
+            return String::new();
+        };
+        if col >= text.w || row >= text.h {
+            return String::new();
+        }
+        let caps = &self.capabilities;
+        let mut c = String::new();
+        match text.cell(col, row) {
+            Some(WIDE) | None => c.push(' '),
+            Some(shown) => text.clusters.push(&mut c, caps.adapt_char(shown)),
+        }
+        let invert = if inverted { "\x1b[7m" } else { "" };
+
+        format!(
+            "{}{}{}{}\x1b[0m",
+            cup(x, y),
+            caps.adapt_sgr(text.value_style()),
+            invert,
+            c
+        )
+    }
+
+    // the bytes that put back the cell the soft cursor was drawn on and draw it where it is
+    // now, the terminal cursor goes back to its cell after them
+    // a full frame drew every cell anew, a dirty one only the rows of the dirty components
+    pub(crate) fn soft_cursor_frame(&mut self, full: bool) -> String {
+        let target = self.cursor_target();
+        let drawn = std::mem::replace(&mut self.soft_cursor.drawn, target);
+        let due = std::mem::take(&mut self.soft_cursor.due);
+        let mut s = String::new();
+        let row = |id: &[u8; 3], y: u16| self.text_ref(id).map_or(0, |t| y.saturating_sub(t.ay0));
+        if let Some((id, cell)) = drawn.filter(|drawn| Some(*drawn) != target) {
+            if !full && !self.redrawn(&id, row(&id, cell[1])) {
+                s += &self.cursor_cell_frame(&id, cell, false);
+            }
+        }
+        if let Some((id, cell)) = target {
+            if full || due || drawn != target || self.redrawn(&id, row(&id, cell[1])) {
+                s += &self.cursor_cell_frame(&id, cell, true);
+            }
+        }
+        if !s.is_empty() {
+            s += &cup(self.cx, self.cy);
+        }
+
+        s
+    }
+}
+
+#[cfg(test)]
+mod soft_cursor {
+    use super::*;
+    use crate::components::Container;
+    use crate::space::{border::Border, padding::Padding};
+
+    // a focused 6x1 input at [2, 1] holding "ab", with a soft cursor that blinks on every tick
+    fn term() -> Term {
+        let mut term = Term::new(0, 10, 3);
+        let mut c = Container::new([0, 0], 0, 0, 10, 3, Border::None, Padding::None);
+        c.items.push(Text::input(
+            [0, 0, 0],
+            2,
+            1,
+            2,
+            1,
+            6,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        ));
+        term.containers.push(c);
+        term.focused = Some([0, 0, 0]);
+        term.paste("ab");
+        term.input_mut(&[0, 0, 0]).unwrap().cx = 1;
+        term.set_soft_cursor(Some(Duration::ZERO));
+
+        term
+    }
+
+    fn render(term: &mut Term) -> String {
+        let mut out = vec![];
+        term.render_dirty_to(&mut out).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn blinks() {
+        let mut term = term();
+        assert!(term.is_dirty());
+        let out = render(&mut term);
+        assert!(out.contains(&cup(3, 1)) && out.contains("\x1b[7mb\x1b[0m"));
+        assert!(!term.is_dirty());
+
+        // only the cell is drawn again, not inverted
+        term.tick();
+        assert!(term.is_dirty());
+        let out = render(&mut term);
+        assert!(out.starts_with(&cup(3, 1)));
+        assert!(out.ends_with(&format!("b\x1b[0m{}", cup(term.cx, term.cy))));
+        assert!(!out.contains("\x1b[7m"));
+        term.tick();
+        assert!(render(&mut term).contains("\x1b[7mb"));
+    }
+
+    #[test]
+    fn held() {
+        let mut term = term();
+        render(&mut term);
+        term.tick();
+        // the paste inverts it again, on the cell the cursor moved to
+        term.paste("c");
+        let out = render(&mut term);
+        assert!(out.contains(&cup(4, 1)) && out.contains("\x1b[7mb"));
+
+        term.focused = None;
+        assert!(term.cursor_due());
+        assert!(!render(&mut term).contains("\x1b[7m"));
+
+        term.set_soft_cursor(None);
+        term.focused = Some([0, 0, 0]);
+        assert!(!term.cursor_due());
+        assert_eq!(term.soft_cursor(), None);
+    }
+}
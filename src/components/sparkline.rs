@@ -0,0 +1,187 @@
+use super::Text;
+
+/// the block chars a Sparkline draws its samples with, from the lowest to the highest
+pub const BARS: &[char] = &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// a row of bars standing for a series of samples, one sample per cell,
+/// shown in the first row of a Text, see Text::set_sparkline
+/// the bars go from 0, or from the lowest sample if it is negative, to the highest sample,
+/// and a NaN sample is left blank
+/// when there are more samples than cells, the last ones are shown unless the samples are
+/// downsampled, see Sparkline::downsample
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sparkline {
+    data: Vec<f64>,
+    // whether the samples are averaged into the cells instead of only the last ones being shown
+    downsample: bool,
+}
+
+impl Sparkline {
+    /// makes a new Sparkline of the samples in data
+    pub fn new(data: &[f64]) -> Self {
+        Self {
+            data: data.to_vec(),
+            downsample: false,
+        }
+    }
+
+    /// whether all the samples are averaged into the cells, as many per cell,
+    /// when there are more samples than cells
+    pub fn downsample(&mut self, downsample: bool) {
+        self.downsample = downsample;
+    }
+
+    /// the samples
+    pub fn data(&self) -> &[f64] {
+        &self.data
+    }
+
+    // the samples shown in w cells
+    fn shown(&self, w: usize) -> Vec<f64> {
+        if self.data.len() <= w || w == 0 {
+            return self.data.clone();
+        }
+        if !self.downsample {
+            return self.data[self.data.len() - w..].to_vec();
+        }
+
+        // the samples from idx * len / w to (idx + 1) * len / w go in the cell at idx
+        let len = self.data.len();
+        (0..w)
+            .map(|idx| {
+                let samples = &self.data[idx * len / w..(idx + 1) * len / w];
+                let finite = samples.iter().filter(|s| s.is_finite());
+                let count = finite.clone().count();
+                match count {
+                    0 => f64::NAN,
+                    count => finite.sum::<f64>() / count as f64,
+                }
+            })
+            .collect()
+    }
+
+    // the cells of the first row of a w wide text
+    fn cells(&self, w: u16) -> Vec<Option<char>> {
+        let shown = self.shown(w as usize);
+        let finite = shown.iter().copied().filter(|s| s.is_finite());
+        let low = finite.clone().fold(0.0, f64::min);
+        let high = finite.fold(f64::MIN, f64::max);
+
+        shown
+            .iter()
+            .map(|sample| {
+                if !sample.is_finite() {
+                    return None;
+                }
+                let level = match high - low {
+                    range if range > 0.0 => (sample - low) / range * (BARS.len() - 1) as f64,
+                    _ => 0.0,
+                };
+
+                Some(BARS[(level.round() as usize).min(BARS.len() - 1)])
+            })
+            .collect()
+    }
+}
+
+// sparklines
+impl Text {
+    /// shows sparkline in the first row of this text, replacing its value
+    pub fn set_sparkline(&mut self, sparkline: Sparkline) {
+        self.sparkline = Some(sparkline);
+        self.sync_sparkline();
+    }
+
+    /// the sparkline this text shows, if it shows one
+    pub fn sparkline(&self) -> Option<&Sparkline> {
+        self.sparkline.as_ref()
+    }
+
+    /// replaces the samples of the sparkline this text shows
+    /// returns false if the text shows no sparkline
+    pub fn set_data(&mut self, data: &[f64]) -> bool {
+        let Some(sparkline) = &mut self.sparkline else {
+            return false;
+        };
+        sparkline.data = data.to_vec();
+        self.sync_sparkline();
+
+        true
+    }
+
+    // writes the bars to the first row
+    fn sync_sparkline(&mut self) {
+        let Some(sparkline) = &self.sparkline else {
+            return;
+        };
+        let mut cells = vec![None; self.w as usize * self.h as usize];
+        cells
+            .iter_mut()
+            .zip(sparkline.cells(self.w))
+            .for_each(|(cell, c)| *cell = c);
+        self.value = cells.into();
+        self.scroll = 0;
+        self.mark_dirty();
+    }
+}
+
+#[cfg(test)]
+mod sparkline {
+    use super::*;
+    use crate::console::Capabilities;
+    use crate::space::{border::Border, padding::Padding};
+
+    fn text(w: u16) -> Text {
+        Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            1,
+            1,
+            w,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        )
+    }
+
+    fn shown(text: &Text) -> String {
+        text.value.iter().map(|c| c.unwrap_or(' ')).collect()
+    }
+
+    #[test]
+    fn bars() {
+        let mut text = text(8);
+        text.set_sparkline(Sparkline::new(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]));
+        assert_eq!(shown(&text), "▁▂▃▄▅▆▇█");
+        assert!(text.set_data(&[2.0, f64::NAN, 2.0]));
+        assert_eq!(shown(&text), "█ █     ");
+        text.set_data(&[]);
+        assert_eq!(shown(&text).trim(), "");
+        text.set_data(&[0.0, 0.0]);
+        assert_eq!(shown(&text).trim(), "▁▁");
+        // negative samples lower the bottom of the bars
+        text.set_data(&[-7.0, 0.0, 7.0]);
+        assert_eq!(shown(&text).trim(), "▁▅█");
+
+        let ascii = BARS.iter().map(|c| Capabilities::default().adapt_char(*c));
+        assert_eq!(ascii.collect::<String>(), "_.:-=+*#");
+    }
+
+    #[test]
+    fn more_samples_than_cells() {
+        let data = (0..8).map(f64::from).collect::<Vec<f64>>();
+        let mut text = text(4);
+        text.set_sparkline(Sparkline::new(&data));
+        // the last 4 samples
+        assert_eq!(shown(&text), "▅▆▇█");
+
+        let mut sparkline = Sparkline::new(&data);
+        sparkline.downsample(true);
+        text.set_sparkline(sparkline);
+        // the averages of 2 samples at a time, 0.5, 2.5, 4.5 and 6.5
+        assert_eq!(shown(&text), "▂▄▆█");
+        assert!(!super::super::Text::default().set_data(&[1.0]));
+    }
+}
@@ -0,0 +1,213 @@
+use super::{Term, Text};
+
+/// the braille dots frames
+pub const BRAILLE: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// the ascii frames, for terminals that can't show braille
+pub const ASCII: &[char] = &['|', '/', '-', '\\'];
+
+// what a spinner shows
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum State {
+    // nothing, before the first start
+    #[default]
+    Idle,
+    Spinning,
+    // the char stop was given
+    Stopped(char),
+}
+
+/// an animation for operations of unknown length, shown in the first cell of a Text,
+/// see Text::set_spinner
+/// it goes to its next frame every few ticks while it spins, see Term::tick
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Spinner {
+    frames: Vec<char>,
+    // how many ticks a frame lasts
+    every: u32,
+    ticks: u32,
+    frame: usize,
+    state: State,
+}
+
+impl Spinner {
+    /// makes a new Spinner cycling through frames, one frame per tick
+    /// it shows nothing until it is started
+    pub fn new(frames: &[char]) -> Self {
+        Self {
+            frames: frames.to_vec(),
+            every: 1,
+            ..Self::default()
+        }
+    }
+
+    /// makes each frame last ticks ticks
+    pub fn every(&mut self, ticks: u32) {
+        self.every = ticks.max(1);
+    }
+
+    /// starts spinning from the first frame
+    pub fn start(&mut self) {
+        self.state = State::Spinning;
+        self.frame = 0;
+        self.ticks = 0;
+    }
+
+    /// stops spinning, last is shown from then on, e.g. '✓' or '✗'
+    pub fn stop(&mut self, last: char) {
+        self.state = State::Stopped(last);
+    }
+
+    /// counts one tick, the next frame comes once the current one lasted long enough
+    /// does nothing unless the spinner spins
+    pub fn advance(&mut self) {
+        if self.state != State::Spinning || self.frames.is_empty() {
+            return;
+        }
+        self.ticks += 1;
+        if self.ticks >= self.every {
+            self.ticks = 0;
+            self.frame = (self.frame + 1) % self.frames.len();
+        }
+    }
+
+    /// whether the spinner spins
+    pub fn is_spinning(&self) -> bool {
+        self.state == State::Spinning
+    }
+
+    /// the char the spinner shows, None before it was first started
+    pub fn frame(&self) -> Option<char> {
+        match self.state {
+            State::Idle => None,
+            State::Spinning => self.frames.get(self.frame).copied(),
+            State::Stopped(last) => Some(last),
+        }
+    }
+}
+
+// spinners
+impl Text {
+    /// shows spinner in the first cell of this text, the rest of the value is left as it is,
+    /// e.g. for a label
+    pub fn set_spinner(&mut self, spinner: Spinner) {
+        self.spinner = Some(spinner);
+        self.sync_spinner();
+    }
+
+    /// the spinner this text shows, if it shows one
+    pub fn spinner(&self) -> Option<&Spinner> {
+        self.spinner.as_ref()
+    }
+
+    /// runs f on the spinner this text shows, e.g. text.spin(|s| s.stop('✓'))
+    /// this text is only marked dirty if the shown char changed
+    /// returns what f returned, None if this text shows no spinner
+    pub fn spin<R>(&mut self, f: impl FnOnce(&mut Spinner) -> R) -> Option<R> {
+        let spinner = self.spinner.as_mut()?;
+        let ret = f(spinner);
+        self.sync_spinner();
+
+        Some(ret)
+    }
+
+    // puts the spinner's char in the first cell if it changed
+    fn sync_spinner(&mut self) {
+        let Some(frame) = self.spinner.as_ref().map(Spinner::frame) else {
+            return;
+        };
+        if self.value.get(0).map_or(true, |c| c == frame) {
+            return;
+        }
+        self.value.set(0, frame);
+        self.mark_dirty();
+    }
+}
+
+impl Term {
+    /// advances every spinner of this term by one tick, see Spinner::advance,
+    /// takes down a flashed status whose time is up, see Term::flash_status,
+    /// blinks the soft cursor, see Term::set_soft_cursor,
+    /// and moves the animating containers on, see Term::animate_move
+    /// EventLoop::run calls it on every Tick
+    pub fn tick(&mut self) {
+        self.containers
+            .iter_mut()
+            .flat_map(|c| c.items.iter_mut())
+            .for_each(|text| _ = text.spin(Spinner::advance));
+        self.tick_status();
+        self.blink();
+        self.step_animations(std::time::Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod spin {
+    use super::*;
+    use crate::components::Container;
+    use crate::space::{border::Border, padding::Padding};
+
+    fn term() -> Term {
+        let mut term = Term::new(0, 30, 8);
+        let mut c = Container::new([0, 0], 0, 0, 20, 4, Border::None, Padding::None);
+        for id in [1, 3] {
+            let text = Text::nonedit(
+                [0, 0, id],
+                0,
+                0,
+                0,
+                0,
+                3,
+                1,
+                &[],
+                Border::None,
+                Padding::None,
+            );
+            c.items.push(text);
+        }
+        term.containers.push(c);
+
+        term
+    }
+
+    fn shown(term: &Term, id: u8) -> Option<char> {
+        term.nonedit_ref(&[0, 0, id]).unwrap().value.get(0).flatten()
+    }
+
+    #[test]
+    fn frames() {
+        let mut term = term();
+        let mut slow = Spinner::new(ASCII);
+        slow.every(2);
+        let text = term.nonedit_mut(&[0, 0, 1]).unwrap();
+        text.set_spinner(Spinner::new(BRAILLE));
+        text.spin(Spinner::start);
+        term.nonedit_mut(&[0, 0, 3]).unwrap().set_spinner(slow);
+        term.nonedit_mut(&[0, 0, 3]).unwrap().spin(Spinner::start);
+        assert_eq!([shown(&term, 1), shown(&term, 3)], [Some('⠋'), Some('|')]);
+
+        term.containers[0]
+            .items
+            .iter_mut()
+            .for_each(|t| _ = t.attributes.remove("dirty"));
+        term.tick();
+        assert_eq!([shown(&term, 1), shown(&term, 3)], [Some('⠙'), Some('|')]);
+        // the slow spinner's frame didn't change, so it is not dirty
+        let dirty = |term: &Term, id| {
+            term.nonedit_ref(&[0, 0, id])
+                .unwrap()
+                .attributes
+                .contains("dirty")
+        };
+        assert!(dirty(&term, 1));
+        assert!(!dirty(&term, 3));
+        term.tick();
+        assert_eq!(shown(&term, 3), Some('/'));
+
+        let text = term.nonedit_mut(&[0, 0, 1]).unwrap();
+        text.spin(|s| s.stop('✓'));
+        assert!(!text.spinner().unwrap().is_spinning());
+        term.tick();
+        assert_eq!(shown(&term, 1), Some('✓'));
+    }
+}
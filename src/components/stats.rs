@@ -0,0 +1,310 @@
+use std::fmt;
+use std::time::Duration;
+#[cfg(feature = "stats")]
+use std::time::Instant;
+
+#[cfg(feature = "stats")]
+use crate::space::{border::Border, padding::Padding};
+
+#[cfg(feature = "stats")]
+use super::{Container, Term, Text, Value};
+
+// the number of frames the fps is worked out over
+#[cfg(feature = "stats")]
+const WINDOW: usize = 32;
+
+// the width of the stats overlay, see Term::stats_overlay
+#[cfg(feature = "stats")]
+const OVERLAY_W: u16 = 36;
+
+/// the numbers of the frames a Term rendered, see Term::collect_stats
+/// without the stats feature they are all zeros, see Term::on_after_render
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderStats {
+    /// the number of frames rendered since the stats were collected or reset
+    pub frames: u64,
+    /// the time the last frame took to lay the components out
+    pub layout: Duration,
+    /// the time the last frame took to be turned into bytes and written
+    pub emit: Duration,
+    /// the number of bytes the last frame wrote
+    pub bytes: usize,
+    /// the number of bytes all the frames wrote
+    pub total_bytes: u64,
+    /// the number of cells that differ from the frame before the last one rendered by
+    /// Term::render, or the number of cells of the components Term::render_dirty drew again
+    pub cells_changed: usize,
+    /// the frames rendered per second over the last 32 frames, 0 until there are 2
+    pub fps: f64,
+}
+
+impl fmt::Display for RenderStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:.0} fps {:.1?} {:.1?} {}B {}c",
+            self.fps, self.layout, self.emit, self.bytes, self.cells_changed
+        )
+    }
+}
+
+// what a term keeps while it collects stats
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Collector {
+    stats: RenderStats,
+    // the instants the last frames started at, the oldest one at next once they are all taken
+    starts: [Option<Instant>; WINDOW],
+    next: usize,
+    // the cells of the last frame drawn by Term::render
+    last: Vec<Option<char>>,
+    // the nonedit showing the stats, see Term::stats_overlay
+    overlay: Option<[u8; 3]>,
+}
+
+#[cfg(feature = "stats")]
+impl Collector {
+    fn start(&mut self, now: Instant) {
+        self.starts[self.next] = Some(now);
+        self.next = (self.next + 1) % WINDOW;
+        let oldest = self.starts[self.next].or(self.starts[0]).unwrap();
+        let frames = self.starts.iter().flatten().count();
+        let span = now.duration_since(oldest).as_secs_f64();
+        self.stats.fps = if frames > 1 && span > 0.0 {
+            (frames - 1) as f64 / span
+        } else {
+            0.0
+        };
+    }
+
+    // the number of cells that differ from the last frame, which cells become
+    fn changed(&mut self, cells: &[Option<char>]) -> usize {
+        if self.last.len() != cells.len() {
+            self.last.clear();
+            self.last.extend_from_slice(cells);
+            return cells.len();
+        }
+        let changed = self.last.iter().zip(cells).filter(|(a, b)| a != b).count();
+        self.last.copy_from_slice(cells);
+
+        changed
+    }
+}
+
+// the instants a frame went through, see Term::frame_started
+#[cfg(feature = "stats")]
+pub(crate) struct FrameTimes {
+    started: Instant,
+    laid_out: Option<Instant>,
+}
+
+#[cfg(feature = "stats")]
+impl Term {
+    /// starts or stops collecting the stats of the frames rendered by Term::render and
+    /// Term::render_dirty, see Term::render_stats
+    /// stopping drops the stats, the stats overlay stays until it is hidden
+    pub fn collect_stats(&mut self, on: bool) {
+        match on {
+            true if self.stats.is_none() => self.stats = Some(Collector::default()),
+            true => (),
+            false => self.stats = None,
+        }
+    }
+
+    /// the stats of the frames rendered since the stats were collected or reset,
+    /// all zeros when they aren't collected
+    pub fn render_stats(&self) -> RenderStats {
+        self.stats.as_ref().map(|c| c.stats).unwrap_or_default()
+    }
+
+    /// zeroes the stats, they go on being collected
+    pub fn reset_stats(&mut self) {
+        if let Some(collector) = &mut self.stats {
+            *collector = Collector {
+                overlay: collector.overlay,
+                ..Collector::default()
+            };
+        }
+    }
+
+    /// the id of the nonedit showing the stats at the top right corner of the terminal,
+    /// the stats of the frame before are written to it at the start of each frame
+    /// the overlay is made and the stats start being collected the first time this is called
+    pub fn stats_overlay(&mut self) -> [u8; 3] {
+        self.collect_stats(true);
+        if let Some(id) = self.stats.as_ref().unwrap().overlay {
+            return id;
+        }
+        let [_, cid] = self
+            .free_container_id()
+            .expect("every container id of the term is taken");
+        let id = [self.id, cid, 1];
+        let w = OVERLAY_W.min(self.w);
+        let x0 = self.w - w;
+        let mut c = Container::new([id[0], id[1]], x0, 0, w, 1, Border::None, Padding::None);
+        c.layer = self.containers.iter().map(|c| c.layer).max().unwrap_or(0).saturating_add(1);
+        let text = Text::nonedit(id, 0, 0, x0 + 1, 0, w, 1, &[], Border::None, Padding::None);
+        c.items.push(text);
+        self.containers.push(c);
+        self.stats.as_mut().unwrap().overlay = Some(id);
+
+        id
+    }
+
+    // the start of a frame, None when no stats are collected
+    // the overlay gets the stats of the frame before before anything is timed
+    pub(crate) fn frame_started(&mut self) -> Option<FrameTimes> {
+        let collector = self.stats.as_ref()?;
+        if let Some(id) = collector.overlay {
+            let shown = collector.stats.to_string();
+            if let Some(text) = self.nonedit_mut(&id) {
+                text.value = Value::blank(0);
+                text.value.extend(shown.chars().take(text.w as usize).map(Some));
+                text.fill();
+                text.mark_dirty();
+            }
+        }
+        let started = Instant::now();
+        self.stats.as_mut().unwrap().start(started);
+
+        Some(FrameTimes {
+            started,
+            laid_out: None,
+        })
+    }
+
+    // the frame was laid out as cells, a whole frame drawn by Term::render
+    pub(crate) fn frame_laid_out(&mut self, times: &mut Option<FrameTimes>, cells: &[Option<char>]) {
+        let (Some(times), Some(collector)) = (times, &mut self.stats) else {
+            return;
+        };
+        times.laid_out = Some(Instant::now());
+        collector.stats.cells_changed = collector.changed(cells);
+    }
+
+    // the frame of the dirty components was laid out, see Term::render_dirty
+    pub(crate) fn dirty_laid_out(&mut self, times: &mut Option<FrameTimes>, cells: usize) {
+        let (Some(times), Some(collector)) = (times, &mut self.stats) else {
+            return;
+        };
+        times.laid_out = Some(Instant::now());
+        collector.stats.cells_changed = cells;
+    }
+
+    // the frame's bytes were written
+    pub(crate) fn frame_written(&mut self, times: Option<FrameTimes>, bytes: usize) {
+        let (Some(times), Some(collector)) = (times, &mut self.stats) else {
+            return;
+        };
+        let laid_out = times.laid_out.unwrap_or(times.started);
+        let stats = &mut collector.stats;
+        stats.frames += 1;
+        stats.layout = laid_out.duration_since(times.started);
+        stats.emit = laid_out.elapsed();
+        stats.bytes = bytes;
+        stats.total_bytes += bytes as u64;
+    }
+
+    // the number of cells of the dirty components, the ones Term::render_dirty draws again
+    pub(crate) fn dirty_cells(&self) -> usize {
+        let area = |[_, _, w, h]: [u16; 4]| w as usize * h as usize;
+        self.containers
+            .iter()
+            .filter(|c| !c.is_hidden())
+            .map(|c| match c.attributes.contains("dirty") {
+                true => area(c.outer_area()),
+                false => c
+                    .items
+                    .iter()
+                    .filter(|t| !t.is_hidden() && t.attributes.contains("dirty"))
+                    .map(|t| area(t.outer_area()))
+                    .sum(),
+            })
+            .sum()
+    }
+}
+
+#[cfg(all(test, feature = "stats"))]
+mod stats {
+    use super::*;
+    use crate::space::{Area, Pos};
+
+    fn term() -> Term {
+        let mut term = Term::new(0, 40, 6);
+        Container::builder([0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 20, h: 3 })
+            .build(&mut term)
+            .unwrap();
+        Text::input_builder([0, 0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 20, h: 1 })
+            .build(&mut term)
+            .unwrap();
+
+        term
+    }
+
+    #[test]
+    fn collected() {
+        let mut term = term();
+        let mut sink = std::io::sink();
+        // off by default
+        term.render_to(&mut sink).unwrap();
+        assert_eq!(term.render_stats(), RenderStats::default());
+
+        term.collect_stats(true);
+        term.render_to(&mut sink).unwrap();
+        let stats = term.render_stats();
+        assert_eq!(stats.frames, 1);
+        assert_eq!(stats.cells_changed, 40 * 6);
+        assert!(stats.bytes > 0);
+        assert_eq!(stats.total_bytes, stats.bytes as u64);
+        assert_eq!(stats.fps, 0.0);
+
+        term.render_to(&mut sink).unwrap();
+        assert_eq!(term.render_stats().cells_changed, 0);
+        term.input_mut(&[0, 0, 0]).unwrap().insert_str("ab");
+        term.render_to(&mut sink).unwrap();
+        let stats = term.render_stats();
+        assert_eq!([stats.frames, stats.cells_changed as u64], [3, 2]);
+        assert!(stats.fps > 0.0);
+        assert!(stats.total_bytes > stats.bytes as u64);
+
+        term.reset_stats();
+        assert_eq!(term.render_stats(), RenderStats::default());
+        term.collect_stats(false);
+        term.render_to(&mut sink).unwrap();
+        assert_eq!(term.render_stats().frames, 0);
+    }
+
+    #[test]
+    fn dirty() {
+        let mut term = term();
+        term.collect_stats(true);
+        term.input_mut(&[0, 0, 0]).unwrap().insert('a');
+        assert_eq!(term.dirty_cells(), 20);
+        term.render_dirty_to(&mut std::io::sink()).unwrap();
+        assert_eq!(term.render_stats().cells_changed, 20);
+        assert_eq!(term.dirty_cells(), 0);
+    }
+
+    #[test]
+    fn overlay() {
+        let mut term = term();
+        let id = term.stats_overlay();
+        assert_eq!(term.stats_overlay(), id);
+        let text = term.nonedit_ref(&id).unwrap();
+        assert_eq!([text.ax0, text.ay0, text.w], [5, 0, 36]);
+
+        let mut sink = std::io::sink();
+        term.render_to(&mut sink).unwrap();
+        term.render_to(&mut sink).unwrap();
+        let shown = term.nonedit_ref(&id).unwrap().value_string();
+        assert!(shown.starts_with("0 fps "), "{}", shown);
+        assert!(shown.ends_with("c"), "{}", shown);
+        // the stats stay when they are reset
+        term.reset_stats();
+        assert_eq!(term.stats_overlay(), id);
+    }
+}
@@ -0,0 +1,235 @@
+use std::time::{Duration, Instant};
+
+use crate::space::{border::Border, padding::Padding, Pos};
+use crate::themes::Style;
+
+use super::{Container, Term, Text};
+
+/// where a status bar segment goes in its row
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Segment {
+    /// at the start of the row
+    #[default]
+    Left,
+    /// in the middle of the row
+    Center,
+    /// at the end of the row
+    Right,
+}
+
+// the status bar of a term, see Term::status_bar
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct StatusBar {
+    // the nonedit showing the bar
+    pub(crate) id: [u8; 3],
+    // the left, center and right segments
+    segments: [String; 3],
+    style: String,
+    // the message shown in place of the segments until the instant
+    flash: Option<(String, Instant)>,
+}
+
+impl StatusBar {
+    // the cells of the bar's w wide row
+    fn cells(&self, w: u16) -> Vec<Option<char>> {
+        let w = w as usize;
+        let mut cells = vec![None; w];
+        let mut put = |text: &str, at: Segment| {
+            let chars = text.chars().take(w).collect::<Vec<char>>();
+            let start = match at {
+                Segment::Left => 0,
+                Segment::Center => (w - chars.len()) / 2,
+                Segment::Right => w - chars.len(),
+            };
+            cells[start..start + chars.len()]
+                .iter_mut()
+                .zip(chars)
+                .for_each(|(cell, c)| *cell = Some(c));
+        };
+        match &self.flash {
+            Some((message, _)) => put(message, Segment::Left),
+            // the left segment wins over the others where they overlap
+            None => {
+                put(&self.segments[2], Segment::Right);
+                put(&self.segments[1], Segment::Center);
+                put(&self.segments[0], Segment::Left);
+            }
+        }
+
+        cells
+    }
+}
+
+impl Term {
+    /// the id of the nonedit of this term's status bar, a full width row pinned to the bottom
+    /// of the terminal, see Term::set_status
+    /// the bar is made the first time this is called
+    pub fn status_bar(&mut self) -> [u8; 3] {
+        if let Some(status) = &self.status {
+            return status.id;
+        }
+        let [_, cid] = self
+            .free_container_id()
+            .expect("every container id of the term is taken");
+        let id = [self.id, cid, 1];
+        let y0 = Pos::End.position(self.h.saturating_sub(1));
+        let mut c = Container::new(
+            [id[0], id[1]],
+            0,
+            y0,
+            self.w,
+            1,
+            Border::None,
+            Padding::None,
+        );
+        let text = Text::nonedit(
+            id,
+            0,
+            0,
+            1,
+            y0 + 1,
+            self.w,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        c.items.push(text);
+        self.containers.push(c);
+        self.status = Some(StatusBar {
+            id,
+            ..StatusBar::default()
+        });
+        self.sync_status();
+
+        id
+    }
+
+    /// shows text in the left segment of the status bar, the whole bar takes style
+    pub fn set_status(&mut self, text: &str, style: Style) {
+        self.status_bar();
+        let status = self.status.as_mut().unwrap();
+        status.segments[0] = text.to_string();
+        status.style = style.style();
+        self.sync_status();
+    }
+
+    /// shows text in the at segment of the status bar
+    pub fn set_status_segment(&mut self, at: Segment, text: &str) {
+        self.status_bar();
+        let status = self.status.as_mut().unwrap();
+        status.segments[at as usize] = text.to_string();
+        self.sync_status();
+    }
+
+    /// empties every segment of the status bar and drops the flashed message
+    pub fn clear_status(&mut self) {
+        let Some(status) = &mut self.status else {
+            return;
+        };
+        status.segments = Default::default();
+        status.flash = None;
+        self.sync_status();
+    }
+
+    /// shows message in place of the status bar's segments for duration,
+    /// the segments are shown again on the first Term::tick after that
+    pub fn flash_status(&mut self, message: &str, duration: Duration) {
+        self.status_bar();
+        let status = self.status.as_mut().unwrap();
+        status.flash = Some((message.to_string(), Instant::now() + duration));
+        self.sync_status();
+    }
+
+    // drops the flashed message once its time is up
+    pub(crate) fn tick_status(&mut self) {
+        let Some(status) = &mut self.status else {
+            return;
+        };
+        if status
+            .flash
+            .as_ref()
+            .is_some_and(|(_, until)| Instant::now() >= *until)
+        {
+            status.flash = None;
+            self.sync_status();
+        }
+    }
+
+    // puts the status bar back at the bottom of the terminal, as wide as it
+    pub(crate) fn reflow_status(&mut self) {
+        let Some(id) = self.status.as_ref().map(|status| status.id) else {
+            return;
+        };
+        let [w, y0] = [self.w, Pos::End.position(self.h.saturating_sub(1))];
+        if let Some(c) = self.container_mut(&[id[0], id[1]]) {
+            [c.w, c.y0] = [w, y0];
+            c.invalidate();
+        }
+        if let Some(text) = self.text_mut(&id) {
+            [text.w, text.ay0] = [w, y0 + 1];
+            text.clamp_cursor();
+            text.invalidate();
+        }
+        self.sync_status();
+    }
+
+    // writes the segments to the status bar's nonedit
+    fn sync_status(&mut self) {
+        let Some(status) = self.status.clone() else {
+            return;
+        };
+        let Some(text) = self.text_mut(&status.id) else {
+            return;
+        };
+        text.value = status.cells(text.w).into();
+        text.vstyle = status.style;
+        text.mark_dirty();
+    }
+}
+
+#[cfg(test)]
+mod status {
+    use super::*;
+
+    fn shown(term: &Term) -> String {
+        let id = term.status.as_ref().unwrap().id;
+        let text = term.nonedit_ref(&id).unwrap();
+
+        text.value.iter().map(|c| c.unwrap_or(' ')).collect()
+    }
+
+    #[test]
+    fn segments() {
+        let mut term = Term::new(0, 20, 6);
+        term.set_status("NORMAL", Style::default());
+        term.set_status_segment(Segment::Center, "ok");
+        term.set_status_segment(Segment::Right, "1:4");
+        assert_eq!(shown(&term), "NORMAL   ok      1:4");
+        let id = term.status_bar();
+        assert_eq!(term.container_ref(&[id[0], id[1]]).unwrap().y0, 5);
+
+        term.resize(10, 3);
+        // the left segment covers the center one when there is no room for both
+        assert_eq!(shown(&term), "NORMAL 1:4");
+        let c = term.container_ref(&[id[0], id[1]]).unwrap();
+        assert_eq!([c.w, c.y0], [10, 2]);
+        assert_eq!(term.nonedit_ref(&id).unwrap().ay0, 3);
+
+        term.clear_status();
+        assert_eq!(shown(&term).trim(), "");
+    }
+
+    #[test]
+    fn flash() {
+        let mut term = Term::new(0, 20, 6);
+        term.set_status("saved", Style::default());
+        term.flash_status("write failed", Duration::from_secs(60));
+        term.tick();
+        assert_eq!(shown(&term).trim_end(), "write failed");
+
+        term.flash_status("write failed", Duration::ZERO);
+        term.tick();
+        assert_eq!(shown(&term).trim_end(), "saved");
+    }
+}
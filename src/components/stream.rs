@@ -0,0 +1,399 @@
+use std::collections::VecDeque;
+
+use crate::space::Overflow;
+
+use super::gutter::split_lines;
+use super::escape::NUL;
+use super::tab_stop::{untab, FILL};
+use super::Text;
+
+// the number of rows each line of a text takes, the oldest line first, see Text::set_max_lines
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Ring {
+    max: usize,
+    rows: VecDeque<usize>,
+    // the rows of all the lines
+    total: usize,
+    // the [end, len, stride] of the value the rows were counted on, see Text::counted
+    counted: [usize; 3],
+}
+
+// the cell the line after the one ending at the cell at starts on, rows of stride cells,
+// empty tells that the line is empty, it takes a row of its own then
+// a line that fills its last row leaves no cell to tell where it ends, the next one
+// starts right under it
+fn next_row(at: usize, stride: usize, empty: bool) -> usize {
+    match empty {
+        true => at + stride,
+        false => at.next_multiple_of(stride),
+    }
+}
+
+// streaming
+impl Text {
+    /// makes this text follow the end of its value, it is scrolled to its last line
+    /// now and after every Text::append_str or Text::append_line, e.g. a tailed log
+    pub fn set_follow(&mut self, follow: bool) {
+        match follow {
+            true => {
+                self.attributes.insert("follow");
+                let to = self.max_scroll() as i32 - self.scroll as i32;
+                self.scroll_by(to as i16);
+            }
+            false => _ = self.attributes.remove("follow"),
+        }
+    }
+
+    /// whether this text follows the end of its value, see Text::set_follow
+    pub fn follows(&self) -> bool {
+        self.attributes.contains("follow")
+    }
+
+    /// adds s at the end of the last line of the value, the newlines of s start new lines,
+    /// e.g. a chunk read from a stream
+    /// the value grows past the area of this text as it needs, a line wider than the rows
+    /// goes on on the next rows, unless this text has a horizontal scrollbar,
+    /// the line is cut at its columns then
+    /// only the rows that changed are rendered again by the next Term::render_dirty,
+    /// the whole text when it follows the end of its value and was scrolled to it
+    pub fn append_str(&mut self, s: &str) {
+        self.append(s, false);
+    }
+
+    /// adds s on a new line after the last line of the value, see Text::append_str
+    pub fn append_line(&mut self, s: &str) {
+        self.append(s, true);
+    }
+
+    /// keeps at most max lines in the value of this text, e.g. a log pane that runs for days,
+    /// the oldest lines are dropped as Text::append_str and Text::append_line add new ones,
+    /// the other edits drop none, at least one line is kept, None lets the value grow
+    /// the rows shown stay on the lines they showed while lines above them are dropped,
+    /// and so do the cursor and the search matches, the matches on the dropped lines
+    /// are dropped with them
+    pub fn set_max_lines(&mut self, max: Option<usize>) {
+        self.ring = max.map(|max| Ring {
+            max: max.max(1),
+            counted: [usize::MAX; 3],
+            ..Default::default()
+        });
+        self.recount();
+        if self.evict() > 0 {
+            self.mark_dirty();
+        }
+    }
+
+    /// the most lines this text keeps, see Text::set_max_lines
+    pub fn max_lines(&self) -> Option<usize> {
+        self.ring.as_ref().map(|ring| ring.max)
+    }
+
+    // the [end, len, stride] of the value, the end being the cell after the last char
+    fn counted(&self) -> [usize; 3] {
+        let end = self.value.last_filled().map_or(0, |idx| idx + 1);
+
+        [end, self.value.len(), self.stride() as usize]
+    }
+
+    // counts the rows of the lines again if the value was edited since they were counted
+    fn recount(&mut self) {
+        let counted = self.counted();
+        let Some(ring) = &mut self.ring else {
+            return;
+        };
+        if ring.counted == counted || counted[2] == 0 {
+            return;
+        }
+        let lines = split_lines(&self.value.to_vec(), counted[2]);
+        ring.rows = lines
+            .iter()
+            .map(|(_, rows)| *rows)
+            .filter(|rows| *rows > 0)
+            .collect();
+        ring.total = ring.rows.iter().sum();
+        ring.counted = counted;
+    }
+
+    // counts the rows of the lines from the one starting on the row from to the end
+    fn count_tail(&mut self, from: usize) {
+        let counted = self.counted();
+        let Some(ring) = &mut self.ring else {
+            return;
+        };
+        while ring.total > from {
+            ring.total -= ring.rows.pop_back().unwrap_or(ring.total);
+        }
+        let stride = counted[2];
+        let tail = self
+            .value
+            .range(from * stride..self.value.len())
+            .collect::<Vec<_>>();
+        for (_, rows) in split_lines(&tail, stride)
+            .into_iter()
+            .filter(|(_, rows)| *rows > 0)
+        {
+            ring.rows.push_back(rows);
+            ring.total += rows;
+        }
+        ring.counted = counted;
+    }
+
+    // drops the oldest lines past the max, the rows shown stay on the lines they showed
+    // returns the number of rows the dropped lines took
+    fn evict(&mut self) -> usize {
+        let stride = self.stride() as usize;
+        let Some(ring) = &mut self.ring else {
+            return 0;
+        };
+        let mut gone = 0;
+        while ring.rows.len() > ring.max {
+            gone += ring.rows.pop_front().unwrap_or(0);
+        }
+        ring.total -= gone;
+        if gone == 0 {
+            return 0;
+        }
+
+        let cells = gone * stride;
+        self.value.drain(0..cells);
+        self.fill();
+        // the rows shown were dropped, the ones under them move up
+        if gone > self.scroll as usize {
+            self.mark_dirty();
+        }
+        self.scroll = (self.scroll as usize).saturating_sub(gone) as u16;
+        if self.cy as usize >= gone {
+            self.cy -= gone as u16;
+        } else {
+            [self.cx, self.cy] = [0, 0];
+        }
+        self.appended = self
+            .appended
+            .map(|cell| cell.map(|idx| idx.saturating_sub(cells)));
+        self.remap_matches(|found| {
+            (found.start >= cells).then(|| found.start - cells..found.end - cells)
+        });
+        let counted = self.counted();
+        if let Some(ring) = &mut self.ring {
+            ring.counted = counted;
+        }
+
+        gone
+    }
+
+    fn append(&mut self, s: &str, new_line: bool) {
+        let stride = self.stride() as usize;
+        if stride == 0 {
+            return;
+        }
+        self.recount();
+        // the row the last line starts on, it is counted again with the new ones
+        let tail = self
+            .ring
+            .as_ref()
+            .map_or(0, |ring| ring.total - ring.rows.back().unwrap_or(&0));
+        let cut = self.scrollbars[1].is_some();
+        let end = self.value.last_filled().map_or(0, |idx| idx + 1);
+        // the newline the last append ended with is kept until something comes after it
+        let pending = self.appended.filter(|[_, last]| *last == end);
+        let mut at = pending.map_or(end, |[at, _]| at);
+        // whether the line the cell at is on is empty, an empty value has no line yet
+        let mut empty = end == 0 || pending.is_some();
+        if new_line && (end > 0 || pending.is_some()) {
+            at = next_row(at, stride, pending.is_some());
+            empty = true;
+        }
+        let first = at / stride;
+
+        self.value.truncate(end);
+        for c in s.chars() {
+            let c = self.own(c);
+            match c {
+                '\n' => {
+                    at = next_row(at, stride, empty);
+                    empty = true;
+                }
+                c if c.is_control() && c != '\t' && self.escape(c).is_none() => (),
+                // a mark or a joiner goes into the cluster before it, the cluster may get wider
+                c if !empty && self.cluster_before(at, c).is_some() => {
+                    let base = self.join_at(at, c).unwrap_or(at);
+                    let end = base + self.value.get(base).flatten().map_or(1, |c| self.cells_of(c, base));
+                    self.value.resize(at);
+                    (at..end).for_each(|_| self.value.push(Some(FILL)));
+                    at = at.max(end);
+                }
+                // the rest of the line is past the scrollbar's columns
+                _ if cut && !empty && at % stride == 0 => (),
+                c if (c == '\t' && self.keeps_tabs())
+                    || self.escape(c).is_some()
+                    || self.clusters.cells(c) > 1 =>
+                {
+                    // a wide char doesn't fit in the last cell of a row, it goes on on the next one
+                    let wide = c != '\t' && self.escape(c).is_none() && self.cells_of(c, at) > 1;
+                    if wide && at % stride == stride - 1 {
+                        self.value.resize(at);
+                        self.value.push(Some(FILL));
+                        at += 1;
+                        if cut {
+                            continue;
+                        }
+                    }
+                    let cells = self.cells_of(c, at);
+                    self.value.resize(at);
+                    self.value.push(Some(if c == '\0' { NUL } else { c }));
+                    (1..cells).for_each(|_| self.value.push(Some(FILL)));
+                    at += cells;
+                    empty = false;
+                }
+                // a tab in a text that keeps none is a space
+                c => {
+                    self.value.resize(at);
+                    self.value.push(Some(untab(c)));
+                    at += 1;
+                    empty = false;
+                }
+            }
+        }
+        let end = self.value.last_filled().map_or(0, |idx| idx + 1);
+        self.appended = (empty && at > end).then_some([at, end]);
+        self.fill();
+        self.count_tail(tail);
+        let gone = self.evict();
+        let [first, last] = [first, at / stride].map(|row| row.saturating_sub(gone));
+
+        if self.follows() && self.scroll != self.max_scroll() {
+            self.scroll = self.max_scroll();
+            self.mark_dirty();
+            return;
+        }
+        let [top, vh] = [self.scroll as usize, self.view()[1] as usize];
+        let span = [first, last + 1].map(|row| (row.clamp(top, top + vh) - top) as u16);
+        self.mark_rows(span[0]..span[1]);
+        if let Overflow::Ellipsis = self.overflow {
+            self.mark_rows(vh.saturating_sub(1) as u16..vh as u16);
+        }
+    }
+}
+
+#[cfg(test)]
+mod stream {
+    use super::*;
+    use crate::components::{Property, Scrollbar};
+    use crate::space::{border::Border, padding::Padding};
+
+    // a multiline 4x2 nonedit
+    fn pane() -> Text {
+        let mut text = Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            0,
+            0,
+            4,
+            2,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        text.attributes.insert("multiline");
+
+        text
+    }
+
+    #[test]
+    fn appended() {
+        let mut text = pane();
+        text.append_line("ab");
+        text.append_str("c\n");
+        text.append_str("de");
+        text.append_line("");
+        text.append_line("fghij");
+        assert_eq!(text.value_string(), "abc\nde\n\nfghi\nj");
+        assert_eq!(text.value.len(), 17);
+        // not following, the top rows stay
+        assert_eq!(text.scroll, 0);
+
+        text.set_follow(true);
+        assert!(text.follows());
+        assert_eq!(text.scroll, 3);
+        text.append_str("k\nl");
+        assert_eq!(text.scroll, 4);
+        assert_eq!([text.cell(0, 0), text.cell(0, 1)], [Some('j'), Some('l')]);
+    }
+
+    #[test]
+    fn cut() {
+        let mut text = pane();
+        text.set_scrollbar(Scrollbar::horizontal(6));
+        text.append_line("abcdefgh");
+        text.append_line("ij");
+        assert_eq!(
+            text.value.range(0..12).flatten().collect::<String>(),
+            "abcdefij"
+        );
+    }
+
+    #[test]
+    fn capped() {
+        let mut text = pane();
+        text.set_max_lines(Some(3));
+        ["a", "b", "c"].iter().for_each(|line| text.append_line(line));
+        text.scroll = 1;
+        text.search("c", false);
+        text.attributes.remove("dirty");
+        text.append_line("d");
+        assert_eq!(text.value_string(), "b\nc\nd");
+        // still showing b and c, nothing moved
+        assert_eq!(text.scroll, 0);
+        assert_eq!(text.matches(), [4..5]);
+        assert!(text.patched.as_ref().is_some_and(|rows| rows.is_empty()));
+
+        // b was on the top row
+        text.append_line("e");
+        assert_eq!(text.value_string(), "c\nd\ne");
+        assert_eq!(text.patched, None);
+        text.append_line("fghij");
+        assert_eq!(text.value_string(), "d\ne\nfghi\nj");
+        assert!(text.matches().is_empty());
+
+        text.set_max_lines(Some(1));
+        assert_eq!(text.max_lines(), Some(1));
+        assert_eq!(text.value_string(), "fghi\nj");
+    }
+
+    #[test]
+    fn tabs() {
+        let mut text = pane();
+        text.properties.insert("tab-width", Property::UInt(2));
+        text.append_line("a\tb");
+        text.append_str("\t");
+        assert_eq!(text.value_string(), "a\tb\t");
+        assert_eq!([text.cell(2, 0), text.cell(3, 0)], [Some('b'), Some(' ')]);
+        text.append_str("\tc");
+        assert_eq!(text.cell(0, 1), Some(' '));
+        assert_eq!(text.cell(2, 1), Some('c'));
+    }
+
+    #[test]
+    fn rows() {
+        let mut text = pane();
+        text.append_line("ab");
+        text.attributes.remove("dirty");
+        text.patched = None;
+        text.append_line("cd");
+        assert_eq!(text.patched, Some(1..2));
+        text.append_str("e");
+        assert_eq!(text.patched, Some(1..2));
+        // the new row is below the area, only the scrollbars and the gutter are rendered
+        text.append_line("f");
+        assert_eq!(text.patched, Some(1..2));
+        assert_eq!(text.scroll, 0);
+
+        text.set_follow(true);
+        text.attributes.remove("dirty");
+        text.append_line("g");
+        assert_eq!(text.scroll, 2);
+        assert!(text.attributes.contains("dirty"));
+        assert_eq!(text.patched, None);
+    }
+}
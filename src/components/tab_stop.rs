@@ -0,0 +1,245 @@
+use super::{Property, Text, TextKind};
+
+// the cells a tab or an escaped control char takes after its own, see Text::tab_width
+// and Text::set_control_display, the cursor goes over them
+pub(crate) const FILL: char = '\u{e000}';
+
+// the columns between two tab stops when a text sets none
+const TAB_WIDTH: usize = 8;
+
+// the char a value cell is shown as when it is not escaped, a tab and its fill cells are blank
+pub(crate) fn untab(c: char) -> char {
+    match c {
+        '\t' | FILL => ' ',
+        c => c,
+    }
+}
+
+// tab stops
+impl Text {
+    /// the number of columns between the tab stops of the value, from the "tab-width"
+    /// Property::UInt or Property::Int of this text, 8 when it has none
+    /// a tab takes the cells up to the next stop, or to the end of its row, it is still one char
+    /// that the cursor and backspace go over at once and that Text::value_string gives back,
+    /// the tabs already in the value take a new width with the next edit
+    /// a nonedit or a multiline input keeps the tabs typed or pasted into it, any other input
+    /// gets a space instead
+    pub fn tab_width(&self) -> usize {
+        let width = match self.properties.get("tab-width") {
+            Some(Property::UInt(width)) => usize::try_from(*width).unwrap_or(usize::MAX),
+            Some(Property::Int(width)) => usize::try_from(*width).unwrap_or(1),
+            _ => TAB_WIDTH,
+        };
+
+        width.max(1)
+    }
+
+    // whether a tab typed into this text is kept, see Text::tab_width
+    pub(crate) fn keeps_tabs(&self) -> bool {
+        self.kind == TextKind::NonEdit || self.attributes.contains("multiline")
+    }
+
+    // the number of cells a tab in the cell idx of the value takes, its own included
+    pub(crate) fn tab_cells(&self, idx: usize) -> usize {
+        let stride = self.stride().max(1) as usize;
+        let [col, width] = [idx % stride, self.tab_width()];
+
+        (width - col % width).min(stride - col)
+    }
+
+    // the number of cells c takes in the cell idx of the value, its own included,
+    // a wide char takes 2, see grapheme
+    pub(crate) fn cells_of(&self, c: char, idx: usize) -> usize {
+        match (c, self.escape(c)) {
+            ('\t', _) => self.tab_cells(idx),
+            (_, Some((_, len))) => len,
+            (c, None) => self.clusters.cells(c).min(self.stride().max(1) as usize),
+        }
+    }
+
+    // gives every tab, escaped control char and wide char of the value the fill cells it takes,
+    // the fill cells left by none are taken out, the cells after them move like they do
+    // for a typed char
+    pub(crate) fn retab(&mut self) {
+        let stride = self.stride() as usize;
+        let laid_out = |c: char| c == '\t' || c == FILL || self.cells_of(c, 0) > 1;
+        if stride == 0 || !self.value.chars().any(laid_out) {
+            return;
+        }
+        let cells = self.value.to_vec();
+        let mut laid = Vec::with_capacity(cells.len());
+        for c in &cells {
+            match c {
+                Some(FILL) => (),
+                Some(c) => {
+                    // a wide char doesn't fit in the last cell of a row, it goes on on the next one
+                    let wide = *c != '\t' && self.escape(*c).is_none() && self.cells_of(*c, 0) > 1;
+                    if wide && laid.len() % stride == stride - 1 {
+                        laid.push(Some(FILL));
+                    }
+                    let n = self.cells_of(*c, laid.len());
+                    laid.push(Some(*c));
+                    laid.resize(laid.len() + n - 1, Some(FILL));
+                }
+                None => laid.push(None),
+            }
+        }
+        if laid != cells {
+            self.value = laid.into();
+        }
+    }
+
+    // the number of fill cells right after the cell idx
+    fn fills_after(&self, idx: usize) -> usize {
+        let cells = self.value.range(idx + 1..self.value.len());
+
+        cells.take_while(|c| *c == Some(FILL)).count()
+    }
+
+    // takes out the fill cells of the char the cell idx is, or is a fill cell of
+    // returns the cell of the char
+    pub(crate) fn drop_fills(&mut self, idx: usize) -> usize {
+        let mut at = idx;
+        while at > 0 && self.value.get(at).flatten() == Some(FILL) {
+            at -= 1;
+        }
+        let fills = self.fills_after(at);
+        self.value.drain(at + 1..at + 1 + fills);
+
+        at
+    }
+
+    // moves the cursor off the fill cells of a tab or a wide char, backwards onto the tab if back
+    pub(crate) fn skip_fills(&mut self, back: bool) {
+        while self.value.get(self.cursor_index()).flatten() == Some(FILL) {
+            let stepped = match back {
+                true => self.step_left(),
+                false => self.step_right(),
+            };
+            if !stepped {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tab_stop {
+    use super::*;
+    use crate::components::{Container, Term};
+    use crate::space::{border::Border, padding::Padding};
+
+    fn text(kind: TextKind, w: u16, h: u16) -> Text {
+        let new = match kind {
+            TextKind::Input => Text::input,
+            TextKind::NonEdit => Text::nonedit,
+        };
+
+        new(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            w,
+            h,
+            &[],
+            Border::None,
+            Padding::None,
+        )
+    }
+
+    #[test]
+    fn expanded() {
+        let mut pane = text(TextKind::NonEdit, 12, 2);
+        pane.attributes.insert("multiline");
+        pane.properties.insert("tab-width", Property::UInt(4));
+        pane.replace_value("a\tb\tc\n\tx");
+        assert_eq!(
+            pane.cells()[..12]
+                .iter()
+                .map(|c| c.unwrap_or('.'))
+                .collect::<String>(),
+            "a   b   c..."
+        );
+        assert_eq!(pane.cell(4, 1), Some('x'));
+        assert_eq!(pane.value_string(), "a\tb\tc\n\tx");
+
+        // the tab after the new char shrinks, the row under it stays
+        pane.cx = 1;
+        pane.cy = 0;
+        pane.insert('z');
+        assert_eq!(
+            pane.cells()[..12]
+                .iter()
+                .map(|c| c.unwrap_or('.'))
+                .collect::<String>(),
+            "az  b   c..."
+        );
+        assert_eq!(pane.cell(4, 1), Some('x'));
+
+        // a tab at the end of the row is cut there
+        let mut narrow = text(TextKind::NonEdit, 6, 1);
+        narrow.replace_value("abcde\t");
+        assert_eq!(narrow.value.len(), 6);
+    }
+
+    #[test]
+    fn cursor() {
+        let mut input = text(TextKind::Input, 12, 2);
+        input.attributes.insert("multiline");
+        input.insert_str("\tb");
+        assert_eq!([input.cx as usize, input.cursor_info().column], [9, 3]);
+        assert_eq!(input.cursor_info().len, 2);
+        input.move_left();
+        assert_eq!(input.cx, 8);
+        // over the whole tab at once
+        input.move_left();
+        assert_eq!(input.cx, 0);
+        input.move_right();
+        assert_eq!(input.cx, 8);
+        assert!(input.backspace());
+        assert_eq!([input.cx as usize, input.value.len()], [0, 24]);
+        assert_eq!(input.value_string(), "b");
+        input.insert('\t');
+        input.cx = 0;
+        assert!(input.delete());
+        assert_eq!(input.value_string(), "b");
+
+        // a single line input types a space
+        let mut term = Term::new(0, 12, 3);
+        let mut c = Container::new([0, 0], 0, 0, 12, 3, Border::None, Padding::None);
+        c.items.push(text(TextKind::Input, 12, 1));
+        term.containers.push(c);
+        term.focused = Some([0, 0, 0]);
+        term.paste("a\tb");
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().value_string(), "a b");
+    }
+
+    #[test]
+    fn sentinels() {
+        // the chars the fill cells, the NULs and the wide chars are kept in are the user's too
+        let value = "tab\u{e000}x\u{e001}\u{e002}";
+        let mut term = Term::new(0, 12, 2);
+        let mut c = Container::new([0, 0], 0, 0, 12, 2, Border::None, Padding::None);
+        c.items.push(text(TextKind::Input, 12, 1));
+        term.containers.push(c);
+        let input = term.input_mut(&[0, 0, 0]).unwrap();
+        input.replace_value(value);
+        assert_eq!(input.value_string(), value);
+        assert_eq!(input.cursor_info().len, 7);
+        input.move_left();
+        assert!(input.backspace());
+        assert_eq!(input.value_string(), "tab\u{e000}x\u{e002}");
+
+        let mut pane = text(TextKind::NonEdit, 12, 1);
+        pane.id = [0, 0, 1];
+        [pane.y0, pane.ay0] = [1, 1];
+        pane.append_str("\t\u{e000}\0");
+        assert_eq!(pane.value_string(), "\t\u{e000}");
+        term.containers[0].items.push(pane);
+        let rows = crate::test::Frame::of(&term).rows();
+        assert_eq!(rows[0].trim_end(), "tab\u{e000}x\u{e002}");
+        assert_eq!(rows[1].trim_end(), "        \u{e000}");
+    }
+}
@@ -0,0 +1,385 @@
+use crate::inputs::keyboard::KbdEvent;
+use crate::themes::Style;
+
+use super::list::{moved_to, shown_from};
+use super::{Term, Text};
+
+/// how wide a table column is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Width {
+    /// this many cells
+    Fixed(u16),
+    /// this percent of the text's width
+    Percent(u8),
+    /// as wide as the widest of the header and the column's cells
+    #[default]
+    Auto,
+}
+
+/// a column of a Table
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Column {
+    header: String,
+    width: Width,
+}
+
+impl Column {
+    /// makes a new Column with header as its header
+    pub fn new(header: &str, width: Width) -> Self {
+        Self {
+            header: header.to_string(),
+            width,
+        }
+    }
+
+    /// the header
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+
+    /// the width constraint
+    pub fn width(&self) -> Width {
+        self.width
+    }
+}
+
+/// rows of cells under a header, with one selected row, shown in a Text, see Text::set_table
+/// a focused table moves its selection with Up, Down, Home, End, PageUp and PageDown,
+/// see Term::on_row_select
+///
+/// the header takes the first row of the text and stays there while the rows scroll
+/// the columns are one cell apart, when they don't fit in the text's width
+/// the widest columns are narrowed first and the cut cells end with an ellipsis
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+    selected: usize,
+    // the index of the row shown right under the header
+    offset: usize,
+    // the style escape sequence of the selected row
+    highlight: String,
+}
+
+impl Table {
+    /// makes a new Table with columns and rows, the first row is selected
+    /// a row's cells go to the columns in order, missing cells are left blank
+    /// the selected row is shown in reverse video unless told otherwise, see Table::highlight
+    pub fn new(columns: Vec<Column>, rows: Vec<Vec<String>>) -> Self {
+        Self {
+            columns,
+            rows,
+            selected: 0,
+            offset: 0,
+            highlight: "\x1b[7m".to_string(),
+        }
+    }
+
+    /// changes the style of the selected row
+    pub fn highlight(&mut self, style: &Style) {
+        self.highlight = style.style();
+    }
+
+    /// the columns
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// the rows
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+
+    /// the index of the selected row
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// the cells of the selected row, None if the table has no rows
+    pub fn selected_row(&self) -> Option<&[String]> {
+        self.rows.get(self.selected).map(Vec::as_slice)
+    }
+
+    /// the index of the row shown right under the header
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// how wide each column is in a text w cells wide
+    pub fn widths(&self, w: u16) -> Vec<u16> {
+        let gaps = self.columns.len().saturating_sub(1) as u16;
+        let room = w.saturating_sub(gaps);
+        let mut widths = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(idx, col)| match col.width {
+                Width::Fixed(n) => n,
+                Width::Percent(p) => (room as u32 * p.min(100) as u32 / 100) as u16,
+                Width::Auto => self
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(idx))
+                    .chain([&col.header])
+                    .map(|cell| cell.chars().count() as u16)
+                    .max()
+                    .unwrap_or(0),
+            })
+            .collect::<Vec<u16>>();
+
+        // narrows the widest column one cell at a time until they all fit
+        while widths.iter().sum::<u16>() > room {
+            let widest = widths.iter_mut().max().unwrap();
+            *widest -= 1;
+        }
+
+        widths
+    }
+
+    // the row of the text the selected row is shown in, if it is shown
+    pub(crate) fn highlighted(&self, h: u16) -> Option<(u16, &str)> {
+        let row = self.selected.checked_sub(self.offset)? + 1;
+        if row >= h as usize || self.rows.is_empty() {
+            return None;
+        }
+
+        Some((row as u16, &self.highlight))
+    }
+
+    // selects the row at idx, the last one if idx is past it,
+    // and scrolls the h - 1 rows under the header just enough for it to be shown
+    fn select(&mut self, idx: usize, h: u16) {
+        self.selected = idx.min(self.rows.len().saturating_sub(1));
+        self.offset = shown_from(self.selected, self.offset, h.saturating_sub(1) as usize);
+    }
+
+    // the value cells of the header and the visible rows, one row per w cells
+    fn cells(&self, w: u16, h: u16) -> Vec<Option<char>> {
+        let widths = self.widths(w);
+        let w = w as usize;
+        let mut cells = vec![None; w * h as usize];
+        let header = self.columns.iter().map(|col| col.header.as_str());
+        let header = header.collect::<Vec<&str>>();
+        let visible = self
+            .rows
+            .iter()
+            .skip(self.offset)
+            .take((h as usize).saturating_sub(1))
+            .map(|row| row.iter().map(String::as_str).collect::<Vec<&str>>());
+        for (y, row) in [header].into_iter().chain(visible).enumerate() {
+            let line = &mut cells[y * w..(y + 1) * w];
+            let mut x = 0;
+            for (cell, &width) in row.iter().zip(&widths) {
+                let width = width as usize;
+                let mut chars = cell.chars().collect::<Vec<char>>();
+                if chars.len() > width && width > 0 {
+                    chars.truncate(width - 1);
+                    chars.push('…');
+                }
+                chars.truncate(width);
+                line[x..x + width]
+                    .iter_mut()
+                    .zip(chars)
+                    .for_each(|(cell, c)| *cell = Some(c));
+                x += width + 1;
+            }
+        }
+
+        cells
+    }
+}
+
+// tables
+impl Text {
+    /// shows table in this text, replacing its value
+    pub fn set_table(&mut self, table: Table) {
+        self.table = Some(table);
+        self.set_focusable(true);
+        self.sync_table();
+    }
+
+    /// the table this text shows, if it shows one
+    pub fn table(&self) -> Option<&Table> {
+        self.table.as_ref()
+    }
+
+    /// replaces the rows of the table this text shows, the selection stays where it was if it can
+    /// returns false if this text shows no table
+    pub fn set_rows(&mut self, rows: Vec<Vec<String>>) -> bool {
+        let Some(table) = &mut self.table else {
+            return false;
+        };
+        table.rows = rows;
+        table.select(table.selected, self.h);
+        self.sync_table();
+
+        true
+    }
+
+    /// selects the row at idx of the table this text shows, the last row if idx is past it
+    /// the rows scroll for it to be shown
+    /// returns whether the selection changed
+    pub fn select_row(&mut self, idx: usize) -> bool {
+        let Some(table) = &mut self.table else {
+            return false;
+        };
+        let before = table.selected;
+        table.select(idx, self.h);
+        let changed = table.selected != before;
+        self.sync_table();
+
+        changed
+    }
+
+    // writes the header and the visible rows to the value
+    fn sync_table(&mut self) {
+        let Some(table) = &self.table else {
+            return;
+        };
+        self.value = table.cells(self.w, self.h).into();
+        self.scroll = 0;
+        self.mark_dirty();
+    }
+
+    // moves the selection of the table this text shows for a key press
+    // returns whether the selection changed, or None if the key is not a table key
+    pub(crate) fn table_key(&mut self, event: &KbdEvent) -> Option<bool> {
+        let table = self.table.as_ref()?;
+        let idx = moved_to(event, table.selected, self.h.saturating_sub(1) as usize)?;
+
+        Some(self.select_row(idx))
+    }
+}
+
+impl Term {
+    /// calls f with the table's id and the selected row's index whenever the selection
+    /// of a focused table moves, replaces the previous callback
+    pub fn on_row_select(&mut self, f: impl FnMut([u8; 3], usize) + 'static) {
+        self.list_hooks.row = Some(Box::new(f));
+    }
+
+    // hands the key to the focused table, if the focused text shows one
+    // returns None if the key is left to the keymaps
+    pub(crate) fn table_key(&mut self, event: &KbdEvent) -> Option<bool> {
+        let id = self.focused?;
+        let text = self.text_mut(&id)?;
+        let moved = text.table_key(event)?;
+        let idx = text.table.as_ref().unwrap().selected;
+        if let Some(hook) = self.list_hooks.row.as_mut().filter(|_| moved) {
+            hook(id, idx);
+        }
+
+        Some(moved)
+    }
+}
+
+#[cfg(test)]
+mod table {
+    use super::*;
+    use crate::components::Container;
+    use crate::inputs::keyboard::{Char, KeyKind, Modifiers, CC};
+    use crate::space::{border::Border, padding::Padding};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn processes() -> Table {
+        let columns = vec![
+            Column::new("pid", Width::Fixed(4)),
+            Column::new("name", Width::Auto),
+            Column::new("cpu", Width::Percent(25)),
+        ];
+        let rows = [
+            ["1", "init", "0.1"],
+            ["42", "cargo", "97.5"],
+            ["7", "sh", "0"],
+        ];
+        let rows = rows.map(|row| row.map(String::from).to_vec()).to_vec();
+
+        Table::new(columns, rows)
+    }
+
+    // a nonedit w wide and 3 high showing the processes
+    fn table_term(w: u16) -> Term {
+        let mut term = Term::new(0, 30, 8);
+        let mut c = Container::new([0, 0], 0, 0, 20, 4, Border::None, Padding::None);
+        let mut text = Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            0,
+            0,
+            w,
+            3,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        text.set_table(processes());
+        c.items.push(text);
+        term.containers.push(c);
+        term.focused = Some([0, 0, 1]);
+
+        term
+    }
+
+    fn rows(text: &Text) -> Vec<String> {
+        text.value
+            .to_vec()
+            .chunks(text.w as usize)
+            .map(|row| row.iter().map(|c| c.unwrap_or(' ')).collect())
+            .collect()
+    }
+
+    #[test]
+    fn layout() {
+        let table = processes();
+        assert_eq!(table.widths(18), [4, 5, 4]);
+        // 6 cells for 3 columns and 2 gaps, the widest ones go first
+        assert_eq!(table.widths(8), [3, 2, 1]);
+
+        let term = table_term(18);
+        let text = term.nonedit_ref(&[0, 0, 1]).unwrap();
+        assert_eq!(
+            rows(text),
+            [
+                "pid  name  cpu    ",
+                "1    init  0.1    ",
+                "42   cargo 97.5   "
+            ]
+        );
+
+        let term = table_term(11);
+        let text = term.nonedit_ref(&[0, 0, 1]).unwrap();
+        assert_eq!(rows(text), ["pid  na… c…", "1    in… 0…", "42   ca… 9…"]);
+    }
+
+    #[test]
+    fn sticky_header() {
+        let mut term = table_term(18);
+        let reported = Rc::new(RefCell::new(vec![]));
+        let seen = reported.clone();
+        term.on_row_select(move |id, idx| seen.borrow_mut().push((id, idx)));
+        let down = KbdEvent {
+            char: Char::CC(CC::Down),
+            modifiers: Modifiers(0),
+            kind: KeyKind::Press,
+        };
+
+        term.key(&down);
+        term.key(&down);
+        // the selection can't go past the last row
+        term.key(&down);
+        let text = term.nonedit_ref(&[0, 0, 1]).unwrap();
+        let table = text.table().unwrap();
+        assert_eq!([table.selected(), table.offset()], [2, 1]);
+        assert_eq!(rows(text)[0], "pid  name  cpu    ");
+        assert_eq!(rows(text)[2], "7    sh    0      ");
+        assert_eq!(table.highlighted(text.h).unwrap().0, 2);
+        assert_eq!(*reported.borrow(), [([0, 0, 1], 1), ([0, 0, 1], 2)]);
+
+        let text = term.nonedit_mut(&[0, 0, 1]).unwrap();
+        text.set_rows(vec![vec!["9".into()]]);
+        assert_eq!(text.table().unwrap().selected_row().unwrap(), ["9"]);
+        assert_eq!(rows(text)[1], "9                 ");
+    }
+}
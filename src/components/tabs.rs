@@ -0,0 +1,334 @@
+use crate::inputs::keyboard::{Char, KbdEvent, CC};
+use crate::themes::Style;
+
+use super::{Term, Text};
+
+/// a bar of tab labels, each showing one container and hiding the others when it is active,
+/// shown in the first row of a Text, see Term::set_tabs
+/// Left and Right, the 1 to 9 keys or a click on a label switch tabs, see Term::switch_tab
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tabs {
+    tabs: Vec<(String, [u8; 2])>,
+    active: usize,
+    // the text that was focused in each tab's container when it was last left
+    focus: Vec<Option<[u8; 3]>>,
+    // the style escape sequence of the active label
+    highlight: String,
+}
+
+impl Tabs {
+    /// makes new Tabs of (label, container id) pairs, the first tab is active
+    /// the active label is shown in reverse video unless told otherwise, see Tabs::highlight
+    pub fn new(tabs: Vec<(String, [u8; 2])>) -> Self {
+        Self {
+            focus: vec![None; tabs.len()],
+            tabs,
+            active: 0,
+            highlight: "\x1b[7m".to_string(),
+        }
+    }
+
+    /// changes the style of the active label
+    pub fn highlight(&mut self, style: &Style) {
+        self.highlight = style.style();
+    }
+
+    /// the (label, container id) pairs
+    pub fn tabs(&self) -> &[(String, [u8; 2])] {
+        &self.tabs
+    }
+
+    /// the index of the active tab
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// the id of the container the active tab shows, None if there are no tabs
+    pub fn active_container(&self) -> Option<[u8; 2]> {
+        self.tabs.get(self.active).map(|(_, id)| *id)
+    }
+
+    // the [start, end) cells of each label, the labels are " label " and 1 cell apart
+    fn spans(&self) -> Vec<[u16; 2]> {
+        let mut start = 0;
+        self.tabs
+            .iter()
+            .map(|(label, _)| {
+                let end = start + label.chars().count() as u16 + 2;
+                let span = [start, end];
+                start = end + 1;
+                span
+            })
+            .collect()
+    }
+
+    // the cells of the active label and their style
+    pub(crate) fn highlighted(&self) -> Option<([u16; 2], &str)> {
+        let span = *self.spans().get(self.active)?;
+
+        Some((span, &self.highlight))
+    }
+
+    // the tab whose label holds the cell x
+    fn at(&self, x: u16) -> Option<usize> {
+        self.spans()
+            .iter()
+            .position(|[start, end]| (*start..*end).contains(&x))
+    }
+}
+
+// tabs
+impl Text {
+    /// the tabs this text shows, if it shows some
+    pub fn tabs(&self) -> Option<&Tabs> {
+        self.tabs.as_ref()
+    }
+
+    // writes the labels to the first row, a bar between each 2 labels
+    fn sync_tabs(&mut self) {
+        let Some(tabs) = &self.tabs else {
+            return;
+        };
+        let w = self.w as usize;
+        let mut cells = vec![None; w * self.h as usize];
+        let labels = tabs.tabs.iter().map(|(label, _)| format!(" {} ", label));
+        let bar = labels.collect::<Vec<String>>().join("│");
+        cells
+            .iter_mut()
+            .take(w)
+            .zip(bar.chars())
+            .for_each(|(cell, c)| *cell = Some(c));
+        self.value = cells.into();
+        self.scroll = 0;
+        self.mark_dirty();
+    }
+}
+
+impl Term {
+    /// shows tabs in the first row of the text with id, replacing its value,
+    /// the container of the active tab is shown and the other tabs' containers are hidden
+    /// returns false if there is no such text
+    pub fn set_tabs(&mut self, id: &[u8; 3], tabs: Tabs) -> bool {
+        let Some(text) = self.text_mut(id) else {
+            return false;
+        };
+        let active = tabs.active;
+        text.tabs = Some(tabs);
+        text.set_focusable(true);
+        text.sync_tabs();
+        self.show_tab(id, active);
+
+        true
+    }
+
+    /// makes the tab at idx of the tabs the text with id shows the active one,
+    /// its container is shown and marked dirty, and the other tabs' containers are hidden
+    /// when the focus was inside the container that is hidden, it goes back to the text that
+    /// was focused in the shown container when it was last left, or to the tabs
+    /// returns whether the active tab changed
+    pub fn switch_tab(&mut self, id: &[u8; 3], idx: usize) -> bool {
+        let Some(tabs) = self.text_ref(id).and_then(Text::tabs) else {
+            return false;
+        };
+        if idx >= tabs.tabs.len() || idx == tabs.active {
+            return false;
+        }
+        let (left, active) = (tabs.active, tabs.tabs[idx].1);
+        let Some(left_container) = tabs.active_container() else {
+            return false;
+        };
+        let inside = self.focused.filter(|f| [f[0], f[1]] == left_container);
+        let restored = tabs.focus[idx];
+
+        let tabs = self.text_mut(id).unwrap().tabs.as_mut().unwrap();
+        tabs.focus[left] = inside.or(tabs.focus[left]);
+        tabs.active = idx;
+        let text = self.text_mut(id).unwrap();
+        text.sync_tabs();
+        self.show_tab(id, idx);
+        if inside.is_some() {
+            let focus = restored.filter(|f| [f[0], f[1]] == active).unwrap_or(*id);
+            _ = self.focus(&focus);
+        }
+
+        true
+    }
+
+    // shows the container of the tab at idx and hides the other tabs' containers,
+    // the cells the hidden ones took are blanked
+    fn show_tab(&mut self, id: &[u8; 3], idx: usize) {
+        let Some(tabs) = self.text_ref(id).and_then(Text::tabs) else {
+            return;
+        };
+        let containers = tabs
+            .tabs
+            .iter()
+            .map(|(_, id)| *id)
+            .collect::<Vec<[u8; 2]>>();
+        let others = containers.iter().enumerate().filter(|(tab, _)| *tab != idx);
+        for (_, cid) in others {
+            let Some(c) = self.container_mut(cid) else {
+                continue;
+            };
+            if !c.is_hidden() {
+                c.hide();
+                let area = c.outer_area();
+                self.uncover(area);
+            }
+        }
+        if let Some(c) = containers.get(idx).and_then(|cid| self.container_mut(cid)) {
+            c.show();
+        }
+    }
+
+    // switches the tabs of the focused text for Left, Right and the 1 to 9 keys
+    // returns None if the key is left to the keymaps
+    pub(crate) fn tabs_key(&mut self, event: &KbdEvent) -> Option<bool> {
+        let id = self.focused?;
+        let tabs = self.text_ref(&id)?.tabs()?;
+        if !event.modifiers.is_empty() {
+            return None;
+        }
+        let idx = match event.char {
+            Char::CC(CC::Left) if tabs.active == 0 => return Some(false),
+            Char::CC(CC::Left) => tabs.active - 1,
+            Char::CC(CC::Right) => tabs.active + 1,
+            Char::Char(c @ '1'..='9') => c as usize - '1' as usize,
+            _ => return None,
+        };
+
+        Some(self.switch_tab(&id, idx))
+    }
+
+    // the tab under the 0 based column x of the text with id, if it shows tabs
+    pub(crate) fn tab_at(&self, id: &[u8; 3], x: u16) -> Option<usize> {
+        let text = self.text_ref(id)?;
+        let x = (x + 1).checked_sub(text.ax0)?;
+
+        text.tabs()?.at(x)
+    }
+}
+
+#[cfg(test)]
+mod tabs {
+    use super::*;
+    use crate::components::{Container, TextKind};
+    use crate::inputs::keyboard::{KeyKind, Modifiers};
+    use crate::inputs::mouse::{MouseButton, MouseEvent, MouseKind};
+    use crate::space::{border::Border, padding::Padding};
+
+    // a tab bar in container 0 switching between containers 1 and 2, an input in each
+    fn app() -> Term {
+        let mut term = Term::new(0, 30, 8);
+        for cid in 0..3 {
+            let mut c = Container::new(
+                [0, cid],
+                0,
+                cid.min(1) as u16,
+                20,
+                4,
+                Border::None,
+                Padding::None,
+            );
+            let kind = if cid == 0 { TextKind::NonEdit } else { TextKind::Input };
+            let text = Text::of_kind(
+                kind,
+                [0, cid, if cid == 0 { 1 } else { 2 }],
+                0,
+                0,
+                1,
+                cid.min(1) as u16 + 1,
+                20,
+                1,
+                &[],
+                Border::None,
+                Padding::None,
+            );
+            c.items.push(text);
+            term.containers.push(c);
+        }
+        let tabs = Tabs::new(vec![("one".into(), [0, 1]), ("two".into(), [0, 2])]);
+        term.set_tabs(&[0, 0, 1], tabs);
+
+        term
+    }
+
+    fn key(char: Char) -> KbdEvent {
+        KbdEvent {
+            char,
+            modifiers: Modifiers(0),
+            kind: KeyKind::Press,
+        }
+    }
+
+    fn hidden(term: &Term) -> [bool; 2] {
+        [1, 2].map(|cid| term.container_ref(&[0, cid]).unwrap().is_hidden())
+    }
+
+    #[test]
+    fn switch() {
+        let mut term = app();
+        let text = term.nonedit_ref(&[0, 0, 1]).unwrap();
+        assert_eq!(text.value_string(), " one │ two ");
+        assert_eq!(
+            text.tabs().unwrap().highlighted(),
+            Some(([0, 5], "\x1b[7m"))
+        );
+        assert_eq!(hidden(&term), [false, true]);
+
+        _ = term.focus(&[0, 0, 1]);
+        term.key(&key(Char::CC(CC::Right)));
+        assert_eq!(hidden(&term), [true, false]);
+        assert!(term
+            .container_ref(&[0, 2])
+            .unwrap()
+            .attributes
+            .contains("dirty"));
+        // the second container was blanked by set_tabs, the first one now
+        assert_eq!(term.uncovered.len(), 2);
+        // the hidden input is gone through by Tab no more
+        term.focus_next();
+        assert_eq!(term.focused, Some([0, 2, 2]));
+        term.focus_next();
+        assert_eq!(term.focused, Some([0, 0, 1]));
+
+        // there is no third tab
+        term.key(&key(Char::CC(CC::Right)));
+        assert_eq!(hidden(&term), [true, false]);
+        term.key(&key(Char::Char('1')));
+        assert_eq!(hidden(&term), [false, true]);
+        // the focus was on the tabs, it stays there
+        assert_eq!(term.focused, Some([0, 0, 1]));
+    }
+
+    #[test]
+    fn restore_focus() {
+        let mut term = app();
+        _ = term.focus(&[0, 1, 2]);
+        // a click on the second label, at the 8th column
+        let click = MouseEvent {
+            column: 7,
+            row: 0,
+            kind: MouseKind::Down,
+            button: MouseButton::Left,
+            modifiers: Modifiers(0),
+        };
+        term.mouse(&click);
+        assert_eq!(
+            term.nonedit_ref(&[0, 0, 1])
+                .unwrap()
+                .tabs()
+                .unwrap()
+                .active(),
+            1
+        );
+        // nothing was focused in the second tab yet
+        assert_eq!(term.focused, Some([0, 0, 1]));
+
+        _ = term.focus(&[0, 2, 2]);
+        term.switch_tab(&[0, 0, 1], 0);
+        assert_eq!(term.focused, Some([0, 1, 2]));
+        term.switch_tab(&[0, 0, 1], 1);
+        assert_eq!(term.focused, Some([0, 2, 2]));
+    }
+}
@@ -3,22 +3,41 @@ use std::io::StdoutLock;
 use std::io::Write;
 
 use crate::console::winsize::winsize;
-use crate::render_pipeline;
+use crate::console::Capabilities;
+use crate::render_pipeline::{self, Scratch};
 use crate::space::{
-    area_conflicts, between,  border_fit, calc_text_abs_ori, resolve_wh, Area, border::Border, padding::Padding, Pos,
+    area_conflicts, between,  border_fit, fit, overlap, calc_text_abs_ori, place, resolve_wh, Area, Polygon, border::Border, padding::Padding, Pos,
 };
+use crate::events::Event;
+use crate::inputs::actions::{Action, ActionResult, Actions, InsertChar};
+use crate::inputs::keyboard::{KbdEvent, KeyKind, Modifiers, SHIFT};
+use super::list::ListHooks;
+use super::popup::Popup;
+use super::scrollbar::Axis;
+use super::status::StatusBar;
+use crate::diagnostics::{self, Level};
+use crate::inputs::history::{History, HistoryKey};
+use crate::inputs::keyboard::{Char, CC};
+use crate::inputs::keymap::{self, InputAction, Keymap};
+use crate::inputs::vim::{self, KillRing, Mode, ModeHook};
+use crate::inputs::mouse::{MouseButton, MouseEvent, MouseKind};
+use crate::inputs::window::WindowEvent;
+#[cfg(feature = "layout")]
 use crate::layout::Layout;
-use crate::themes::Style;
+use crate::themes::{Style, Theme};
 
 use super::{ContainerMeta, NonEditMeta, InputMeta};
 use super::Property;
 use super::{ComponentTreeError, SpaceError, IdError};
-use super::{Container, Text};
+use super::{Container, Hook, Text, TextKind};
 
 /// Term object that is basically the overall wrapper around back end for the terminal display
-#[derive(Debug, Default)]
+/// a clone shares the registered actions but gets none of the on_* hooks, which are left out
+/// of the comparisons too
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Term {
     /// the layout
+    #[cfg(feature = "layout")]
     pub layout: Layout,
     /// this Term's id
     pub id: u8,
@@ -43,6 +62,119 @@ pub struct Term {
     /// attributes are like properties but they dont have values, only names
     /// e.g., focusable
     pub attributes: HashSet<&'static str>,
+    /// whether this Term takes over the alternate screen or renders inline in the main one
+    pub mode: ScreenMode,
+    /// what the terminal can display, the render pipeline adapts its output to these
+    pub capabilities: Capabilities,
+    /// how Term::bell gives its feedback
+    pub bell_mode: BellMode,
+    /// the styles this Term's components take in their states, see Theme
+    pub theme: Theme,
+    // the cursor position at the last save_cursor call
+    pub(crate) saved_cursor: Option<[u16; 2]>,
+    /// the app's key bindings, Term::key looks keys up here before the default editing keys
+    pub keymap: Keymap,
+    /// the app's actions, which keys get bound to through InputAction::Named
+    pub actions: Actions,
+    /// what the vim mode inputs deleted and yanked, shared between the inputs
+    pub kill_ring: KillRing,
+    // the values submitted in the inputs, see Term::history
+    pub(crate) histories: HashMap<HistoryKey, History>,
+    // what Term::on_mode_change was given
+    pub(crate) mode_hook: ModeHook,
+    // what Term::on_reject was given
+    pub(crate) reject_hook: super::filter::RejectHook,
+    // what Term::on_cursor_move was given
+    pub(crate) cursor_hook: Hook<super::cursor_info::CursorHook>,
+    // what Term::on_before_render and Term::on_after_render were given
+    pub(crate) render_hooks: Hook<super::render_hooks::RenderHooks>,
+    // the cursor drawn by the library, see Term::set_soft_cursor
+    pub(crate) soft_cursor: super::soft_cursor::SoftCursor,
+    // what Term::on_select and Term::on_activate were given
+    pub(crate) list_hooks: Hook<ListHooks>,
+    // the containers in flight, see Term::animate_move
+    pub(crate) animations: Vec<super::animate::Animation>,
+    // the areas closed dropdowns left, blanked by the next render_dirty call
+    pub(crate) uncovered: Vec<[u16; 4]>,
+    // the open popups, the topmost last
+    pub(crate) popups: Vec<Popup>,
+    // the containers the focus is confined to, the one in use last, see Term::push_focus_scope
+    pub(crate) focus_scopes: Vec<[u8; 2]>,
+    // the text of the keymap help popup, see Term::show_keymap_help
+    pub(crate) keymap_help: Option<[u8; 3]>,
+    // the status bar, see Term::status_bar
+    pub(crate) status: Option<StatusBar>,
+    // the text under the pointer, see Term::hovered
+    pub(crate) hover: super::hover::Hover,
+    // what Term::wheel_lines was given
+    pub(crate) wheel_lines: Option<u16>,
+    // the order Tab goes through the texts in, see Term::tab_order
+    pub(crate) tab_order: super::TabOrder,
+    // the areas the containers take, see Term::area_taken
+    pub(crate) areas: super::cache::Areas,
+    // the areas the texts are hit in, see Term::hit_test
+    pub(crate) hits: super::hit::Hits,
+    // the count of the changes to the geometry of its components, see Term::hit_test
+    pub(crate) changes: super::hit::Changes,
+    // the text whose scrollbar is dragged, and the scrollbar's edge
+    pub(crate) scrollbar_drag: Option<([u8; 3], Axis)>,
+    // the buffers Term::render puts the frames together in
+    pub(crate) scratch: Scratch,
+    // what Term::backend was given
+    pub(crate) backend: render_pipeline::Held,
+    // the rows of the main screen an inline term is drawn in, see Term::set_inline
+    pub(crate) inline: Option<render_pipeline::Inline>,
+    // what a linear term last wrote, see ScreenMode::Linear
+    pub(crate) linear: render_pipeline::Linear,
+    // the rows Term::print_line prints in, see Term::print_rows
+    pub(crate) print_rows: Option<[u16; 2]>,
+    // the stats of the rendered frames, see Term::collect_stats
+    #[cfg(feature = "stats")]
+    pub(crate) stats: Option<super::stats::Collector>,
+}
+
+/// decides what Term::bell does
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum BellMode {
+    /// writes BEL, the terminal decides what to do with it
+    #[default]
+    Audible,
+    /// briefly flashes the focused text's border, or the whole screen when nothing is focused
+    Visual,
+    /// the bell is silenced
+    None,
+}
+
+/// a pending visual bell flash
+/// hand it back to Term::flush_bell once it is due so that the flash gets undrawn
+#[derive(Debug, Clone, PartialEq)]
+pub struct BellToken {
+    /// the flashed text, None if the whole screen was flashed
+    pub target: Option<[u8; 3]>,
+    /// when the flash should end
+    pub until: std::time::Instant,
+}
+
+impl BellToken {
+    /// whether the flash has lasted long enough
+    pub fn due(&self) -> bool {
+        std::time::Instant::now() >= self.until
+    }
+}
+
+/// decides which terminal screen a Term renders to
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ScreenMode {
+    /// render inside the main screen, among the shell's scrollback
+    #[default]
+    Inline,
+    /// render inside the alternate screen, the main screen and its scrollback are left intact
+    Fullscreen,
+    /// write the components as plain lines in reading order, without moving the cursor or
+    /// styling anything, for screen readers and dumb terminals, the keys are still handled
+    /// Term::render writes every text, Term::render_dirty the texts that changed and the focus
+    /// when it moves, see Term::linear_text and Term::linear_from_env
+    Linear,
 }
 
 impl Term {
@@ -59,19 +191,93 @@ impl Term {
     /// the term method would always validate the the new id before creating a term inside the tree
     /// if this function is called alongside tree's push_term() method then validating this term's
     /// id becomes the caller's job
+    ///
+    /// # Panics
+    /// panics if w or h is 0, see Term::try_new
     pub fn new(id: u8, w: u16, h: u16) -> Self {
-        Term {
+        match Self::try_new(id, w, h) {
+            Ok(term) => term,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// makes a new Term like Term::new
+    ///
+    /// # Errors
+    /// returns EmptyArea if w or h is 0, nothing can be put in or drawn to such a term
+    pub fn try_new(id: u8, w: u16, h: u16) -> Result<Self, ComponentTreeError> {
+        if w == 0 || h == 0 {
+            return Err(ComponentTreeError::EmptyArea { area: [w, h] });
+        }
+
+        Ok(Term {
             id,
             w,
             h,
+            capabilities: Capabilities::detected().clone(),
             ..Self::default()
+        })
+    }
+
+    /// changes what Term::bell does
+    pub fn bell_mode(&mut self, mode: BellMode) {
+        self.bell_mode = mode;
+    }
+
+    /// overrides the detected terminal capabilities
+    /// e.g., to force ascii borders or disable truecolor
+    pub fn capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = capabilities;
+    }
+
+    /// turns the colors off, or back on, whatever the terminal can display, the styles keep
+    /// their other attributes, see Capabilities::monochrome, it starts as NO_COLOR tells
+    /// the whole display is drawn again by the next Term::render_dirty call
+    pub fn set_monochrome(&mut self, monochrome: bool) {
+        self.capabilities.monochrome = monochrome;
+        self.containers.iter_mut().for_each(Container::mark_dirty);
+    }
+
+    // the style of the theme under key, without its colors on a monochrome term
+    pub(crate) fn theme_style(&self, key: &str) -> Option<String> {
+        let style = self.theme.get(key)?;
+
+        Some(match self.capabilities.monochrome {
+            true => self.capabilities.adapt_sgr(style),
+            false => style.to_string(),
+        })
+    }
+
+    /// changes which screen this Term renders to
+    /// takes effect the next time the Term is started
+    /// a fullscreen or linear Term is not inline anymore, see Term::set_inline
+    pub fn screen_mode(&mut self, mode: ScreenMode) {
+        self.mode = mode;
+        if mode != ScreenMode::Inline {
+            self.inline = None;
         }
     }
 
+    /// returns a new term that takes the whole terminal window
+    /// when the window size can't be detected, the term falls back to
+    /// winsize::DEFAULT_COLS x winsize::DEFAULT_ROWS, see Term::try_with_area
     pub fn with_area(id: u8) -> Self  {
-        let ws = winsize::from_ioctl();
-        Term {
-            id, w: ws.cols(), h: ws.rows(), ..Default::default()        }
+        let ws = crate::console::winsize::detect().unwrap_or_else(|_| winsize::fallback());
+
+        Self::new(id, ws.cols(), ws.rows())
+    }
+
+    /// returns a new term that takes the whole terminal window
+    /// the size is that of the terminal behind stdout or stderr, or the COLUMNS and LINES
+    /// environment variables, see winsize::detect_terminal
+    ///
+    /// # Errors
+    /// returns WindowSize when there is no terminal or COLUMNS or LINES holds a bad value,
+    /// so that a program that isn't run in a terminal doesn't lay itself out in a made up one
+    pub fn try_with_area(id: u8) -> Result<Self, ComponentTreeError> {
+        let ws = crate::console::winsize::detect_terminal().map_err(ComponentTreeError::WindowSize)?;
+
+        Self::try_new(id, ws.cols(), ws.rows())
     }
 
     // since overlay is not implemented yet, this doesn't assign anything but just checks that the
@@ -82,36 +288,14 @@ impl Term {
         cont: &Container,
         // layer: u8,
     ) -> Result<(), SpaceError> {
-        let [x0, y0] = [cont.x0, cont.y0];
-        let [w, h] = cont.decorate();
-
-        if self.w * self.h < w * h
-            || x0 > self.w
-            || y0 > self.h
-            || w > self.w
-            || h > self.h
-            || x0 + w > self.w
-            || y0 + h > self.h
-        {
-            return Err(SpaceError::AreaOutOfBounds);
-        }
-
-        let mut e = 0;
-
-        self.containers.iter().for_each(|c| {
-            if e == 0 {
-                let [top, right, bottom, left] =
-                    area_conflicts(x0, y0, cont.w, cont.h, c.x0, c.y0, c.w, c.h);
-                // conflict case
-                if (left > 0 || right < 0) && (top > 0 || bottom < 0) {
-                    // TODO: actually handle overlay logic
-                    e = 1;
-                }
-            }
-        });
-
-        if e == 1 {
-            return Err(SpaceError::OriginOutOfBounds);
+        fit([cont.x0, cont.y0], cont.decorate(), [self.w, self.h])?;
+
+        // containers on other layers are rendered over each other, a container with the id
+        // of cont is the one it takes the place of
+        if let Some(other) = self.area_taken(cont.id, cont.layer, cont.outer_area()) {
+            return Err(SpaceError::AreaTaken {
+                id: other.id.to_vec(),
+            });
         }
 
         Ok(())
@@ -125,38 +309,121 @@ impl Term {
 
 impl Term {
     /// syncs the position of the cursor in the term display to match the data in the backend
+    /// the cursor is put on the cell of the focused text's view its cursor is shown on, the scroll
+    /// offsets taken off, and stays inside that view and the term, even when the text's cursor
+    /// was left outside of its area, see Text::clamp_cursor
+    ///
+    /// # Errors
+    ///
+    /// returns NothingFocused when no text is focused and BadID when the focused id
+    /// doesn't lead to a text anymore, the cursor doesn't move then
     pub fn sync_cursor(&mut self) -> Result<(), ComponentTreeError> {
-        let id = self.focused.unwrap();
-        let text = if id[2] % 2 == 0 {
-            self.input_ref(&id)
-        } else {
-            self.nonedit_ref(&id)
-        }
-        .unwrap();
+        let id = self.focused.ok_or(ComponentTreeError::NothingFocused)?;
+        let text = self
+            .text_ref(&id)
+            .ok_or_else(|| ComponentTreeError::BadID { id: id.to_vec() })?;
 
-        let [cx, cy] = [text.ax0 + text.cx, text.ay0 + text.cy];
+        let [x, y] = text.view_cursor();
+        let cx = text.ax0.saturating_add(x).min(self.w);
+        let cy = text.ay0.saturating_add(y).min(self.h);
 
-        self.cx = cx;
-        self.cy = cy;
+        Ok(self.move_cursor_to(cx, cy)?)
+    }
+
+    /// moves the terminal cursor to the [x, y] cell, x is the 1 based column like the one of the
+    /// components' absolute origins
+    /// this is the one place cx and cy get changed and the cursor movement gets written,
+    /// through the backend this Term was given, or to stdout when it is a terminal,
+    /// see Term::backend, a linear Term only changes cx and cy, see ScreenMode::Linear
+    ///
+    /// # Errors
+    ///
+    /// returns an error if the cell is outside of this term, x past w or y past h,
+    /// in which case the cursor doesn't move
+    pub fn move_cursor_to(&mut self, x: u16, y: u16) -> Result<(), SpaceError> {
+        if x > self.w || y > self.h {
+            return Err(SpaceError::OriginOutOfBounds {
+                origin: [x, y],
+                available: [self.w, self.h],
+            });
+        }
+
+        self.cx = x;
+        self.cy = y;
+        let cup = render_pipeline::cup(x, self.screen_row(y));
+        self.write_cursor(cup.as_bytes());
 
         Ok(())
     }
 
+    // writes a cursor sequence, a linear term doesn't move the cursor, see ScreenMode::Linear
+    fn write_cursor(&mut self, bytes: &[u8]) {
+        if self.mode != ScreenMode::Linear {
+            self.write_out(bytes);
+        }
+    }
+
+    /// saves the cursor position, DECSC
+    /// the position is also kept here so that restore_cursor works
+    /// even when the terminal has lost track of it in the meantime
+    pub fn save_cursor(&mut self) {
+        self.saved_cursor = Some([self.cx, self.cy]);
+        self.write_cursor(b"\x1b7");
+    }
+
+    /// moves the cursor back to where it was at the last save_cursor call, DECRC
+    /// does nothing if the cursor was never saved
+    pub fn restore_cursor(&mut self) {
+        let Some([x, y]) = self.saved_cursor.take() else {
+            return;
+        };
+        self.write_cursor(b"\x1b8");
+        // the term may have shrunk since the save
+        _ = self.move_cursor_to(x.min(self.w), y.min(self.h));
+    }
+
     /// makes the text object with the given id the term's current active object
     /// places cursor in the new position by calling sync_cursor
     // TODO: probably make the entire focus part of ragout-extended crate
+    ///
+    /// # Errors
+    /// returns BadID if there is no text with id or it is behind an open popup,
+    /// OutOfFocusScope if it is outside of the focus scope in use, see Term::push_focus_scope,
+    /// Disabled if it is disabled and NotFocusable if it is hidden or not focusable
     pub fn focus(&mut self, id: &[u8; 3]) -> Result<(), ComponentTreeError> {
-        let condition = match id[2] % 2 == 0 {
-            true => self.has_input(&id),
-            false => self.has_nonedit(&id),
-        };
+        self.can_focus(id)?;
 
-        if !condition {
-            return Err(ComponentTreeError::BadID);
+        if self.focused != Some(*id) {
+            self.leave_history();
+            for (id, focused) in [(self.focused, false), (Some(*id), true)] {
+                if let Some(text) = id.and_then(|id| self.text_mut(&id)) {
+                    text.focus_checkbox(focused);
+                }
+            }
         }
-
         self.focused = Some(*id);
-        self.sync_cursor();
+
+        self.sync_cursor()
+    }
+
+    // whether the text with id can be focused, by Term::focus, Tab or a click
+    pub(crate) fn can_focus(&self, id: &[u8; 3]) -> Result<(), ComponentTreeError> {
+        let text = self.text_ref(id);
+        // nothing behind an open popup can be focused
+        let Some(text) = text.filter(|_| self.reachable(id)) else {
+            return Err(ComponentTreeError::BadID { id: id.to_vec() });
+        };
+        if !self.in_focus_scope(id) {
+            let scope = self.focus_scope().unwrap();
+            return Err(ComponentTreeError::OutOfFocusScope { id: *id, scope });
+        }
+        let cont = self.container_ref(&[id[0], id[1]]).unwrap();
+        if cont.is_hidden() || text.is_hidden() || !text.as_widget().wants_focus() {
+            return Err(ComponentTreeError::NotFocusable { id: *id });
+        }
+        if text.is_disabled() {
+            return Err(ComponentTreeError::Disabled { id: *id });
+        }
 
         Ok(())
     }
@@ -172,24 +439,780 @@ impl Term {
         // let id = self.active.unwrap_or(return Err(ComponentTreeError::BadID));
         let id = match self.focused {
             Some(id) => id,
-            None => return Err(ComponentTreeError::BadID),
+            None => return Err(ComponentTreeError::NothingFocused),
         };
 
-        match id[2] % 2 == 0 {
-            true => {
-                let t = self.input_ref(&id).unwrap();
-                Ok([t.ax0, t.ay0])
+        let t = self.text_ref(&id).unwrap();
+        Ok([t.ax0, t.ay0])
+    }
+}
+
+impl Term {
+    /// hides the container or the text with id, see Container::hide and Text::hide
+    /// the next Term::render_dirty call blanks the cells it took and renders again what is
+    /// under them, a focused text that is hidden, or in a hidden container, loses the focus
+    ///
+    /// # Errors
+    /// returns BadID if id is not the 2 bytes id of a container or the 3 bytes id of a text
+    /// of this term
+    pub fn hide(&mut self, id: &[u8]) -> Result<(), ComponentTreeError> {
+        self.hide_with(id, false)
+    }
+
+    /// hides the container or the text with id like Term::hide, the components around it
+    /// can then be placed over its area, see Container::collapse and Text::collapse
+    pub fn hide_collapsed(&mut self, id: &[u8]) -> Result<(), ComponentTreeError> {
+        self.hide_with(id, true)
+    }
+
+    fn hide_with(&mut self, id: &[u8], collapse: bool) -> Result<(), ComponentTreeError> {
+        let area = match *id {
+            [t, c] => {
+                let cont = self
+                    .container_mut(&[t, c])
+                    .ok_or_else(|| ComponentTreeError::BadID { id: id.to_vec() })?;
+                let shown = !cont.is_hidden();
+                match collapse {
+                    true => cont.collapse(),
+                    false => cont.hide(),
+                }
+                shown.then(|| cont.outer_area())
             }
-            false => {
-                let t = self.nonedit_ref(&id).unwrap();
-                Ok([t.ax0, t.ay0])
+            [t, c, i] => {
+                let text = self
+                    .text_mut(&[t, c, i])
+                    .ok_or_else(|| ComponentTreeError::BadID { id: id.to_vec() })?;
+                let shown = !text.is_hidden();
+                match collapse {
+                    true => text.collapse(),
+                    false => text.hide(),
+                }
+                shown.then(|| text.outer_area())
             }
+            _ => return Err(ComponentTreeError::BadID { id: id.to_vec() }),
+        };
+        if let Some(area) = area {
+            self.uncover(area);
+        }
+        if self.focused.is_some_and(|f| f.starts_with(id)) {
+            self.focused = None;
         }
+
+        Ok(())
+    }
+
+    /// shows the hidden, or collapsed, container or text with id again
+    /// it is marked dirty, as well as what it is shown over, so that the next
+    /// Term::render_dirty call draws it where it was
+    ///
+    /// # Errors
+    /// returns BadID if id is not the id of a container or a text of this term
+    pub fn show(&mut self, id: &[u8]) -> Result<(), ComponentTreeError> {
+        let area = match *id {
+            [t, c] => self.container_mut(&[t, c]).map(|cont| {
+                cont.show();
+                cont.outer_area()
+            }),
+            [t, c, i] => self.text_mut(&[t, c, i]).map(|text| {
+                text.show();
+                text.outer_area()
+            }),
+            _ => None,
+        };
+        let area = area.ok_or_else(|| ComponentTreeError::BadID { id: id.to_vec() })?;
+        self.mark_dirty_over(area);
+
+        Ok(())
+    }
+
+    /// gives the container or the text with id the style its content is rendered in,
+    /// see Container::set_style and Text::set_style, it is marked dirty
+    ///
+    /// # Errors
+    /// returns BadID if id is not the id of a container or a text of this term
+    pub fn set_style(&mut self, id: &[u8], style: Style) -> Result<(), ComponentTreeError> {
+        let set = match *id {
+            [t, c] => self.container_mut(&[t, c]).map(|cont| cont.set_style(Some(style))),
+            [t, c, i] => self.text_mut(&[t, c, i]).map(|text| text.set_style(Some(style))),
+            _ => None,
+        };
+
+        set.ok_or_else(|| ComponentTreeError::BadID { id: id.to_vec() })
+    }
+
+    /// disables or enables the text with id, it is marked dirty
+    /// a disabled text is rendered in the "input.disabled" style of this term's theme,
+    /// Tab skips it and it takes no edits, see Text::disable
+    /// the focus goes to the next text that can be focused when the focused text is disabled,
+    /// nothing is focused when there is none
+    ///
+    /// # Errors
+    /// returns BadID if there is no text with id
+    pub fn set_disabled(&mut self, id: &[u8; 3], disabled: bool) -> Result<(), ComponentTreeError> {
+        let style = self.theme_style("input.disabled");
+        let text = self
+            .text_mut(id)
+            .ok_or_else(|| ComponentTreeError::BadID { id: id.to_vec() })?;
+        if text.is_disabled() == disabled {
+            return Ok(());
+        }
+        match (disabled, style) {
+            (true, Some(style)) => {
+                text.dstyle = style;
+                text.disable();
+            }
+            (true, None) => text.disable(),
+            (false, _) => text.enable(),
+        }
+        text.mark_dirty();
+
+        if disabled && self.focused == Some(*id) {
+            self.focus_next();
+        }
+        // the disabled text was the only one that could be focused
+        if disabled && self.focused == Some(*id) {
+            self.leave_history();
+            if let Some(text) = self.text_mut(id) {
+                text.focus_checkbox(false);
+            }
+            self.focused = None;
+        }
+
+        Ok(())
+    }
+    /// makes the text with id an input, which is edited, or a nonedit, which is read-only
+    /// the text stays where it is in its container with its value, cursor and keymap
+    /// a text made editable is made focusable, a text made read-only stays as focusable as it was,
+    /// so the focused text keeps the focus and its keymap can make it editable again
+    ///
+    /// # Errors
+    /// returns BadID if there is no text with id
+    pub fn set_editable(&mut self, id: &[u8; 3], editable: bool) -> Result<(), ComponentTreeError> {
+        let kind = match editable {
+            true => TextKind::Input,
+            false => TextKind::NonEdit,
+        };
+        let text = self
+            .text_ref(id)
+            .ok_or_else(|| ComponentTreeError::BadID { id: id.to_vec() })?;
+        if text.kind == kind {
+            return Ok(());
+        }
+        // a history search or browse of the focused input ends before it turns read-only
+        if !editable && self.focused == Some(*id) {
+            self.leave_history();
+        }
+
+        let text = self.text_mut(id).unwrap();
+        text.kind = kind;
+        if editable {
+            text.set_focusable(true);
+        }
+
+        Ok(())
+    }
+}
+
+impl Term {
+    /// returns the id of the topmost text whose area, border included, holds the [x, y] cell
+    /// the coordinates are 0 based, like the ones of MouseEvent
+    /// the part of a text that is clipped by its container can not be hit, see Term::hit_test
+    pub fn component_at(&self, x: u16, y: u16) -> Option<[u8; 3]> {
+        self.hit_test(x, y)
+    }
+
+    /// reacts to a mouse event
+    /// a widget under the pointer gets the clicks and the wheel first, see Widget::handle_event
+    /// a left click on an input focuses it, one on a tab label switches to that tab,
+    /// one on a scrollbar scrolls there, see Scrollbar, and the wheel scrolls the text under
+    /// the pointer, see Term::wheel_lines
+    /// the motion with no button pressed moves the hover, see Term::hovered
+    /// returns the id of the text under the pointer
+    pub fn mouse(&mut self, event: &MouseEvent) -> Option<[u8; 3]> {
+        let placed = self.unplaced(event)?;
+        let event = &placed;
+        if event.kind == MouseKind::Move {
+            return self.hover_mouse(event);
+        }
+        if let Some(id) = self.scrollbar_mouse(event) {
+            return Some(id);
+        }
+        let id = self
+            .component_at(event.column, event.row)
+            .filter(|id| self.reachable(id));
+        // a widget gets the event before the built-in handling, and the focus from a click
+        if let Some(id) = id.filter(|id| self.widget_mouse(id, event).is_some()) {
+            if (event.kind, event.button) == (MouseKind::Down, MouseButton::Left) {
+                _ = self.focus(&id);
+            }
+            return Some(id);
+        }
+        if let Some(lines) = self.wheel(event.kind, event.modifiers) {
+            self.scroll_under(id, lines);
+            return id;
+        }
+        let id = id?;
+
+        match (event.kind, event.button) {
+            (MouseKind::Down, MouseButton::Left) if self.tab_at(&id, event.column).is_some() => {
+                let tab = self.tab_at(&id, event.column).unwrap();
+                self.switch_tab(&id, tab);
+            }
+            (MouseKind::Down, MouseButton::Left) if self.can_focus(&id).is_ok() => {
+                _ = self.focus(&id);
+            }
+            _ => (),
+        }
+
+        Some(id)
+    }
+}
+
+// wheel scrolling
+impl Term {
+    /// changes the number of lines, or columns, a wheel notch scrolls by, 3 by default
+    pub fn wheel_lines(&mut self, lines: u16) {
+        self.wheel_lines = Some(lines);
+    }
+
+    // the [columns, lines] a wheel event scrolls by, shift turns the vertical wheel sideways
+    // None if the event is no wheel event
+    fn wheel(&self, kind: MouseKind, modifiers: Modifiers) -> Option<[i16; 2]> {
+        let notch = self.wheel_lines.unwrap_or(3).min(i16::MAX as u16) as i16;
+        let shift = modifiers.0 & SHIFT != 0;
+        let [columns, lines] = match kind {
+            MouseKind::ScrollUp => [0, -notch],
+            MouseKind::ScrollDown => [0, notch],
+            MouseKind::ScrollLeft => [-notch, 0],
+            MouseKind::ScrollRight => [notch, 0],
+            _ => return None,
+        };
+
+        Some(match shift {
+            true => [columns + lines, 0],
+            false => [columns, lines],
+        })
+    }
+
+    // scrolls the text under the pointer, or the focused text when the one under the pointer
+    // has nothing to scroll to that way, both ends clamp the offset
+    fn scroll_under(&mut self, under: Option<[u8; 3]>, [columns, lines]: [i16; 2]) {
+        let scrollable = |text: &Text| match lines {
+            0 => text.max_hscroll() > 0,
+            _ => text.max_scroll() > 0,
+        };
+        let focused = self.focused.filter(|id| self.reachable(id));
+        let target = [under, focused]
+            .into_iter()
+            .flatten()
+            .find(|id| self.text_ref(id).is_some_and(scrollable));
+        let Some(text) = target.and_then(|id| self.text_mut(&id)) else {
+            return;
+        };
+        match lines {
+            0 => text.hscroll_by(columns),
+            _ => text.scroll_by(lines),
+        };
+    }
+}
+
+impl Term {
+    /// gives this term the new terminal window size, e.g. on an Event::Resize
+    /// every container is marked dirty, since a resized terminal has to be drawn anew,
+    /// and the status bar is kept at the bottom, as wide as the terminal
+    /// the cursors of the texts are brought back inside their areas, see Text::clamp_cursor
+    pub fn resize(&mut self, w: u16, h: u16) {
+        self.w = w;
+        self.h = h;
+        self.reanchor(h);
+        self.forget_hover_cell();
+        self.reflow_status();
+        self.containers.iter_mut().for_each(|c| {
+            c.items.iter_mut().for_each(|t| _ = t.clamp_cursor());
+            c.mark_dirty();
+        });
     }
 }
 
 impl Term {
-    /// adds a new Container object to this Term's containers
+    /// hands a key press to the widget of the focused text, see Widget::handle_event,
+    /// one it ignores is resolved through the focused component's keymap, this Term's keymap
+    /// then Keymap::defaults, and the action it is bound to is applied, see Term::apply
+    /// a char that is not bound is typed into the focused input, see InsertChar
+    /// returns the action the key resolved to, so that the app can take care of the actions
+    /// that are left to it, like Submit or Named ones with no registered action
+    /// key releases do nothing and resolve to no action
+    pub fn key(&mut self, event: &KbdEvent) -> Option<InputAction> {
+        let action = self.key_event(event).0;
+        self.hold_cursor();
+        self.report_cursor();
+
+        action
+    }
+
+    /// hands event to the action it is bound to
+    /// keys go through the keymaps like in Term::key, pastes are typed into the focused input,
+    /// both go to the focused widget first, see Widget, other events do nothing here
+    pub fn dispatch(&mut self, event: &Event) -> ActionResult {
+        match event {
+            Event::Key(ke) => {
+                let result = self.key_event(ke).1;
+                self.hold_cursor();
+                self.report_cursor();
+
+                result
+            }
+            Event::Paste(paste) => ActionResult {
+                redraw: match self.widget_key(event) {
+                    Some(drawn) => drawn,
+                    None => self.paste(&paste.text),
+                },
+                submit: false,
+            },
+            _ => ActionResult::default(),
+        }
+    }
+
+    fn key_event(&mut self, event: &KbdEvent) -> (Option<InputAction>, ActionResult) {
+        if event.kind == KeyKind::Release {
+            return (None, ActionResult::default());
+        }
+        if let Some(scrolled) = self.keymap_help_key(event) {
+            return (None, ActionResult { redraw: scrolled, submit: false });
+        }
+        if self.focused_disabled() {
+            return self.disabled_key(event);
+        }
+        if let Some(drawn) = self.widget_key(&Event::Key(event.clone())) {
+            return (None, ActionResult { redraw: drawn, submit: false });
+        }
+        if let Some(edited) = self.vim_key(event) {
+            return (None, ActionResult { redraw: edited, submit: false });
+        }
+        if let Some(edited) = self.search_key(event) {
+            return (None, ActionResult { redraw: edited, submit: false });
+        }
+        if let Some(moved) = self.list_key(event) {
+            return (None, ActionResult { redraw: moved, submit: false });
+        }
+        if let Some(switched) = self.tabs_key(event) {
+            return (None, ActionResult { redraw: switched, submit: false });
+        }
+        if let Some(toggled) = self.checkbox_key(event) {
+            return (None, ActionResult { redraw: toggled, submit: false });
+        }
+        if let Some(edited) = self.dropdown_key(event) {
+            return (None, ActionResult { redraw: edited, submit: false });
+        }
+        if let Some(moved) = self.table_key(event) {
+            return (None, ActionResult { redraw: moved, submit: false });
+        }
+        if let Some(moved) = self.tree_key(event) {
+            return (None, ActionResult { redraw: moved, submit: false });
+        }
+        if let Some(closed) = self.popup_key(event) {
+            return (None, ActionResult { redraw: closed, submit: false });
+        }
+        let action = self.resolve(event);
+        let key = Event::Key(event.clone());
+        let result = match &action {
+            Some(action) => self.apply(action, &key),
+            None => InsertChar.apply(self, &key),
+        };
+
+        (action, result)
+    }
+
+    // whether the focused text was disabled after it got the focus
+    fn focused_disabled(&self) -> bool {
+        self.focused
+            .and_then(|id| self.text_ref(&id))
+            .is_some_and(Text::is_disabled)
+    }
+
+    // a disabled text takes no edits, only the keys that move the focus away from it
+    // and the app's named actions are applied
+    fn disabled_key(&mut self, event: &KbdEvent) -> (Option<InputAction>, ActionResult) {
+        let action = self.resolve(event);
+        let result = match &action {
+            Some(
+                action @ (InputAction::FocusNext | InputAction::FocusPrev | InputAction::Named(_)),
+            ) => self.apply(action, &Event::Key(event.clone())),
+            _ => ActionResult::default(),
+        };
+
+        (action, result)
+    }
+
+    // the action event is bound to in the focused component's keymap, this Term's keymap
+    // or the defaults
+    fn resolve(&self, event: &KbdEvent) -> Option<InputAction> {
+        let focused = self
+            .focused
+            .and_then(|id| self.input_ref(&id).or_else(|| self.nonedit_ref(&id)));
+        let mut layers = vec![];
+        if let Some(text) = focused {
+            layers.push(&text.keymap);
+        }
+        layers.extend([&self.keymap, Keymap::defaults()]);
+
+        keymap::resolve(&layers, event).cloned()
+    }
+
+    // which history the input with the id goes through
+    // its "history-group" property names the history it shares with the other inputs of the group
+    fn history_key(&self, id: &[u8; 3]) -> HistoryKey {
+        match self.input_ref(id).and_then(|t| t.properties.get("history-group")) {
+            Some(Property::String(group)) => HistoryKey::Group(group.clone()),
+            _ => HistoryKey::Input(*id),
+        }
+    }
+
+    /// the history of the input with the id, None if nothing was recorded for it yet
+    /// inputs with the same "history-group" property have the same history
+    pub fn history(&self, id: &[u8; 3]) -> Option<&History> {
+        self.histories.get(&self.history_key(id))
+    }
+
+    /// the history of the input with the id, made empty if there is none yet
+    /// e.g. to set its rules before anything is recorded
+    pub fn history_mut(&mut self, id: &[u8; 3]) -> &mut History {
+        let key = self.history_key(id);
+
+        self.histories.entry(key).or_default()
+    }
+
+    // the history of the focused input
+    fn focused_history(&mut self) -> Option<&mut History> {
+        let id = self.focused.filter(|id| self.has_input(id))?;
+
+        Some(self.history_mut(&id))
+    }
+
+    fn is_searching(&self) -> bool {
+        self.focused
+            .and_then(|id| self.history(&id))
+            .is_some_and(History::is_searching)
+    }
+
+    // records the focused input's value in its history
+    pub(crate) fn record_submit(&mut self) {
+        let Some(id) = self.focused else {
+            return;
+        };
+        let Some(text) = self.input_mut(&id) else {
+            return;
+        };
+        let entry = text.value_string();
+        text.history_reset();
+        self.history_mut(&id).push(entry);
+    }
+
+    // puts the previous history entry in the focused input
+    pub(crate) fn history_prev(&mut self) -> bool {
+        self.browse_history(Text::history_prev)
+    }
+
+    // puts the next history entry in the focused input
+    pub(crate) fn history_next(&mut self) -> bool {
+        self.browse_history(Text::history_next)
+    }
+
+    fn browse_history(&mut self, f: impl FnOnce(&mut Text, &History) -> bool) -> bool {
+        let Some(id) = self.focused.filter(|id| self.has_input(id)) else {
+            return false;
+        };
+        let key = self.history_key(&id);
+        let history = self.histories.entry(key).or_default();
+        let Some(text) = self
+            .containers
+            .iter_mut()
+            .find_map(|c| c.items.iter_mut().find(|t| t.id == id))
+        else {
+            return false;
+        };
+        let moved = f(text, history);
+        _ = self.sync_cursor();
+
+        moved
+    }
+
+    // the previously focused input stops going through its history, it keeps what it shows
+    pub(crate) fn leave_history(&mut self) {
+        let Some(id) = self.focused.filter(|id| self.has_input(id)) else {
+            return;
+        };
+        if self.is_searching() {
+            self.end_search(true);
+        }
+        if let Some(text) = self.input_mut(&id) {
+            text.history_reset();
+        }
+    }
+
+    // starts a reverse search through the history, or goes to the next older match
+    pub(crate) fn history_search(&mut self) -> bool {
+        if self.is_searching() {
+            self.focused_history().unwrap().search_older();
+            return self.show_search();
+        }
+        let Some(text) = self.focused.and_then(|id| self.input_ref(&id)) else {
+            return false;
+        };
+        let (typed, cursor) = (text.value.to_vec(), [text.cx, text.cy]);
+        self.focused_history().unwrap().start_search(typed, cursor);
+
+        self.show_search()
+    }
+
+    // the search prompt goes in the focused input while the search goes on
+    fn show_search(&mut self) -> bool {
+        let Some(prompt) = self.focused_history().and_then(|h| h.search_prompt()) else {
+            return false;
+        };
+
+        self.edit(|text| {
+            text.replace_value(&prompt);
+            true
+        })
+    }
+
+    // hands the key to the history search if one is going on
+    // chars extend the query, backspace shortens it, enter takes the match and esc gives back
+    // what was typed before the search, any other key that is not bound to HistorySearch
+    // takes the match then does what it does
+    // returns None if the key is left to the keymaps
+    fn search_key(&mut self, event: &KbdEvent) -> Option<bool> {
+        if !self.is_searching() {
+            return None;
+        }
+        if !(event.modifiers.is_empty() || event.modifiers.0 == crate::inputs::keyboard::SHIFT) {
+            if self.resolve(event) != Some(InputAction::HistorySearch) {
+                self.end_search(true);
+            }
+            return None;
+        }
+        let history = self.focused_history().unwrap();
+        match event.char {
+            Char::Char(c) => history.search_push(c),
+            Char::CC(CC::BS) => history.search_pop(),
+            Char::CC(CC::CR) => return Some(self.end_search(true)),
+            Char::CC(CC::ESC) => return Some(self.end_search(false)),
+            Char::CC(_) => {
+                self.end_search(true);
+                return None;
+            }
+        }
+
+        Some(self.show_search())
+    }
+
+    // ends the history search, the focused input gets the match if accept is true
+    // or if there is none, what was typed before the search
+    fn end_search(&mut self, accept: bool) -> bool {
+        let Some(history) = self.focused_history() else {
+            return false;
+        };
+        let found = history.search_match().map(str::to_string);
+        let Some(search) = history.end_search() else {
+            return false;
+        };
+
+        self.edit(|text| {
+            match found.filter(|_| accept) {
+                Some(found) => text.replace_value(&found),
+                None => {
+                    text.value = search.typed.into();
+                    [text.cx, text.cy] = search.cursor;
+                    text.mark_dirty();
+                }
+            }
+            true
+        })
+    }
+
+    /// calls f with the list's id, the selected index and item whenever the selection
+    /// of a focused list moves, replaces the previous callback
+    pub fn on_select(&mut self, f: impl FnMut([u8; 3], usize, &str) + 'static) {
+        self.list_hooks.select = Some(Box::new(f));
+    }
+
+    /// calls f with the list's id, the selected index and item when Enter is pressed
+    /// on a focused list, replaces the previous callback
+    pub fn on_activate(&mut self, f: impl FnMut([u8; 3], usize, &str) + 'static) {
+        self.list_hooks.activate = Some(Box::new(f));
+    }
+
+    // hands the key to the focused list, if the focused text shows one
+    // returns None if the key is left to the keymaps
+    fn list_key(&mut self, event: &KbdEvent) -> Option<bool> {
+        let id = self.focused?;
+        let text = self.text_mut(&id)?;
+        text.list.as_ref()?;
+
+        let enter = event.char == Char::CC(CC::CR) && event.modifiers.is_empty();
+        let moved = match enter {
+            true => false,
+            false => text.list_key(event)?,
+        };
+        let list = text.list.as_ref().unwrap();
+        let (idx, item) = (list.selected(), list.selected_item()?.to_string());
+        let hook = match enter {
+            true => self.list_hooks.activate.as_mut(),
+            false => self.list_hooks.select.as_mut().filter(|_| moved),
+        };
+        if let Some(hook) = hook {
+            hook(id, idx, &item);
+        }
+
+        Some(moved)
+    }
+
+    /// calls f with the input's id and its new mode whenever a vim mode input changes modes,
+    /// e.g. to show the mode in a status line, replaces the previous callback
+    pub fn on_mode_change(&mut self, f: impl FnMut([u8; 3], Mode) + 'static) {
+        self.mode_hook = Hook(Some(Box::new(f)));
+    }
+
+    // hands the key to the focused input's vim mode if it has the "vim" attribute
+    // returns None if the key is left to the keymaps
+    fn vim_key(&mut self, event: &KbdEvent) -> Option<bool> {
+        let id = self.focused.filter(|id| self.has_input(id))?;
+        let validated = self.validated_value(&id);
+        let mut ring = std::mem::take(&mut self.kill_ring);
+        let (edited, before, after) = {
+            let text = self.input_mut(&id).filter(|t| t.attributes.contains("vim"));
+            let handled = text.map(|text| {
+                let before = text.vim.mode();
+                (vim::key(text, event, &mut ring), before, text.vim.mode())
+            });
+            self.kill_ring = ring;
+            handled?
+        };
+        let edited = edited?;
+        self.revalidate(&id, validated);
+        self.report_rejected(&id);
+
+        if before != after {
+            if crate::console::stdout_is_tty() {
+                let mut writer = std::io::stdout().lock();
+                _ = writer.write(after.cursor_shape());
+                _ = writer.flush();
+            }
+            if let Some(hook) = self.mode_hook.0.as_mut() {
+                hook(id, after);
+            }
+        }
+        _ = self.sync_cursor();
+
+        Some(edited)
+    }
+
+    /// applies action, event being the event it was bound to
+    /// a Named action runs the action registered under its name in this Term's actions,
+    /// if there is one, any other action does its built-in editing
+    pub fn apply(&mut self, action: &InputAction, event: &Event) -> ActionResult {
+        let InputAction::Named(name) = action else {
+            return action.apply(self, event);
+        };
+        let Some(registered) = self.actions.take(name) else {
+            return ActionResult::default();
+        };
+        let result = registered.apply(self, event);
+        self.actions.put_back(name, registered);
+
+        result
+    }
+
+    /// types a pasted text into the focused input, as one edit
+    /// see Text::insert_str for what happens to its newlines
+    /// returns false if no input is focused
+    pub fn paste(&mut self, text: &str) -> bool {
+        self.edit(|input| {
+            input.insert_str(text);
+            true
+        })
+    }
+
+    /// runs f on the focused input then puts the terminal cursor where the input's cursor is
+    /// returns what f returned, or false if no input is focused
+    /// a disabled input is not edited
+    pub fn edit(&mut self, f: impl FnOnce(&mut Text) -> bool) -> bool {
+        let Some(id) = self.focused.filter(|id| self.has_input(id) && !self.focused_disabled()) else {
+            return false;
+        };
+        let before = self.validated_value(&id);
+        let Some(text) = self.input_mut(&id) else {
+            return false;
+        };
+        let edited = f(text);
+        _ = self.sync_cursor();
+        self.revalidate(&id, before);
+        self.report_rejected(&id);
+        self.hold_cursor();
+        self.report_cursor();
+
+        edited
+    }
+
+    /// focuses the input after the focused one, the first input comes after the last one
+    /// see Term::tab_order for the order they come in
+    pub fn focus_next(&mut self) {
+        self.cycle_focus(false);
+    }
+
+    /// focuses the input before the focused one, the last input comes before the first one
+    pub fn focus_prev(&mut self) {
+        self.cycle_focus(true);
+    }
+
+    // goes through the texts in tab order from the focused one, or from the first or last one
+    // when none is, and focuses the next one that can be focused
+    fn cycle_focus(&mut self, back: bool) {
+        let ids = self.tab_order();
+        // the focused text comes last, it may be the only one that can be focused
+        let at = self.focused.and_then(|id| ids.iter().position(|i| *i == id));
+        let mut order = match at {
+            Some(i) => [&ids[i + 1..], &ids[..=i]].concat(),
+            None => ids,
+        };
+        if back {
+            order.reverse();
+            if at.is_some() {
+                order.rotate_left(1);
+            }
+        }
+
+        if let Some(next) = order.into_iter().find(|id| self.can_focus(id).is_ok()) {
+            _ = self.focus(&next);
+        }
+    }
+}
+
+impl Term {
+    /// keeps track of the terminal window focus reports
+    pub fn window(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::WindowGainedFocus => {
+                self.attributes.remove("terminal-unfocused");
+            }
+            WindowEvent::WindowLostFocus => {
+                self.attributes.insert("terminal-unfocused");
+            }
+            _ => (),
+        }
+    }
+
+    /// whether the terminal window had the focus at the last focus report
+    /// true when no report came in yet
+    pub fn is_terminal_focused(&self) -> bool {
+        !self.attributes.contains("terminal-unfocused")
+    }
+}
+
+impl Term {
+    /// adds a new Container object to this Term's containers,
+    /// a shorthand for Container::builder, which takes every option of the container
     ///
     /// # Examples
     /// ```
@@ -219,49 +1242,65 @@ impl Term {
         border: Border,
         padding: Padding,
     ) -> Result<(), ComponentTreeError> {
-        if self.is_valid_container_id(&id) {
-            eprintln!("bad id");
-            return Err(ComponentTreeError::BadID);
-        }
-
-        let [wextra, hextra] = resolve_wh(&border, &padding);
+        Container::builder(*id)
+            .pos(hpos, vpos)
+            .shape(shape)
+            .area(area)
+            .border(border)
+            .padding(padding)
+            .build(self)
+            .map(|_| ())
+    }
 
-        let [w, h] = area.unwrap([self.w, self.h]);
-        let [w, h] = [w - wextra, h - hextra];
+    /// adds a new Container made from meta to this Term's containers, with the first free
+    /// container id, the meta can make more containers after that
+    /// returns the id of the container
+    ///
+    /// # Errors
+    /// the same as ContainerMeta::validate
+    pub fn container_from_meta(&mut self, meta: &ContainerMeta) -> Result<[u8; 2], ComponentTreeError> {
+        let cont = meta.make(self)?;
+        let id = cont.id;
+        self.containers.push(cont);
 
-        let [x0, y0] = hpos.clone().point(vpos.clone(), [self.w, self.h]);
-        let [x0, y0] = [
-            if let Pos::End = hpos {
-                x0 - w - wextra
-            } else {
-                x0
-            },
-            if let Pos::End = vpos {
-                y0 - h - hextra
-            } else {
-                y0
-            },
-        ];
+        Ok(id)
+    }
 
-        if let Border::Manual { .. } = border {
-            if !border_fit(&border, &padding, self.w, self.h) {
-                return Err(ComponentTreeError::BoundsNotRespected);
+    /// shrinks the container with id to its texts, see Container::fit_to_children
+    /// with reanchor, a container made by Container::builder or a ContainerMeta is then placed
+    /// again by the hpos and vpos it was given, inside the term, and its texts move with it
+    /// the cells the container no longer takes are blanked by the next Term::render_dirty call
+    /// returns whether the container changed
+    ///
+    /// # Errors
+    /// returns BadID if there is no container with id, and the same errors as Term::push_container
+    /// if the container would take some of the area of another container, in which case
+    /// it is left as it was
+    pub fn fit_container(&mut self, id: &[u8; 2], reanchor: bool) -> Result<bool, ComponentTreeError> {
+        let Some(idx) = self.containers.iter().position(|c| c.id == *id) else {
+            return Err(ComponentTreeError::BadID { id: id.to_vec() });
+        };
+        let mut cont = self.containers[idx].clone();
+        let before = cont.outer_area();
+        let mut changed = cont.fit_to_children();
+        if let Some([hpos, vpos]) = cont.anchor.clone().filter(|_| reanchor) {
+            let origin = place(&hpos, &vpos, [self.w, self.h], cont.decorate());
+            if origin != [cont.x0, cont.y0] {
+                [cont.x0, cont.y0] = origin;
+                cont.invalidate();
+                cont.mark_dirty();
+                changed = true;
             }
         }
-
-        let cont = Container::new([id[0], id[1]], x0, y0, w, h, border, padding);
-
-        if self.assign_valid_container_area(&cont).is_err() {
-            return Err(ComponentTreeError::BoundsNotRespected);
+        if !changed {
+            return Ok(false);
         }
 
-        self.containers.push(cont);
-
-        Ok(())
-    }
+        self.assign_valid_container_area(&cont)?;
+        self.containers[idx] = cont;
+        self.uncover(before);
 
-    pub fn container_from_meta(&mut self, meta: &mut ContainerMeta) {
-        self.containers.push(meta.container());
+        Ok(true)
     }
 
     /// pushes an existing Container to this Term's container vector
@@ -285,7 +1324,7 @@ impl Term {
     /// let cont = Container::new(&[0, 0], 11111, 3, 34, 18, Border::Uniform('+'), Padding::None);
     /// let Err(res) = term.push_container(cont) else { unreachable!("you should have been an
     /// error") };
-    /// assert_eq!(res.1, ComponentTreeError::BoundsNotRespected);
+    /// assert!(matches!(res.1, ComponentTreeError::OriginOutOfBounds { .. }));
     /// ```
     ///
     /// ## Success
@@ -302,15 +1341,16 @@ impl Term {
     /// error value
     pub fn push_container(&mut self, c: Container) -> Result<(), (Container, ComponentTreeError)> {
         if self.has_container(&c.id) {
-            return Err((c, ComponentTreeError::IDAlreadyExists));
+            let id = c.id.to_vec();
+            return Err((c, ComponentTreeError::DuplicateId { id }));
         }
 
         // NOTE: assign_valid_thing_area series of functions need to be split to 2 fns
         // validate_thing_area and reassign_valid_thing_area
         // this fn's case only needs the validate_thing_area part
 
-        if self.assign_valid_container_area(&c).is_err() {
-            return Err((c, ComponentTreeError::BoundsNotRespected));
+        if let Err(e) = self.assign_valid_container_area(&c) {
+            return Err((c, e.into()));
         }
 
         self.containers.push(c);
@@ -348,19 +1388,34 @@ impl Term {
 
     /// pushes an existing input Text object to a child container of this Term
     pub fn push_input(&mut self, i: Text) -> Result<(), (Text, ComponentTreeError)> {
-        if !self.has_container(&[i.id[0], i.id[1]]) || self.has_input(&i.id) || i.id[2] % 2 != 0 {
-            return Err((i, ComponentTreeError::BadID));
+        if !i.is_input() {
+            let id = i.id;
+            return Err((i, ComponentTreeError::WrongKindForId { id, input: true }));
+        }
+        if let Err(e) = self.text_id_check(&i.id) {
+            return Err((i, e.into()));
+        }
+        let cont = self.container_ref(&[i.id[0], i.id[1]]).unwrap();
+        if let Err(e) = cont.assign_valid_text_area(&i) {
+            return Err((i, e.into()));
         }
 
+        let id = i.id;
         self.container_mut(&[i.id[0], i.id[1]])
             .unwrap()
             .items
             .push(i);
+        // the focus goes into a popup that was just opened
+        if self.focused.is_none() && self.top_popup() == Some([id[0], id[1]]) {
+            _ = self.focus(&id);
+        }
 
         Ok(())
     }
 
-    /// ...
+    /// adds a new input Text object to the container of this Term with the id's first 2 bytes,
+    /// a shorthand for Text::input_builder, which takes every option of the text
+    /// see TextBuilder::build for the errors
     pub fn input(
         &mut self,
         id: &[u8; 3],
@@ -375,86 +1430,37 @@ impl Term {
         border: Border,
         padding: Padding,
     ) -> Result<(), ComponentTreeError> {
-        if !self.is_valid_input_id(&id) {
-            eprintln!("bad id: {:?}", id);
-            return Err(ComponentTreeError::IdError(IdError::IdAlreadyTaken));
-        }
-
-
-        // let [x0, y0] = [text.x0, text.y0];
-        // let [w, h] = text.decorate();
-
-
-        let mut cont = self.container_mut(&[id[0], id[1]]).unwrap();
-        let contwh = [cont.w, cont.h];
-
-        let [wextra, hextra] = resolve_wh(&border, &padding);
-
-
-        let [w, h] = area.unwrap(contwh);
-        let [w, h] = [w - wextra, h - hextra];
-        let [x0, y0] = hpos.clone().point(vpos.clone(), [w, h]);
-
-        if cont.area_out_of_bounds(&[w,h]) {
-            return Err(ComponentTreeError::SpaceError(SpaceError::AreaOutOfBounds));
-        } else if cont.origin_out_of_bounds(&[w,h], &[x0, y0] ) {
-            return Err(ComponentTreeError::SpaceError(SpaceError::OriginOutOfBounds));
-        }
+        self.text_id_check(id)?;
+
+        Text::input_builder(*id)
+            .pos(hpos, vpos)
+            .shape(shape)
+            .area(area)
+            .border(border)
+            .padding(padding)
+            .build(self)
+            .map(|_| ())
+    }
 
-        if let Border::Manual { .. } = border {
-            if !border_fit(&border, &padding, w, h) {
-                return Err(ComponentTreeError::BoundsNotRespected);
+    /// adds a new input Text made from meta to the meta's parent container, with the first
+    /// free text id of the container, the meta can make more inputs after that
+    /// returns the id of the input
+    ///
+    /// # Errors
+    /// returns an error if there is no container with the meta's parent id,
+    /// and otherwise the same as InputMeta::validate
+    pub fn input_from_meta(&mut self, meta: &InputMeta) -> Result<[u8; 3], ComponentTreeError> {
+        let Some(cont) = self.container_mut(&meta.cid()) else {
+            return Err(IdError::ParentIdNotFound {
+                id: meta.cid().to_vec(),
             }
-        }
-
-        let [x0, y0] = hpos.clone().point(vpos.clone(), contwh);
-        let [x0, y0] = [
-            if let Pos::End = hpos {
-                x0 - w - wextra
-            } else {
-                x0
-            },
-            if let Pos::End = vpos {
-                y0 - h - hextra
-            } else {
-                y0
-            },
-        ];
-
-        let [ax0, ay0] = calc_text_abs_ori(&[id[0], id[1]], &[x0, y0], &border, &padding, &cont);
-
-        let input = Text::new(
-            [id[0], id[1], id[2]],
-            x0,
-            y0,
-            ax0,
-            ay0,
-            w,
-            h,
-            &[],
-            border,
-            padding,
-        );
-
-        if cont.assign_valid_text_area(&input).is_err() {
-            return Err(ComponentTreeError::BoundsNotRespected);
-        }
-
+            .into());
+        };
+        let input = meta.make(cont)?;
+        let id = input.id;
         cont.items.push(input);
 
-        Ok(())
-    }
-
-    pub fn input_from_meta(&mut self, meta: &mut InputMeta) -> Result<(), ComponentTreeError> {
-        let res = self.container_mut(&meta.cid());
-        if res.is_none() {
-            return Err(ComponentTreeError::BadID);
-        }
-
-        let cont = res.unwrap();
-        cont.items.push(meta.input());
-
-        Ok(())
+        Ok(id)
     }
 
     /// takes only term and container ids and automatically assigns an id for the input
@@ -481,6 +1487,8 @@ impl Term {
     //     Ok(id)
     // }
 
+    /// adds a new non editable Text object with value to the container of this Term with the
+    /// id's first 2 bytes, a shorthand for Text::nonedit_builder
     pub fn nonedit(
         &mut self,
         id: &[u8; 3],
@@ -496,90 +1504,53 @@ impl Term {
         padding: Padding,
         value: &[Option<char>],
     ) -> Result<(), ComponentTreeError> {
-        if !self.is_valid_nonedit_id(&id) {
-            eprintln!("bad id");
-            return Err(ComponentTreeError::BadID);
-        }
-
-        let mut cont = self.container_mut(&[id[0], id[1]]).unwrap();
-        let contwh = [cont.w, cont.h];
-
-        let [wextra, hextra] = resolve_wh(&border, &padding);
-
-        let [w, h] = area.unwrap(contwh);
-        let [w, h] = [w - wextra, h - hextra];
+        self.text_id_check(id)?;
+
+        Text::nonedit_builder(*id)
+            .pos(hpos, vpos)
+            .shape(shape)
+            .area(area)
+            .border(border)
+            .padding(padding)
+            .value(value)
+            .build(self)
+            .map(|_| ())
+    }
 
-        if let Border::Manual { .. } = border {
-            if !border_fit(&border, &padding, w, h) {
-                return Err(ComponentTreeError::BoundsNotRespected);
+    /// adds a new non editable Text made from meta to the meta's parent container, with the
+    /// first free text id of the container, the meta can make more nonedits after that
+    /// returns the id of the nonedit
+    ///
+    /// # Errors
+    /// returns an error if there is no container with the meta's parent id,
+    /// and otherwise the same as NonEditMeta::validate
+    pub fn nonedit_from_meta(&mut self, meta: &NonEditMeta) -> Result<[u8; 3], ComponentTreeError> {
+        let Some(cont) = self.container_mut(&meta.cid()) else {
+            return Err(IdError::ParentIdNotFound {
+                id: meta.cid().to_vec(),
             }
-        }
-
-        let [x0, y0] = hpos.clone().point(vpos.clone(), contwh);
-        let [x0, y0] = [
-            if let Pos::End = hpos {
-                x0 - w - wextra
-            } else {
-                x0
-            },
-            if let Pos::End = vpos {
-                y0 - h - hextra
-            } else {
-                y0
-            },
-        ];
-
-        if value.len() as u16 > w * h {
-            eprintln!(
-                "value of len {} too long for bounds w * h {}",
-                value.len(),
-                w * h
-            );
-            return Err(ComponentTreeError::BadValue);
-        }
-
-        let [ax0, ay0] = calc_text_abs_ori(&[id[0], id[1]], &[x0, y0], &border, &padding, &cont);
-
-        let nonedit = Text::new(
-            [id[0], id[1], id[2]],
-            x0,
-            y0,
-            ax0,
-            ay0,
-            w,
-            h,
-            value,
-            border,
-            padding,
-        );
-
-        if cont.assign_valid_text_area(&nonedit).is_err() {
-            return Err(ComponentTreeError::BoundsNotRespected);
-        }
-
+            .into());
+        };
+        let nonedit = meta.make(cont)?;
+        let id = nonedit.id;
         cont.items.push(nonedit);
 
-        Ok(())
-    }
-
-    pub fn nonedit_from_meta(&mut self, meta: &mut NonEditMeta) -> Result<(), ComponentTreeError>{
-        let res = self.container_mut(&meta.cid());
-        if res.is_none() {
-            return Err(ComponentTreeError::BadID);
-        }
-
-        let cont = res.unwrap();
-        cont.items.push(meta.nonedit(vec![]));
-
-        Ok(())
+        Ok(id)
     }
 
     /// pushes provided non editable Text object into a the Container with the given id if it
     /// exists and the Text object is valid, otherwise returns the error and Text object instance
     pub fn push_nonedit(&mut self, ne: Text) -> Result<(), (Text, ComponentTreeError)> {
-        if !self.has_container(&[ne.id[0], ne.id[1]]) || self.has_input(&ne.id) || ne.id[2] % 2 == 0
-        {
-            return Err((ne, ComponentTreeError::BadID));
+        if ne.is_input() {
+            let id = ne.id;
+            return Err((ne, ComponentTreeError::WrongKindForId { id, input: false }));
+        }
+        if let Err(e) = self.text_id_check(&ne.id) {
+            return Err((ne, e.into()));
+        }
+        let cont = self.container_ref(&[ne.id[0], ne.id[1]]).unwrap();
+        if let Err(e) = cont.assign_valid_text_area(&ne) {
+            return Err((ne, e.into()));
         }
 
         self.container_mut(&[ne.id[0], ne.id[1]])
@@ -631,7 +1602,7 @@ impl Term {
 
         cont.items
             .iter()
-            .find(|input| input.id[2] % 2 == 0 && input.id == *id)
+            .find(|input| input.is_input() && input.id == *id)
     }
 
     /// returns an optional mutable reference of the input with the provided id if it exists
@@ -642,7 +1613,7 @@ impl Term {
 
         cont.items
             .iter_mut()
-            .find(|input| input.id[2] % 2 == 0 && input.id == *id)
+            .find(|input| input.is_input() && input.id == *id)
     }
 
     /// returns an optional immutable reference of the noneditable with the provided id if it exists
@@ -653,7 +1624,7 @@ impl Term {
 
         cont.items
             .iter()
-            .find(|input| input.id[2] % 2 != 0 && input.id == *id)
+            .find(|input| !input.is_input() && input.id == *id)
     }
 
     /// returns an optional mutable reference of the noneditable with the provided id if it exists
@@ -664,7 +1635,23 @@ impl Term {
 
         cont.items
             .iter_mut()
-            .find(|input| input.id[2] % 2 != 0 && input.id == *id)
+            .find(|input| !input.is_input() && input.id == *id)
+    }
+
+    /// returns a reference to the input or nonedit with the given id
+    pub fn text_ref(&self, id: &[u8; 3]) -> Option<&Text> {
+        self.container_ref(&[id[0], id[1]])?
+            .items
+            .iter()
+            .find(|t| t.id == *id)
+    }
+
+    /// returns a mutable reference to the input or nonedit with the given id
+    pub fn text_mut(&mut self, id: &[u8; 3]) -> Option<&mut Text> {
+        self.container_mut(&[id[0], id[1]])?
+            .items
+            .iter_mut()
+            .find(|t| t.id == *id)
     }
 
     /// returns the number of containers inside this term
@@ -681,7 +1668,7 @@ impl Term {
     pub fn ilen(&self) -> usize {
         self.containers
             .iter()
-            .map(|c| c.items.iter().filter(|t| t.id[2] % 2 == 0).count())
+            .map(|c| c.items.iter().filter(|t| t.is_input()).count())
             .sum::<usize>()
     }
 
@@ -689,7 +1676,7 @@ impl Term {
     pub fn nelen(&self) -> usize {
         self.containers
             .iter()
-            .map(|c| c.items.iter().filter(|t| t.id[2] % 2 != 0).count())
+            .map(|c| c.items.iter().filter(|t| !t.is_input()).count())
             .sum::<usize>()
     }
 
@@ -713,10 +1700,12 @@ impl Term {
             Some(cont) => cont
                 .items
                 .iter()
-                .find(|input| input.id[2] % 2 == 0 && input.id == *id)
+                .find(|input| input.is_input() && input.id == *id)
                 .is_some(),
             None => {
-                eprintln!("no container with such id was found {:?}", &id[..2]);
+                diagnostics::log(Level::Debug, || {
+                    format!("no container with the id {:?}", &id[..2])
+                });
                 false
             }
         }
@@ -728,61 +1717,14 @@ impl Term {
             Some(cont) => cont
                 .items
                 .iter()
-                .find(|input| input.id[2] % 2 != 0 && input.id == *id)
+                .find(|input| !input.is_input() && input.id == *id)
                 .is_some(),
             None => {
-                eprintln!("no container with such id was found {:?}", &id[..2]);
+                diagnostics::log(Level::Debug, || {
+                    format!("no container with the id {:?}", &id[..2])
+                });
                 false
             }
         }
     }
-
-        // NOTE: this method does not check the validity of the provided term id
-    fn assign_container_id(&self, term: u8) -> u8 {
-
-        let mut id = 0;
-        for cont in &self.containers {
-            if cont.id[1] == id {
-                id += 1;
-            } else {
-                break;
-            }
-        }
-
-        id
-    }
-
-        // NOTE: this method does not check the validity of the provided term and container ids
-    fn assign_input_id(&self, term: u8, cont: u8) -> u8 {
-        let cont = self.container_ref(&[term, cont]).unwrap();
-
-        let mut id = 0;
-        let mut iter = cont.items.iter().filter(|i| i.id[2] % 2 == 0);
-        while let Some(item) = iter.next() {
-            if item.id[2] == id {
-                id += 2;
-            } else {
-                break;
-            }
-        }
-
-        id
-    }
-
-        // NOTE: this method does not check the validity of the provided term and container ids
-    fn assign_nonedit_id(&self, term: u8, cont: u8) -> u8 {
-        let cont = self.container_ref(&[term, cont]).unwrap();
-
-        let mut id = 0;
-        let mut iter = cont.items.iter().filter(|i| i.id[2] % 2 != 0);
-        while let Some(item) = iter.next() {
-            if item.id[2] == id {
-                id += 2;
-            } else {
-                break;
-            }
-        }
-
-        id
-    }
 }
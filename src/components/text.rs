@@ -3,17 +3,45 @@ use std::io::StdoutLock;
 use std::io::Write;
 
 use crate::console::winsize::winsize;
+use crate::inputs::history::History;
+use crate::inputs::keymap::Keymap;
+use crate::inputs::vim::{Mode, Vim};
 use crate::render_pipeline;
-use crate::space::{area_conflicts, between, border::Border, border_fit, padding::Padding};
+use crate::space::{area_conflicts, between, border::Border, border_fit, padding::Padding, Overflow};
 use crate::themes::Style;
 
+use super::{Checkbox, Dropdown, Gauge, InputFilter, List, LogPane, Scrollbar, Sparkline, Spinner, Table, Tabs, Tree};
 use super::Property;
 use super::{ComponentTreeError, SpaceError};
-use super::{Container, Term};
+use super::{Cached, Container, Term, Value};
+use super::escape::NUL;
+use super::grapheme::Clusters;
+use super::tab_stop::FILL;
+
+/// whether a Text is an input, which the user edits, or a nonedit, which shows a value
+/// a text gets its kind when it is made, its id says nothing about it, see Term::set_editable
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextKind {
+    #[default]
+    Input,
+    NonEdit,
+}
+
+impl TextKind {
+    /// the kind an id had under the old convention, inputs with an even last byte and
+    /// nonedits with an odd one
+    #[deprecated(note = "the kind of a text is not taken from its id anymore")]
+    pub fn of(id: &[u8; 3]) -> Self {
+        match id[2] % 2 == 0 {
+            true => Self::Input,
+            false => Self::NonEdit,
+        }
+    }
+}
 
 /// Text objects are direct children of the Container objects
 /// and indirect children of the Term grand parent
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Text {
     /// the layer of this Text inside its parent Container
     /// decide which Text takes render priority in case of conflict
@@ -21,11 +49,15 @@ pub struct Text {
     pub layer: u8,
     /// unique id
     pub id: [u8; 3],
+    // whether this text is an input or a nonedit, see Text::kind
+    pub(crate) kind: TextKind,
     /// temporary value holder for use when scorrling history
     // this should be part of properties
-    pub temp: Vec<Option<char>>,
+    pub temp: Value,
     /// the value inside this Text object
-    pub value: Vec<Option<char>>,
+    pub value: Value,
+    // the clusters the chars of the value stand for, see grapheme
+    pub(crate) clusters: Clusters,
     /// history cursor current value
     // this field should be part of properties
     pub hicu: usize,
@@ -33,9 +65,9 @@ pub struct Text {
     pub w: u16,
     /// height
     pub h: u16,
-    /// this Text's cursor x coordinate
+    /// this Text's cursor x coordinate, from 0 to w - 1, see Text::set_cursor
     pub cx: u16,
-    /// this Text's cursor y coordinate
+    /// this Text's cursor y coordinate, from 0 to h - 1, see Text::set_cursor
     pub cy: u16,
     /// origin point x coordinate relative to the dimensions of the parent Container
     pub x0: u16,
@@ -49,21 +81,108 @@ pub struct Text {
     pub border: Border,
     /// padding value
     pub padding: Padding,
+    /// what to do with the part of the value that doesn't fit inside w * h
+    pub overflow: Overflow,
+    /// the number of value lines scrolled past the top of this text's area
+    pub scroll: u16,
+    /// the number of value columns scrolled past the left of this text's area,
+    /// only used while this text has a horizontal scrollbar, see Scrollbar::horizontal
+    pub hscroll: u16,
     // the following 2 fields have now become part of properties
     /// border style
     pub bstyle: String,
     /// value style
     pub vstyle: String,
+    /// the style of this text's value, see Text::set_style
+    pub style: Option<Style>,
+    /// the style of this text's value while it is focused, over its style
+    pub focus_style: Option<Style>,
+    /// the style of this text's border while it is focused, or of its container's border
+    /// when this text has none
+    pub focus_border_style: Option<Style>,
+    /// the style of this text's value while the pointer is over it, see Term::hovered
+    pub hover_style: Option<Style>,
+    // the style this text's value is rendered in while it is hovered, see Term::hover_to
+    pub(crate) hover: Option<String>,
+    // the [value, border] styles this text is rendered in while it is focused, None when it is
+    // not focused or the style is left as it is, see Term::mark_focused
+    pub(crate) focus: [Option<String>; 2],
+    /// the value style while this text is disabled, dimmed unless it was changed,
+    /// Term::set_disabled gives it the "input.disabled" style of the Term's theme
+    pub dstyle: String,
 
     pub properties: HashMap<&'static str, Property>,
     pub attributes: HashSet<&'static str>,
+    /// the key bindings of this text, they win over the Term's while this text is focused
+    pub keymap: Keymap,
+    /// the modal editing state, only used while this text has the "vim" attribute
+    pub vim: Vim,
+    // the list this text shows, see Text::set_list
+    pub(crate) list: Option<List>,
+    // the spinner this text shows, see Text::set_spinner
+    pub(crate) spinner: Option<Spinner>,
+    // the table this text shows, see Text::set_table
+    pub(crate) table: Option<Table>,
+    // the dropdown this text shows, see Text::set_dropdown
+    pub(crate) dropdown: Option<Dropdown>,
+    // the checkbox this text shows, see Text::set_checkbox
+    pub(crate) checkbox: Option<Checkbox>,
+    // the tabs this text shows, see Term::set_tabs
+    pub(crate) tabs: Option<Tabs>,
+    // the sparkline this text shows, see Text::set_sparkline
+    pub(crate) sparkline: Option<Sparkline>,
+    // the widget this text hosts, see Text::set_widget
+    pub(crate) widget: Option<super::widget::Hosted>,
+    // the gauge this text shows, see Text::set_gauge
+    pub(crate) gauge: Option<Gauge>,
+    // the tree this text shows, see Text::set_tree
+    pub(crate) tree: Option<Tree>,
+    // the log pane this text shows, see Text::set_log_pane
+    pub(crate) log: Option<LogPane>,
+    // the queue of the other threads' writes to this text, see Text::sender
+    pub(crate) sender: Option<super::PaneSender>,
+    // the matches of the last search and their styles, see Text::search
+    pub(crate) search: Option<super::search::Search>,
+    // the line numbers shown on the left of the value, see Text::set_line_numbers
+    pub(crate) gutter: super::gutter::Gutter,
+    // what the typed chars have to be, see Text::set_filter
+    pub(crate) filter: Option<InputFilter>,
+    // how the control chars of the value are shown, see Text::set_control_display
+    pub(crate) control_display: Option<super::ControlDisplay>,
+    // the direction the lines are read in, see Text::set_direction
+    pub(crate) direction: super::Direction,
+    // the form the typed chars are put in, see Text::set_normalization
+    #[cfg(feature = "normalization")]
+    pub(crate) normalization: Option<super::Normalization>,
+    // the format of the value, see Text::set_mask
+    pub(crate) mask: Option<&'static str>,
+    // the chars the filter or the mask rejected, see Term::on_reject
+    pub(crate) rejected: String,
+    // the validation state the app gave this text, see Term::set_validation
+    pub(crate) validated: super::validation::Validated,
+    // the rows of the area the patches changed since the last render, None when the whole
+    // text is rendered, see Text::apply_patch
+    pub(crate) patched: Option<std::ops::Range<u16>>,
+    // the cell the next Text::append_str goes to when the last append ended with a newline,
+    // and the end of the value it left, see Text::append_str
+    pub(crate) appended: Option<[usize; 2]>,
+    // the rows of the lines of the value and the most lines it keeps, see Text::set_max_lines
+    pub(crate) ring: Option<super::stream::Ring>,
+    // the vertical and horizontal scrollbars of this text, see Text::set_scrollbar
+    pub(crate) scrollbars: [Option<Scrollbar>; 2],
+    // the decorated size of this text and the area it takes, see Text::decorate
+    pub(crate) decoration: Cached<[u16; 2]>,
+    pub(crate) outer: Cached<[u16; 4]>,
+    // the count of the changes to the geometry of the components of its term
+    pub(crate) changes: super::hit::Changes,
 }
 
-// NOTE: Inputs can only have pair IDs
-// while NonEdits can only have odd IDs
 impl Text {
     /// creates a new Text objects
     /// takes most of Text's field values as arguments and returns a Text instance
+    /// the text is an input if the last byte of id is even and a nonedit if it is odd
+    #[deprecated(note = "use Text::input or Text::nonedit")]
+    #[allow(deprecated)]
     pub fn new(
         id: [u8; 3],
         x0: u16,
@@ -76,32 +195,134 @@ impl Text {
         border: Border,
         padding: Padding,
     ) -> Text {
+        let kind = TextKind::of(&id);
+        Self::of_kind(kind, id, x0, y0, ax0, ay0, w, h, value, border, padding)
+    }
+
+    /// creates a new input, takes the same values as Text::nonedit
+    pub fn input(
+        id: [u8; 3],
+        x0: u16,
+        y0: u16,
+        ax0: u16,
+        ay0: u16,
+        w: u16,
+        h: u16,
+        value: &[Option<char>],
+        border: Border,
+        padding: Padding,
+    ) -> Text {
+        Self::of_kind(TextKind::Input, id, x0, y0, ax0, ay0, w, h, value, border, padding)
+    }
+
+    /// creates a new nonedit
+    /// x0 and y0 are its origin inside its container, ax0 and ay0 its absolute origin,
+    /// see Text::nonedit_builder for a text that is placed and checked against its container
+    pub fn nonedit(
+        id: [u8; 3],
+        x0: u16,
+        y0: u16,
+        ax0: u16,
+        ay0: u16,
+        w: u16,
+        h: u16,
+        value: &[Option<char>],
+        border: Border,
+        padding: Padding,
+    ) -> Text {
+        Self::of_kind(TextKind::NonEdit, id, x0, y0, ax0, ay0, w, h, value, border, padding)
+    }
+
+    pub(crate) fn of_kind(
+        kind: TextKind,
+        id: [u8; 3],
+        x0: u16,
+        y0: u16,
+        ax0: u16,
+        ay0: u16,
+        w: u16,
+        h: u16,
+        value: &[Option<char>],
+        border: Border,
+        padding: Padding,
+    ) -> Text {
+        // inputs can be focused from the start, nonedits once they are made focusable
+        let mut attributes = HashSet::new();
+        if kind == TextKind::Input {
+            attributes.insert("focusable");
+        }
+
+        let mut clusters = Clusters::default();
+        let value = value.iter().map(|c| c.map(|c| clusters.own(c)));
+
         Text {
             id,
+            kind,
             w,
             h,
-            temp: vec![],
+            temp: Value::new(),
             hicu: 0,
             x0,
             y0,
             ax0,
             ay0,
             properties: HashMap::new(),
-            attributes: HashSet::new(),
+            attributes,
+            keymap: Keymap::default(),
+            vim: Vim::default(),
+            list: None,
+            spinner: None,
+            table: None,
+            dropdown: None,
+            checkbox: None,
+            tabs: None,
+            sparkline: None,
+            widget: None,
+            gauge: None,
+            tree: None,
+            log: None,
+            sender: None,
+            search: None,
+            gutter: Default::default(),
+            filter: None,
+            control_display: None,
+            direction: Default::default(),
+            #[cfg(feature = "normalization")]
+            normalization: Some(Default::default()),
+            mask: None,
+            rejected: String::new(),
+            validated: Default::default(),
+            patched: None,
+            appended: None,
+            ring: None,
+            scrollbars: [None, None],
+            decoration: Cached::default(),
+            outer: Cached::default(),
+            changes: Default::default(),
             border,
             padding,
+            overflow: Overflow::Clip,
+            scroll: 0,
+            hscroll: 0,
             value: {
-                let mut v = Vec::with_capacity((w * h) as usize);
-                v.resize((w * h) as usize, None);
-                v.extend_from_slice(value);
+                let mut v = Value::blank(w as usize * h as usize);
+                v.extend(value);
 
                 v
             },
+            clusters,
             cx: 0,
             cy: 0,
 
             layer: 0,
             vstyle: "".to_string(),
+            style: None,
+            focus_style: None,
+            focus_border_style: None,
+            focus: [None, None],
+            hover_style: None,
+            hover: None,
+            dstyle: Style::new().faint().style(),
             bstyle: "".to_string(),
         }
     }
@@ -111,11 +332,171 @@ impl Text {
         self.vstyle = style.style();
     }
 
+    /// gives this text the style its value is rendered in, None renders it in no style
+    /// the style goes before the value and is reset after it, the text is marked dirty
+    pub fn set_style(&mut self, style: Option<Style>) {
+        self.vstyle = style.as_ref().map_or_else(String::new, Style::style);
+        self.style = style;
+        self.mark_dirty();
+    }
+
+    /// gives this text the style its value is rendered in while it is focused,
+    /// it wins over the style from Text::set_style and the theme's "focused.content" style,
+    /// the text is marked dirty
+    pub fn set_focus_style(&mut self, style: Option<Style>) {
+        self.focus_style = style;
+        self.mark_dirty();
+    }
+
+    /// gives this text the style its border is rendered in while it is focused, or its
+    /// container's border when it has none, it wins over the theme's "focused.border" style,
+    /// the text is marked dirty
+    pub fn set_focus_border_style(&mut self, style: Option<Style>) {
+        self.focus_border_style = style;
+        self.mark_dirty();
+    }
+
+    /// whether the padding inside this text's border is rendered in the value style too,
+    /// it is left alone by default, the text is marked dirty
+    pub fn style_padding(&mut self, styled: bool) {
+        match styled {
+            true => self.attributes.insert("style-padding"),
+            false => self.attributes.remove("style-padding"),
+        };
+        self.mark_dirty();
+    }
+
+    /// changes the value style of this text while it is disabled
+    pub fn dstyle(&mut self, style: &Style) {
+        self.dstyle = style.style();
+    }
+
     /// changes the border style of this text
     pub fn bstyle(&mut self, style: &Style) {
         self.bstyle = style.style();
     }
 
+    /// changes how the part of the value that doesn't fit inside this text's area is rendered
+    pub fn overflow(&mut self, overflow: Overflow) {
+        self.overflow = overflow;
+    }
+
+    /// marks this text to be rendered again by the next Term::render_dirty call
+    pub fn mark_dirty(&mut self) {
+        self.attributes.insert("dirty");
+        self.patched = None;
+    }
+
+    /// hides this text, a hidden text is not rendered, hit by the mouse or focused by Tab
+    /// the cells it took stay on the display until something is rendered over them,
+    /// see Term::hide, and no other text can take them, see Text::collapse
+    pub fn hide(&mut self) {
+        if self.attributes.insert("hidden") {
+            self.changes.bump();
+        }
+    }
+
+    /// hides this text and gives up its area, the other texts of its container can be placed
+    /// over it until it is shown again
+    pub fn collapse(&mut self) {
+        self.hide();
+        self.attributes.insert("collapse");
+    }
+
+    /// shows this text again, hidden or collapsed, it is marked dirty
+    pub fn show(&mut self) {
+        self.attributes.remove("collapse");
+        if self.attributes.remove("hidden") {
+            self.changes.bump();
+            self.mark_dirty();
+        }
+    }
+
+    /// whether this text is an input or a nonedit
+    pub fn kind(&self) -> TextKind {
+        self.kind
+    }
+
+    /// whether this text is an input
+    pub fn is_input(&self) -> bool {
+        self.kind == TextKind::Input
+    }
+
+    /// whether this text is hidden
+    pub fn is_hidden(&self) -> bool {
+        self.attributes.contains("hidden")
+    }
+
+    /// whether this text is collapsed, hidden without taking its area
+    pub fn is_collapsed(&self) -> bool {
+        self.is_hidden() && self.attributes.contains("collapse")
+    }
+
+    /// makes this text one that can be focused or not, see Term::focus
+    /// inputs are focusable when they are made, and the nonedits that show a checkbox, tabs,
+    /// a list, a table, a tree or a dropdown are made focusable when they get it
+    pub fn set_focusable(&mut self, focusable: bool) {
+        match focusable {
+            true => self.attributes.insert("focusable"),
+            false => self.attributes.remove("focusable"),
+        };
+    }
+
+    /// whether this text can be focused when it is shown and enabled
+    pub fn is_focusable(&self) -> bool {
+        self.attributes.contains("focusable")
+    }
+
+    /// disables this text, a disabled text can't be focused, see Term::focus
+    pub fn disable(&mut self) {
+        self.attributes.insert("disabled");
+    }
+
+    /// enables this text again
+    pub fn enable(&mut self) {
+        self.attributes.remove("disabled");
+    }
+
+    /// whether this text is disabled
+    pub fn is_disabled(&self) -> bool {
+        self.attributes.contains("disabled")
+    }
+
+    // the area this text takes on the display, border included, as [x, y, w, h]
+    pub(crate) fn outer_area(&self) -> [u16; 4] {
+        self.outer.get(self.geometry(), || {
+            let [_, _, _, _, pir, pil, pit, pib] = render_pipeline::spread_padding(&self.padding);
+            let b = if let Border::None = self.border { 0 } else { 1 };
+
+            [
+                self.ax0.saturating_sub(pil + b),
+                self.ay0.saturating_sub(pit + b),
+                self.w + pil + pir + 2 * b,
+                self.h + pit + pib + 2 * b,
+            ]
+        })
+    }
+
+    /// returns the greatest scroll offset, 0 unless the value has more lines than this text's
+    /// height, less the row a shown horizontal scrollbar takes
+    pub fn max_scroll(&self) -> u16 {
+        self.lines().saturating_sub(self.view()[1])
+    }
+
+    /// scrolls the value by the given number of lines, negative values scroll back up
+    /// the offset is clamped between 0 and max_scroll
+    /// returns whether the offset changed
+    pub fn scroll_by(&mut self, lines: i16) -> bool {
+        let scroll = (self.scroll as i32 + lines as i32).clamp(0, self.max_scroll() as i32) as u16;
+        let changed = scroll != self.scroll;
+        self.scroll = scroll;
+        if changed {
+            self.mark_dirty();
+        }
+
+        changed
+    }
+
     // pub fn with_layer(id: [u8; 3], layer: u8) -> Self {
     //     Text {
     //         layer,
@@ -132,3 +513,376 @@ impl Text {
         [self.id[0], self.id[1]]
     }
 }
+
+// editing, the value is edited cell by cell, the cursor being at the [cx, cy] cell
+impl Text {
+    pub(crate) fn cursor_index(&self) -> usize {
+        self.cx as usize + self.cy as usize * self.w as usize
+    }
+
+    // puts the cursor on the cell at idx of the value, the last cell if idx is past it
+    pub(crate) fn set_cursor_index(&mut self, idx: usize) {
+        let area = self.w as usize * self.h as usize;
+        let idx = idx.min(area.saturating_sub(1));
+        self.cx = (idx % self.w.max(1) as usize) as u16;
+        self.cy = (idx / self.w.max(1) as usize) as u16;
+    }
+
+    /// puts the cursor on the [x, y] cell of the value, the scroll offsets are left as they are
+    ///
+    /// # Errors
+    /// returns OriginOutOfBounds if the cell is outside of the w x h area of this text,
+    /// in which case the cursor doesn't move
+    pub fn set_cursor(&mut self, x: u16, y: u16) -> Result<(), SpaceError> {
+        if x >= self.w || y >= self.h {
+            return Err(SpaceError::OriginOutOfBounds {
+                origin: [x, y],
+                available: [self.w, self.h],
+            });
+        }
+        [self.cx, self.cy] = [x, y];
+
+        Ok(())
+    }
+
+    /// moves the cursor by dx columns and dy lines, it stops at the edges of the w x h area
+    /// of this text, it doesn't go on to the next line
+    /// returns whether the cursor moved
+    pub fn move_cursor(&mut self, dx: i16, dy: i16) -> bool {
+        let before = [self.cx, self.cy];
+        self.clamp_cursor();
+        let last = |len: u16| len.saturating_sub(1) as i32;
+        self.cx = (self.cx as i32 + dx as i32).clamp(0, last(self.w)) as u16;
+        self.cy = (self.cy as i32 + dy as i32).clamp(0, last(self.h)) as u16;
+
+        [self.cx, self.cy] != before
+    }
+
+    /// brings the cursor back inside the w x h area of this text, e.g. after the area shrank
+    /// or cx and cy were changed directly, Term::resize does it for every text
+    /// returns whether the cursor moved
+    pub fn clamp_cursor(&mut self) -> bool {
+        let before = [self.cx, self.cy];
+        self.cx = self.cx.min(self.w.saturating_sub(1));
+        self.cy = self.cy.min(self.h.saturating_sub(1));
+
+        [self.cx, self.cy] != before
+    }
+
+    // the [x, y] cell of the view the cursor is shown on, the scroll offsets taken off,
+    // a cursor scrolled out of the view is shown on its nearest cell
+    pub(crate) fn view_cursor(&self) -> [u16; 2] {
+        let [vw, vh] = self.view();
+        let x = self.cursor_column().saturating_sub(self.hscroll);
+        let y = self.cy.saturating_sub(self.scroll);
+
+        [x.min(vw.saturating_sub(1)), y.min(vh.saturating_sub(1))]
+    }
+
+    // the value gets its length back after an edit, so that it doesn't shrink under the area,
+    // and its tabs their widths, see Text::tab_width
+    pub(crate) fn fill(&mut self) {
+        self.retab();
+        let area = self.w as usize * self.h as usize;
+        while self.value.len() < area {
+            self.value.push(None);
+        }
+        while self.value.len() > area && self.value.last() == Some(None) {
+            self.value.pop();
+        }
+    }
+
+    /// types c at the cursor, the rest of the value moves one cell to the right
+    /// returns false if the filter or the mask of this text rejected c, see Text::set_filter
+    pub fn insert(&mut self, c: char) -> bool {
+        #[cfg(feature = "normalization")]
+        let typed = {
+            let chars = self.normalize_typed(c.encode_utf8(&mut [0; 4]));
+            chars.chars().fold(true, |typed, c| self.put(c) && typed)
+        };
+        #[cfg(not(feature = "normalization"))]
+        let typed = self.put(c);
+        self.mark_dirty();
+
+        typed
+    }
+
+    /// types s at the cursor in one go, e.g. a paste
+    /// newlines go to the start of the next line in a text with the "multiline" attribute
+    /// and are typed as spaces in any other text
+    /// the chars the filter or the mask of this text rejects are left out
+    pub fn insert_str(&mut self, s: &str) {
+        let multiline = self.attributes.contains("multiline");
+        #[cfg(feature = "normalization")]
+        let s = &self.normalize_typed(s);
+        for c in s.chars() {
+            match c {
+                '\n' if multiline => _ = self.newline(),
+                '\n' => _ = self.put(' '),
+                '\t' => _ = self.put('\t'),
+                c if c.is_control() && self.escape(c).is_none() => (),
+                c => _ = self.put(c),
+            }
+        }
+        self.mark_dirty();
+    }
+
+    // types c, a rejected c is kept for Term::on_reject
+    fn put(&mut self, c: char) -> bool {
+        let c = match c {
+            '\t' if !self.keeps_tabs() => ' ',
+            '\0' => NUL,
+            c => self.own(c),
+        };
+        let typed = match self.mask {
+            Some(mask) => self.put_masked(mask, c),
+            None if !self.accepts(c) => false,
+            None => {
+                let idx = self.cursor_index().min(self.value.len());
+                // a mark or a joiner goes into the cluster before the cursor, see grapheme
+                let at = match self.join_at(idx, c) {
+                    Some(at) => at,
+                    None => {
+                        self.value.insert(idx, Some(c));
+                        idx
+                    }
+                };
+                self.fill();
+                // a wide char that doesn't fit in the last cell of its row goes on on the next one
+                let at = at + (self.value.get(at).flatten() == Some(FILL)) as usize;
+                self.set_cursor_index(at);
+                self.move_right();
+                true
+            }
+        };
+        if !typed {
+            self.clusters.push(&mut self.rejected, c);
+        }
+
+        typed
+    }
+
+    /// removes the char behind the cursor
+    /// returns false if the cursor is at the start of the value
+    pub fn backspace(&mut self) -> bool {
+        if let Some(mask) = self.mask {
+            return self.clear_masked(mask, true);
+        }
+        let idx = self.cursor_index();
+        if idx == 0 || idx > self.value.len() {
+            return false;
+        }
+        // a tab goes with its fill cells
+        let at = self.drop_fills(idx - 1);
+        self.value.remove(at);
+        self.fill();
+        match at + 1 == idx {
+            true => _ = self.move_left(),
+            false => self.set_cursor_index(at),
+        }
+        self.mark_dirty();
+
+        true
+    }
+
+    /// removes the char under the cursor
+    /// returns false if there is nothing under the cursor
+    pub fn delete(&mut self) -> bool {
+        if let Some(mask) = self.mask {
+            return self.clear_masked(mask, false);
+        }
+        let idx = self.cursor_index();
+        if idx >= self.value.len() {
+            return false;
+        }
+        let at = self.drop_fills(idx);
+        self.value.remove(at);
+        self.fill();
+        self.set_cursor_index(at);
+        self.mark_dirty();
+
+        true
+    }
+
+    /// moves the cursor one cell to the left, to the end of the line above at the start of a line
+    /// the literals of a mask are skipped, see Text::set_mask
+    pub fn move_left(&mut self) -> bool {
+        let idx = self.cursor_index();
+        self.step_left();
+        self.skip_fills(true);
+        self.skip_literals(true);
+
+        self.cursor_index() != idx
+    }
+
+    /// moves the cursor one cell to the right, to the start of the line below at the end of a line
+    /// the literals of a mask are skipped, see Text::set_mask
+    pub fn move_right(&mut self) -> bool {
+        let idx = self.cursor_index();
+        self.step_right();
+        self.skip_fills(false);
+        self.skip_literals(false);
+
+        self.cursor_index() != idx
+    }
+
+    pub(crate) fn step_left(&mut self) -> bool {
+        match (self.cx, self.cy) {
+            (0, 0) => return false,
+            (0, _) => {
+                self.cy -= 1;
+                self.cx = self.w.saturating_sub(1);
+            }
+            _ => self.cx -= 1,
+        }
+
+        true
+    }
+
+    pub(crate) fn step_right(&mut self) -> bool {
+        if self.cx + 1 < self.w {
+            self.cx += 1;
+        } else if self.cy + 1 < self.h {
+            self.cx = 0;
+            self.cy += 1;
+        } else {
+            return false;
+        }
+
+        true
+    }
+
+    /// moves the cursor one line up
+    pub fn move_up(&mut self) -> bool {
+        self.move_cursor(0, -1)
+    }
+
+    /// moves the cursor one line down
+    pub fn move_down(&mut self) -> bool {
+        self.move_cursor(0, 1)
+    }
+
+    /// moves the cursor to the start of its line
+    pub fn move_home(&mut self) -> bool {
+        self.move_cursor(i16::MIN, 0)
+    }
+
+    /// moves the cursor right after the last char of its line
+    pub fn move_end(&mut self) -> bool {
+        let start = self.cy as usize * self.w as usize;
+        let line = self.value.range(start..start + self.w as usize).collect::<Vec<_>>();
+        let end = line
+            .iter()
+            .rposition(|c| c.is_some())
+            .map_or(0, |i| i + 1)
+            .min(self.w.saturating_sub(1) as usize) as u16;
+        let moved = self.cx != end;
+        self.cx = end;
+
+        moved
+    }
+
+    /// the value as a string, the empty cells are left out, a tab is given back as one char
+    /// lines are separated by newlines in a text with the "multiline" attribute
+    pub fn value_string(&self) -> String {
+        let chars = |cells: &mut dyn Iterator<Item = char>| {
+            let mut s = String::new();
+            for c in cells {
+                match c {
+                    FILL => (),
+                    NUL => s.push('\0'),
+                    c => self.clusters.push(&mut s, c),
+                }
+            }
+            s
+        };
+        if !self.attributes.contains("multiline") || self.w == 0 {
+            return chars(&mut self.value.chars());
+        }
+
+        let cells = self.value.to_vec();
+        let lines = cells
+            .chunks(self.w as usize)
+            .map(|line| chars(&mut line.iter().flatten().copied()))
+            .collect::<Vec<String>>();
+        let end = lines.iter().rposition(|l| !l.is_empty()).map_or(0, |i| i + 1);
+
+        lines[..end].join("\n")
+    }
+
+    /// replaces the value with s, the cursor ends up after it, see Text::insert_str
+    /// a masked text gets its mask back first, then s fills its blanks
+    pub fn replace_value(&mut self, s: &str) {
+        self.value = match self.mask {
+            Some(mask) => mask.into(),
+            None => Value::blank(self.w as usize * self.h as usize),
+        };
+        self.fill();
+        self.cx = 0;
+        self.cy = 0;
+        self.skip_literals(false);
+        self.insert_str(s);
+    }
+
+    // puts the cursor right after the last char of the value
+    fn cursor_to_end(&mut self) {
+        let end = self.value.last_filled().map_or(0, |i| i + 1);
+        self.set_cursor_index(end);
+    }
+
+    // puts the previous history entry in the value, what was typed is kept in temp
+    pub(crate) fn history_prev(&mut self, history: &History) -> bool {
+        let Some(entry) = history.get(self.hicu) else {
+            return false;
+        };
+        if self.hicu == 0 {
+            self.temp = self.value.clone();
+        }
+        self.hicu += 1;
+        self.replace_value(entry);
+
+        true
+    }
+
+    // puts the next history entry in the value, what was typed once past the newest one
+    pub(crate) fn history_next(&mut self, history: &History) -> bool {
+        match self.hicu {
+            0 => return false,
+            1 => {
+                self.hicu = 0;
+                self.value = std::mem::take(&mut self.temp);
+                self.fill();
+                self.cursor_to_end();
+                self.mark_dirty();
+            }
+            _ => {
+                self.hicu -= 1;
+                let entry = history.get(self.hicu - 1).unwrap_or_default();
+                self.replace_value(entry);
+            }
+        }
+
+        true
+    }
+
+    // the history is not being gone through anymore, e.g. after a submit
+    pub(crate) fn history_reset(&mut self) {
+        self.hicu = 0;
+        self.temp.clear();
+    }
+
+    /// the vim mode of this text, None if it doesn't have the "vim" attribute
+    pub fn mode(&self) -> Option<Mode> {
+        self.attributes.contains("vim").then(|| self.vim.mode())
+    }
+
+    /// moves the cursor to the start of the next line
+    pub fn newline(&mut self) -> bool {
+        if !self.move_down() {
+            return false;
+        }
+        self.cx = 0;
+
+        true
+    }
+}
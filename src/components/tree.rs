@@ -0,0 +1,473 @@
+use crate::inputs::keyboard::{Char, KbdEvent, CC};
+use crate::themes::Style;
+
+use super::list::{moved_to, shown_from};
+use super::{Term, Text};
+
+/// a node of a Tree, a label and the nodes under it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Node {
+    label: String,
+    children: Vec<Node>,
+    expanded: bool,
+    // whether the children are yet to be given by the Term::on_expand callback
+    lazy: bool,
+}
+
+impl Node {
+    /// makes a new collapsed Node with label and children
+    pub fn new(label: &str, children: Vec<Node>) -> Self {
+        Self {
+            label: label.to_string(),
+            children,
+            expanded: false,
+            lazy: false,
+        }
+    }
+
+    /// makes a new collapsed Node with label whose children are given by the Term::on_expand
+    /// callback the first time it is expanded
+    pub fn lazy(label: &str) -> Self {
+        Self {
+            lazy: true,
+            ..Self::new(label, vec![])
+        }
+    }
+
+    /// expands or collapses the node, a lazy node is expanded by Term::expand only
+    pub fn expanded(&mut self, expanded: bool) {
+        self.expanded = expanded && !self.lazy;
+    }
+
+    /// the label
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// the nodes under this one, none for a lazy node that was not expanded yet
+    pub fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    /// whether the children of the node are shown
+    pub fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+
+    // whether the node has children, or may have some
+    fn branch(&self) -> bool {
+        self.lazy || !self.children.is_empty()
+    }
+}
+
+/// a tree of nodes with one selected node, shown in a Text, see Text::set_tree
+/// a node takes one row, indented by its depth, and the children of a node are only shown
+/// while it is expanded
+/// a focused tree moves its selection across the shown nodes with Up, Down, Home, End, PageUp
+/// and PageDown, Right expands the selected node then goes to its first child, Left collapses
+/// it then goes to its parent, and Enter expands or collapses it
+///
+/// the selection is kept as the path to the selected node, see Tree::selected, so it stays
+/// on the same node when the nodes above it expand or collapse
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tree {
+    roots: Vec<Node>,
+    // the path of the selected node
+    selected: Vec<usize>,
+    // the index of the shown node in the first row
+    offset: usize,
+    // the markers shown before the collapsed and the expanded branches
+    markers: [char; 2],
+    // the style escape sequence of the selected row
+    highlight: String,
+}
+
+impl Tree {
+    /// makes a new Tree of the nodes in roots, the first root is selected
+    /// the selected row is shown in reverse video unless told otherwise, see Tree::highlight
+    pub fn new(roots: Vec<Node>) -> Self {
+        Self {
+            selected: if roots.is_empty() { vec![] } else { vec![0] },
+            roots,
+            offset: 0,
+            markers: ['▸', '▾'],
+            highlight: "\x1b[7m".to_string(),
+        }
+    }
+
+    /// changes the style of the selected row
+    pub fn highlight(&mut self, style: &Style) {
+        self.highlight = style.style();
+    }
+
+    /// changes the markers shown before the collapsed and the expanded nodes that have
+    /// children, '▸' and '▾' unless told otherwise, e.g. '+' and '-'
+    pub fn markers(&mut self, collapsed: char, expanded: char) {
+        self.markers = [collapsed, expanded];
+    }
+
+    /// the nodes at the top of the tree
+    pub fn roots(&self) -> &[Node] {
+        &self.roots
+    }
+
+    /// the path of the selected node, the index of each node on the way to it, starting with
+    /// the root's index, empty if the tree is empty
+    pub fn selected(&self) -> &[usize] {
+        &self.selected
+    }
+
+    /// the selected node, None if the tree is empty
+    pub fn selected_node(&self) -> Option<&Node> {
+        self.node(&self.selected)
+    }
+
+    /// the index of the shown node in the first row
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// the node at path, see Tree::selected
+    pub fn node(&self, path: &[usize]) -> Option<&Node> {
+        let (first, rest) = path.split_first()?;
+
+        rest.iter()
+            .try_fold(self.roots.get(*first)?, |node, idx| node.children.get(*idx))
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut Node> {
+        let (first, rest) = path.split_first()?;
+
+        rest.iter()
+            .try_fold(self.roots.get_mut(*first)?, |node, idx| {
+                node.children.get_mut(*idx)
+            })
+    }
+
+    // the paths of the shown nodes, the ones under expanded nodes only, in row order
+    fn rows(&self) -> Vec<Vec<usize>> {
+        fn walk(nodes: &[Node], path: &mut Vec<usize>, rows: &mut Vec<Vec<usize>>) {
+            for (idx, node) in nodes.iter().enumerate() {
+                path.push(idx);
+                rows.push(path.clone());
+                if node.expanded {
+                    walk(&node.children, path, rows);
+                }
+                path.pop();
+            }
+        }
+        let mut rows = vec![];
+        walk(&self.roots, &mut vec![], &mut rows);
+
+        rows
+    }
+
+    // the row of the shown nodes the selected node is in
+    fn row(&self) -> Option<usize> {
+        self.rows().iter().position(|path| *path == self.selected)
+    }
+
+    // the row of the text the selected node is shown in, if it is shown
+    pub(crate) fn highlighted(&self, h: u16) -> Option<(u16, &str)> {
+        let row = self.row()?.checked_sub(self.offset)?;
+        if row >= h as usize {
+            return None;
+        }
+
+        Some((row as u16, &self.highlight))
+    }
+
+    // selects the node at path and scrolls the h rows just enough for it to be shown
+    fn select(&mut self, path: Vec<usize>, h: u16) {
+        self.selected = path;
+        if let Some(row) = self.row() {
+            self.offset = shown_from(row, self.offset, h as usize);
+        }
+    }
+
+    // the value cells of the shown nodes, one node per row of w cells
+    fn cells(&self, w: u16, h: u16) -> Vec<Option<char>> {
+        let w = w as usize;
+        let mut cells = vec![None; w * h as usize];
+        let rows = self.rows();
+        let shown = rows.iter().skip(self.offset).take(h as usize);
+        for (row, path) in shown.enumerate() {
+            let node = self.node(path).unwrap();
+            let marker = match (node.branch(), node.expanded) {
+                (true, expanded) => self.markers[expanded as usize],
+                (false, _) => ' ',
+            };
+            let indent = " ".repeat(2 * (path.len() - 1));
+            let line = format!("{}{} {}", indent, marker, node.label);
+            cells[row * w..(row + 1) * w]
+                .iter_mut()
+                .zip(line.chars())
+                .for_each(|(cell, c)| *cell = (c != ' ').then_some(c));
+        }
+
+        cells
+    }
+}
+
+// trees
+impl Text {
+    /// shows tree in this text, replacing its value
+    pub fn set_tree(&mut self, tree: Tree) {
+        self.tree = Some(tree);
+        self.set_focusable(true);
+        self.sync_tree();
+    }
+
+    /// the tree this text shows, if it shows one
+    pub fn tree(&self) -> Option<&Tree> {
+        self.tree.as_ref()
+    }
+
+    /// selects the node at path of the tree this text shows, the tree scrolls for it
+    /// to be shown
+    /// returns false if there is no such node or it is not shown, see Tree::selected
+    pub fn select_node(&mut self, path: &[usize]) -> bool {
+        let Some(tree) = &mut self.tree else {
+            return false;
+        };
+        if !tree.rows().iter().any(|row| row == path) {
+            return false;
+        }
+        tree.select(path.to_vec(), self.h);
+        self.sync_tree();
+
+        true
+    }
+
+    // writes the shown nodes to the value
+    fn sync_tree(&mut self) {
+        let Some(tree) = &self.tree else {
+            return;
+        };
+        self.value = tree.cells(self.w, self.h).into();
+        self.scroll = 0;
+        self.mark_dirty();
+    }
+}
+
+impl Term {
+    /// calls f with the tree's id, a lazy node's path and label the first time the node is
+    /// expanded, the nodes f returns become its children, see Node::lazy
+    /// replaces the previous callback
+    pub fn on_expand(&mut self, f: impl FnMut([u8; 3], &[usize], &str) -> Vec<Node> + 'static) {
+        self.list_hooks.expand = Some(Box::new(f));
+    }
+
+    /// expands the node at path of the tree the text with id shows, a lazy node is given its
+    /// children by the Term::on_expand callback first
+    /// returns whether the node was expanded, false if it has no children or already is
+    pub fn expand(&mut self, id: &[u8; 3], path: &[usize]) -> bool {
+        let Some(node) = self
+            .text_mut(id)
+            .and_then(|text| text.tree.as_mut())
+            .and_then(|tree| tree.node_mut(path))
+        else {
+            return false;
+        };
+        if !node.branch() || node.expanded {
+            return false;
+        }
+        if node.lazy {
+            node.lazy = false;
+            let label = node.label.clone();
+            let children = match &mut self.list_hooks.expand {
+                Some(hook) => hook(*id, path, &label),
+                None => vec![],
+            };
+            let text = self.text_mut(id).unwrap();
+            let node = text.tree.as_mut().unwrap().node_mut(path).unwrap();
+            node.children = children;
+            // nothing to expand after all
+            if node.children.is_empty() {
+                text.sync_tree();
+                return false;
+            }
+        }
+
+        let text = self.text_mut(id).unwrap();
+        let tree = text.tree.as_mut().unwrap();
+        tree.node_mut(path).unwrap().expanded = true;
+        tree.select(tree.selected.clone(), text.h);
+        text.sync_tree();
+
+        true
+    }
+
+    /// collapses the node at path of the tree the text with id shows, the selection goes to it
+    /// if it was under it
+    /// returns whether the node was collapsed
+    pub fn collapse(&mut self, id: &[u8; 3], path: &[usize]) -> bool {
+        let Some(text) = self.text_mut(id) else {
+            return false;
+        };
+        let h = text.h;
+        let Some(tree) = &mut text.tree else {
+            return false;
+        };
+        match tree.node_mut(path) {
+            Some(node) if node.expanded => node.expanded = false,
+            _ => return false,
+        }
+        let selected = match tree.selected.starts_with(path) {
+            true => path.to_vec(),
+            false => tree.selected.clone(),
+        };
+        tree.select(selected, h);
+        text.sync_tree();
+
+        true
+    }
+
+    // moves the selection of the focused tree, expands and collapses its nodes
+    // returns None if the key is left to the keymaps
+    pub(crate) fn tree_key(&mut self, event: &KbdEvent) -> Option<bool> {
+        let id = self.focused?;
+        let text = self.text_ref(&id)?;
+        let tree = text.tree.as_ref()?;
+        if !event.modifiers.is_empty() {
+            return None;
+        }
+        let path = tree.selected.clone();
+        let node = tree.node(&path)?;
+        let [branch, expanded] = [node.branch(), node.expanded];
+        let (first, parent) = (!node.children.is_empty(), path.len() > 1);
+
+        let select =
+            |term: &mut Self, path: &[usize]| term.text_mut(&id).unwrap().select_node(path);
+        match event.char {
+            Char::CC(CC::Right) if branch && !expanded => Some(self.expand(&id, &path)),
+            Char::CC(CC::Right) if expanded && first => {
+                Some(select(self, &[&path[..], &[0]].concat()))
+            }
+            Char::CC(CC::Left) if expanded => Some(self.collapse(&id, &path)),
+            Char::CC(CC::Left) if parent => Some(select(self, &path[..path.len() - 1])),
+            Char::CC(CC::Right | CC::Left) => Some(false),
+            Char::CC(CC::CR) if expanded => Some(self.collapse(&id, &path)),
+            Char::CC(CC::CR) => Some(branch && self.expand(&id, &path)),
+            _ => {
+                let rows = tree.rows();
+                let row = tree.row()?;
+                let idx = moved_to(event, row, text.h as usize)?.min(rows.len() - 1);
+
+                Some(idx != row && select(self, &rows[idx]))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tree {
+    use super::*;
+    use crate::components::Container;
+    use crate::inputs::keyboard::{KeyKind, Modifiers};
+    use crate::space::{border::Border, padding::Padding};
+
+    // a 12x3 nonedit showing src/{main.rs, lazy}, docs
+    fn picker() -> Term {
+        let mut term = Term::new(0, 30, 8);
+        let mut c = Container::new([0, 0], 0, 0, 20, 4, Border::None, Padding::None);
+        let mut text = Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            1,
+            1,
+            12,
+            3,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        let src = Node::new(
+            "src",
+            vec![Node::new("main.rs", vec![]), Node::lazy("lazy")],
+        );
+        text.set_tree(Tree::new(vec![src, Node::new("docs", vec![])]));
+        c.items.push(text);
+        term.containers.push(c);
+        term.focused = Some([0, 0, 1]);
+
+        term
+    }
+
+    fn cc(cc: CC) -> KbdEvent {
+        KbdEvent {
+            char: Char::CC(cc),
+            modifiers: Modifiers(0),
+            kind: KeyKind::Press,
+        }
+    }
+
+    fn rows(term: &Term) -> Vec<String> {
+        let text = term.nonedit_ref(&[0, 0, 1]).unwrap();
+        text.value
+            .to_vec()
+            .chunks(text.w as usize)
+            .map(|row| row.iter().map(|c| c.unwrap_or(' ')).collect::<String>())
+            .map(|row| row.trim_end().to_string())
+            .collect()
+    }
+
+    fn tree(term: &Term) -> &Tree {
+        term.nonedit_ref(&[0, 0, 1]).unwrap().tree().unwrap()
+    }
+
+    #[test]
+    fn expand_collapse() {
+        let mut term = picker();
+        assert_eq!(rows(&term), ["▸ src", "  docs", ""]);
+        term.key(&cc(CC::Down));
+        assert_eq!(tree(&term).selected(), [1]);
+
+        // the selection stays on docs when src expands above it
+        assert!(term.expand(&[0, 0, 1], &[0]));
+        // docs is scrolled to
+        assert_eq!(rows(&term), ["    main.rs", "  ▸ lazy", "  docs"]);
+        assert_eq!(tree(&term).selected(), [1]);
+        assert_eq!([tree(&term).offset(), 1], [1, 1]);
+        assert_eq!(tree(&term).highlighted(3), Some((2, "\x1b[7m")));
+
+        term.key(&cc(CC::Home));
+        term.key(&cc(CC::Right));
+        assert_eq!(tree(&term).selected(), [0, 0]);
+        assert_eq!(tree(&term).selected_node().unwrap().label(), "main.rs");
+        term.key(&cc(CC::Left));
+        assert_eq!(tree(&term).selected(), [0]);
+        term.key(&cc(CC::Down));
+        term.key(&cc(CC::Down));
+        // collapsing src takes the selection from under it
+        assert!(term.collapse(&[0, 0, 1], &[0]));
+        assert_eq!(tree(&term).selected(), [0]);
+        assert_eq!(rows(&term), ["▸ src", "  docs", ""]);
+        term.key(&cc(CC::CR));
+        assert!(tree(&term).roots()[0].is_expanded());
+    }
+
+    #[test]
+    fn lazy_children() {
+        let mut term = picker();
+        let mut asked = 0;
+        term.on_expand(move |_, path, label| {
+            asked += 1;
+            assert_eq!((path, label, asked), (&[0, 1][..], "lazy", 1));
+            vec![Node::new("found", vec![])]
+        });
+        term.expand(&[0, 0, 1], &[0]);
+        assert!(term.expand(&[0, 0, 1], &[0, 1]));
+        term.collapse(&[0, 0, 1], &[0, 1]);
+        // the children are asked for once
+        assert!(term.expand(&[0, 0, 1], &[0, 1]));
+        assert_eq!(tree(&term).node(&[0, 1, 0]).map(Node::label), Some("found"));
+
+        let mut markers = Tree::new(vec![Node::new("a", vec![Node::new("b", vec![])])]);
+        markers.markers('+', '-');
+        let text = term.nonedit_mut(&[0, 0, 1]).unwrap();
+        text.set_tree(markers);
+        assert_eq!(rows(&term)[0], "+ a");
+    }
+}
@@ -0,0 +1,282 @@
+use crate::space::border::Border;
+
+use super::{ComponentTreeError, Term, Text, Value};
+
+/// what the app found out about the value of an input, e.g. after checking it in the background
+/// see Term::set_validation
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Validation {
+    /// not checked yet, or edited since it was
+    #[default]
+    Unknown,
+    Valid,
+    /// the value is wrong, the message says why
+    Invalid(String),
+}
+
+impl Validation {
+    /// the message of an Invalid state
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Self::Invalid(message) => Some(message),
+            _ => None,
+        }
+    }
+
+    // the theme style of the state
+    fn theme_key(&self) -> Option<&'static str> {
+        match self {
+            Self::Unknown => None,
+            Self::Valid => Some("validation.valid"),
+            Self::Invalid(_) => Some("validation.invalid"),
+        }
+    }
+
+    // the glyph a borderless text shows for the state
+    fn glyph(&self) -> Option<char> {
+        match self {
+            Self::Unknown => None,
+            Self::Valid => Some('✓'),
+            Self::Invalid(_) => Some('✗'),
+        }
+    }
+}
+
+// the validation state of a text, the style it is shown in and the nonedit showing its message
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct Validated {
+    state: Validation,
+    style: String,
+    note: Option<[u8; 3]>,
+}
+
+impl Text {
+    /// the validation state of this text, see Term::set_validation
+    pub fn validation(&self) -> &Validation {
+        &self.validated.state
+    }
+
+    // the style the border of this text is drawn in for its validation state,
+    // a state without one, e.g. on a monochrome term, leaves the border as it is
+    pub(crate) fn validation_border(&self) -> Option<&String> {
+        let shown =
+            self.validated.state != Validation::Unknown && !self.validated.style.is_empty();
+
+        shown.then_some(&self.validated.style)
+    }
+
+    // the glyph a borderless text shows on the last cell of its first row, and its style
+    pub(crate) fn validation_glyph(&self) -> Option<(char, &str)> {
+        let Border::None = self.border else {
+            return None;
+        };
+
+        Some((self.validated.state.glyph()?, &self.validated.style))
+    }
+}
+
+// the first cells chars of message, the last one is an ellipsis when the message is cut
+fn clipped(message: &str, cells: usize) -> String {
+    if message.chars().count() <= cells {
+        return message.to_string();
+    }
+    let mut clipped = message
+        .chars()
+        .take(cells.saturating_sub(1))
+        .collect::<String>();
+    if cells > 0 {
+        clipped.push('…');
+    }
+
+    clipped
+}
+
+impl Term {
+    /// gives the text with id a validation state, a bordered text gets its border in the
+    /// theme's "validation.valid" or "validation.invalid" style and a borderless one shows
+    /// ✓ or ✗ in that style on the last cell of its first row
+    /// the state goes back to Unknown the next time the value is edited
+    /// returns BadID if there is no such text
+    pub fn set_validation(
+        &mut self,
+        id: &[u8; 3],
+        state: Validation,
+    ) -> Result<(), ComponentTreeError> {
+        let style = state.theme_key().and_then(|key| self.theme_style(key));
+        let style = style.unwrap_or_default();
+        let text = self
+            .text_mut(id)
+            .ok_or_else(|| ComponentTreeError::BadID { id: id.to_vec() })?;
+        if text.validated.state == state {
+            return Ok(());
+        }
+        let message = state.message().unwrap_or_default().to_string();
+        text.validated.state = state;
+        text.validated.style = style;
+        text.mark_dirty();
+
+        if let Some(note) = text.validated.note {
+            self.note_validation(&note, &message);
+        }
+
+        Ok(())
+    }
+
+    /// the validation state of the text with id, None if there is no such text
+    pub fn validation(&self, id: &[u8; 3]) -> Option<&Validation> {
+        self.text_ref(id).map(Text::validation)
+    }
+
+    /// shows the message of the validation state of the text with id in the nonedit with note,
+    /// e.g. one right under the field, the message is cut to fit in the nonedit's area,
+    /// the nonedit is emptied while there is no message
+    /// returns BadID if either of the texts is missing
+    pub fn attach_validation(
+        &mut self,
+        id: &[u8; 3],
+        note: &[u8; 3],
+    ) -> Result<(), ComponentTreeError> {
+        if self.nonedit_ref(note).is_none() {
+            return Err(ComponentTreeError::BadID { id: note.to_vec() });
+        }
+        let text = self
+            .text_mut(id)
+            .ok_or_else(|| ComponentTreeError::BadID { id: id.to_vec() })?;
+        text.validated.note = Some(*note);
+        let message = text
+            .validated
+            .state
+            .message()
+            .unwrap_or_default()
+            .to_string();
+        self.note_validation(note, &message);
+
+        Ok(())
+    }
+
+    fn note_validation(&mut self, note: &[u8; 3], message: &str) {
+        let Some(text) = self.text_mut(note) else {
+            return;
+        };
+        let message = clipped(message, text.w as usize * text.h as usize);
+        text.replace_value(&message);
+    }
+
+    // the value of the text with id, when it has a state an edit would reset
+    pub(crate) fn validated_value(&self, id: &[u8; 3]) -> Option<Value> {
+        let text = self.text_ref(id)?;
+
+        (text.validated.state != Validation::Unknown).then(|| text.value.clone())
+    }
+
+    // puts the text with id back to Unknown when its value is not before anymore
+    pub(crate) fn revalidate(&mut self, id: &[u8; 3], before: Option<Value>) {
+        let edited =
+            before.is_some_and(|before| self.text_ref(id).is_some_and(|t| t.value != before));
+        if edited {
+            _ = self.set_validation(id, Validation::Unknown);
+        }
+    }
+}
+
+#[cfg(test)]
+mod validation {
+    use super::*;
+    use crate::components::Container;
+    use crate::console::Capabilities;
+    use crate::space::padding::Padding;
+
+    // a focused bordered 6x1 input, a borderless 6x1 input under it and a 5x1 nonedit
+    fn term() -> Term {
+        let mut term = Term::new(0, 20, 8);
+        let mut c = Container::new([0, 0], 0, 0, 12, 6, Border::None, Padding::None);
+        let (none, frame) = (Border::None, Border::Uniform('#'));
+        let padding = Padding::None;
+        c.items.push(Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            2,
+            1,
+            6,
+            1,
+            &[],
+            frame,
+            padding,
+        ));
+        c.items
+            .push(Text::input([0, 0, 1], 0, 3, 1, 3, 6, 1, &[], none, padding));
+        c.items.push(Text::nonedit(
+            [0, 0, 2],
+            0,
+            4,
+            1,
+            4,
+            5,
+            1,
+            &[],
+            none,
+            padding,
+        ));
+        term.containers.push(c);
+        term.focused = Some([0, 0, 0]);
+
+        term
+    }
+
+    #[test]
+    fn states() {
+        let mut term = term();
+        assert_eq!(term.validation(&[0, 0, 0]), Some(&Validation::Unknown));
+        term.set_validation(&[0, 0, 0], Validation::Valid).unwrap();
+        let text = term.text_ref(&[0, 0, 0]).unwrap();
+        assert!(text.attributes.contains("dirty"));
+        let valid = term.theme.get("validation.valid").unwrap();
+        assert_eq!(text.validation_border().map(String::as_str), Some(valid));
+        assert_eq!(text.validation_glyph(), None);
+
+        // the next edit resets it
+        term.paste("ab");
+        assert_eq!(term.validation(&[0, 0, 0]), Some(&Validation::Unknown));
+        assert_eq!(term.text_ref(&[0, 0, 0]).unwrap().validation_border(), None);
+
+        let err = term.set_validation(&[0, 0, 9], Validation::Valid);
+        assert!(matches!(err, Err(ComponentTreeError::BadID { .. })));
+    }
+
+    #[test]
+    fn glyph() {
+        let mut term = term();
+        let invalid = Validation::Invalid("taken".to_string());
+        term.set_validation(&[0, 0, 1], invalid).unwrap();
+        let text = term.text_ref(&[0, 0, 1]).unwrap();
+        let style = term.theme.get("validation.invalid").unwrap();
+        assert_eq!(text.validation_glyph(), Some(('✗', style)));
+        assert_eq!(text.cell(5, 0), Some('✗'));
+
+        let mut out = vec![];
+        let caps = Capabilities::default();
+        text.render_value_with(&mut out, &caps);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(&format!("{}✗\x1b[0m", caps.adapt_sgr(style))));
+    }
+
+    #[test]
+    fn attached() {
+        let mut term = term();
+        term.attach_validation(&[0, 0, 1], &[0, 0, 2]).unwrap();
+        let message = "this name is taken";
+        term.set_validation(&[0, 0, 1], Validation::Invalid(message.to_string()))
+            .unwrap();
+        let note = term.nonedit_ref(&[0, 0, 2]).unwrap();
+        assert_eq!(note.value_string(), "this…");
+        assert_eq!(
+            term.validation(&[0, 0, 1]).and_then(Validation::message),
+            Some(message)
+        );
+
+        term.set_validation(&[0, 0, 1], Validation::Valid).unwrap();
+        assert_eq!(term.nonedit_ref(&[0, 0, 2]).unwrap().value_string(), "");
+        assert!(term.attach_validation(&[0, 0, 1], &[0, 0, 1]).is_err());
+    }
+}
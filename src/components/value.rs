@@ -0,0 +1,439 @@
+use std::ops::Range;
+
+// an empty cell, a Some('\0') cell is kept as an empty one
+const HOLE: char = '\0';
+
+// the byte offset of one cell in every STRIDE is kept
+const STRIDE: usize = 64;
+
+/// the value of a Text, a row after row run of w * h cells each holding a char or nothing
+/// the cells are kept in one contiguous string, an empty cell taking a single byte,
+/// a value of ascii chars is looked up by cell in constant time, any other value
+/// in at most 64 chars from the nearest kept offset
+#[derive(Default, Clone, PartialEq, Eq, Hash)]
+pub struct Value {
+    buf: String,
+    // the number of cells, the chars of buf
+    len: usize,
+    // the byte offsets of the cells 0, STRIDE, 2 * STRIDE.. of buf, kept up with every edit
+    marks: Vec<usize>,
+}
+
+impl Value {
+    /// creates an empty value, with no cells
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// creates a value of len empty cells
+    pub fn blank(len: usize) -> Self {
+        Self {
+            buf: HOLE.to_string().repeat(len),
+            len,
+            marks: (0..len).step_by(STRIDE).collect(),
+        }
+    }
+
+    /// the number of cells of this value
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// whether this value has no cells
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // the byte offset of the cell at idx of buf, its end when idx is len
+    fn offset(&self, idx: usize) -> usize {
+        // every char takes a single byte
+        if self.buf.len() == self.len {
+            return idx;
+        }
+        let Some(&from) = self.marks.get(idx / STRIDE) else {
+            return self.buf.len();
+        };
+
+        self.buf[from..]
+            .char_indices()
+            .nth(idx % STRIDE)
+            .map_or(self.buf.len(), |(offset, _)| from + offset)
+    }
+
+    // the kept offsets of the cells past idx
+    fn marks_past(&mut self, idx: usize) -> &mut [usize] {
+        let first = (idx / STRIDE + 1).min(self.marks.len());
+
+        &mut self.marks[first..]
+    }
+
+    // works the kept offsets of the cells from idx on out again
+    fn remark(&mut self, idx: usize) {
+        self.marks.truncate(idx.div_ceil(STRIDE));
+        // the last kept offset is the one of the cell the scan starts from
+        let (skip, from) = self.marks.last().map_or((0, 0), |&from| (STRIDE, from));
+        let offsets = self.buf[from..].char_indices().skip(skip).step_by(STRIDE);
+        self.marks.extend(offsets.map(|(offset, _)| from + offset));
+    }
+
+    /// the cell at idx, None if idx is past the last cell
+    pub fn get(&self, idx: usize) -> Option<Option<char>> {
+        if idx >= self.len {
+            return None;
+        }
+
+        self.buf[self.offset(idx)..].chars().next().map(cell)
+    }
+
+    /// the last cell, None if this value has no cells
+    pub fn last(&self) -> Option<Option<char>> {
+        self.buf.chars().next_back().map(cell)
+    }
+
+    /// the index of the last cell holding a char
+    pub fn last_filled(&self) -> Option<usize> {
+        self.buf
+            .chars()
+            .rev()
+            .position(|c| c != HOLE)
+            .map(|back| self.len - 1 - back)
+    }
+
+    /// the cells of this value
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Option<char>> + '_ {
+        self.buf.chars().map(cell)
+    }
+
+    /// the cells of range, the ones past the last cell are left out
+    pub fn range(&self, range: Range<usize>) -> impl Iterator<Item = Option<char>> + '_ {
+        let start = range.start.min(self.len);
+        let end = range.end.clamp(start, self.len);
+
+        self.buf[self.offset(start)..].chars().take(end - start).map(cell)
+    }
+
+    /// the chars of this value, the empty cells are left out
+    pub fn chars(&self) -> impl DoubleEndedIterator<Item = char> + '_ {
+        self.buf.chars().filter(|c| *c != HOLE)
+    }
+
+    /// the cells of this value, as the old cell per element storage
+    pub fn to_vec(&self) -> Vec<Option<char>> {
+        self.iter().collect()
+    }
+
+    /// puts c in the cell at idx
+    ///
+    /// # Panics
+    /// if idx is past the last cell
+    pub fn set(&mut self, idx: usize, c: Option<char>) {
+        assert!(idx < self.len, "cell {} is past the value's {} cells", idx, self.len);
+        let start = self.offset(idx);
+        let end = start + self.buf[start..].chars().next().map_or(0, char::len_utf8);
+        let c = hole(c);
+        self.buf.replace_range(start..end, c.encode_utf8(&mut [0; 4]));
+        // the cells after it moved by as many bytes as the char is longer or shorter
+        let [was, is] = [end - start, c.len_utf8()];
+        self.marks_past(idx).iter_mut().for_each(|mark| *mark = *mark + is - was);
+    }
+
+    /// puts a new cell holding c at idx, the cells after it move one cell to the right
+    ///
+    /// # Panics
+    /// if idx is greater than the number of cells
+    pub fn insert(&mut self, idx: usize, c: Option<char>) {
+        assert!(idx <= self.len, "cell {} is past the value's {} cells", idx, self.len);
+        let offset = self.offset(idx);
+        let c = hole(c);
+        self.buf.insert(offset, c);
+        // each kept cell is now the one that was before it
+        let buf = &self.buf;
+        let first = (idx / STRIDE + 1).min(self.marks.len());
+        self.marks[first..].iter_mut().for_each(|mark| {
+            let moved = *mark + c.len_utf8();
+            *mark = moved - buf[..moved].chars().next_back().map_or(0, char::len_utf8);
+        });
+        // the new last cell starts another stride
+        if self.len % STRIDE == 0 {
+            let last = self.buf.chars().next_back().map_or(0, char::len_utf8);
+            self.marks.push(self.buf.len() - last);
+        }
+        self.len += 1;
+    }
+
+    /// takes the cell at idx out, the cells after it move one cell to the left
+    ///
+    /// # Panics
+    /// if idx is past the last cell
+    pub fn remove(&mut self, idx: usize) -> Option<char> {
+        assert!(idx < self.len, "cell {} is past the value's {} cells", idx, self.len);
+        let offset = self.offset(idx);
+        // each kept cell is now the one that was after it
+        let buf = &self.buf;
+        let removed = buf[offset..].chars().next().map_or(0, char::len_utf8);
+        let first = (idx / STRIDE + 1).min(self.marks.len());
+        self.marks[first..].iter_mut().for_each(|mark| {
+            let next = *mark + buf[*mark..].chars().next().map_or(0, char::len_utf8);
+            *mark = next - removed;
+        });
+        self.len -= 1;
+        self.marks.truncate(self.len.div_ceil(STRIDE));
+
+        cell(self.buf.remove(offset))
+    }
+
+    /// takes the cells of range out and gives them back, the cells past the last one are left out
+    pub fn drain(&mut self, range: Range<usize>) -> Vec<Option<char>> {
+        let start = range.start.min(self.len);
+        let end = range.end.clamp(start, self.len);
+        let [from, to] = [self.offset(start), self.offset(end)];
+        self.len -= end - start;
+        let drained = self.buf.drain(from..to).map(cell).collect();
+        self.remark(start);
+
+        drained
+    }
+
+    /// adds a cell holding c after the last cell
+    pub fn push(&mut self, c: Option<char>) {
+        if self.len % STRIDE == 0 {
+            self.marks.push(self.buf.len());
+        }
+        self.buf.push(hole(c));
+        self.len += 1;
+    }
+
+    /// takes the last cell out, None if this value has no cells
+    pub fn pop(&mut self) -> Option<Option<char>> {
+        let c = self.buf.pop()?;
+        self.len -= 1;
+        self.marks.truncate(self.len.div_ceil(STRIDE));
+
+        Some(cell(c))
+    }
+
+    /// keeps the first len cells
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            let offset = self.offset(len);
+            self.buf.truncate(offset);
+            self.len = len;
+            self.marks.truncate(len.div_ceil(STRIDE));
+        }
+    }
+
+    /// gives this value len cells, the ones it lacks are empty
+    pub fn resize(&mut self, len: usize) {
+        if len < self.len {
+            return self.truncate(len);
+        }
+        (self.len..len).for_each(|_| self.push(None));
+    }
+
+    /// takes all the cells out
+    pub fn clear(&mut self) {
+        self.buf.clear();
+        self.len = 0;
+        self.marks.clear();
+    }
+}
+
+// shown like the cells it holds
+impl std::fmt::Debug for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// the cell a char of buf stands for
+fn cell(c: char) -> Option<char> {
+    (c != HOLE).then_some(c)
+}
+
+// the char of buf a cell is kept as
+fn hole(c: Option<char>) -> char {
+    c.unwrap_or(HOLE)
+}
+
+/// a cell per char of s
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        s.chars().map(Some).collect()
+    }
+}
+
+impl From<&[Option<char>]> for Value {
+    fn from(cells: &[Option<char>]) -> Self {
+        cells.iter().copied().collect()
+    }
+}
+
+impl From<Vec<Option<char>>> for Value {
+    fn from(cells: Vec<Option<char>>) -> Self {
+        cells.into_iter().collect()
+    }
+}
+
+impl FromIterator<Option<char>> for Value {
+    fn from_iter<I: IntoIterator<Item = Option<char>>>(cells: I) -> Self {
+        let mut value = Self::new();
+        cells.into_iter().for_each(|c| value.push(c));
+
+        value
+    }
+}
+
+impl Extend<Option<char>> for Value {
+    fn extend<I: IntoIterator<Item = Option<char>>>(&mut self, cells: I) {
+        cells.into_iter().for_each(|c| self.push(c));
+    }
+}
+
+impl PartialEq<[Option<char>]> for Value {
+    fn eq(&self, cells: &[Option<char>]) -> bool {
+        self.len == cells.len() && self.iter().eq(cells.iter().copied())
+    }
+}
+
+impl PartialEq<Vec<Option<char>>> for Value {
+    fn eq(&self, cells: &Vec<Option<char>>) -> bool {
+        *self == cells[..]
+    }
+}
+
+impl<const N: usize> PartialEq<[Option<char>; N]> for Value {
+    fn eq(&self, cells: &[Option<char>; N]) -> bool {
+        *self == cells[..]
+    }
+}
+
+#[cfg(test)]
+mod value {
+    use super::*;
+
+    fn cells(s: &str) -> Vec<Option<char>> {
+        s.chars().map(|c| (c != '_').then_some(c)).collect()
+    }
+
+    #[test]
+    fn conversions() {
+        let value = Value::from(&cells("ab_c")[..]);
+        assert_eq!(value.len(), 4);
+        assert_eq!(value, cells("ab_c"));
+        assert_eq!(value.to_vec(), cells("ab_c"));
+        assert_eq!(value.chars().collect::<String>(), "abc");
+        assert_eq!(Value::from("ab"), [Some('a'), Some('b')]);
+        assert_eq!(Value::blank(3), cells("___"));
+        // a nul char is an empty cell
+        assert_eq!(Value::from("a\0"), cells("a_"));
+    }
+
+    #[test]
+    fn cells_lookup() {
+        for s in ["ab_c__", "aé_ç__"] {
+            let value = Value::from(cells(s));
+            let expected = cells(s);
+            (0..expected.len()).for_each(|idx| assert_eq!(value.get(idx), Some(expected[idx])));
+            assert_eq!(value.get(expected.len()), None);
+            assert_eq!(value.last(), Some(None));
+            assert_eq!(value.last_filled(), Some(3));
+            assert_eq!(value.range(1..3).collect::<Vec<_>>(), expected[1..3]);
+            assert_eq!(value.range(4..9).count(), 2);
+        }
+        assert_eq!(Value::blank(2).last_filled(), None);
+        assert_eq!(Value::new().last(), None);
+    }
+
+    #[test]
+    fn edits() {
+        for s in ["abc_", "aéç_"] {
+            let mut value = Value::from(cells(s));
+            let mut expected = cells(s);
+
+            value.insert(1, Some('ü'));
+            expected.insert(1, Some('ü'));
+            assert_eq!(value, expected);
+
+            assert_eq!(value.remove(2), expected.remove(2));
+            assert_eq!(value, expected);
+
+            value.set(0, None);
+            expected[0] = None;
+            value.set(3, Some('z'));
+            expected[3] = Some('z');
+            assert_eq!(value, expected);
+
+            assert_eq!(value.drain(1..3), expected.drain(1..3).collect::<Vec<_>>());
+            assert_eq!(value, expected);
+
+            value.push(Some('é'));
+            expected.push(Some('é'));
+            assert_eq!(value.pop(), expected.pop());
+            value.resize(5);
+            expected.resize(5, None);
+            assert_eq!(value, expected);
+            value.truncate(1);
+            expected.truncate(1);
+            assert_eq!(value, expected);
+            assert_eq!(value.len(), 1);
+        }
+    }
+
+    // edits all over a value of a few hundred cells, some chars taking more than a byte,
+    // the cells are looked up through the kept offsets after each one
+    #[test]
+    fn long_edits() {
+        let mut expected = cells(&"aé_ç€b".repeat(60));
+        let mut value = Value::from(&expected[..]);
+        let mut seed = 7usize;
+        let mut next = |bound: usize| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) % bound.max(1)
+        };
+        for round in 0..400 {
+            let idx = next(expected.len());
+            let c = ['x', 'é', '€', '𝄞'][round % 4];
+            match round % 6 {
+                0 | 1 => {
+                    value.insert(idx, Some(c));
+                    expected.insert(idx, Some(c));
+                }
+                2 => assert_eq!(value.remove(idx), expected.remove(idx)),
+                3 => {
+                    value.set(idx, Some(c));
+                    expected[idx] = Some(c);
+                }
+                4 => {
+                    value.push(None);
+                    expected.push(None);
+                    assert_eq!(value.pop(), expected.pop());
+                    value.push(Some(c));
+                    expected.push(Some(c));
+                }
+                _ => {
+                    let end = idx + next(80);
+                    let drained = value.drain(idx..end);
+                    let end = end.min(expected.len());
+                    assert_eq!(drained, expected.drain(idx..end).collect::<Vec<_>>());
+                    value.extend(drained.iter().rev().copied());
+                    expected.extend(drained.iter().rev().copied());
+                }
+            }
+            assert_eq!(value.len(), expected.len());
+            let cells = 0..=expected.len();
+            cells.for_each(|idx| assert_eq!(value.get(idx), expected.get(idx).copied()));
+        }
+        value.truncate(100);
+        expected.truncate(100);
+        value.resize(300);
+        expected.resize(300, None);
+        assert_eq!(value, expected);
+        assert_eq!(value.range(90..200).count(), 110);
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_past_the_end() {
+        Value::blank(2).set(2, Some('a'));
+    }
+}
@@ -0,0 +1,434 @@
+use std::any::Any;
+
+use super::{ComponentTreeError, Term, Text};
+use crate::events::Event;
+use crate::inputs::mouse::MouseEvent;
+use crate::space::{Area, Pos};
+
+/// what a widget did with an event, see Widget::handle_event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventResult {
+    /// the event goes on to the keymaps, like it would with no widget
+    Ignored,
+    /// the widget took the event and looks the same
+    Consumed,
+    /// the widget took the event and is drawn again
+    Redraw,
+}
+
+/// the cells a widget draws itself in, the size of its area, see Widget::render
+/// they start blank, the cells past the area are left out
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderCtx {
+    w: u16,
+    h: u16,
+    cells: Vec<Option<char>>,
+}
+
+impl RenderCtx {
+    /// blank cells for a widget of [w, h]
+    pub fn new([w, h]: [u16; 2]) -> Self {
+        Self {
+            w,
+            h,
+            cells: vec![None; w as usize * h as usize],
+        }
+    }
+
+    /// the [w, h] of the cells
+    pub fn size(&self) -> [u16; 2] {
+        [self.w, self.h]
+    }
+
+    /// sets the [x, y] cell to c, None blanks it
+    pub fn set(&mut self, x: u16, y: u16, c: Option<char>) {
+        if x < self.w && y < self.h {
+            self.cells[y as usize * self.w as usize + x as usize] = c;
+        }
+    }
+
+    /// the char of the [x, y] cell
+    pub fn get(&self, x: u16, y: u16) -> Option<char> {
+        match x < self.w && y < self.h {
+            true => self.cells[y as usize * self.w as usize + x as usize],
+            false => None,
+        }
+    }
+
+    /// writes s from the [x, y] cell on, a cell per char, cut at the end of the row
+    pub fn print(&mut self, x: u16, y: u16, s: &str) {
+        let end = self.w.saturating_sub(x) as usize;
+        s.chars()
+            .take(end)
+            .zip(x..)
+            .for_each(|(c, x)| self.set(x, y, Some(c)));
+    }
+
+    /// the cells row by row
+    pub fn cells(&self) -> &[Option<char>] {
+        &self.cells
+    }
+}
+
+/// a component of the app's own, e.g. a color picker or a calendar, that lives in a container,
+/// see Term::push_widget
+/// it is hosted by a nonedit of its area that gives it its id, the focus, the hit testing and
+/// the dirty tracking, what it renders is the value of that nonedit
+/// Text is a widget too, the term goes through this trait for both, see Term::widget_ref
+pub trait Widget: Any + CloneWidget {
+    /// the [x, y, w, h] area the widget takes, its origin inside the value area of its container
+    fn area(&self) -> [u16; 4];
+
+    /// draws the widget into ctx, the cells of its area
+    fn render(&self, ctx: &mut RenderCtx);
+
+    /// reacts to the keys and pastes that come in while the widget is focused,
+    /// and the mouse events over it, their column and row are the ones inside its area
+    fn handle_event(&mut self, event: &Event) -> EventResult;
+
+    /// whether Tab and the clicks can focus the widget
+    fn wants_focus(&self) -> bool;
+}
+
+/// clones a boxed widget, every Widget that is Clone has it
+pub trait CloneWidget {
+    /// a clone of this widget in a new box
+    fn clone_widget(&self) -> Box<dyn Widget>;
+}
+
+impl<W: Widget + Clone> CloneWidget for W {
+    fn clone_widget(&self) -> Box<dyn Widget> {
+        Box::new(self.clone())
+    }
+}
+
+// the widget a text hosts
+pub(crate) struct Hosted(pub(crate) Box<dyn Widget>);
+
+impl Clone for Hosted {
+    fn clone(&self) -> Self {
+        Self(self.0.clone_widget())
+    }
+}
+
+impl std::fmt::Debug for Hosted {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Hosted({:?})", self.0.area())
+    }
+}
+
+// two widgets are the same when they draw the same cells in the same area
+impl PartialEq for Hosted {
+    fn eq(&self, other: &Self) -> bool {
+        let drawn = |w: &dyn Widget| {
+            let [.., width, height] = w.area();
+            let mut ctx = RenderCtx::new([width, height]);
+            w.render(&mut ctx);
+
+            (w.area(), ctx)
+        };
+
+        drawn(&*self.0) == drawn(&*other.0)
+    }
+}
+
+// a text draws its value from its scroll on and leaves its events to the keymaps
+impl Widget for Text {
+    fn area(&self) -> [u16; 4] {
+        [self.x0, self.y0, self.w, self.h]
+    }
+
+    fn render(&self, ctx: &mut RenderCtx) {
+        let w = self.w as usize;
+        let start = self.scroll as usize * w;
+        for (idx, c) in self
+            .value
+            .iter()
+            .skip(start)
+            .take(w * self.h as usize)
+            .enumerate()
+        {
+            ctx.set((idx % w) as u16, (idx / w) as u16, c);
+        }
+    }
+
+    fn handle_event(&mut self, _: &Event) -> EventResult {
+        EventResult::Ignored
+    }
+
+    fn wants_focus(&self) -> bool {
+        self.is_focusable()
+    }
+}
+
+// widgets
+impl Text {
+    /// makes this text the host of widget, its value is what the widget draws from then on,
+    /// it is focusable when the widget wants the focus
+    /// see Term::push_widget for a widget that gets a text of its own
+    pub fn set_widget(&mut self, widget: impl Widget) {
+        self.set_focusable(widget.wants_focus());
+        self.widget = Some(Hosted(Box::new(widget)));
+        self.sync_widget();
+    }
+
+    /// the widget this text hosts, if it hosts one
+    pub fn hosted(&self) -> Option<&dyn Widget> {
+        self.widget.as_ref().map(|hosted| &*hosted.0)
+    }
+
+    // the widget the term goes through for this text, the hosted one or the text itself
+    pub(crate) fn as_widget(&self) -> &dyn Widget {
+        match &self.widget {
+            Some(hosted) => &*hosted.0,
+            None => self,
+        }
+    }
+
+    // the widget events go to, see Text::as_widget
+    fn as_widget_mut(&mut self) -> &mut dyn Widget {
+        if self.widget.is_none() {
+            return self;
+        }
+
+        &mut *self.widget.as_mut().unwrap().0
+    }
+
+    // draws the hosted widget into the value, only marks this text dirty if a cell changed
+    fn sync_widget(&mut self) {
+        let Some(hosted) = &self.widget else {
+            return;
+        };
+        let mut ctx = RenderCtx::new([self.w, self.h]);
+        hosted.0.render(&mut ctx);
+        if self.value.to_vec() != ctx.cells {
+            self.value = ctx.cells.into();
+            self.scroll = 0;
+            self.mark_dirty();
+        }
+    }
+
+    // hands event to the widget of this text, the hosted one is drawn again for Redraw
+    fn widget_event(&mut self, event: &Event) -> EventResult {
+        let result = self.as_widget_mut().handle_event(event);
+        if result == EventResult::Redraw {
+            self.sync_widget();
+        }
+
+        result
+    }
+}
+
+impl Term {
+    /// adds widget to the container of id, hosted by a new nonedit with id at the area of the
+    /// widget, see Widget
+    ///
+    /// # Errors
+    /// the same as TextBuilder::build, e.g. when the area of the widget doesn't fit in its
+    /// container
+    pub fn push_widget(
+        &mut self,
+        id: [u8; 3],
+        widget: impl Widget,
+    ) -> Result<(), ComponentTreeError> {
+        let [x, y, w, h] = widget.area();
+        Text::nonedit_builder(id)
+            .pos(Pos::Value(x), Pos::Value(y))
+            .area(Area::Values { w, h })
+            .build(self)?;
+        self.text_mut(&id).unwrap().set_widget(widget);
+
+        Ok(())
+    }
+
+    /// the widget of the text with id, the one it hosts or the text itself,
+    /// None when there is no such text
+    pub fn widget_ref(&self, id: &[u8; 3]) -> Option<&dyn Widget> {
+        self.text_ref(id).map(Text::as_widget)
+    }
+
+    /// calls f with the widget of type W the text with id hosts and draws it again after,
+    /// e.g. to give a picker a new color, returns what f returned
+    /// None when there is no such text or it hosts no W
+    pub fn widget_mut<W: Widget, T>(
+        &mut self,
+        id: &[u8; 3],
+        f: impl FnOnce(&mut W) -> T,
+    ) -> Option<T> {
+        let text = self.text_mut(id)?;
+        let widget: &mut dyn Any = &mut *text.widget.as_mut()?.0;
+        let out = f(widget.downcast_mut::<W>()?);
+        text.sync_widget();
+
+        Some(out)
+    }
+
+    // hands a key or a paste to the widget of the focused text
+    // returns None when the widget ignored it, whether it is drawn again otherwise
+    pub(crate) fn widget_key(&mut self, event: &Event) -> Option<bool> {
+        let id = self.focused?;
+        match self.text_mut(&id)?.widget_event(event) {
+            EventResult::Ignored => None,
+            result => Some(result == EventResult::Redraw),
+        }
+    }
+
+    // hands a mouse event to the widget of the text with id, its cells made the ones inside
+    // the area of the text, see Term::widget_key
+    pub(crate) fn widget_mouse(&mut self, id: &[u8; 3], event: &MouseEvent) -> Option<bool> {
+        let text = self.text_mut(id)?;
+        let inside = MouseEvent {
+            column: event.column.saturating_sub(text.ax0),
+            row: event.row.saturating_sub(text.ay0),
+            ..event.clone()
+        };
+        match text.widget_event(&Event::Mouse(inside)) {
+            EventResult::Ignored => None,
+            result => Some(result == EventResult::Redraw),
+        }
+    }
+}
+
+#[cfg(test)]
+mod widget {
+    use super::*;
+    use crate::components::Container;
+    use crate::inputs::keyboard::{Char, KbdEvent, KeyKind, Modifiers, CC};
+    use crate::inputs::mouse::{MouseButton, MouseKind};
+    use crate::space::{border::Border, padding::Padding};
+
+    // a counter that + and - change and a click resets
+    #[derive(Clone)]
+    struct Counter(i32);
+
+    impl Widget for Counter {
+        fn area(&self) -> [u16; 4] {
+            [2, 1, 6, 1]
+        }
+
+        fn render(&self, ctx: &mut RenderCtx) {
+            ctx.print(0, 0, &format!("n: {}", self.0));
+        }
+
+        fn handle_event(&mut self, event: &Event) -> EventResult {
+            match event {
+                Event::Key(KbdEvent {
+                    char: Char::Char('+'),
+                    ..
+                }) => self.0 += 1,
+                Event::Key(KbdEvent {
+                    char: Char::Char('-'),
+                    ..
+                }) => self.0 -= 1,
+                Event::Mouse(MouseEvent {
+                    kind: MouseKind::Down,
+                    column: 0,
+                    ..
+                }) => self.0 = 0,
+                Event::Mouse(_) => return EventResult::Consumed,
+                _ => return EventResult::Ignored,
+            }
+
+            EventResult::Redraw
+        }
+
+        fn wants_focus(&self) -> bool {
+            true
+        }
+    }
+
+    fn key(char: Char) -> KbdEvent {
+        KbdEvent {
+            char,
+            modifiers: Modifiers(0),
+            kind: KeyKind::Press,
+        }
+    }
+
+    fn shown(term: &Term) -> String {
+        let value = &term.text_ref(&[0, 0, 1]).unwrap().value;
+
+        value.iter().map(|c| c.unwrap_or(' ')).collect()
+    }
+
+    fn term() -> Term {
+        let mut term = Term::new(0, 20, 6);
+        let c = Container::new([0, 0], 0, 0, 12, 4, Border::None, Padding::None);
+        term.containers.push(c);
+        term.push_widget([0, 0, 1], Counter(3)).unwrap();
+
+        term
+    }
+
+    #[test]
+    fn hosted() {
+        let mut term = term();
+        assert_eq!(shown(&term), "n: 3  ");
+        let text = term.text_ref(&[0, 0, 1]).unwrap();
+        assert_eq!([text.x0, text.y0, text.w, text.h], [2, 1, 6, 1]);
+        assert!(term.widget_ref(&[0, 0, 1]).unwrap().wants_focus());
+        // there is room for one counter only
+        assert!(term.push_widget([0, 0, 1], Counter(0)).is_err());
+
+        term.focus(&[0, 0, 1]).unwrap();
+        term.key(&key(Char::Char('+')));
+        term.key(&key(Char::Char('+')));
+        assert_eq!(shown(&term), "n: 5  ");
+        assert!(term
+            .text_ref(&[0, 0, 1])
+            .unwrap()
+            .attributes
+            .contains("dirty"));
+        // the keys it ignores go to the keymaps
+        assert_eq!(term.widget_key(&Event::Key(key(Char::CC(CC::TAB)))), None);
+
+        let changed = term.widget_mut(&[0, 0, 1], |counter: &mut Counter| {
+            counter.0 = -1;
+            counter.0
+        });
+        assert_eq!(changed, Some(-1));
+        assert_eq!(shown(&term), "n: -1 ");
+        assert_eq!(term.widget_mut(&[0, 0, 1], |_: &mut Text| ()), None);
+    }
+
+    #[test]
+    fn clicked() {
+        let mut term = term();
+        let click = |column| MouseEvent {
+            kind: MouseKind::Down,
+            button: MouseButton::Left,
+            column,
+            row: 1,
+            modifiers: Modifiers(0),
+        };
+        // the first cell of the widget is its column 0
+        assert_eq!(term.mouse(&click(2)), Some([0, 0, 1]));
+        assert_eq!(shown(&term), "n: 0  ");
+        // the widget took the click and got the focus from it
+        assert_eq!(term.focused, Some([0, 0, 1]));
+        assert_eq!(term.mouse(&click(9)), None);
+    }
+
+    #[test]
+    fn texts() {
+        let mut text = Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            0,
+            0,
+            3,
+            2,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        text.value = vec![Some('a'), None, Some('b'), Some('c')].into();
+        let mut ctx = RenderCtx::new([3, 2]);
+        Widget::render(&text, &mut ctx);
+        assert_eq!(ctx.get(2, 0), Some('b'));
+        assert_eq!(ctx.get(0, 1), Some('c'));
+        assert_eq!(text.widget_event(&Event::Tick), EventResult::Ignored);
+        assert!(!text.as_widget().wants_focus());
+    }
+}
@@ -0,0 +1,886 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::components::{ComponentTreeError, Container, Property, Term, Text, TextKind};
+use crate::space::{border::Border, padding::Padding, Area, Pos};
+
+/// the errors of Term::from_config, each one names the entry it is about, "term" for the
+/// fields before the first section, and the line it is on
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// a line that is not a [section], a key = value pair, a comment nor empty
+    Syntax { line: usize },
+    /// a field that is not known, has a value of the wrong kind or is missing
+    Field {
+        entry: String,
+        field: String,
+        line: usize,
+    },
+    /// a section whose name is taken by a section before it
+    Duplicate { entry: String, line: usize },
+    /// a text whose container is not declared before it
+    Parent { entry: String, line: usize },
+    /// the component of the entry doesn't fit where it goes or its id is taken
+    Component {
+        entry: String,
+        line: usize,
+        error: ComponentTreeError,
+    },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax { line } => {
+                write!(
+                    f,
+                    "line {}: expected a [section] or a key = value pair",
+                    line
+                )
+            }
+            Self::Field { entry, field, line } => {
+                write!(
+                    f,
+                    "line {}: bad or missing field '{}' in '{}'",
+                    line, field, entry
+                )
+            }
+            Self::Duplicate { entry, line } => {
+                write!(f, "line {}: the name '{}' is already taken", line, entry)
+            }
+            Self::Parent { entry, line } => write!(
+                f,
+                "line {}: '{}' is in no container declared before it",
+                line, entry
+            ),
+            Self::Component { entry, line, error } => {
+                write!(f, "line {}: '{}' could not be made: {}", line, entry, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Component { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+// a config value
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Array(Vec<Value>),
+}
+
+// a [section] and its fields, the fields before the first section are the term's
+#[derive(Debug)]
+struct Entry {
+    name: String,
+    line: usize,
+    // key, value and line
+    fields: Vec<(String, Value, usize)>,
+}
+
+const TERM_FIELDS: [&str; 3] = ["id", "w", "h"];
+const CONTAINER_FIELDS: [&str; 9] = [
+    "hpos",
+    "vpos",
+    "w",
+    "h",
+    "border",
+    "padding",
+    "margin",
+    "layer",
+    "attributes",
+];
+const TEXT_FIELDS: [&str; 11] = [
+    "kind",
+    "hpos",
+    "vpos",
+    "w",
+    "h",
+    "border",
+    "padding",
+    "margin",
+    "layer",
+    "attributes",
+    "value",
+];
+
+impl Entry {
+    fn value(&self, key: &str) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|(k, ..)| k == key)
+            .map(|(_, value, _)| value)
+    }
+
+    // the error of a field this entry has a bad value for, or doesn't have
+    fn bad(&self, key: &str) -> ConfigError {
+        let line = self
+            .fields
+            .iter()
+            .find(|(k, ..)| k == key)
+            .map_or(self.line, |(.., line)| *line);
+
+        ConfigError::Field {
+            entry: self.name.clone(),
+            field: key.to_string(),
+            line,
+        }
+    }
+
+    // the first field that is not one of known nor a property
+    fn check(&self, known: &[&str]) -> Result<(), ConfigError> {
+        match self
+            .fields
+            .iter()
+            .find(|(k, ..)| !known.contains(&k.as_str()) && !k.starts_with("properties."))
+        {
+            Some((key, ..)) => Err(self.bad(key)),
+            None => Ok(()),
+        }
+    }
+
+    fn int(&self, key: &str, max: u16) -> Result<Option<u16>, ConfigError> {
+        match self.value(key) {
+            None => Ok(None),
+            Some(Value::Int(n)) if (0..=max as i64).contains(n) => Ok(Some(*n as u16)),
+            Some(_) => Err(self.bad(key)),
+        }
+    }
+
+    fn pos(&self, key: &str) -> Result<Pos, ConfigError> {
+        match self.value(key) {
+            None => Ok(Pos::Center),
+            Some(Value::Str(s)) => match s.as_str() {
+                "start" => Ok(Pos::Start),
+                "center" => Ok(Pos::Center),
+                "end" => Ok(Pos::End),
+                _ => Err(self.bad(key)),
+            },
+            Some(_) => self.int(key, u16::MAX).map(|n| Pos::Value(n.unwrap())),
+        }
+    }
+
+    // a width or height of cells, of a percentage of the parent's or "fill"
+    fn size(&self, key: &str, parent: u16) -> Result<u16, ConfigError> {
+        match self.value(key) {
+            None => Ok(parent),
+            Some(Value::Str(s)) if s == "fill" => Ok(parent),
+            Some(Value::Str(s)) => {
+                let percent = s.strip_suffix('%').and_then(|p| p.parse::<u16>().ok());
+                let [w, _] = Area::Percent {
+                    w: percent.ok_or_else(|| self.bad(key))?,
+                    h: 0,
+                }
+                .unwrap([parent, 0]);
+
+                Ok(w)
+            }
+            Some(_) => self.int(key, u16::MAX).map(Option::unwrap),
+        }
+    }
+
+    fn border(&self) -> Result<Border, ConfigError> {
+        let Some(value) = self.value("border") else {
+            return Ok(Border::None);
+        };
+        let Value::Str(s) = value else {
+            return Err(self.bad("border"));
+        };
+        let chars = s.chars().collect::<Vec<char>>();
+
+        Ok(match (s.as_str(), chars.as_slice()) {
+            ("none", _) => Border::None,
+            ("rounded", _) => Border::rounded(),
+            ("light", _) => Border::polyform('┌', '┐', '┘', '└', '│', '─'),
+            ("heavy", _) => Border::polyform('┏', '┓', '┛', '┗', '┃', '━'),
+            ("double", _) => Border::polyform('╔', '╗', '╝', '╚', '║', '═'),
+            ("ascii", _) => Border::polyform('+', '+', '+', '+', '|', '-'),
+            (_, [c]) => Border::Uniform(*c),
+            (_, [tl, tr, br, bl, rl, tb]) => Border::polyform(*tl, *tr, *br, *bl, *rl, *tb),
+            _ => return Err(self.bad("border")),
+        })
+    }
+
+    // the [top, bottom, right, left] sides of the padding or margin field
+    fn sides(&self, key: &str) -> Result<Option<[u16; 4]>, ConfigError> {
+        let side = |value: &Value| match value {
+            Value::Int(n) if (0..=u16::MAX as i64).contains(n) => Some(*n as u16),
+            _ => None,
+        };
+        let sides = match self.value(key) {
+            None => return Ok(None),
+            Some(Value::Array(values)) if values.len() == 4 => {
+                let sides = values.iter().map(side).collect::<Option<Vec<u16>>>();
+                sides.map(|s| [s[0], s[1], s[2], s[3]])
+            }
+            Some(value) => side(value).map(|n| [n; 4]),
+        };
+
+        sides.map(Some).ok_or_else(|| self.bad(key))
+    }
+
+    fn padding(&self) -> Result<Padding, ConfigError> {
+        Ok(match (self.sides("padding")?, self.sides("margin")?) {
+            (None, None) => Padding::None,
+            (Some([top, bottom, right, left]), None) => Padding::Inner {
+                top,
+                bottom,
+                right,
+                left,
+            },
+            (None, Some([top, bottom, right, left])) => Padding::Outer {
+                top,
+                bottom,
+                right,
+                left,
+            },
+            (Some(inner), Some(outer)) => Padding::InOut {
+                inner_top: inner[0],
+                inner_bottom: inner[1],
+                inner_right: inner[2],
+                inner_left: inner[3],
+                outer_top: outer[0],
+                outer_bottom: outer[1],
+                outer_right: outer[2],
+                outer_left: outer[3],
+            },
+        })
+    }
+
+    fn attributes(&self) -> Result<Vec<&'static str>, ConfigError> {
+        match self.value("attributes") {
+            None => Ok(vec![]),
+            Some(Value::Array(values)) => values
+                .iter()
+                .map(|value| match value {
+                    Value::Str(s) => Ok(interned(s)),
+                    _ => Err(self.bad("attributes")),
+                })
+                .collect(),
+            Some(_) => Err(self.bad("attributes")),
+        }
+    }
+
+    // the properties.name fields, then the name of the entry as the "name" property
+    fn properties(&self, name: &str) -> Vec<(&'static str, Property)> {
+        fn property(value: &Value) -> Property {
+            match value {
+                Value::Str(s) => Property::String(s.clone()),
+                Value::Int(n) => Property::Int(*n),
+                Value::Float(n) => Property::Float(*n),
+                Value::Bool(b) => Property::Bool(*b),
+                Value::Array(values) => Property::Vec(values.iter().map(property).collect()),
+            }
+        }
+        let mut properties = self
+            .fields
+            .iter()
+            .filter_map(|(key, value, _)| Some((key.strip_prefix("properties.")?, value)))
+            .map(|(key, value)| (interned(key), property(value)))
+            .collect::<Vec<_>>();
+        properties.push(("name", Property::String(name.to_string())));
+
+        properties
+    }
+}
+
+// the attribute and property names are &'static str, the ones read from configs are leaked
+// once each and handed out again every time they come up
+fn interned(name: &str) -> &'static str {
+    static NAMES: std::sync::OnceLock<std::sync::Mutex<HashSet<&'static str>>> =
+        std::sync::OnceLock::new();
+    let mut names = NAMES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    match names.get(name) {
+        Some(name) => name,
+        None => {
+            let name = &*Box::leak(name.to_string().into_boxed_str());
+            names.insert(name);
+
+            name
+        }
+    }
+}
+
+// the entries of a config, the term's first
+fn parse(config: &str) -> Result<Vec<Entry>, ConfigError> {
+    let mut entries = vec![Entry {
+        name: "term".to_string(),
+        line: 1,
+        fields: vec![],
+    }];
+    for (idx, line) in config.lines().enumerate() {
+        let (text, line) = (line.trim(), idx + 1);
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        if let Some(header) = text.strip_prefix('[') {
+            let (name, rest) = header.split_once(']').ok_or(ConfigError::Syntax { line })?;
+            let name = name.trim();
+            let segments = name.split('.').collect::<Vec<&str>>();
+            let valid = |s: &&str| {
+                !s.is_empty()
+                    && s.chars()
+                        .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+            };
+            if segments.len() > 2 || !segments.iter().all(valid) || !comment(rest) {
+                return Err(ConfigError::Syntax { line });
+            }
+            if entries.iter().any(|e| e.name == name) {
+                return Err(ConfigError::Duplicate {
+                    entry: name.to_string(),
+                    line,
+                });
+            }
+            entries.push(Entry {
+                name: name.to_string(),
+                line,
+                fields: vec![],
+            });
+            continue;
+        }
+        let (key, value) = text.split_once('=').ok_or(ConfigError::Syntax { line })?;
+        let (value, rest) = parse_value(value.trim_start()).ok_or(ConfigError::Syntax { line })?;
+        if key.trim().is_empty() || !comment(rest) {
+            return Err(ConfigError::Syntax { line });
+        }
+        let entry = entries.last_mut().unwrap();
+        let key = key.trim().to_string();
+        if entry.value(&key).is_some() {
+            return Err(ConfigError::Field {
+                entry: entry.name.clone(),
+                field: key,
+                line,
+            });
+        }
+        entry.fields.push((key, value, line));
+    }
+
+    Ok(entries)
+}
+
+// whether what is left of a line is nothing but a comment
+fn comment(rest: &str) -> bool {
+    let rest = rest.trim();
+
+    rest.is_empty() || rest.starts_with('#')
+}
+
+// the value s starts with and what comes after it
+fn parse_value(s: &str) -> Option<(Value, &str)> {
+    if let Some(mut rest) = s.strip_prefix('"') {
+        let mut string = String::new();
+        loop {
+            let mut chars = rest.chars();
+            match chars.next()? {
+                '"' => return Some((Value::Str(string), chars.as_str())),
+                '\\' => string.push(match chars.next()? {
+                    'n' => '\n',
+                    't' => '\t',
+                    c @ ('"' | '\\') => c,
+                    _ => return None,
+                }),
+                c => string.push(c),
+            }
+            rest = chars.as_str();
+        }
+    }
+    if let Some(mut rest) = s.strip_prefix('[') {
+        let mut values = vec![];
+        loop {
+            rest = rest.trim_start();
+            if let Some(rest) = rest.strip_prefix(']') {
+                return Some((Value::Array(values), rest));
+            }
+            let (value, after) = parse_value(rest)?;
+            values.push(value);
+            rest = after.trim_start();
+            rest = match rest.strip_prefix(',') {
+                Some(after) => after,
+                None if rest.starts_with(']') => rest,
+                None => return None,
+            };
+        }
+    }
+    for (word, b) in [("true", true), ("false", false)] {
+        if let Some(rest) = s.strip_prefix(word) {
+            return Some((Value::Bool(b), rest));
+        }
+    }
+    let end = s
+        .find(|c: char| !(c.is_ascii_digit() || "+-._eE".contains(c)))
+        .unwrap_or(s.len());
+    let (number, rest) = s.split_at(end);
+    let number = number.replace('_', "");
+    if let Ok(n) = number.parse::<i64>() {
+        return Some((Value::Int(n), rest));
+    }
+
+    number.parse::<f64>().ok().map(|n| (Value::Float(n), rest))
+}
+
+impl Term {
+    /// makes a Term from a config, its fields come first, then a [name] section for each
+    /// container and a [container.name] section for each text, after the container's section
+    ///
+    /// the containers get the ids [term id, 0], [term id, 1] and so on in the order they are
+    /// declared, the texts in each container get the first of its text ids that is free,
+    /// 0, 1, 2... in the order they are declared, the name of each one is kept in its
+    /// "name" property
+    ///
+    /// # Format
+    /// ```toml
+    /// # the term's id, 0 if not given, and size in cells
+    /// id = 0
+    /// w = 80
+    /// h = 24
+    ///
+    /// [sidebar]
+    /// # start, center, end or a cell, center if not given
+    /// hpos = "start"
+    /// vpos = "start"
+    /// # cells, a percentage of the parent's or fill, fill if not given
+    /// w = "30%"
+    /// h = "fill"
+    /// # none, rounded, light, heavy, double, ascii, a char or the 6 chars of the top left,
+    /// # top right, bottom right and bottom left corners and the sides and top and bottom
+    /// border = "rounded"
+    /// # the top, bottom, right and left padding, inside the border, or one for all 4
+    /// padding = [0, 0, 1, 1]
+    /// # the same, outside the border
+    /// margin = 0
+    /// layer = 0
+    /// attributes = ["sidebar"]
+    /// properties.title = "files"
+    ///
+    /// [sidebar.search_box]
+    /// # input or nonedit
+    /// kind = "input"
+    /// w = 20
+    /// h = 1
+    /// value = "initial value"
+    /// ```
+    ///
+    /// # Errors
+    /// returns the first error of the config, see ConfigError, the components are made the
+    /// same way Container::builder and Text::builder_of make them
+    pub fn from_config(config: &str) -> Result<Term, ConfigError> {
+        let mut entries = parse(config)?.into_iter();
+        let root = entries.next().unwrap();
+        root.check(&TERM_FIELDS)?;
+        let id = root.int("id", u8::MAX as u16)?.unwrap_or(0) as u8;
+        let w = root.int("w", u16::MAX)?.ok_or_else(|| root.bad("w"))?;
+        let h = root.int("h", u16::MAX)?.ok_or_else(|| root.bad("h"))?;
+        let mut term = Term::new(id, w, h);
+
+        // the name and id of each container
+        let mut containers: Vec<(String, [u8; 2])> = vec![];
+        for entry in entries {
+            let component = |error: ComponentTreeError| ConfigError::Component {
+                entry: entry.name.clone(),
+                line: entry.line,
+                error,
+            };
+            match entry.name.split_once('.') {
+                None => {
+                    entry.check(&CONTAINER_FIELDS)?;
+                    let cid = containers.len() as u8;
+                    let mut builder = Container::builder([id, cid])
+                        .pos(entry.pos("hpos")?, entry.pos("vpos")?)
+                        .area(Area::Values {
+                            w: entry.size("w", w)?,
+                            h: entry.size("h", h)?,
+                        })
+                        .border(entry.border()?)
+                        .padding(entry.padding()?)
+                        .layer(entry.int("layer", u8::MAX as u16)?.unwrap_or(0) as u8);
+                    for attribute in entry.attributes()? {
+                        builder = builder.attribute(attribute);
+                    }
+                    for (name, property) in entry.properties(&entry.name) {
+                        builder = builder.property(name, property);
+                    }
+                    let cid = builder.build(&mut term).map_err(component)?;
+                    containers.push((entry.name.clone(), cid));
+                }
+                Some((parent, name)) => {
+                    entry.check(&TEXT_FIELDS)?;
+                    let Some((_, cid)) = containers.iter().find(|(n, _)| n == parent) else {
+                        return Err(ConfigError::Parent {
+                            entry: entry.name.clone(),
+                            line: entry.line,
+                        });
+                    };
+                    let kind = match entry.value("kind") {
+                        Some(Value::Str(kind)) if kind == "input" => TextKind::Input,
+                        Some(Value::Str(kind)) if kind == "nonedit" => TextKind::NonEdit,
+                        _ => return Err(entry.bad("kind")),
+                    };
+                    let cont = term.container_ref(cid).unwrap();
+                    let cwh = [cont.w, cont.h];
+                    let Some(tid) = cont.free_text_id() else {
+                        return Err(component(ComponentTreeError::DuplicateId {
+                            id: cid.to_vec(),
+                        }));
+                    };
+                    let value = match entry.value("value") {
+                        None => vec![],
+                        Some(Value::Str(s)) => s.chars().map(Some).collect(),
+                        Some(_) => return Err(entry.bad("value")),
+                    };
+                    let mut builder = Text::builder_of(kind, tid)
+                        .pos(entry.pos("hpos")?, entry.pos("vpos")?)
+                        .area(Area::Values {
+                            w: entry.size("w", cwh[0])?,
+                            h: entry.size("h", cwh[1])?,
+                        })
+                        .border(entry.border()?)
+                        .padding(entry.padding()?)
+                        .layer(entry.int("layer", u8::MAX as u16)?.unwrap_or(0) as u8)
+                        .value(&value);
+                    for attribute in entry.attributes()? {
+                        builder = builder.attribute(attribute);
+                    }
+                    for (name, property) in entry.properties(name) {
+                        builder = builder.property(name, property);
+                    }
+                    builder.build(&mut term).map_err(component)?;
+                }
+            }
+        }
+
+        Ok(term)
+    }
+
+    /// this term as a config Term::from_config makes it back from, with the positions and
+    /// sizes in cells and the components named by their "name" properties
+    /// the manual borders, the attributes the term uses for itself and the properties that
+    /// are not strings, numbers, bools or lists of those are left out
+    pub fn to_config(&self) -> String {
+        let mut config = format!("id = {}\nw = {}\nh = {}\n", self.id, self.w, self.h);
+        for c in &self.containers {
+            let cname = name(&c.properties).unwrap_or_else(|| format!("container{}", c.id[1]));
+            _ = write!(config, "\n[{}]\n", cname);
+            let [w, h] = c.decorate();
+            fields(
+                &mut config,
+                [c.x0, c.y0, w, h],
+                &c.border,
+                &c.padding,
+                c.layer,
+            );
+            extras(&mut config, &c.attributes, &c.properties);
+            for t in &c.items {
+                let tname = name(&t.properties).unwrap_or_else(|| format!("text{}", t.id[2]));
+                _ = write!(config, "\n[{}.{}]\n", cname, tname);
+                let kind = if t.is_input() { "input" } else { "nonedit" };
+                _ = writeln!(config, "kind = \"{}\"", kind);
+                let [w, h] = t.decorate();
+                fields(
+                    &mut config,
+                    [t.x0, t.y0, w, h],
+                    &t.border,
+                    &t.padding,
+                    t.layer,
+                );
+                let value = t.value.chars().collect::<String>();
+                if !value.is_empty() {
+                    _ = writeln!(config, "value = {}", quote(&value));
+                }
+                extras(&mut config, &t.attributes, &t.properties);
+            }
+        }
+
+        config
+    }
+}
+
+// the "name" property
+fn name(properties: &HashMap<&'static str, Property>) -> Option<String> {
+    match properties.get("name") {
+        Some(Property::String(name)) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+// writes the place, size, border, padding and layer fields of a component
+fn fields(
+    config: &mut String,
+    [x0, y0, w, h]: [u16; 4],
+    border: &Border,
+    padding: &Padding,
+    layer: u8,
+) {
+    _ = writeln!(config, "hpos = {}\nvpos = {}\nw = {}\nh = {}", x0, y0, w, h);
+    match *border {
+        Border::None | Border::Manual { .. } => (),
+        Border::Uniform(c) => _ = writeln!(config, "border = {}", quote(&c.to_string())),
+        Border::Polyform {
+            trcorner,
+            tlcorner,
+            blcorner,
+            brcorner,
+            rl,
+            tb,
+        } => {
+            let chars = [tlcorner, trcorner, brcorner, blcorner, rl, tb];
+            _ = writeln!(
+                config,
+                "border = {}",
+                quote(&chars.iter().collect::<String>())
+            );
+        }
+    }
+    let (inner, outer) = match *padding {
+        Padding::None => (None, None),
+        Padding::Inner {
+            top,
+            bottom,
+            right,
+            left,
+        } => (Some([top, bottom, right, left]), None),
+        Padding::Outer {
+            top,
+            bottom,
+            right,
+            left,
+        } => (None, Some([top, bottom, right, left])),
+        Padding::InOut {
+            inner_top,
+            inner_bottom,
+            inner_right,
+            inner_left,
+            outer_top,
+            outer_bottom,
+            outer_right,
+            outer_left,
+        } => (
+            Some([inner_top, inner_bottom, inner_right, inner_left]),
+            Some([outer_top, outer_bottom, outer_right, outer_left]),
+        ),
+    };
+    for (key, sides) in [("padding", inner), ("margin", outer)] {
+        if let Some(sides) = sides {
+            _ = writeln!(config, "{} = {:?}", key, sides);
+        }
+    }
+    if layer != 0 {
+        _ = writeln!(config, "layer = {}", layer);
+    }
+}
+
+// writes the attributes and properties of a component, sorted
+fn extras(
+    config: &mut String,
+    attributes: &HashSet<&'static str>,
+    properties: &HashMap<&'static str, Property>,
+) {
+    // the term marks the components it has to render again with dirty
+    let mut attributes = attributes
+        .iter()
+        .filter(|a| **a != "dirty")
+        .map(|a| quote(a))
+        .collect::<Vec<String>>();
+    attributes.sort();
+    if !attributes.is_empty() {
+        _ = writeln!(config, "attributes = [{}]", attributes.join(", "));
+    }
+    let mut properties = properties
+        .iter()
+        .filter(|(name, _)| **name != "name")
+        .filter_map(|(name, property)| Some((*name, value(property)?)))
+        .collect::<Vec<(&str, String)>>();
+    properties.sort();
+    for (name, value) in properties {
+        _ = writeln!(config, "properties.{} = {}", name, value);
+    }
+}
+
+// the config value of a property, None if it has none
+fn value(property: &Property) -> Option<String> {
+    Some(match property {
+        Property::String(s) => quote(s),
+        Property::Int(n) => n.to_string(),
+        Property::UInt(n) if *n <= i64::MAX as u64 => n.to_string(),
+        Property::Float(n) if n.is_finite() => format!("{:?}", n),
+        Property::Bool(b) => b.to_string(),
+        Property::Vec(values) => {
+            let values = values.iter().map(value).collect::<Option<Vec<String>>>()?;
+            format!("[{}]", values.join(", "))
+        }
+        _ => return None,
+    })
+}
+
+// s as a config string
+fn quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t");
+
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod config {
+    use super::*;
+
+    const CONFIG: &str = r##"
+        w = 40
+        h = 10
+
+        [sidebar]
+        hpos = "start"
+        vpos = "start"
+        w = "50%"
+        border = "rounded"
+        attributes = ["side"]
+        properties.title = "files"
+
+        [sidebar.search_box] # the search field
+        kind = "input"
+        hpos = "start"
+        vpos = "start"
+        w = 18
+        h = 1
+
+        [sidebar.title]
+        kind = "nonedit"
+        hpos = "start"
+        vpos = 1
+        w = 5
+        h = 1
+        value = "fi\"le"
+        padding = [0, 0, 0, 0]
+
+        [main]
+        hpos = "end"
+        vpos = "start"
+        w = 20
+        border = "#"
+        margin = 0
+        properties.weights = [1, 2.5, true]
+    "##;
+
+    #[test]
+    fn load() {
+        let term = Term::from_config(CONFIG).unwrap();
+        let sidebar = term.container_ref(&[0, 0]).unwrap();
+        assert_eq!(
+            [sidebar.x0, sidebar.y0, sidebar.w, sidebar.h],
+            [0, 0, 18, 8]
+        );
+        assert!(sidebar.attributes.contains("side"));
+        assert!(matches!(
+            sidebar.properties.get("title"),
+            Some(Property::String(s)) if s == "files"
+        ));
+        assert!(term.input_ref(&[0, 0, 0]).is_some());
+        let title = term.nonedit_ref(&[0, 0, 1]).unwrap();
+        assert_eq!(title.value_string(), "fi\"le");
+        assert!(matches!(
+            title.properties.get("name"),
+            Some(Property::String(s)) if s == "title"
+        ));
+        let main = term.container_ref(&[0, 1]).unwrap();
+        assert_eq!([main.x0, main.w], [20, 18]);
+    }
+
+    #[test]
+    fn interned_names() {
+        let keys = |term: &Term| {
+            let sidebar = term.container_ref(&[0, 0]).unwrap();
+            let side = *sidebar.attributes.get("side").unwrap();
+            let (title, _) = sidebar.properties.get_key_value("title").unwrap();
+
+            [side.as_ptr(), title.as_ptr()]
+        };
+        // the names read twice are the same leaked ones
+        let first = Term::from_config(CONFIG).unwrap();
+        let second = Term::from_config(CONFIG).unwrap();
+        assert_eq!(keys(&first), keys(&second));
+    }
+
+    #[test]
+    fn round_trip() {
+        let term = Term::from_config(CONFIG).unwrap();
+        let config = term.to_config();
+        assert!(config.contains("[sidebar.title]\nkind = \"nonedit\"\nhpos = 0\nvpos = 1\n"));
+        assert!(config.contains("border = \"╭╮╯╰│─\""));
+        assert!(config.contains("properties.weights = [1, 2.5, true]"));
+        let again = Term::from_config(&config).unwrap();
+        assert_eq!(again.dump(), term.dump());
+        assert_eq!(again.to_config(), config);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |config: &str| Term::from_config(config).unwrap_err();
+        assert_eq!(error("w = 4\nh"), ConfigError::Syntax { line: 2 });
+        assert_eq!(
+            error("w = 4"),
+            ConfigError::Field {
+                entry: "term".into(),
+                field: "h".into(),
+                line: 1
+            }
+        );
+        assert_eq!(
+            error("w = 4\nh = 4\n[a]\nbroder = \"none\""),
+            ConfigError::Field {
+                entry: "a".into(),
+                field: "broder".into(),
+                line: 4
+            }
+        );
+        assert_eq!(
+            error("w = 4\nh = 4\nw = 5"),
+            ConfigError::Field {
+                entry: "term".into(),
+                field: "w".into(),
+                line: 3
+            }
+        );
+        assert_eq!(
+            error("w = 4\nh = 4\n[a]\n[a]"),
+            ConfigError::Duplicate {
+                entry: "a".into(),
+                line: 4
+            }
+        );
+        assert_eq!(
+            error("w = 4\nh = 4\n[a.b]\nkind = \"input\""),
+            ConfigError::Parent {
+                entry: "a.b".into(),
+                line: 3
+            }
+        );
+        let too_wide = error("w = 4\nh = 4\n[a]\nw = 9");
+        assert!(matches!(
+            too_wide,
+            ConfigError::Component {
+                ref entry,
+                line: 3,
+                error: ComponentTreeError::AreaOutOfBounds { .. },
+            } if entry == "a"
+        ));
+        assert_eq!(
+            too_wide.to_string(),
+            "line 3: 'a' could not be made: a 9x4 area does not fit in the available 4x4"
+        );
+    }
+}
@@ -1,12 +1,42 @@
 /// termios c ffi, raw mode utilities
+#[cfg(unix)]
 pub mod raw_mode;
 /// winsize c ffi, use for getting the terminal window widtn and height
+#[cfg(unix)]
 pub mod winsize;
+/// the terminal driver a Term draws through
+pub mod backend;
+/// terminal capabilities detection
+pub mod capabilities;
+/// terminal queries, such as the cursor position report
+pub mod query;
+/// the panic hook that restores the terminal
+pub mod panic;
+/// printing in raw mode, see raw_println!
+pub mod print;
+/// wakes a thread parked on input, a self-pipe on unix
+pub(crate) mod wake;
+/// signal handling, job control
+#[cfg(all(unix, feature = "events"))]
+pub mod signals;
+/// win32 console ffi, provides the windows raw_mode and winsize modules
+#[cfg(windows)]
+pub(crate) mod windows;
+
+#[cfg(windows)]
+pub use windows::{raw_mode, winsize};
+
+pub use backend::Backend;
+#[cfg(unix)]
+pub use backend::UnixBackend;
+pub use capabilities::Capabilities;
+pub use panic::install_panic_hook;
+pub use query::query_cursor_position;
 
 use std::io::StdoutLock;
 use std::io::Write;
 
-pub use raw_mode::{cooked_mode, raw_mode};
+pub use raw_mode::{cooked_mode, raw_mode, RawMode};
 
 /// exits the terminal alternate screen back to the original screen
 pub fn leave_alternate_screen(writer: &mut StdoutLock) {
@@ -19,6 +49,47 @@ pub fn enter_alternate_screen(writer: &mut StdoutLock) {
     _ = writer.flush();
 }
 
+/// whether stdout is a terminal
+/// escape sequences that only make sense to a terminal can be skipped when it isn't
+pub fn stdout_is_tty() -> bool {
+    #[cfg(unix)]
+    {
+        extern "C" {
+            fn isatty(fd: i32) -> i32;
+        }
+
+        unsafe { isatty(1) == 1 }
+    }
+
+    #[cfg(windows)]
+    {
+        windows::stdout_is_console()
+    }
+}
+
+/// returns the OSC 2 sequence that sets the terminal window title to the given title
+/// control chars are dropped from the title so that it can't end the sequence early
+/// or smuggle other escape sequences in
+pub fn title_sequence(title: &str) -> String {
+    let title = title.chars().filter(|c| !c.is_control()).collect::<String>();
+
+    format!("\x1b]2;{}\x1b\\", title)
+}
+
 pub fn workers() -> (std::io::StdinLock<'static>, std::io::StdoutLock<'static>) {
     (std::io::stdin().lock(), std::io::stdout().lock())
 }
+
+#[cfg(test)]
+mod title {
+    use super::title_sequence;
+
+    #[test]
+    fn sanitized() {
+        assert_eq!(title_sequence("myapp — foo.txt"), "\x1b]2;myapp — foo.txt\x1b\\");
+        assert_eq!(
+            title_sequence("a\x07b\x1b]0;evil\x1b\\c\u{9c}\n"),
+            "\x1b]2;ab]0;evil\\c\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use std::io::Write;
+
+#[cfg(unix)]
+use super::RawMode;
+
+/// what a Term needs from the terminal it draws to
+/// a backend is a writer, the frames and the escape sequences go through Write,
+/// on top of that it has to put the terminal in and out of raw mode and know its size
+/// the cursor, alternate screen and mouse methods write the usual ansi sequences
+/// unless a backend has its own way of doing them
+///
+/// UnixBackend is the termios and ioctl one that Term::start uses,
+/// see Term::backend for drawing a Term through another one
+pub trait Backend: Write {
+    /// puts the terminal in raw mode, calling it again while raw mode is on does nothing
+    fn enable_raw_mode(&mut self) -> std::io::Result<()>;
+
+    /// gives the terminal its original mode back, does nothing if raw mode is off
+    fn disable_raw_mode(&mut self) -> std::io::Result<()>;
+
+    /// the terminal size as [columns, rows]
+    fn size(&self) -> std::io::Result<[u16; 2]>;
+
+    /// moves the cursor to the 1 based column x and the row y, see render_pipeline::cup
+    fn move_cursor(&mut self, x: u16, y: u16) -> std::io::Result<()> {
+        self.write_all(format!("\x1b[{};{}f", y, x).as_bytes())
+    }
+
+    /// moves to the alternate screen
+    fn enter_alternate_screen(&mut self) -> std::io::Result<()> {
+        self.write_all(b"\x1b[?1049h\x1b[0;0f")?;
+        self.flush()
+    }
+
+    /// leaves the alternate screen back to the main one
+    fn leave_alternate_screen(&mut self) -> std::io::Result<()> {
+        self.write_all(b"\x1b[?1049l")?;
+        self.flush()
+    }
+
+    /// turns on the sgr mouse reporting
+    fn enable_mouse(&mut self) -> std::io::Result<()> {
+        self.write_all(crate::inputs::mouse::ENABLE_MOUSE)?;
+        self.flush()
+    }
+
+    /// turns off the sgr mouse reporting
+    fn disable_mouse(&mut self) -> std::io::Result<()> {
+        self.write_all(crate::inputs::mouse::DISABLE_MOUSE)?;
+        self.flush()
+    }
+}
+
+/// the backend of a unix terminal: termios for raw mode, the winsize ioctl for the size
+/// and stdout for the rest
+/// raw mode is a RawMode guard, so what was turned on through the backend is torn down
+/// when it is dropped or the program panics, like with Term::start
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct UnixBackend {
+    out: std::io::Stdout,
+    raw: Option<RawMode>,
+}
+
+#[cfg(unix)]
+impl Default for UnixBackend {
+    fn default() -> Self {
+        Self {
+            out: std::io::stdout(),
+            raw: None,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl UnixBackend {
+    /// makes a backend writing to stdout, raw mode stays off until it is enabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(unix)]
+impl Write for UnixBackend {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.out.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(unix)]
+impl Backend for UnixBackend {
+    fn enable_raw_mode(&mut self) -> std::io::Result<()> {
+        if self.raw.is_none() {
+            self.raw = Some(RawMode::enable()?);
+        }
+
+        Ok(())
+    }
+
+    fn disable_raw_mode(&mut self) -> std::io::Result<()> {
+        match self.raw.take() {
+            Some(mut guard) => guard.restore(),
+            None => Ok(()),
+        }
+    }
+
+    fn size(&self) -> std::io::Result<[u16; 2]> {
+        super::winsize::detect()
+            .map(|ws| [ws.cols(), ws.rows()])
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{:?}", e)))
+    }
+
+    // the guard leaves the alternate screen again when raw mode is disabled
+    fn enter_alternate_screen(&mut self) -> std::io::Result<()> {
+        match &mut self.raw {
+            Some(guard) => guard.enter_alternate_screen(&mut self.out),
+            None => {
+                self.out.write_all(b"\x1b[?1049h\x1b[0;0f")?;
+                self.out.flush()
+            }
+        }
+    }
+
+    // the guard turns the mouse reporting off again when raw mode is disabled
+    fn enable_mouse(&mut self) -> std::io::Result<()> {
+        match &mut self.raw {
+            Some(guard) => guard.enable_mouse(&mut self.out),
+            None => {
+                self.out.write_all(crate::inputs::mouse::ENABLE_MOUSE)?;
+                self.out.flush()
+            }
+        }
+    }
+}
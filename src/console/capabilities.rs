@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// what the terminal is able to display
+/// detected once from the environment, see Capabilities::detect
+/// can also be built by hand to force some rendering mode
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Capabilities {
+    /// 24 bit rgb colors, "38;2;r;g;b"
+    pub truecolor: bool,
+    /// the xterm 256 colors palette, "38;5;n"
+    pub colors_256: bool,
+    /// non ascii chars, such as box drawing borders
+    pub unicode: bool,
+    /// the synchronized output mode 2026, frames get drawn at once instead of line by line
+    pub synchronized_output: bool,
+    /// OSC 8 hyperlinks
+    pub hyperlinks: bool,
+    /// no colors at all, only the other attributes, e.g. bold, underline and reverse,
+    /// set by NO_COLOR
+    pub monochrome: bool,
+}
+
+// terminals that are known to support everything
+const MODERN_TERMS: [&str; 4] = ["kitty", "wezterm", "foot", "ghostty"];
+const MODERN_PROGRAMS: [&str; 4] = ["WezTerm", "iTerm.app", "ghostty", "vscode"];
+
+impl Capabilities {
+    /// every capability turned on
+    pub fn full() -> Self {
+        Self {
+            truecolor: true,
+            colors_256: true,
+            unicode: true,
+            synchronized_output: true,
+            hyperlinks: true,
+            monochrome: false,
+        }
+    }
+
+    /// returns the capabilities detected from the environment
+    /// the detection only runs the first time this is called
+    pub fn detected() -> &'static Self {
+        static DETECTED: OnceLock<Capabilities> = OnceLock::new();
+
+        DETECTED.get_or_init(Self::detect)
+    }
+
+    /// detects the capabilities from the TERM, COLORTERM, NO_COLOR, TERM_PROGRAM
+    /// and locale environment variables
+    pub fn detect() -> Self {
+        Self::from_vars(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+    }
+
+    // the env lookup is a parameter so that the detection can be tested
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let term = var("TERM").unwrap_or_default();
+        let no_color = var("NO_COLOR").is_some();
+        if term == "dumb" {
+            return Self {
+                monochrome: no_color,
+                ..Default::default()
+            };
+        }
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        let modern = MODERN_TERMS.iter().any(|t| term.contains(t))
+            || MODERN_PROGRAMS.iter().any(|p| program == *p);
+
+        let colorterm = var("COLORTERM").unwrap_or_default();
+        let truecolor =
+            !no_color && (modern || colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("-direct"));
+        let colors_256 = !no_color && (truecolor || term.contains("256color"));
+
+        // the first set one of these decides the locale
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .into_iter()
+            .find_map(|v| var(v))
+            .unwrap_or_default()
+            .to_lowercase();
+        let unicode = !term.starts_with("vt")
+            && (locale.contains("utf-8") || locale.contains("utf8"));
+
+        Self {
+            truecolor,
+            colors_256,
+            unicode,
+            synchronized_output: modern,
+            hyperlinks: modern || var("VTE_VERSION").is_some(),
+            monochrome: no_color,
+        }
+    }
+
+    /// rewrites the colors of the sgr escape sequences in s to what the terminal can display
+    /// rgb colors become the nearest 256 palette colors, of the 6x6x6 cube or the gray ramp,
+    /// or the nearest of the 16 basic colors when the palette is not supported either,
+    /// and so do the palette colors, a Style can give a color its own, see Style::text_fallback
+    /// a monochrome terminal gets no colors, a sequence that only set colors is dropped
+    pub fn adapt_sgr(&self, s: &str) -> String {
+        if (self.truecolor && !self.monochrome) || !s.contains("\x1b[") {
+            return s.to_string();
+        }
+
+        let mut out = String::with_capacity(s.len());
+        let mut rest = s;
+        while let Some(start) = rest.find("\x1b[") {
+            out.push_str(&rest[..start]);
+            rest = &rest[start + 2..];
+            out.push_str("\x1b[");
+
+            let end = rest
+                .find(|c: char| !c.is_ascii_digit() && c != ';')
+                .unwrap_or(rest.len());
+            if rest[end..].starts_with('m') {
+                let params = self.adapt_params(&rest[..end]);
+                // what is left of a sequence of colors would reset the attributes
+                if params.is_empty() && end > 0 {
+                    out.truncate(out.len() - 2);
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+                out.push_str(&params);
+                rest = &rest[end..];
+            }
+        }
+        out.push_str(rest);
+
+        out
+    }
+
+    fn adapt_params(&self, params: &str) -> String {
+        let params = params.split(';').collect::<Vec<&str>>();
+        let mut out = vec![];
+        let mut idx = 0;
+        while idx < params.len() {
+            let p = params[idx];
+            let base = if p == "38" { 30 } else { 40 };
+            let color = (p == "38" || p == "48").then(|| params.get(idx + 1)).flatten();
+            if self.monochrome {
+                idx += match color {
+                    Some(&"2") => 5,
+                    Some(&"5") => 3,
+                    _ if is_color(p) => 1,
+                    _ => {
+                        out.push(p.to_string());
+                        1
+                    }
+                };
+                continue;
+            }
+            if color == Some(&"2") && idx + 5 <= params.len() {
+                let rgb = [2, 3, 4].map(|i| params[idx + i].parse::<u8>().unwrap_or(0));
+                let [n, basic] = downgraded(rgb);
+                match self.colors_256 {
+                    true => out.push(format!("{};5;{}", p, n)),
+                    false => out.push(basic_param(base, basic)),
+                }
+                idx += 5;
+                continue;
+            }
+            if color == Some(&"5") && idx + 3 <= params.len() && !self.colors_256 {
+                let n = params[idx + 2].parse::<u8>().unwrap_or(0);
+                let basic = match n {
+                    0..=15 => n,
+                    _ => nearest(palette(n), 0..=15),
+                };
+                out.push(basic_param(base, basic));
+                idx += 3;
+                continue;
+            }
+            out.push(p.to_string());
+            idx += 1;
+        }
+
+        out.join(";")
+    }
+
+    /// swaps box drawing and block chars for their closest ascii look alike
+    /// when the terminal can not display unicode
+    pub fn adapt_char(&self, c: char) -> char {
+        if self.unicode || c.is_ascii() {
+            return c;
+        }
+
+        match c {
+            '─' | '━' | '┄' | '┅' | '┈' | '┉' | '═' | '╌' | '╍' | '╴' | '╶' | '╸' | '╺' | '╼'
+            | '╾' => '-',
+            '│' | '┃' | '┆' | '┇' | '┊' | '┋' | '║' | '╎' | '╏' | '╵' | '╷' | '╹' | '╻' | '╽'
+            | '╿' => '|',
+            '╱' => '/',
+            '╲' => '\\',
+            '╳' => 'X',
+            '…' => '.',
+            '▁' => '_',
+            '▂' => '.',
+            '▃' => ':',
+            '▄' => '-',
+            '▅' => '=',
+            '▆' => '+',
+            '▇' => '*',
+            '█' | '▉' | '▊' | '▋' | '▌' => '#',
+            '▍' | '▎' | '▏' => ' ',
+            '\u{2500}'..='\u{257F}' => '+',
+            c => c,
+        }
+    }
+}
+
+// the rgb values of the 16 basic colors, as xterm shows them
+const BASIC: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [205, 0, 0],
+    [0, 205, 0],
+    [205, 205, 0],
+    [0, 0, 238],
+    [205, 0, 205],
+    [0, 205, 205],
+    [229, 229, 229],
+    [127, 127, 127],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [92, 92, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+];
+
+// the levels of the channels of the 6x6x6 cube of the 256 colors palette
+const STEPS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+// the rgb values of the 256 colors palette color n
+fn palette(n: u8) -> [u8; 3] {
+    match n {
+        0..=15 => BASIC[n as usize],
+        16..=231 => {
+            let n = n - 16;
+            [n / 36, n / 6 % 6, n % 6].map(|i| STEPS[i as usize])
+        }
+        _ => [8 + 10 * (n - 232); 3],
+    }
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    (0..3)
+        .map(|i| (a[i] as i32 - b[i] as i32).pow(2) as u32)
+        .sum()
+}
+
+// the color of the range of the 256 colors palette nearest to rgb, the first one on a tie
+fn nearest(rgb: [u8; 3], range: std::ops::RangeInclusive<u8>) -> u8 {
+    range.min_by_key(|n| distance(palette(*n), rgb)).unwrap_or(0)
+}
+
+// the 256 colors palette color and the basic color rgb is displayed as, worked out once
+// per color, unless a Style gave it a fallback, see Style::text_fallback
+fn downgraded(rgb: [u8; 3]) -> [u8; 2] {
+    let mut colors = colors().lock().unwrap_or_else(|e| e.into_inner());
+    *colors.entry(rgb).or_insert_with(|| {
+        let cube = nearest(rgb, 16..=231);
+        let gray = nearest(rgb, 232..=255);
+        let n = match distance(palette(gray), rgb) < distance(palette(cube), rgb) {
+            true => gray,
+            false => cube,
+        };
+
+        [n, nearest(rgb, 0..=15)]
+    })
+}
+
+fn colors() -> &'static Mutex<HashMap<[u8; 3], [u8; 2]>> {
+    static COLORS: OnceLock<Mutex<HashMap<[u8; 3], [u8; 2]>>> = OnceLock::new();
+
+    COLORS.get_or_init(Default::default)
+}
+
+// makes rgb be displayed as the 256 colors palette color n, or the basic color basic,
+// by the terminals that can't display it, see Style::text_fallback
+pub(crate) fn set_fallback(rgb: [u8; 3], [n, basic]: [u8; 2]) {
+    let mut colors = colors().lock().unwrap_or_else(|e| e.into_inner());
+    colors.insert(rgb, [n, basic.min(15)]);
+}
+
+// whether the sgr param p sets the text or background color to a basic or the default one
+fn is_color(p: &str) -> bool {
+    matches!(p.parse::<u8>(), Ok(30..=39 | 40..=49 | 90..=97 | 100..=107))
+}
+
+// the sgr param of the basic color n, for the text with base 30 or the background with base 40
+fn basic_param(base: u8, n: u8) -> String {
+    match n {
+        0..=7 => (base + n).to_string(),
+        _ => (base + 60 + n - 8).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod detect {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn caps(vars: &[(&str, &str)]) -> Capabilities {
+        let vars = vars.iter().copied().collect::<HashMap<&str, &str>>();
+
+        Capabilities::from_vars(|v| vars.get(v).map(|v| v.to_string()))
+    }
+
+    #[test]
+    fn env() {
+        let c = caps(&[("TERM", "xterm-256color"), ("LANG", "en_US.UTF-8")]);
+        assert!(!c.truecolor && c.colors_256 && c.unicode);
+
+        let c = caps(&[("TERM", "xterm-256color"), ("COLORTERM", "truecolor")]);
+        assert!(c.truecolor && !c.unicode);
+
+        let c = caps(&[("TERM", "xterm-kitty"), ("NO_COLOR", "1"), ("LC_ALL", "C.utf8")]);
+        assert!(!c.truecolor && !c.colors_256 && c.unicode && c.synchronized_output);
+
+        assert_eq!(caps(&[("TERM", "dumb"), ("LANG", "en_US.UTF-8")]), Capabilities::default());
+    }
+
+    #[test]
+    fn sgr() {
+        let s = "\x1b[1;38;2;255;0;0;48;2;0;0;0mx\x1b[0m";
+        assert_eq!(Capabilities::full().adapt_sgr(s), s);
+
+        let c = Capabilities {
+            colors_256: true,
+            ..Default::default()
+        };
+        assert_eq!(c.adapt_sgr(s), "\x1b[1;38;5;196;48;5;16mx\x1b[0m");
+        assert_eq!(Capabilities::default().adapt_sgr(s), "\x1b[1;91;40mx\x1b[0m");
+        // cursor moves are left alone
+        assert_eq!(Capabilities::default().adapt_sgr("\x1b[2;3f"), "\x1b[2;3f");
+    }
+
+    #[test]
+    fn monochrome() {
+        let c = caps(&[("TERM", "xterm-kitty"), ("NO_COLOR", "1")]);
+        assert!(c.monochrome);
+        let s = "\x1b[1;38;2;255;0;0;48;5;4;7mx\x1b[31;49m\x1b[0m\x1b[m";
+        assert_eq!(c.adapt_sgr(s), "\x1b[1;7mx\x1b[0m\x1b[m");
+        assert!(!caps(&[("TERM", "xterm-kitty")]).monochrome);
+    }
+
+    #[test]
+    fn downgrade() {
+        for (rgb, n, basic) in [
+            ([255, 0, 0], 196, 9),
+            ([128, 128, 128], 244, 8),
+            ([95, 135, 175], 67, 8),
+            ([0, 0, 200], 20, 4),
+            ([250, 250, 250], 231, 15),
+            ([30, 30, 30], 234, 0),
+        ] {
+            assert_eq!(downgraded(rgb), [n, basic], "{:?}", rgb);
+        }
+        let c = Capabilities::default();
+        assert_eq!(c.adapt_sgr("\x1b[38;5;196;48;5;4m"), "\x1b[91;44m");
+        assert_eq!(c.adapt_sgr("\x1b[38;5;244m"), "\x1b[90m");
+    }
+
+    #[cfg(feature = "themes")]
+    #[test]
+    fn fallback() {
+        use crate::themes::Style;
+
+        let style = Style::new()
+            .text_color(&[1, 2, 3])
+            .text_fallback(33, 12)
+            .style();
+        let c = Capabilities {
+            colors_256: true,
+            ..Default::default()
+        };
+        assert_eq!(c.adapt_sgr(&style), "\x1b[38;5;33m");
+        assert_eq!(Capabilities::default().adapt_sgr(&style), "\x1b[94m");
+        assert_eq!(Capabilities::full().adapt_sgr(&style), style);
+    }
+
+    #[test]
+    fn ascii_borders() {
+        let c = Capabilities::default();
+        let ascii = "╭──┬╮│║┼…"
+            .chars()
+            .map(|ch| c.adapt_char(ch))
+            .collect::<String>();
+        assert_eq!(ascii, "+--++||+.");
+        assert_eq!(Capabilities::full().adapt_char('╭'), '╭');
+    }
+}
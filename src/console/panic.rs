@@ -0,0 +1,89 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::Once;
+
+use super::raw_mode;
+
+// what the live RawMode guards turned on, so that the panic hook knows what to undo
+// without having a hold of the guards themselves
+pub(crate) const MOUSE: u8 = 1;
+pub(crate) const FOCUS_REPORTS: u8 = 2;
+pub(crate) const SAVED_TITLE: u8 = 4;
+pub(crate) const HIDDEN_CURSOR: u8 = 8;
+pub(crate) const ALTERNATE_SCREEN: u8 = 16;
+pub(crate) const KEY_EVENTS: u8 = 32;
+
+static ENABLED: AtomicU8 = AtomicU8::new(0);
+
+// bumped every time the hook restores the terminal
+// guards made before that have nothing left to restore
+static EPOCH: AtomicUsize = AtomicUsize::new(0);
+
+static INSTALL: Once = Once::new();
+
+// records that a guard turned on the given features
+pub(crate) fn mark(features: u8) {
+    ENABLED.fetch_or(features, Ordering::SeqCst);
+}
+
+// forgets the recorded features, once no guard is left alive
+pub(crate) fn unmark_all() {
+    ENABLED.store(0, Ordering::SeqCst);
+}
+
+pub(crate) fn epoch() -> usize {
+    EPOCH.load(Ordering::SeqCst)
+}
+
+// the sequences that undo the given features
+// same order as the guards' own teardown
+pub(crate) fn teardown(features: u8) -> Vec<u8> {
+    let mut s = vec![];
+    // kitty keeps a keyboard mode stack per screen, so this one goes before leaving the alternate screen
+    if features & KEY_EVENTS != 0 {
+        s.extend_from_slice(crate::inputs::keyboard::DISABLE_KEY_EVENTS);
+    }
+    if features & MOUSE != 0 {
+        s.extend_from_slice(crate::inputs::mouse::DISABLE_MOUSE);
+    }
+    if features & FOCUS_REPORTS != 0 {
+        s.extend_from_slice(crate::inputs::window::DISABLE_FOCUS_REPORTS);
+    }
+    if features & SAVED_TITLE != 0 {
+        s.extend_from_slice(b"\x1b[23;0t");
+    }
+    if features & (HIDDEN_CURSOR | ALTERNATE_SCREEN) != 0 {
+        s.extend_from_slice(b"\x1b[?25h");
+    }
+    if features & ALTERNATE_SCREEN != 0 {
+        s.extend_from_slice(b"\x1b[?1049l");
+    }
+
+    s
+}
+
+// restores the terminal on behalf of every live guard
+// the guards find out through the epoch and do nothing once dropped
+pub(crate) fn restore(writer: &mut impl Write) {
+    EPOCH.fetch_add(1, Ordering::SeqCst);
+    raw_mode::restore_all();
+    _ = writer.write_all(&teardown(ENABLED.swap(0, Ordering::SeqCst)));
+    _ = writer.flush();
+}
+
+/// installs a panic hook that gives the terminal its original state back before the panic
+/// message gets printed, so that the message and backtrace end up readable on the main screen
+/// the flags of every live RawMode are restored and whatever the guards turned on
+/// (alternate screen, hidden cursor, mouse and focus reports, title) is undone,
+/// the guards themselves then do nothing when dropped
+/// the previously installed hook still runs right after, calling this more than once does nothing
+pub fn install_panic_hook() {
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            // stderr, since the panicking thread may not be the one holding the stdout lock
+            restore(&mut std::io::stderr());
+            previous(info);
+        }));
+    });
+}
@@ -0,0 +1,106 @@
+use std::io::Write;
+
+// the number of cells each char of line is drawn over, in the order they come,
+// a mark goes with the char before it
+#[cfg(feature = "components")]
+fn cells(line: &str) -> Vec<(String, usize)> {
+    use crate::components::grapheme;
+
+    grapheme::split(line)
+        .into_iter()
+        .map(|c| (c.to_string(), grapheme::cells(c)))
+        .collect()
+}
+
+#[cfg(not(feature = "components"))]
+fn cells(line: &str) -> Vec<(String, usize)> {
+    line.chars().map(|c| (c.to_string(), 1)).collect()
+}
+
+/// text as it is printed in raw mode, every line break is a "\r\n" and a line longer than width
+/// cells is broken into lines of width cells, raw mode doesn't go back to the first column on
+/// a "\n" and the terminal's own wrapping leaves the cursor where a frame doesn't expect it
+/// None leaves the lines as long as they are
+pub fn raw_text(text: &str, width: Option<u16>) -> String {
+    let width = width.map_or(usize::MAX, |w| w.max(1) as usize);
+    let mut s = String::with_capacity(text.len());
+    for (idx, line) in text.split('\n').enumerate() {
+        if idx > 0 {
+            s.push_str("\r\n");
+        }
+        let mut taken = 0;
+        for (chars, n) in cells(line.strip_suffix('\r').unwrap_or(line)) {
+            if taken + n > width && taken > 0 {
+                s.push_str("\r\n");
+                taken = 0;
+            }
+            s.push_str(&chars);
+            taken += n;
+        }
+    }
+
+    s
+}
+
+/// prints text to stdout as raw_text has it, at the width of the terminal when stdout is one,
+/// in one write, see raw_print! and raw_println!
+/// it can be called before there is any Term, a Term prints its lines with Term::print_line
+pub fn raw_print(text: &str) {
+    let width = match super::stdout_is_tty() {
+        true => super::winsize::detect().ok().map(|ws| ws.cols()),
+        false => None,
+    };
+    let mut writer = std::io::stdout().lock();
+    _ = writer.write_all(raw_text(text, width).as_bytes());
+    _ = writer.flush();
+}
+
+/// print! for raw mode, every "\n" is a "\r\n" and the long lines are broken at the width of the
+/// terminal, see console::print::raw_print
+///
+/// # Examples
+/// ```
+/// ragout::raw_print!("{} of {} ", 3, 5);
+/// ```
+#[macro_export]
+macro_rules! raw_print {
+    ($($arg:tt)*) => {
+        $crate::console::print::raw_print(&::std::format!($($arg)*))
+    };
+}
+
+/// println! for raw mode, see raw_print!
+///
+/// # Examples
+/// ```
+/// ragout::raw_println!("saved {}", "notes.txt");
+/// ```
+#[macro_export]
+macro_rules! raw_println {
+    () => {
+        $crate::console::print::raw_print("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::console::print::raw_print(&::std::format!("{}\n", ::std::format_args!($($arg)*)))
+    };
+}
+
+#[cfg(test)]
+mod print {
+    use super::*;
+
+    #[test]
+    fn breaks() {
+        assert_eq!(raw_text("a\nb\r\nc\n", None), "a\r\nb\r\nc\r\n");
+        assert_eq!(raw_text("abcdefg", Some(3)), "abc\r\ndef\r\ng");
+        assert_eq!(raw_text("ab\ncdef", Some(2)), "ab\r\ncd\r\nef");
+    }
+
+    #[cfg(feature = "components")]
+    #[test]
+    fn wide() {
+        // the wide char that doesn't fit goes on the next line, whole
+        assert_eq!(raw_text("ab漢字", Some(3)), "ab\r\n漢\r\n字");
+        assert_eq!(raw_text("e\u{301}fg", Some(2)), "e\u{301}f\r\ng");
+    }
+}
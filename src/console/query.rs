@@ -0,0 +1,188 @@
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// how long query_cursor_position waits for the terminal to answer
+pub const QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+
+// from /usr/include/poll.h and /usr/include/unistd.h
+#[cfg(unix)]
+#[repr(C)]
+struct pollfd {
+    fd: i32,
+    events: i16,
+    revents: i16,
+}
+
+#[cfg(unix)]
+const POLLIN: i16 = 0x001;
+
+#[cfg(unix)]
+extern "C" {
+    fn poll(fds: *mut pollfd, nfds: u64, timeout: i32) -> i32;
+    fn read(fd: i32, buf: *mut u8, count: usize) -> isize;
+}
+
+/// asks the terminal where the cursor is, DSR 6
+/// returns the 0 based (x, y) cell of the cursor
+///
+/// raw mode has to be on, otherwise the reply only arrives once the user hits enter
+/// input that comes in before or after the reply is kept for inputs::read
+///
+/// # Errors
+///
+/// returns an error of kind TimedOut if the terminal doesn't answer within QUERY_TIMEOUT
+#[cfg(unix)]
+pub fn query_cursor_position() -> std::io::Result<(u16, u16)> {
+    let mut writer = std::io::stdout().lock();
+    writer.write_all(b"\x1b[6n")?;
+    writer.flush()?;
+
+    let start = Instant::now();
+    let mut bytes = vec![];
+    let mut buf = [0u8; 64];
+
+    loop {
+        if let Some((idx, len, x, y)) = parse_cpr(&bytes) {
+            // everything around the reply belongs to the key parser
+            bytes.drain(idx..idx + len);
+            crate::inputs::keep_pending(&bytes);
+
+            return Ok((x, y));
+        }
+
+        let left = QUERY_TIMEOUT.saturating_sub(start.elapsed());
+        if left.is_zero() {
+            crate::inputs::keep_pending(&bytes);
+
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "the terminal did not answer the cursor position query",
+            ));
+        }
+
+        match read_timeout(&mut buf, left) {
+            Ok(n) => bytes.extend_from_slice(&buf[..n]),
+            Err(e) => {
+                crate::inputs::keep_pending(&bytes);
+
+                return Err(e);
+            }
+        }
+    }
+}
+
+// reads whatever stdin has within timeout into buf
+// returns 0 when nothing came in, an interrupted wait counts as nothing
+#[cfg(unix)]
+pub(crate) fn read_timeout(buf: &mut [u8], timeout: Duration) -> std::io::Result<usize> {
+    read_fd_timeout(0, buf, timeout, &[])
+}
+
+// reads whatever fd has within timeout into buf
+// also returns 0 as soon as one of the wakers is woken, the wakes are used up
+// a negative fd is not waited on
+#[cfg(unix)]
+pub(crate) fn read_fd_timeout(
+    fd: i32,
+    buf: &mut [u8],
+    timeout: Duration,
+    wakers: &[&crate::console::wake::Waker],
+) -> std::io::Result<usize> {
+    let mut fds = std::iter::once(fd)
+        .chain(wakers.iter().map(|w| w.fd()))
+        .map(|fd| pollfd {
+            fd,
+            events: POLLIN,
+            revents: 0,
+        })
+        .collect::<Vec<pollfd>>();
+    let ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+    let ready = unsafe { poll(fds.as_mut_ptr(), fds.len() as u64, ms) };
+    if ready < 0 {
+        let e = std::io::Error::last_os_error();
+        if e.kind() == std::io::ErrorKind::Interrupted {
+            return Ok(0);
+        }
+
+        return Err(e);
+    }
+    wakers
+        .iter()
+        .zip(&fds[1..])
+        .filter(|(_, fd)| fd.revents != 0)
+        .for_each(|(w, _)| w.drain());
+    if fds[0].revents == 0 {
+        return Ok(0);
+    }
+
+    let n = unsafe { read(fd, buf.as_mut_ptr(), buf.len()) };
+    if n < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(n as usize)
+}
+
+/// asks the console where the cursor is
+/// returns the 0 based (x, y) cell of the cursor inside the visible window
+#[cfg(windows)]
+pub fn query_cursor_position() -> std::io::Result<(u16, u16)> {
+    crate::console::windows::cursor_position()
+}
+
+// finds a '\x1b[row;colR' reply inside bytes
+// returns the reply's start index and length and the 0 based x and y
+fn parse_cpr(bytes: &[u8]) -> Option<(usize, usize, u16, u16)> {
+    let mut from = 0;
+    while let Some(idx) = bytes[from..]
+        .windows(2)
+        .position(|w| w == [27, 91])
+        .map(|i| i + from)
+    {
+        from = idx + 2;
+        let Some(end) = bytes[from..].iter().position(|b| !b.is_ascii_digit() && *b != b';') else {
+            return None;
+        };
+        if bytes[from + end] != b'R' {
+            continue;
+        }
+
+        let params = std::str::from_utf8(&bytes[from..from + end]).ok()?;
+        let Some((row, col)) = params.split_once(';') else {
+            continue;
+        };
+        let (Ok(row), Ok(col)) = (row.parse::<u16>(), col.parse::<u16>()) else {
+            continue;
+        };
+
+        return Some((idx, end + 3, col.saturating_sub(1), row.saturating_sub(1)));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod cpr {
+    use super::parse_cpr;
+
+    #[test]
+    fn reply() {
+        assert_eq!(parse_cpr(b"\x1b[12;40R"), Some((0, 8, 39, 11)));
+    }
+
+    #[test]
+    fn surrounded() {
+        // a key press before the reply and an arrow key right after it
+        let bytes = b"a\x1b[1;1R\x1b[A";
+        let (idx, len, x, y) = parse_cpr(bytes).unwrap();
+        assert_eq!([x, y], [0, 0]);
+        assert_eq!(&bytes[..idx], b"a");
+        assert_eq!(&bytes[idx + len..], b"\x1b[A");
+    }
+
+    #[test]
+    fn incomplete() {
+        assert_eq!(parse_cpr(b"\x1b[12;4"), None);
+        assert_eq!(parse_cpr(b"\x1b[A\x1b[5R"), None);
+    }
+}
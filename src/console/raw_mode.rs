@@ -2,6 +2,7 @@ use std::io::BufRead;
 
 use std::io::Read;
 use std::io::Write;
+use std::sync::Mutex;
 // from /usr/include/termios.h
 extern "C" {
     fn tcgetattr(__fd: i32, __termios_p: *mut termios) -> i32;
@@ -10,6 +11,7 @@ extern "C" {
 
 /// ffi bindings for the c struct found here 'from /usr/include/asm-generic/termbits.h'
 /// termios can be used to manipulate terminal flags
+#[cfg(any(target_os = "linux", target_os = "android"))]
 #[derive(Debug, Default, Clone)]
 #[repr(C)]
 pub struct termios {
@@ -18,12 +20,66 @@ pub struct termios {
     c_cflag: tcflag_t,
     c_lflag: tcflag_t,
     c_line: cc_t,
-    c_cc: cc_t,
+    c_cc: [cc_t; NCCS],
+    c_ispeed: speed_t,
+    c_ospeed: speed_t,
 }
 
-// from /usr/include/libr/sflib/common/sftypes.h
-type cc_t = [u8; 8];
+/// ffi bindings for the c struct found in '/usr/include/sys/termios.h' of macos and the bsds
+/// termios can be used to manipulate terminal flags
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+#[derive(Debug, Default, Clone)]
+#[repr(C)]
+pub struct termios {
+    c_iflag: tcflag_t,
+    c_oflag: tcflag_t,
+    c_cflag: tcflag_t,
+    c_lflag: tcflag_t,
+    c_cc: [cc_t; NCCS],
+    c_ispeed: speed_t,
+    c_ospeed: speed_t,
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd",
+)))]
+compile_error!("raw mode doesn't know the termios layout of this target");
+
+// from /usr/include/bits/termios-struct.h
+// tcgetattr writes the whole struct, so it has to be exactly as big as the c one
+type cc_t = u8;
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+type tcflag_t = u64;
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+type speed_t = u64;
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
 type tcflag_t = u32;
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+type speed_t = u32;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const NCCS: usize = 32;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const NCCS: usize = 20;
+
+// the sizes of the c structs
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const _: () = assert!(std::mem::size_of::<termios>() == 60);
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+const _: () = assert!(std::mem::size_of::<termios>() == 72);
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+)))]
+const _: () = assert!(std::mem::size_of::<termios>() == 44);
 
 // from /usr/include/libr/sflib/common/sftypes.h
 const TCSANOW: i32 = 0;
@@ -38,48 +94,84 @@ const STDERR_FILENO: i32 = 2;
 // It is recommended to read [this]('https://www.gnu.org/software/libc/manual/html_node/Input-Modes.html') before using the tcsetattr fn directly if you don't know what the various fileds of termios are and what their flags do
 // Otherwise, if you intend to only use tcgetattr or the abstract enable/disable_raw/rare_mode fns then no reading is required
 // flags definitions can be found in these header files: '/usr/include/bits/termios-c_*.h'
+// NOTE: the header files write the flags in octal
 // Recommended to read [this]('https://smnd.sk/anino/programming/c/unix_examples/raw.html') if you want to understand the reasoning behind the particular flag configuration of raw_mode
 
 // # [important]("https://www.gnu.org/software/libc/manual/html_node/Terminal-Modes.html")
-// # lflag bits
-// turning this flag off means input won't be displayed on the terminal anymore
-const ECHO: u32 = 10;
-// turning this flag off disables the INTR, QUIT and SUSP signals
-// basically, when this is off, stuff like ctrl-c (SIGINT) won't work
-// turning this bit off renders c_cc's special characters off
-const ISIG: u32 = 1;
-// canonical input mode gives meaning to escape sequences,
-// without it 'Backspace' would not delete the char behind cursor and 'Enter' will not submit input
-const ICANON: u32 = 2;
-//
-const IEXTEN: u32 = 100_000;
-
-// # iflag bits
-// disabling this disables signal interrupt on break, we don't want it in raw mode
-const BRKINT: u32 = 2;
-// when this is enabled input is checked for parity,
-// this bit is a pair with cflag's PARENB bit
-const INPCK: u32 = 20;
-// this strips the 8th bit off an input ascii char / byte
-const ISTRIP: u32 = 40;
-// maps LF to CR
-const INLCR: u32 = 100;
-// this bit maps CR to NL, meaning that it automatically prepends a cr (\r) on lf (\n)
-const ICRNL: u32 = 400;
-// machine ouput start/stop control
-// we'll turn this off, but keep its input counterpart
-const IXON: u32 = 2000;
-
-// # oflag bits
-// this bit enables output post processing, processes output before displaying it so that it is
-// rendered correctly on the terminal
-const OPOST: u32 = 1;
-
-// # cflag bits
-// specifies that a byte will be 8 bits
-const CS8: u32 = 60;
-// when this is enabled a parity bit is added to output values
-const PARENB: u32 = 400;
+// linux numbers the flags differently from macos and the bsds
+use flags::*;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod flags {
+    use super::tcflag_t;
+
+    // # lflag bits
+    // turning this flag off means input won't be displayed on the terminal anymore
+    pub(crate) const ECHO: tcflag_t = 0o10;
+    // turning this flag off disables the INTR, QUIT and SUSP signals
+    // basically, when this is off, stuff like ctrl-c (SIGINT) won't work
+    // turning this bit off renders c_cc's special characters off
+    pub(crate) const ISIG: tcflag_t = 0o1;
+    // canonical input mode gives meaning to escape sequences,
+    // without it 'Backspace' would not delete the char behind cursor and 'Enter' will not submit input
+    pub(crate) const ICANON: tcflag_t = 0o2;
+    //
+    pub(crate) const IEXTEN: tcflag_t = 0o100000;
+
+    // # iflag bits
+    // disabling this disables signal interrupt on break, we don't want it in raw mode
+    pub(crate) const BRKINT: tcflag_t = 0o2;
+    // when this is enabled input is checked for parity,
+    // this bit is a pair with cflag's PARENB bit
+    pub(crate) const INPCK: tcflag_t = 0o20;
+    // this strips the 8th bit off an input ascii char / byte
+    pub(crate) const ISTRIP: tcflag_t = 0o40;
+    // maps LF to CR
+    pub(crate) const INLCR: tcflag_t = 0o100;
+    // this bit maps CR to NL, meaning that it automatically prepends a cr (\r) on lf (\n)
+    pub(crate) const ICRNL: tcflag_t = 0o400;
+    // machine ouput start/stop control
+    // we'll turn this off, but keep its input counterpart
+    pub(crate) const IXON: tcflag_t = 0o2000;
+
+    // # oflag bits
+    // this bit enables output post processing, processes output before displaying it so that it is
+    // rendered correctly on the terminal
+    pub(crate) const OPOST: tcflag_t = 0o1;
+
+    // # cflag bits
+    // specifies that a byte will be 8 bits
+    pub(crate) const CS8: tcflag_t = 0o60;
+    // when this is enabled a parity bit is added to output values
+    pub(crate) const PARENB: tcflag_t = 0o400;
+}
+
+// the same flags as macos and the bsds number them, from '/usr/include/sys/termios.h'
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+mod flags {
+    use super::tcflag_t;
+
+    // # lflag bits
+    pub(crate) const ECHO: tcflag_t = 0x8;
+    pub(crate) const ISIG: tcflag_t = 0x80;
+    pub(crate) const ICANON: tcflag_t = 0x100;
+    pub(crate) const IEXTEN: tcflag_t = 0x400;
+
+    // # iflag bits
+    pub(crate) const BRKINT: tcflag_t = 0x2;
+    pub(crate) const INPCK: tcflag_t = 0x10;
+    pub(crate) const ISTRIP: tcflag_t = 0x20;
+    pub(crate) const INLCR: tcflag_t = 0x40;
+    pub(crate) const ICRNL: tcflag_t = 0x100;
+    pub(crate) const IXON: tcflag_t = 0x200;
+
+    // # oflag bits
+    pub(crate) const OPOST: tcflag_t = 0x1;
+
+    // # cflag bits
+    pub(crate) const CS8: tcflag_t = 0x300;
+    pub(crate) const PARENB: tcflag_t = 0x1000;
+}
 
 /// enables raw mode through disabling the relevant terminal flags - mainly ECHO and CANONICAL mode
 /// returns the original terminal flags in a termios instance for use when disabling raw mode
@@ -88,26 +180,525 @@ pub fn raw_mode() -> termios {
     unsafe {
         let mut original: termios = std::mem::zeroed();
         let _res = tcgetattr(STDIN_FILENO, &mut original);
-        let mut raw = original.clone();
-        raw.c_lflag &= !(ISIG | ICANON | ECHO | IEXTEN);
-        raw.c_iflag &= !(BRKINT | INPCK | ISTRIP | INLCR | ICRNL | IXON);
-        raw.c_oflag &= !OPOST;
-        raw.c_cflag &= !PARENB;
-        raw.c_cflag |= CS8;
-
-        let _res = tcsetattr(STDIN_FILENO, TCSANOW, &raw);
+        let _res = tcsetattr(STDIN_FILENO, TCSANOW, &make_raw(&original));
 
         original
     }
 }
 
+// returns a copy of the given flags with the raw mode flags set
+fn make_raw(original: &termios) -> termios {
+    let mut raw = original.clone();
+    raw.c_lflag &= !(ISIG | ICANON | ECHO | IEXTEN);
+    raw.c_iflag &= !(BRKINT | INPCK | ISTRIP | INLCR | ICRNL | IXON);
+    raw.c_oflag &= !OPOST;
+    raw.c_cflag &= !PARENB;
+    raw.c_cflag |= CS8;
+
+    raw
+}
+
 /// disables raw mode to original flags configuration
 /// takes the original flags from the 'original' termios instance returned from enable_raw_mode
 pub fn cooked_mode(original: termios) -> i32 {
     unsafe { tcsetattr(STDIN_FILENO, TCSANOW, &original) }
 }
 
+// the fds that currently have a live RawMode guard on them
+// holds the guard count and the flags to restore once the last guard is gone
+static GUARDED: Mutex<Vec<(i32, usize, termios)>> = Mutex::new(Vec::new());
+
+/// a guard that keeps the terminal in raw mode for as long as it lives
+/// the original terminal flags are restored when the guard is dropped
+/// even if the program is unwinding from a panic
+///
+/// guards can be nested, only the outermost guard of a fd saves and restores its flags
+#[derive(Debug)]
+pub struct RawMode {
+    fd: i32,
+    active: bool,
+    alternate_screen: bool,
+    hidden_cursor: bool,
+    mouse: bool,
+    focus_reports: bool,
+    saved_title: bool,
+    key_events: bool,
+    // the panic hook epoch this guard was made in
+    epoch: usize,
+}
+
+impl RawMode {
+    /// puts stdin in raw mode and returns the guard holding its original flags
+    pub fn enable() -> std::io::Result<Self> {
+        Self::with_fd(STDIN_FILENO)
+    }
+
+    /// puts the terminal behind fd in raw mode and returns the guard holding its original flags
+    /// fails if fd is not a terminal
+    pub fn with_fd(fd: i32) -> std::io::Result<Self> {
+        let mut guarded = GUARDED.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(entry) = guarded.iter_mut().find(|(gfd, ..)| *gfd == fd) {
+            entry.1 += 1;
+        } else {
+            let mut original: termios = unsafe { std::mem::zeroed() };
+            if unsafe { tcgetattr(fd, &mut original) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            if unsafe { tcsetattr(fd, TCSANOW, &make_raw(&original)) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            guarded.push((fd, 1, original));
+        }
+
+        Ok(Self {
+            fd,
+            active: true,
+            alternate_screen: false,
+            hidden_cursor: false,
+            mouse: false,
+            focus_reports: false,
+            saved_title: false,
+            key_events: false,
+            epoch: crate::console::panic::epoch(),
+        })
+    }
+
+    /// the fd this guard put in raw mode
+    pub fn fd(&self) -> i32 {
+        self.fd
+    }
+
+    /// moves to the alternate screen, the guard leaves it again when restoring
+    pub fn enter_alternate_screen(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(b"\x1b[?1049h\x1b[0;0f")?;
+        writer.flush()?;
+        self.alternate_screen = true;
+        crate::console::panic::mark(crate::console::panic::ALTERNATE_SCREEN);
+
+        Ok(())
+    }
+
+    /// hides the terminal cursor, the guard shows it again when restoring
+    pub fn hide_cursor(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(b"\x1b[?25l")?;
+        writer.flush()?;
+        self.hidden_cursor = true;
+        crate::console::panic::mark(crate::console::panic::HIDDEN_CURSOR);
+
+        Ok(())
+    }
+
+    /// turns on the sgr mouse reporting, the guard turns it off again when restoring
+    pub fn enable_mouse(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(crate::inputs::mouse::ENABLE_MOUSE)?;
+        writer.flush()?;
+        self.mouse = true;
+        crate::console::panic::mark(crate::console::panic::MOUSE);
+
+        Ok(())
+    }
+
+    /// turns on the terminal focus in/out reports, the guard turns them off again when restoring
+    pub fn enable_focus_reports(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(crate::inputs::window::ENABLE_FOCUS_REPORTS)?;
+        writer.flush()?;
+        self.focus_reports = true;
+        crate::console::panic::mark(crate::console::panic::FOCUS_REPORTS);
+
+        Ok(())
+    }
+
+    /// pushes the terminal window title on the terminal's title stack (XTWINOPS 22)
+    /// the guard pops it back (XTWINOPS 23) when restoring,
+    /// so that titles set in the meantime don't outlive the program
+    pub fn save_title(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(b"\x1b[22;0t")?;
+        writer.flush()?;
+        self.saved_title = true;
+        crate::console::panic::mark(crate::console::panic::SAVED_TITLE);
+
+        Ok(())
+    }
+
+    /// asks the terminal to report key releases and repeats and to encode modified keys,
+    /// like ctrl + shift + i, that otherwise can't be told apart,
+    /// through the kitty keyboard protocol or xterm's modifyOtherKeys, whichever it speaks
+    /// the guard turns them off again when restoring
+    pub fn enable_key_events(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(crate::inputs::keyboard::ENABLE_KEY_EVENTS)?;
+        writer.flush()?;
+        self.key_events = true;
+        crate::console::panic::mark(crate::console::panic::KEY_EVENTS);
+
+        Ok(())
+    }
+
+    // what was turned on through this guard, as panic hook feature bits
+    fn features(&self) -> u8 {
+        use crate::console::panic::*;
+
+        [
+            (self.key_events, KEY_EVENTS),
+            (self.mouse, MOUSE),
+            (self.focus_reports, FOCUS_REPORTS),
+            (self.saved_title, SAVED_TITLE),
+            (self.hidden_cursor, HIDDEN_CURSOR),
+            (self.alternate_screen, ALTERNATE_SCREEN),
+        ]
+        .into_iter()
+        .filter(|(on, _)| *on)
+        .fold(0, |bits, (_, bit)| bits | bit)
+    }
+
+    // the sequences that undo what was turned on through this guard
+    fn teardown(&self) -> Vec<u8> {
+        crate::console::panic::teardown(self.features())
+    }
+
+    // the sequences that turn back on what teardown undid
+    fn setup(&self) -> Vec<u8> {
+        let mut s = vec![];
+        if self.alternate_screen {
+            s.extend_from_slice(b"\x1b[?1049h\x1b[0;0f");
+        }
+        if self.hidden_cursor {
+            s.extend_from_slice(b"\x1b[?25l");
+        }
+        if self.saved_title {
+            s.extend_from_slice(b"\x1b[22;0t");
+        }
+        if self.focus_reports {
+            s.extend_from_slice(crate::inputs::window::ENABLE_FOCUS_REPORTS);
+        }
+        if self.mouse {
+            s.extend_from_slice(crate::inputs::mouse::ENABLE_MOUSE);
+        }
+        if self.key_events {
+            s.extend_from_slice(crate::inputs::keyboard::ENABLE_KEY_EVENTS);
+        }
+
+        s
+    }
+
+    /// gives the terminal back its original state for a while, e.g. before the program gets stopped
+    /// the guard stays alive, resume puts everything back the way it was
+    pub fn suspend(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(&self.teardown())?;
+        writer.flush()?;
+
+        let guarded = GUARDED.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, _, original)) = guarded.iter().find(|(gfd, ..)| *gfd == self.fd) {
+            if unsafe { tcsetattr(self.fd, TCSANOW, original) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// undoes suspend, raw mode and everything that was turned on through this guard come back
+    pub fn resume(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        let guarded = GUARDED.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, _, original)) = guarded.iter().find(|(gfd, ..)| *gfd == self.fd) {
+            if unsafe { tcsetattr(self.fd, TCSANOW, &make_raw(original)) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        drop(guarded);
+
+        writer.write_all(&self.setup())?;
+        writer.flush()
+    }
+
+    /// restores the terminal without waiting for the guard to be dropped
+    /// calling this more than once does nothing
+    pub fn restore(&mut self) -> std::io::Result<()> {
+        self.restore_to(&mut std::io::stdout())
+    }
+
+    fn restore_to(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        if !self.active {
+            return Ok(());
+        }
+        self.active = false;
+        // the panic hook already restored everything
+        if self.epoch != crate::console::panic::epoch() {
+            return Ok(());
+        }
+
+        let teardown = self.teardown();
+        // a failed write doesn't keep the terminal raw, the error is returned once it is restored
+        let written = match teardown.is_empty() {
+            true => Ok(()),
+            false => writer.write_all(&teardown).and_then(|_| writer.flush()),
+        };
+
+        let mut guarded = GUARDED.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(idx) = guarded.iter().position(|(gfd, ..)| *gfd == self.fd) else {
+            return written;
+        };
+        guarded[idx].1 -= 1;
+        if guarded[idx].1 > 0 {
+            return written;
+        }
+
+        let (_, _, original) = guarded.remove(idx);
+        if guarded.is_empty() {
+            crate::console::panic::unmark_all();
+        }
+        if unsafe { tcsetattr(self.fd, TCSANOW, &original) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        written
+    }
+}
+
+// gives every guarded fd its original flags back and forgets about them
+// used by the panic hook, the guards themselves are left alone
+pub(crate) fn restore_all() {
+    let mut guarded = GUARDED.lock().unwrap_or_else(|e| e.into_inner());
+    guarded.drain(..).for_each(|(fd, _, original)| unsafe {
+        tcsetattr(fd, TCSANOW, &original);
+    });
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        _ = self.restore();
+    }
+}
+
 /// enables rare mode
 /// a terminal mode that is between cooked and raw
 /// in terms of features and functionality
 fn rare_mode() {}
+
+#[cfg(test)]
+mod guard {
+    use super::*;
+
+    extern "C" {
+        fn posix_openpt(flags: i32) -> i32;
+        fn grantpt(fd: i32) -> i32;
+        fn unlockpt(fd: i32) -> i32;
+        fn ptsname(fd: i32) -> *const std::ffi::c_char;
+        fn open(path: *const std::ffi::c_char, flags: i32, ...) -> i32;
+        fn close(fd: i32) -> i32;
+    }
+
+    const O_RDWR: i32 = 0o2;
+    const O_NOCTTY: i32 = 0o400;
+
+    // the panic hook test restores every guard, the other tests wait for it
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    fn serial() -> std::sync::MutexGuard<'static, ()> {
+        SERIAL.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // a pseudo terminal pair standing in for the real terminal
+    // returns the master and slave fds
+    fn pty() -> (i32, i32) {
+        unsafe {
+            let master = posix_openpt(O_RDWR | O_NOCTTY);
+            assert!(master >= 0);
+            assert_eq!(grantpt(master), 0);
+            assert_eq!(unlockpt(master), 0);
+            let slave = open(ptsname(master), O_RDWR | O_NOCTTY);
+            assert!(slave >= 0);
+
+            (master, slave)
+        }
+    }
+
+    fn lflag(fd: i32) -> tcflag_t {
+        let mut t: termios = unsafe { std::mem::zeroed() };
+        assert_eq!(unsafe { tcgetattr(fd, &mut t) }, 0);
+
+        t.c_lflag
+    }
+
+    #[test]
+    fn restores_on_drop() {
+        let _serial = serial();
+        let (master, slave) = pty();
+        let original = lflag(slave);
+        assert_ne!(original & ECHO, 0);
+
+        let guard = RawMode::with_fd(slave).unwrap();
+        assert_eq!(lflag(slave) & (ECHO | ICANON), 0);
+        drop(guard);
+        assert_eq!(lflag(slave), original);
+
+        unsafe {
+            close(slave);
+            close(master);
+        }
+    }
+
+    #[test]
+    fn nested() {
+        let _serial = serial();
+        let (master, slave) = pty();
+        let original = lflag(slave);
+
+        let outer = RawMode::with_fd(slave).unwrap();
+        let mut inner = RawMode::with_fd(slave).unwrap();
+        inner.restore().unwrap();
+        inner.restore().unwrap();
+        // the outer guard is still alive
+        assert_eq!(lflag(slave) & ECHO, 0);
+        drop(inner);
+        assert_eq!(lflag(slave) & ECHO, 0);
+        drop(outer);
+        assert_eq!(lflag(slave), original);
+
+        unsafe {
+            close(slave);
+            close(master);
+        }
+    }
+
+    #[test]
+    fn screen_and_cursor() {
+        let _serial = serial();
+        let (master, slave) = pty();
+        let mut guard = RawMode::with_fd(slave).unwrap();
+        let mut out = vec![];
+        guard.enter_alternate_screen(&mut out).unwrap();
+        guard.hide_cursor(&mut out).unwrap();
+        out.clear();
+        guard.restore_to(&mut out).unwrap();
+        assert_eq!(out, b"\x1b[?25h\x1b[?1049l");
+
+        // nothing is written twice
+        out.clear();
+        guard.restore_to(&mut out).unwrap();
+        assert!(out.is_empty());
+
+        unsafe {
+            close(slave);
+            close(master);
+        }
+    }
+
+    #[test]
+    fn key_events() {
+        let _serial = serial();
+        let (master, slave) = pty();
+        let mut guard = RawMode::with_fd(slave).unwrap();
+        let mut out = vec![];
+        guard.enter_alternate_screen(&mut out).unwrap();
+        guard.enable_key_events(&mut out).unwrap();
+        assert!(out.ends_with(b"\x1b[>3u\x1b[>4;2m"));
+        out.clear();
+        // the keyboard modes are popped while still on the alternate screen
+        guard.restore_to(&mut out).unwrap();
+        assert_eq!(out, b"\x1b[<u\x1b[>4m\x1b[?25h\x1b[?1049l");
+
+        unsafe {
+            close(slave);
+            close(master);
+        }
+    }
+
+    // a writer that can't be written to, like a closed terminal
+    struct Closed;
+
+    impl Write for Closed {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn failed_write() {
+        let _serial = serial();
+        let (master, slave) = pty();
+        let original = lflag(slave);
+
+        let mut guard = RawMode::with_fd(slave).unwrap();
+        guard.hide_cursor(&mut vec![]).unwrap();
+        let err = guard.restore_to(&mut Closed).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+        // the terminal is restored all the same
+        assert_eq!(lflag(slave), original);
+        assert!(GUARDED.lock().unwrap().iter().all(|(fd, ..)| *fd != slave));
+
+        unsafe {
+            close(slave);
+            close(master);
+        }
+    }
+
+    #[test]
+    fn suspend_resume() {
+        let _serial = serial();
+        let (master, slave) = pty();
+        let original = lflag(slave);
+
+        let mut guard = RawMode::with_fd(slave).unwrap();
+        guard.alternate_screen = true;
+        let mut out = vec![];
+        guard.suspend(&mut out).unwrap();
+        assert_eq!(lflag(slave), original);
+        assert_eq!(out, b"\x1b[?25h\x1b[?1049l");
+
+        out.clear();
+        guard.resume(&mut out).unwrap();
+        assert_eq!(lflag(slave) & ECHO, 0);
+        assert_eq!(out, b"\x1b[?1049h\x1b[0;0f");
+
+        // keeps the drop from writing to the test's stdout
+        guard.alternate_screen = false;
+        drop(guard);
+        assert_eq!(lflag(slave), original);
+        unsafe {
+            close(slave);
+            close(master);
+        }
+    }
+
+    #[test]
+    fn panic_hook() {
+        let _serial = serial();
+        let (master, slave) = pty();
+        let original = lflag(slave);
+
+        let mut guard = RawMode::with_fd(slave).unwrap();
+        let mut out = vec![];
+        guard.enable_mouse(&mut out).unwrap();
+        guard.hide_cursor(&mut out).unwrap();
+        out.clear();
+        crate::console::panic::restore(&mut out);
+        assert_eq!(lflag(slave), original);
+        assert_eq!(out, b"\x1b[?1006l\x1b[?1002l\x1b[?25h");
+
+        // the guard leaves the terminal alone once the hook restored it
+        out.clear();
+        guard.restore_to(&mut out).unwrap();
+        assert!(out.is_empty());
+        assert!(GUARDED.lock().unwrap().iter().all(|(fd, ..)| *fd != slave));
+
+        // guards made after the hook ran restore as usual
+        let guard = RawMode::with_fd(slave).unwrap();
+        assert_eq!(lflag(slave) & ECHO, 0);
+        drop(guard);
+        assert_eq!(lflag(slave), original);
+
+        unsafe {
+            close(slave);
+            close(master);
+        }
+    }
+
+    #[test]
+    fn not_a_terminal() {
+        assert!(RawMode::with_fd(-1).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU8, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+use super::wake::Waker;
+use crate::events::Signal;
+
+// from /usr/include/signal.h
+extern "C" {
+    fn signal(signum: i32, handler: usize) -> usize;
+    fn raise(sig: i32) -> i32;
+    fn write(fd: i32, buf: *const u8, count: usize) -> isize;
+}
+
+// from /usr/include/bits/signum-arch.h on linux and /usr/include/sys/signal.h on macos and the bsds
+const SIGHUP: i32 = 1;
+const SIGINT: i32 = 2;
+const SIGTERM: i32 = 15;
+const SIGWINCH: i32 = 28;
+// the job control signals are numbered differently on each side
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SIGCONT: i32 = 18;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SIGSTOP: i32 = 19;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SIGTSTP: i32 = 20;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const SIGCONT: i32 = 19;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const SIGSTOP: i32 = 17;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const SIGTSTP: i32 = 18;
+// SIG_ERR and SIG_DFL
+const SIG_ERR: usize = usize::MAX;
+const SIG_DFL: usize = 0;
+
+// set by the SIGTSTP handler, handlers may only touch atomics
+static TSTP: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_tstp(_: i32) {
+    TSTP.store(true, Ordering::SeqCst);
+}
+
+// the pipe the SIGWINCH, SIGINT, SIGTERM and SIGHUP handlers write to, to wake up a waiting poll
+static SIGNAL_FD: AtomicI32 = AtomicI32::new(-1);
+static SIGNAL_WAKER: OnceLock<Option<Waker>> = OnceLock::new();
+
+fn wake() {
+    let fd = SIGNAL_FD.load(Ordering::SeqCst);
+    if fd >= 0 {
+        // write is async signal safe, the pipe doesn't block when full
+        _ = unsafe { write(fd, [1u8].as_ptr(), 1) };
+    }
+}
+
+// the waker every caught signal wakes, made on the first call
+fn signal_waker() -> std::io::Result<&'static Waker> {
+    let waker = SIGNAL_WAKER.get_or_init(|| {
+        let waker = Waker::new().ok();
+        if let Some(waker) = &waker {
+            SIGNAL_FD.store(waker.write_fd(), Ordering::SeqCst);
+        }
+
+        waker
+    });
+
+    waker
+        .as_ref()
+        .ok_or_else(|| std::io::Error::other("the signal wake up pipe could not be made"))
+}
+
+// set by the SIGWINCH handler
+static WINCH: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_winch(_: i32) {
+    WINCH.store(true, Ordering::SeqCst);
+    wake();
+}
+
+// catches SIGWINCH, take_winch tells whether the window got resized since
+// the returned waker is woken by every SIGWINCH, so that a poll waiting on input returns right away
+// the handler does nothing else, so a resize never lands in the middle of a frame's output
+pub(crate) fn catch_winch() -> std::io::Result<&'static Waker> {
+    let waker = signal_waker()?;
+    if unsafe { signal(SIGWINCH, on_winch as usize) } == SIG_ERR {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(waker)
+}
+
+/// whether a SIGWINCH was caught since the last call
+/// any number of resizes in between count as one
+pub fn take_winch() -> bool {
+    WINCH.swap(false, Ordering::SeqCst)
+}
+
+// the signals that asked the program to end and were not taken yet, one bit each
+static EXIT: AtomicU8 = AtomicU8::new(0);
+
+fn number(signal: Signal) -> i32 {
+    match signal {
+        Signal::Interrupt => SIGINT,
+        Signal::Term => SIGTERM,
+        Signal::Hup => SIGHUP,
+    }
+}
+
+fn bit(sig: i32) -> u8 {
+    match sig {
+        SIGINT => 1,
+        SIGTERM => 2,
+        SIGHUP => 4,
+        _ => 0,
+    }
+}
+
+extern "C" fn on_exit(sig: i32) {
+    EXIT.fetch_or(bit(sig), Ordering::SeqCst);
+    wake();
+}
+
+// the handlers SIGINT, SIGTERM and SIGHUP had before catch_exit, in that order
+static PREVIOUS: [AtomicUsize; 3] = [const { AtomicUsize::new(SIG_DFL) }; 3];
+
+// catches SIGINT, SIGTERM and SIGHUP, take_signal tells which came in since
+// the returned waker is woken by each of them, like the one catch_winch returns
+pub(crate) fn catch_exit() -> std::io::Result<&'static Waker> {
+    let waker = signal_waker()?;
+    for (sig, previous) in [SIGINT, SIGTERM, SIGHUP].into_iter().zip(&PREVIOUS) {
+        let handler = unsafe { signal(sig, on_exit as usize) };
+        if handler == SIG_ERR {
+            return Err(std::io::Error::last_os_error());
+        }
+        // caught twice, the handler from before the first catch stays the one to give back
+        if handler != on_exit as usize {
+            previous.store(handler, Ordering::SeqCst);
+        }
+    }
+
+    Ok(waker)
+}
+
+// gives SIGINT, SIGTERM and SIGHUP the handlers they had before catch_exit back
+pub(crate) fn release_exit() {
+    for (sig, previous) in [SIGINT, SIGTERM, SIGHUP].into_iter().zip(&PREVIOUS) {
+        unsafe { signal(sig, previous.swap(SIG_DFL, Ordering::SeqCst)) };
+    }
+    EXIT.store(0, Ordering::SeqCst);
+}
+
+/// the next caught SIGTERM, SIGHUP or SIGINT that was not taken yet, in that order
+/// the same signal coming in many times before it is taken counts as one
+pub fn take_signal() -> Option<Signal> {
+    [Signal::Term, Signal::Hup, Signal::Interrupt]
+        .into_iter()
+        .find(|signal| {
+            let bit = bit(number(*signal));
+            EXIT.fetch_and(!bit, Ordering::SeqCst) & bit != 0
+        })
+}
+
+/// ends the program the way signal would have, had it not been caught
+/// the terminal has to be restored before this is called, nothing gets dropped on the way out
+pub fn die(caught: Signal) -> ! {
+    let sig = number(caught);
+    unsafe {
+        signal(sig, SIG_DFL);
+        raise(sig);
+    }
+
+    // the signal is blocked somehow
+    std::process::exit(128 + sig)
+}
+
+/// catches SIGTSTP instead of letting it stop the program right away
+/// with the terminal still in raw mode
+/// take_tstp tells whether one came in since
+pub fn catch_tstp() -> std::io::Result<()> {
+    if unsafe { signal(SIGTSTP, on_tstp as usize) } == SIG_ERR {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+/// whether a SIGTSTP was caught since the last call
+pub fn take_tstp() -> bool {
+    TSTP.swap(false, Ordering::SeqCst)
+}
+
+/// stops the program, like a SIGTSTP would have had it not been caught
+/// returns once the program is continued, e.g. by the shell's fg
+pub fn stop() -> std::io::Result<()> {
+    if unsafe { raise(SIGSTOP) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod caught {
+    use super::*;
+
+    #[test]
+    fn coalesced() {
+        catch_winch().unwrap();
+        take_winch();
+        unsafe {
+            raise(SIGWINCH);
+            raise(SIGWINCH);
+        }
+        assert!(take_winch());
+        assert!(!take_winch());
+    }
+
+    // the exit signals are caught and released by more than one test
+    static EXIT_TESTS: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn exit_signals() {
+        let _serial = EXIT_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+        catch_exit().unwrap();
+        while take_signal().is_some() {}
+        unsafe {
+            raise(SIGHUP);
+            raise(SIGINT);
+            raise(SIGTERM);
+            raise(SIGTERM);
+        }
+        assert_eq!(take_signal(), Some(Signal::Term));
+        assert_eq!(take_signal(), Some(Signal::Hup));
+        assert_eq!(take_signal(), Some(Signal::Interrupt));
+        assert_eq!(take_signal(), None);
+    }
+
+    extern "C" fn on_hup(_: i32) {}
+
+    #[test]
+    fn released() {
+        let _serial = EXIT_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+        catch_exit().unwrap();
+        release_exit();
+        // the handler in place is the default one
+        assert_eq!(unsafe { signal(SIGHUP, SIG_DFL) }, SIG_DFL);
+        assert_eq!(unsafe { signal(SIGTERM, SIG_DFL) }, SIG_DFL);
+
+        // the app's own handler comes back, even after catching twice
+        unsafe { signal(SIGHUP, on_hup as usize) };
+        catch_exit().unwrap();
+        catch_exit().unwrap();
+        release_exit();
+        assert_eq!(unsafe { signal(SIGHUP, SIG_DFL) }, on_hup as usize);
+        assert_eq!(unsafe { signal(SIGINT, SIG_DFL) }, SIG_DFL);
+    }
+}
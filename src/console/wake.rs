@@ -0,0 +1,146 @@
+// from /usr/include/unistd.h and /usr/include/fcntl.h
+#[cfg(unix)]
+extern "C" {
+    fn pipe(fds: *mut i32) -> i32;
+    fn read(fd: i32, buf: *mut u8, count: usize) -> isize;
+    fn write(fd: i32, buf: *const u8, count: usize) -> isize;
+    fn close(fd: i32) -> i32;
+    fn fcntl(fd: i32, cmd: i32, ...) -> i32;
+}
+
+#[cfg(unix)]
+const F_GETFL: i32 = 3;
+#[cfg(unix)]
+const F_SETFL: i32 = 4;
+#[cfg(all(unix, target_os = "linux"))]
+const O_NONBLOCK: i32 = 0o4000;
+#[cfg(all(unix, not(target_os = "linux")))]
+const O_NONBLOCK: i32 = 0x0004;
+
+/// wakes a thread that is parked waiting for input, from any other thread
+/// a self-pipe on unix, an auto reset event object on windows
+#[derive(Debug)]
+pub(crate) struct Waker {
+    #[cfg(unix)]
+    read: i32,
+    #[cfg(unix)]
+    write: i32,
+    // the event handle, kept as an integer so that the waker can be shared between threads
+    #[cfg(windows)]
+    event: usize,
+}
+
+#[cfg(unix)]
+impl Waker {
+    pub(crate) fn new() -> std::io::Result<Self> {
+        let mut fds = [0i32; 2];
+        if unsafe { pipe(fds.as_mut_ptr()) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let waker = Self {
+            read: fds[0],
+            write: fds[1],
+        };
+        // a full pipe already wakes the reader, so writes never have to wait for room
+        for fd in fds {
+            let flags = unsafe { fcntl(fd, F_GETFL) };
+            if flags < 0 || unsafe { fcntl(fd, F_SETFL, flags | O_NONBLOCK) } < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+
+        Ok(waker)
+    }
+
+    // the end of the pipe to poll for reading
+    pub(crate) fn fd(&self) -> i32 {
+        self.read
+    }
+
+    // the end of the pipe that wake writes to, a signal handler may write to it too
+    pub(crate) fn write_fd(&self) -> i32 {
+        self.write
+    }
+
+    pub(crate) fn wake(&self) {
+        _ = unsafe { write(self.write, [1u8].as_ptr(), 1) };
+    }
+
+    // empties the pipe, so that the next poll waits again
+    pub(crate) fn drain(&self) {
+        let mut buf = [0u8; 64];
+        while unsafe { read(self.read, buf.as_mut_ptr(), buf.len()) } > 0 {}
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Waker {
+    fn drop(&mut self) {
+        unsafe {
+            close(self.read);
+            close(self.write);
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Waker {
+    pub(crate) fn new() -> std::io::Result<Self> {
+        Ok(Self {
+            event: crate::console::windows::create_event()? as usize,
+        })
+    }
+
+    pub(crate) fn handle(&self) -> *mut std::ffi::c_void {
+        self.event as *mut std::ffi::c_void
+    }
+
+    pub(crate) fn wake(&self) {
+        crate::console::windows::set_event(self.handle());
+    }
+
+    // the event resets itself once a wait sees it
+    pub(crate) fn drain(&self) {}
+}
+
+#[cfg(windows)]
+impl Drop for Waker {
+    fn drop(&mut self) {
+        crate::console::windows::close_handle(self.handle());
+    }
+}
+
+#[cfg(all(test, unix))]
+mod waker {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn wakes_poll() {
+        let waker = std::sync::Arc::new(Waker::new().unwrap());
+        let remote = waker.clone();
+        let start = Instant::now();
+        let thread = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            remote.wake();
+        });
+
+        let mut buf = [0u8; 8];
+        let n = crate::console::query::read_fd_timeout(
+            -1,
+            &mut buf,
+            Duration::from_secs(5),
+            &[&waker],
+        )
+        .unwrap();
+        assert_eq!(n, 0);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        thread.join().unwrap();
+
+        // many wakes never block, even once the pipe is full
+        for _ in 0..100_000 {
+            waker.wake();
+        }
+        waker.drain();
+    }
+}
@@ -0,0 +1,182 @@
+/// console mode utilities, the windows counterpart of the termios raw mode
+pub mod raw_mode;
+/// console screen buffer info, use for getting the console window width and height
+pub mod winsize;
+
+use std::ffi::c_void;
+
+// from consoleapi.h, processenv.h and wincon.h
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetStdHandle(nStdHandle: u32) -> *mut c_void;
+    fn GetConsoleMode(hConsoleHandle: *mut c_void, lpMode: *mut u32) -> i32;
+    fn SetConsoleMode(hConsoleHandle: *mut c_void, dwMode: u32) -> i32;
+    fn GetConsoleScreenBufferInfo(
+        hConsoleOutput: *mut c_void,
+        lpConsoleScreenBufferInfo: *mut CONSOLE_SCREEN_BUFFER_INFO,
+    ) -> i32;
+    fn WaitForMultipleObjects(
+        nCount: u32,
+        lpHandles: *const *mut c_void,
+        bWaitAll: i32,
+        dwMilliseconds: u32,
+    ) -> u32;
+    fn CreateEventW(
+        lpEventAttributes: *mut c_void,
+        bManualReset: i32,
+        bInitialState: i32,
+        lpName: *const u16,
+    ) -> *mut c_void;
+    fn SetEvent(hEvent: *mut c_void) -> i32;
+    fn CloseHandle(hObject: *mut c_void) -> i32;
+    fn ReadConsoleInputW(
+        hConsoleInput: *mut c_void,
+        lpBuffer: *mut INPUT_RECORD,
+        nLength: u32,
+        lpNumberOfEventsRead: *mut u32,
+    ) -> i32;
+}
+
+// from winbase.h, (DWORD)-10, -11 and -12
+const STD_INPUT_HANDLE: u32 = 0xFFFF_FFF6;
+const STD_OUTPUT_HANDLE: u32 = 0xFFFF_FFF5;
+const STD_ERROR_HANDLE: u32 = 0xFFFF_FFF4;
+
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C)]
+struct COORD {
+    x: i16,
+    y: i16,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C)]
+struct SMALL_RECT {
+    left: i16,
+    top: i16,
+    right: i16,
+    bottom: i16,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C)]
+struct CONSOLE_SCREEN_BUFFER_INFO {
+    size: COORD,
+    cursor_position: COORD,
+    attributes: u16,
+    window: SMALL_RECT,
+    maximum_window_size: COORD,
+}
+
+// the event member of the c struct is a union of all the event records
+// only key events are read, the key record is as big as the biggest union member
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C)]
+struct INPUT_RECORD {
+    event_type: u16,
+    event: KEY_EVENT_RECORD,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C)]
+struct KEY_EVENT_RECORD {
+    key_down: i32,
+    repeat_count: u16,
+    virtual_key_code: u16,
+    virtual_scan_code: u16,
+    // union of a WCHAR and a CHAR, the W functions fill the WCHAR
+    uchar: u16,
+    control_key_state: u32,
+}
+
+const KEY_EVENT: u16 = 0x0001;
+
+// from synchapi.h and winbase.h
+const WAIT_OBJECT_0: u32 = 0;
+const WAIT_TIMEOUT: u32 = 0x102;
+
+/// whether stdout is a console
+pub(crate) fn stdout_is_console() -> bool {
+    let mut mode = 0;
+
+    unsafe { GetConsoleMode(GetStdHandle(STD_OUTPUT_HANDLE), &mut mode) != 0 }
+}
+
+/// returns the 0 based (x, y) cell of the cursor inside the visible window of stdout's screen buffer
+pub(crate) fn cursor_position() -> std::io::Result<(u16, u16)> {
+    let mut info = CONSOLE_SCREEN_BUFFER_INFO::default();
+    if unsafe { GetConsoleScreenBufferInfo(GetStdHandle(STD_OUTPUT_HANDLE), &mut info) } == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok((
+        (info.cursor_position.x - info.window.left).max(0) as u16,
+        (info.cursor_position.y - info.window.top).max(0) as u16,
+    ))
+}
+
+// what ended a wait_input
+#[derive(Debug, PartialEq)]
+pub(crate) enum Wait {
+    Input,
+    Woken,
+    Timeout,
+}
+
+/// waits up to timeout for the console input to have some record, or for the waker to be woken
+/// records that are not key presses count too, so read_key may still block afterwards
+pub(crate) fn wait_input(
+    timeout: std::time::Duration,
+    waker: Option<&crate::console::wake::Waker>,
+) -> std::io::Result<Wait> {
+    let handles = [
+        unsafe { GetStdHandle(STD_INPUT_HANDLE) },
+        waker.map_or(std::ptr::null_mut(), |w| w.handle()),
+    ];
+    let count = if waker.is_some() { 2 } else { 1 };
+    let ms = timeout.as_millis().min(u32::MAX as u128 - 1) as u32;
+
+    match unsafe { WaitForMultipleObjects(count, handles.as_ptr(), 0, ms) } {
+        WAIT_OBJECT_0 => Ok(Wait::Input),
+        WAIT_TIMEOUT => Ok(Wait::Timeout),
+        n if n == WAIT_OBJECT_0 + 1 => Ok(Wait::Woken),
+        _ => Err(std::io::Error::last_os_error()),
+    }
+}
+
+// an auto reset event object, for the Waker
+pub(crate) fn create_event() -> std::io::Result<*mut c_void> {
+    let event = unsafe { CreateEventW(std::ptr::null_mut(), 0, 0, std::ptr::null()) };
+    if event.is_null() {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(event)
+}
+
+pub(crate) fn set_event(event: *mut c_void) {
+    unsafe { SetEvent(event) };
+}
+
+pub(crate) fn close_handle(handle: *mut c_void) {
+    unsafe { CloseHandle(handle) };
+}
+
+/// reads console input records until a key press comes in
+/// returns the key's virtual key code, its utf-16 char value and the control key state
+pub(crate) fn read_key() -> std::io::Result<(u16, u16, u32)> {
+    let handle = unsafe { GetStdHandle(STD_INPUT_HANDLE) };
+    let mut record = INPUT_RECORD::default();
+    let mut read = 0;
+
+    loop {
+        if unsafe { ReadConsoleInputW(handle, &mut record, 1, &mut read) } == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        // key releases, mouse, focus, menu and buffer size events are skipped
+        if read == 1 && record.event_type == KEY_EVENT && record.event.key_down != 0 {
+            let key = record.event;
+            return Ok((key.virtual_key_code, key.uchar, key.control_key_state));
+        }
+    }
+}
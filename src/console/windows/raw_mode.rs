@@ -0,0 +1,311 @@
+use std::io::Write;
+use std::sync::Mutex;
+
+use super::{GetConsoleMode, GetStdHandle, SetConsoleMode, STD_INPUT_HANDLE, STD_OUTPUT_HANDLE};
+
+/// the console modes of the input and output handles
+/// named after its unix counterpart so that both platforms share the same raw mode api
+#[derive(Debug, Default, Clone)]
+pub struct termios {
+    input: u32,
+    output: u32,
+}
+
+// input mode flags, from wincon.h
+// CTRL+C and the like are delivered as key events instead of being handled by the system
+const ENABLE_PROCESSED_INPUT: u32 = 0x0001;
+// ReadFile/ReadConsole wait for a carriage return
+const ENABLE_LINE_INPUT: u32 = 0x0002;
+// typed chars are echoed back
+const ENABLE_ECHO_INPUT: u32 = 0x0004;
+
+// output mode flags, from wincon.h
+// the console parses the vt100 escape sequences the render pipeline writes
+const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+// reads the current console modes
+fn modes() -> std::io::Result<termios> {
+    let mut original = termios::default();
+    unsafe {
+        if GetConsoleMode(GetStdHandle(STD_INPUT_HANDLE), &mut original.input) == 0
+            || GetConsoleMode(GetStdHandle(STD_OUTPUT_HANDLE), &mut original.output) == 0
+        {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+
+    Ok(original)
+}
+
+fn set_modes(modes: &termios) -> std::io::Result<()> {
+    unsafe {
+        if SetConsoleMode(GetStdHandle(STD_INPUT_HANDLE), modes.input) == 0
+            || SetConsoleMode(GetStdHandle(STD_OUTPUT_HANDLE), modes.output) == 0
+        {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+
+    Ok(())
+}
+
+// returns a copy of the given modes with the raw mode flags set
+fn make_raw(original: &termios) -> termios {
+    termios {
+        input: original.input & !(ENABLE_PROCESSED_INPUT | ENABLE_LINE_INPUT | ENABLE_ECHO_INPUT),
+        output: original.output | ENABLE_VIRTUAL_TERMINAL_PROCESSING,
+    }
+}
+
+/// enables raw mode through disabling line input, echo and input processing
+/// and enables the virtual terminal processing of the output
+/// returns the original console modes in a termios instance for use when disabling raw mode
+pub fn raw_mode() -> termios {
+    let original = modes().unwrap_or_default();
+    _ = set_modes(&make_raw(&original));
+
+    original
+}
+
+/// disables raw mode to original console modes
+/// takes the original modes from the 'original' termios instance returned from raw_mode
+pub fn cooked_mode(original: termios) -> i32 {
+    if set_modes(&original).is_ok() {
+        0
+    } else {
+        -1
+    }
+}
+
+// the live RawMode guards count and the modes to restore once the last guard is gone
+static GUARDED: Mutex<Option<(usize, termios)>> = Mutex::new(None);
+
+/// a guard that keeps the console in raw mode for as long as it lives
+/// the original console modes are restored when the guard is dropped
+/// even if the program is unwinding from a panic
+///
+/// guards can be nested, only the outermost guard saves and restores the modes
+#[derive(Debug)]
+pub struct RawMode {
+    active: bool,
+    alternate_screen: bool,
+    hidden_cursor: bool,
+    mouse: bool,
+    focus_reports: bool,
+    saved_title: bool,
+    key_events: bool,
+    // the panic hook epoch this guard was made in
+    epoch: usize,
+}
+
+impl RawMode {
+    /// puts the console in raw mode and returns the guard holding its original modes
+    /// fails if stdin or stdout is not a console
+    pub fn enable() -> std::io::Result<Self> {
+        let mut guarded = GUARDED.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((count, _)) = guarded.as_mut() {
+            *count += 1;
+        } else {
+            let original = modes()?;
+            set_modes(&make_raw(&original))?;
+            *guarded = Some((1, original));
+        }
+
+        Ok(Self {
+            active: true,
+            alternate_screen: false,
+            hidden_cursor: false,
+            mouse: false,
+            focus_reports: false,
+            saved_title: false,
+            key_events: false,
+            epoch: crate::console::panic::epoch(),
+        })
+    }
+
+    /// moves to the alternate screen, the guard leaves it again when restoring
+    pub fn enter_alternate_screen(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(b"\x1b[?1049h\x1b[0;0f")?;
+        writer.flush()?;
+        self.alternate_screen = true;
+        crate::console::panic::mark(crate::console::panic::ALTERNATE_SCREEN);
+
+        Ok(())
+    }
+
+    /// hides the console cursor, the guard shows it again when restoring
+    pub fn hide_cursor(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(b"\x1b[?25l")?;
+        writer.flush()?;
+        self.hidden_cursor = true;
+        crate::console::panic::mark(crate::console::panic::HIDDEN_CURSOR);
+
+        Ok(())
+    }
+
+    /// turns on the sgr mouse reporting, the guard turns it off again when restoring
+    pub fn enable_mouse(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(crate::inputs::mouse::ENABLE_MOUSE)?;
+        writer.flush()?;
+        self.mouse = true;
+        crate::console::panic::mark(crate::console::panic::MOUSE);
+
+        Ok(())
+    }
+
+    /// turns on the terminal focus in/out reports, the guard turns them off again when restoring
+    pub fn enable_focus_reports(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(crate::inputs::window::ENABLE_FOCUS_REPORTS)?;
+        writer.flush()?;
+        self.focus_reports = true;
+        crate::console::panic::mark(crate::console::panic::FOCUS_REPORTS);
+
+        Ok(())
+    }
+
+    /// pushes the terminal window title on the terminal's title stack (XTWINOPS 22)
+    /// the guard pops it back (XTWINOPS 23) when restoring,
+    /// so that titles set in the meantime don't outlive the program
+    pub fn save_title(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(b"\x1b[22;0t")?;
+        writer.flush()?;
+        self.saved_title = true;
+        crate::console::panic::mark(crate::console::panic::SAVED_TITLE);
+
+        Ok(())
+    }
+
+    /// asks the terminal to report key releases and repeats and to encode modified keys,
+    /// like ctrl + shift + i, that otherwise can't be told apart,
+    /// through the kitty keyboard protocol or xterm's modifyOtherKeys, whichever it speaks
+    /// the guard turns them off again when restoring
+    pub fn enable_key_events(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(crate::inputs::keyboard::ENABLE_KEY_EVENTS)?;
+        writer.flush()?;
+        self.key_events = true;
+        crate::console::panic::mark(crate::console::panic::KEY_EVENTS);
+
+        Ok(())
+    }
+
+    // what was turned on through this guard, as panic hook feature bits
+    fn features(&self) -> u8 {
+        use crate::console::panic::*;
+
+        [
+            (self.key_events, KEY_EVENTS),
+            (self.mouse, MOUSE),
+            (self.focus_reports, FOCUS_REPORTS),
+            (self.saved_title, SAVED_TITLE),
+            (self.hidden_cursor, HIDDEN_CURSOR),
+            (self.alternate_screen, ALTERNATE_SCREEN),
+        ]
+        .into_iter()
+        .filter(|(on, _)| *on)
+        .fold(0, |bits, (_, bit)| bits | bit)
+    }
+
+    // the sequences that undo what was turned on through this guard
+    fn teardown(&self) -> Vec<u8> {
+        crate::console::panic::teardown(self.features())
+    }
+
+    // the sequences that turn back on what teardown undid
+    fn setup(&self) -> Vec<u8> {
+        let mut s = vec![];
+        if self.alternate_screen {
+            s.extend_from_slice(b"\x1b[?1049h\x1b[0;0f");
+        }
+        if self.hidden_cursor {
+            s.extend_from_slice(b"\x1b[?25l");
+        }
+        if self.saved_title {
+            s.extend_from_slice(b"\x1b[22;0t");
+        }
+        if self.focus_reports {
+            s.extend_from_slice(crate::inputs::window::ENABLE_FOCUS_REPORTS);
+        }
+        if self.mouse {
+            s.extend_from_slice(crate::inputs::mouse::ENABLE_MOUSE);
+        }
+        if self.key_events {
+            s.extend_from_slice(crate::inputs::keyboard::ENABLE_KEY_EVENTS);
+        }
+
+        s
+    }
+
+    /// gives the console back its original modes for a while
+    /// the guard stays alive, resume puts everything back the way it was
+    pub fn suspend(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(&self.teardown())?;
+        writer.flush()?;
+
+        let guarded = GUARDED.lock().unwrap_or_else(|e| e.into_inner());
+        match guarded.as_ref() {
+            Some((_, original)) => set_modes(original),
+            None => Ok(()),
+        }
+    }
+
+    /// undoes suspend, raw mode and everything that was turned on through this guard come back
+    pub fn resume(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        if let Some((_, original)) = GUARDED.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            set_modes(&make_raw(original))?;
+        }
+
+        writer.write_all(&self.setup())?;
+        writer.flush()
+    }
+
+    /// restores the console without waiting for the guard to be dropped
+    /// calling this more than once does nothing
+    pub fn restore(&mut self) -> std::io::Result<()> {
+        if !self.active {
+            return Ok(());
+        }
+        self.active = false;
+        // the panic hook already restored everything
+        if self.epoch != crate::console::panic::epoch() {
+            return Ok(());
+        }
+
+        let mut writer = std::io::stdout();
+        let teardown = self.teardown();
+        // a failed write doesn't keep the console raw, the error is returned once it is restored
+        let written = match teardown.is_empty() {
+            true => Ok(()),
+            false => writer.write_all(&teardown).and_then(|_| writer.flush()),
+        };
+
+        let mut guarded = GUARDED.lock().unwrap_or_else(|e| e.into_inner());
+        let Some((count, _)) = guarded.as_mut() else {
+            return written;
+        };
+        *count -= 1;
+        if *count > 0 {
+            return written;
+        }
+
+        let (_, original) = guarded.take().unwrap();
+        crate::console::panic::unmark_all();
+        set_modes(&original)?;
+
+        written
+    }
+}
+
+// gives the console its original modes back and forgets about them
+// used by the panic hook, the guards themselves are left alone
+pub(crate) fn restore_all() {
+    let mut guarded = GUARDED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((_, original)) = guarded.take() {
+        _ = set_modes(&original);
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        _ = self.restore();
+    }
+}
@@ -0,0 +1,187 @@
+use super::{GetConsoleScreenBufferInfo, GetStdHandle, CONSOLE_SCREEN_BUFFER_INFO};
+use super::{STD_ERROR_HANDLE, STD_OUTPUT_HANDLE};
+
+/// the console window size
+/// named after its unix counterpart so that both platforms share the same winsize api
+#[derive(Debug, Default)]
+pub struct winsize {
+    ws_row: u16,
+    ws_col: u16,
+    source: Source,
+}
+
+/// where a winsize's dimensions were taken from
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Source {
+    /// the screen buffer of stdout
+    #[default]
+    Stdout,
+    /// the screen buffer of stderr, used when stdout is not a console
+    Stderr,
+    /// the COLUMNS and LINES environment variables
+    Env,
+    /// neither of the above were available, DEFAULT_COLS x DEFAULT_ROWS is used
+    Default,
+}
+
+/// errors of the window size detection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WinsizeError {
+    /// COLUMNS or LINES is set to something that is not a positive 16 bit number
+    BadEnvValue { var: &'static str, value: String },
+    /// neither stdout nor stderr is a console and COLUMNS and LINES are not set,
+    /// only returned by detect_terminal, detect falls back to DEFAULT_COLS x DEFAULT_ROWS
+    NoTerminal,
+}
+
+impl std::fmt::Display for WinsizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadEnvValue { var, value } => {
+                write!(f, "{} is set to {:?}, which is not a window size", var, value)
+            }
+            Self::NoTerminal => write!(f, "there is no terminal to take the window size from"),
+        }
+    }
+}
+
+impl std::error::Error for WinsizeError {}
+
+/// the width used when the window size could not be detected
+pub const DEFAULT_COLS: u16 = 80;
+/// the height used when the window size could not be detected
+pub const DEFAULT_ROWS: u16 = 24;
+
+/// detects the window size, trying in order
+/// the stdout screen buffer, the stderr screen buffer, the COLUMNS/LINES environment variables
+/// and finally DEFAULT_COLS x DEFAULT_ROWS
+///
+/// the returned winsize's source() tells which one was used
+///
+/// # Errors
+///
+/// returns an error if COLUMNS or LINES had to be read and one of them holds an invalid value
+pub fn detect() -> Result<winsize, WinsizeError> {
+    match detect_terminal() {
+        Err(WinsizeError::NoTerminal) => Ok(winsize::fallback()),
+        detected => detected,
+    }
+}
+
+/// detects the window size like detect, without falling back to DEFAULT_COLS x DEFAULT_ROWS,
+/// so that there being no terminal can be told apart from a small one
+///
+/// # Errors
+///
+/// returns NoTerminal when neither stdout nor stderr is a console and COLUMNS and LINES are not set,
+/// and the errors of detect
+pub fn detect_terminal() -> Result<winsize, WinsizeError> {
+    if let Some(ws) = winsize::from_handle(STD_OUTPUT_HANDLE, Source::Stdout) {
+        return Ok(ws);
+    }
+    if let Some(ws) = winsize::from_handle(STD_ERROR_HANDLE, Source::Stderr) {
+        return Ok(ws);
+    }
+
+    let ws = winsize::from_env(
+        std::env::var("COLUMNS").ok().as_deref(),
+        std::env::var("LINES").ok().as_deref(),
+    )?;
+    match ws.source() {
+        Source::Default => Err(WinsizeError::NoTerminal),
+        _ => Ok(ws),
+    }
+}
+
+// parses a COLUMNS or LINES value
+fn env_dim(var: &'static str, value: Option<&str>) -> Result<Option<u16>, WinsizeError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    match value.trim().parse::<u16>() {
+        Ok(dim) if dim > 0 => Ok(Some(dim)),
+        _ => Err(WinsizeError::BadEnvValue {
+            var,
+            value: value.to_string(),
+        }),
+    }
+}
+
+impl winsize {
+    /// creates a new winsize instance with the current window width and height
+    /// kept under its unix name, the size comes from the stdout screen buffer
+    pub fn from_ioctl() -> Self {
+        Self::from_handle(STD_OUTPUT_HANDLE, Source::Stdout).unwrap_or_default()
+    }
+
+    /// the DEFAULT_COLS x DEFAULT_ROWS window size
+    pub fn fallback() -> Self {
+        Self {
+            ws_col: DEFAULT_COLS,
+            ws_row: DEFAULT_ROWS,
+            source: Source::Default,
+        }
+    }
+
+    // reads the visible window of the screen buffer behind the given std handle
+    // None if the handle is not a console or the window has a zero area
+    fn from_handle(std_handle: u32, source: Source) -> Option<Self> {
+        let mut info = CONSOLE_SCREEN_BUFFER_INFO::default();
+        let res = unsafe { GetConsoleScreenBufferInfo(GetStdHandle(std_handle), &mut info) };
+        // the window rect bounds are inclusive
+        let cols = info.window.right - info.window.left + 1;
+        let rows = info.window.bottom - info.window.top + 1;
+        if res == 0 || cols <= 0 || rows <= 0 {
+            return None;
+        }
+
+        Some(Self {
+            ws_col: cols as u16,
+            ws_row: rows as u16,
+            source,
+        })
+    }
+
+    // builds a winsize out of the COLUMNS and LINES values
+    // a missing value is taken from the fallback size
+    fn from_env(cols: Option<&str>, rows: Option<&str>) -> Result<Self, WinsizeError> {
+        let cols = env_dim("COLUMNS", cols)?;
+        let rows = env_dim("LINES", rows)?;
+        if cols.is_none() && rows.is_none() {
+            return Ok(Self::fallback());
+        }
+
+        Ok(Self {
+            ws_col: cols.unwrap_or(DEFAULT_COLS),
+            ws_row: rows.unwrap_or(DEFAULT_ROWS),
+            source: Source::Env,
+        })
+    }
+
+    /// returns where this winsize's dimensions came from
+    pub fn source(&self) -> Source {
+        self.source
+    }
+
+    /// returns the width of the terminal window from this winsize instane
+    pub fn cols(&self) -> u16 {
+        self.ws_col
+    }
+
+    /// returns the height of the terminal window from this winsize instane
+    pub fn rows(&self) -> u16 {
+        self.ws_row
+    }
+
+    pub fn resized(&mut self) -> bool {
+        let ws = Self::from_ioctl();
+        if self.cols() != ws.cols() || self.rows() != ws.rows() {
+            *self = ws;
+
+            return true;
+        }
+
+        false
+    }
+}
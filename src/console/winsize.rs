@@ -22,8 +22,53 @@ pub struct winsize {
     ws_col: u16,
     ws_xpixel: u16,
     ws_ypixel: u16,
+    // not part of the c struct, ioctl only ever writes the 4 fields above
+    source: Source,
 }
 
+/// where a winsize's dimensions were taken from
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum Source {
+    /// TIOCGWINSZ on stdout
+    #[default]
+    Stdout,
+    /// TIOCGWINSZ on stderr, used when stdout is not a tty
+    Stderr,
+    /// the COLUMNS and LINES environment variables
+    Env,
+    /// neither of the above were available, DEFAULT_COLS x DEFAULT_ROWS is used
+    Default,
+}
+
+/// errors of the window size detection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WinsizeError {
+    /// COLUMNS or LINES is set to something that is not a positive 16 bit number
+    BadEnvValue { var: &'static str, value: String },
+    /// neither stdout nor stderr is a tty and COLUMNS and LINES are not set,
+    /// only returned by detect_terminal, detect falls back to DEFAULT_COLS x DEFAULT_ROWS
+    NoTerminal,
+}
+
+impl std::fmt::Display for WinsizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadEnvValue { var, value } => {
+                write!(f, "{} is set to {:?}, which is not a window size", var, value)
+            }
+            Self::NoTerminal => write!(f, "there is no terminal to take the window size from"),
+        }
+    }
+}
+
+impl std::error::Error for WinsizeError {}
+
+/// the width used when the window size could not be detected
+pub const DEFAULT_COLS: u16 = 80;
+/// the height used when the window size could not be detected
+pub const DEFAULT_ROWS: u16 = 24;
+
 // from /usr/include/unistd.h
 const STDIN_FILENO: i32 = 0;
 const STDOUT_FILENO: i32 = 1;
@@ -33,6 +78,62 @@ const STDERR_FILENO: i32 = 2;
 const TIOCGWINSZ: u64 = 0x5413;
 const TIOCSWINSZ: u64 = 0x5414;
 
+/// detects the window size, trying in order
+/// TIOCGWINSZ on stdout, TIOCGWINSZ on stderr, the COLUMNS/LINES environment variables
+/// and finally DEFAULT_COLS x DEFAULT_ROWS
+///
+/// the returned winsize's source() tells which one was used
+///
+/// # Errors
+///
+/// returns an error if COLUMNS or LINES had to be read and one of them holds an invalid value
+pub fn detect() -> Result<winsize, WinsizeError> {
+    match detect_terminal() {
+        Err(WinsizeError::NoTerminal) => Ok(winsize::fallback()),
+        detected => detected,
+    }
+}
+
+/// detects the window size like detect, without falling back to DEFAULT_COLS x DEFAULT_ROWS,
+/// so that there being no terminal can be told apart from a small one
+///
+/// # Errors
+///
+/// returns NoTerminal when neither stdout nor stderr is a tty and COLUMNS and LINES are not set,
+/// and the errors of detect
+pub fn detect_terminal() -> Result<winsize, WinsizeError> {
+    if let Some(ws) = winsize::from_fd(STDOUT_FILENO, Source::Stdout) {
+        return Ok(ws);
+    }
+    if let Some(ws) = winsize::from_fd(STDERR_FILENO, Source::Stderr) {
+        return Ok(ws);
+    }
+
+    let ws = winsize::from_env(
+        std::env::var("COLUMNS").ok().as_deref(),
+        std::env::var("LINES").ok().as_deref(),
+    )?;
+    match ws.source() {
+        Source::Default => Err(WinsizeError::NoTerminal),
+        _ => Ok(ws),
+    }
+}
+
+// parses a COLUMNS or LINES value
+fn env_dim(var: &'static str, value: Option<&str>) -> Result<Option<u16>, WinsizeError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    match value.trim().parse::<u16>() {
+        Ok(dim) if dim > 0 => Ok(Some(dim)),
+        _ => Err(WinsizeError::BadEnvValue {
+            var,
+            value: value.to_string(),
+        }),
+    }
+}
+
 impl winsize {
     /// creates a new winsize instance with the current window width and height
     pub fn from_ioctl() -> Self {
@@ -42,6 +143,53 @@ impl winsize {
         ws
     }
 
+    /// the DEFAULT_COLS x DEFAULT_ROWS window size
+    pub fn fallback() -> Self {
+        Self {
+            ws_col: DEFAULT_COLS,
+            ws_row: DEFAULT_ROWS,
+            source: Source::Default,
+            ..Default::default()
+        }
+    }
+
+    // asks the terminal behind fd for its size
+    // None if fd is not a tty or the tty reports a zero area
+    fn from_fd(fd: i32, source: Source) -> Option<Self> {
+        let mut ws = Self {
+            source,
+            ..Default::default()
+        };
+        let res = unsafe { ioctl(fd, TIOCGWINSZ, &mut ws) };
+        if res != 0 || ws.ws_col == 0 || ws.ws_row == 0 {
+            return None;
+        }
+
+        Some(ws)
+    }
+
+    // builds a winsize out of the COLUMNS and LINES values
+    // a missing value is taken from the fallback size
+    fn from_env(cols: Option<&str>, rows: Option<&str>) -> Result<Self, WinsizeError> {
+        let cols = env_dim("COLUMNS", cols)?;
+        let rows = env_dim("LINES", rows)?;
+        if cols.is_none() && rows.is_none() {
+            return Ok(Self::fallback());
+        }
+
+        Ok(Self {
+            ws_col: cols.unwrap_or(DEFAULT_COLS),
+            ws_row: rows.unwrap_or(DEFAULT_ROWS),
+            source: Source::Env,
+            ..Default::default()
+        })
+    }
+
+    /// returns where this winsize's dimensions came from
+    pub fn source(&self) -> Source {
+        self.source
+    }
+
     /// returns the width of the terminal window from this winsize instane
     pub fn cols(&self) -> u16 {
         self.ws_col
@@ -63,3 +211,48 @@ impl winsize {
         false
     }
 }
+
+#[cfg(test)]
+mod detect {
+    use super::*;
+
+    #[test]
+    fn env() {
+        let ws = winsize::from_env(Some("120"), Some("40")).unwrap();
+        assert_eq!([ws.cols(), ws.rows()], [120, 40]);
+        assert_eq!(ws.source(), Source::Env);
+
+        let ws = winsize::from_env(None, Some("40")).unwrap();
+        assert_eq!([ws.cols(), ws.rows()], [DEFAULT_COLS, 40]);
+    }
+
+    #[test]
+    fn fallback() {
+        let ws = winsize::from_env(None, None).unwrap();
+        assert_eq!([ws.cols(), ws.rows()], [80, 24]);
+        assert_eq!(ws.source(), Source::Default);
+    }
+
+    #[test]
+    fn bad_env() {
+        assert_eq!(
+            winsize::from_env(Some("wide"), None).unwrap_err(),
+            WinsizeError::BadEnvValue {
+                var: "COLUMNS",
+                value: "wide".to_string()
+            }
+        );
+        assert!(winsize::from_env(Some("80"), Some("0")).is_err());
+    }
+
+    #[test]
+    fn not_a_tty() {
+        assert!(winsize::from_fd(-1, Source::Stdout).is_none());
+        // whatever runs the tests, detect never says there is no terminal
+        assert!(detect().err() != Some(WinsizeError::NoTerminal));
+        if let Ok(ws) = detect_terminal() {
+            assert_ne!(ws.source(), Source::Default);
+            assert!(ws.cols() > 0 && ws.rows() > 0);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::RwLock;
+
+/// how much a diagnostic matters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    /// details that only help when looking into how the crate behaves
+    Debug,
+    Info,
+    /// something was asked for that could not be done, and was ignored
+    Warn,
+    Error,
+}
+
+type Logger = Box<dyn Fn(Level, &str) + Send + Sync>;
+
+static LOGGER: RwLock<Option<Logger>> = RwLock::new(None);
+
+/// routes the crate's diagnostics to logger, e.g. to write them to a file
+/// nothing is written anywhere until a logger is set, since writing to the terminal would
+/// corrupt the display, replaces the previous logger
+pub fn set_logger(logger: impl Fn(Level, &str) + Send + Sync + 'static) {
+    *LOGGER.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(logger));
+}
+
+/// drops the logger, the diagnostics go nowhere from then on
+pub fn clear_logger() {
+    *LOGGER.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+// hands the message to the logger, the message is only made when there is one
+pub(crate) fn log(level: Level, message: impl FnOnce() -> String) {
+    let logger = LOGGER.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(logger) = logger.as_ref() {
+        logger(level, &message());
+    }
+}
+
+#[cfg(all(test, feature = "components"))]
+mod diagnostics {
+    use super::*;
+    use crate::components::Term;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn logger() {
+        let logged = Arc::new(Mutex::new(vec![]));
+        let seen = logged.clone();
+        set_logger(move |level, message| seen.lock().unwrap().push((level, message.to_string())));
+
+        let term = Term::new(0, 10, 10);
+        assert!(!term.has_input(&[0, 9, 0]));
+        // the other tests may log too while the logger is set
+        let message = (Level::Debug, "no container with the id [0, 9]".to_string());
+        assert!(logged.lock().unwrap().contains(&message));
+        clear_logger();
+    }
+}
@@ -0,0 +1,842 @@
+use std::io::StdoutLock;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "components")]
+use crate::components::Term;
+use crate::inputs::keyboard::{decode_ki, Char, KbdEvent, KeyKind, Modifiers, PasteEvent};
+use crate::inputs::mouse::{decode_mi, decode_sgr, MouseEvent};
+use crate::inputs::window::{decode_focus, WindowEvent};
+
+/// input recording and replay
+pub mod record;
+/// user events, sent to the loop from other threads
+pub mod sender;
+
+pub use record::{Recorder, Replay};
+pub use sender::{EventSender, UserEvent, DEFAULT_USER_QUEUE};
+
+/// the async event stream, tokio based
+#[cfg(all(feature = "async", unix))]
+pub mod stream;
+
+#[cfg(all(feature = "async", unix))]
+pub use stream::{event_stream, EventStream};
+
+/// conversions between crossterm's events and these
+#[cfg(feature = "crossterm-compat")]
+pub mod compat;
+
+/// how long EventLoop waits for input before it gives a Tick, unless told otherwise
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// how long the rest of a sequence gets to come in
+/// before the bytes that came so far are taken as they are, e.g. a lone Esc
+pub const DEFAULT_ESCAPE_TIMEOUT: Duration = Duration::from_millis(25);
+
+/// an input event, as handed out by the EventLoop
+#[derive(Debug)]
+pub enum Event {
+    /// a key press
+    Key(KbdEvent),
+    /// a mouse report, only sent once the mouse reporting has been turned on
+    Mouse(MouseEvent),
+    /// a terminal window focus report, only sent once the focus reporting has been turned on
+    Window(WindowEvent),
+    /// the terminal window got resized to w columns and h rows
+    Resize { w: u16, h: u16 },
+    /// a bracketed paste
+    Paste(PasteEvent),
+    /// an event the app sent through an EventSender
+    User(UserEvent),
+    /// a signal asked the program to end, only sent once EventLoop::catch_signals is on,
+    /// see EventLoop::run for what happens if it is ignored
+    Signal(Signal),
+    /// the timeout went by with no input
+    Tick,
+    /// an event from another input source that has nothing to match it here,
+    /// e.g. a crossterm media key, see the crossterm-compat feature
+    Unsupported,
+}
+
+/// a signal that asks the program to end
+/// only Ctrl-C comes in as one on windows, see EventLoop::ctrl_c_signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Signal {
+    /// SIGINT, or Ctrl-C once EventLoop::ctrl_c_signal is on
+    Interrupt,
+    /// SIGTERM, e.g. from kill or the system shutting down
+    Term,
+    /// SIGHUP, the terminal went away
+    Hup,
+}
+
+/// how many bytes of a bracketed paste are kept, unless told otherwise
+pub const DEFAULT_PASTE_CAP: usize = 1 << 20;
+
+/// puts the read input bytes back together into events
+/// an escape sequence split across reads is kept until the rest of it comes in
+/// a bracketed paste comes out as one Paste event, cut down to the paste cap
+#[derive(Debug)]
+pub struct Parser {
+    bytes: Vec<u8>,
+    paste_cap: usize,
+    // the kept start of a paste that went over the cap, while the rest of it is skipped
+    paste: Option<Vec<u8>>,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self {
+            bytes: vec![],
+            paste_cap: DEFAULT_PASTE_CAP,
+            paste: None,
+        }
+    }
+}
+
+impl Parser {
+    /// makes a new Parser with no bytes in it
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sets how many bytes of a paste are kept at most
+    /// the rest of a bigger paste is skipped and the event is flagged as truncated
+    pub fn paste_cap(&mut self, cap: usize) {
+        self.paste_cap = cap;
+    }
+
+    /// hands new input bytes to the parser
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    /// whether some bytes are still waiting for the rest of their sequence
+    pub fn is_pending(&self) -> bool {
+        !self.bytes.is_empty() || self.paste.is_some()
+    }
+
+    /// whether a bracketed paste started and didn't end yet
+    pub fn in_paste(&self) -> bool {
+        self.paste.is_some() || self.bytes.starts_with(PASTE_START)
+    }
+
+    /// takes the next whole event out of the fed bytes
+    /// returns None once the bytes left are not a whole event yet
+    /// sequences that are not known are dropped
+    pub fn next_event(&mut self) -> Option<Event> {
+        if self.paste.is_some() {
+            return self.skip_paste();
+        }
+
+        while let Some(len) = token_len(&self.bytes) {
+            let token = self.bytes.drain(..len).collect::<Vec<u8>>();
+            if let Some(event) = decode(&token, self.paste_cap) {
+                return Some(event);
+            }
+        }
+
+        // a paste that is already over the cap doesn't need to be held on to until it ends
+        if self.bytes.starts_with(PASTE_START)
+            && self.bytes.len() - PASTE_START.len() > self.paste_cap
+        {
+            let kept = self.bytes[PASTE_START.len()..][..self.paste_cap].to_vec();
+            self.bytes.drain(..PASTE_START.len() + self.paste_cap);
+            self.paste = Some(kept);
+
+            return self.skip_paste();
+        }
+
+        None
+    }
+
+    // drops the bytes of the paste that went over the cap, up to the end of the paste
+    fn skip_paste(&mut self) -> Option<Event> {
+        match find(&self.bytes, PASTE_END) {
+            Some(idx) => {
+                self.bytes.drain(..idx + PASTE_END.len());
+                let kept = self.paste.take()?;
+
+                Some(paste_event(&kept, true, self.paste_cap))
+            }
+            None => {
+                // the end of the paste may be split across reads
+                let skipped = self.bytes.len().saturating_sub(PASTE_END.len() - 1);
+                self.bytes.drain(..skipped);
+
+                None
+            }
+        }
+    }
+
+    /// stops waiting for the rest of the pending sequence
+    /// a lone escape byte is an Esc key press and an escape byte followed by one more byte
+    /// is that key with Alt, a paste that never ended comes out truncated,
+    /// anything else that was pending is dropped
+    pub fn flush(&mut self) -> Option<Event> {
+        let bytes = std::mem::take(&mut self.bytes);
+        if let Some(kept) = self.paste.take() {
+            return Some(paste_event(&kept, true, self.paste_cap));
+        }
+
+        match bytes[..] {
+            [27] | [27, 0..=127] => decode_ki(&bytes).map(Event::Key),
+            _ if bytes.starts_with(PASTE_START) => Some(paste_event(
+                &bytes[PASTE_START.len()..],
+                true,
+                self.paste_cap,
+            )),
+            _ => None,
+        }
+    }
+}
+
+// the length of the first event in bytes
+// None when bytes are empty or end in the middle of a sequence
+fn token_len(bytes: &[u8]) -> Option<usize> {
+    let b0 = *bytes.first()?;
+    if b0 != 27 {
+        let len = match b0 {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 1,
+        };
+
+        return (bytes.len() >= len).then_some(len);
+    }
+
+    match bytes.get(1) {
+        None => None,
+        Some(b'[') => csi_len(bytes),
+        // ss3, the final byte may come after a modifier digit, e.g. '\x1bO5P'
+        Some(b'O') => bytes
+            .iter()
+            .skip(2)
+            .position(|b| !b.is_ascii_digit())
+            .map(|i| i + 3),
+        // alt + ascii
+        Some(0..=127) => Some(2),
+        // an Esc right before a utf8 char
+        Some(_) => Some(1),
+    }
+}
+
+fn csi_len(bytes: &[u8]) -> Option<usize> {
+    match bytes.get(2) {
+        None => None,
+        // sgr mouse, ends in M or m
+        Some(b'<') => bytes.iter().position(|b| *b == b'M' || *b == b'm').map(|i| i + 1),
+        // x10 mouse, 3 bytes after the M
+        Some(b'M') => (bytes.len() >= 6).then_some(6),
+        _ if bytes.starts_with(PASTE_START) => find(bytes, PASTE_END).map(|i| i + PASTE_END.len()),
+        _ => {
+            for (i, b) in bytes.iter().enumerate().skip(2) {
+                match b {
+                    // the final byte
+                    0x40..=0x7E => return Some(i + 1),
+                    // parameter and intermediate bytes
+                    0x20..=0x3F => (),
+                    // not a sequence after all, it ends right before this byte
+                    _ => return Some(i),
+                }
+            }
+
+            None
+        }
+    }
+}
+
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+fn find(bytes: &[u8], pat: &[u8]) -> Option<usize> {
+    bytes.windows(pat.len()).position(|w| w == pat)
+}
+
+// the paste of text, cut down to cap bytes on a char boundary
+// carriage returns, which some terminals paste line breaks as, become newlines
+fn paste_event(text: &[u8], truncated: bool, cap: usize) -> Event {
+    let mut text = String::from_utf8_lossy(text)
+        .replace("\r\n", "\n")
+        .replace('\r', "\n");
+    let truncated = truncated || text.len() > cap;
+    if text.len() > cap {
+        let mut end = cap;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+
+    Event::Paste(PasteEvent { text, truncated })
+}
+
+fn decode(token: &[u8], paste_cap: usize) -> Option<Event> {
+    if let Some(focus) = decode_focus(token) {
+        return Some(Event::Window(focus));
+    }
+
+    match token {
+        [27, b'[', b'<', ..] => decode_sgr(token).into_iter().next().map(Event::Mouse),
+        [27, b'[', b'M', _, _, _] => decode_mi(token).into_iter().next().map(Event::Mouse),
+        _ if token.starts_with(PASTE_START) => {
+            let text = &token[PASTE_START.len()..token.len() - PASTE_END.len()];
+
+            Some(paste_event(text, false, paste_cap))
+        }
+        _ if decodable(token) => decode_ki(token).map(Event::Key),
+        _ => None,
+    }
+}
+
+// whether the keyboard decoder knows these bytes
+// it panics on sequences it doesn't know, so those never make it there
+// csi and ss3 sequences go to decode_csi, which gives None instead
+fn decodable(token: &[u8]) -> bool {
+    match token {
+        [0..=127] | [27, 0..=127] => true,
+        [27, b'[' | b'O', _, ..] => true,
+        [27, ..] => false,
+        // a single utf8 char
+        _ => std::str::from_utf8(token).is_ok_and(|s| s.chars().count() == 1),
+    }
+}
+
+/// reads stdin and hands out one event at a time
+/// a Tick comes out whenever the timeout goes by with nothing read
+#[derive(Debug)]
+pub struct EventLoop {
+    timeout: Duration,
+    escape_timeout: Duration,
+    parser: Parser,
+    size: Option<[u16; 2]>,
+    // made on the first sender call
+    user: Option<Arc<sender::Queue>>,
+    user_capacity: usize,
+    // woken by SIGWINCH, and SIGINT, SIGTERM and SIGHUP when they are caught,
+    // the handlers get installed on the first poll
+    #[cfg(unix)]
+    signals: Option<&'static crate::console::wake::Waker>,
+    // whether SIGINT, SIGTERM and SIGHUP are caught, and whether their handlers are installed
+    #[cfg(unix)]
+    catch_signals: bool,
+    #[cfg(unix)]
+    caught: bool,
+    ctrl_c_signal: bool,
+    recorder: Option<Recorder>,
+    // read instead of stdin when set
+    replay: Option<Replay>,
+}
+
+impl Default for EventLoop {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            escape_timeout: DEFAULT_ESCAPE_TIMEOUT,
+            parser: Parser::new(),
+            size: None,
+            user: None,
+            user_capacity: DEFAULT_USER_QUEUE,
+            #[cfg(unix)]
+            signals: None,
+            #[cfg(unix)]
+            catch_signals: false,
+            #[cfg(unix)]
+            caught: false,
+            ctrl_c_signal: false,
+            recorder: None,
+            replay: None,
+        }
+    }
+}
+
+impl EventLoop {
+    /// makes a new EventLoop that ticks every DEFAULT_TIMEOUT with no input
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sets how long to wait for input before giving a Tick
+    pub fn timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// sets how long the rest of a sequence gets to come in before a lone Esc is reported
+    /// slow links, like ssh, may need more than the default
+    pub fn escape_timeout(&mut self, timeout: Duration) {
+        self.escape_timeout = timeout;
+    }
+
+    /// catches SIGINT, SIGTERM and SIGHUP from the next poll on and hands them out as
+    /// Event::Signal, so the terminal can be restored before the program ends, see EventLoop::run
+    /// they are left to their default handling, or whatever the app set up, by default,
+    /// and get the handlers they had before back once this loop is dropped
+    #[cfg(unix)]
+    pub fn catch_signals(&mut self, catch: bool) {
+        self.catch_signals = catch;
+        if !catch && self.caught {
+            crate::console::signals::release_exit();
+            self.caught = false;
+        }
+    }
+
+    /// hands Ctrl-C out as Event::Signal(Signal::Interrupt) instead of a key press
+    /// raw mode turns the terminal's own Ctrl-C handling off, so it is a key press by default
+    pub fn ctrl_c_signal(&mut self, signal: bool) {
+        self.ctrl_c_signal = signal;
+    }
+
+    // Ctrl-C as a signal, if ctrl_c_signal is on
+    fn interrupt(&self, event: Event) -> Event {
+        match event {
+            Event::Key(KbdEvent {
+                char: Char::Char('c'),
+                modifiers: Modifiers(2),
+                kind,
+            }) if self.ctrl_c_signal && kind != KeyKind::Release => Event::Signal(Signal::Interrupt),
+            event => event,
+        }
+    }
+
+    /// records every input chunk read from stdin to the file at path, see Recorder
+    /// only the unix input is made of bytes, nothing gets recorded on windows
+    pub fn record(&mut self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        self.recorder = Some(Recorder::create(path)?);
+
+        Ok(())
+    }
+
+    /// hands out the events of replay instead of reading stdin
+    /// the poll timeouts don't apply, see Replay::timed for the pace of the events
+    /// once the replay is over, polling gives an error of kind UnexpectedEof,
+    /// which is what ends a run over a replay
+    pub fn replay(&mut self, replay: Replay) {
+        self.replay = Some(replay);
+    }
+
+    // the next event of the replay, if there is one
+    fn replayed(&mut self) -> Option<std::io::Result<Option<Event>>> {
+        let replay = self.replay.as_mut()?;
+
+        Some(match replay.next_event() {
+            Some(event) => Ok(Some(event)),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "the replay is over",
+            )),
+        })
+    }
+
+    /// sets how many bytes of a paste are kept at most, see Parser::paste_cap
+    pub fn paste_cap(&mut self, cap: usize) {
+        self.parser.paste_cap(cap);
+    }
+
+    /// sets how many user events are held on to at most, the oldest ones are dropped past that
+    pub fn user_queue(&mut self, capacity: usize) {
+        self.user_capacity = capacity;
+        if let Some(queue) = &self.user {
+            queue.capacity(capacity);
+        }
+    }
+
+    /// a sender of user events to this loop, it can be cloned and moved to other threads
+    /// sending wakes the loop up, even while it is waiting for input
+    ///
+    /// # Errors
+    ///
+    /// returns an error if the wake up pipe could not be made
+    pub fn sender(&mut self) -> std::io::Result<EventSender> {
+        if self.user.is_none() {
+            self.user = Some(Arc::new(sender::Queue::new(self.user_capacity)?));
+        }
+
+        Ok(EventSender::new(self.user.as_ref().unwrap()))
+    }
+
+    // the next user event that was sent, if any
+    fn user_event(&self) -> Option<Event> {
+        self.user.as_ref()?.pop().map(Event::User)
+    }
+
+    // a resize event if the window size changed since the last look
+    // the first look only takes note of the size
+    // on unix the size is only looked at again once a SIGWINCH came in,
+    // a burst of resizes in between gives one event with the latest size
+    fn resized(&mut self) -> Option<Event> {
+        #[cfg(unix)]
+        if self.size.is_some() && !crate::console::signals::take_winch() {
+            return None;
+        }
+
+        let ws = crate::console::winsize::winsize::from_ioctl();
+        let size = [ws.cols(), ws.rows()];
+        // not a terminal
+        if size.contains(&0) {
+            return None;
+        }
+
+        match self.size.replace(size) {
+            Some(old) if old != size => Some(Event::Resize {
+                w: size[0],
+                h: size[1],
+            }),
+            _ => None,
+        }
+    }
+
+    /// blocks until the next event, or a Tick once the timeout goes by with no input
+    /// raw mode has to be on, otherwise the input only comes in once the user hits enter
+    pub fn next_event(&mut self) -> std::io::Result<Event> {
+        self.poll_event(self.timeout)
+            .map(|event| event.unwrap_or(Event::Tick))
+    }
+
+    /// returns the next event if one comes in within timeout, None otherwise
+    /// a timeout of zero only takes what is already there
+    ///
+    /// a lone escape byte could be an Esc key press or the start of a sequence,
+    /// so the rest of a sequence always gets the escape timeout to come in, even past timeout,
+    /// and the rest of a started paste gets the timeout set with EventLoop::timeout
+    #[cfg(unix)]
+    pub fn poll_event(&mut self, timeout: Duration) -> std::io::Result<Option<Event>> {
+        if let Some(replayed) = self.replayed() {
+            return replayed;
+        }
+        if self.signals.is_none() {
+            self.signals = crate::console::signals::catch_winch().ok();
+        }
+        if self.catch_signals && !self.caught {
+            crate::console::signals::catch_exit()?;
+            self.caught = true;
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut buf = [0u8; 1024];
+        loop {
+            if let Some(signal) = crate::console::signals::take_signal() {
+                return Ok(Some(Event::Signal(signal)));
+            }
+            if let Some(event) = self.parser.next_event() {
+                return Ok(Some(self.interrupt(event)));
+            }
+            if let Some(event) = self.user_event() {
+                return Ok(Some(event));
+            }
+            if let Some(event) = self.resized() {
+                return Ok(Some(event));
+            }
+
+            let pending = crate::inputs::take_pending();
+            if !pending.is_empty() {
+                if let Some(recorder) = &mut self.recorder {
+                    recorder.chunk(&pending)?;
+                }
+                self.parser.feed(&pending);
+                continue;
+            }
+
+            let left = deadline.saturating_duration_since(Instant::now());
+            // a paste is pasted all at once but may come in over many reads on a slow link
+            let wait = match (self.parser.in_paste(), self.parser.is_pending()) {
+                (true, _) => self.timeout,
+                (false, true) => self.escape_timeout,
+                (false, false) => left,
+            };
+            let wakers = self
+                .user
+                .as_ref()
+                .map(|queue| &queue.waker)
+                .into_iter()
+                .chain(self.signals)
+                .collect::<Vec<&crate::console::wake::Waker>>();
+            let n = crate::console::query::read_fd_timeout(0, &mut buf, wait, &wakers)?;
+            if n > 0 {
+                if let Some(recorder) = &mut self.recorder {
+                    recorder.chunk(&buf[..n])?;
+                }
+                self.parser.feed(&buf[..n]);
+                continue;
+            }
+
+            if self.parser.is_pending() {
+                if let Some(event) = self.parser.flush() {
+                    return Ok(Some(self.interrupt(event)));
+                }
+                continue;
+            }
+
+            // an interrupted wait comes back before the deadline
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// returns the next key press or user event if one comes in within timeout, None otherwise
+    #[cfg(windows)]
+    pub fn poll_event(&mut self, timeout: Duration) -> std::io::Result<Option<Event>> {
+        if let Some(replayed) = self.replayed() {
+            return replayed;
+        }
+        use crate::console::windows::{wait_input, Wait};
+
+        if let Some(event) = self.resized() {
+            return Ok(Some(event));
+        }
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(event) = self.user_event() {
+                return Ok(Some(event));
+            }
+
+            let left = deadline.saturating_duration_since(Instant::now());
+            let waker = self.user.as_ref().map(|queue| &queue.waker);
+            match wait_input(left, waker)? {
+                Wait::Input => {
+                    return crate::inputs::keyboard::read_kbd_event()
+                        .map(|ke| Some(self.interrupt(Event::Key(ke))))
+                }
+                Wait::Woken => (),
+                Wait::Timeout => return Ok(None),
+            }
+        }
+    }
+
+    /// starts the term, see Term::start, then hands every event to the handler
+    /// the lines logged to the log panes are drained into them, see Term::drain_logs,
+    /// and so are the bytes sent to the texts, see Term::drain_senders,
+    /// and the components the handler marked dirty are rendered after each event
+    /// a Resize goes through Term::resize before the handler gets it,
+    /// the display is then cleared and drawn anew, a Tick goes through Term::tick first
+    /// returns once the handler breaks, the terminal gets torn down on the way out
+    /// however the loop is left
+    ///
+    /// a Signal the handler doesn't break on ends the program once the terminal is torn down,
+    /// like the signal would have with no handler for it, see EventLoop::catch_signals
+    #[cfg(feature = "components")]
+    pub fn run<F>(
+        &mut self,
+        term: &mut Term,
+        writer: &mut StdoutLock,
+        mut handler: F,
+    ) -> std::io::Result<()>
+    where
+        F: FnMut(&mut Term, Event) -> ControlFlow<()>,
+    {
+        let guard = term.start(writer)?;
+
+        loop {
+            let event = self.next_event()?;
+            let resized = match event {
+                Event::Resize { w, h } => {
+                    term.resize(w, h);
+                    true
+                }
+                Event::Tick => {
+                    term.tick();
+                    false
+                }
+                _ => false,
+            };
+            let signal = match event {
+                Event::Signal(signal) => Some(signal),
+                _ => None,
+            };
+            if handler(term, event).is_break() {
+                return Ok(());
+            }
+            if let Some(signal) = signal {
+                drop(guard);
+                #[cfg(unix)]
+                crate::console::signals::die(signal);
+                // Ctrl-C is the only signal on windows
+                #[cfg(windows)]
+                std::process::exit(130);
+            }
+            if resized {
+                term.clear(writer)?;
+            }
+            term.drain_logs();
+            term.drain_senders();
+            if term.is_dirty() {
+                term.render_dirty(writer)?;
+            }
+        }
+    }
+}
+
+/// returns the next input event if one comes in within timeout, None otherwise
+/// see EventLoop::poll_event, window resizes are only noticed by a kept EventLoop
+pub fn poll_event(timeout: Duration) -> std::io::Result<Option<Event>> {
+    // the bytes of an unfinished sequence are kept for the next call once this is dropped
+    EventLoop::new().poll_event(timeout)
+}
+
+impl Drop for EventLoop {
+    fn drop(&mut self) {
+        // half a sequence is not lost when another EventLoop takes over
+        crate::inputs::keep_pending(&self.parser.bytes);
+        #[cfg(unix)]
+        if self.caught {
+            crate::console::signals::release_exit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod parse {
+    use super::*;
+    use crate::inputs::keyboard::{Char, Modifiers, CC};
+    use crate::inputs::mouse::MouseKind;
+
+    fn key(event: Option<Event>) -> (Char, Modifiers) {
+        match event {
+            Some(Event::Key(ke)) => (ke.char, ke.modifiers),
+            e => panic!("expected a key, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn split_sequences() {
+        let mut parser = Parser::new();
+        parser.feed(b"\x1b[");
+        assert!(parser.next_event().is_none());
+        assert!(parser.is_pending());
+        parser.feed(b"A");
+        assert_eq!(key(parser.next_event()).0, Char::CC(CC::Up));
+
+        parser.feed(b"\x1b[<0;10");
+        assert!(parser.next_event().is_none());
+        parser.feed(b";5M");
+        match parser.next_event() {
+            Some(Event::Mouse(me)) => assert_eq!([me.column, me.row], [9, 4]),
+            e => panic!("expected a mouse event, got {:?}", e),
+        }
+
+        parser.feed(&"é".as_bytes()[..1]);
+        assert!(parser.next_event().is_none());
+        parser.feed(&"é".as_bytes()[1..]);
+        assert_eq!(key(parser.next_event()).0, Char::Char('é'));
+        assert!(!parser.is_pending());
+    }
+
+    #[test]
+    fn many_in_one_read() {
+        let mut parser = Parser::new();
+        parser.feed(b"a\x1b[1;5C\x1b[Ib\x1b[<64;1;1M");
+        assert_eq!(key(parser.next_event()).0, Char::Char('a'));
+        assert_eq!(
+            key(parser.next_event()),
+            (Char::CC(CC::Right), Modifiers(crate::inputs::keyboard::CONTROL))
+        );
+        assert!(matches!(
+            parser.next_event(),
+            Some(Event::Window(WindowEvent::WindowGainedFocus))
+        ));
+        assert_eq!(key(parser.next_event()).0, Char::Char('b'));
+        assert!(matches!(
+            parser.next_event(),
+            Some(Event::Mouse(MouseEvent {
+                kind: MouseKind::ScrollUp,
+                ..
+            }))
+        ));
+        assert!(parser.next_event().is_none());
+    }
+
+    #[test]
+    fn lone_escape() {
+        let mut parser = Parser::new();
+        parser.feed(b"\x1b");
+        assert!(parser.next_event().is_none());
+        assert_eq!(key(parser.flush()).0, Char::CC(CC::ESC));
+
+        parser.feed(b"\x1b[");
+        assert_eq!(
+            key(parser.flush()),
+            (Char::Char('['), Modifiers(crate::inputs::keyboard::ALT))
+        );
+        assert!(!parser.is_pending());
+    }
+
+    #[test]
+    fn paste() {
+        let mut parser = Parser::new();
+        parser.feed(b"\x1b[200~two\nlines");
+        assert!(parser.next_event().is_none());
+        assert!(parser.in_paste());
+        parser.feed(b"\x1b[201~");
+        match parser.next_event() {
+            Some(Event::Paste(PasteEvent { text, truncated })) => {
+                assert_eq!(text, "two\nlines");
+                assert!(!truncated);
+            }
+            e => panic!("expected a paste, got {:?}", e),
+        }
+        assert!(!parser.in_paste());
+    }
+
+    #[test]
+    fn paste_cap() {
+        let mut parser = Parser::new();
+        parser.paste_cap(4);
+        parser.feed(b"\x1b[200~a\r\nb");
+        parser.feed(b"cdefgh");
+        // the rest of the paste is skipped as it comes in
+        assert!(parser.next_event().is_none());
+        assert!(parser.is_pending());
+        parser.feed(b"ijk\x1b[20");
+        assert!(parser.next_event().is_none());
+        parser.feed(b"1~x");
+        match parser.next_event() {
+            Some(Event::Paste(PasteEvent { text, truncated })) => {
+                assert_eq!(text, "a\nb");
+                assert!(truncated);
+            }
+            e => panic!("expected a paste, got {:?}", e),
+        }
+        assert_eq!(key(parser.next_event()).0, Char::Char('x'));
+
+        // a paste that never ends is handed out as far as it went
+        parser.feed(b"\x1b[200~ab");
+        assert!(parser.next_event().is_none());
+        assert!(matches!(
+            parser.flush(),
+            Some(Event::Paste(PasteEvent {
+                truncated: true,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn unknown_dropped() {
+        let mut parser = Parser::new();
+        parser.feed(b"\x1b[?99x\x1b[99~q");
+        assert_eq!(key(parser.next_event()).0, Char::Char('q'));
+    }
+
+    #[test]
+    fn ctrl_c() {
+        let mut parser = Parser::new();
+        let mut event_loop = EventLoop::new();
+        parser.feed(b"\x03\x03");
+        assert!(matches!(
+            event_loop.interrupt(parser.next_event().unwrap()),
+            Event::Key(KbdEvent {
+                char: Char::Char('c'),
+                ..
+            })
+        ));
+        event_loop.ctrl_c_signal(true);
+        assert!(matches!(
+            event_loop.interrupt(parser.next_event().unwrap()),
+            Event::Signal(Signal::Interrupt)
+        ));
+    }
+}
@@ -0,0 +1,358 @@
+// the crossterm events in ragout's terms and back
+// crossterm's own paths always start with :: so they don't read as this module's
+
+use ::crossterm::event as ct;
+
+use super::{paste_event, Event};
+use crate::inputs::keyboard::{Char, KbdEvent, KeyKind, Modifiers, CC};
+use crate::inputs::mouse::{MouseButton, MouseEvent, MouseKind};
+use crate::inputs::window::WindowEvent;
+
+impl From<ct::Event> for Event {
+    /// the ragout event of a crossterm one, the keys that have nothing to match them in ragout
+    /// become Event::Unsupported
+    fn from(event: ct::Event) -> Self {
+        match event {
+            ct::Event::Key(ke) => key(ke).map_or(Event::Unsupported, Event::Key),
+            ct::Event::Mouse(me) => Event::Mouse(mouse(me)),
+            ct::Event::Resize(w, h) => Event::Resize { w, h },
+            ct::Event::FocusGained => Event::Window(WindowEvent::WindowGainedFocus),
+            ct::Event::FocusLost => Event::Window(WindowEvent::WindowLostFocus),
+            // crossterm has the whole paste already, there is no cap to cut it down to
+            ct::Event::Paste(text) => paste_event(text.as_bytes(), false, usize::MAX),
+        }
+    }
+}
+
+impl From<ct::KeyModifiers> for Modifiers {
+    /// meta is taken as super, like the xterm sequences have it, hyper is dropped
+    fn from(mods: ct::KeyModifiers) -> Self {
+        [
+            (ct::KeyModifiers::SHIFT, Modifiers::SHIFT),
+            (ct::KeyModifiers::ALT, Modifiers::ALT),
+            (ct::KeyModifiers::CONTROL, Modifiers::CONTROL),
+            (ct::KeyModifiers::SUPER, Modifiers::SUPER),
+            (ct::KeyModifiers::META, Modifiers::SUPER),
+        ]
+        .into_iter()
+        .filter(|(theirs, _)| mods.contains(*theirs))
+        .fold(Modifiers::NONE, |acc, (_, flag)| acc | flag)
+    }
+}
+
+impl From<Modifiers> for ct::KeyModifiers {
+    fn from(mods: Modifiers) -> Self {
+        [
+            (Modifiers::SHIFT, ct::KeyModifiers::SHIFT),
+            (Modifiers::ALT, ct::KeyModifiers::ALT),
+            (Modifiers::CONTROL, ct::KeyModifiers::CONTROL),
+            (Modifiers::SUPER, ct::KeyModifiers::SUPER),
+        ]
+        .into_iter()
+        .filter(|(flag, _)| mods.contains(*flag))
+        .fold(ct::KeyModifiers::NONE, |acc, (_, theirs)| acc | theirs)
+    }
+}
+
+impl From<ct::KeyEventKind> for KeyKind {
+    fn from(kind: ct::KeyEventKind) -> Self {
+        match kind {
+            ct::KeyEventKind::Press => KeyKind::Press,
+            ct::KeyEventKind::Repeat => KeyKind::Repeat,
+            ct::KeyEventKind::Release => KeyKind::Release,
+        }
+    }
+}
+
+impl From<KeyKind> for ct::KeyEventKind {
+    fn from(kind: KeyKind) -> Self {
+        match kind {
+            KeyKind::Press => ct::KeyEventKind::Press,
+            KeyKind::Repeat => ct::KeyEventKind::Repeat,
+            KeyKind::Release => ct::KeyEventKind::Release,
+        }
+    }
+}
+
+impl From<KbdEvent> for ct::KeyEvent {
+    /// every ragout key has a crossterm one, a shifted tab is crossterm's BackTab
+    fn from(ke: KbdEvent) -> Self {
+        let code = match ke.char {
+            Char::Char(c) => ct::KeyCode::Char(c),
+            Char::CC(CC::TAB) if ke.modifiers.contains(Modifiers::SHIFT) => ct::KeyCode::BackTab,
+            Char::CC(cc) => match cc {
+                CC::BS => ct::KeyCode::Backspace,
+                CC::TAB => ct::KeyCode::Tab,
+                CC::CR => ct::KeyCode::Enter,
+                CC::ESC => ct::KeyCode::Esc,
+                CC::Up => ct::KeyCode::Up,
+                CC::Down => ct::KeyCode::Down,
+                CC::Right => ct::KeyCode::Right,
+                CC::Left => ct::KeyCode::Left,
+                CC::Insert => ct::KeyCode::Insert,
+                CC::Delete => ct::KeyCode::Delete,
+                CC::Home => ct::KeyCode::Home,
+                CC::End => ct::KeyCode::End,
+                CC::PageUp => ct::KeyCode::PageUp,
+                CC::PageDown => ct::KeyCode::PageDown,
+                CC::F1 => ct::KeyCode::F(1),
+                CC::F2 => ct::KeyCode::F(2),
+                CC::F3 => ct::KeyCode::F(3),
+                CC::F4 => ct::KeyCode::F(4),
+                CC::F5 => ct::KeyCode::F(5),
+                CC::F6 => ct::KeyCode::F(6),
+                CC::F7 => ct::KeyCode::F(7),
+                CC::F8 => ct::KeyCode::F(8),
+                CC::F9 => ct::KeyCode::F(9),
+                CC::F10 => ct::KeyCode::F(10),
+                CC::F11 => ct::KeyCode::F(11),
+                CC::F12 => ct::KeyCode::F(12),
+            },
+        };
+
+        ct::KeyEvent::new_with_kind(code, ke.modifiers.into(), ke.kind.into())
+    }
+}
+
+// the key of a crossterm key event, None for the keys ragout has no Char for
+// a BackTab is a tab with shift, like the '\x1b[Z' sequence
+fn key(ke: ct::KeyEvent) -> Option<KbdEvent> {
+    let mut modifiers = Modifiers::from(ke.modifiers);
+    let char = match ke.code {
+        ct::KeyCode::Char(c) => Char::Char(c),
+        // ctrl + space or ctrl + @, which the decoder takes as ctrl + @ too
+        ct::KeyCode::Null => {
+            modifiers |= Modifiers::CONTROL;
+            Char::Char('@')
+        }
+        ct::KeyCode::BackTab => {
+            modifiers |= Modifiers::SHIFT;
+            Char::CC(CC::TAB)
+        }
+        ct::KeyCode::Backspace => Char::CC(CC::BS),
+        ct::KeyCode::Tab => Char::CC(CC::TAB),
+        ct::KeyCode::Enter => Char::CC(CC::CR),
+        ct::KeyCode::Esc => Char::CC(CC::ESC),
+        ct::KeyCode::Up => Char::CC(CC::Up),
+        ct::KeyCode::Down => Char::CC(CC::Down),
+        ct::KeyCode::Right => Char::CC(CC::Right),
+        ct::KeyCode::Left => Char::CC(CC::Left),
+        ct::KeyCode::Insert => Char::CC(CC::Insert),
+        ct::KeyCode::Delete => Char::CC(CC::Delete),
+        ct::KeyCode::Home => Char::CC(CC::Home),
+        ct::KeyCode::End => Char::CC(CC::End),
+        ct::KeyCode::PageUp => Char::CC(CC::PageUp),
+        ct::KeyCode::PageDown => Char::CC(CC::PageDown),
+        ct::KeyCode::F(n) => Char::CC(match n {
+            1 => CC::F1,
+            2 => CC::F2,
+            3 => CC::F3,
+            4 => CC::F4,
+            5 => CC::F5,
+            6 => CC::F6,
+            7 => CC::F7,
+            8 => CC::F8,
+            9 => CC::F9,
+            10 => CC::F10,
+            11 => CC::F11,
+            12 => CC::F12,
+            _ => return None,
+        }),
+        // lock, media, keypad begin and lone modifier keys
+        _ => return None,
+    };
+
+    Some(KbdEvent {
+        char,
+        modifiers,
+        kind: ke.kind.into(),
+    })
+}
+
+// the mouse event of a crossterm one, crossterm's columns and rows are 0 based too
+fn mouse(me: ct::MouseEvent) -> MouseEvent {
+    let button = |button| match button {
+        ct::MouseButton::Left => MouseButton::Left,
+        ct::MouseButton::Middle => MouseButton::Middle,
+        ct::MouseButton::Right => MouseButton::Right,
+    };
+    let (kind, button) = match me.kind {
+        ct::MouseEventKind::Down(b) => (MouseKind::Down, button(b)),
+        ct::MouseEventKind::Up(b) => (MouseKind::Up, button(b)),
+        ct::MouseEventKind::Drag(b) => (MouseKind::Drag, button(b)),
+        ct::MouseEventKind::Moved => (MouseKind::Move, MouseButton::None),
+        ct::MouseEventKind::ScrollUp => (MouseKind::ScrollUp, MouseButton::None),
+        ct::MouseEventKind::ScrollDown => (MouseKind::ScrollDown, MouseButton::None),
+        ct::MouseEventKind::ScrollLeft => (MouseKind::ScrollLeft, MouseButton::None),
+        ct::MouseEventKind::ScrollRight => (MouseKind::ScrollRight, MouseButton::None),
+    };
+
+    MouseEvent {
+        kind,
+        button,
+        column: me.column,
+        row: me.row,
+        modifiers: me.modifiers.into(),
+    }
+}
+
+impl Event {
+    /// the crossterm event of this event, None for the ones crossterm has nothing for:
+    /// user events, signals, ticks, unsupported events and button events of an unknown button
+    pub fn to_crossterm(&self) -> Option<ct::Event> {
+        Some(match self {
+            Event::Key(ke) => ct::Event::Key(ke.clone().into()),
+            Event::Mouse(me) => {
+                let button = match me.button {
+                    MouseButton::Left => Some(ct::MouseButton::Left),
+                    MouseButton::Middle => Some(ct::MouseButton::Middle),
+                    MouseButton::Right => Some(ct::MouseButton::Right),
+                    MouseButton::None => None,
+                };
+                let kind = match me.kind {
+                    MouseKind::Down => ct::MouseEventKind::Down(button?),
+                    MouseKind::Up => ct::MouseEventKind::Up(button?),
+                    MouseKind::Drag => ct::MouseEventKind::Drag(button?),
+                    MouseKind::Move => ct::MouseEventKind::Moved,
+                    MouseKind::ScrollUp => ct::MouseEventKind::ScrollUp,
+                    MouseKind::ScrollDown => ct::MouseEventKind::ScrollDown,
+                    MouseKind::ScrollLeft => ct::MouseEventKind::ScrollLeft,
+                    MouseKind::ScrollRight => ct::MouseEventKind::ScrollRight,
+                };
+
+                ct::Event::Mouse(ct::MouseEvent {
+                    kind,
+                    column: me.column,
+                    row: me.row,
+                    modifiers: me.modifiers.into(),
+                })
+            }
+            Event::Window(WindowEvent::WindowGainedFocus) => ct::Event::FocusGained,
+            Event::Window(WindowEvent::WindowLostFocus) => ct::Event::FocusLost,
+            Event::Resize { w, h } => ct::Event::Resize(*w, *h),
+            Event::Paste(paste) => ct::Event::Paste(paste.text.clone()),
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod compat {
+    use super::*;
+
+    fn ct_key(code: ct::KeyCode, mods: ct::KeyModifiers) -> ct::Event {
+        ct::Event::Key(ct::KeyEvent::new(code, mods))
+    }
+
+    #[test]
+    fn keys() {
+        let event = Event::from(ct_key(ct::KeyCode::Char('a'), ct::KeyModifiers::CONTROL));
+        match &event {
+            Event::Key(ke) => {
+                assert_eq!(ke.char, Char::Char('a'));
+                assert_eq!(ke.modifiers, Modifiers::CONTROL);
+                assert_eq!(ke.kind, KeyKind::Press);
+            }
+            e => panic!("expected a key, got {:?}", e),
+        }
+        assert_eq!(
+            event.to_crossterm(),
+            Some(ct_key(ct::KeyCode::Char('a'), ct::KeyModifiers::CONTROL))
+        );
+
+        let mods = ct::KeyModifiers::SHIFT | ct::KeyModifiers::ALT;
+        match Event::from(ct_key(ct::KeyCode::F(5), mods)) {
+            Event::Key(ke) => {
+                assert_eq!(ke.char, Char::CC(CC::F5));
+                assert_eq!(ke.modifiers, Modifiers::SHIFT | Modifiers::ALT);
+            }
+            e => panic!("expected a key, got {:?}", e),
+        }
+        match Event::from(ct_key(ct::KeyCode::BackTab, ct::KeyModifiers::SHIFT)) {
+            Event::Key(ke) => {
+                assert_eq!(ke.char, Char::CC(CC::TAB));
+                assert_eq!(ke.modifiers, Modifiers::SHIFT);
+                assert_eq!(
+                    Event::Key(ke).to_crossterm(),
+                    Some(ct_key(ct::KeyCode::BackTab, ct::KeyModifiers::SHIFT))
+                );
+            }
+            e => panic!("expected a key, got {:?}", e),
+        }
+
+        let none = ct::KeyModifiers::NONE;
+        for code in [ct::KeyCode::F(13), ct::KeyCode::CapsLock, ct::KeyCode::Menu] {
+            assert!(matches!(
+                Event::from(ct_key(code, none)),
+                Event::Unsupported
+            ));
+        }
+    }
+
+    #[test]
+    fn mouse_and_window() {
+        let me = ct::MouseEvent {
+            kind: ct::MouseEventKind::Drag(ct::MouseButton::Right),
+            column: 3,
+            row: 7,
+            modifiers: ct::KeyModifiers::ALT,
+        };
+        let event = Event::from(ct::Event::Mouse(me));
+        match &event {
+            Event::Mouse(me) => {
+                assert_eq!([me.column, me.row], [3, 7]);
+                assert_eq!((me.kind, me.button), (MouseKind::Drag, MouseButton::Right));
+                assert_eq!(me.modifiers, Modifiers::ALT);
+            }
+            e => panic!("expected a mouse event, got {:?}", e),
+        }
+        assert_eq!(event.to_crossterm(), Some(ct::Event::Mouse(me)));
+
+        // an x10 release doesn't say which button went up
+        let release = Event::Mouse(MouseEvent {
+            kind: MouseKind::Up,
+            ..MouseEvent::default()
+        });
+        assert_eq!(release.to_crossterm(), None);
+
+        assert!(matches!(
+            Event::from(ct::Event::Resize(80, 24)),
+            Event::Resize { w: 80, h: 24 }
+        ));
+        assert!(matches!(
+            Event::from(ct::Event::FocusLost),
+            Event::Window(WindowEvent::WindowLostFocus)
+        ));
+        match Event::from(ct::Event::Paste("a\r\nb".into())) {
+            Event::Paste(paste) => assert_eq!(paste.text, "a\nb"),
+            e => panic!("expected a paste, got {:?}", e),
+        }
+        assert_eq!(Event::Tick.to_crossterm(), None);
+    }
+
+    #[cfg(feature = "components")]
+    #[test]
+    fn dispatched() {
+        use crate::components::Term;
+
+        let mut term = Term::new(0, 20, 4);
+        crate::components::Container::builder([0, 0])
+            .area(crate::space::Area::Values { w: 20, h: 4 })
+            .build(&mut term)
+            .unwrap();
+        crate::components::Text::input_builder([0, 0, 0])
+            .pos(crate::space::Pos::Start, crate::space::Pos::Start)
+            .area(crate::space::Area::Values { w: 10, h: 1 })
+            .build(&mut term)
+            .unwrap();
+        term.focus(&[0, 0, 0]).unwrap();
+        let none = ct::KeyModifiers::NONE;
+        for event in [
+            ct_key(ct::KeyCode::Char('h'), none),
+            ct_key(ct::KeyCode::Char('i'), none),
+        ] {
+            term.dispatch(&event.into());
+        }
+        assert_eq!(term.input_ref(&[0, 0, 0]).unwrap().value_string(), "hi");
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::{Event, Parser};
+
+// the first bytes of a recording, the trailing number is the format version
+const MAGIC: &[u8] = b"ragout-rec 1\n";
+
+/// writes the raw input chunks read from stdin to a file, see EventLoop::record
+///
+/// the file starts with a short header, then every chunk takes a record of
+/// the microseconds since the recording started (u64, little endian),
+/// the chunk's length (u32, little endian) and the chunk's bytes
+#[derive(Debug)]
+pub struct Recorder {
+    file: BufWriter<File>,
+    start: Instant,
+}
+
+impl Recorder {
+    /// creates, or truncates, the file at path and starts the recording clock
+    pub fn create(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(MAGIC)?;
+
+        Ok(Self {
+            file,
+            start: Instant::now(),
+        })
+    }
+
+    /// writes one chunk with the time it came in
+    /// every chunk is flushed right away, so that a crash doesn't lose the input that led to it
+    pub fn chunk(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        let micros = self.start.elapsed().as_micros().min(u64::MAX as u128) as u64;
+        self.file.write_all(&micros.to_le_bytes())?;
+        self.file.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        self.file.write_all(bytes)?;
+        self.file.flush()
+    }
+}
+
+/// the events of a recording, read back through the same parser the EventLoop uses
+/// made by Event::replay
+#[derive(Debug)]
+pub struct Replay {
+    chunks: VecDeque<(Duration, Vec<u8>)>,
+    parser: Parser,
+    timed: bool,
+    start: Option<Instant>,
+}
+
+impl Event {
+    /// reads the recording at path, the events come out as fast as they are asked for,
+    /// see Replay::timed to get them at the pace they were recorded at
+    ///
+    /// # Errors
+    ///
+    /// returns an error of kind InvalidData if the file is not a recording
+    pub fn replay(path: impl AsRef<Path>) -> std::io::Result<Replay> {
+        let mut bytes = vec![];
+        File::open(path)?.read_to_end(&mut bytes)?;
+
+        Replay::from_bytes(&bytes)
+    }
+}
+
+impl Replay {
+    fn from_bytes(bytes: &[u8]) -> std::io::Result<Self> {
+        let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidData, "not a recording");
+        let mut rest = bytes.strip_prefix(MAGIC).ok_or_else(invalid)?;
+
+        let mut chunks = VecDeque::new();
+        while !rest.is_empty() {
+            let (head, tail) = rest.split_at_checked(12).ok_or_else(invalid)?;
+            let micros = u64::from_le_bytes(head[..8].try_into().unwrap());
+            let len = u32::from_le_bytes(head[8..].try_into().unwrap()) as usize;
+            let (chunk, tail) = tail.split_at_checked(len).ok_or_else(invalid)?;
+            chunks.push_back((Duration::from_micros(micros), chunk.to_vec()));
+            rest = tail;
+        }
+
+        Ok(Self {
+            chunks,
+            parser: Parser::new(),
+            timed: false,
+            start: None,
+        })
+    }
+
+    /// makes the chunks come in at the pace they were recorded at, counted from the first event
+    pub fn timed(&mut self, timed: bool) {
+        self.timed = timed;
+    }
+
+    /// whether every event of the recording was handed out
+    pub fn is_done(&self) -> bool {
+        self.chunks.is_empty() && !self.parser.is_pending()
+    }
+
+    /// the next event of the recording, None once the recording is over
+    /// a sequence the recording ended in the middle of is flushed like a timed out one
+    pub fn next_event(&mut self) -> Option<Event> {
+        let start = *self.start.get_or_insert_with(Instant::now);
+        loop {
+            if let Some(event) = self.parser.next_event() {
+                return Some(event);
+            }
+
+            let Some((at, chunk)) = self.chunks.pop_front() else {
+                return match self.parser.is_pending() {
+                    true => self.parser.flush().or_else(|| self.next_event()),
+                    false => None,
+                };
+            };
+            if self.timed {
+                std::thread::sleep(at.saturating_sub(start.elapsed()));
+            }
+            self.parser.feed(&chunk);
+        }
+    }
+}
+
+impl Iterator for Replay {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        self.next_event()
+    }
+}
+
+#[cfg(test)]
+mod replay {
+    use super::*;
+    use crate::inputs::keyboard::{Char, CC};
+
+    fn keys(replay: Replay) -> Vec<Char> {
+        replay
+            .map(|event| match event {
+                Event::Key(ke) => ke.char,
+                e => panic!("expected a key, got {:?}", e),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = std::env::temp_dir().join(format!("ragout-rec-{}", std::process::id()));
+        let mut recorder = Recorder::create(&path).unwrap();
+        // a sequence split across two reads
+        recorder.chunk(b"a\x1b[").unwrap();
+        recorder.chunk(b"Ab").unwrap();
+        // a recording that ends on a lone Esc
+        recorder.chunk(b"\x1b").unwrap();
+        drop(recorder);
+
+        let replay = Event::replay(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            keys(replay),
+            [
+                Char::Char('a'),
+                Char::CC(CC::Up),
+                Char::Char('b'),
+                Char::CC(CC::ESC)
+            ]
+        );
+    }
+
+    #[test]
+    fn timed() {
+        let mut bytes = MAGIC.to_vec();
+        for (micros, chunk) in [(0u64, b"x"), (30_000, b"y")] {
+            bytes.extend_from_slice(&micros.to_le_bytes());
+            bytes.extend_from_slice(&1u32.to_le_bytes());
+            bytes.extend_from_slice(chunk);
+        }
+
+        let mut replay = Replay::from_bytes(&bytes).unwrap();
+        replay.timed(true);
+        let start = Instant::now();
+        assert_eq!(keys(replay), [Char::Char('x'), Char::Char('y')]);
+        assert!(start.elapsed() >= Duration::from_millis(30));
+
+        assert!(Replay::from_bytes(b"not a recording").is_err());
+        assert!(Replay::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+use crate::console::wake::Waker;
+
+/// how many user events an EventLoop holds on to at most, unless told otherwise
+pub const DEFAULT_USER_QUEUE: usize = 1024;
+
+/// an event sent by the app through an EventSender
+/// downcast it back to the type that was sent, e.g. event.downcast::<Response>()
+pub type UserEvent = Box<dyn Any + Send>;
+
+// the user events that were sent but not handed out yet, shared with the senders
+#[derive(Debug)]
+pub(crate) struct Queue {
+    events: Mutex<VecDeque<UserEvent>>,
+    capacity: AtomicUsize,
+    pub(crate) waker: Waker,
+}
+
+impl Queue {
+    pub(crate) fn new(capacity: usize) -> std::io::Result<Self> {
+        Ok(Self {
+            events: Mutex::new(VecDeque::new()),
+            capacity: AtomicUsize::new(capacity.max(1)),
+            waker: Waker::new()?,
+        })
+    }
+
+    pub(crate) fn capacity(&self, capacity: usize) {
+        self.capacity.store(capacity.max(1), Ordering::SeqCst);
+    }
+
+    fn push(&self, event: UserEvent) {
+        let mut events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        let capacity = self.capacity.load(Ordering::SeqCst);
+        while events.len() >= capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    pub(crate) fn pop(&self) -> Option<UserEvent> {
+        self.events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front()
+    }
+}
+
+/// sends user events to an EventLoop from any thread, see EventLoop::sender
+/// the events come out of the loop as Event::User, in the order they were sent,
+/// in between the input events
+///
+/// the loop holds on to a bounded number of user events, see EventLoop::user_queue,
+/// once that many are waiting the oldest one is dropped to make room for the new one,
+/// so sending never blocks, however far behind the loop is
+#[derive(Debug, Clone)]
+pub struct EventSender {
+    queue: Weak<Queue>,
+}
+
+impl EventSender {
+    pub(crate) fn new(queue: &Arc<Queue>) -> Self {
+        Self {
+            queue: Arc::downgrade(queue),
+        }
+    }
+
+    /// sends event to the loop, waking it up if it is waiting for input
+    ///
+    /// # Errors
+    ///
+    /// gives event back if the EventLoop is gone
+    pub fn send<T: Any + Send>(&self, event: T) -> Result<(), T> {
+        let Some(queue) = self.queue.upgrade() else {
+            return Err(event);
+        };
+        queue.push(Box::new(event));
+        queue.waker.wake();
+
+        Ok(())
+    }
+
+    /// whether the EventLoop this sender came from is still around
+    pub fn is_connected(&self) -> bool {
+        self.queue.strong_count() > 0
+    }
+}
+
+#[cfg(test)]
+mod send {
+    use super::*;
+
+    #[test]
+    fn order_and_bound() {
+        let queue = Arc::new(Queue::new(3).unwrap());
+        let sender = EventSender::new(&queue);
+        for i in 0..5u32 {
+            sender.send(i).unwrap();
+        }
+
+        // the oldest events made room for the newer ones
+        let events = std::iter::from_fn(|| queue.pop())
+            .map(|e| *e.downcast::<u32>().unwrap())
+            .collect::<Vec<u32>>();
+        assert_eq!(events, [2, 3, 4]);
+    }
+
+    #[test]
+    fn disconnected() {
+        let queue = Arc::new(Queue::new(DEFAULT_USER_QUEUE).unwrap());
+        let sender = EventSender::new(&queue);
+        let clone = sender.clone();
+        assert!(clone.is_connected());
+        drop(queue);
+        assert!(!sender.is_connected());
+        assert_eq!(clone.send("late"), Err("late"));
+    }
+}
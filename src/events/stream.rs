@@ -0,0 +1,104 @@
+use std::future::Future;
+use std::os::fd::{AsRawFd, RawFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::Stream;
+use tokio::io::unix::AsyncFd;
+use tokio::time::Sleep;
+
+use super::{Event, Parser, DEFAULT_ESCAPE_TIMEOUT};
+
+// stdin, without owning fd 0
+struct Stdin;
+
+impl AsRawFd for Stdin {
+    fn as_raw_fd(&self) -> RawFd {
+        0
+    }
+}
+
+/// the async counterpart of EventLoop, see event_stream
+pub struct EventStream {
+    fd: AsyncFd<Stdin>,
+    parser: Parser,
+    // runs while the parser waits for the rest of a sequence
+    sequence: Option<Pin<Box<Sleep>>>,
+}
+
+/// makes a stream of the events read from stdin, the same events EventLoop hands out
+/// minus the Ticks, tokio's own timers can do that
+/// has to be called inside a tokio runtime with io and time enabled
+///
+/// the stream only holds on to bytes between polls, never in the middle of one,
+/// so dropping a pending next() loses nothing
+/// and bytes that are still in the stream when it is dropped, like half an escape sequence,
+/// are kept for the next stream or EventLoop
+pub fn event_stream() -> std::io::Result<EventStream> {
+    Ok(EventStream {
+        fd: AsyncFd::new(Stdin)?,
+        parser: Parser::new(),
+        sequence: None,
+    })
+}
+
+impl Stream for EventStream {
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        let this = self.get_mut();
+        let mut buf = [0u8; 1024];
+
+        loop {
+            if let Some(event) = this.parser.next_event() {
+                this.sequence = None;
+                return Poll::Ready(Some(event));
+            }
+
+            let pending = crate::inputs::take_pending();
+            if !pending.is_empty() {
+                this.parser.feed(&pending);
+                continue;
+            }
+
+            match this.fd.poll_read_ready(cx) {
+                Poll::Ready(Ok(mut guard)) => {
+                    // stdin stays blocking, so it is only read once poll says there is something
+                    match crate::console::query::read_timeout(&mut buf, Duration::ZERO) {
+                        Ok(0) => guard.clear_ready(),
+                        Ok(n) => {
+                            this.parser.feed(&buf[..n]);
+                            this.sequence = None;
+                        }
+                        Err(_) => return Poll::Ready(None),
+                    }
+                    continue;
+                }
+                Poll::Ready(Err(_)) => return Poll::Ready(None),
+                Poll::Pending => (),
+            }
+
+            if !this.parser.is_pending() {
+                return Poll::Pending;
+            }
+
+            let sleep = this
+                .sequence
+                .get_or_insert_with(|| Box::pin(tokio::time::sleep(DEFAULT_ESCAPE_TIMEOUT)));
+            if sleep.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+            this.sequence = None;
+            if let Some(event) = this.parser.flush() {
+                return Poll::Ready(Some(event));
+            }
+        }
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        crate::inputs::keep_pending(&self.parser.bytes);
+    }
+}
@@ -1,26 +1,67 @@
+/// the Action trait, what bound keys do
+#[cfg(feature = "components")]
+pub mod actions;
+/// multi key bindings, such as 'gg' or 'dd'
+pub mod chords;
 /// keyboard raw input listening and decoding into human readable keyboard input events
 pub mod keyboard;
+/// key bindings, the rebindable keymap of input actions
+pub mod keymap;
+/// the history of the submitted input values
+pub mod history;
+/// modal editing, the opt-in vim mode of inputs
+#[cfg(feature = "components")]
+pub mod vim;
 /// mouse/touchpad raw input listening and decoding into human readable keyboard input events
 pub mod mouse;
 /// window user input events, such as a resize or a focus change
 pub mod window;
 
 use keyboard::{decode_ki, decode_ki_kai, Char, KbdEvent, PasteEvent};
-use mouse::{decode_mi, MouseEvent};
-use window::WindowEvent;
+use mouse::{decode_mi, decode_sgr, is_sgr, MouseEvent};
+use window::{decode_focus, WindowEvent};
 
 use std::io::BufRead;
 use std::io::StdinLock;
+use std::sync::Mutex;
 use std::time::SystemTime;
 
-use crate::components::Term;
 use crate::console::winsize::winsize;
 
+// input bytes that were read while looking for something else, e.g. a terminal query reply
+// they are handed out by the next read call before anything new is read
+static PENDING: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+// keeps bytes for the next read call
+pub(crate) fn keep_pending(bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+
+    PENDING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .extend_from_slice(bytes);
+}
+
+// hands out the kept bytes
+pub(crate) fn take_pending() -> Vec<u8> {
+    std::mem::take(&mut *PENDING.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
 /// reads the keyboard input event bytes
 pub fn read<'a>(reader: &'a mut StdinLock, buffer: &'a mut Vec<u8>) -> &'a mut Vec<u8> {
     // TODO: non blocking reads
     buffer.clear();
 
+    let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    if !pending.is_empty() {
+        buffer.append(&mut pending);
+
+        return buffer;
+    }
+    drop(pending);
+
     let buf = reader.fill_buf().unwrap();
     buffer.extend_from_slice(buf);
 
@@ -30,6 +71,24 @@ pub fn read<'a>(reader: &'a mut StdinLock, buffer: &'a mut Vec<u8>) -> &'a mut V
     buffer
 }
 
+/// reads the next keyboard input event
+/// blocks until some input is available
+/// returns None if the read bytes are not a single keyboard input event
+#[cfg(unix)]
+pub fn kbd_event(reader: &mut StdinLock) -> Option<KbdEvent> {
+    let mut buffer = vec![];
+
+    decode_ki(read(reader, &mut buffer))
+}
+
+/// reads the next keyboard input event
+/// blocks until some input is available
+/// key presses are read from the console input records instead of the reader
+#[cfg(windows)]
+pub fn kbd_event(reader: &mut StdinLock) -> Option<KbdEvent> {
+    keyboard::read_kbd_event().ok()
+}
+
 /// resolves the read input bytes to an InputEvent struct instance
 pub fn event(bytes: &[u8], ws: &mut winsize) -> InputEvent {
     // FIXME: this only gets triggered on this blocking event fn
@@ -39,6 +98,21 @@ pub fn event(bytes: &[u8], ws: &mut winsize) -> InputEvent {
             time: SystemTime::now(),
             event: Interaction::WindowEvent(WindowEvent::WindowResized),
         };
+    } else if let Some(focus) = decode_focus(bytes) {
+        // checked before the keyboard since the escape decoder would take it for a broken arrow key
+        return InputEvent {
+            time: SystemTime::now(),
+            event: Interaction::WindowEvent(focus),
+        };
+    } else if let Some(mouse) = is_sgr(bytes)
+        .then(|| decode_sgr(bytes).into_iter().next())
+        .flatten()
+    {
+        // sgr mouse, the sequence is decoded once
+        return InputEvent {
+            time: SystemTime::now(),
+            event: Interaction::MouseEvent(mouse),
+        };
     } else if bytes.len() % 6 == 0 && bytes[..3] == [27, 91, 77] {
         // mouse
         return InputEvent {
@@ -59,8 +133,8 @@ pub fn event(bytes: &[u8], ws: &mut winsize) -> InputEvent {
     } else {
         // paste
         return InputEvent {
-            event: Interaction::PasteEvent(PasteEvent(
-                decode_ki_kai(bytes.to_vec())
+            event: Interaction::PasteEvent(PasteEvent {
+                text: decode_ki_kai(bytes.to_vec())
                     .into_iter()
                     .filter(|r| r.is_ok())
                     .map(|r| r.unwrap())
@@ -72,7 +146,8 @@ pub fn event(bytes: &[u8], ws: &mut winsize) -> InputEvent {
                         ch
                     })
                     .collect::<String>(),
-            )),
+                truncated: false,
+            }),
             time: SystemTime::now(),
         };
     }
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::keyboard::{Char, ALT, CONTROL, SUPER};
+use super::keymap::InputAction;
+use crate::components::{Term, Text};
+use crate::events::Event;
+
+/// what an action did, so that the dispatch loop knows what is left to do
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ActionResult {
+    /// some component changed and needs to be rendered again
+    pub redraw: bool,
+    /// the focused input's value was submitted
+    pub submit: bool,
+}
+
+impl ActionResult {
+    /// the result of an action that changed what is displayed
+    pub fn redraw() -> Self {
+        Self {
+            redraw: true,
+            submit: false,
+        }
+    }
+
+    /// the result of an action that submitted the focused input's value
+    pub fn submit() -> Self {
+        Self {
+            redraw: false,
+            submit: true,
+        }
+    }
+}
+
+/// something a key can be bound to
+/// the built-in editing actions are the InputAction variants, apps register their own
+/// under a name, see Actions, and bind keys to InputAction::Named with that name
+///
+/// closures taking the term and the event are actions too
+pub trait Action {
+    /// does what the action does to term, event is the event the action was bound to
+    fn apply(&self, term: &mut Term, event: &Event) -> ActionResult;
+}
+
+impl<F> Action for F
+where
+    F: Fn(&mut Term, &Event) -> ActionResult,
+{
+    fn apply(&self, term: &mut Term, event: &Event) -> ActionResult {
+        self(term, event)
+    }
+}
+
+impl Action for InputAction {
+    /// the built-in editing of the focused input
+    /// Submit records the value in the term's history and reports the submit,
+    /// the Named actions do nothing here, Term::apply runs the action registered under their name
+    fn apply(&self, term: &mut Term, _: &Event) -> ActionResult {
+        let edited = match self {
+            Self::Backspace => term.edit(Text::backspace),
+            Self::Delete => term.edit(Text::delete),
+            Self::Left => term.edit(Text::arrow_left),
+            Self::Right => term.edit(Text::arrow_right),
+            Self::Up => term.edit(Text::move_up),
+            Self::Down => term.edit(Text::move_down),
+            Self::Home => term.edit(Text::move_home),
+            Self::End => term.edit(Text::move_end),
+            Self::Newline => term.edit(Text::newline),
+            Self::FocusNext => {
+                term.focus_next();
+                false
+            }
+            Self::FocusPrev => {
+                term.focus_prev();
+                false
+            }
+            Self::HistoryPrev => term.history_prev(),
+            Self::HistoryNext => term.history_next(),
+            Self::HistorySearch => term.history_search(),
+            Self::Submit => {
+                term.record_submit();
+                return ActionResult::submit();
+            }
+            Self::NoOp | Self::Named(_) => false,
+        };
+
+        ActionResult {
+            redraw: edited,
+            submit: false,
+        }
+    }
+}
+
+/// types the char of a key event into the focused input
+/// what a char that is bound to nothing does
+/// chars that come with ctrl, alt or super are not typed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InsertChar;
+
+impl Action for InsertChar {
+    fn apply(&self, term: &mut Term, event: &Event) -> ActionResult {
+        let Event::Key(ke) = event else {
+            return ActionResult::default();
+        };
+        let Char::Char(c) = ke.char else {
+            return ActionResult::default();
+        };
+        if ke.modifiers.0 & (CONTROL | ALT | SUPER) != 0 {
+            return ActionResult::default();
+        }
+
+        let edited = term.edit(|text| {
+            text.insert(c);
+            true
+        });
+
+        ActionResult {
+            redraw: edited,
+            submit: false,
+        }
+    }
+}
+
+/// the actions an app registered, by name
+/// a clone shares the registered actions, 2 Actions are equal if they have the same names
+#[derive(Default, Clone)]
+pub struct Actions {
+    actions: HashMap<String, Rc<dyn Action>>,
+}
+
+impl PartialEq for Actions {
+    fn eq(&self, other: &Self) -> bool {
+        self.actions.len() == other.actions.len()
+            && self.actions.keys().all(|name| other.actions.contains_key(name))
+    }
+}
+
+impl std::fmt::Debug for Actions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.actions.keys()).finish()
+    }
+}
+
+impl Actions {
+    /// makes a new Actions with nothing registered
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// registers action under name, replacing the action that was registered under it
+    pub fn register(&mut self, name: &str, action: impl Action + 'static) {
+        self.actions.insert(name.to_string(), Rc::new(action));
+    }
+
+    /// removes the action registered under name
+    pub fn unregister(&mut self, name: &str) -> Option<Rc<dyn Action>> {
+        self.actions.remove(name)
+    }
+
+    /// whether an action is registered under name
+    pub fn contains(&self, name: &str) -> bool {
+        self.actions.contains_key(name)
+    }
+
+    // takes the action out while it runs, since it needs the term that holds it
+    // registering under the same name in the meantime wins over putting it back
+    pub(crate) fn take(&mut self, name: &str) -> Option<Rc<dyn Action>> {
+        self.actions.remove(name)
+    }
+
+    pub(crate) fn put_back(&mut self, name: &str, action: Rc<dyn Action>) {
+        self.actions.entry(name.to_string()).or_insert(action);
+    }
+}
@@ -0,0 +1,285 @@
+use std::time::{Duration, Instant};
+
+use super::keyboard::KbdEvent;
+
+/// how long Chords waits for the next key of a chord, unless told otherwise
+pub const DEFAULT_CHORD_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// where a chord binding applies
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChordScope {
+    /// no matter what is focused
+    Global,
+    /// only while the text with this id is focused
+    /// takes precedence over a global binding of the same keys
+    Component([u8; 3]),
+}
+
+impl ChordScope {
+    fn applies(&self, focused: Option<[u8; 3]>) -> bool {
+        match self {
+            Self::Global => true,
+            Self::Component(id) => focused == Some(*id),
+        }
+    }
+}
+
+/// what Chords makes out of the key presses it was given, in the order it happened
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChordOutput<A> {
+    /// a key press that is not part of any chord, to be handled as usual
+    Key(KbdEvent),
+    /// a chord was completed
+    Action(A),
+}
+
+/// recognizes key sequences, like 'gg' or 'dd', and maps them to actions
+/// keys that could still become a chord are held back until the chord is complete,
+/// or until it is clear that it won't be, at which point they are handed back in order
+#[derive(Debug)]
+pub struct Chords<A> {
+    bindings: Vec<(ChordScope, Vec<KbdEvent>, A)>,
+    pending: Vec<KbdEvent>,
+    last: Option<Instant>,
+    timeout: Duration,
+}
+
+impl<A> Default for Chords<A> {
+    fn default() -> Self {
+        Self {
+            bindings: vec![],
+            pending: vec![],
+            last: None,
+            timeout: DEFAULT_CHORD_TIMEOUT,
+        }
+    }
+}
+
+impl<A: Clone> Chords<A> {
+    /// makes a new Chords with no bindings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sets how long to wait for the next key of a chord
+    pub fn timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// binds the keys sequence to action in scope
+    /// an earlier binding of the same keys in the same scope is replaced
+    pub fn bind(&mut self, scope: ChordScope, keys: &[KbdEvent], action: A) {
+        assert!(!keys.is_empty(), "a chord needs at least one key");
+
+        self.unbind(scope, keys);
+        self.bindings.push((scope, keys.to_vec(), action));
+    }
+
+    /// removes the binding of the keys sequence in scope, returns its action
+    pub fn unbind(&mut self, scope: ChordScope, keys: &[KbdEvent]) -> Option<A> {
+        let idx = self
+            .bindings
+            .iter()
+            .position(|(s, k, _)| *s == scope && k == keys)?;
+
+        Some(self.bindings.remove(idx).2)
+    }
+
+    /// the keys that are being held back, waiting for the rest of a chord
+    pub fn pending(&self) -> &[KbdEvent] {
+        &self.pending
+    }
+
+    /// the bindings that could still be completed from the pending keys,
+    /// e.g. for a which-key style hint
+    pub fn pending_bindings(&self, focused: Option<[u8; 3]>) -> Vec<(&[KbdEvent], &A)> {
+        if self.pending.is_empty() {
+            return vec![];
+        }
+
+        self.bindings
+            .iter()
+            .filter(|(s, k, _)| {
+                s.applies(focused) && k.len() > self.pending.len() && k.starts_with(&self.pending)
+            })
+            .map(|(_, k, a)| (k.as_slice(), a))
+            .collect()
+    }
+
+    /// feeds the next key press, focused is the id of the focused text, if any
+    /// an empty output means the key was held back
+    pub fn key(&mut self, event: KbdEvent, focused: Option<[u8; 3]>) -> Vec<ChordOutput<A>> {
+        let mut out = self.expire(focused);
+        self.pending.push(event);
+
+        loop {
+            if self.continues(focused) {
+                self.last = Some(Instant::now());
+                return out;
+            }
+            self.last = None;
+            if let Some(action) = self.exact(&self.pending, focused) {
+                self.pending.clear();
+                out.push(ChordOutput::Action(action));
+                return out;
+            }
+            if self.pending.len() == 1 {
+                out.push(ChordOutput::Key(self.pending.remove(0)));
+                return out;
+            }
+
+            // no chord goes on with the last key,
+            // the keys before it are settled alone then the last key starts over
+            let last = self.pending.pop().unwrap();
+            self.settle(focused, &mut out);
+            self.pending.push(last);
+        }
+    }
+
+    /// settles the held back keys once the chord timeout went by since the last key
+    /// should be called when no key comes in for a while, e.g. on every Tick
+    pub fn expire(&mut self, focused: Option<[u8; 3]>) -> Vec<ChordOutput<A>> {
+        let mut out = vec![];
+        if self.last.is_some_and(|last| last.elapsed() >= self.timeout) {
+            self.settle(focused, &mut out);
+        }
+
+        out
+    }
+
+    // whether some binding is longer than the pending keys and starts with them
+    fn continues(&self, focused: Option<[u8; 3]>) -> bool {
+        self.bindings.iter().any(|(s, k, _)| {
+            s.applies(focused) && k.len() > self.pending.len() && k.starts_with(&self.pending)
+        })
+    }
+
+    // the action bound to exactly these keys, component bindings first
+    fn exact(&self, keys: &[KbdEvent], focused: Option<[u8; 3]>) -> Option<A> {
+        let bound = |scope: fn(&ChordScope) -> bool| {
+            self.bindings
+                .iter()
+                .find(|(s, k, _)| scope(s) && s.applies(focused) && k == keys)
+                .map(|(_, _, a)| a.clone())
+        };
+
+        bound(|s| matches!(s, ChordScope::Component(_)))
+            .or_else(|| bound(|s| *s == ChordScope::Global))
+    }
+
+    // stops waiting on the pending keys
+    // they fire their action if they are a chord of their own, otherwise they are handed back
+    fn settle(&mut self, focused: Option<[u8; 3]>, out: &mut Vec<ChordOutput<A>>) {
+        self.last = None;
+        let pending = std::mem::take(&mut self.pending);
+        match self.exact(&pending, focused) {
+            Some(action) => out.push(ChordOutput::Action(action)),
+            None => out.extend(pending.into_iter().map(ChordOutput::Key)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod chord {
+    use super::*;
+
+    fn keys(s: &str) -> Vec<KbdEvent> {
+        s.chars().map(KbdEvent::from).collect()
+    }
+
+    fn chords() -> Chords<&'static str> {
+        let mut chords = Chords::new();
+        chords.bind(ChordScope::Global, &keys("gg"), "top");
+        chords.bind(ChordScope::Global, &keys("dd"), "delete line");
+        chords.bind(ChordScope::Global, &keys("dw"), "delete word");
+        chords.bind(ChordScope::Component([0, 0, 0]), &keys("dd"), "clear input");
+
+        chords
+    }
+
+    #[test]
+    fn full_match() {
+        let mut chords = chords();
+        assert!(chords.key('g'.into(), None).is_empty());
+        assert_eq!(chords.pending(), keys("g"));
+        assert_eq!(
+            chords.key('g'.into(), None),
+            vec![ChordOutput::Action("top")]
+        );
+        assert!(chords.pending().is_empty());
+    }
+
+    #[test]
+    fn mismatch_replays() {
+        let mut chords = chords();
+        assert!(chords.key('g'.into(), None).is_empty());
+        assert_eq!(
+            chords.key('x'.into(), None),
+            vec![ChordOutput::Key('g'.into()), ChordOutput::Key('x'.into())]
+        );
+
+        // the last key can start a chord of its own
+        chords.key('g'.into(), None);
+        assert_eq!(
+            chords.key('d'.into(), None),
+            vec![ChordOutput::Key('g'.into())]
+        );
+        assert_eq!(chords.pending(), keys("d"));
+        assert_eq!(
+            chords.key('w'.into(), None),
+            vec![ChordOutput::Action("delete word")]
+        );
+
+        // keys that start no chord go right through
+        assert_eq!(
+            chords.key('q'.into(), None),
+            vec![ChordOutput::Key('q'.into())]
+        );
+    }
+
+    #[test]
+    fn timeout() {
+        let mut chords = chords();
+        chords.timeout(Duration::ZERO);
+        chords.key('d'.into(), None);
+        assert_eq!(chords.expire(None), vec![ChordOutput::Key('d'.into())]);
+        assert!(chords.pending().is_empty());
+
+        // a held back key that timed out comes before the next key
+        chords.key('g'.into(), None);
+        assert_eq!(
+            chords.key('q'.into(), None),
+            vec![ChordOutput::Key('g'.into()), ChordOutput::Key('q'.into())]
+        );
+    }
+
+    #[test]
+    fn scopes() {
+        let mut chords = chords();
+        chords.key('d'.into(), Some([0, 0, 0]));
+        assert_eq!(
+            chords.key('d'.into(), Some([0, 0, 0])),
+            vec![ChordOutput::Action("clear input")]
+        );
+        chords.key('d'.into(), Some([0, 0, 2]));
+        assert_eq!(
+            chords.key('d'.into(), Some([0, 0, 2])),
+            vec![ChordOutput::Action("delete line")]
+        );
+    }
+
+    #[test]
+    fn hints() {
+        let mut chords = chords();
+        assert!(chords.pending_bindings(None).is_empty());
+        chords.key('d'.into(), None);
+        let mut hints = chords
+            .pending_bindings(None)
+            .into_iter()
+            .map(|(_, a)| *a)
+            .collect::<Vec<&str>>();
+        hints.sort();
+        assert_eq!(hints, ["delete line", "delete word"]);
+        assert_eq!(chords.pending_bindings(Some([0, 0, 0])).len(), 3);
+    }
+}
@@ -0,0 +1,367 @@
+use std::sync::Arc;
+
+/// which repeated entries a History leaves out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Dedup {
+    /// every entry is kept
+    #[default]
+    Off,
+    /// an entry that is the same as the newest one is not recorded
+    Consecutive,
+    /// an entry that was recorded before is moved up to be the newest one
+    All,
+}
+
+/// which history an input goes through, see Term::history
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HistoryKey {
+    /// the input's own history
+    Input([u8; 3]),
+    /// the history of the inputs with this "history-group" property
+    Group(String),
+}
+
+// what History::ignore was given
+#[derive(Clone)]
+struct Ignore(Arc<dyn Fn(&str) -> bool>);
+
+impl std::fmt::Debug for Ignore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Ignore")
+    }
+}
+
+// the rules are equal if they are the same closure
+impl PartialEq for Ignore {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// the values submitted in an input, oldest first
+/// Term::apply records every Submit in it, HistoryPrev and HistoryNext go through it
+/// and HistorySearch looks through it, newest first
+///
+/// the entries go through the ignore rule, the dedup rule then the max_entries rule
+/// as they are recorded
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct History {
+    entries: Vec<String>,
+    search: Option<Search>,
+    max_entries: Option<usize>,
+    dedup: Dedup,
+    ignore: Option<Ignore>,
+}
+
+/// a reverse incremental search through a History, see History::search_prompt
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Search {
+    pub(crate) query: String,
+    // the index of the current match in the history's entries
+    pub(crate) at: Option<usize>,
+    // whether the query matches nothing older than the current match
+    pub(crate) failed: bool,
+    // what the input held when the search started, given back by Esc
+    pub(crate) typed: Vec<Option<char>>,
+    pub(crate) cursor: [u16; 2],
+}
+
+impl History {
+    /// makes a new empty History
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// keeps at most max entries, the oldest ones are dropped to make room for newer ones
+    /// entries past max are dropped right away
+    pub fn max_entries(&mut self, max: usize) {
+        self.max_entries = Some(max);
+        self.evict();
+    }
+
+    /// sets which repeated entries are left out
+    pub fn dedup(&mut self, dedup: Dedup) {
+        self.dedup = dedup;
+    }
+
+    /// leaves out the entries ignore returns true for, e.g. the ones starting with a space
+    pub fn ignore(&mut self, ignore: impl Fn(&str) -> bool + 'static) {
+        self.ignore = Some(Ignore(Arc::new(ignore)));
+    }
+
+    /// records a submitted value, empty values are not recorded
+    /// returns whether the value was recorded, see History's rules
+    pub fn push(&mut self, entry: String) -> bool {
+        if entry.is_empty() || self.ignore.as_ref().is_some_and(|ignore| ignore.0(&entry)) {
+            return false;
+        }
+        match self.dedup {
+            Dedup::Off => (),
+            Dedup::Consecutive if self.entries.last() == Some(&entry) => return false,
+            Dedup::Consecutive => (),
+            Dedup::All => {
+                if let Some(idx) = self.entries.iter().position(|e| *e == entry) {
+                    self.remove(idx);
+                }
+            }
+        }
+        self.entries.push(entry);
+        self.evict();
+
+        true
+    }
+
+    // removes the entry at idx, the search match keeps pointing at the same entry
+    fn remove(&mut self, idx: usize) {
+        self.entries.remove(idx);
+        if let Some(search) = &mut self.search {
+            search.at = match search.at {
+                Some(at) if at == idx => None,
+                Some(at) if at > idx => Some(at - 1),
+                at => at,
+            };
+        }
+    }
+
+    // drops the oldest entries past max_entries
+    fn evict(&mut self) {
+        let Some(max) = self.max_entries else {
+            return;
+        };
+        while self.entries.len() > max {
+            self.remove(0);
+        }
+    }
+
+    /// records every entry of entries, oldest first, like as many submits would
+    /// e.g. to load the entries an app saved in an earlier session
+    pub fn extend(&mut self, entries: impl IntoIterator<Item = String>) {
+        for entry in entries {
+            self.push(entry);
+        }
+    }
+
+    /// the entries, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(String::as_str)
+    }
+
+    /// removes every entry, the rules are kept
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        if let Some(search) = &mut self.search {
+            search.at = None;
+        }
+    }
+
+    /// how many entries there are
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// whether there are no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// the entry back entries before the newest one, get(0) is the newest entry
+    pub fn get(&self, back: usize) -> Option<&str> {
+        let idx = self.entries.len().checked_sub(back + 1)?;
+
+        self.entries.get(idx).map(String::as_str)
+    }
+
+    /// whether a reverse search is going on
+    pub fn is_searching(&self) -> bool {
+        self.search.is_some()
+    }
+
+    /// what the searching input shows, e.g. "(reverse-i-search)`git': git push"
+    /// None if no search is going on
+    pub fn search_prompt(&self) -> Option<String> {
+        let search = self.search.as_ref()?;
+        let found = search.at.map_or("", |at| self.entries[at].as_str());
+
+        Some(format!(
+            "({}reverse-i-search)`{}': {}",
+            if search.failed { "failed " } else { "" },
+            search.query,
+            found
+        ))
+    }
+
+    /// the entry the search is at
+    pub fn search_match(&self) -> Option<&str> {
+        let at = self.search.as_ref()?.at?;
+
+        Some(&self.entries[at])
+    }
+
+    // the newest entry before the before index that holds query
+    fn find(&self, query: &str, before: usize) -> Option<usize> {
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+
+    pub(crate) fn start_search(&mut self, typed: Vec<Option<char>>, cursor: [u16; 2]) {
+        self.search = Some(Search {
+            query: String::new(),
+            at: None,
+            failed: false,
+            typed,
+            cursor,
+        });
+    }
+
+    // looks for the query again, from the before index down
+    fn research(&mut self, before: usize) {
+        let Some(query) = self.search.as_ref().map(|s| s.query.clone()) else {
+            return;
+        };
+        let found = self.find(&query, before);
+        let search = self.search.as_mut().unwrap();
+        search.failed = found.is_none();
+        if found.is_some() {
+            search.at = found;
+        }
+    }
+
+    // a char was typed during the search, the current match is kept if it still matches
+    pub(crate) fn search_push(&mut self, c: char) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        search.query.push(c);
+        let before = search.at.map_or(self.entries.len(), |at| at + 1);
+        self.research(before);
+    }
+
+    // the last char of the query was removed, the search starts over from the newest entry
+    pub(crate) fn search_pop(&mut self) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        search.query.pop();
+        search.at = None;
+        self.research(self.entries.len());
+    }
+
+    // Ctrl-R again, goes to the next older match
+    pub(crate) fn search_older(&mut self) {
+        let Some(search) = &self.search else {
+            return;
+        };
+        let before = search.at.unwrap_or(self.entries.len());
+        self.research(before);
+    }
+
+    pub(crate) fn end_search(&mut self) -> Option<Search> {
+        self.search.take()
+    }
+}
+
+#[cfg(test)]
+mod entries {
+    use super::*;
+
+    fn history() -> History {
+        let mut history = History::new();
+        for entry in ["git status", "cargo test", "git push", "ls"] {
+            history.push(entry.to_string());
+        }
+
+        history
+    }
+
+    #[test]
+    fn incremental() {
+        let mut history = history();
+        history.start_search(vec![], [0, 0]);
+        history.search_push('g');
+        assert_eq!(history.search_match(), Some("git push"));
+        history.search_push('i');
+        history.search_push('t');
+        assert_eq!(
+            history.search_prompt().unwrap(),
+            "(reverse-i-search)`git': git push"
+        );
+
+        history.search_older();
+        assert_eq!(history.search_match(), Some("git status"));
+        // nothing older matches, the match stays where it was
+        history.search_older();
+        assert_eq!(
+            history.search_prompt().unwrap(),
+            "(failed reverse-i-search)`git': git status"
+        );
+
+        history.search_pop();
+        assert_eq!(history.search_match(), Some("git push"));
+        assert!(history.end_search().is_some());
+        assert!(!history.is_searching());
+    }
+
+    #[test]
+    fn get() {
+        let history = history();
+        assert_eq!(history.get(0), Some("ls"));
+        assert_eq!(history.get(3), Some("git status"));
+        assert_eq!(history.get(4), None);
+    }
+
+    #[test]
+    fn rules() {
+        let mut history = History::new();
+        history.dedup(Dedup::Consecutive);
+        history.ignore(|entry| entry.starts_with(' ') || entry.len() < 2);
+        for entry in ["ls", "ls", " secret", "x", "cd", "ls"] {
+            history.push(entry.to_string());
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.get(1), Some("cd"));
+
+        history.dedup(Dedup::All);
+        assert!(history.push("cd".to_string()));
+        assert_eq!([history.get(0), history.get(1)], [Some("cd"), Some("ls")]);
+        assert_eq!(history.len(), 3);
+    }
+
+    #[test]
+    fn eviction() {
+        let mut history = history();
+        history.start_search(vec![], [0, 0]);
+        history.search_push('c');
+        assert_eq!(history.search_match(), Some("cargo test"));
+
+        history.max_entries(3);
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.get(2), Some("cargo test"));
+        // the match is the same entry after the oldest one went away
+        assert_eq!(history.search_match(), Some("cargo test"));
+        history.search_older();
+        assert_eq!(history.search_match(), Some("cargo test"));
+
+        history.end_search();
+        history.push("pwd".to_string());
+        assert_eq!(history.len(), 3);
+        assert_eq!(
+            [history.get(0), history.get(2)],
+            [Some("pwd"), Some("git push")]
+        );
+    }
+
+    #[test]
+    fn preload() {
+        let mut history = History::new();
+        history.max_entries(3);
+        history.dedup(Dedup::Consecutive);
+        history.extend(["a", "b", "b", "c", "d"].map(String::from));
+        assert_eq!(history.iter().collect::<Vec<&str>>(), ["b", "c", "d"]);
+
+        history.clear();
+        assert!(history.is_empty());
+        history.extend(["e".to_string()]);
+        assert_eq!(history.get(0), Some("e"));
+    }
+}
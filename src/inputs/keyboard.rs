@@ -7,7 +7,7 @@ use std::io::Write;
 
 /// char enum
 /// could be either a control character (cc) or a normal utf-8 char
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Char {
     /// control character such as \r (carriage return)
     CC(CC),
@@ -16,8 +16,14 @@ pub enum Char {
 }
 
 /// paste event for when ctrl+v is hit
-#[derive(Debug)]
-pub struct PasteEvent(pub String);
+/// the whole pasted text in one event, newlines included
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasteEvent {
+    /// the pasted text
+    pub text: String,
+    /// whether the paste went over the paste cap and text only holds its start
+    pub truncated: bool,
+}
 
 impl Default for Char {
     fn default() -> Self {
@@ -79,7 +85,8 @@ impl Char {
 
     fn from_cc_extra(byte: u8) -> Self {
         Char::CC(match byte {
-            51 => CC::Insert,
+            50 => CC::Insert,
+            51 => CC::Delete,
             52 => CC::End,
             54 => CC::PageDown,
             53 => CC::PageUp,
@@ -132,7 +139,7 @@ impl Char {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 /// control characters that represent well established escape sequence or should not be printed as
 /// normal chars
 pub enum CC {
@@ -155,7 +162,9 @@ pub enum CC {
     /// arrow left key
     Left,
     /// insert key
-    Insert, // Del
+    Insert,
+    /// delete key
+    Delete,
     /// home key
     Home,
     /// end key
@@ -197,7 +206,8 @@ pub enum CC {
 
 /// the keyboard event modifiers struct
 /// ot can take the values of: NONE, SUPER, CTRL, ALT, SHIFT, or any combination of the last 4 mods
-#[derive(Default, Clone, PartialEq)]
+/// the flags combine with |, e.g. Modifiers::CONTROL | Modifiers::SHIFT
+#[derive(Default, Clone, Copy, PartialEq)]
 pub struct Modifiers(pub u8);
 
 impl std::fmt::Display for Modifiers {
@@ -212,13 +222,45 @@ impl std::fmt::Debug for Modifiers {
     }
 }
 
-const SUPER: u8 = 0x01;
-const CONTROL: u8 = 0x02;
-const ALT: u8 = 0x04;
-const SHIFT: u8 = 0x08;
-const NONE: u8 = 0x0;
+pub(crate) const SUPER: u8 = 0x01;
+pub(crate) const CONTROL: u8 = 0x02;
+pub(crate) const ALT: u8 = 0x04;
+pub(crate) const SHIFT: u8 = 0x08;
+pub(crate) const NONE: u8 = 0x0;
 
 impl Modifiers {
+    /// no modifier
+    pub const NONE: Self = Self(NONE);
+    /// the super (windows, command) key
+    pub const SUPER: Self = Self(SUPER);
+    /// the ctrl key
+    pub const CONTROL: Self = Self(CONTROL);
+    /// the alt (meta, option) key
+    pub const ALT: Self = Self(ALT);
+    /// the shift key
+    pub const SHIFT: Self = Self(SHIFT);
+
+    /// whether all the flags of other are set in self
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// whether no flag is set
+    pub fn is_empty(&self) -> bool {
+        self.0 == NONE
+    }
+
+    // the xterm modifier parameter, found in sequences like '\x1b[1;5A', is 1 + the flags
+    // shift = 1, alt = 2, ctrl = 4 and super (kitty) or meta (xterm) = 8
+    // higher kitty flags (hyper, meta, caps lock and num lock) are dropped
+    fn from_xterm(param: u32) -> Self {
+        let bits = param.saturating_sub(1);
+        [(1, SHIFT), (2, ALT), (4, CONTROL), (8, SUPER)]
+            .into_iter()
+            .filter(|(bit, _)| bits & bit != 0)
+            .fold(Self(NONE), |mods, (_, flag)| mods | Self(flag))
+    }
+
     fn from_byte(byte: u8) -> Self {
         assert!(byte < 16);
 
@@ -299,13 +341,43 @@ impl Modifiers {
     }
 }
 
+impl std::ops::BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// whether a key went down, is being held down or went up
+/// terminals only tell repeats and releases apart from presses
+/// once they were asked to, see RawMode::enable_key_events
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum KeyKind {
+    /// the key went down
+    #[default]
+    Press,
+    /// the key is being held down
+    Repeat,
+    /// the key went up
+    Release,
+}
+
 /// the keyboard input event struct
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct KbdEvent {
     /// char can be a control char or a normal utf-8 char
     pub char: Char,
     /// keyboard input event modifiers
     pub modifiers: Modifiers,
+    /// press, repeat or release
+    pub kind: KeyKind,
 }
 
 impl Default for KbdEvent {
@@ -313,6 +385,18 @@ impl Default for KbdEvent {
         Self {
             modifiers: Modifiers(0x0),
             char: Char::CC(CC::ESC),
+            kind: KeyKind::Press,
+        }
+    }
+}
+
+impl From<char> for KbdEvent {
+    /// the key press of a char with no modifiers
+    fn from(c: char) -> Self {
+        Self {
+            char: Char::Char(c),
+            modifiers: Modifiers(NONE),
+            kind: KeyKind::Press,
         }
     }
 }
@@ -333,8 +417,18 @@ impl KbdEvent {
             false
         }
     }
+
+    /// whether self and other are the same key with the same modifiers, whatever their kind
+    pub fn same_key(&self, other: &Self) -> bool {
+        self.char == other.char && self.modifiers == other.modifiers
+    }
 }
 
+// kitty keyboard protocol, flags 1 (disambiguate) and 2 (report event types),
+// then xterm's modifyOtherKeys level 2 for the terminals that don't speak kitty
+pub(crate) const ENABLE_KEY_EVENTS: &[u8] = b"\x1b[>3u\x1b[>4;2m";
+pub(crate) const DISABLE_KEY_EVENTS: &[u8] = b"\x1b[<u\x1b[>4m";
+
 mod utf8_decoder {
     use super::*;
 
@@ -368,6 +462,7 @@ mod utf8_decoder {
                     *ke = KbdEvent {
                         char: Char::from_ctrl_ascii(byte),
                         modifiers: Modifiers(CONTROL),
+                        kind: KeyKind::Press,
                     }
                 }
             },
@@ -543,6 +638,10 @@ mod utf8_decoder {
     // basically no ctrl-v or any way to dump multiple chars of input at once
     /// decodes the bytes from 1 keyboard input event into a KbdEvent instance
     pub fn decode_ki(bytes: &[u8]) -> Option<KbdEvent> {
+        if let [27, b'[' | b'O', _, ..] = bytes {
+            return decode_csi(bytes);
+        }
+
         let mut ke: KbdEvent = Default::default();
         match bytes.len() {
             1 => decode_1_byte(bytes[0], &mut ke),
@@ -595,6 +694,7 @@ mod utf8_decoder {
                         v.push(Ok(KbdEvent {
                             char: Char::CC(CC::ESC),
                             modifiers: Modifiers::from_byte(0),
+                            kind: KeyKind::Press,
                         }));
 
                         return v;
@@ -741,6 +841,7 @@ mod utf8_decoder {
                             v.push(Ok(KbdEvent {
                                 char: Char::CC(CC::ESC),
                                 modifiers: Modifiers::from_byte(0),
+                                kind: KeyKind::Press,
                             }));
                             bytes = [b1, b2]
                                 .into_iter()
@@ -917,6 +1018,270 @@ use utf8_decoder::*;
 
 pub use utf8_decoder::{decode_ki, decode_ki_kai};
 
+// decodes csi and ss3 key sequences by their grammar rather than their length
+// ESC [ params final, where params are ';' separated numbers with ':' separated sub numbers
+mod csi_decoder {
+    use super::*;
+
+    // the numbers of one parameter, an empty slot is None
+    fn params(bytes: &[u8]) -> Option<Vec<Vec<Option<u32>>>> {
+        std::str::from_utf8(bytes)
+            .ok()?
+            .split(';')
+            .map(|p| {
+                p.split(':')
+                    .map(|n| match n {
+                        "" => Ok(None),
+                        n => n.parse().map(Some),
+                    })
+                    .collect::<Result<Vec<Option<u32>>, _>>()
+                    .ok()
+            })
+            .collect()
+    }
+
+    fn param(params: &[Vec<Option<u32>>], idx: usize, sub: usize) -> Option<u32> {
+        params.get(idx)?.get(sub).copied().flatten()
+    }
+
+    fn kind(event: Option<u32>) -> KeyKind {
+        match event {
+            Some(2) => KeyKind::Repeat,
+            Some(3) => KeyKind::Release,
+            _ => KeyKind::Press,
+        }
+    }
+
+    // the key named by the final byte of a csi or ss3 sequence
+    fn from_final(byte: u8) -> Option<Char> {
+        Some(match byte {
+            b'A'..=b'D' => Char::from_arrow_key(byte),
+            b'H' => Char::CC(CC::Home),
+            b'F' => Char::CC(CC::End),
+            b'P'..=b'S' => Char::from_fn_key3(byte),
+            // the keypad enter in application mode
+            b'M' => Char::CC(CC::CR),
+            _ => return None,
+        })
+    }
+
+    // the key named by the first parameter of a '~' sequence
+    // xterm, vte and tmux use 1 and 4 for home and end, rxvt uses 7 and 8
+    fn from_tilde(n: u32) -> Option<Char> {
+        Some(Char::CC(match n {
+            1 | 7 => CC::Home,
+            2 => CC::Insert,
+            3 => CC::Delete,
+            4 | 8 => CC::End,
+            5 => CC::PageUp,
+            6 => CC::PageDown,
+            11 => CC::F1,
+            12 => CC::F2,
+            13 => CC::F3,
+            14 => CC::F4,
+            15 => CC::F5,
+            17 => CC::F6,
+            18 => CC::F7,
+            19 => CC::F8,
+            20 => CC::F9,
+            21 => CC::F10,
+            23 => CC::F11,
+            24 => CC::F12,
+            _ => return None,
+        }))
+    }
+
+    // the key of a unicode code point, as kitty's 'u' and modifyOtherKeys sequences give it
+    fn from_code(code: u32) -> Option<Char> {
+        Some(match code {
+            9 => Char::CC(CC::TAB),
+            13 => Char::CC(CC::CR),
+            27 => Char::CC(CC::ESC),
+            8 | 127 => Char::CC(CC::BS),
+            0..=31 => return None,
+            code => Char::Char(char::from_u32(code)?),
+        })
+    }
+
+    /// decodes one csi or ss3 key sequence, including the xterm 'CSI 1;<mods>' modifier
+    /// encodings, kitty's keyboard protocol and xterm's modifyOtherKeys
+    /// returns None if the bytes are not a key sequence this decoder knows
+    pub fn decode_csi(bytes: &[u8]) -> Option<KbdEvent> {
+        let (&last, body) = match bytes {
+            [27, b'O', body @ ..] | [27, b'[', body @ ..] => body.split_last()?,
+            _ => return None,
+        };
+        // private parameters and intermediate bytes belong to other kinds of sequences
+        if !body
+            .iter()
+            .all(|b| b.is_ascii_digit() || *b == b';' || *b == b':')
+        {
+            return None;
+        }
+        let params = params(body)?;
+
+        let mut ke = KbdEvent::default();
+        match (bytes[1], last) {
+            // ss3 keys may only carry a modifier, e.g. '\x1bO5P'
+            (b'O', _) => {
+                ke.char = from_final(last)?;
+                ke.modifiers = Modifiers::from_xterm(param(&params, 0, 0).unwrap_or(1));
+            }
+            (_, b'u') => {
+                ke.char = from_code(param(&params, 0, 0)?)?;
+                ke.modifiers = Modifiers::from_xterm(param(&params, 1, 0).unwrap_or(1));
+                ke.kind = kind(param(&params, 1, 1));
+            }
+            // modifyOtherKeys, '\x1b[27;<mods>;<code>~'
+            (_, b'~') if param(&params, 0, 0) == Some(27) => {
+                ke.char = from_code(param(&params, 2, 0)?)?;
+                ke.modifiers = Modifiers::from_xterm(param(&params, 1, 0).unwrap_or(1));
+            }
+            (_, b'~') => {
+                ke.char = from_tilde(param(&params, 0, 0)?)?;
+                ke.modifiers = Modifiers::from_xterm(param(&params, 1, 0).unwrap_or(1));
+                ke.kind = kind(param(&params, 1, 1));
+            }
+            (_, b'Z') => {
+                ke.char = Char::CC(CC::TAB);
+                ke.modifiers = Modifiers::from_xterm(param(&params, 1, 0).unwrap_or(1));
+                ke.modifiers |= Modifiers::SHIFT;
+            }
+            (_, _) => {
+                ke.char = from_final(last)?;
+                ke.modifiers = Modifiers::from_xterm(param(&params, 1, 0).unwrap_or(1));
+                ke.kind = kind(param(&params, 1, 1));
+            }
+        }
+
+        // a shifted letter is the upper case letter, like a shifted key press without any protocol
+        if let Char::Char(c) = ke.char {
+            if ke.modifiers.contains(Modifiers::SHIFT) && c.is_ascii_lowercase() {
+                ke.char = Char::Char(c.to_ascii_uppercase());
+                ke.modifiers.0 &= !SHIFT;
+            }
+        }
+
+        Some(ke)
+    }
+}
+
+pub use csi_decoder::decode_csi;
+
+#[cfg(windows)]
+mod win32_decoder {
+    use super::*;
+
+    // control key state flags, from wincon.h
+    const RIGHT_ALT_PRESSED: u32 = 0x0001;
+    const LEFT_ALT_PRESSED: u32 = 0x0002;
+    const RIGHT_CTRL_PRESSED: u32 = 0x0004;
+    const LEFT_CTRL_PRESSED: u32 = 0x0008;
+    const SHIFT_PRESSED: u32 = 0x0010;
+
+    fn modifiers(state: u32) -> Modifiers {
+        let mut mods = Modifiers(NONE);
+        if state & (LEFT_CTRL_PRESSED | RIGHT_CTRL_PRESSED) != 0 {
+            mods.or(CONTROL);
+        }
+        if state & (LEFT_ALT_PRESSED | RIGHT_ALT_PRESSED) != 0 {
+            mods.or(ALT);
+        }
+        if state & SHIFT_PRESSED != 0 {
+            mods.or(SHIFT);
+        }
+
+        mods
+    }
+
+    // virtual key codes, from winuser.h
+    fn from_virtual_key(vk: u16) -> Option<CC> {
+        Some(match vk {
+            0x08 => CC::BS,
+            0x09 => CC::TAB,
+            0x0D => CC::CR,
+            0x1B => CC::ESC,
+            0x21 => CC::PageUp,
+            0x22 => CC::PageDown,
+            0x23 => CC::End,
+            0x24 => CC::Home,
+            0x25 => CC::Left,
+            0x26 => CC::Up,
+            0x27 => CC::Right,
+            0x28 => CC::Down,
+            0x2D => CC::Insert,
+            0x2E => CC::Delete,
+            0x70 => CC::F1,
+            0x71 => CC::F2,
+            0x72 => CC::F3,
+            0x73 => CC::F4,
+            0x74 => CC::F5,
+            0x75 => CC::F6,
+            0x76 => CC::F7,
+            0x77 => CC::F8,
+            0x78 => CC::F9,
+            0x79 => CC::F10,
+            0x7A => CC::F11,
+            0x7B => CC::F12,
+            _ => return None,
+        })
+    }
+
+    // the keys that only ever modify other keys, shift, ctrl, alt, caps lock and the windows keys
+    fn is_modifier_key(vk: u16) -> bool {
+        matches!(vk, 0x10 | 0x11 | 0x12 | 0x14 | 0x5B | 0x5C)
+    }
+
+    /// reads console key presses until one maps onto a KbdEvent
+    /// chars follow the same model as the unix decoder;
+    /// a shifted char carries no SHIFT modifier and ctrl + letter is the letter with CONTROL
+    pub fn read_kbd_event() -> std::io::Result<KbdEvent> {
+        loop {
+            let (vk, unit, state) = crate::console::windows::read_key()?;
+            if is_modifier_key(vk) {
+                continue;
+            }
+
+            let mut mods = modifiers(state);
+            if let Some(cc) = from_virtual_key(vk) {
+                return Ok(KbdEvent {
+                    char: Char::CC(cc),
+                    modifiers: mods,
+                    kind: KeyKind::Press,
+                });
+            }
+
+            let ch = match unit {
+                0 => continue,
+                // ctrl + letter comes in as the letter's control code
+                1..=26 => (unit as u8 + 96) as char,
+                // a char outside of the bmp comes in as 2 key presses, one per surrogate
+                0xD800..=0xDBFF => {
+                    let (_, low, _) = crate::console::windows::read_key()?;
+                    match char::decode_utf16([unit, low]).next() {
+                        Some(Ok(ch)) => ch,
+                        _ => continue,
+                    }
+                }
+                _ => match char::from_u32(unit as u32) {
+                    Some(ch) => ch,
+                    None => continue,
+                },
+            };
+            mods.0 &= !SHIFT;
+
+            return Ok(KbdEvent {
+                char: Char::Char(ch),
+                modifiers: mods,
+                kind: KeyKind::Press,
+            });
+        }
+    }
+}
+
+#[cfg(windows)]
+pub use win32_decoder::read_kbd_event;
+
 /// old keyboard input reading function
 /// not needed anymore
 pub fn kbd_read() {
@@ -962,3 +1327,131 @@ pub fn kbd_read() {
         }
     }
 }
+
+#[cfg(test)]
+mod sequences {
+    use super::*;
+
+    fn key(char: Char, modifiers: u8) -> KbdEvent {
+        KbdEvent {
+            char,
+            modifiers: Modifiers(modifiers),
+            kind: KeyKind::Press,
+        }
+    }
+
+    fn cc(cc: CC, modifiers: u8) -> KbdEvent {
+        key(Char::CC(cc), modifiers)
+    }
+
+    #[test]
+    fn xterm() {
+        let table: &[(&[u8], KbdEvent)] = &[
+            (b"\x1b[A", cc(CC::Up, NONE)),
+            (b"\x1b[1;2A", cc(CC::Up, SHIFT)),
+            (b"\x1b[1;6D", cc(CC::Left, CONTROL | SHIFT)),
+            (b"\x1b[1;3C", cc(CC::Right, ALT)),
+            (b"\x1b[1;9B", cc(CC::Down, SUPER)),
+            (b"\x1b[1;16B", cc(CC::Down, SUPER | CONTROL | ALT | SHIFT)),
+            (b"\x1b[H", cc(CC::Home, NONE)),
+            (b"\x1b[1;5H", cc(CC::Home, CONTROL)),
+            (b"\x1b[1;2F", cc(CC::End, SHIFT)),
+            (b"\x1b[5;5~", cc(CC::PageUp, CONTROL)),
+            (b"\x1b[6;3~", cc(CC::PageDown, ALT)),
+            (b"\x1b[2~", cc(CC::Insert, NONE)),
+            (b"\x1b[3;2~", cc(CC::Delete, SHIFT)),
+            (b"\x1b[1;5P", cc(CC::F1, CONTROL)),
+            (b"\x1b[1;2S", cc(CC::F4, SHIFT)),
+            (b"\x1b[15;2~", cc(CC::F5, SHIFT)),
+            (b"\x1b[24;7~", cc(CC::F12, CONTROL | ALT)),
+            (b"\x1b[Z", cc(CC::TAB, SHIFT)),
+            (b"\x1b[27;3;13~", cc(CC::CR, ALT)),
+            (b"\x1b[27;5;105~", key(Char::Char('i'), CONTROL)),
+            (b"\x1b[27;6;105~", key(Char::Char('I'), CONTROL)),
+        ];
+        for (bytes, event) in table {
+            assert_eq!(decode_csi(bytes).as_ref(), Some(event), "{bytes:?}");
+        }
+    }
+
+    #[test]
+    fn vte() {
+        let table: &[(&[u8], KbdEvent)] = &[
+            (b"\x1bOA", cc(CC::Up, NONE)),
+            (b"\x1bOH", cc(CC::Home, NONE)),
+            (b"\x1bOF", cc(CC::End, NONE)),
+            (b"\x1bOP", cc(CC::F1, NONE)),
+            (b"\x1bOM", cc(CC::CR, NONE)),
+            (b"\x1bO5R", cc(CC::F3, CONTROL)),
+            (b"\x1b[1;2P", cc(CC::F1, SHIFT)),
+            (b"\x1b[3;5~", cc(CC::Delete, CONTROL)),
+        ];
+        for (bytes, event) in table {
+            assert_eq!(decode_csi(bytes).as_ref(), Some(event), "{bytes:?}");
+        }
+    }
+
+    #[test]
+    fn tmux() {
+        let table: &[(&[u8], KbdEvent)] = &[
+            (b"\x1b[1~", cc(CC::Home, NONE)),
+            (b"\x1b[4~", cc(CC::End, NONE)),
+            (b"\x1b[1;5A", cc(CC::Up, CONTROL)),
+            (b"\x1b[11~", cc(CC::F1, NONE)),
+            (b"\x1b[7~", cc(CC::Home, NONE)),
+            (b"\x1b[8;2~", cc(CC::End, SHIFT)),
+            (b"\x1b[27;5;9~", cc(CC::TAB, CONTROL)),
+        ];
+        for (bytes, event) in table {
+            assert_eq!(decode_csi(bytes).as_ref(), Some(event), "{bytes:?}");
+        }
+    }
+
+    #[test]
+    fn kitty() {
+        let release = |mut e: KbdEvent| {
+            e.kind = KeyKind::Release;
+            e
+        };
+        let table: &[(&[u8], KbdEvent)] = &[
+            (b"\x1b[97u", key(Char::Char('a'), NONE)),
+            (b"\x1b[97;5u", key(Char::Char('a'), CONTROL)),
+            (b"\x1b[97;1:3u", release(key(Char::Char('a'), NONE))),
+            (b"\x1b[13;3u", cc(CC::CR, ALT)),
+            (b"\x1b[27u", cc(CC::ESC, NONE)),
+            (b"\x1b[127;5u", cc(CC::BS, CONTROL)),
+            (b"\x1b[1;5:3A", release(cc(CC::Up, CONTROL))),
+            (b"\x1b[3;1:3~", release(cc(CC::Delete, NONE))),
+        ];
+        for (bytes, event) in table {
+            assert_eq!(decode_csi(bytes).as_ref(), Some(event), "{bytes:?}");
+        }
+
+        let repeat = decode_csi(b"\x1b[1;1:2B").unwrap();
+        assert_eq!(repeat.kind, KeyKind::Repeat);
+        assert!(repeat.same_key(&cc(CC::Down, NONE)));
+    }
+
+    #[test]
+    fn unknown() {
+        for bytes in [
+            &b"\x1b[?1u"[..],
+            b"\x1b[>1;2c",
+            b"\x1b[99~",
+            b"\x1b[5u",
+            b"\x1b[I",
+            b"\x1bOx",
+        ] {
+            assert_eq!(decode_csi(bytes), None, "{bytes:?}");
+        }
+    }
+
+    #[test]
+    fn modifiers() {
+        let mods = Modifiers::CONTROL | Modifiers::SHIFT;
+        assert!(mods.contains(Modifiers::SHIFT));
+        assert!(!mods.contains(Modifiers::ALT));
+        assert!(Modifiers::default().is_empty());
+        assert_eq!(mods, Modifiers(CONTROL | SHIFT));
+    }
+}
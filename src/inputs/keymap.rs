@@ -0,0 +1,636 @@
+use std::sync::OnceLock;
+
+use super::keyboard::{Char, KbdEvent, KeyKind, Modifiers, ALT, CC, CONTROL, NONE, SHIFT, SUPER};
+
+/// what a key press does to the focused input
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputAction {
+    /// removes the char behind the cursor
+    Backspace,
+    /// removes the char under the cursor
+    Delete,
+    /// moves the cursor one cell to the left
+    Left,
+    /// moves the cursor one cell to the right
+    Right,
+    /// moves the cursor one line up
+    Up,
+    /// moves the cursor one line down
+    Down,
+    /// moves the cursor to the start of the line
+    Home,
+    /// moves the cursor right after the last char of the line
+    End,
+    /// moves the cursor to the start of the next line
+    Newline,
+    /// the value is done, left to the app
+    Submit,
+    /// focuses the next input
+    FocusNext,
+    /// focuses the previous input
+    FocusPrev,
+    /// puts the previous entry of the input history in the input
+    HistoryPrev,
+    /// puts the next entry of the input history in the input, or what was typed before
+    HistoryNext,
+    /// starts a reverse incremental search through the input history,
+    /// or goes to the next older match during one
+    HistorySearch,
+    /// does nothing
+    /// binding a key to NoOp hides the bindings of the layers under it
+    NoOp,
+    /// an action the app defines, left to the app
+    Named(String),
+}
+
+impl InputAction {
+    /// the name of this action in the keymap text format
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Backspace => "backspace",
+            Self::Delete => "delete",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Home => "home",
+            Self::End => "end",
+            Self::Newline => "newline",
+            Self::Submit => "submit",
+            Self::FocusNext => "focus-next",
+            Self::FocusPrev => "focus-prev",
+            Self::HistoryPrev => "history-prev",
+            Self::HistoryNext => "history-next",
+            Self::HistorySearch => "history-search",
+            Self::NoOp => "no-op",
+            Self::Named(name) => name,
+        }
+    }
+
+    /// the action with the given name, names that are not built in make a Named action
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "backspace" => Self::Backspace,
+            "delete" => Self::Delete,
+            "left" => Self::Left,
+            "right" => Self::Right,
+            "up" => Self::Up,
+            "down" => Self::Down,
+            "home" => Self::Home,
+            "end" => Self::End,
+            "newline" => Self::Newline,
+            "submit" => Self::Submit,
+            "focus-next" => Self::FocusNext,
+            "focus-prev" => Self::FocusPrev,
+            "history-prev" => Self::HistoryPrev,
+            "history-next" => Self::HistoryNext,
+            "history-search" => Self::HistorySearch,
+            "no-op" => Self::NoOp,
+            name => Self::Named(name.to_string()),
+        }
+    }
+}
+
+/// keymap errors
+#[derive(Debug, PartialEq)]
+pub enum KeymapError {
+    /// the key is already bound to another action, Keymap::rebind replaces a binding
+    Conflict { key: KbdEvent, bound: InputAction },
+    /// a keymap text line is not 'key = action'
+    BadLine(usize),
+    /// a keymap text line has a key that is not known
+    BadKey(usize),
+}
+
+/// the category and the description of a binding, see Keymap::describe
+/// Term::show_keymap_help groups the bindings by category
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindingHelp {
+    pub category: String,
+    pub description: String,
+}
+
+/// an ordered map of key presses to input actions
+///
+/// the text format has one 'key = action' binding per line, a '#' at the start of a line or
+/// after a space starts a comment, the '#' key alone is written '\#', e.g. '\# = search'
+/// keys are a char or a key name (enter, tab, esc, backspace, space, up, down, left, right,
+/// insert, delete, home, end, pageup, pagedown, f1 to f12), after any number of 'ctrl+', 'alt+',
+/// 'shift+' and 'super+' modifiers, e.g. 'ctrl+alt+x = quit'
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Keymap {
+    binds: Vec<(KbdEvent, InputAction)>,
+    help: Vec<(KbdEvent, BindingHelp)>,
+}
+
+impl Keymap {
+    /// makes a new Keymap with no bindings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the editing keys every Term falls back to
+    pub fn defaults() -> &'static Keymap {
+        static DEFAULTS: OnceLock<Keymap> = OnceLock::new();
+
+        DEFAULTS.get_or_init(|| {
+            let mut keymap = Keymap::new();
+            [
+                (CC::BS, NONE, InputAction::Backspace),
+                (CC::Delete, NONE, InputAction::Delete),
+                (CC::Left, NONE, InputAction::Left),
+                (CC::Right, NONE, InputAction::Right),
+                (CC::Up, NONE, InputAction::Up),
+                (CC::Down, NONE, InputAction::Down),
+                (CC::Home, NONE, InputAction::Home),
+                (CC::End, NONE, InputAction::End),
+                (CC::CR, NONE, InputAction::Submit),
+                (CC::CR, ALT, InputAction::Newline),
+                (CC::TAB, NONE, InputAction::FocusNext),
+                (CC::TAB, SHIFT, InputAction::FocusPrev),
+            ]
+            .into_iter()
+            .for_each(|(cc, mods, action)| {
+                let key = KbdEvent {
+                    char: Char::CC(cc),
+                    modifiers: Modifiers(mods),
+                    kind: KeyKind::Press,
+                };
+                let (category, description) = default_help(&action);
+                keymap.rebind(key.clone(), action);
+                keymap.describe(&key, category, description);
+            });
+            [
+                ('p', InputAction::HistoryPrev),
+                ('n', InputAction::HistoryNext),
+                ('r', InputAction::HistorySearch),
+            ]
+            .into_iter()
+            .for_each(|(c, action)| {
+                let key = KbdEvent {
+                    char: Char::Char(c),
+                    modifiers: Modifiers(CONTROL),
+                    kind: KeyKind::Press,
+                };
+                let (category, description) = default_help(&action);
+                keymap.rebind(key.clone(), action);
+                keymap.describe(&key, category, description);
+            });
+
+            keymap
+        })
+    }
+
+    /// binds key to action
+    ///
+    /// # Errors
+    ///
+    /// returns a Conflict error if key is already bound to another action
+    pub fn bind(&mut self, key: KbdEvent, action: InputAction) -> Result<(), KeymapError> {
+        match self.lookup(&key) {
+            Some(bound) if *bound != action => Err(KeymapError::Conflict {
+                key,
+                bound: bound.clone(),
+            }),
+            Some(_) => Ok(()),
+            None => {
+                self.binds.push((key, action));
+
+                Ok(())
+            }
+        }
+    }
+
+    /// binds key to action, like Keymap::bind, and describes the binding, see Keymap::describe
+    ///
+    /// # Errors
+    ///
+    /// returns a Conflict error if key is already bound to another action
+    pub fn bind_described(
+        &mut self,
+        key: KbdEvent,
+        action: InputAction,
+        category: &str,
+        description: &str,
+    ) -> Result<(), KeymapError> {
+        self.bind(key.clone(), action)?;
+        self.describe(&key, category, description);
+
+        Ok(())
+    }
+
+    /// gives the binding of key a category, e.g. "navigation", and a description,
+    /// shown by Term::show_keymap_help, the binding keeps them when it is rebound
+    /// returns false if key is not bound
+    pub fn describe(&mut self, key: &KbdEvent, category: &str, description: &str) -> bool {
+        if self.lookup(key).is_none() {
+            return false;
+        }
+        let help = BindingHelp {
+            category: category.to_string(),
+            description: description.to_string(),
+        };
+        match self.help.iter_mut().find(|(k, _)| k.same_key(key)) {
+            Some((_, h)) => *h = help,
+            None => self.help.push((key.clone(), help)),
+        }
+
+        true
+    }
+
+    /// the category and description of the binding of key, see Keymap::describe
+    pub fn help(&self, key: &KbdEvent) -> Option<&BindingHelp> {
+        self.help
+            .iter()
+            .find(|(k, _)| k.same_key(key))
+            .map(|(_, h)| h)
+    }
+
+    /// binds key to action, replacing the key's current binding, which is returned
+    pub fn rebind(&mut self, key: KbdEvent, action: InputAction) -> Option<InputAction> {
+        match self.binds.iter_mut().find(|(k, _)| k.same_key(&key)) {
+            Some((_, bound)) => Some(std::mem::replace(bound, action)),
+            None => {
+                self.binds.push((key, action));
+
+                None
+            }
+        }
+    }
+
+    /// removes the key's binding and its description, returns the action it was bound to
+    pub fn unbind(&mut self, key: &KbdEvent) -> Option<InputAction> {
+        let idx = self.binds.iter().position(|(k, _)| k.same_key(key))?;
+        self.help.retain(|(k, _)| !k.same_key(key));
+
+        Some(self.binds.remove(idx).1)
+    }
+
+    /// the action key is bound to, a repeat or release of the key finds the same binding
+    pub fn lookup(&self, key: &KbdEvent) -> Option<&InputAction> {
+        self.binds
+            .iter()
+            .find(|(k, _)| k.same_key(key))
+            .map(|(_, a)| a)
+    }
+
+    /// the bindings, in the order they were made
+    pub fn iter(&self) -> impl Iterator<Item = &(KbdEvent, InputAction)> {
+        self.binds.iter()
+    }
+
+    /// puts the bindings of over on top of this keymap's, over's bindings and descriptions win
+    pub fn overlay(&mut self, over: &Keymap) {
+        over.binds.iter().for_each(|(k, a)| {
+            self.rebind(k.clone(), a.clone());
+        });
+        over.help.iter().for_each(|(k, h)| {
+            self.describe(k, &h.category, &h.description);
+        });
+    }
+
+    /// reads a keymap in the text format
+    ///
+    /// # Errors
+    ///
+    /// returns the first bad line, or a Conflict error if a key is bound twice to different actions
+    pub fn from_text(text: &str) -> Result<Self, KeymapError> {
+        let mut keymap = Keymap::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let Some((key, action)) = line.split_once(" = ") else {
+                return Err(KeymapError::BadLine(idx + 1));
+            };
+            let action = action.trim();
+            if action.is_empty() {
+                return Err(KeymapError::BadLine(idx + 1));
+            }
+            let key = parse_key(key.trim()).ok_or(KeymapError::BadKey(idx + 1))?;
+
+            keymap.bind(key, InputAction::from_name(action))?;
+        }
+
+        Ok(keymap)
+    }
+
+    /// writes this keymap in the text format
+    pub fn to_text(&self) -> String {
+        self.binds
+            .iter()
+            .filter_map(|(k, a)| Some(format!("{} = {}\n", key_name(k)?, a.name())))
+            .collect()
+    }
+}
+
+/// the action the first layer that binds key binds it to
+/// layers go from the most specific to the least, e.g. a component's map, the term's map
+/// then the defaults
+pub fn resolve<'a>(layers: &[&'a Keymap], key: &KbdEvent) -> Option<&'a InputAction> {
+    layers.iter().find_map(|keymap| keymap.lookup(key))
+}
+
+// the category and description of the bindings of Keymap::defaults
+fn default_help(action: &InputAction) -> (&'static str, &'static str) {
+    match action {
+        InputAction::Backspace => ("editing", "delete the char before the cursor"),
+        InputAction::Delete => ("editing", "delete the char under the cursor"),
+        InputAction::Left => ("editing", "move the cursor left"),
+        InputAction::Right => ("editing", "move the cursor right"),
+        InputAction::Up => ("editing", "move the cursor up"),
+        InputAction::Down => ("editing", "move the cursor down"),
+        InputAction::Home => ("editing", "move the cursor to the start of the line"),
+        InputAction::End => ("editing", "move the cursor to the end of the line"),
+        InputAction::Newline => ("editing", "start a new line"),
+        InputAction::Submit => ("editing", "submit the value"),
+        InputAction::FocusNext => ("focus", "focus the next input"),
+        InputAction::FocusPrev => ("focus", "focus the previous input"),
+        InputAction::HistoryPrev => ("history", "previous entry"),
+        InputAction::HistoryNext => ("history", "next entry"),
+        InputAction::HistorySearch => ("history", "search the history"),
+        _ => ("other", ""),
+    }
+}
+
+/// the key for people to read, e.g. "Ctrl+Shift+→", see chord_label for a key sequence
+pub fn key_label(key: &KbdEvent) -> String {
+    let mut label = MODIFIER_LABELS
+        .iter()
+        .filter(|(bit, _)| key.modifiers.0 & bit != 0)
+        .map(|(_, n)| format!("{}+", n))
+        .collect::<String>();
+
+    match &key.char {
+        Char::Char(' ') => label.push_str("Space"),
+        Char::Char(c) => label.push(*c),
+        Char::CC(cc) => match KEY_LABELS.iter().find(|(c, _)| c == cc) {
+            Some((_, n)) => label.push_str(n),
+            None => label.push_str(&format!("{:?}", cc)),
+        },
+    }
+
+    label
+}
+
+/// a key sequence for people to read, the keys separated by spaces, e.g. "g g"
+pub fn chord_label(keys: &[KbdEvent]) -> String {
+    keys.iter().map(key_label).collect::<Vec<_>>().join(" ")
+}
+
+const MODIFIER_LABELS: [(u8, &str); 4] = [
+    (CONTROL, "Ctrl"),
+    (ALT, "Alt"),
+    (SHIFT, "Shift"),
+    (SUPER, "Super"),
+];
+
+const KEY_LABELS: [(CC, &str); 14] = [
+    (CC::CR, "Enter"),
+    (CC::TAB, "Tab"),
+    (CC::ESC, "Esc"),
+    (CC::BS, "Backspace"),
+    (CC::Up, "↑"),
+    (CC::Down, "↓"),
+    (CC::Left, "←"),
+    (CC::Right, "→"),
+    (CC::Insert, "Insert"),
+    (CC::Delete, "Delete"),
+    (CC::Home, "Home"),
+    (CC::End, "End"),
+    (CC::PageUp, "PgUp"),
+    (CC::PageDown, "PgDn"),
+];
+
+const MODIFIER_NAMES: [(u8, &str); 4] = [
+    (CONTROL, "ctrl"),
+    (ALT, "alt"),
+    (SHIFT, "shift"),
+    (SUPER, "super"),
+];
+
+const KEY_NAMES: [(CC, &str); 26] = [
+    (CC::CR, "enter"),
+    (CC::TAB, "tab"),
+    (CC::ESC, "esc"),
+    (CC::BS, "backspace"),
+    (CC::Up, "up"),
+    (CC::Down, "down"),
+    (CC::Left, "left"),
+    (CC::Right, "right"),
+    (CC::Insert, "insert"),
+    (CC::Delete, "delete"),
+    (CC::Home, "home"),
+    (CC::End, "end"),
+    (CC::PageUp, "pageup"),
+    (CC::PageDown, "pagedown"),
+    (CC::F1, "f1"),
+    (CC::F2, "f2"),
+    (CC::F3, "f3"),
+    (CC::F4, "f4"),
+    (CC::F5, "f5"),
+    (CC::F6, "f6"),
+    (CC::F7, "f7"),
+    (CC::F8, "f8"),
+    (CC::F9, "f9"),
+    (CC::F10, "f10"),
+    (CC::F11, "f11"),
+    (CC::F12, "f12"),
+];
+
+// the line without its comment, a '#' right after something else is a key, as in 'ctrl+#'
+fn strip_comment(line: &str) -> &str {
+    let mut prev = None;
+    for (idx, c) in line.char_indices() {
+        if c == '#' && prev.is_none_or(char::is_whitespace) {
+            return &line[..idx];
+        }
+        prev = Some(c);
+    }
+
+    line
+}
+
+fn parse_key(s: &str) -> Option<KbdEvent> {
+    let (mods, key) = match s {
+        "+" => ("", "+"),
+        "\\#" => ("", "#"),
+        s => match s.strip_suffix("++") {
+            Some(mods) => (mods, "+"),
+            None => s.rsplit_once('+').unwrap_or(("", s)),
+        },
+    };
+
+    let mut modifiers = Modifiers(NONE);
+    for name in mods.split('+').filter(|m| !m.is_empty()) {
+        let (bit, _) = MODIFIER_NAMES.iter().find(|(_, n)| *n == name)?;
+        modifiers.0 |= bit;
+    }
+
+    let char = match KEY_NAMES.iter().find(|(_, n)| *n == key) {
+        Some((cc, _)) => Char::CC(cc.clone()),
+        None if key == "space" => Char::Char(' '),
+        None => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Char::Char(c),
+                _ => return None,
+            }
+        }
+    };
+
+    Some(KbdEvent {
+        char,
+        modifiers,
+        kind: KeyKind::Press,
+    })
+}
+
+fn key_name(key: &KbdEvent) -> Option<String> {
+    let mut name = MODIFIER_NAMES
+        .iter()
+        .filter(|(bit, _)| key.modifiers.0 & bit != 0)
+        .map(|(_, n)| format!("{}+", n))
+        .collect::<String>();
+
+    match &key.char {
+        Char::Char(' ') => name.push_str("space"),
+        // a '#' at the start of a line starts a comment
+        Char::Char('#') if name.is_empty() => name.push_str("\\#"),
+        Char::Char(c) => name.push(*c),
+        Char::CC(cc) => {
+            let (_, n) = KEY_NAMES.iter().find(|(c, _)| c == cc)?;
+            name.push_str(n);
+        }
+    }
+
+    Some(name)
+}
+
+#[cfg(test)]
+mod keymap {
+    use super::*;
+
+    fn ctrl(c: char) -> KbdEvent {
+        KbdEvent {
+            char: Char::Char(c),
+            modifiers: Modifiers(CONTROL),
+            kind: KeyKind::Press,
+        }
+    }
+
+    #[test]
+    fn bind() {
+        let mut keymap = Keymap::new();
+        keymap
+            .bind(ctrl('s'), InputAction::Named("save".into()))
+            .unwrap();
+        assert_eq!(
+            keymap.bind(ctrl('s'), InputAction::Named("save".into())),
+            Ok(())
+        );
+        assert_eq!(
+            keymap.bind(ctrl('s'), InputAction::Submit),
+            Err(KeymapError::Conflict {
+                key: ctrl('s'),
+                bound: InputAction::Named("save".into())
+            })
+        );
+
+        assert_eq!(
+            keymap.rebind(ctrl('s'), InputAction::Submit),
+            Some(InputAction::Named("save".into()))
+        );
+        assert_eq!(keymap.lookup(&ctrl('s')), Some(&InputAction::Submit));
+        assert_eq!(keymap.unbind(&ctrl('s')), Some(InputAction::Submit));
+        assert_eq!(keymap.lookup(&ctrl('s')), None);
+    }
+
+    #[test]
+    fn text() {
+        let text = "# my keys\nctrl+s = save\nalt+enter = newline\nshift+tab = focus-prev\nctrl++ = zoom # bigger\nspace = no-op\nf12 = submit\n";
+        let keymap = Keymap::from_text(text).unwrap();
+        assert_eq!(
+            keymap.lookup(&ctrl('+')),
+            Some(&InputAction::Named("zoom".into()))
+        );
+        assert_eq!(keymap.lookup(&' '.into()), Some(&InputAction::NoOp));
+        assert_eq!(Keymap::from_text(&keymap.to_text()).unwrap(), keymap);
+
+        // '#' is a key when it doesn't follow a space
+        let keymap = Keymap::from_text("ctrl+# = count #\n\\# = search # find").unwrap();
+        assert_eq!(
+            keymap.lookup(&ctrl('#')),
+            Some(&InputAction::Named("count".into()))
+        );
+        assert_eq!(
+            keymap.lookup(&'#'.into()),
+            Some(&InputAction::Named("search".into()))
+        );
+        assert_eq!(Keymap::from_text(&keymap.to_text()).unwrap(), keymap);
+
+        assert_eq!(
+            Keymap::from_text("ctrl+s save"),
+            Err(KeymapError::BadLine(1))
+        );
+        assert_eq!(
+            Keymap::from_text("\nhyper+s = save"),
+            Err(KeymapError::BadKey(2))
+        );
+        assert!(matches!(
+            Keymap::from_text("a = left\na = right"),
+            Err(KeymapError::Conflict { .. })
+        ));
+    }
+
+    #[test]
+    fn labels() {
+        let right = KbdEvent {
+            char: Char::CC(CC::Right),
+            modifiers: Modifiers(CONTROL | SHIFT),
+            kind: KeyKind::Press,
+        };
+        assert_eq!(key_label(&right), "Ctrl+Shift+→");
+        assert_eq!(key_label(&' '.into()), "Space");
+        assert_eq!(chord_label(&['g'.into(), 'g'.into()]), "g g");
+
+        let mut keymap = Keymap::new();
+        assert!(!keymap.describe(&ctrl('s'), "file", "save"));
+        keymap
+            .bind_described(ctrl('s'), InputAction::Named("save".into()), "file", "save")
+            .unwrap();
+        keymap.rebind(ctrl('s'), InputAction::Submit);
+        assert_eq!(keymap.help(&ctrl('s')).unwrap().category, "file");
+        keymap.unbind(&ctrl('s'));
+        assert_eq!(keymap.help(&ctrl('s')), None);
+    }
+
+    #[test]
+    fn layers() {
+        let user = Keymap::from_text("tab = submit\nctrl+q = quit").unwrap();
+        let mut component = Keymap::new();
+        component.bind(ctrl('q'), InputAction::NoOp).unwrap();
+        let tab = KbdEvent {
+            char: Char::CC(CC::TAB),
+            modifiers: Modifiers(NONE),
+            kind: KeyKind::Press,
+        };
+        let bs = KbdEvent {
+            char: Char::CC(CC::BS),
+            modifiers: Modifiers(NONE),
+            kind: KeyKind::Press,
+        };
+
+        let layers = [&component, &user, Keymap::defaults()];
+        assert_eq!(resolve(&layers, &tab), Some(&InputAction::Submit));
+        assert_eq!(resolve(&layers, &ctrl('q')), Some(&InputAction::NoOp));
+        assert_eq!(resolve(&layers, &bs), Some(&InputAction::Backspace));
+        assert_eq!(resolve(&layers, &'x'.into()), None);
+
+        let mut merged = Keymap::defaults().clone();
+        merged.overlay(&user);
+        assert_eq!(merged.lookup(&tab), Some(&InputAction::Submit));
+    }
+}
@@ -1,134 +1,243 @@
 use std::io::{StdoutLock, Write};
 
-use crate::inputs::keyboard::Modifiers;
+use crate::inputs::keyboard::{Modifiers, ALT, CONTROL, SHIFT};
 
 /// Mouse event struct
-/// contains the mouse gesture, modifiers and the [x,y] coordinates of the cursor
-#[derive(Default, Debug, Clone)]
+/// contains what the mouse did, the button that did it, the modifiers
+/// and the 0 based [column, row] cell of the pointer
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct MouseEvent {
-    gesture: Gesture,
-    modifiers: Modifiers,
-    position: [u8; 2],
+    /// what happened
+    pub kind: MouseKind,
+    /// the button involved, MouseButton::None for motion without a pressed button and for the wheel
+    pub button: MouseButton,
+    /// the 0 based column of the pointer
+    pub column: u16,
+    /// the 0 based row of the pointer
+    pub row: u16,
+    /// the keyboard modifiers that were held during the event
+    pub modifiers: Modifiers,
 }
 
-/// Mouse gesture enum
-/// contains the possible mouse event gestures
-#[derive(Default, Debug, Clone)]
-pub enum Gesture {
-    /// simply moving in any direction
-    /// provides the current coordinates of the cursor
-    Move(u8, u8),
+/// what a mouse event did
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum MouseKind {
+    /// a button was pressed
+    Down,
+    /// a button was released
+    Up,
+    /// the pointer moved while a button was pressed
+    Drag,
+    /// the pointer moved with no button pressed
+    /// only reported when any motion tracking (1003) is on
+    #[default]
+    Move,
+    /// wheel / touchpad 2 fingers up
+    ScrollUp,
+    /// wheel / touchpad 2 fingers down
+    ScrollDown,
+    /// touchpad 2 fingers left
+    ScrollLeft,
+    /// touchpad 2 fingers right
+    ScrollRight,
+}
+
+/// the mouse button of a mouse event
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum MouseButton {
     /// 1 finger touchpad press
-    LeftPress,
-    /// release the mouse/touchpad
-    Release,
-    /// 2 fingers touchpad press
-    RightPress,
+    Left,
     /// 3 fingers touchpad press
-    WheelePress,
-    /// touchpad 2 fingers up
-    WheeleUp,
-    /// touchpad 2 fingers down
-    WheeleDown,
-    /// touchpad 2 finger right
-    /// can't replicate with the mouse, but it is with the touchpad (probably phone
-    /// touch too)
-    WheeleRight,
-    /// touchpad 2 finger left
-    /// don't know if it is possible with the mouse, but it is with the touchpad (probably phone
-    /// touch too)
-    WheeleLeft,
-    /// the no event variant
+    Middle,
+    /// 2 fingers touchpad press
+    Right,
+    /// no button, or the button is not known, like in x10 releases
     #[default]
     None,
 }
 
-/// decodes input bytes into a vec of mouse events
-pub fn decode_mi(bytes: &[u8]) -> Vec<MouseEvent> {
-    let mut rem = bytes.len();
-    assert_eq!(rem % 6, 0);
-
-    let mut v: Vec<MouseEvent> = Vec::new();
-
-    let mut me = MouseEvent::default();
-    let mut bytes = bytes.into_iter();
-
-    while rem != 0 {
-        let eve = (0..6)
-            .into_iter()
-            .map(|_| bytes.next())
-            .filter(|o| o.is_some())
-            .map(|o| *o.unwrap())
-            .collect::<Vec<u8>>();
-
-        decode_6_bytes(eve, &mut me);
-
-        v.push(me.clone());
+// builds a mouse event out of the button code and the 0 based position
+// the button code's lowest 2 bits are the button, 4 is shift, 8 is alt, 16 is ctrl,
+// 32 is motion and 64 is the wheel
+// release is only set by sgr reports, x10 reports releases as button 3
+fn from_code(code: u16, column: u16, row: u16, release: bool) -> MouseEvent {
+    let mut modifiers = Modifiers(0);
+    if code & 4 != 0 {
+        modifiers.0 |= SHIFT;
+    }
+    if code & 8 != 0 {
+        modifiers.0 |= ALT;
+    }
+    if code & 16 != 0 {
+        modifiers.0 |= CONTROL;
+    }
 
-        rem -= 6;
+    let button = match code & 3 {
+        0 => MouseButton::Left,
+        1 => MouseButton::Middle,
+        2 => MouseButton::Right,
+        _ => MouseButton::None,
+    };
+
+    let (kind, button) = if code & 64 != 0 {
+        let kind = match code & 3 {
+            0 => MouseKind::ScrollUp,
+            1 => MouseKind::ScrollDown,
+            2 => MouseKind::ScrollLeft,
+            _ => MouseKind::ScrollRight,
+        };
+        (kind, MouseButton::None)
+    } else if code & 32 != 0 {
+        match button {
+            MouseButton::None => (MouseKind::Move, button),
+            _ => (MouseKind::Drag, button),
+        }
+    } else if release || button == MouseButton::None {
+        (MouseKind::Up, button)
+    } else {
+        (MouseKind::Down, button)
+    };
+
+    MouseEvent {
+        kind,
+        button,
+        column,
+        row,
+        modifiers,
     }
+}
 
-    v
+/// decodes x10 input bytes into a vec of mouse events
+pub fn decode_mi(bytes: &[u8]) -> Vec<MouseEvent> {
+    assert_eq!(bytes.len() % 6, 0);
+
+    bytes.chunks(6).map(decode_6_bytes).collect()
 }
 
 // th fourth byte; bytes[3] denotes the action being taken as well as the modifiers
 // the last 2 bytes are for cursor position (x, y)
 // the cursor position returned always start from 33 so should remove 33 from both x and y
 // to get the correct position
-fn decode_6_bytes(bytes: Vec<u8>, me: &mut MouseEvent) {
+fn decode_6_bytes(bytes: &[u8]) -> MouseEvent {
     // assert mouse escape sequence
-    assert_eq!(bytes[0], 27);
-    assert_eq!(bytes[1], 91);
-    assert_eq!(bytes[2], 77);
-    me.modifiers = mouse_modifiers(bytes[3]);
-    me.gesture = mouse_gesture(bytes[3], bytes[4], bytes[5]);
-    me.position = [bytes[4] - 33, bytes[5] - 33];
+    assert_eq!(bytes[..3], [27, 91, 77]);
+
+    from_code(
+        bytes[3].saturating_sub(32) as u16,
+        bytes[4].saturating_sub(33) as u16,
+        bytes[5].saturating_sub(33) as u16,
+        false,
+    )
 }
 
-fn mouse_gesture(byte: u8, bx: u8, by: u8) -> Gesture {
-    match byte {
-        35 | 43 | 51 | 59 => Gesture::Release,
-        67 | 71 | 75 | 83 | 87 | 79 | 91 | 95 => Gesture::None,
-        32 | 40 | 48 | 56 => Gesture::LeftPress,
-        33 | 41 | 49 | 57 => Gesture::WheelePress,
-        34 | 50 | 52 | 48 => Gesture::RightPress,
-        96..=123 => match byte % 4 {
-            0 => Gesture::WheeleDown,
-            1 => Gesture::WheeleUp,
-            2 => Gesture::WheeleRight,
-            3 => Gesture::WheeleLeft,
-            _ => unreachable!("these codes rejuvenate in cycles of 4"),
-        },
-        _ => unreachable!("all possible cases have been handled"),
-    }
+/// whether bytes start with an sgr mouse report
+pub fn is_sgr(bytes: &[u8]) -> bool {
+    bytes.starts_with(&[27, 91, 60])
 }
 
-fn mouse_modifiers(byte: u8) -> Modifiers {
-    // shift + 3 finger/wheele click does a paste
-    match byte {
-        67 | 32..=35 | 96..=99 => Modifiers(0),
-        83 | 48..=51 | 112..=115 => Modifiers(2),
-        71 | 100..=103 => Modifiers(8),
-        75 | 40..=43 | 104..=107 => Modifiers(4),
-        87 | 116..=119 => Modifiers(10),          // ctrl + shift
-        91 | 56..=58 | 120..=123 => Modifiers(6), // ctrl + alt
-        79 | 108..=111 => Modifiers(12),          // shift + alt
-        95 | 124..=127 => Modifiers(15),
-        _ => unreachable!("all possible cases have been handled"),
+/// decodes sgr input bytes, '\x1b[<b;x;yM' for presses and '\x1b[<b;x;ym' for releases,
+/// into a vec of mouse events
+/// the 1 based terminal cells are converted to the crate's 0 based space
+/// malformed reports are skipped
+pub fn decode_sgr(bytes: &[u8]) -> Vec<MouseEvent> {
+    let mut v = vec![];
+    let mut rest = bytes;
+
+    while is_sgr(rest) {
+        let Some(end) = rest.iter().position(|b| *b == b'M' || *b == b'm') else {
+            break;
+        };
+
+        let params = std::str::from_utf8(&rest[3..end])
+            .ok()
+            .map(|s| {
+                s.split(';')
+                    .map(|p| p.parse::<u16>().ok())
+                    .collect::<Option<Vec<u16>>>()
+            })
+            .flatten();
+        if let Some([code, x, y]) = params.as_deref() {
+            v.push(from_code(
+                *code,
+                x.saturating_sub(1),
+                y.saturating_sub(1),
+                rest[end] == b'm',
+            ));
+        }
+
+        rest = &rest[end + 1..];
     }
+
+    v
 }
 
 /// enables raw mouse input reading
 /// when this function is called, the prgram can start reading raw mouse input events
 /// which can then be used in the program
+/// presses, releases, drags and the wheel get reported in the sgr encoding
 pub fn enable_mouse_input(writer: &mut StdoutLock) {
     // NOTE: the following line enables the terminal to receive mouse events
-    _ = writer.write(b"\x1b[?1003h");
+    _ = writer.write(ENABLE_MOUSE);
 }
 
 /// disables raw mouse input reading
 /// makes the mouse unusable in the program
 /// once this is called, the terminal emulator will start detecting mouse input events again
 pub fn disable_mouse_input(writer: &mut StdoutLock) {
-    _ = writer.write(b"\x1b[?1003l");
+    _ = writer.write(DISABLE_MOUSE);
+}
+
+// button event tracking (1002) in the sgr encoding (1006)
+pub(crate) const ENABLE_MOUSE: &[u8] = b"\x1b[?1002h\x1b[?1006h";
+pub(crate) const DISABLE_MOUSE: &[u8] = b"\x1b[?1006l\x1b[?1002l";
+
+#[cfg(test)]
+mod decode {
+    use super::*;
+
+    #[test]
+    fn sgr() {
+        let events = decode_sgr(b"\x1b[<0;10;5M\x1b[<0;10;5m");
+        assert_eq!(
+            events[0],
+            MouseEvent {
+                kind: MouseKind::Down,
+                button: MouseButton::Left,
+                column: 9,
+                row: 4,
+                modifiers: Modifiers(0),
+            }
+        );
+        assert_eq!(events[1].kind, MouseKind::Up);
+        assert_eq!(events[1].button, MouseButton::Left);
+
+        let e = &decode_sgr(b"\x1b[<34;1;1M")[0];
+        assert_eq!([e.column, e.row], [0, 0]);
+        assert_eq!((e.kind, e.button), (MouseKind::Drag, MouseButton::Right));
+
+        let e = &decode_sgr(b"\x1b[<81;3;3M")[0];
+        assert_eq!(e.kind, MouseKind::ScrollDown);
+        assert_eq!(e.modifiers, Modifiers(CONTROL));
+
+        // large terminals go past the x10 limit of 223 cells
+        let e = &decode_sgr(b"\x1b[<0;300;2M")[0];
+        assert_eq!(e.column, 299);
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(decode_sgr(b"\x1b[<0;x;5M").is_empty());
+        assert!(decode_sgr(b"\x1b[<0;10").is_empty());
+        assert_eq!(decode_sgr(b"\x1b[<0;10M\x1b[<64;2;2M").len(), 1);
+    }
+
+    #[test]
+    fn x10() {
+        let e = &decode_mi(&[27, 91, 77, 32, 43, 38])[0];
+        assert_eq!((e.kind, e.button), (MouseKind::Down, MouseButton::Left));
+        assert_eq!([e.column, e.row], [10, 5]);
+
+        let e = &decode_mi(&[27, 91, 77, 35, 43, 38])[0];
+        assert_eq!(e.kind, MouseKind::Up);
+    }
 }
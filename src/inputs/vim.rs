@@ -0,0 +1,499 @@
+use std::collections::VecDeque;
+
+use super::keyboard::{Char, KbdEvent, CC};
+use crate::components::{Hook, Text};
+
+/// how many killed texts a KillRing holds on to
+pub const KILL_RING_SIZE: usize = 16;
+
+/// the editing mode of a text with the "vim" attribute
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// keys move the cursor and edit the value, nothing gets typed
+    #[default]
+    Normal,
+    /// keys get typed like in a text without the "vim" attribute
+    Insert,
+    /// keys move the cursor, extending the selection from where Visual mode was entered
+    Visual,
+}
+
+impl Mode {
+    /// the mode's name, e.g. for a status line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Normal => "NORMAL",
+            Self::Insert => "INSERT",
+            Self::Visual => "VISUAL",
+        }
+    }
+
+    /// the DECSCUSR sequence of the cursor shape the mode is shown with
+    /// a block in Normal mode, a bar in Insert mode and an underline in Visual mode
+    pub fn cursor_shape(&self) -> &'static [u8] {
+        match self {
+            Self::Normal => b"\x1b[2 q",
+            Self::Insert => b"\x1b[6 q",
+            Self::Visual => b"\x1b[4 q",
+        }
+    }
+}
+
+/// the texts that were deleted or yanked, the latest one is what p pastes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KillRing {
+    kills: VecDeque<String>,
+}
+
+impl KillRing {
+    /// adds a killed text, the oldest one is dropped once there are KILL_RING_SIZE of them
+    pub fn push(&mut self, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        if self.kills.len() == KILL_RING_SIZE {
+            self.kills.pop_front();
+        }
+        self.kills.push_back(text);
+    }
+
+    /// the last killed text
+    pub fn latest(&self) -> Option<&str> {
+        self.kills.back().map(String::as_str)
+    }
+
+    /// how many killed texts there are
+    pub fn len(&self) -> usize {
+        self.kills.len()
+    }
+
+    /// whether nothing was killed yet
+    pub fn is_empty(&self) -> bool {
+        self.kills.is_empty()
+    }
+}
+
+/// what Term::on_mode_change was given
+pub(crate) type ModeHook = Hook<Option<Box<dyn FnMut([u8; 3], Mode)>>>;
+
+/// the modal editing state of a text, only used while the text has the "vim" attribute
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Vim {
+    mode: Mode,
+    // the count prefix typed so far, e.g. the 3 of 3w
+    count: Option<usize>,
+    // an operator waiting for its motion, e.g. the d of dw
+    operator: Option<char>,
+    // the cell Visual mode was entered at
+    anchor: usize,
+}
+
+impl Vim {
+    /// the current mode
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn take_count(&mut self) -> usize {
+        self.count.take().unwrap_or(1)
+    }
+
+    fn reset(&mut self) {
+        self.count = None;
+        self.operator = None;
+    }
+}
+
+// the kinds of cells words are made of, blanks separate words
+fn class(c: Option<char>) -> u8 {
+    match c {
+        None => 0,
+        Some(c) if c.is_whitespace() => 0,
+        Some(c) if c.is_alphanumeric() || c == '_' => 1,
+        Some(_) => 2,
+    }
+}
+
+// the start of the next word, value.len() if there is none
+fn next_word(value: &[Option<char>], mut idx: usize) -> usize {
+    let len = value.len();
+    let start = value.get(idx).map_or(0, |c| class(*c));
+    if start != 0 {
+        while idx < len && class(value[idx]) == start {
+            idx += 1;
+        }
+    }
+    while idx < len && class(value[idx]) == 0 {
+        idx += 1;
+    }
+
+    idx
+}
+
+// the end of the word after idx, the last cell if there is none
+fn word_end(value: &[Option<char>], mut idx: usize) -> usize {
+    let len = value.len();
+    idx += 1;
+    while idx < len && class(value[idx]) == 0 {
+        idx += 1;
+    }
+    if idx >= len {
+        return len.saturating_sub(1);
+    }
+    let cls = class(value[idx]);
+    while idx + 1 < len && class(value[idx + 1]) == cls {
+        idx += 1;
+    }
+
+    idx
+}
+
+// the start of the word before idx
+fn prev_word(value: &[Option<char>], mut idx: usize) -> usize {
+    if idx == 0 {
+        return 0;
+    }
+    idx = (idx - 1).min(value.len().saturating_sub(1));
+    while idx > 0 && class(value[idx]) == 0 {
+        idx -= 1;
+    }
+    let cls = class(value[idx]);
+    while idx > 0 && class(value[idx - 1]) == cls {
+        idx -= 1;
+    }
+
+    idx
+}
+
+// the cell a motion key moves the cursor to, repeated count times
+fn motion(text: &Text, c: char, count: usize) -> Option<usize> {
+    let w = text.w as usize;
+    let mut idx = text.cursor_index();
+    let value = text.value.to_vec();
+    for _ in 0..count {
+        idx = match c {
+            'h' if idx % w > 0 => idx - 1,
+            'l' if idx % w + 1 < w => idx + 1,
+            'j' if idx / w + 1 < text.h as usize => idx + w,
+            'k' if idx >= w => idx - w,
+            'h' | 'l' | 'j' | 'k' => idx,
+            'w' => next_word(&value, idx),
+            'e' => word_end(&value, idx),
+            'b' => prev_word(&value, idx),
+            '0' => idx - idx % w,
+            _ => return None,
+        };
+    }
+
+    Some(idx)
+}
+
+// removes the cells of range from the value and gives back their chars
+// the cursor goes to the start of the range
+fn cut(text: &mut Text, start: usize, end: usize) -> String {
+    let end = end.min(text.value.len());
+    if start >= end {
+        return String::new();
+    }
+    let killed = text
+        .value
+        .drain(start..end)
+        .into_iter()
+        .map(|c| c.unwrap_or(' '))
+        .collect::<String>();
+    text.fill();
+    text.set_cursor_index(start);
+    text.mark_dirty();
+
+    killed.trim_end().to_string()
+}
+
+fn enter(text: &mut Text, mode: Mode) {
+    text.vim.reset();
+    text.vim.mode = mode;
+    text.vim.anchor = text.cursor_index();
+}
+
+/// handles a key press in a text with the "vim" attribute
+/// returns whether the text changed, or None if the key is left to the keymaps,
+/// which is the case for every key in Insert mode but Esc,
+/// and for keys with ctrl, alt or super and the control keys other than Esc in the other modes
+pub(crate) fn key(text: &mut Text, event: &KbdEvent, ring: &mut KillRing) -> Option<bool> {
+    if text.w == 0 || text.h == 0 {
+        return None;
+    }
+    if !(event.modifiers.is_empty() || event.modifiers.0 == super::keyboard::SHIFT) {
+        return None;
+    }
+    let c = match event.char {
+        Char::CC(CC::ESC) => {
+            if text.vim.mode == Mode::Insert && text.cx > 0 {
+                text.cx -= 1;
+            }
+            enter(text, Mode::Normal);
+            return Some(true);
+        }
+        _ if text.vim.mode == Mode::Insert => return None,
+        Char::Char(c) => c,
+        Char::CC(_) => return None,
+    };
+
+    if c.is_ascii_digit() && (c != '0' || text.vim.count.is_some()) {
+        let digit = c.to_digit(10).unwrap() as usize;
+        text.vim.count = Some((text.vim.count.unwrap_or(0) * 10 + digit).min(9999));
+        return Some(false);
+    }
+
+    match text.vim.mode {
+        Mode::Visual => Some(visual(text, c, ring)),
+        _ => Some(normal(text, c, ring)),
+    }
+}
+
+fn normal(text: &mut Text, c: char, ring: &mut KillRing) -> bool {
+    if text.vim.operator.take() == Some('d') {
+        let count = text.vim.take_count();
+        let idx = text.cursor_index();
+        let killed = match c {
+            'd' => {
+                let start = idx - idx % text.w as usize;
+                cut(text, start, start + text.w as usize * count)
+            }
+            'e' => {
+                let end = motion(text, 'e', count).unwrap();
+                cut(text, idx, end + 1)
+            }
+            c => match motion(text, c, count) {
+                Some(to) => cut(text, idx.min(to), idx.max(to)),
+                None => return false,
+            },
+        };
+        ring.push(killed);
+        text.vim.reset();
+
+        return true;
+    }
+
+    let count = text.vim.take_count();
+    match c {
+        'i' => enter(text, Mode::Insert),
+        'a' => {
+            if text
+                .value
+                .get(text.cursor_index())
+                .is_some_and(|c| c.is_some())
+            {
+                text.move_right();
+            }
+            enter(text, Mode::Insert);
+        }
+        'v' => enter(text, Mode::Visual),
+        'x' => {
+            let idx = text.cursor_index();
+            let end = (idx + count).min(idx - idx % text.w as usize + text.w as usize);
+            let killed = cut(text, idx, end);
+            ring.push(killed);
+        }
+        'd' => {
+            text.vim.operator = Some('d');
+            // the count carries over to the motion, 2dw deletes 2 words
+            text.vim.count = Some(count);
+            return false;
+        }
+        'p' => {
+            let Some(kill) = ring.latest() else {
+                return false;
+            };
+            if text
+                .value
+                .get(text.cursor_index())
+                .is_some_and(|c| c.is_some())
+            {
+                text.move_right();
+            }
+            text.insert_str(&kill.repeat(count));
+        }
+        c => match motion(text, c, count) {
+            Some(to) => text.set_cursor_index(to),
+            None => {
+                text.vim.reset();
+                return false;
+            }
+        },
+    }
+
+    true
+}
+
+fn visual(text: &mut Text, c: char, ring: &mut KillRing) -> bool {
+    let count = text.vim.take_count();
+    match c {
+        'v' => enter(text, Mode::Normal),
+        'd' | 'x' | 'y' => {
+            let idx = text.cursor_index();
+            let [start, end] = [idx.min(text.vim.anchor), idx.max(text.vim.anchor) + 1];
+            let killed = match c {
+                'y' => {
+                    let end = end.min(text.value.len());
+                    let yanked = text
+                        .value
+                        .range(start.min(end)..end)
+                        .map(|c| c.unwrap_or(' '))
+                        .collect::<String>();
+                    text.set_cursor_index(start);
+                    yanked.trim_end().to_string()
+                }
+                _ => cut(text, start, end),
+            };
+            ring.push(killed);
+            enter(text, Mode::Normal);
+        }
+        c => match motion(text, c, count) {
+            Some(to) => text.set_cursor_index(to),
+            None => return false,
+        },
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod modal {
+    use super::*;
+    use crate::components::{Container, Term};
+    use crate::inputs::keyboard::{KeyKind, Modifiers};
+    use crate::space::{border::Border, padding::Padding};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // a vim input of 3 lines of 10 cells holding value
+    fn vim_term(value: &str) -> Term {
+        let mut term = Term::new(0, 30, 8);
+        let mut c = Container::new([0, 0], 0, 0, 20, 4, Border::None, Padding::None);
+        let mut text = Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            10,
+            3,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        text.attributes.insert("vim");
+        text.insert_str(value);
+        text.cx = 0;
+        text.cy = 0;
+        c.items.push(text);
+        term.containers.push(c);
+        term.focused = Some([0, 0, 0]);
+
+        term
+    }
+
+    fn keys(term: &mut Term, keys: &str) {
+        for c in keys.chars() {
+            let event = match c {
+                '\x1b' => KbdEvent {
+                    char: Char::CC(CC::ESC),
+                    modifiers: Modifiers(0),
+                    kind: KeyKind::Press,
+                },
+                c => c.into(),
+            };
+            term.key(&event);
+        }
+    }
+
+    fn text(term: &Term) -> &Text {
+        term.input_ref(&[0, 0, 0]).unwrap()
+    }
+
+    fn value(term: &Term) -> String {
+        let value = text(term).value.iter().map(|c| c.unwrap_or(' '));
+
+        value.collect::<String>().trim_end().to_string()
+    }
+
+    #[test]
+    fn modes() {
+        let mut term = vim_term("");
+        let changes = Rc::new(RefCell::new(vec![]));
+        let seen = changes.clone();
+        term.on_mode_change(move |id, mode| seen.borrow_mut().push((id, mode)));
+
+        assert_eq!(text(&term).mode(), Some(Mode::Normal));
+        // nothing gets typed in Normal mode
+        keys(&mut term, "z");
+        assert_eq!(value(&term), "");
+
+        keys(&mut term, "ihi\x1b");
+        assert_eq!(value(&term), "hi");
+        // leaving Insert mode steps back onto the last typed char
+        assert_eq!(text(&term).cx, 1);
+        keys(&mut term, "a!\x1bv\x1b");
+        assert_eq!(value(&term), "hi!");
+        assert_eq!(
+            *changes.borrow(),
+            [
+                ([0, 0, 0], Mode::Insert),
+                ([0, 0, 0], Mode::Normal),
+                ([0, 0, 0], Mode::Insert),
+                ([0, 0, 0], Mode::Normal),
+                ([0, 0, 0], Mode::Visual),
+                ([0, 0, 0], Mode::Normal),
+            ]
+        );
+
+        // a text without the attribute has no mode and types every key
+        term.input_mut(&[0, 0, 0]).unwrap().attributes.remove("vim");
+        assert_eq!(text(&term).mode(), None);
+        keys(&mut term, "x");
+        assert_eq!(value(&term), "hix!");
+    }
+
+    #[test]
+    fn motions() {
+        let mut term = vim_term("ab cd,ef g");
+        keys(&mut term, "w");
+        assert_eq!(text(&term).cx, 3);
+        keys(&mut term, "w");
+        assert_eq!(text(&term).cx, 5);
+        keys(&mut term, "0");
+        keys(&mut term, "3w");
+        assert_eq!(text(&term).cx, 6);
+        keys(&mut term, "e");
+        assert_eq!(text(&term).cx, 7);
+        keys(&mut term, "2b");
+        assert_eq!(text(&term).cx, 5);
+        keys(&mut term, "jlk");
+        assert_eq!([text(&term).cx, text(&term).cy], [6, 0]);
+        // h doesn't leave the line
+        keys(&mut term, "9h");
+        assert_eq!(text(&term).cx, 0);
+    }
+
+    #[test]
+    fn deletions() {
+        let mut term = vim_term("one two three");
+        keys(&mut term, "dw");
+        assert_eq!(value(&term), "two three");
+        keys(&mut term, "2x");
+        assert_eq!(value(&term), "o three");
+        keys(&mut term, "2p");
+        assert_eq!(value(&term), "otwtw three");
+
+        let mut term = vim_term("line one  line two");
+        keys(&mut term, "jdd");
+        assert_eq!(value(&term), "line one");
+        assert_eq!(term.kill_ring.latest(), Some("line two"));
+
+        let mut term = vim_term("abcdef");
+        keys(&mut term, "lvlld");
+        assert_eq!(value(&term), "aef");
+        assert_eq!(text(&term).mode(), Some(Mode::Normal));
+        assert_eq!(term.kill_ring.latest(), Some("bcd"));
+    }
+}
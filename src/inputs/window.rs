@@ -1,10 +1,12 @@
 use crate::console::winsize::winsize;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum WindowEvent {
     WindowResized,
-    // WindowGainedFocus,
-    // WindowLostFocus,
+    /// the terminal window got the focus back, reported as '\x1b[I'
+    WindowGainedFocus,
+    /// the terminal window lost the focus, reported as '\x1b[O'
+    WindowLostFocus,
     // WindowMaximized,
     // WindowMinimized,
     // WindowClosed,
@@ -14,7 +16,34 @@ pub enum WindowEvent {
 
 pub struct WindowResized;
 
-use crate::components::Term;
+/// decodes a focus report, '\x1b[I' or '\x1b[O'
+/// these are only sent once the focus reporting has been turned on
+pub fn decode_focus(bytes: &[u8]) -> Option<WindowEvent> {
+    match bytes {
+        [27, 91, 73] => Some(WindowEvent::WindowGainedFocus),
+        [27, 91, 79] => Some(WindowEvent::WindowLostFocus),
+        _ => None,
+    }
+}
+
+// DECSET 1004
+pub(crate) const ENABLE_FOCUS_REPORTS: &[u8] = b"\x1b[?1004h";
+pub(crate) const DISABLE_FOCUS_REPORTS: &[u8] = b"\x1b[?1004l";
+
+#[cfg(test)]
+mod focus {
+    use super::*;
+
+    #[test]
+    fn decode() {
+        assert_eq!(decode_focus(b"\x1b[I"), Some(WindowEvent::WindowGainedFocus));
+        assert_eq!(decode_focus(b"\x1b[O"), Some(WindowEvent::WindowLostFocus));
+        // arrow keys and ss3 sequences are not focus reports
+        assert_eq!(decode_focus(b"\x1b[A"), None);
+        assert_eq!(decode_focus(b"\x1bOP"), None);
+    }
+}
+
 
 // resolve linux display/compositor protocol
 // returns 0 for wayland or 1 for x11
@@ -1,6 +1,6 @@
-use crate::components::{ComponentTree, Container, SpaceError, Term, Text};
+use crate::components::{Container, SpaceError, Text};
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub enum Layout {
     #[default]
     /// no particular layout rules are applied on the children
@@ -19,37 +19,7 @@ pub enum Layout {
 // TODO: if flex/grid then apply rules on every comp_push()/comp() methods
 // both Flex and Grid would have to use the attributes and properties feature
 
-impl Term {
-    pub(crate) fn is_valid_nonedit_id(&self, id: &[u8; 3]) -> bool {
-        id[2] % 2 != 0 && self.has_container(&[id[0], id[1]]) && !self.has_nonedit(&id)
-    }
-
-    pub(crate) fn is_valid_input_id(&self, id: &[u8; 3]) -> bool {
-        id[2] % 2 == 0 && self.has_container(&[id[0], id[1]]) && !self.has_input(&id)
-    }
-
-    pub(crate) fn is_valid_container_id(&self, id: &[u8; 2]) -> bool {
-        !self.has_container(&id)
-    }
-}
-
 impl Container {
-    pub(crate) fn area_out_of_bounds(&self, wh: &[u16; 2]) -> bool {
-        let [w, h] = *wh;
-        if self.w * self.h < w * h || w > self.w || h > self.h {
-            return true;
-        }
-
-        false
-    }
-
-    // flex and grid dont respect origins
-    pub(crate) fn origin_out_of_bounds(&self, xy: &[u16; 2], wh: &[u16; 2]) -> bool {
-        let [x0, y0] = *xy;
-        let [w, h] = *wh;
-        x0 > self.w || y0 > self.h || x0 + w > self.w || y0 + h > self.h
-    }
-
     // TODO: make width/height augmented by paddings/border or both
 
     fn input_from_meta() {}
@@ -67,9 +37,15 @@ impl Container {
 
     fn input_space_validation(&self, mut text: Text) -> Result<Text, SpaceError> {
         if self.area_out_of_bounds(&[text.w, text.h]) {
-            return Err(SpaceError::AreaOutOfBounds);
+            return Err(SpaceError::AreaOutOfBounds {
+                requested: [text.w, text.h],
+                available: [self.w, self.h],
+            });
         } else if self.origin_out_of_bounds(&[text.w, text.h], &[text.x0, text.y0]) {
-            return Err(SpaceError::OriginOutOfBounds);
+            return Err(SpaceError::OriginOutOfBounds {
+                origin: [text.x0, text.y0],
+                available: [self.w, self.h],
+            });
         }
 
         Ok(text)
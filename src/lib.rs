@@ -1,22 +1,56 @@
 //! ragout is a zero dependencies tui crate
+//! the optional async feature pulls in tokio for the event stream
+//! the console utilities and the input decoding are always there, the rest is behind the
+//! components, layout, themes and events features, all of them on by default
 #![allow(warnings)]
 // #![deny(missing_docs)]
 /// defines the 4 basic objects; ComponentTree, Term, Container and Text
+#[cfg(feature = "components")]
 pub mod components;
 /// console utilities; winsize and termios (raw_mode)
 pub mod console;
+/// loads a Term from a config text and saves it back to one
+#[cfg(feature = "config")]
+pub mod config;
+pub mod diagnostics;
+/// the event loop, reads the input and puts it back together into events
+#[cfg(feature = "events")]
+pub mod events;
 /// keyboard and mouse input detection and decoding
 pub mod inputs;
+// the tree! macro
+#[cfg(feature = "components")]
+mod macros;
+#[cfg(feature = "components")]
 pub mod overlay;
 /// rendering logic of the objects from data to the terminal display
+#[cfg(feature = "components")]
 pub mod render_pipeline;
 /// space logic, such as area checks and border/padding definitions
+#[cfg(feature = "components")]
 pub mod space;
+/// frame assertions for regression testing layouts, see assert_frame_eq
+#[cfg(feature = "components")]
+pub mod test;
 /// implements a Style type that abstracts the graphic rendition function of the vt100 video terminal
+#[cfg(feature = "themes")]
 pub mod themes;
 
+#[cfg(feature = "layout")]
 pub mod layout;
 
+pub use console::install_panic_hook;
+pub use diagnostics::{set_logger, Level};
+#[cfg(feature = "components")]
+pub use components::{LogPane, LogQueue};
+#[cfg(feature = "events")]
+pub use events::{poll_event, Event, EventLoop, EventSender, Signal};
+#[cfg(all(feature = "async", unix))]
+pub use events::event_stream;
+/// the Screen derive, a build method making the components of a struct of their ids
+#[cfg(feature = "derive")]
+pub use ragout_derive::Screen;
+
 // INFO: [very useful](https://vt100.net/docs/vt510-rm/contents.html)
 
 // TODO: scrolling probably use \x1b[y0;y1r
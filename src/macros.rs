@@ -0,0 +1,149 @@
+/// declares a Term and its component tree in one place
+/// containers get the ids [term id, 0], [term id, 1] and so on in the order they are declared,
+/// the texts in each container get the ids 0, 1, 2... in the order they are declared, whatever
+/// their kind
+/// each component takes the ContainerBuilder or TextBuilder options between its parentheses
+///
+/// evaluates to a Result of the term and a struct with a field holding the id of each named
+/// component, a name that is used twice doesn't compile
+/// the result is the first error a builder returns, see ContainerBuilder::build and
+/// TextBuilder::build
+///
+/// # Examples
+/// ```
+/// # use ragout::tree;
+/// # use ragout::space::{Area, Pos};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let (mut term, ids) = tree! {
+///     term(0, 80, 24) {
+///         sidebar: container(.pos(Pos::Start, Pos::Start).area(Area::Percent { w: 30, h: 100 })) {
+///             title: nonedit(.pos(Pos::Start, Pos::Start).area(Area::Values { w: 10, h: 1 })),
+///             search_box: input(
+///                 .pos(Pos::Start, Pos::Value(1))
+///                 .area(Area::Values { w: 20, h: 1 })
+///                 .attribute("search")
+///             ),
+///         },
+///         main: container(.pos(Pos::End, Pos::Start).area(Area::Percent { w: 70, h: 100 })) {},
+///     }
+/// }?;
+/// term.focus(&ids.search_box)?;
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! tree {
+    (@kind input) => {
+        $crate::components::TextKind::Input
+    };
+    (@kind nonedit) => {
+        $crate::components::TextKind::NonEdit
+    };
+    (
+        term($tid:expr, $w:expr, $h:expr) {
+            $(
+                $cname:ident : container $( ( $( .$copt:ident ( $( $carg:expr ),* $(,)? ) )* ) )? {
+                    $(
+                        $tname:ident : $kind:ident
+                            $( ( $( .$topt:ident ( $( $targ:expr ),* $(,)? ) )* ) )?
+                    ),* $(,)?
+                }
+            ),* $(,)?
+        }
+    ) => {{
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct Ids {
+            $(
+                pub $cname: [u8; 2],
+                $( pub $tname: [u8; 3], )*
+            )*
+        }
+
+        (|| -> ::std::result::Result<
+            ($crate::components::Term, Ids),
+            $crate::components::ComponentTreeError,
+        > {
+            let tid: u8 = $tid;
+            let mut term = $crate::components::Term::new(tid, $w, $h);
+            #[allow(unused_mut)]
+            let mut cid: u8 = 0;
+            $(
+                let $cname = $crate::components::Container::builder([tid, cid])
+                    $( $( .$copt( $( $carg ),* ) )* )?
+                    .build(&mut term)?;
+                cid += 1;
+                #[allow(unused_mut, unused_variables)]
+                let mut next: u8 = 0;
+                $(
+                    let $tname = $crate::components::Text::builder_of(
+                        $crate::tree!(@kind $kind),
+                        [$cname[0], $cname[1], next],
+                    )
+                        $( $( .$topt( $( $targ ),* ) )* )?
+                        .build(&mut term)?;
+                    // the 257th text gets a taken id and fails to build
+                    next = next.wrapping_add(1);
+                )*
+            )*
+            // the last bump is never read otherwise
+            let _ = cid;
+
+            Ok((term, Ids { $( $cname, $( $tname, )* )* }))
+        })()
+    }};
+}
+
+#[cfg(test)]
+mod tree {
+    use crate::components::ComponentTreeError;
+    use crate::space::{border::Border, Area, Pos};
+
+    #[test]
+    fn ids() {
+        let (term, ids) = tree! {
+            term(3, 40, 10) {
+                sidebar: container(.pos(Pos::Start, Pos::Start).area(Area::Percent { w: 50, h: 100 })) {
+                    title: nonedit(
+                        .pos(Pos::Start, Pos::Start)
+                        .area(Area::Values { w: 10, h: 1 })
+                        .value(&[Some('f')])
+                    ),
+                    search_box: input(
+                        .pos(Pos::Start, Pos::Value(1))
+                        .area(Area::Values { w: 10, h: 1 })
+                        .attribute("search")
+                    ),
+                    notes: input(.pos(Pos::Start, Pos::Value(2)).area(Area::Values { w: 10, h: 1 })),
+                },
+                main: container(
+                    .pos(Pos::End, Pos::Start)
+                    .area(Area::Percent { w: 50, h: 100 })
+                    .border(Border::rounded())
+                ) {},
+            }
+        }
+        .unwrap();
+        assert_eq!([ids.sidebar, ids.main], [[3, 0], [3, 1]]);
+        assert_eq!(
+            [ids.title, ids.search_box, ids.notes],
+            [[3, 0, 0], [3, 0, 1], [3, 0, 2]]
+        );
+        assert_eq!(term.nonedit_ref(&ids.title).unwrap().value_string(), "f");
+        let search = term.input_ref(&ids.search_box).unwrap();
+        assert!(search.attributes.contains("search"));
+        assert_eq!(term.container_ref(&ids.main).unwrap().x0, 20);
+    }
+
+    #[test]
+    fn space_error() {
+        let res = tree! {
+            term(0, 20, 5) {
+                wide: container(.area(Area::Values { w: 30, h: 5 })) {},
+            }
+        };
+        assert!(matches!(
+            res,
+            Err(ComponentTreeError::AreaOutOfBounds { .. })
+        ));
+    }
+}
@@ -6,9 +6,23 @@ use crate::components::*;
 use crate::space::{border::Border, padding::Padding};
 use crate::themes::Style;
 
+mod backend;
+mod diff;
+mod inline;
+mod linear;
+mod print;
+mod junction;
+mod region;
 pub mod container;
+mod scratch;
 pub mod term;
 pub mod text;
+mod tree;
+
+pub(crate) use backend::Held;
+pub(crate) use inline::Inline;
+pub(crate) use linear::Linear;
+pub(crate) use scratch::Scratch;
 
 // NOTE: an object can not be initialized unless
 // its id is valid,
@@ -56,6 +70,53 @@ pub(crate) fn spread_padding(p: &Padding) -> [u16; 8] {
     }
 }
 
+// the cursor position escape sequence
+// every cursor movement of a Term goes through this
+pub(crate) fn cup(x: u16, y: u16) -> String {
+    format!("\x1b[{};{}f", y, x)
+}
+
+// erases the [x, y, w, h] area, the erased cells take the background of the current style
+// x is the 1 based column and y the row, like the ones of the components' absolute origins
+pub(crate) fn erase(s: &mut String, [x, y, w, h]: [u16; 4]) {
+    for row in y..y + h {
+        *s += &format!("\x1b[{};{}f\x1b[{}X", row, x, w);
+    }
+}
+
+// writes a child's prepared cells into its parent's cells, with the child origin at [x0, y0]
+// every child cell that falls outside of the parent's w * h grid is dropped
+// so that a child never writes over its parent's neighbors or wraps onto the next line
+pub(crate) fn blit(
+    dst: &mut [Option<char>],
+    [dw, dh]: [u16; 2],
+    src: &[Option<char>],
+    [sw, sh]: [u16; 2],
+    [x0, y0]: [u16; 2],
+) {
+    for line in 0..sh {
+        let y = y0 as usize + line as usize;
+        if y >= dh as usize {
+            break;
+        }
+
+        for col in 0..sw {
+            let x = x0 as usize + col as usize;
+            if x >= dw as usize {
+                break;
+            }
+
+            let cell = src
+                .get(col as usize + line as usize * sw as usize)
+                .copied()
+                .flatten();
+            if cell.is_some() {
+                dst[x + y * dw as usize] = cell;
+            }
+        }
+    }
+}
+
 fn log_buf(buf: &[Option<char>], w: u16, h: u16) {
     print!("lines");
     for ih in 0..h {
@@ -66,3 +127,325 @@ fn log_buf(buf: &[Option<char>], w: u16, h: u16) {
     }
     println!("");
 }
+
+#[cfg(test)]
+mod clip {
+    use crate::components::{Container, Term, Text};
+    use crate::space::{border::Border, padding::Padding, Overflow};
+
+    // two 10x3 containers side by side, the left one holding a 10x3 input
+    fn term() -> Term {
+        let mut term = Term::new(0, 30, 6);
+        let mut left = Container::new([0, 0], 0, 0, 10, 3, Border::None, Padding::None);
+        left.items.push(Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            1,
+            0,
+            10,
+            3,
+            &[],
+            Border::None,
+            Padding::None,
+        ));
+        term.containers.push(left);
+        term.containers
+            .push(Container::new([0, 1], 10, 0, 10, 3, Border::None, Padding::None));
+
+        term
+    }
+
+    fn assert_untouched(term: &Term, cells: &[Option<char>]) {
+        // the right container cells
+        for y in 0..3 {
+            for x in 10..20 {
+                assert_eq!(cells[(x + y * term.w) as usize], None, "cell [{x}, {y}]");
+            }
+        }
+        // the rows under both containers
+        assert!(cells[(3 * term.w) as usize..].iter().all(|c| c.is_none()));
+    }
+
+    #[test]
+    fn oversized_value() {
+        let mut term = term();
+        term.input_mut(&[0, 0, 0]).unwrap().value = vec![Some('x'); 100].into();
+
+        let cells = term.prepare();
+        assert!(cells[..10].iter().all(|c| *c == Some('x')));
+        assert_untouched(&term, &cells);
+    }
+
+    #[test]
+    fn oversized_area() {
+        let mut term = term();
+        let input = term.input_mut(&[0, 0, 0]).unwrap();
+        input.w = 25;
+        input.h = 5;
+        input.invalidate();
+        input.value = vec![Some('x'); 125].into();
+
+        let cells = term.prepare();
+        assert_untouched(&term, &cells);
+    }
+
+    #[test]
+    fn bordered_oversized_value() {
+        let mut term = term();
+        let input = term.input_mut(&[0, 0, 0]).unwrap();
+        input.w = 8;
+        input.h = 1;
+        input.border = Border::Uniform('#');
+        input.invalidate();
+        input.value = vec![Some('x'); 40].into();
+
+        let cells = term.prepare();
+        assert_eq!(cells[9], Some('#'));
+        assert_eq!(cells[1 + term.w as usize], Some('x'));
+        assert_untouched(&term, &cells);
+    }
+
+    #[test]
+    fn ellipsis() {
+        let mut term = term();
+        let input = term.input_mut(&[0, 0, 0]).unwrap();
+        input.value = vec![Some('x'); 31].into();
+        input.overflow(Overflow::Ellipsis);
+
+        let cells = term.prepare();
+        assert_eq!(cells[(9 + 2 * term.w) as usize], Some('…'));
+        assert_eq!(cells[(8 + 2 * term.w) as usize], Some('x'));
+        assert_untouched(&term, &cells);
+
+        // a value that fits gets no ellipsis
+        term.input_mut(&[0, 0, 0]).unwrap().value = vec![Some('x'); 30].into();
+        let cells = term.prepare();
+        assert_eq!(cells[(9 + 2 * term.w) as usize], Some('x'));
+    }
+
+    #[test]
+    fn short_value() {
+        let mut term = term();
+        term.input_mut(&[0, 0, 0]).unwrap().value = vec![Some('x'); 4].into();
+
+        let cells = term.prepare();
+        assert!(cells[..4].iter().all(|c| *c == Some('x')));
+        assert!(cells[4..].iter().all(|c| c.is_none()));
+    }
+}
+
+#[cfg(test)]
+mod allocations {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    use crate::components::{Container, Term, Text};
+    use crate::space::{border::Border, Area, Overflow, Pos};
+
+    // the system allocator, counting the allocations of each thread
+    // so that the tests running next to each other don't get in the count
+    struct Counting;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static COUNTING: Counting = Counting;
+
+    fn allocations(f: impl FnOnce()) -> usize {
+        let before = ALLOCATIONS.with(Cell::get);
+        f();
+
+        ALLOCATIONS.with(Cell::get) - before
+    }
+
+    #[test]
+    fn steady_frame() {
+        let mut term = Term::new(0, 40, 10);
+        Container::builder([0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 30, h: 8 })
+            .border(Border::rounded())
+            .build(&mut term)
+            .unwrap();
+        Text::input_builder([0, 0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 20, h: 3 })
+            .border(Border::Uniform('*'))
+            .build(&mut term)
+            .unwrap();
+        Text::nonedit_builder([0, 0, 1])
+            .pos(Pos::Start, Pos::Value(4))
+            .area(Area::Values { w: 10, h: 2 })
+            .overflow(Overflow::Ellipsis)
+            .build(&mut term)
+            .unwrap();
+        term.input_mut(&[0, 0, 0]).unwrap().replace_value("typed");
+        term.nonedit_mut(&[0, 0, 1]).unwrap().replace_value(&"héllo ".repeat(5));
+
+        let mut sink = std::io::sink();
+        // the buffers get their capacity in the first frames
+        assert_ne!(allocations(|| term.render_to(&mut sink).unwrap()), 0);
+        term.render_to(&mut sink).unwrap();
+        assert_eq!(allocations(|| term.render_to(&mut sink).unwrap()), 0);
+
+        // a value edit doesn't change any size
+        term.input_mut(&[0, 0, 0]).unwrap().insert('!');
+        assert_eq!(allocations(|| term.render_to(&mut sink).unwrap()), 0);
+        // only the cell that changed is drawn
+        let out = String::from_utf8_lossy(&term.scratch.out);
+        assert!(out.contains('!') && !out.contains("typed"));
+    }
+}
+
+#[cfg(test)]
+mod styles {
+    use crate::components::{ComponentTreeError, Container, Term, Text, Validation};
+    use crate::console::Capabilities;
+    use crate::space::{border::Border, padding::Padding, Area, Pos};
+    use crate::themes::Style;
+
+    // an input with a border and a column of padding on each side, at [3, 1]
+    fn term() -> Term {
+        let mut term = Term::new(0, 30, 8);
+        term.capabilities(Capabilities::full());
+        Container::builder([0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 20, h: 6 })
+            .build(&mut term)
+            .unwrap();
+        Text::input_builder([0, 0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 8, h: 3 })
+            .border(Border::Uniform('*'))
+            .padding(Padding::inner(0, 0, 1, 1))
+            .build(&mut term)
+            .unwrap();
+        dirty(&mut term);
+
+        term
+    }
+
+    fn dirty(term: &mut Term) -> String {
+        let mut out = vec![];
+        term.render_dirty_to(&mut out).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn styled() {
+        let mut term = term();
+        let yellow = Style::new()
+            .text_color(&[200, 200, 0])
+            .background_color(&[40, 40, 40]);
+        term.set_style(&[0, 0, 0], yellow.clone()).unwrap();
+        let frame = dirty(&mut term);
+        assert!(frame.contains(&yellow.style()));
+        assert!(frame.ends_with("\x1b[0m"));
+        // the padding is left alone unless it is asked for
+        assert!(!frame.contains("\x1b[1;2f\x1b[6X"));
+        term.text_mut(&[0, 0, 0]).unwrap().style_padding(true);
+        assert!(dirty(&mut term).contains("\x1b[1;2f\x1b[6X"));
+
+        // the focus style wins while the text is focused
+        let focus = Style::new().bold();
+        term.text_mut(&[0, 0, 0]).unwrap().set_focus_style(Some(focus.clone()));
+        dirty(&mut term);
+        term.focus(&[0, 0, 0]).unwrap();
+        let frame = dirty(&mut term);
+        assert!(frame.contains(&focus.style()) && !frame.contains(&yellow.style()));
+        term.focused = None;
+        assert!(dirty(&mut term).contains(&yellow.style()));
+        assert!(dirty(&mut term).is_empty());
+
+        let blue = Style::new().background_color(&[0, 0, 80]);
+        term.set_style(&[0, 0], blue.clone()).unwrap();
+        let frame = dirty(&mut term);
+        assert!(frame.contains(&format!("{}\x1b[0;1f\x1b[20X", blue.style())));
+        assert_eq!(
+            term.set_style(&[0, 9], blue),
+            Err(ComponentTreeError::BadID { id: vec![0, 9] })
+        );
+    }
+
+    #[test]
+    fn monochrome() {
+        let mut term = term();
+        let style = Style::new()
+            .text_color(&[200, 200, 0])
+            .background_color(&[40, 40, 40]);
+        term.set_style(&[0, 0], style.clone().bold()).unwrap();
+        term.set_monochrome(true);
+        let frame = dirty(&mut term);
+        assert!(!frame.contains("38;") && !frame.contains("48;"));
+        assert!(frame.contains("\x1b[1m"));
+
+        term.set_style(&[0, 0, 0], style.clone()).unwrap();
+        term.theme.set("focused.border", &style.reverse());
+        term.focus(&[0, 0, 0]).unwrap();
+        term.set_validation(&[0, 0, 0], Validation::Valid).unwrap();
+        let frame = dirty(&mut term);
+        assert!(!frame.contains("38;") && !frame.contains("48;"));
+        // the attributes are kept, the focused border is reversed and the content underlined
+        assert!(frame.contains("\x1b[7m") && frame.contains("\x1b[4m"));
+    }
+
+    #[test]
+    fn focused() {
+        let mut term = term();
+        term.focus(&[0, 0, 0]).unwrap();
+        assert!(term.is_dirty());
+        let frame = dirty(&mut term);
+        assert!(frame.contains("\x1b[1m\x1b[0;1f"));
+        assert!(frame.contains("\x1b[4m"));
+
+        // the blur takes the highlight off
+        term.focused = None;
+        assert!(term.is_dirty());
+        let frame = dirty(&mut term);
+        assert!(!frame.contains("\x1b[1m") && !frame.contains("\x1b[4m"));
+        assert!(!term.is_dirty());
+
+        // a borderless text highlights its container's border
+        let mut term = Term::new(0, 30, 8);
+        term.capabilities(Capabilities::full());
+        Container::builder([0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 20, h: 6 })
+            .border(Border::Uniform('#'))
+            .build(&mut term)
+            .unwrap();
+        Text::input_builder([0, 0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 8, h: 1 })
+            .build(&mut term)
+            .unwrap();
+        term.text_mut(&[0, 0, 0])
+            .unwrap()
+            .set_focus_border_style(Some(Style::new().italic()));
+        dirty(&mut term);
+        term.focus(&[0, 0, 0]).unwrap();
+        assert!(dirty(&mut term).contains("\x1b[3m\x1b[0;1f#"));
+
+        // apps that draw their own highlight
+        term.style_focus(false);
+        assert!(dirty(&mut term).contains("\x1b[0;1f#"));
+        term.focused = None;
+        term.focus(&[0, 0, 0]).unwrap();
+        assert!(!term.is_dirty());
+    }
+}
@@ -0,0 +1,266 @@
+use std::io::Write;
+
+use crate::components::{ScreenMode, Term};
+use crate::console::Backend;
+
+// the backend a Term was given, see Term::backend
+#[derive(Default)]
+pub(crate) struct Held(Option<Box<dyn Backend>>);
+
+// a terminal is not shared, a clone of a Term draws through the unix backend again
+impl Clone for Held {
+    fn clone(&self) -> Self {
+        Self(None)
+    }
+}
+
+// the backend doesn't make a term
+impl PartialEq for Held {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for Held {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("Backend"),
+            None => f.write_str("UnixBackend"),
+        }
+    }
+}
+
+impl Term {
+    /// makes this Term draw through backend in Term::start_backend, Term::draw and the other
+    /// backend methods, a Term that wasn't given one uses a UnixBackend
+    /// the methods that take a StdoutLock write to it as they always did
+    pub fn backend(&mut self, backend: impl Backend + 'static) {
+        self.backend = Held(Some(Box::new(backend)));
+    }
+
+    /// takes back the backend this Term was given, the Term goes back to the unix one
+    pub fn take_backend(&mut self) -> Option<Box<dyn Backend>> {
+        self.backend.0.take()
+    }
+
+    // the backend to draw through, it goes back to this term through put_backend
+    fn held(&mut self) -> std::io::Result<Box<dyn Backend>> {
+        if let Some(backend) = self.backend.0.take() {
+            return Ok(backend);
+        }
+        #[cfg(unix)]
+        return Ok(Box::new(crate::console::backend::UnixBackend::new()));
+        #[cfg(windows)]
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "there is no default backend on windows, see Term::backend",
+        ));
+    }
+
+    // hands the backend to f, then gives it back to this term
+    fn with_backend<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self, &mut Box<dyn Backend>) -> std::io::Result<T>,
+    ) -> std::io::Result<T> {
+        let mut backend = self.held()?;
+        let done = f(self, &mut backend);
+        self.backend = Held(Some(backend));
+
+        done
+    }
+
+    // writes bytes through the backend this term was given, or to stdout when it wasn't given
+    // one and stdout is a terminal, the cursor methods write through this
+    pub(crate) fn write_out(&mut self, bytes: &[u8]) {
+        match self.backend.0.as_mut() {
+            Some(backend) => {
+                _ = backend.write_all(bytes);
+                _ = backend.flush();
+            }
+            None if crate::console::stdout_is_tty() => {
+                let mut writer = std::io::stdout().lock();
+                _ = writer.write_all(bytes);
+                _ = writer.flush();
+            }
+            None => (),
+        }
+    }
+
+    /// Term::start through the backend: raw mode, and for fullscreen Terms the alternate screen
+    /// and a first frame, Term::stop_backend undoes it
+    pub fn start_backend(&mut self) -> std::io::Result<()> {
+        self.with_backend(|term, backend| {
+            backend.enable_raw_mode()?;
+            if term.mode == ScreenMode::Fullscreen {
+                backend.enter_alternate_screen()?;
+                term.attributes.insert("alt-screen");
+                backend.write_all(b"\x1b[H\x1b[J")?;
+                term.forget_frame();
+                term.render_to(backend)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// leaves the alternate screen if the Term is in it and turns raw mode off
+    pub fn stop_backend(&mut self) -> std::io::Result<()> {
+        self.with_backend(|term, backend| {
+            if term.attributes.remove("alt-screen") {
+                backend.leave_alternate_screen()?;
+            }
+            backend.disable_raw_mode()
+        })
+    }
+
+    /// Term::render through the backend
+    pub fn draw(&mut self) -> std::io::Result<()> {
+        self.with_backend(|term, backend| term.render_to(backend))
+    }
+
+    /// Term::render_dirty through the backend
+    pub fn draw_dirty(&mut self) -> std::io::Result<()> {
+        self.with_backend(|term, backend| term.render_dirty_to(backend))
+    }
+
+    /// resizes this Term to the size of the backend's terminal, see Term::resize
+    /// returns whether the size changed
+    pub fn fit_backend(&mut self) -> std::io::Result<bool> {
+        self.with_backend(|term, backend| {
+            let [w, h] = backend.size()?;
+            if [w, h] == [term.w, term.h] {
+                return Ok(false);
+            }
+            term.resize(w, h);
+
+            Ok(true)
+        })
+    }
+}
+
+#[cfg(test)]
+mod backend {
+    use super::*;
+    use crate::components::{Container, Text};
+    use crate::space::{Area, Pos};
+    use std::sync::{Arc, Mutex};
+
+    // what the test backend was told, shared with the test after the term took the backend
+    #[derive(Default)]
+    struct Seen {
+        bytes: Vec<u8>,
+        raw: bool,
+    }
+
+    struct Double {
+        seen: Arc<Mutex<Seen>>,
+        size: [u16; 2],
+    }
+
+    impl Write for Double {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.seen.lock().unwrap().bytes.extend_from_slice(buf);
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Backend for Double {
+        fn enable_raw_mode(&mut self) -> std::io::Result<()> {
+            self.seen.lock().unwrap().raw = true;
+
+            Ok(())
+        }
+
+        fn disable_raw_mode(&mut self) -> std::io::Result<()> {
+            self.seen.lock().unwrap().raw = false;
+
+            Ok(())
+        }
+
+        fn size(&self) -> std::io::Result<[u16; 2]> {
+            Ok(self.size)
+        }
+    }
+
+    fn term(size: [u16; 2]) -> (Term, Arc<Mutex<Seen>>) {
+        let mut term = Term::new(0, 20, 4);
+        Container::builder([0, 0])
+            .area(Area::Values { w: 20, h: 4 })
+            .build(&mut term)
+            .unwrap();
+        Text::nonedit_builder([0, 0, 1])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 10, h: 1 })
+            .value_str("drawn")
+            .build(&mut term)
+            .unwrap();
+        let seen = Arc::new(Mutex::new(Seen::default()));
+        term.backend(Double {
+            seen: seen.clone(),
+            size,
+        });
+
+        (term, seen)
+    }
+
+    #[test]
+    fn drawn() {
+        let (mut term, seen) = term([20, 4]);
+        term.screen_mode(ScreenMode::Fullscreen);
+        term.start_backend().unwrap();
+        {
+            let seen = seen.lock().unwrap();
+            assert!(seen.raw);
+            assert!(seen.bytes.starts_with(b"\x1b[?1049h"));
+        }
+
+        seen.lock().unwrap().bytes.clear();
+        term.forget_frame();
+        term.draw().unwrap();
+        let frame = term.frame_with(&term.capabilities.clone());
+        assert_eq!(seen.lock().unwrap().bytes, frame.into_bytes());
+
+        term.stop_backend().unwrap();
+        let seen = seen.lock().unwrap();
+        assert!(!seen.raw);
+        assert!(seen.bytes.ends_with(b"\x1b[?1049l"));
+    }
+
+    #[test]
+    fn cursor() {
+        let (mut term, seen) = term([20, 4]);
+        term.move_cursor_to(5, 2).unwrap();
+        term.save_cursor();
+        term.move_cursor_to(19, 3).unwrap();
+        term.restore_cursor();
+        assert_eq!(
+            seen.lock().unwrap().bytes,
+            b"\x1b[2;5f\x1b7\x1b[3;19f\x1b8\x1b[2;5f"
+        );
+
+        // a linear term keeps track of the cursor without moving it
+        term.screen_mode(ScreenMode::Linear);
+        seen.lock().unwrap().bytes.clear();
+        term.move_cursor_to(3, 1).unwrap();
+        term.save_cursor();
+        term.restore_cursor();
+        assert!(seen.lock().unwrap().bytes.is_empty());
+        assert_eq!([term.cx, term.cy], [3, 1]);
+    }
+
+    #[test]
+    fn fitted() {
+        let (mut term, _) = term([30, 6]);
+        assert_eq!(term.fit_backend().unwrap(), true);
+        assert_eq!([term.w, term.h], [30, 6]);
+        assert_eq!(term.fit_backend().unwrap(), false);
+
+        assert!(term.take_backend().is_some());
+        assert!(term.take_backend().is_none());
+    }
+}
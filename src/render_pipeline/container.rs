@@ -3,24 +3,46 @@ use std::io::{StdoutLock, Write};
 use std::str::Chars;
 
 use crate::components::*;
-use crate::space::{border::Border, padding::Padding};
+use crate::console::Capabilities;
+use crate::space::{border::Border, padding::Padding, resolve_wh};
 use crate::themes::Style;
 
-use super::spread_padding;
+use super::{spread_padding, Scratch};
 
 impl Container {
     /// wrapper around the render_border and render_value method calls
     pub fn render(&self, writer: &mut StdoutLock) {
-        self.render_border(writer);
-        self.render_value(writer);
+        self.render_with(writer, Capabilities::detected());
+    }
+
+    pub(crate) fn render_with(&self, writer: &mut impl Write, caps: &Capabilities) {
+        self.render_border_with(writer, caps);
+        self.render_value_with(writer, caps);
     }
 
     /// renders only the items inside the container
     pub fn render_value(&self, writer: &mut StdoutLock) {
-        let [_, pol, pot, _, _, pil, pit, _] = spread_padding(&self.padding);
+        self.render_value_with(writer, Capabilities::detected());
+    }
+
+    pub(crate) fn render_value_with(&self, writer: &mut impl Write, caps: &Capabilities) {
+        let [_, pol, pot, _, pir, pil, pit, pib] = spread_padding(&self.padding);
         let cb = if let Border::None = self.border { 0 } else { 1 };
 
-        self.items.iter().for_each(|t| {
+        // the area goes in the style first, the texts are rendered over it
+        if let Some(style) = &self.style {
+            let [x, y] = [self.x0 + pol + cb + pil + 1, self.y0 + pot + cb + pit];
+            let area = match self.attributes.contains("style-padding") {
+                true => [x - pil, y - pit, pil + self.w + pir, pit + self.h + pib],
+                false => [x, y, self.w, self.h],
+            };
+            let mut s = caps.adapt_sgr(&style.style());
+            super::erase(&mut s, area);
+            s += "\x1b[0m";
+            _ = writer.write_all(s.as_bytes());
+        }
+
+        self.items.iter().filter(|t| !t.is_hidden()).for_each(|t| {
             let [_, tpol, tpot, _, _, tpil, tpit, _] = spread_padding(&t.padding);
             let tb = if let Border::None = t.border { 0 } else { 1 };
 
@@ -29,20 +51,26 @@ impl Container {
                 self.y0 + pot + cb + pit + t.y0 + tpot + tb + tpit,
             ];
 
-            t.render_value(writer);
+            t.render_value_with(writer, caps);
         });
     }
 
     /// renders only the container border
     pub fn render_border(&self, writer: &mut StdoutLock) {
+        self.render_border_with(writer, Capabilities::detected());
+    }
+
+    pub(crate) fn render_border_with(&self, writer: &mut impl Write, caps: &Capabilities) {
         let [_, pol, pot, _, pir, pil, pit, pib] = spread_padding(&self.padding);
         let [xb, yb] = [self.x0 + pol + 1, self.y0 + pot];
-        let mut s = format!("{}\x1b[{};{}f", &self.bstyle, yb, xb);
+        let bstyle = self.focus.as_ref().unwrap_or(&self.bstyle);
+        let mut s = format!("{}\x1b[{};{}f", caps.adapt_sgr(bstyle), yb, xb);
 
         let wb = pil + 1 + self.w + 1 + pir;
         let hb = pit + 1 + self.h + 1 + pib;
 
         if let Border::Uniform(c) = self.border {
+            let c = caps.adapt_char(c);
             for _ in 0..wb {
                 s.push(c)
             }
@@ -66,53 +94,25 @@ impl Container {
 
             s.push_str("\x1b[0m");
 
-            writer.write(s.as_bytes());
+            _ = writer.write_all(s.as_bytes());
         }
     }
 
     // adds padding and border to the width and height of the container
     // should be called from the sef render method
     pub(crate) fn decorate(&self) -> [u16; 2] {
-        let [mut wextra, mut hextra] = match self.border {
-            Border::None => [self.w, self.h],
-            _ => [self.w + 2, self.h + 2],
-        };
-
-        [wextra, hextra] = match self.padding {
-            Padding::None => [wextra, hextra],
-            Padding::Inner {
-                top,
-                bottom,
-                right,
-                left,
-            } => [wextra + right + left, hextra + top + bottom],
-            Padding::Outer {
-                top,
-                bottom,
-                right,
-                left,
-            } => [wextra + right + left, hextra + top + bottom],
-            Padding::InOut {
-                inner_top,
-                inner_bottom,
-                inner_right,
-                inner_left,
-                outer_top,
-                outer_bottom,
-                outer_right,
-                outer_left,
-            } => [
-                wextra + inner_right + inner_left + outer_right + outer_left,
-                hextra + inner_top + inner_bottom + outer_top + outer_bottom,
-            ],
-        };
+        self.decoration.get(self.geometry(), || {
+            // a size that doesn't fit in a u16 saturates, it is then too big for any parent
+            let [wextra, hextra] = resolve_wh(&self.border, &self.padding).unwrap_or([u16::MAX; 2]);
 
-        [wextra, hextra]
+            [self.w.saturating_add(wextra), self.h.saturating_add(hextra)]
+        })
     }
 
     // prepares the border and paddings of the container
     // then calls all the self items prepare methods
-    pub(super) fn prepare(&self) -> (Vec<Option<char>>, [u16; 2]) {
+    // the lines are a grid of scratch, they go back to it once used
+    pub(super) fn prepare(&self, scratch: &mut Scratch) -> (Vec<Option<char>>, [u16; 2]) {
         // make out each line of the item, padding and border included
         // then render line
         // until all lines are rendered
@@ -122,42 +122,33 @@ impl Container {
             _ => 1,
         };
 
-        let mut lines: Vec<Option<char>> = vec![];
-
         // wx is the number of chars in a line
         // hx is the number of lines
         let [wx, mut hx] = self.decorate();
-        lines.resize((wx * hx) as usize, None);
+        let mut lines = scratch.grid(wx as usize * hx as usize);
 
         self.process(&mut lines);
 
-        self.items.iter().for_each(|t| {
-            let mut idx = pol + brdr + pil + t.x0 + (pot + brdr + pit + t.y0) * wx;
-            let mut line = 0;
-            let (cells, [twx, thx]) = t.prepare();
-
-            loop {
-                // write the item line inside the container lines
-                for tidx in 0..twx {
-                    let cell = cells[(tidx + line * twx) as usize];
-                    if cell.is_some() {
-                        lines[idx as usize] = cell;
-                    }
-                    idx += 1;
-                }
-
-                // skip the end of current line that is not inside item twx
-                // then skip the next line beginning up to t.xy
-                idx += wx - twx;
-
-                // increment item lines by one until last line
-                line += 1;
-                if line == thx {
-                    break;
-                }
-            }
+        // the items are first laid on the container's value area alone
+        // so that whatever part of them lies outside of it gets clipped
+        // instead of running over the container's border or its neighbors
+        let mut value = scratch.grid(self.w as usize * self.h as usize);
+
+        self.items.iter().filter(|t| !t.is_hidden()).for_each(|t| {
+            let (cells, twh) = t.prepare(scratch);
+            super::blit(&mut value, [self.w, self.h], &cells, twh, [t.x0, t.y0]);
+            scratch.put_back(cells);
         });
 
+        super::blit(
+            &mut lines,
+            [wx, hx],
+            &value,
+            [self.w, self.h],
+            [pol + brdr + pil, pot + brdr + pit],
+        );
+        scratch.put_back(value);
+
         // log_buf(&lines, wx, hx);
         (lines, [wx, hx])
     }
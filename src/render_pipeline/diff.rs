@@ -0,0 +1,309 @@
+use crate::components::grapheme::{Clusters, WIDE};
+use crate::components::Term;
+use crate::space::border::Border;
+
+use super::scratch::{push_char, push_cup};
+use super::{spread_padding, Scratch};
+
+// the unchanged cells a run of changed ones goes on over, drawing them again
+// takes fewer bytes than moving the cursor past them
+const GAP: usize = 4;
+
+// paints the cells of the [x, y, w, h] area of a grid gw cells wide that fall in clip with id,
+// the ring only paints the edges of the area
+fn paint(painted: &mut [u16], gw: u16, area: [u16; 4], clip: [u16; 4], ring: bool, id: u16) {
+    let [x, y, w, h] = area;
+    let [cx, cy, cw, ch] = clip;
+    let (x0, y0) = (x.max(cx), y.max(cy));
+    let x1 = x.saturating_add(w).min(cx.saturating_add(cw));
+    let y1 = y.saturating_add(h).min(cy.saturating_add(ch));
+    for row in y0..y1 {
+        for col in x0..x1 {
+            let edge = row == y || col == x || row + 1 == y + h || col + 1 == x + w;
+            if !ring || edge {
+                painted[row as usize * gw as usize + col as usize] = id;
+            }
+        }
+    }
+}
+
+// the sgr that goes from the style with id from to the one with id to, every style
+// starts from a reset
+fn switch(out: &mut Vec<u8>, sgr: &[String], to: u16) {
+    out.extend_from_slice(b"\x1b[0m");
+    if let Some(style) = sgr.get(to as usize) {
+        out.extend_from_slice(style.as_bytes());
+    }
+}
+
+impl Term {
+    /// makes the next Term::render draw every cell, e.g. once something else drew over
+    /// the display, Term::clear calls it
+    /// the renders after it only draw the cells whose char or style changed since the frame
+    /// before them
+    pub fn forget_frame(&self) {
+        self.scratch.stale.set(true);
+    }
+
+    // paints every cell of the term in the style of the part of a component it shows,
+    // the borders and the areas of the containers and the texts, over each other like
+    // they are drawn
+    pub(super) fn paint_styles(&self, scratch: &mut Scratch) {
+        let [w, h] = [self.w, self.h];
+        let mut painted = std::mem::take(&mut scratch.painted);
+        painted.clear();
+        painted.resize(w as usize * h as usize, 0);
+        let styles = &mut scratch.styles;
+        let term = [0, 0, w, h];
+
+        for (_, c) in self.draw_order() {
+            let [_, pol, pot, _, pir, pil, pit, pib] = spread_padding(&c.padding);
+            let b = if let Border::None = c.border { 0 } else { 1 };
+            let frame = [
+                c.x0 + pol,
+                c.y0 + pot,
+                pil + c.w + pir + 2 * b,
+                pit + c.h + pib + 2 * b,
+            ];
+            if b == 1 {
+                let id = styles.id(c.focus.as_ref().unwrap_or(&c.bstyle));
+                paint(&mut painted, w, frame, term, true, id);
+            }
+            let inner = [frame[0] + b + pil, frame[1] + b + pit, c.w, c.h];
+            if let Some(style) = &c.style {
+                let area = match c.attributes.contains("style-padding") {
+                    true => [frame[0] + b, frame[1] + b, pil + c.w + pir, pit + c.h + pib],
+                    false => inner,
+                };
+                let id = styles.style_id(style);
+                paint(&mut painted, w, area, term, false, id);
+            }
+
+            for t in c.items.iter().filter(|t| !t.is_hidden()) {
+                let [_, pol, pot, _, pir, pil, pit, pib] = spread_padding(&t.padding);
+                let b = if let Border::None = t.border { 0 } else { 1 };
+                let [x, y] = [inner[0] + t.x0 + pol, inner[1] + t.y0 + pot];
+                let frame = [x, y, pil + t.w + pir + 2 * b, pit + t.h + pib + 2 * b];
+                if b == 1 {
+                    let bstyle = t.validation_border().or(t.focus[1].as_ref());
+                    let id = styles.id(bstyle.unwrap_or(&t.bstyle));
+                    paint(&mut painted, w, frame, inner, true, id);
+                }
+                let area = [x + b + pil, y + b + pit, t.w, t.h];
+                paint(
+                    &mut painted,
+                    w,
+                    area,
+                    inner,
+                    false,
+                    styles.id(t.value_style()),
+                );
+            }
+        }
+
+        scratch.painted = painted;
+    }
+
+    // puts the escape sequences of every cell in out, from the top left cell on,
+    // the display was cleared before, an empty cell is skipped
+    pub(super) fn encode_all(
+        &self,
+        out: &mut Vec<u8>,
+        cells: &[Option<char>],
+        painted: &[u16],
+        sgr: &[String],
+        (caps, clusters): (&crate::console::Capabilities, &Clusters),
+    ) {
+        let mut style = 0;
+        for (idx, c) in cells.iter().enumerate() {
+            match c {
+                Some(c) => {
+                    if painted[idx] != style {
+                        style = painted[idx];
+                        switch(out, sgr, style);
+                    }
+                    push_char(out, clusters, caps.adapt_char(*c));
+                }
+                None => out.extend_from_slice(b"\x1b[C"),
+            }
+            // the rows are put on the display one under the other, no line break after
+            // the last one since it would scroll it
+            if (idx + 1) % self.w as usize == 0 && idx + 1 < cells.len() {
+                out.extend_from_slice(b"\r\n");
+            }
+        }
+        if style != 0 {
+            out.extend_from_slice(b"\x1b[0m");
+        }
+    }
+
+    // puts the escape sequences of the cells that changed since the last frame in out,
+    // row by row, the changed cells close to each other are drawn in one run
+    // an emptied cell is drawn as a space, a new sgr only goes out when the style changes
+    pub(super) fn encode_changes(
+        &self,
+        out: &mut Vec<u8>,
+        cells: &[Option<char>],
+        painted: &[u16],
+        last: (&[Option<char>], &[u16]),
+        sgr: &[String],
+        (caps, clusters): (&crate::console::Capabilities, &Clusters),
+    ) {
+        let w = self.w as usize;
+        let changed = |idx: usize| cells[idx] != last.0[idx] || painted[idx] != last.1[idx];
+        let mut style = 0;
+        for row in 0..self.h as usize {
+            let mut x = 0;
+            while x < w {
+                if !changed(row * w + x) {
+                    x += 1;
+                    continue;
+                }
+                // a run can't start on the right half of a wide char
+                let mut start = x;
+                while start > 0 && cells[row * w + start] == Some(WIDE) {
+                    start -= 1;
+                }
+                let mut end = x + 1;
+                while let Some(next) = (end..w).find(|x| changed(row * w + x)) {
+                    if next - end > GAP {
+                        break;
+                    }
+                    end = next + 1;
+                }
+                push_cup(out, start as u16 + 1, row as u16 + 1);
+                for idx in row * w + start..row * w + end {
+                    if painted[idx] != style {
+                        style = painted[idx];
+                        switch(out, sgr, style);
+                    }
+                    push_char(out, clusters, cells[idx].map_or(' ', |c| caps.adapt_char(c)));
+                }
+                x = end;
+            }
+        }
+        if style != 0 {
+            out.extend_from_slice(b"\x1b[0m");
+        }
+    }
+}
+
+#[cfg(test)]
+mod diff {
+    use crate::components::{Container, Term, Text};
+    use crate::console::Capabilities;
+    use crate::render_pipeline::cup;
+    use crate::space::{border::Border, padding::Padding};
+    use crate::themes::Style;
+
+    // a 12x4 term with a bordered 10x2 container at its top left holding an 8x1 nonedit
+    fn term() -> Term {
+        let mut term = Term::new(0, 12, 4);
+        term.capabilities(Capabilities {
+            truecolor: true,
+            ..Default::default()
+        });
+        let mut c = Container::new([0, 0], 0, 0, 8, 2, Border::Uniform('#'), Padding::None);
+        c.items.push(Text::nonedit(
+            [0, 0, 1],
+            0,
+            0,
+            0,
+            0,
+            8,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        ));
+        c.invalidate();
+        term.containers.push(c);
+        term.nonedit_mut(&[0, 0, 1]).unwrap().replace_value("abc");
+
+        term
+    }
+
+    fn render(term: &mut Term) -> String {
+        let mut out = vec![];
+        term.render_to(&mut out).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn unchanged() {
+        let mut term = term();
+        let full = render(&mut term);
+        assert!(full.starts_with("##########\x1b[C\x1b[C\r\n#abc\x1b[C"));
+        assert_eq!(render(&mut term), cup(term.cx, term.cy));
+
+        term.nonedit_mut(&[0, 0, 1]).unwrap().replace_value("abd");
+        assert_eq!(
+            render(&mut term),
+            format!("{}d{}", cup(4, 2), cup(term.cx, term.cy))
+        );
+        // a cleared display is drawn in full
+        term.forget_frame();
+        assert_eq!(render(&mut term).len(), full.len());
+    }
+
+    // takes a few bytes of the first write then fails
+    struct Broken(usize);
+
+    impl std::io::Write for Broken {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            match self.0 {
+                0 => Err(std::io::ErrorKind::BrokenPipe.into()),
+                n => {
+                    self.0 = 0;
+                    Ok(n.min(buf.len()))
+                }
+            }
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn failed_write() {
+        let mut term = term();
+        let full = render(&mut term);
+        term.nonedit_mut(&[0, 0, 1]).unwrap().replace_value("abd");
+        assert!(term.render_to(&mut Broken(3)).is_err());
+        // the display may have anything of that frame, the next one is drawn in full
+        assert_eq!(render(&mut term).len(), full.len());
+    }
+
+    #[test]
+    fn restyled() {
+        let mut term = term();
+        render(&mut term);
+        let red = Style::new().text_color(&[200, 0, 0]);
+        term.containers[0].bstyle(&red);
+        let sgr = format!("\x1b[0m{}", red.style());
+
+        // only the border is drawn again, in one run on the top and bottom rows,
+        // and one cell on each side of the rows between them
+        let expected = format!(
+            "{}{}##########{}#{}#{}#{}#{}##########\x1b[0m{}",
+            cup(1, 1),
+            sgr,
+            cup(1, 2),
+            cup(10, 2),
+            cup(1, 3),
+            cup(10, 3),
+            cup(1, 4),
+            cup(term.cx, term.cy),
+        );
+        assert_eq!(render(&mut term), expected);
+
+        // the text takes the style of its value, the cells around it are left as they are
+        let blue = Style::new().background_color(&[0, 0, 200]);
+        term.set_style(&[0, 0, 1], blue.clone()).unwrap();
+        let frame = render(&mut term);
+        assert!(frame.starts_with(&format!("{}\x1b[0m{}abc     ", cup(2, 2), blue.style())));
+        assert_eq!(frame.matches("\x1b[0m").count(), 2);
+    }
+}
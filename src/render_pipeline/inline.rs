@@ -0,0 +1,329 @@
+use std::io::{StdoutLock, Write};
+
+use crate::components::{ScreenMode, Term};
+use crate::console::winsize::{self, DEFAULT_ROWS};
+use crate::inputs::mouse::MouseEvent;
+
+use super::cup;
+use super::scratch::push_num;
+use super::Scratch;
+
+// the rows of the main screen an inline term is drawn in, see Term::set_inline
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Inline {
+    // the most rows the term takes
+    rows: u16,
+    // the 0 based row of the screen the first row of the term is on
+    origin: u16,
+    // the rows of the screen
+    screen: u16,
+}
+
+// the row of the screen the area of rows rows starts on, under the cursor at the 0 based
+// [x, y] cell of a screen of screen rows, and the lines the cursor goes down for it to fit,
+// the screen scrolls up under the last one
+// the area starts on the line after the cursor when the cursor is not at the start of its line
+fn anchored([x, y]: [u16; 2], rows: u16, screen: u16) -> (u16, u16) {
+    let start = y + (x > 0) as u16;
+    let origin = start.min(screen - rows);
+
+    (origin, start - y + rows - 1)
+}
+
+// writes frame into out with the origin added to the row of every cursor position sequence
+fn shift_rows(frame: &[u8], origin: u16, out: &mut Vec<u8>) {
+    let mut at = 0;
+    while at < frame.len() {
+        if !frame[at..].starts_with(b"\x1b[") {
+            out.push(frame[at]);
+            at += 1;
+            continue;
+        }
+        let params = frame[at + 2..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit() || **b == b';')
+            .count();
+        let end = at + 2 + params;
+        let Some(&last) = frame.get(end).filter(|b| [b'f', b'H'].contains(b)) else {
+            out.extend_from_slice(b"\x1b[");
+            at += 2;
+            continue;
+        };
+        let mut nums = frame[at + 2..end].split(|b| *b == b';').map(|num| {
+            num.iter()
+                .fold(0u16, |n, d| n.saturating_mul(10).saturating_add((d - b'0') as u16))
+        });
+        // a missing or 0 row is the first one
+        let row = nums.next().unwrap_or(1).max(1);
+        let column = nums.next().unwrap_or(1).max(1);
+        out.extend_from_slice(b"\x1b[");
+        push_num(out, row.saturating_add(origin));
+        out.push(b';');
+        push_num(out, column);
+        out.push(last);
+        at = end + 1;
+    }
+}
+
+impl Term {
+    /// renders this Term inline in the main screen, in the rows rows under the shell prompt,
+    /// the scrollback above them stays on the display, like fzf does
+    /// Term::start asks the terminal where the cursor is, scrolls the screen up when there
+    /// are fewer than rows rows under it and gives the term as many rows,
+    /// the rows of everything rendered are counted from the first one of them
+    /// Term::finish_inline clears them when the app is done
+    /// None renders the term from the top of the screen again
+    /// takes effect the next time the Term is started, see Term::screen_mode
+    pub fn set_inline(&mut self, rows: Option<u16>) {
+        self.inline = rows.map(|rows| Inline {
+            rows: rows.max(1),
+            ..Default::default()
+        });
+        if self.inline.is_some() {
+            self.mode = ScreenMode::Inline;
+        }
+    }
+
+    /// the 0 based row of the screen the first row of this inline term is on,
+    /// None when the term is not inline, see Term::set_inline
+    pub fn inline_origin(&self) -> Option<u16> {
+        self.inline.map(|inline| inline.origin)
+    }
+
+    // gives the inline term its rows under the cursor, see Term::set_inline
+    pub(super) fn anchor(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        let screen = winsize::detect().map_or(DEFAULT_ROWS, |ws| ws.rows());
+        // a terminal that doesn't answer has the cursor at the end of its last line
+        let cursor = crate::console::query_cursor_position()
+            .map_or([1, screen.saturating_sub(1)], |(x, y)| [x, y]);
+
+        self.anchor_at(writer, cursor, screen)
+    }
+
+    // gives the inline term the rows under the cursor at the 0 based cursor cell of a screen
+    // of screen rows
+    pub(crate) fn anchor_at(
+        &mut self,
+        writer: &mut impl Write,
+        cursor: [u16; 2],
+        screen: u16,
+    ) -> std::io::Result<()> {
+        let Some(inline) = self.inline.as_mut() else {
+            return Ok(());
+        };
+        let screen = screen.max(1);
+        let rows = inline.rows.min(screen);
+        let (origin, lines) = anchored(cursor, rows, screen);
+        inline.origin = origin;
+
+        let mut s = String::from("\r");
+        (0..lines).for_each(|_| s.push('\n'));
+        s += &cup(1, origin + 1);
+        writer.write_all(s.as_bytes())?;
+        writer.flush()?;
+        self.resize(self.w, screen);
+
+        Ok(())
+    }
+
+    // keeps the inline term on the screen of h rows, the rows above it are left as they are
+    // the next frame is drawn in full, over the rows the term takes now
+    pub(crate) fn reanchor(&mut self, h: u16) {
+        let Some(inline) = self.inline.as_mut() else {
+            return;
+        };
+        inline.screen = h.max(1);
+        self.h = inline.rows.min(inline.screen);
+        inline.origin = inline.origin.min(inline.screen - self.h);
+        self.forget_frame();
+    }
+
+    // moves the inline term down under the lines lines printed from its first row on, the screen
+    // scrolls up when they don't fit, see Term::print_line
+    pub(crate) fn push_down(&mut self, s: &mut String, lines: u16) {
+        let Some(inline) = self.inline.as_mut() else {
+            return;
+        };
+        // the cursor is at the start of the line after them
+        let y = inline.origin.saturating_add(lines).min(inline.screen - 1);
+        let (origin, down) = anchored([0, y], self.h, inline.screen);
+        inline.origin = origin;
+        (0..down).for_each(|_| s.push('\n'));
+        self.forget_frame();
+    }
+
+    /// clears the rows this inline term was drawn in and puts the cursor at the start of the
+    /// first one, where the shell prompt goes, summary is left there on a line of its own
+    /// nothing is written when the term is not inline, see Term::set_inline
+    pub fn finish_inline(&mut self, writer: &mut StdoutLock, summary: Option<&str>) {
+        _ = writer.write_all(&self.teardown(summary));
+        _ = writer.flush();
+    }
+
+    // the bytes Term::finish_inline writes
+    pub(crate) fn teardown(&self, summary: Option<&str>) -> Vec<u8> {
+        let Some(inline) = self.inline else {
+            return vec![];
+        };
+        let mut s = String::from("\x1b[0m");
+        for row in 0..self.h {
+            s += &cup(1, inline.origin + row + 1);
+            s += "\x1b[2K";
+        }
+        s += &cup(1, inline.origin + 1);
+        if let Some(summary) = summary {
+            s += summary;
+            s += "\r\n";
+        }
+
+        s.into_bytes()
+    }
+
+    // the row of the screen the row y of this term is on, y itself when the term is not inline
+    pub(crate) fn screen_row(&self, y: u16) -> u16 {
+        match self.inline {
+            Some(inline) => y.max(1).saturating_add(inline.origin),
+            None => y,
+        }
+    }
+
+    // the event with the row of this term the pointer is on, None when the pointer is above
+    // the inline term
+    pub(crate) fn unplaced(&self, event: &MouseEvent) -> Option<MouseEvent> {
+        let origin = self.inline.map_or(0, |inline| inline.origin);
+
+        Some(MouseEvent {
+            row: event.row.checked_sub(origin)?,
+            ..event.clone()
+        })
+    }
+
+    // moves the frame in the out buffer of scratch to the rows of the inline term
+    pub(super) fn place(&self, scratch: &mut Scratch) {
+        if let Some(inline) = self.inline {
+            scratch.placed.clear();
+            shift_rows(&scratch.out, inline.origin, &mut scratch.placed);
+            std::mem::swap(&mut scratch.out, &mut scratch.placed);
+        }
+    }
+
+    // the frame moved to the rows of the inline term
+    pub(super) fn placed(&self, frame: Vec<u8>) -> Vec<u8> {
+        let Some(inline) = self.inline else {
+            return frame;
+        };
+        let mut placed = Vec::with_capacity(frame.len());
+        shift_rows(&frame, inline.origin, &mut placed);
+
+        placed
+    }
+}
+
+#[cfg(test)]
+mod inline {
+    use super::*;
+    use crate::components::{Container, Text};
+    use crate::inputs::mouse::{MouseButton, MouseKind};
+    use crate::space::{border::Border, padding::Padding};
+
+    // a 10x24 term holding a focused 8x1 input, inline in 5 rows
+    fn term() -> Term {
+        let mut term = Term::new(0, 10, 24);
+        let mut c = Container::new([0, 0], 0, 0, 8, 1, Border::None, Padding::None);
+        c.items.push(Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            8,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        ));
+        term.containers.push(c);
+        term.focused = Some([0, 0, 0]);
+        term.set_inline(Some(5));
+        term.capabilities.synchronized_output = false;
+
+        term
+    }
+
+    #[test]
+    fn anchor() {
+        assert_eq!(anchored([0, 3], 5, 24), (3, 4));
+        // past the prompt, on the next line
+        assert_eq!(anchored([7, 3], 5, 24), (4, 5));
+        // at the bottom the screen scrolls up
+        assert_eq!(anchored([0, 22], 5, 24), (19, 4));
+        assert_eq!(anchored([7, 23], 5, 24), (19, 5));
+
+        let mut term = term();
+        let mut out = vec![];
+        term.anchor_at(&mut out, [2, 21], 24).unwrap();
+        assert_eq!(out, format!("\r\n\n\n\n\n{}", cup(1, 20)).into_bytes());
+        assert_eq!([term.inline_origin(), Some(term.h)], [Some(19), Some(5)]);
+
+        // a short window gives it all its rows
+        term.anchor_at(&mut vec![], [0, 0], 3).unwrap();
+        assert_eq!([term.inline_origin(), Some(term.h)], [Some(0), Some(3)]);
+    }
+
+    #[test]
+    fn frame() {
+        let mut term = term();
+        term.anchor_at(&mut vec![], [0, 10], 24).unwrap();
+        term.paste("ab");
+        let mut out = vec![];
+        term.render_to(&mut out).unwrap();
+        let frame = String::from_utf8(out).unwrap();
+        // the frame starts on the first row of the term and erases the rows under it,
+        // nothing above them
+        assert!(frame.starts_with(&format!("{}\x1b[Jab", cup(1, 11))));
+        assert!(!frame.contains("\x1b[H") && !frame.contains("\x1b[2J"));
+        assert!(frame.ends_with(&cup(term.cx, 11)));
+
+        // the changes go to the rows of the term too
+        term.paste("c");
+        let mut out = vec![];
+        term.render_to(&mut out).unwrap();
+        let frame = String::from_utf8(out).unwrap();
+        assert!(frame.starts_with(&format!("{}c", cup(3, 11))));
+
+        // so does the mouse
+        let mut click = MouseEvent {
+            kind: MouseKind::Down,
+            button: MouseButton::Left,
+            column: 1,
+            row: 10,
+            ..Default::default()
+        };
+        assert_eq!(term.mouse(&click), Some([0, 0, 0]));
+        click.row = 9;
+        assert_eq!(term.mouse(&click), None);
+    }
+
+    #[test]
+    fn resize() {
+        let mut term = term();
+        term.anchor_at(&mut vec![], [0, 20], 24).unwrap();
+        assert_eq!(term.inline_origin(), Some(19));
+        // the term stays on the screen, over the rows it had
+        term.resize(10, 22);
+        assert_eq!([term.inline_origin(), Some(term.h)], [Some(17), Some(5)]);
+        term.resize(10, 40);
+        assert_eq!([term.inline_origin(), Some(term.h)], [Some(17), Some(5)]);
+
+        // the teardown clears exactly the rows it took
+        let clear = (18..23).map(|row| format!("{}\x1b[2K", cup(1, row)));
+        let clear = clear.collect::<String>();
+        let bytes = term.teardown(None);
+        assert_eq!(bytes, format!("\x1b[0m{clear}{}", cup(1, 18)).into_bytes());
+        let bytes = term.teardown(Some("picked: ab"));
+        assert!(bytes.ends_with(format!("{}picked: ab\r\n", cup(1, 18)).as_bytes()));
+
+        term.set_inline(None);
+        assert!(term.teardown(None).is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+use crate::components::Term;
+use crate::space::border::Border;
+
+use super::{spread_padding, Scratch};
+
+// the directions a border cell has lines in
+const UP: u8 = 1;
+const RIGHT: u8 = 2;
+const DOWN: u8 = 4;
+const LEFT: u8 = 8;
+
+// the glyph of each set of directions, UP | RIGHT is the bottom left corner
+const LIGHT: [char; 16] = [
+    ' ', '│', '─', '└', '│', '│', '┌', '├', '─', '┘', '─', '┴', '┐', '┤', '┬', '┼',
+];
+const ROUNDED: [char; 16] = [
+    ' ', '│', '─', '╰', '│', '│', '╭', '├', '─', '╯', '─', '┴', '╮', '┤', '┬', '┼',
+];
+const DOUBLE: [char; 16] = [
+    ' ', '║', '═', '╚', '║', '║', '╔', '╠', '═', '╝', '═', '╩', '╗', '╣', '╦', '╬',
+];
+
+// the border sets whose edges are joined, see Term::merge_borders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Lines {
+    Light,
+    Rounded,
+    Double,
+}
+
+impl Lines {
+    // the set the chars of border come from, None for a border of no set
+    fn of(border: &Border) -> Option<Self> {
+        let Border::Polyform {
+            tlcorner,
+            trcorner,
+            brcorner,
+            blcorner,
+            rl,
+            tb,
+        } = *border
+        else {
+            return None;
+        };
+        let chars = [tlcorner, trcorner, brcorner, blcorner, rl, tb];
+
+        [Self::Light, Self::Rounded, Self::Double]
+            .into_iter()
+            .find(|lines| {
+                let glyphs = lines.glyphs();
+                chars == [6, 12, 9, 3, 5, 10].map(|dirs| glyphs[dirs])
+            })
+    }
+
+    fn glyphs(self) -> &'static [char; 16] {
+        match self {
+            Self::Light => &LIGHT,
+            Self::Rounded => &ROUNDED,
+            Self::Double => &DOUBLE,
+        }
+    }
+
+    // whether the edges of the two sets can meet, the light and the rounded lines are as thick
+    fn joins(self, other: Self) -> bool {
+        (self == Self::Double) == (other == Self::Double)
+    }
+}
+
+impl Term {
+    /// joins the edges the containers share, one drawn over the other, into one line
+    /// when merged is true, the cells they meet in get the junction, ├ ┬ ┼ and the like,
+    /// of the directions the lines go in from them
+    /// only the borders of the light, rounded and double sets are joined, see Border::square,
+    /// Border::rounded and Border::double, a double edge isn't joined with a light one
+    /// it sets the "merge-borders" attribute that Term::render looks for
+    pub fn merge_borders(&mut self, merged: bool) {
+        match merged {
+            true => self.attributes.insert("merge-borders"),
+            false => self.attributes.remove("merge-borders"),
+        };
+    }
+
+    // puts the junctions of the borders of the containers in cells, the ones shown over
+    // by something else are left as they are
+    pub(super) fn join_borders(&self, cells: &mut [Option<char>], scratch: &mut Scratch) {
+        let [w, h] = [self.w as usize, self.h as usize];
+        let mut joints = std::mem::take(&mut scratch.joints);
+        joints.clear();
+        joints.resize(w * h, (0, None));
+
+        for (_, c) in self.draw_order() {
+            let Some(lines) = Lines::of(&c.border) else {
+                continue;
+            };
+            let [_, pol, pot, _, pir, pil, pit, pib] = spread_padding(&c.padding);
+            let [x, y] = [(c.x0 + pol) as usize, (c.y0 + pot) as usize];
+            let [fw, fh] = [(pil + c.w + pir + 2) as usize, (pit + c.h + pib + 2) as usize];
+            for row in y..(y + fh).min(h) {
+                for col in x..(x + fw).min(w) {
+                    let [top, bottom] = [row == y, row + 1 == y + fh];
+                    let [left, right] = [col == x, col + 1 == x + fw];
+                    let mut dirs = 0;
+                    if (left || right) && !top {
+                        dirs |= UP;
+                    }
+                    if (left || right) && !bottom {
+                        dirs |= DOWN;
+                    }
+                    if (top || bottom) && !left {
+                        dirs |= LEFT;
+                    }
+                    if (top || bottom) && !right {
+                        dirs |= RIGHT;
+                    }
+                    if dirs == 0 {
+                        continue;
+                    }
+                    let joint = &mut joints[row * w + col];
+                    *joint = match joint.1 {
+                        Some(under) if under.joins(lines) => (joint.0 | dirs, Some(lines)),
+                        _ => (dirs, Some(lines)),
+                    };
+                }
+            }
+        }
+
+        for (cell, (dirs, lines)) in cells.iter_mut().zip(&joints) {
+            let Some(lines) = lines else {
+                continue;
+            };
+            // the edge is still what is shown there
+            let shown = [LIGHT, ROUNDED, DOUBLE]
+                .iter()
+                .any(|glyphs| cell.is_some_and(|c| c != ' ' && glyphs.contains(&c)));
+            if shown {
+                *cell = Some(lines.glyphs()[*dirs as usize]);
+            }
+        }
+        scratch.joints = joints;
+    }
+}
+
+#[cfg(test)]
+mod junction {
+    use crate::components::{Container, Term};
+    use crate::space::{border::Border, padding::Padding};
+    use crate::test::Frame;
+
+    // a term holding the containers of the [x0, y0, w, h] areas, with border
+    fn term(areas: &[[u16; 4]], border: impl Fn(usize) -> Border) -> Term {
+        let mut term = Term::new(0, 12, 6);
+        for (idx, [x0, y0, w, h]) in areas.iter().copied().enumerate() {
+            let id = [idx as u8, 0];
+            let c = Container::new(id, x0, y0, w, h, border(idx), Padding::None);
+            term.containers.push(c);
+        }
+        term.merge_borders(true);
+
+        term
+    }
+
+    fn rows(term: &Term) -> Vec<String> {
+        let rows = Frame::of(term).rows();
+
+        rows.iter().map(|row| row.trim_end().to_string()).collect()
+    }
+
+    #[test]
+    fn l_shaped() {
+        let mut term = term(&[[0, 0, 3, 1], [0, 2, 6, 1]], |_| Border::square());
+        assert_eq!(
+            rows(&term),
+            ["┌───┐", "│   │", "├───┴──┐", "│      │", "└──────┘", ""]
+        );
+
+        // left apart, the lower one is drawn over the upper one
+        term.merge_borders(false);
+        assert_eq!(rows(&term)[2], "┌──────┐");
+    }
+
+    #[test]
+    fn t_shaped() {
+        let areas = [[0, 0, 7, 1], [0, 2, 3, 1], [4, 2, 3, 1]];
+        // the rounded corners of the top one join the light edges of the others
+        let term = term(&areas, |idx| match idx {
+            0 => Border::rounded(),
+            _ => Border::square(),
+        });
+        assert_eq!(
+            rows(&term),
+            ["╭───────╮", "│       │", "├───┬───┤", "│   │   │", "└───┴───┘", ""]
+        );
+    }
+
+    #[test]
+    fn grid() {
+        let areas = [[0, 0, 3, 1], [4, 0, 3, 1], [0, 2, 3, 1], [4, 2, 3, 1]];
+        let doubles = term(&areas, |_| Border::double());
+        assert_eq!(
+            rows(&doubles),
+            ["╔═══╦═══╗", "║   ║   ║", "╠═══╬═══╣", "║   ║   ║", "╚═══╩═══╝", ""]
+        );
+
+        // a light edge isn't joined with a double one
+        let mixed = term(&areas, |idx| match idx {
+            3 => Border::square(),
+            _ => Border::double(),
+        });
+        assert_eq!(rows(&mixed)[2], "╠═══┌───┐");
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::io::Write;
+
+use crate::components::{Property, ScreenMode, Term, Text};
+
+// what a linear term last wrote, see ScreenMode::Linear
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Linear {
+    // the lines written for each text
+    written: HashMap<[u8; 3], Vec<String>>,
+    // the focused text last announced
+    announced: Option<[u8; 3]>,
+}
+
+// whether the value of the RAGOUT_LINEAR variable asks for the linear mode
+fn linear_env(value: Option<&str>) -> bool {
+    value.is_some_and(|v| v.trim() == "1")
+}
+
+// the "label" or "title" string property of a component
+fn label_of<'a>(properties: &'a HashMap<&'static str, Property>) -> Option<&'a str> {
+    ["label", "title"]
+        .iter()
+        .find_map(|key| match properties.get(key) {
+            Some(Property::String(s)) => Some(s.as_str()),
+            _ => None,
+        })
+}
+
+// the label a text's lines are prefixed with, its label or title property,
+// or what it is and its id
+fn text_label(text: &Text) -> String {
+    match label_of(&text.properties) {
+        Some(label) => label.to_string(),
+        None => {
+            let kind = if text.is_input() { "input" } else { "text" };
+            format!("{} {}.{}.{}", kind, text.id[0], text.id[1], text.id[2])
+        }
+    }
+}
+
+// the lines of a text, its label then its value, the first line of the value goes after the label
+// the blank cells at the end of the lines and the blank lines at the end are left out
+fn text_lines(text: &Text) -> Vec<String> {
+    let value = text.value_string().replace('\0', " ");
+    let mut lines = value
+        .lines()
+        .map(|l| l.trim_end().to_string())
+        .collect::<Vec<_>>();
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+    let label = text_label(text);
+    match lines.first_mut() {
+        Some(first) => *first = format!("{}: {}", label, first),
+        None => lines.push(format!("{}:", label)),
+    }
+
+    lines
+}
+
+fn push_lines(out: &mut Vec<u8>, lines: &[String]) {
+    // the terminal is raw, a line feed alone doesn't go back to the first column
+    for line in lines {
+        out.extend_from_slice(line.as_bytes());
+        out.extend_from_slice(b"\r\n");
+    }
+}
+
+impl Term {
+    /// switches this Term to ScreenMode::Linear when the RAGOUT_LINEAR environment variable
+    /// is 1, for screen readers and dumb terminals, see Term::screen_mode
+    /// returns whether it did
+    pub fn linear_from_env(&mut self) -> bool {
+        let linear = linear_env(std::env::var("RAGOUT_LINEAR").ok().as_deref());
+        if linear {
+            self.screen_mode(ScreenMode::Linear);
+        }
+
+        linear
+    }
+
+    /// the ids of the shown texts in the order a linear Term reads them, see ScreenMode::Linear,
+    /// the containers from top to bottom then left to right, the texts of each one the same way,
+    /// the components that start on the same cell keep the order they were added in
+    pub fn reading_order(&self) -> Vec<[u8; 3]> {
+        let mut containers = self
+            .containers
+            .iter()
+            .filter(|c| !c.is_hidden())
+            .collect::<Vec<_>>();
+        containers.sort_by_key(|c| (c.y0, c.x0));
+
+        let mut order = vec![];
+        for c in containers {
+            let mut texts = c.items.iter().filter(|t| !t.is_hidden()).collect::<Vec<_>>();
+            texts.sort_by_key(|t| (t.ay0, t.ax0));
+            order.extend(texts.iter().map(|t| t.id));
+        }
+
+        order
+    }
+
+    /// the whole of this Term as a linear Term writes it, see ScreenMode::Linear,
+    /// the texts in reading order, see Term::reading_order, each one's label then value,
+    /// the title of a container on a line before its texts, then the focused text
+    /// lines end with "\r\n"
+    pub fn linear_text(&self) -> String {
+        let mut out = vec![];
+        let mut container = None;
+        for id in self.reading_order() {
+            if container != Some([id[0], id[1]]) {
+                container = Some([id[0], id[1]]);
+                let c = self.container_ref(&[id[0], id[1]]).unwrap();
+                if let Some(title) = label_of(&c.properties) {
+                    push_lines(&mut out, &[format!("[{}]", title)]);
+                }
+            }
+            push_lines(&mut out, &text_lines(self.text_ref(&id).unwrap()));
+        }
+        if let Some(focus) = self.focus_line() {
+            push_lines(&mut out, &[focus]);
+        }
+
+        String::from_utf8(out).unwrap()
+    }
+
+    // the line announcing the focused text
+    fn focus_line(&self) -> Option<String> {
+        let text = self.focused.and_then(|id| self.text_ref(&id))?;
+
+        Some(format!("focus: {}", text_label(text)))
+    }
+
+    // writes the whole term in lines, what Term::render does in the linear mode
+    pub(super) fn render_linear(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        let text = self.linear_text();
+        self.linear = Linear {
+            written: self
+                .reading_order()
+                .into_iter()
+                .map(|id| (id, text_lines(self.text_ref(&id).unwrap())))
+                .collect(),
+            announced: self.focused,
+        };
+        self.unmark_dirty();
+        writer.write_all(text.as_bytes())?;
+        writer.flush()
+    }
+
+    // writes the lines of the texts whose lines changed since they were last written, then the
+    // focused text if the focus moved, what Term::render_dirty does in the linear mode
+    pub(super) fn render_dirty_linear(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        let mut out = vec![];
+        for id in self.reading_order() {
+            let lines = text_lines(self.text_ref(&id).unwrap());
+            if self.linear.written.get(&id) != Some(&lines) {
+                push_lines(&mut out, &lines);
+                self.linear.written.insert(id, lines);
+            }
+        }
+        if self.linear_focus_moved() {
+            self.linear.announced = self.focused;
+            if let Some(focus) = self.focus_line() {
+                push_lines(&mut out, &[focus]);
+            }
+        }
+        self.unmark_dirty();
+        writer.write_all(&out)?;
+        writer.flush()
+    }
+
+    // whether the focus moved since it was last announced
+    pub(super) fn linear_focus_moved(&self) -> bool {
+        self.linear.announced != self.focused
+    }
+
+    fn unmark_dirty(&mut self) {
+        self.containers.iter_mut().for_each(|c| {
+            c.attributes.remove("dirty");
+            c.items.iter_mut().for_each(|t| {
+                t.attributes.remove("dirty");
+                t.patched = None;
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod linear {
+    use super::*;
+    use crate::components::Container;
+    use crate::space::{border::Border, padding::Padding};
+
+    // a titled form on the right of a log, the form's inputs are added bottom first
+    fn app() -> Term {
+        let mut term = Term::new(0, 40, 6);
+        let mut log = Container::new([0, 0], 0, 0, 20, 6, Border::None, Padding::None);
+        let mut text = Text::nonedit(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            20,
+            3,
+            &[],
+            Border::None,
+            Padding::None,
+        );
+        text.attributes.insert("multiline");
+        text.append_str("started");
+        text.append_line("ready");
+        log.items.push(text);
+
+        let mut form = Container::new([0, 1], 20, 0, 20, 6, Border::None, Padding::None);
+        form.properties
+            .insert("title", Property::String("login".into()));
+        for (iid, y, label) in [(0, 2, "password"), (1, 0, "name")] {
+            let mut input = Text::input(
+                [0, 1, iid],
+                0,
+                y,
+                20,
+                y,
+                20,
+                1,
+                &[],
+                Border::None,
+                Padding::None,
+            );
+            input
+                .properties
+                .insert("label", Property::String(label.into()));
+            form.items.push(input);
+        }
+        term.containers.push(form);
+        term.containers.push(log);
+
+        term
+    }
+
+    #[test]
+    fn env() {
+        assert!(linear_env(Some("1")));
+        assert!(!linear_env(Some("0")));
+        assert!(!linear_env(Some("")));
+        assert!(!linear_env(None));
+    }
+
+    #[test]
+    fn order() {
+        let mut term = app();
+        assert_eq!(term.reading_order(), [[0, 0, 0], [0, 1, 1], [0, 1, 0]]);
+        term.hide(&[0, 0]).unwrap();
+        assert_eq!(term.reading_order(), [[0, 1, 1], [0, 1, 0]]);
+    }
+
+    #[test]
+    fn text() {
+        let mut term = app();
+        _ = term.focus(&[0, 1, 1]);
+        term.paste("ragout");
+
+        assert_eq!(
+            term.linear_text(),
+            "text 0.0.0: started\r\nready\r\n[login]\r\nname: ragout\r\npassword:\r\nfocus: name\r\n"
+        );
+    }
+
+    #[test]
+    fn dirty() {
+        let mut term = app();
+        term.screen_mode(ScreenMode::Linear);
+        let mut out = vec![];
+        term.render_to(&mut out).unwrap();
+        assert!(!out.contains(&b'\x1b'));
+
+        _ = term.focus(&[0, 1, 1]);
+        term.paste("ab");
+        let mut out = vec![];
+        term.render_dirty_to(&mut out).unwrap();
+        assert_eq!(out, b"name: ab\r\nfocus: name\r\n");
+
+        // nothing changed, nothing is written
+        let mut out = vec![];
+        term.render_dirty_to(&mut out).unwrap();
+        assert!(out.is_empty());
+        assert!(!term.is_dirty());
+    }
+}
@@ -0,0 +1,126 @@
+use std::io::{StdoutLock, Write};
+
+use crate::components::Term;
+use crate::console::print::raw_text;
+
+use super::cup;
+
+impl Term {
+    /// sets the [first, last] 0 based rows of this term the lines of Term::print_line
+    /// scroll through, e.g. the rows a log area is left in, the rows after the last one keep
+    /// what they show
+    /// None, the default, prints above an inline term, see Term::set_inline, and in the last
+    /// row of any other one
+    pub fn print_rows(&mut self, rows: Option<[u16; 2]>) {
+        self.print_rows = rows.map(|[first, last]| [first.min(last), first.max(last)]);
+    }
+
+    /// prints line for the app in raw mode, without breaking the frame, the line breaks are
+    /// "\r\n"s and the long lines are broken at the width of the term, see raw_text
+    /// the lines go in the print rows, see Term::print_rows, or, on an inline term,
+    /// where the term was, which is drawn again under them
+    /// the cursor goes back to the focused input, see Term::sync_cursor, and everything goes
+    /// out in one write that a render from another thread can't get in the middle of
+    pub fn print_line(&mut self, writer: &mut StdoutLock, line: &str) {
+        _ = writer.write_all(&self.printed(line));
+        // writer holds stdout, the cursor movement goes out with the line
+        _ = self.sync_cursor();
+        _ = writer.flush();
+    }
+
+    // the bytes Term::print_line writes for line before the cursor goes back to the input
+    pub(crate) fn printed(&mut self, line: &str) -> Vec<u8> {
+        let text = raw_text(line, Some(self.w));
+        let mut s = String::from("\x1b[0m");
+        if let (None, Some(_)) = (self.print_rows, self.inline) {
+            s += &cup(1, self.screen_row(1));
+            s += "\x1b[J";
+            s += &text;
+            s += "\r\n";
+            self.push_down(&mut s, text.matches("\r\n").count() as u16 + 1);
+            let mut out = s.into_bytes();
+            // a vec takes the whole frame
+            _ = self.render_to(&mut out);
+
+            return out;
+        }
+
+        let last = self.h - 1;
+        let [first, last] = self.print_rows.unwrap_or([last; 2]).map(|row| row.min(last));
+        let [first, last] = [first, last].map(|row| self.screen_row(row + 1));
+        // the lines scroll the rows up from the last one
+        s += &format!("\x1b[{};{}r", first, last);
+        s += &cup(1, last);
+        for line in text.split("\r\n") {
+            s += "\r\n";
+            s += line;
+        }
+        s += "\x1b[r";
+        s += &cup(self.cx, self.screen_row(self.cy));
+
+        s.into_bytes()
+    }
+}
+
+#[cfg(test)]
+mod print {
+    use super::*;
+    use crate::components::{Container, Text};
+    use crate::space::{border::Border, padding::Padding};
+
+    // a 6x4 term holding a focused 6x1 input
+    fn term() -> Term {
+        let mut term = Term::new(0, 6, 4);
+        let mut c = Container::new([0, 0], 0, 0, 6, 1, Border::None, Padding::None);
+        c.items.push(Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            6,
+            1,
+            &[],
+            Border::None,
+            Padding::None,
+        ));
+        term.containers.push(c);
+        term.focused = Some([0, 0, 0]);
+        term.capabilities.synchronized_output = false;
+
+        term
+    }
+
+    #[test]
+    fn rows() {
+        let mut term = term();
+        term.print_rows(Some([3, 1]));
+        let bytes = String::from_utf8(term.printed("done\nlong line")).unwrap();
+        let lines = format!("{}\r\ndone\r\nlong l\r\nine", cup(1, 4));
+        assert_eq!(bytes, format!("\x1b[0m\x1b[2;4r{lines}\x1b[r{}", cup(0, 0)));
+
+        // the last row of the term by default
+        term.print_rows(None);
+        let bytes = String::from_utf8(term.printed("ok")).unwrap();
+        assert!(bytes.starts_with(&format!("\x1b[0m\x1b[4;4r{}\r\nok", cup(1, 4))));
+    }
+
+    #[test]
+    fn inline() {
+        let mut term = term();
+        term.set_inline(Some(2));
+        term.anchor_at(&mut vec![], [0, 10], 12).unwrap();
+        assert_eq!(term.inline_origin(), Some(10));
+        let bytes = String::from_utf8(term.printed("ab\ncd")).unwrap();
+        // the lines go where the term was, the screen scrolls up for the term to fit under them
+        let lines = format!("\x1b[0m{}\x1b[Jab\r\ncd\r\n\n", cup(1, 11));
+        assert!(bytes.starts_with(&lines));
+        assert_eq!(term.inline_origin(), Some(10));
+        assert!(bytes[lines.len()..].contains(&format!("{}\x1b[J", cup(1, 11))));
+
+        // high up the screen it goes down
+        term.anchor_at(&mut vec![], [0, 2], 12).unwrap();
+        term.printed("ab");
+        assert_eq!(term.inline_origin(), Some(3));
+    }
+}
@@ -0,0 +1,146 @@
+use crate::components::grapheme::{Clusters, WIDE};
+use crate::components::{ComponentTreeError, Term};
+use crate::space::border::Border;
+
+use super::{spread_padding, Scratch};
+
+// the rows of the [w, h] cells of a grid wx cells wide, from the cell [x, y],
+// empty cells are spaces and the rows end with their last char,
+// a cluster is given back as its chars, see Scratch::clusters
+fn rows(cells: &[Option<char>], clusters: &Clusters, wx: u16, [x, y, w, h]: [u16; 4]) -> String {
+    let row = |r: u16| {
+        let start = ((y + r) * wx + x) as usize;
+        let mut row = String::new();
+        for c in &cells[start..start + w as usize] {
+            match c {
+                Some(WIDE) => (),
+                Some(c) => clusters.push(&mut row, *c),
+                None => row.push(' '),
+            }
+        }
+
+        row.trim_end().to_string()
+    };
+
+    (0..h).map(row).collect::<Vec<String>>().join("\n")
+}
+
+impl Term {
+    /// what the container, or the text, with id shows, as plain rows joined with newlines,
+    /// e.g. to copy a pane to the clipboard or to a file
+    /// the component is laid out like it is rendered, clipped, wrapped and scrolled,
+    /// without its styles, its border and paddings are left in when borders is true,
+    /// the outer paddings are always left out, and so are the components drawn over it
+    /// empty cells are spaces and the spaces at the end of the rows are trimmed,
+    /// a hidden component gives an empty string
+    ///
+    /// # Errors
+    /// returns BadID if id is not the id of a container or a text of this term
+    pub fn snapshot_region(&self, id: &[u8], borders: bool) -> Result<String, ComponentTreeError> {
+        let bad = || ComponentTreeError::BadID { id: id.to_vec() };
+        let mut scratch = Scratch::default();
+        let (cells, [wx, hx], border, padding, [w, h]) = match *id {
+            [t, c] => {
+                let cont = self.container_ref(&[t, c]).ok_or_else(bad)?;
+                if cont.is_hidden() {
+                    return Ok(String::new());
+                }
+                let (cells, wh) = cont.prepare(&mut scratch);
+                (cells, wh, &cont.border, &cont.padding, [cont.w, cont.h])
+            }
+            [t, c, i] => {
+                let text = self.text_ref(&[t, c, i]).ok_or_else(bad)?;
+                if text.is_hidden() {
+                    return Ok(String::new());
+                }
+                let (cells, wh) = text.prepare(&mut scratch);
+                (cells, wh, &text.border, &text.padding, [text.w, text.h])
+            }
+            _ => return Err(bad()),
+        };
+        let [por, pol, pot, pob, _, pil, pit, _] = spread_padding(padding);
+        let b = if let Border::None = border { 0 } else { 1 };
+        let area = match borders {
+            true => [pol, pot, wx - pol - por, hx - pot - pob],
+            false => [pol + b + pil, pot + b + pit, w, h],
+        };
+
+        Ok(rows(&cells, &scratch.clusters, wx, area))
+    }
+}
+
+#[cfg(test)]
+mod region {
+    use crate::components::{ComponentTreeError, Container, Term, Text};
+    use crate::space::{border::Border, padding::Padding};
+
+    // a bordered 8x2 container at [1, 1] holding a 4x1 input and a bordered nonedit under it
+    fn term() -> Term {
+        let mut term = Term::new(0, 20, 6);
+        let padding = Padding::Outer {
+            top: 0,
+            bottom: 0,
+            right: 0,
+            left: 1,
+        };
+        let mut c = Container::new([0, 0], 1, 1, 8, 4, Border::Uniform('#'), padding);
+        let none = Padding::None;
+        c.items.push(Text::input(
+            [0, 0, 0],
+            0,
+            0,
+            0,
+            0,
+            4,
+            1,
+            &[],
+            Border::None,
+            none,
+        ));
+        c.items.push(Text::nonedit(
+            [0, 0, 1],
+            0,
+            1,
+            0,
+            0,
+            6,
+            1,
+            &[],
+            Border::Uniform('*'),
+            none,
+        ));
+        term.containers.push(c);
+        term.containers[0].invalidate();
+        let text = term.input_mut(&[0, 0, 0]).unwrap();
+        text.replace_value("ab");
+        term.nonedit_mut(&[0, 0, 1]).unwrap().replace_value("note");
+
+        term
+    }
+
+    #[test]
+    fn container() {
+        let term = term();
+        let inner = term.snapshot_region(&[0, 0], false).unwrap();
+        assert_eq!(inner, "ab\n********\n*note  *\n********");
+        let framed = term.snapshot_region(&[0, 0], true).unwrap();
+        let rows = framed.lines().collect::<Vec<&str>>();
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[0], "#".repeat(10));
+        assert_eq!(rows[1], "#ab      #");
+        assert_eq!(rows[3], "#*note  *#");
+    }
+
+    #[test]
+    fn text() {
+        let mut term = term();
+        assert_eq!(term.snapshot_region(&[0, 0, 1], false).unwrap(), "note");
+        let framed = term.snapshot_region(&[0, 0, 1], true).unwrap();
+        assert_eq!(framed, "********\n*note  *\n********");
+
+        term.hide(&[0, 0, 1]).unwrap();
+        assert_eq!(term.snapshot_region(&[0, 0, 1], true).unwrap(), "");
+        let err = term.snapshot_region(&[0], true);
+        assert!(matches!(err, Err(ComponentTreeError::BadID { .. })));
+    }
+}
@@ -0,0 +1,194 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use crate::components::grapheme::{Clusters, WIDE};
+use crate::console::Capabilities;
+use crate::themes::Style;
+
+// the buffers a Term puts its frames together in, kept from one frame to the next
+// every buffer keeps the greatest capacity it was given, so that once a frame of the same
+// size was rendered twice the next ones allocate nothing
+#[derive(Default, Clone)]
+pub(crate) struct Scratch {
+    // the bytes of the frame
+    pub(crate) out: Vec<u8>,
+    // the cell grids of the term, its containers and its texts
+    // the grids are taken and put back in the same order every frame,
+    // so that each one is always given to the same component
+    grids: Vec<Vec<Option<char>>>,
+    // the style of each cell of the term, see Styles
+    pub(crate) painted: Vec<u16>,
+    // the cells and their styles the last frame drew, the next one only draws the ones
+    // that changed, see Term::forget_frame
+    pub(crate) last: Vec<Option<char>>,
+    pub(crate) last_painted: Vec<u16>,
+    // whether the display was drawn over since the last frame
+    pub(crate) stale: Cell<bool>,
+    pub(crate) styles: Styles,
+    // the clusters the cells of the grids stand for, the texts' own are put in it as they are
+    // laid out, see Text::prepare
+    pub(crate) clusters: Clusters,
+    // the directions the borders go in from each cell, see Term::merge_borders
+    pub(crate) joints: Vec<(u8, Option<super::junction::Lines>)>,
+    // the frame moved to the rows of an inline term, see Term::set_inline
+    pub(crate) placed: Vec<u8>,
+}
+
+// the styles the cells were painted in, each one is given an id the first time it is seen,
+// 0 is no style
+#[derive(Default, Clone)]
+pub(crate) struct Styles {
+    sgr: Vec<String>,
+    ids: HashMap<String, u16>,
+    kept: HashMap<Style, u16>,
+    // the sgr of every id as caps adapted them
+    adapted: Vec<String>,
+    caps: Option<Capabilities>,
+}
+
+impl Styles {
+    // the id of the sgr escape sequence, an empty one is no style
+    pub(crate) fn id(&mut self, sgr: &str) -> u16 {
+        if sgr.is_empty() {
+            return 0;
+        }
+        if let Some(id) = self.ids.get(sgr) {
+            return *id;
+        }
+        let id = self.push(sgr.to_string());
+        self.ids.insert(sgr.to_string(), id);
+
+        id
+    }
+
+    // the id of the style, worked out once per style
+    pub(crate) fn style_id(&mut self, style: &Style) -> u16 {
+        if let Some(id) = self.kept.get(style) {
+            return *id;
+        }
+        let id = self.id(&style.style());
+        self.kept.insert(style.clone(), id);
+
+        id
+    }
+
+    // the styles past the last id are left out, their cells are drawn without a style
+    fn push(&mut self, sgr: String) -> u16 {
+        if self.sgr.is_empty() {
+            self.sgr.push(String::new());
+        }
+        if self.sgr.len() > u16::MAX as usize {
+            return 0;
+        }
+        self.sgr.push(sgr);
+
+        (self.sgr.len() - 1) as u16
+    }
+
+    // the sgr of every id, as the terminal with caps displays it
+    pub(crate) fn adapted(&mut self, caps: &Capabilities) -> &[String] {
+        if self.caps.as_ref() != Some(caps) {
+            self.adapted.clear();
+            self.caps = Some(caps.clone());
+        }
+        let new = self.adapted.len().min(self.sgr.len());
+        self.adapted
+            .extend(self.sgr[new..].iter().map(|s| caps.adapt_sgr(s)));
+
+        &self.adapted
+    }
+}
+
+impl Scratch {
+    // a grid of len empty cells, put it back once done with it
+    pub(crate) fn grid(&mut self, len: usize) -> Vec<Option<char>> {
+        let mut grid = self.grids.pop().unwrap_or_default();
+        grid.clear();
+        grid.resize(len, None);
+
+        grid
+    }
+
+    // gives a grid back, the grids have to be put back in the reverse order they were taken in
+    pub(crate) fn put_back(&mut self, grid: Vec<Option<char>>) {
+        self.grids.push(grid);
+    }
+}
+
+// the buffers don't make a term
+impl PartialEq for Scratch {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for Scratch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("Scratch")
+    }
+}
+
+// writes n in decimal at the end of out
+pub(crate) fn push_num(out: &mut Vec<u8>, n: u16) {
+    let mut digits = [0u8; 5];
+    let mut idx = digits.len();
+    let mut n = n;
+    loop {
+        idx -= 1;
+        digits[idx] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    out.extend_from_slice(&digits[idx..]);
+}
+
+// writes the cursor position escape sequence at the end of out, see cup
+pub(crate) fn push_cup(out: &mut Vec<u8>, x: u16, y: u16) {
+    out.extend_from_slice(b"\x1b[");
+    push_num(out, y);
+    out.push(b';');
+    push_num(out, x);
+    out.push(b'f');
+}
+
+// writes c utf-8 encoded at the end of out, or the chars of the cluster it stands for,
+// nothing for the cell right of a wide char, the terminal drew the char over it
+pub(crate) fn push_char(out: &mut Vec<u8>, clusters: &Clusters, c: char) {
+    match (c, clusters.get(c)) {
+        (WIDE, _) => (),
+        (_, Some(chars)) => out.extend_from_slice(chars.as_bytes()),
+        (c, None) => out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod scratch {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        for n in [0, 7, 10, 305, u16::MAX] {
+            let mut out = vec![];
+            push_num(&mut out, n);
+            assert_eq!(out, n.to_string().into_bytes());
+        }
+        let mut out = vec![];
+        push_cup(&mut out, 12, 3);
+        assert_eq!(out, super::super::cup(12, 3).into_bytes());
+    }
+
+    #[test]
+    fn grids() {
+        let mut scratch = Scratch::default();
+        let mut big = scratch.grid(100);
+        big[0] = Some('a');
+        let capacity = big.capacity();
+        scratch.put_back(big);
+
+        let small = scratch.grid(10);
+        assert_eq!(small, vec![None; 10]);
+        assert_eq!(small.capacity(), capacity);
+    }
+}
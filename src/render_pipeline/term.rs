@@ -3,16 +3,19 @@ use std::io::{StdoutLock, Write};
 use std::str::Chars;
 
 use crate::components::*;
+use crate::components::popup;
+use crate::console::{Capabilities, RawMode};
+use crate::inputs::keyboard::{Char, KbdEvent, KeyKind, Modifiers};
 use crate::space::{border::Border, padding::Padding};
 use crate::themes::Style;
 
-use super::spread_padding;
+use super::scratch::{push_char, push_cup};
+use super::{spread_padding, Scratch};
 
 impl Term {
     /// renders the cursor in the self cx, cy position
     pub fn render_cursor(&self, writer: &mut StdoutLock) {
-        let pos = format!("\x1b[{};{}f", self.cy, self.cx);
-        _ = writer.write(pos.as_bytes());
+        _ = writer.write(super::cup(self.cx, self.screen_row(self.cy)).as_bytes());
     }
 
     /// renders only the text objects that have seen some value/border change since the last event
@@ -37,7 +40,24 @@ impl Term {
     /// of br (border render), vr (value render) or ar (all render) then the matching part gets
     /// rendered
 
+    /// runs the render hooks if Term::hook_partial_renders says so
     pub fn property_render(
+        &mut self,
+        writer: &mut StdoutLock,
+        key: &str,
+        br: Property,
+        vr: Property,
+        ar: Property,
+    ) {
+        match self.hooks_partial_renders() {
+            true => self.with_hooks(writer, |term, writer| {
+                term.property_render_to(writer, key, br, vr, ar)
+            }),
+            false => self.property_render_to(writer, key, br, vr, ar),
+        }
+    }
+
+    fn property_render_to(
         &self,
         writer: &mut StdoutLock,
         key: &str,
@@ -49,27 +69,27 @@ impl Term {
             if let Some(val) = c.properties.get(key) {
                 match val {
                     br => {
-                        c.render_border(writer);
+                        c.render_border_with(writer, &self.capabilities);
                         c.items.iter().for_each(|t| {
                             if let Some(val) = t.properties.get(key) {
                                 match val {
-                                    br => t.render_border(writer),
-                                    vr => t.render_value(writer),
-                                    ar => t.render(writer),
+                                    br => t.render_border_with(writer, &self.capabilities),
+                                    vr => t.render_value_with(writer, &self.capabilities),
+                                    ar => t.render_with(writer, &self.capabilities),
                                 }
                             }
                         });
                     }
-                    vr => c.render_value(writer),
-                    ar => c.render(writer),
+                    vr => c.render_value_with(writer, &self.capabilities),
+                    ar => c.render_with(writer, &self.capabilities),
                 }
             } else {
                 c.items.iter().for_each(|t| {
                     if let Some(val) = t.properties.get(key) {
                         match val {
-                            br => t.render_border(writer),
-                            vr => t.render_value(writer),
-                            ar => t.render(writer),
+                            br => t.render_border_with(writer, &self.capabilities),
+                            vr => t.render_value_with(writer, &self.capabilities),
+                            ar => t.render_with(writer, &self.capabilities),
                         }
                     }
                 });
@@ -82,52 +102,218 @@ impl Term {
 
     // NOTE: this method renders the entire component; both border and value
     // since it has no way of telling which part to render
-    pub fn attribute_render(&self, writer: &mut StdoutLock, attr: &str) {
-        self.containers.iter().for_each(|c| {
+    // runs the render hooks if Term::hook_partial_renders says so
+    pub fn attribute_render(&mut self, writer: &mut StdoutLock, attr: &str) {
+        self.attribute_render_hooked(writer, attr);
+    }
+
+    pub(crate) fn attribute_render_hooked(&mut self, writer: &mut impl Write, attr: &str) {
+        match self.hooks_partial_renders() {
+            true => self.with_hooks(writer, |term, writer| term.attribute_render_to(writer, attr)),
+            false => self.attribute_render_to(writer, attr),
+        }
+    }
+
+    fn attribute_render_to(&self, writer: &mut impl Write, attr: &str) {
+        let dimmed_below = self.dimmed_below();
+        self.draw_order().for_each(|(idx, c)| {
+            let mut render = vec![];
             if c.attributes.contains(attr) {
-                c.render(writer);
+                c.render_with(&mut render, &self.capabilities);
             } else {
-                c.items.iter().for_each(|t| {
-                    if t.attributes.contains(attr) {
-                        t.render(writer);
+                c.items.iter().filter(|t| !t.is_hidden()).for_each(|t| {
+                    // a patched text only renders the rows the patches changed
+                    match (t.attributes.contains(attr), &t.patched) {
+                        (true, Some(rows)) if attr == "dirty" => {
+                            t.render_rows_with(&mut render, &self.capabilities, rows.clone())
+                        }
+                        (true, _) => t.render_with(&mut render, &self.capabilities),
+                        (false, _) => (),
                     }
                 });
             }
+            // what is under a dimming popup is faint
+            if dimmed_below.is_some_and(|below| idx < below) && !render.is_empty() {
+                render = popup::dim(&render);
+            }
+            _ = writer.write_all(&render);
         });
     }
 
-    pub fn partial_render(&self, writer: &mut StdoutLock) {}
+    /// whether some component was marked dirty since the last Term::render_dirty call,
+    /// the focus moved to or from a text that is styled while it is focused
+    /// or the soft cursor blinked, see Term::set_soft_cursor
+    pub fn is_dirty(&self) -> bool {
+        let marked = self.containers.iter().any(|c| {
+            c.attributes.contains("dirty") || c.items.iter().any(|t| t.attributes.contains("dirty"))
+        });
 
-    fn prepare(&self) -> (Vec<Option<char>>) {
-        let mut lines: Vec<Option<char>> = vec![];
-        lines.resize((self.w * self.h) as usize, None);
+        if self.mode == ScreenMode::Linear {
+            return marked || self.linear_focus_moved();
+        }
 
-        self.containers.iter().for_each(|c| {
-            let mut idx = c.x0 + c.y0 * self.w;
-            let mut line = 0;
-            let (cells, [cwx, chx]) = c.prepare();
-
-            loop {
-                // write the item line inside the container lines
-                for cidx in 0..cwx {
-                    let cell = cells[(cidx + line * cwx) as usize];
-                    if cell.is_some() {
-                        lines[idx as usize] = cell;
-                    }
-                    idx += 1;
-                }
+        marked || self.focus_moved() || self.cursor_due()
+    }
+
+    /// renders the components that were marked dirty since the last call then unmarks them
+    pub fn render_dirty(&mut self, writer: &mut StdoutLock) -> std::io::Result<()> {
+        self.render_dirty_to(writer)
+    }
+
+    // the render hooks run around the frame, see Term::on_before_render
+    pub(crate) fn render_dirty_to(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        self.with_hooks(writer, Self::dirty_frame_to)
+    }
+
+    fn dirty_frame_to(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        if self.mode == ScreenMode::Linear {
+            return self.render_dirty_linear(writer);
+        }
+        #[cfg(feature = "stats")]
+        let mut times = self.frame_started();
+        #[cfg(feature = "stats")]
+        let cells = times.as_ref().map_or(0, |_| self.dirty_cells());
+        let frame = self.dirty_frame();
+        #[cfg(feature = "stats")]
+        self.dirty_laid_out(&mut times, cells);
+        // the whole frame or nothing, a short write would leave half a sequence on the display
+        let written = writer.write_all(&frame).and_then(|_| writer.flush());
+        if written.is_err() {
+            self.lose_frame();
+        }
+        #[cfg(feature = "stats")]
+        self.frame_written(times, frame.len());
+
+        written
+    }
+
+    // a dirty frame that failed to go out unmarked its components,
+    // whatever of it made it out gets drawn again in full
+    fn lose_frame(&mut self) {
+        self.forget_frame();
+        self.containers.iter_mut().for_each(|c| {
+            c.mark_dirty();
+            c.items.iter_mut().for_each(|t| t.mark_dirty());
+        });
+    }
+
+    // the bytes that render the dirty components, which get unmarked
+    fn dirty_frame(&mut self) -> Vec<u8> {
+        self.raise_popups();
+        self.mark_focused();
+        let mut frame = self.uncovered_frame().into_bytes();
+        self.attribute_render_to(&mut frame, "dirty");
+        // open dropdowns go over whatever was rendered under them
+        frame.extend(self.overlays_frame().into_bytes());
+        frame.extend(self.soft_cursor_frame(false).into_bytes());
+        self.containers.iter_mut().for_each(|c| {
+            c.attributes.remove("dirty");
+            c.items.iter_mut().for_each(|t| {
+                t.attributes.remove("dirty");
+                t.patched = None;
+            });
+        });
+        // the display is not the last frame Term::render drew anymore
+        if !frame.is_empty() {
+            self.forget_frame();
+        }
+
+        self.placed(frame)
+    }
+
+    // the focused text and the [value, border] styles it is rendered in, its own focus styles
+    // or the theme's "focused.content" and "focused.border" ones, None on a "plain-focus" term
+    fn focus_styles(&self) -> Option<([u8; 3], [Option<String>; 2])> {
+        let text = self.focused.and_then(|id| self.text_ref(&id))?;
+        if self.attributes.contains("plain-focus") {
+            return None;
+        }
+        let own = [&text.focus_style, &text.focus_border_style];
+        let keys = ["focused.content", "focused.border"];
+        let styles = [0, 1].map(|idx| match own[idx] {
+            Some(style) => Some(style.style()),
+            None => self.theme_style(keys[idx]),
+        });
+
+        Some((text.id, styles))
+    }
+
+    // the styles of the text with id, given the focused one's
+    fn focus_of(
+        focus: &Option<([u8; 3], [Option<String>; 2])>,
+        id: [u8; 3],
+    ) -> [Option<String>; 2] {
+        match focus {
+            Some((focused, styles)) if *focused == id => styles.clone(),
+            _ => [None, None],
+        }
+    }
+
+    // whether the focus moved since the last frame and a text has to be drawn in other styles
+    fn focus_moved(&self) -> bool {
+        let focus = self.focus_styles();
+        let mut texts = self.containers.iter().flat_map(|c| c.items.iter());
 
-                // skip the end of current line that is not inside item twx
-                // then skip the next line beginning up to c.xy
-                idx += self.w - cwx;
+        texts.any(|t| t.focus != Self::focus_of(&focus, t.id))
+    }
 
-                // increment item lines by one until last line
-                line += 1;
-                if line == chx {
-                    break;
+    // gives the texts the styles they are rendered in, a borderless focused text gives its border
+    // style to its container, the components whose styles changed are dirty
+    fn mark_focused(&mut self) {
+        let focus = self.focus_styles();
+        for c in self.containers.iter_mut() {
+            let mut within = None;
+            for t in c.items.iter_mut() {
+                let styles = Self::focus_of(&focus, t.id);
+                if let (Border::None, None) = (&t.border, &within) {
+                    within = styles[1].clone();
+                }
+                if t.focus != styles {
+                    t.focus = styles;
+                    t.mark_dirty();
                 }
             }
+            if c.focus != within {
+                c.focus = within;
+                c.mark_dirty();
+            }
+        }
+    }
+
+    /// makes the focused text look like the others when styled is false, for apps that draw
+    /// their own focus indicators, see Text::set_focus_style and Theme
+    pub fn style_focus(&mut self, styled: bool) {
+        match styled {
+            true => self.attributes.remove("plain-focus"),
+            false => self.attributes.insert("plain-focus"),
+        };
+    }
+
+    pub fn partial_render(&self, writer: &mut StdoutLock) {}
+
+    pub(crate) fn prepare(&self) -> (Vec<Option<char>>) {
+        self.prepare_in(&mut Scratch::default())
+    }
+
+    // the cells of this term, put together in the grids of scratch
+    // the cells are a grid of scratch too, they go back to it once used
+    fn prepare_in(&self, scratch: &mut Scratch) -> Vec<Option<char>> {
+        // the clusters of the frames before this one are numbered again from the first,
+        // the last frame can't be told from this one, see Clusters::reclaim
+        if scratch.clusters.is_full() {
+            scratch.clusters = Default::default();
+            scratch.stale.set(true);
+        }
+        let mut lines = scratch.grid(self.w as usize * self.h as usize);
+
+        self.draw_order().for_each(|(_, c)| {
+            let (cells, cwh) = c.prepare(scratch);
+            super::blit(&mut lines, [self.w, self.h], &cells, cwh, [c.x0, c.y0]);
+            scratch.put_back(cells);
         });
+        if self.attributes.contains("merge-borders") {
+            self.join_borders(&mut lines, scratch);
+        }
 
         // NOTE: this part is really hard to debug since term is the size of the entire terminal
         // window and has no border or padding
@@ -143,46 +329,309 @@ impl Term {
     // this is expected behavior, although it's bad
     // need a way to map whatever style to some range of positions in the term buffer
     // that way, atomic style implementation becomes easy to call from anywhere
-    pub fn render(&mut self, writer: &mut StdoutLock) {
-        let cells = self.prepare();
+    pub fn render(&mut self, writer: &mut StdoutLock) -> std::io::Result<()> {
+        self.render_to(writer)
+    }
 
-        let mut s = String::new();
+    // writes the frame to writer, it is put together in this term's scratch buffers
+    // a frame of the same size as the ones before it allocates nothing
+    // the render hooks run around the frame, see Term::on_before_render
+    pub(crate) fn render_to(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        self.with_hooks(writer, Self::frame_to)
+    }
 
-        let mut line = 0;
-        let mut idx = 0;
+    fn frame_to(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        if self.mode == ScreenMode::Linear {
+            return self.render_linear(writer);
+        }
+        #[cfg(feature = "stats")]
+        let mut times = self.frame_started();
+        let mut scratch = std::mem::take(&mut self.scratch);
+        let cells = self.prepare_in(&mut scratch);
+        #[cfg(feature = "stats")]
+        self.frame_laid_out(&mut times, &cells);
+        self.encode(&self.capabilities, &mut scratch, cells);
+        let cursor = self.soft_cursor_frame(true);
+        // the soft cursor is not in the frame, the next one draws its cell again
+        if !cursor.is_empty() {
+            scratch.stale.set(true);
+        }
+        scratch.out.extend(cursor.into_bytes());
+        self.place(&mut scratch);
+        let written = writer.write_all(&scratch.out).and_then(|_| writer.flush());
+        // the frame was diffed against one that may not be on the display now
+        if written.is_err() {
+            scratch.stale.set(true);
+        }
+        #[cfg(feature = "stats")]
+        self.frame_written(times, scratch.out.len());
+        self.scratch = scratch;
 
-        cells.iter().for_each(|c| {
-            if let Some(ch) = c {
-                // print!("found char, ");
-                s.push(*ch);
-            } else {
-                // print!("found space, ");
-                s.push_str("\x1b[C");
+        written
+    }
+
+    // the frame drawn as it is with caps
+    pub(crate) fn frame_with(&self, caps: &Capabilities) -> String {
+        let mut scratch = Scratch::default();
+        self.frame_in(caps, &mut scratch);
+
+        String::from_utf8(scratch.out).unwrap()
+    }
+
+    // puts the frame drawn as it is with caps in the out buffer of scratch
+    fn frame_in(&self, caps: &Capabilities, scratch: &mut Scratch) {
+        let cells = self.prepare_in(scratch);
+        self.encode(caps, scratch, cells);
+    }
+
+    // puts the escape sequences that draw cells with caps in the out buffer of scratch,
+    // the cells go back to scratch
+    // only the cells whose char or style changed since the frame before are drawn, unless
+    // the display was drawn over since, see Term::forget_frame
+    fn encode(&self, caps: &Capabilities, scratch: &mut Scratch, cells: Vec<Option<char>>) {
+        self.paint_styles(scratch);
+        let diff = !scratch.stale.get() && scratch.last.len() == cells.len();
+        let Scratch {
+            out: s,
+            painted,
+            last,
+            last_painted,
+            styles,
+            clusters,
+            ..
+        } = scratch;
+        let sgr = styles.adapted(caps);
+        s.clear();
+        // the terminal holds the frame back until it is complete
+        if caps.synchronized_output {
+            s.extend_from_slice(b"\x1b[?2026h");
+        }
+
+        // an inline term is drawn from its first row, over whatever the rows under it hold
+        if !diff && self.inline.is_some() {
+            push_cup(s, 1, 1);
+            s.extend_from_slice(b"\x1b[J");
+        }
+        match diff {
+            true => {
+                let last = (&last[..], &last_painted[..]);
+                self.encode_changes(s, &cells, painted, last, sgr, (caps, clusters))
+            }
+            false => self.encode_all(s, &cells, painted, sgr, (caps, clusters)),
+        }
+        last.clear();
+        last.extend_from_slice(&cells);
+        last_painted.clear();
+        last_painted.extend_from_slice(painted);
+
+        // nothing is allocated unless a dropdown is open
+        let overlays = self.overlays_frame_with(caps);
+        // the cells under the dropdowns are not the ones of the frame anymore
+        scratch.stale.set(!overlays.is_empty());
+        let s = &mut scratch.out;
+        s.extend_from_slice(overlays.as_bytes());
+        push_cup(s, self.cx, self.cy);
+        if caps.synchronized_output {
+            s.extend_from_slice(b"\x1b[?2026l");
+        }
+        scratch.put_back(cells);
+    }
+
+    /// puts the terminal in raw mode and, for fullscreen Terms, moves to the alternate screen
+    /// an inline Term is given its rows under the cursor and rendered there, see Term::set_inline
+    /// a linear Term writes its lines where the cursor is, see ScreenMode::Linear
+    /// everything is torn down together once the returned guard is dropped
+    pub fn start(&mut self, writer: &mut StdoutLock) -> std::io::Result<RawMode> {
+        let mut guard = RawMode::enable()?;
+        if self.inline.is_some() {
+            self.anchor(writer)?;
+            self.render(writer)?;
+        }
+        if self.mode == ScreenMode::Fullscreen {
+            guard.enter_alternate_screen(writer)?;
+            self.attributes.insert("alt-screen");
+            self.clear(writer)?;
+            self.render(writer)?;
+        }
+        if self.mode == ScreenMode::Linear {
+            self.render(writer)?;
+        }
+
+        Ok(guard)
+    }
+
+    /// gives the bell feedback chosen by this Term's bell_mode
+    /// a visual bell returns the token that undraws the flash, see Term::flush_bell
+    pub fn bell(&mut self, writer: &mut StdoutLock) -> Option<BellToken> {
+        match self.bell_mode {
+            BellMode::Audible => {
+                _ = writer.write(b"\x07");
+                _ = writer.flush();
+
+                None
             }
-            idx += 1;
-            if idx == self.w {
-                idx = 0;
-                line += 1;
-                if line < self.h - 1 {
-                    // println!("breaking line at {{{}}}", &s[s.len() - 1..s.len()]);
-                    s.push_str("\r\n");
+            BellMode::Visual => Some(self.visual_bell(writer, std::time::Duration::from_millis(100))),
+            BellMode::None => None,
+        }
+    }
+
+    /// renders the focused text's border in reverse video,
+    /// or reverses the whole screen when there is no focused text
+    /// the flash stays until the returned token is given to Term::flush_bell
+    pub fn visual_bell(&mut self, writer: &mut StdoutLock, duration: std::time::Duration) -> BellToken {
+        let until = std::time::Instant::now() + duration;
+        let caps = self.capabilities.clone();
+        let focused = self.focused.and_then(|id| self.text_mut(&id));
+
+        let Some(text) = focused else {
+            _ = writer.write(b"\x1b[?5h");
+            _ = writer.flush();
+
+            return BellToken { target: None, until };
+        };
+
+        let bstyle = std::mem::take(&mut text.bstyle);
+        let focus = text.focus[1].take();
+        text.bstyle = format!("{}\x1b[7m", focus.as_ref().unwrap_or(&bstyle));
+        text.render_border_with(writer, &caps);
+        text.bstyle = bstyle;
+        text.focus[1] = focus;
+        _ = writer.flush();
+
+        BellToken {
+            target: Some(text.id),
+            until,
+        }
+    }
+
+    /// undraws a visual bell flash once its token is due
+    /// returns false if the token is not due yet, in which case it should be flushed again later
+    pub fn flush_bell(&mut self, writer: &mut StdoutLock, token: &BellToken) -> bool {
+        if !token.due() {
+            return false;
+        }
+
+        match token.target {
+            None => {
+                _ = writer.write(b"\x1b[?5l");
+                _ = writer.flush();
+            }
+            Some(id) => {
+                if let Some(text) = self.text_mut(&id) {
+                    text.mark_dirty();
                 }
+                _ = self.render_dirty(writer);
             }
+        }
+
+        true
+    }
+
+    /// lets Term::job_control stop the program on Ctrl-Z and SIGTSTP
+    /// apps that want to swallow Ctrl-Z just leave this off
+    pub fn enable_job_control(&mut self) -> std::io::Result<()> {
+        #[cfg(unix)]
+        crate::console::signals::catch_tstp()?;
+        self.attributes.insert("job-control");
+
+        Ok(())
+    }
+
+    /// stops the program when job control is enabled and either event is a Ctrl-Z
+    /// or a SIGTSTP came in
+    /// the terminal gets its original state back while the program is stopped,
+    /// and once continued raw mode comes back, the window size is detected again
+    /// and the whole term is redrawn
+    /// returns whether the program was stopped
+    pub fn job_control(
+        &mut self,
+        event: Option<&KbdEvent>,
+        guard: &mut RawMode,
+        writer: &mut StdoutLock,
+    ) -> std::io::Result<bool> {
+        if !self.attributes.contains("job-control") {
+            return Ok(false);
+        }
+
+        let ctrl_z = matches!(
+            event,
+            Some(KbdEvent {
+                char: Char::Char('z'),
+                modifiers: Modifiers(2),
+                kind: KeyKind::Press,
+            })
+        );
+        #[cfg(unix)]
+        let stop = crate::console::signals::take_tstp() || ctrl_z;
+        // no job control on windows
+        #[cfg(windows)]
+        let stop = false;
+        if !stop {
+            return Ok(false);
+        }
+
+        guard.suspend(writer)?;
+        #[cfg(unix)]
+        crate::console::signals::stop()?;
+        guard.resume(writer)?;
+
+        // the window may have been resized while the program was stopped
+        if let Ok(ws) = crate::console::winsize::detect() {
+            self.w = ws.cols();
+            self.h = ws.rows();
+        }
+        self.containers.iter_mut().for_each(|c| {
+            c.mark_dirty();
+            c.items.iter_mut().for_each(|t| t.mark_dirty());
         });
+        self.clear(writer)?;
+        self.render_dirty(writer)?;
 
-        assert_eq!(line, self.h);
+        Ok(true)
+    }
 
-        let pos = format!("\x1b[{};{}f", self.cy, self.cx);
-        s.push_str(&pos);
-        // println!("{}", s);
-        _ = writer.write(s.as_bytes());
-        _ = writer.flush();
+    /// sets the terminal window title
+    /// does nothing when stdout is not a terminal
+    pub fn set_title(&self, writer: &mut StdoutLock, title: &str) -> std::io::Result<()> {
+        if !crate::console::stdout_is_tty() {
+            return Ok(());
+        }
+
+        writer.write_all(crate::console::title_sequence(title).as_bytes())?;
+        writer.flush()
+    }
+
+    /// moves to the alternate screen and renders the whole Term into it
+    /// the alternate screen starts blank, so there is nothing to keep from the previous frame
+    pub fn enter_alt_screen(&mut self, writer: &mut StdoutLock) -> std::io::Result<()> {
+        writer.write_all(b"\x1b[?1049h")?;
+        self.attributes.insert("alt-screen");
+        self.clear(writer)?;
+        self.render(writer)
+    }
+
+    /// leaves the alternate screen back to the main one
+    /// the cursor gets its main screen position back and is made visible again
+    pub fn leave_alt_screen(&mut self, writer: &mut StdoutLock) -> std::io::Result<()> {
+        if !self.attributes.remove("alt-screen") {
+            return Ok(());
+        }
+        writer.write_all(b"\x1b[?1049l\x1b[?25h")?;
+        writer.flush()
     }
 
     /// clears the whole terminal display
     /// first implementation of clear
-    pub fn clear(&self, writer: &mut StdoutLock) {
-        writer.write(b"\x1b[H\x1b[J");
+    /// an inline Term only clears its own rows and the ones under them, see Term::set_inline
+    pub fn clear(&self, writer: &mut StdoutLock) -> std::io::Result<()> {
+        // whatever is left of the last frame is drawn again, even if clearing it failed
+        self.forget_frame();
+        match self.inline {
+            Some(_) => {
+                writer.write_all(format!("{}\x1b[J", super::cup(1, self.screen_row(1))).as_bytes())
+            }
+            None => writer.write_all(b"\x1b[H\x1b[J"),
+        }
     }
 
     /// clears the whole terminal display
@@ -193,6 +642,104 @@ impl Term {
             .into_iter()
             .for_each(|_| s.push_str("\x1b[2K\x1b[C"));
         s.push_str("\x1b[H");
-        writer.write(s.as_bytes());
+        _ = writer.write_all(s.as_bytes());
+        self.forget_frame();
+    }
+}
+
+#[cfg(feature = "async")]
+impl Term {
+    /// the async counterpart of Term::render
+    pub async fn render_async(
+        &mut self,
+        writer: &mut (impl tokio::io::AsyncWrite + Unpin),
+    ) -> std::io::Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let ran = self.before_render();
+        self.unwind_hooks_async(writer, ran).await;
+        #[cfg(feature = "stats")]
+        let mut times = self.frame_started();
+        let mut scratch = std::mem::take(&mut self.scratch);
+        let cells = self.prepare_in(&mut scratch);
+        #[cfg(feature = "stats")]
+        self.frame_laid_out(&mut times, &cells);
+        self.encode(&self.capabilities, &mut scratch, cells);
+        self.place(&mut scratch);
+        let written = writer.write_all(&scratch.out).await;
+        // the frame was diffed against one that may not be on the display now
+        if written.is_err() {
+            scratch.stale.set(true);
+        }
+        #[cfg(feature = "stats")]
+        self.frame_written(times, scratch.out.len());
+        self.scratch = scratch;
+        written?;
+        writer.flush().await?;
+        let ran = self.after_render();
+        self.unwind_hooks_async(writer, ran).await;
+
+        Ok(())
+    }
+
+    /// the async counterpart of Term::render_dirty
+    pub async fn render_dirty_async(
+        &mut self,
+        writer: &mut (impl tokio::io::AsyncWrite + Unpin),
+    ) -> std::io::Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let ran = self.before_render();
+        self.unwind_hooks_async(writer, ran).await;
+        #[cfg(feature = "stats")]
+        let mut times = self.frame_started();
+        #[cfg(feature = "stats")]
+        let cells = times.as_ref().map_or(0, |_| self.dirty_cells());
+        let frame = self.dirty_frame();
+        #[cfg(feature = "stats")]
+        self.dirty_laid_out(&mut times, cells);
+        let written = writer.write_all(&frame).await;
+        if written.is_err() {
+            self.lose_frame();
+        }
+        #[cfg(feature = "stats")]
+        self.frame_written(times, frame.len());
+        written?;
+        writer.flush().await?;
+        let ran = self.after_render();
+        self.unwind_hooks_async(writer, ran).await;
+
+        Ok(())
+    }
+
+    // the async counterpart of Term::unwind_hooks
+    async fn unwind_hooks_async(
+        &self,
+        writer: &mut (impl tokio::io::AsyncWrite + Unpin),
+        ran: std::thread::Result<()>,
+    ) {
+        use tokio::io::AsyncWriteExt;
+
+        if let Err(panic) = ran {
+            _ = writer.write_all(&self.frame_epilogue()).await;
+            _ = writer.flush().await;
+            std::panic::resume_unwind(panic);
+        }
+    }
+
+    /// the async counterpart of Term::clear
+    pub async fn clear_async(
+        &self,
+        writer: &mut (impl tokio::io::AsyncWrite + Unpin),
+    ) -> std::io::Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        match self.inline {
+            Some(_) => {
+                let clear = format!("{}\x1b[J", super::cup(1, self.screen_row(1)));
+                writer.write_all(clear.as_bytes()).await
+            }
+            None => writer.write_all(b"\x1b[H\x1b[J").await,
+        }
     }
 }
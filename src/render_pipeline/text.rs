@@ -1,147 +1,306 @@
 use std::collections::HashMap;
 use std::io::{StdoutLock, Write};
+use std::ops::Range;
 use std::str::Chars;
 
+use crate::components::grapheme::WIDE;
 use crate::components::*;
-use crate::space::{border::Border, padding::Padding};
+use crate::console::Capabilities;
+use crate::space::{border::Border, padding::Padding, resolve_wh, Overflow};
 use crate::themes::Style;
 
-use super::spread_padding;
+use super::{spread_padding, Scratch};
 
 impl Text {
     /// wrapper around the render_border and render_value method calls
     pub fn render(&self, writer: &mut StdoutLock) {
-        self.render_border(writer);
-        self.render_value(writer);
+        self.render_with(writer, Capabilities::detected());
+    }
+
+    pub(crate) fn render_with(&self, writer: &mut impl Write, caps: &Capabilities) {
+        self.render_border_with(writer, caps);
+        self.render_value_with(writer, caps);
     }
 
     /// renders only the text border
     pub fn render_border(&self, writer: &mut StdoutLock) {
+        self.render_border_with(writer, Capabilities::detected());
+    }
+
+    pub(crate) fn render_border_with(&self, writer: &mut impl Write, caps: &Capabilities) {
+        // a borderless text can sit on the first row, there is no row above it to work out
+        let Border::Uniform(c) = self.border else {
+            return;
+        };
         let [por, pol, pot, pob, pir, pil, pit, pib] = spread_padding(&self.padding);
         let [xb, yb] = [self.ax0 - pil - 1, self.ay0 - pit - 1];
-        let mut s = format!("{}\x1b[{};{}f", &self.bstyle, yb, xb);
+        // the validation state shows over the focus
+        let bstyle = self.validation_border().or(self.focus[1].as_ref());
+        let bstyle = bstyle.unwrap_or(&self.bstyle);
+        let mut s = format!("{}\x1b[{};{}f", caps.adapt_sgr(bstyle), yb, xb);
 
         let wb = pil + 1 + self.w + 1 + pir;
         let hb = pit + 1 + self.h + 1 + pib;
 
-        if let Border::Uniform(c) = self.border {
-            for _ in 0..wb {
-                s.push(c)
-            }
+        let c = caps.adapt_char(c);
+        for _ in 0..wb {
+            s.push(c)
+        }
 
-            for idx in 1..hb - 1 {
-                s.push_str(&format!(
-                    "\x1b[{};{}f{}\x1b[{};{}f{}",
-                    yb + idx,
-                    xb,
-                    c,
-                    yb + idx,
-                    xb + wb - 1,
-                    c
-                ));
-            }
+        for idx in 1..hb - 1 {
+            s.push_str(&format!(
+                "\x1b[{};{}f{}\x1b[{};{}f{}",
+                yb + idx,
+                xb,
+                c,
+                yb + idx,
+                xb + wb - 1,
+                c
+            ));
+        }
 
-            s.push_str(&format!("\x1b[{};{}f", yb + hb - 1, xb));
-            for _ in 0..wb {
-                s.push(c)
-            }
+        s.push_str(&format!("\x1b[{};{}f", yb + hb - 1, xb));
+        for _ in 0..wb {
+            s.push(c)
+        }
 
-            s.push_str("\x1b[0m");
+        s.push_str("\x1b[0m");
 
-            writer.write(s.as_bytes());
-        }
+        _ = writer.write_all(s.as_bytes());
     }
 
     /// renders only the text value
     pub fn render_value(&self, writer: &mut StdoutLock) {
+        self.render_value_with(writer, Capabilities::detected());
+    }
+
+    pub(crate) fn render_value_with(&self, writer: &mut impl Write, caps: &Capabilities) {
+        self.render_rows_with(writer, caps, 0..self.h);
+    }
+
+    // renders only the value rows of the area in rows, and the scrollbars and the gutter,
+    // see Text::apply_patch
+    pub(crate) fn render_rows_with(
+        &self,
+        writer: &mut impl Write,
+        caps: &Capabilities,
+        rows: Range<u16>,
+    ) {
         let h0 = self.ay0;
+        let vstyle = self.value_style();
 
         let del = |s: &mut String, y: u16| {
             *s += &format!("\x1b[{};{}f\x1b[{}X", y, self.ax0, self.w);
         };
 
+        // the selected row of a list, a table or a tree, see List::highlight, Table::highlight
+        // and Tree::highlight, the brackets of a focused checkbox, the active tab label
+        // or the level prefixes of a log pane, and the search matches, as the row, its [start, end) cells and the style
+        let row = |(y, style)| (y, [0, self.w], style);
+        let first = |(cells, style)| (0, cells, style);
+        let selectable = (&self.list, &self.table, &self.tree);
+        let highlighted = match (selectable, &self.checkbox, &self.tabs) {
+            ((Some(list), ..), ..) => list.highlighted(self.h).map(row),
+            ((None, Some(table), _), ..) => table.highlighted(self.h).map(row),
+            ((None, None, Some(tree)), ..) => tree.highlighted(self.h).map(row),
+            (_, Some(checkbox), _) => checkbox.focused().map(first),
+            (_, None, Some(tabs)) => tabs.highlighted().map(first),
+            (_, None, None) => None,
+        };
+        let mut highlighted = highlighted.into_iter().collect::<Vec<(u16, [u16; 2], &str)>>();
+        if let Some(log) = &self.log {
+            highlighted.extend(log.prefixes(self.scroll, self.h));
+        }
+        highlighted.extend(self.search_highlights());
+        if let Some((_, style)) = self.validation_glyph() {
+            let vw = self.view()[0];
+            highlighted.push((0, [vw.saturating_sub(1), vw], style));
+        }
+        // a highlight ends with the row, so that its style is reset
+        highlighted.iter_mut().for_each(|(_, [_, end], _)| *end = (*end).min(self.w));
+
+        let cells = self.cells();
         let put = |s: &mut String, y: u16| {
             *s += &format!("\x1b[{};{}f", h0 + y, self.ax0);
+            let highlight = highlighted.iter().filter(|(row, _, _)| *row == y);
             for idx in 0..self.w {
-                let c = self.value[(idx + y * self.w) as usize];
-                if c.is_some() {
-                    s.push(c.unwrap());
-                } else {
-                    s.push_str("\x1b[C")
+                let inside = highlight
+                    .clone()
+                    .find(|(_, [start, end], _)| (*start..*end).contains(&idx))
+                    .copied();
+                if let Some((_, _, style)) = inside.filter(|(_, [start, _], _)| *start == idx) {
+                    *s += &caps.adapt_sgr(style);
+                }
+                match (self.shown(&cells, idx, y), inside) {
+                    (Some(WIDE), _) => (),
+                    (Some(c), _) => self.clusters.push(s, caps.adapt_char(c)),
+                    // the highlight goes all the way across its cells
+                    (None, Some(_)) => s.push(' '),
+                    (None, None) => s.push_str("\x1b[C"),
                 };
+                if inside.is_some_and(|(_, [_, end], _)| end == idx + 1) {
+                    *s += "\x1b[0m";
+                    *s += &caps.adapt_sgr(vstyle);
+                }
             }
         };
 
-        let mut s = format!("{}", &self.vstyle);
+        let mut s = caps.adapt_sgr(vstyle);
+        if self.attributes.contains("style-padding") && rows == (0..self.h) {
+            let [_, _, _, _, pir, pil, pit, pib] = spread_padding(&self.padding);
+            let area = [self.ax0 - pil, h0 - pit, pil + self.w + pir, pit + self.h + pib];
+            super::erase(&mut s, area);
+        }
 
         // iterate through lines
-        for idx in 0..self.h {
+        for idx in rows.start..rows.end.min(self.h) {
             del(&mut s, h0 + idx);
             put(&mut s, idx);
         }
+        self.render_scrollbars(&mut s, caps);
+        self.render_gutter(&mut s, caps);
 
         s += "\x1b[0m";
 
-        writer.write(s.as_bytes());
+        _ = writer.write_all(s.as_bytes());
     }
 
-    // returns the text component's width and height + the component borders and paddings
-    pub(crate) fn decorate(&self) -> [u16; 2] {
-        let [mut wextra, mut hextra] = match self.border {
-            Border::None => [self.w, self.h],
-            _ => [self.w + 2, self.h + 2],
-        };
+    // the style the value is rendered in, a disabled text is dimmed, see Text::dstyle,
+    // a focused one takes its focus style and a hovered one its hover style
+    pub(crate) fn value_style(&self) -> &str {
+        match (self.is_disabled(), &self.focus[0], &self.hover) {
+            (true, ..) => &self.dstyle,
+            (false, Some(style), _) | (false, None, Some(style)) => style,
+            (false, None, None) => &self.vstyle,
+        }
+    }
+
+    /// returns the value cell at the [x, y] coordinates of this text's area
+    /// coordinates outside of the area always give None, no matter what the value holds,
+    /// so that an oversized value can never be rendered past this text's borders,
+    /// and so do the ones under a shown scrollbar
+    pub(crate) fn cell(&self, x: u16, y: u16) -> Option<char> {
+        if x >= self.w || y >= self.h {
+            return None;
+        }
+
+        self.shown(&self.cells(), x, y)
+    }
+
+    // the w * h cells of this text's area, row after row, as they are shown
+    // the value is laid out on the area once per render, see Text::cell
+    pub(crate) fn cells(&self) -> Vec<Option<char>> {
+        let mut cells = vec![None; self.w as usize * self.h as usize];
+        self.lay_out(&mut cells);
+
+        cells
+    }
 
-        [wextra, hextra] = match self.padding {
-            Padding::None => [wextra, hextra],
-            Padding::Inner {
-                top,
-                bottom,
-                right,
-                left,
-            } => [wextra + right + left, hextra + top + bottom],
-            Padding::Outer {
-                top,
-                bottom,
-                right,
-                left,
-            } => [wextra + right + left, hextra + top + bottom],
-            Padding::InOut {
-                inner_top,
-                inner_bottom,
-                inner_right,
-                inner_left,
-                outer_top,
-                outer_bottom,
-                outer_right,
-                outer_left,
-            } => [
-                wextra + inner_right + inner_left + outer_right + outer_left,
-                hextra + inner_top + inner_bottom + outer_top + outer_bottom,
-            ],
+    // lays the value out on the w * h empty cells, see Text::cells
+    fn lay_out(&self, cells: &mut [Option<char>]) {
+        let w = self.w as usize;
+        let [vw, vh] = self.view().map(usize::from);
+        let [stride, hscroll] = [self.stride() as usize, self.hscroll as usize];
+        if vw == 0 || vh == 0 || stride == 0 {
+            return;
+        }
+
+        let start = self.scroll as usize * stride;
+        let shown = self.shown_cells(start..start + vh * stride).enumerate();
+        for (idx, c) in shown {
+            let [x, y] = [idx % stride, idx / stride];
+            if (hscroll..hscroll + vw).contains(&x) {
+                cells[x - hscroll + y * w] = c;
+            }
+        }
+        // a wide char cut by the edges of the view is left out, both its cells are blank
+        for row in cells.chunks_mut(w).take(vh) {
+            if row[0] == Some(WIDE) {
+                row[0] = Some(' ');
+            }
+            if row[vw - 1].is_some_and(|c| c != WIDE && self.clusters.cells(c) > 1) {
+                row[vw - 1] = Some(' ');
+            }
+        }
+        for row in cells.chunks_mut(w).take(vh) {
+            self.in_direction(&mut row[..vw]);
+        }
+        // and so is the one a glyph goes over half of
+        let over = |cells: &mut [Option<char>], idx: usize, glyph: char| {
+            if idx > 0 && cells[idx] == Some(WIDE) {
+                cells[idx - 1] = Some(' ');
+            }
+            if cells.get(idx + 1) == Some(&Some(WIDE)) {
+                cells[idx + 1] = Some(' ');
+            }
+            cells[idx] = Some(glyph);
         };
 
-        [wextra, hextra]
+        // the ellipsis is at the end of the last line, on the left of an rtl one
+        if let Overflow::Ellipsis = self.overflow {
+            let end = match self.direction() {
+                Direction::Ltr => vw - 1,
+                Direction::Rtl => 0,
+            };
+            if self.overflows() {
+                over(cells, end + (vh - 1) * w, '…');
+            }
+        }
+        if let Some((glyph, _)) = self.validation_glyph() {
+            over(cells, vw - 1, glyph);
+        }
+    }
+
+    // the cell at [x, y] of the cells given by Text::cells
+    fn shown(&self, cells: &[Option<char>], x: u16, y: u16) -> Option<char> {
+        cells[x as usize + y as usize * self.w as usize]
+    }
+
+    // whether any of the value cells lies below this text's scrolled w * h area
+    fn overflows(&self) -> bool {
+        let end = self.stride() as usize * (self.view()[1] as usize + self.scroll as usize);
+
+        self.value.last_filled().is_some_and(|last| last >= end)
+    }
+
+    // returns the text component's width and height + the component borders and paddings
+    pub(crate) fn decorate(&self) -> [u16; 2] {
+        self.decoration.get(self.geometry(), || {
+            // a size that doesn't fit in a u16 saturates, it is then too big for any parent
+            let [wextra, hextra] = resolve_wh(&self.border, &self.padding).unwrap_or([u16::MAX; 2]);
+
+            [self.w.saturating_add(wextra), self.h.saturating_add(hextra)]
+        })
     }
 
     // this should be used inside the container prepare method
-    pub(super) fn prepare(&self) -> (Vec<Option<char>>, [u16; 2]) {
+    // the lines are a grid of scratch, they go back to it once used
+    pub(super) fn prepare(&self, scratch: &mut Scratch) -> (Vec<Option<char>>, [u16; 2]) {
         // make out each line of the item, padding and border included
         // then render line
         // until all lines are rendered
-        let mut lines: Vec<Option<char>> = vec![];
         // wx is the number of chars in a line
         // hx is the number of lines
         let [wx, mut hx] = self.decorate();
+        let mut lines = scratch.grid(wx as usize * hx as usize);
 
-        lines.resize((wx * hx) as usize, None);
-
-        self.process(&mut lines);
+        let mut cells = scratch.grid(self.w as usize * self.h as usize);
+        self.lay_out(&mut cells);
+        for c in cells.iter_mut().flatten() {
+            *c = scratch.clusters.take(&self.clusters, *c);
+        }
+        self.process(&mut lines, &cells);
+        scratch.put_back(cells);
+        if self.line_numbers() {
+            let [_, pol, pot, _, _, pil, pit, _] = spread_padding(&self.padding);
+            let b = if let Border::None = self.border { 0 } else { 1 };
+            self.put_gutter(&mut lines, wx, [pol + b + pil, pot + b + pit]);
+        }
 
         (lines, [wx, hx])
     }
 
-    fn process(&self, lines: &mut Vec<Option<char>>) {
+    fn process(&self, lines: &mut Vec<Option<char>>, cells: &[Option<char>]) {
         let [por, pol, pot, pob, pir, pil, pit, pib] = spread_padding(&self.padding);
 
         let [wx, hx] = self.decorate();
@@ -149,10 +308,10 @@ impl Text {
         // we skip as many lines as the value of padding outer top
         // if padding outer bottom > 0 then lst line gets nothing
         match self.border {
-            Border::None => return,
+            Border::None => self.process_none(lines, cells, wx, pol, pot, pil, pit),
 
             Border::Uniform(c) => {
-                self.process_uniform(c, lines, wx, hx, por, pol, pot, pob, pir, pil, pit, pib)
+                self.process_uniform(c, lines, cells, wx, hx, por, pol, pot, pob, pir, pil, pit, pib)
             }
 
             Border::Polyform {
@@ -163,8 +322,8 @@ impl Text {
                 rl,
                 tb,
             } => self.process_polyform(
-                trcorner, tlcorner, blcorner, brcorner, tb, rl, lines, wx, hx, por, pol, pot, pob,
-                pir, pil, pit, pib,
+                trcorner, tlcorner, blcorner, brcorner, tb, rl, lines, cells, wx, hx, por, pol,
+                pot, pob, pir, pil, pit, pib,
             ),
 
             Border::Manual {
@@ -187,7 +346,7 @@ impl Text {
             } => {
                 self.process_manual(
                     tlcorner, trcorner, blcorner, brcorner, r0, rp, r1, l0, lp, l1, t0, tp, t1, b0,
-                    bp, b1, lines, wx, hx, por, pol, pot, pob, pir, pil, pit, pib,
+                    bp, b1, lines, cells, wx, hx, por, pol, pot, pob, pir, pil, pit, pib,
                 );
             }
         }
@@ -195,25 +354,29 @@ impl Text {
 
     fn process_none(
         &self,
-        c: char,
         lines: &mut Vec<Option<char>>,
+        cells: &[Option<char>],
         wx: u16,
-        hx: u16,
-        por: u16,
         pol: u16,
         pot: u16,
-        pob: u16,
-        pir: u16,
         pil: u16,
         pit: u16,
-        pib: u16,
     ) {
+        // no border cells to write, only the value lines after the top paddings
+        for line in 0..self.h {
+            let mut idx = (pot + pit + line) * wx + pol + pil;
+            for vi in 0..self.w {
+                lines[idx as usize] = self.shown(cells, vi, line);
+                idx += 1;
+            }
+        }
     }
 
     fn process_uniform(
         &self,
         c: char,
         lines: &mut Vec<Option<char>>,
+        cells: &[Option<char>],
         wx: u16,
         hx: u16,
         por: u16,
@@ -302,10 +465,7 @@ impl Text {
                     //     pit,
                     //     vi + (self.w * (line - pot - 1 - pit)) as usize
                     // );
-                    let i = vi + (self.w * (line - pot - 1 - pit)) as usize;
-                    if i < self.value.len() {
-                        lines[idx as usize] = self.value[i];
-                    }
+                    lines[idx as usize] = self.shown(cells, vi as u16, line - pot - 1 - pit);
                     idx += 1;
                     // log_buf(&lines, wx, hx);
                 }
@@ -370,6 +530,7 @@ impl Text {
         btb: char,
         blr: char,
         lines: &mut Vec<Option<char>>,
+        cells: &[Option<char>],
         wx: u16,
         hx: u16,
         por: u16,
@@ -467,10 +628,7 @@ impl Text {
                 //     pit,
                 //     vi + (self.w * (line - pot - 1 - pit)) as usize
                 // );
-                let i = vi + (self.w * (line - pot - 1 - pit)) as usize;
-                if i < self.value.len() {
-                    lines[idx as usize] = self.value[i];
-                }
+                lines[idx as usize] = self.shown(cells, vi as u16, line - pot - 1 - pit);
                 idx += 1;
                 // log_buf(&lines, wx, hx);
             }
@@ -551,6 +709,7 @@ impl Text {
         bp: char,
         b1: &str,
         lines: &mut Vec<Option<char>>,
+        cells: &[Option<char>],
         wx: u16,
         hx: u16,
         por: u16,
@@ -688,10 +847,7 @@ impl Text {
                 //     pit,
                 //     vi + (self.w * (line - pot - 1 - pit)) as usize
                 // );
-                let i = vi + (self.w * (line - pot - 1 - pit)) as usize;
-                if i < self.value.len() {
-                    lines[idx as usize] = self.value[i];
-                }
+                lines[idx as usize] = self.shown(cells, vi as u16, line - pot - 1 - pit);
                 idx += 1;
                 // log_buf(&lines, wx, hx);
             }
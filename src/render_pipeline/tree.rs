@@ -0,0 +1,95 @@
+use std::io::{StdoutLock, Write};
+
+use crate::components::ComponentTree;
+
+impl ComponentTree {
+    /// renders the active Term of this tree, nothing is written when there is none
+    /// the first render after the active term changed clears the display and draws the term
+    /// in full, with the cursor where it was in that term, the renders after it only draw its
+    /// dirty components, see Term::render_dirty
+    pub fn render(&mut self, writer: &mut StdoutLock) -> std::io::Result<()> {
+        self.render_to(writer)
+    }
+
+    pub(crate) fn render_to(&mut self, writer: &mut impl Write) -> std::io::Result<()> {
+        let Some(id) = self.term else {
+            return Ok(());
+        };
+        let switched = self.drawn != Some(id);
+        let Some(term) = self.term_mut(id) else {
+            return Ok(());
+        };
+        if switched {
+            term.forget_frame();
+            writer.write_all(b"\x1b[H\x1b[J")?;
+            term.render_to(writer)?;
+        } else {
+            term.render_dirty_to(writer)?;
+        }
+        self.drawn = Some(id);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tree {
+    use crate::components::{ComponentTree, Container, Term, Text};
+    use crate::space::{Area, Pos};
+
+    // a tree with the terms 0 and 1, each with an input holding name
+    fn tree() -> ComponentTree {
+        let mut tree = ComponentTree::new();
+        tree.remove_term(0).unwrap();
+        for (id, name) in [(0, "main"), (1, "settings")] {
+            let mut term = Term::new(id, 20, 4);
+            Container::builder([id, 0])
+                .area(Area::Values { w: 20, h: 4 })
+                .build(&mut term)
+                .unwrap();
+            Text::input_builder([id, 0, 0])
+                .pos(Pos::Start, Pos::Start)
+                .area(Area::Values { w: 10, h: 1 })
+                .build(&mut term)
+                .unwrap();
+            term.text_mut(&[id, 0, 0]).unwrap().replace_value(name);
+            tree.push(term).unwrap();
+        }
+        tree.set_active(0).unwrap();
+
+        tree
+    }
+
+    fn rendered(tree: &mut ComponentTree) -> String {
+        let mut out = vec![];
+        tree.render_to(&mut out).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn switched() {
+        let mut tree = tree();
+        let main = rendered(&mut tree);
+        assert!(main.starts_with("\x1b[H\x1b[J"));
+        assert!(main.contains("main"));
+        // nothing is dirty, the term is drawn already
+        assert!(!rendered(&mut tree).starts_with("\x1b[H\x1b[J"));
+
+        tree.active_mut().unwrap().cx = 3;
+        tree.set_active(1).unwrap();
+        let settings = rendered(&mut tree);
+        assert!(settings.starts_with("\x1b[H\x1b[J"));
+        assert!(settings.contains("settings"));
+        assert!(!settings.contains("main"));
+
+        // term 0 gets its cursor back
+        tree.set_active(0).unwrap();
+        let back = rendered(&mut tree);
+        let term = tree.active().unwrap();
+        assert!(back.contains(&crate::render_pipeline::cup(3, term.cy)));
+
+        tree.remove_term(0).unwrap();
+        assert!(rendered(&mut tree).is_empty());
+    }
+}
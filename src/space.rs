@@ -7,12 +7,15 @@ use std::{
     },
 };
 
-use crate::components::{ComponentTree, Container, Term, Text};
+use crate::components::{ComponentTree, Container, SpaceError, Term, Text};
 use crate::render_pipeline;
 
+mod areas;
 pub mod border;
 pub mod padding;
 
+pub(crate) use areas::AreaIndex;
+
 use border::Border;
 use padding::Padding;
 
@@ -20,6 +23,11 @@ pub(crate) fn between<T: std::cmp::PartialOrd>(a: T, b: T, c: T) -> bool {
     a < b && b < c
 }
 
+// whether a length long span starting at start goes past end, or past what a u16 holds
+pub(crate) fn ends_past(start: u16, length: u16, end: u16) -> bool {
+    start.checked_add(length).map_or(true, |stop| stop > end)
+}
+
 // this exists because the border doesn't have a width and height in this lib, it is always a
 // single cell box surrounding the value
 // NOTE: this need be used only for Border::Manual
@@ -92,44 +100,23 @@ pub(crate) fn calc_text_abs_ori(
     ]
 }
 
-pub(crate) fn resolve_wh(border: &Border, padding: &Padding) -> [u16; 2] {
-    let bv = if let Border::None = border { 0 } else { 1 };
-    let [pw, ph] = if let Padding::Inner {
-        top,
-        bottom,
-        right,
-        left,
-    } = padding
-    {
-        [right + left, top + bottom]
-    } else if let Padding::Outer {
-        top,
-        bottom,
-        right,
-        left,
-    } = padding
-    {
-        [right + left, top + bottom]
-    } else if let Padding::InOut {
-        inner_top,
-        inner_bottom,
-        inner_right,
-        inner_left,
-        outer_top,
-        outer_bottom,
-        outer_right,
-        outer_left,
-    } = padding
-    {
-        [
-            inner_right + inner_left + outer_right + outer_left,
-            inner_top + inner_bottom + outer_top + outer_bottom,
-        ]
-    } else {
-        [0; 2]
+// the width and height the border and padding add to an area,
+// None when they don't fit in a u16
+pub(crate) fn resolve_wh(border: &Border, padding: &Padding) -> Option<[u16; 2]> {
+    let bv = if let Border::None = border { 0 } else { 2 };
+    let [por, pol, pot, pob, pir, pil, pit, pib] = render_pipeline::spread_padding(padding);
+    let sum = |values: [u16; 4]| {
+        values
+            .into_iter()
+            .try_fold(bv, |total: u16, value| total.checked_add(value))
     };
 
-    [pw + bv * 2, ph + bv * 2]
+    Some([sum([por, pol, pir, pil])?, sum([pot, pob, pit, pib])?])
+}
+
+// whether the [x, y, w, h] areas a and b share a cell
+pub(crate) fn overlap(a: [u16; 4], b: [u16; 4]) -> bool {
+    a[0] < b[0] + b[2] && b[0] < a[0] + a[2] && a[1] < b[1] + b[3] && b[1] < a[1] + a[3]
 }
 
 pub(crate) fn area_conflicts(
@@ -171,7 +158,7 @@ pub(crate) fn area_conflicts(
 }
 
 // use this instead of width/height
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Polygon {
     Square {
         top_left: u16,
@@ -239,7 +226,7 @@ impl Polygon {
 
 // can be either vertical or horizontal
 // use instead of passing x0 and y0
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub enum Pos {
     /// position component at the start of parent's area, either vertically or horizontally
     Start,
@@ -270,7 +257,35 @@ impl Pos {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+// the origin of an [ow, oh] area placed by hpos and vpos inside a [w, h] parent,
+// the end of the area is at the end of the parent for Pos::End
+// every component is placed with this, and checked at that origin with fit
+pub(crate) fn place(hpos: &Pos, vpos: &Pos, [w, h]: [u16; 2], [ow, oh]: [u16; 2]) -> [u16; 2] {
+    hpos.clone()
+        .point(vpos.clone(), [w.saturating_sub(ow), h.saturating_sub(oh)])
+}
+
+// checks that an [w, h] area at origin fits inside a parent of size available
+pub(crate) fn fit(
+    origin: [u16; 2],
+    [w, h]: [u16; 2],
+    available: [u16; 2],
+) -> Result<(), SpaceError> {
+    let [x0, y0] = origin;
+    if w > available[0] || h > available[1] {
+        return Err(SpaceError::AreaOutOfBounds {
+            requested: [w, h],
+            available,
+        });
+    }
+    if ends_past(x0, w, available[0]) || ends_past(y0, h, available[1]) {
+        return Err(SpaceError::OriginOutOfBounds { origin, available });
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub enum Area {
     #[default]
     Zero,
@@ -279,6 +294,11 @@ pub enum Area {
         w: u16,
         h: u16,
     },
+    /// a percentage of the parent's width and height
+    Percent {
+        w: u16,
+        h: u16,
+    },
 }
 
 impl Area {
@@ -305,10 +325,25 @@ impl Area {
             Self::Fill => values,
             Self::Zero => [0; 2],
             Self::Values { w, h } => [w, h],
+            Self::Percent { w, h } => [
+                (values[0] as u32 * w.min(100) as u32 / 100) as u16,
+                (values[1] as u32 * h.min(100) as u32 / 100) as u16,
+            ],
         }
     }
 }
 
+/// decides what happens to the part of a Text value that does not fit inside its area
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Overflow {
+    /// the cells beyond the text area are dropped
+    #[default]
+    Clip,
+    /// the cells beyond the text area are dropped
+    /// and the last visible cell is replaced with an ellipsis '…'
+    Ellipsis,
+}
+
 impl Term {
     /// rescales the Term object dimensions to fit the new window size
     pub fn rescale(&mut self, wdiff: u16, hdiff: u16) {
@@ -327,6 +362,7 @@ impl Container {
         self.h *= hdiff;
         self.x0 *= wdiff;
         self.y0 *= hdiff;
+        self.invalidate();
     }
 }
 
@@ -340,6 +376,7 @@ impl Text {
         self.h *= hdiff;
         self.x0 *= wdiff;
         self.y0 *= hdiff;
+        self.invalidate();
 
         if self.cx >= self.w {
             self.cx = self.w - 1
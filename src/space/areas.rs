@@ -0,0 +1,188 @@
+use super::overlap;
+
+// the side of the square of cells a bucket holds
+const BUCKET: u32 = 16;
+
+// the areas of the containers of a term put in buckets of BUCKET x BUCKET cells, each one is in
+// the buckets of the cells it takes, an area is only checked against the ones in its buckets
+// the areas past the size it was made for go in the buckets of the last row and column
+#[derive(Debug, Default, Clone)]
+pub(crate) struct AreaIndex {
+    // the buckets across and down
+    dims: [u32; 2],
+    // the indices of the areas in each bucket, row by row
+    buckets: Vec<Vec<u32>>,
+    // the layer and the [x, y, w, h] area of each container, in the order of the containers,
+    // None for one that takes no area
+    entries: Vec<Option<(u8, [u16; 4])>>,
+}
+
+impl AreaIndex {
+    // an empty index for the areas of a term of size
+    pub(crate) fn new([w, h]: [u16; 2]) -> Self {
+        let dims = [w, h].map(|side| (side as u32).div_ceil(BUCKET).max(1));
+
+        Self {
+            dims,
+            buckets: vec![vec![]; (dims[0] * dims[1]) as usize],
+            entries: vec![],
+        }
+    }
+
+    // the buckets the cells of area are in
+    fn buckets_of(&self, [x, y, w, h]: [u16; 4]) -> impl Iterator<Item = usize> {
+        let [across, down] = self.dims;
+        // an area of no columns or rows is in the bucket of its origin, overlap counts
+        // the ones with other areas on both sides of it
+        let bucket = |start: u16, side: u16, dim: u32| {
+            let end = start as u32 + side.max(1) as u32 - 1;
+            (start as u32 / BUCKET).min(dim - 1)..(end / BUCKET).min(dim - 1) + 1
+        };
+        let [columns, rows] = [bucket(x, w, across), bucket(y, h, down)];
+
+        rows.flat_map(move |row| {
+            columns
+                .clone()
+                .map(move |col| (row * across + col) as usize)
+        })
+    }
+
+    // the number of containers the index holds
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    // adds the area of the next container
+    pub(crate) fn push(&mut self, entry: Option<(u8, [u16; 4])>) {
+        let idx = self.entries.len() as u32;
+        if let Some((_, area)) = entry {
+            let buckets = self.buckets_of(area).collect::<Vec<usize>>();
+            buckets.into_iter().for_each(|b| self.buckets[b].push(idx));
+        }
+        self.entries.push(entry);
+    }
+
+    // gives the nth container its new area
+    pub(crate) fn set(&mut self, n: usize, entry: Option<(u8, [u16; 4])>) {
+        if let Some((_, area)) = self.entries[n] {
+            let buckets = self.buckets_of(area).collect::<Vec<usize>>();
+            buckets
+                .into_iter()
+                .for_each(|b| self.buckets[b].retain(|idx| *idx as usize != n));
+        }
+        if let Some((_, area)) = entry {
+            let buckets = self.buckets_of(area).collect::<Vec<usize>>();
+            buckets
+                .into_iter()
+                .for_each(|b| self.buckets[b].push(n as u32));
+        }
+        self.entries[n] = entry;
+    }
+
+    // the first container on layer whose area shares a cell with area, skip left out,
+    // the same one checking them all in order finds
+    pub(crate) fn find(
+        &self,
+        layer: u8,
+        area: [u16; 4],
+        skip: impl Fn(usize) -> bool,
+    ) -> Option<usize> {
+        self.buckets_of(area)
+            .flat_map(|b| self.buckets[b].iter().map(|idx| *idx as usize))
+            .filter(|idx| match self.entries[*idx] {
+                Some((on, taken)) => on == layer && overlap(taken, area),
+                None => false,
+            })
+            .filter(|idx| !skip(*idx))
+            .min()
+    }
+
+    // the sorted indices of the containers whose areas share a cell with area, on any layer
+    pub(crate) fn overlapping(&self, area: [u16; 4]) -> Vec<usize> {
+        let mut hit = self
+            .buckets_of(area)
+            .flat_map(|b| self.buckets[b].iter().map(|idx| *idx as usize))
+            .filter(|idx| self.entries[*idx].is_some_and(|(_, taken)| overlap(taken, area)))
+            .collect::<Vec<usize>>();
+        hit.sort_unstable();
+        hit.dedup();
+
+        hit
+    }
+}
+
+#[cfg(test)]
+mod areas {
+    use super::*;
+
+    // the numbers of a xorshift generator
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: u16) -> u16 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+
+            (self.0 % n as u64) as u16
+        }
+    }
+
+    // an area of up to 40 x 12 cells, some of it past the 100 x 30 the index is made for
+    fn rect(rng: &mut Rng) -> [u16; 4] {
+        [rng.below(110), rng.below(34), rng.below(40), rng.below(12)]
+    }
+
+    // checks every entry in order
+    fn brute(entries: &[Option<(u8, [u16; 4])>], layer: u8, area: [u16; 4]) -> Option<usize> {
+        entries
+            .iter()
+            .position(|entry| entry.is_some_and(|(on, taken)| on == layer && overlap(taken, area)))
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..50 {
+            let mut index = AreaIndex::new([100, 30]);
+            let mut entries = vec![];
+            for _ in 0..rng.below(60) {
+                let entry = (rng.below(8) > 0).then(|| (rng.below(2) as u8, rect(&mut rng)));
+                index.push(entry);
+                entries.push(entry);
+            }
+            // some of them move
+            for _ in 0..rng.below(10).min(entries.len() as u16) {
+                let n = rng.below(entries.len() as u16) as usize;
+                let entry = Some((rng.below(2) as u8, rect(&mut rng)));
+                index.set(n, entry);
+                entries[n] = entry;
+            }
+            for _ in 0..100 {
+                let (layer, area) = (rng.below(2) as u8, rect(&mut rng));
+                assert_eq!(
+                    index.find(layer, area, |_| false),
+                    brute(&entries, layer, area)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn edges() {
+        let mut index = AreaIndex::new([40, 20]);
+        index.push(Some((0, [15, 0, 2, 2])));
+        index.push(Some((0, [0, 0, 16, 1])));
+        index.push(None);
+        // the areas that touch the edges of the buckets and an area of no columns
+        assert_eq!(index.find(0, [16, 1, 1, 1], |_| false), Some(0));
+        assert_eq!(index.find(0, [17, 0, 5, 5], |_| false), None);
+        assert_eq!(index.find(0, [15, 0, 0, 4], |_| false), Some(1));
+        assert_eq!(index.find(0, [17, 0, 0, 4], |_| false), None);
+        assert_eq!(index.find(0, [0, 0, 40, 20], |idx| idx == 0), Some(1));
+        // past the size of the index
+        index.push(Some((1, [60, 30, 3, 3])));
+        assert_eq!(index.find(1, [62, 32, 9, 9], |_| false), Some(3));
+        assert_eq!(index.len(), 4);
+    }
+}
@@ -1,5 +1,5 @@
 /// Container and Text objects border
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Border {
     /// no border
     #[default]
@@ -90,6 +90,21 @@ impl Border {
         }
     }
 
+    /// creates a new Border with the Polyform variant, with rounded corners and light lines
+    pub fn rounded() -> Self {
+        Self::polyform('╭', '╮', '╯', '╰', '│', '─')
+    }
+
+    /// creates a new Border with the Polyform variant, with square corners and light lines
+    pub fn square() -> Self {
+        Self::polyform('┌', '┐', '┘', '└', '│', '─')
+    }
+
+    /// creates a new Border with the Polyform variant, with double lines
+    pub fn double() -> Self {
+        Self::polyform('╔', '╗', '╝', '╚', '║', '═')
+    }
+
     /// creates a new Border with the Manual variant
     pub fn manual(
         tlcorner: char,
@@ -1,5 +1,5 @@
 /// Container and Text objects padding space
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Padding {
     /// no padding
     #[default]
@@ -0,0 +1,607 @@
+//! frame assertions, the way to regression test layouts
+//!
+//! a Frame is what Term::render would leave on a cleared terminal display, put back together
+//! into a plain grid of cells, each with the char and the style it was drawn with
+//! assert_frame_eq and assert_region_eq compare one with the expected rows and print a cell
+//! level diff of the rows that differ when they don't match
+//!
+//! ```
+//! # use ragout::{assert_frame_eq, assert_region_eq};
+//! # use ragout::components::{Container, Term};
+//! # use ragout::space::{border::Border, Area, Pos};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut term = Term::new(0, 10, 3);
+//! Container::builder([0, 0])
+//!     .pos(Pos::Start, Pos::Start)
+//!     .area(Area::Values { w: 6, h: 3 })
+//!     .border(Border::Uniform('*'))
+//!     .build(&mut term)?;
+//! assert_frame_eq!(term, "******\n*    *\n******\n");
+//! assert_region_eq!(term, [0, 0, 2, 2], "**\n* \n");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::components::grapheme::{cells, joins, split};
+use crate::components::Term;
+use crate::console::Capabilities;
+
+/// how a frame gets compared with the expected one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compare {
+    /// the spaces at the end of the rows and the blank rows at the end of the frames
+    /// are left out
+    pub trim_trailing: bool,
+    /// only the chars are compared, the styles the cells were drawn with are left out
+    pub chars_only: bool,
+}
+
+impl Default for Compare {
+    fn default() -> Self {
+        Self {
+            trim_trailing: true,
+            chars_only: false,
+        }
+    }
+}
+
+impl Compare {
+    /// the cells are compared as they are
+    pub fn exact() -> Self {
+        Self {
+            trim_trailing: false,
+            chars_only: false,
+        }
+    }
+
+    /// only the chars of the cells are compared
+    pub fn chars() -> Self {
+        Self {
+            chars_only: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// a cell of a Frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    /// the chars drawn in the cell, a space if nothing was drawn
+    /// a grapheme cluster of more than one char, e.g. an emoji sequence, is kept whole,
+    /// see Frame::rows, and the cell right of a wide char is a space
+    pub char: String,
+    /// the sgr sequences that were on when the char was drawn, empty if none were
+    pub style: String,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            char: " ".to_string(),
+            style: String::new(),
+        }
+    }
+}
+
+/// a w x h grid of cells, see the module docs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    w: u16,
+    h: u16,
+    cells: Vec<Cell>,
+    // whether the cells know what style they were drawn with, a frame made from text doesn't
+    styled: bool,
+}
+
+impl Frame {
+    /// the frame of term, drawn with every capability on so that it doesn't depend on the
+    /// terminal the tests run in
+    pub fn of(term: &Term) -> Self {
+        Self::with(term, &Capabilities::full())
+    }
+
+    /// the frame of term, drawn as it is with caps, e.g. to check the ascii borders
+    pub fn with(term: &Term, caps: &Capabilities) -> Self {
+        let mut frame = Self {
+            w: term.w,
+            h: term.h,
+            cells: vec![Cell::default(); term.w as usize * term.h as usize],
+            styled: true,
+        };
+        if term.w > 0 && term.h > 0 {
+            frame.draw(&term.frame_with(caps));
+        }
+
+        frame
+    }
+
+    /// the frame of text, each line is a row and the rows shorter than the longest one are
+    /// filled with spaces, the cells have no style
+    pub fn parse(text: &str) -> Self {
+        let mut rows = text.split('\n').collect::<Vec<&str>>();
+        // the last line break ends the last row
+        if rows.last() == Some(&"") {
+            rows.pop();
+        }
+        // a wide char takes its cell and a blank one
+        let rows = rows
+            .iter()
+            .map(|r| {
+                split(r)
+                    .into_iter()
+                    .flat_map(|c| [c, " "].into_iter().take(cells(c)))
+                    .collect::<Vec<&str>>()
+            })
+            .collect::<Vec<Vec<&str>>>();
+        let w = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let cells = rows
+            .iter()
+            .flat_map(|r| {
+                let mut row = r
+                    .iter()
+                    .map(|char| Cell {
+                        char: char.to_string(),
+                        style: String::new(),
+                    })
+                    .collect::<Vec<Cell>>();
+                row.resize(w, Cell::default());
+                row
+            })
+            .collect();
+
+        Self {
+            w: w as u16,
+            h: rows.len() as u16,
+            cells,
+            styled: false,
+        }
+    }
+
+    /// the width and height of this frame
+    pub fn size(&self) -> [u16; 2] {
+        [self.w, self.h]
+    }
+
+    /// the cell at column x of row y, both starting at 0
+    pub fn cell(&self, x: u16, y: u16) -> Option<&Cell> {
+        if x >= self.w || y >= self.h {
+            return None;
+        }
+
+        self.cells.get(y as usize * self.w as usize + x as usize)
+    }
+
+    /// the chars of each row, a cluster is given back as the chars it stands for
+    /// and the blank cell right of a wide char is left out
+    pub fn rows(&self) -> Vec<String> {
+        let row = |row: &[Cell]| {
+            let mut s = String::new();
+            let mut x = 0;
+            while let Some(cell) = row.get(x) {
+                s += &cell.char;
+                x += cells(&cell.char);
+            }
+            s
+        };
+
+        self.cells
+            .chunks(self.w.max(1) as usize)
+            .take(self.h as usize)
+            .map(row)
+            .collect()
+    }
+
+    /// the [x, y, w, h] part of this frame, the part of it that is out of this frame is dropped
+    pub fn region(&self, [x, y, w, h]: [u16; 4]) -> Self {
+        let w = w.min(self.w.saturating_sub(x));
+        let h = h.min(self.h.saturating_sub(y));
+        let cells = (y..y + h)
+            .flat_map(|row| (x..x + w).map(move |col| [col, row]))
+            .map(|[col, row]| self.cell(col, row).unwrap().clone())
+            .collect();
+
+        Self {
+            w,
+            h,
+            cells,
+            styled: self.styled,
+        }
+    }
+
+    /// None if this frame matches expected, otherwise a report of the rows that don't
+    /// the styles are only compared when both frames know them, see Frame::parse
+    pub fn diff(&self, expected: &Self, compare: Compare) -> Option<String> {
+        let styles = self.styled && expected.styled && !compare.chars_only;
+        let [aw, ah] = self.trimmed(compare);
+        let [ew, eh] = expected.trimmed(compare);
+        let [w, h] = [aw.max(ew), ah.max(eh)];
+        let blank = Cell::default();
+
+        let mut report = String::new();
+        let mut count = 0;
+        for y in 0..h {
+            let cells = |frame: &Self, [fw, fh]: [u16; 2]| {
+                (0..w)
+                    .map(|x| match x < fw && y < fh {
+                        true => frame.cell(x, y).unwrap_or(&blank).clone(),
+                        false => blank.clone(),
+                    })
+                    .collect::<Vec<Cell>>()
+            };
+            let actual = cells(self, [aw, ah]);
+            let wanted = cells(expected, [ew, eh]);
+            let differ = (0..w as usize)
+                .filter(|&x| {
+                    actual[x].char != wanted[x].char || styles && actual[x].style != wanted[x].style
+                })
+                .collect::<Vec<usize>>();
+            if differ.is_empty() {
+                continue;
+            }
+            count += differ.len();
+
+            let text = |row: &[Cell]| row.iter().map(|c| c.char.as_str()).collect::<String>();
+            report += &format!("-{:>4} |{}|\n", y, text(&wanted));
+            report += &format!("+{:>4} |{}|\n", y, text(&actual));
+            let mut marks = vec![' '; w as usize];
+            differ.iter().for_each(|&x| marks[x] = '^');
+            report += &format!(
+                "      {}  col {:?}\n",
+                marks.iter().collect::<String>(),
+                differ
+            );
+            for &x in differ.iter().filter(|&&x| actual[x].char == wanted[x].char) {
+                report += &format!(
+                    "      style at col {}: expected {:?}, found {:?}\n",
+                    x, wanted[x].style, actual[x].style
+                );
+            }
+        }
+        if count == 0 {
+            return None;
+        }
+
+        let mut head = format!("{} cells differ (- expected, + found)\n", count);
+        if !compare.trim_trailing && [self.w, self.h] != [expected.w, expected.h] {
+            head += &format!(
+                "expected a {}x{} frame, found a {}x{} one\n",
+                expected.w, expected.h, self.w, self.h
+            );
+        }
+
+        Some(head + &report)
+    }
+
+    // the width and height that are compared, without the trailing blanks if they are left out
+    fn trimmed(&self, compare: Compare) -> [u16; 2] {
+        if !compare.trim_trailing {
+            return [self.w, self.h];
+        }
+        let blank = |c: &Cell| c.char == " " && (compare.chars_only || c.style.is_empty());
+        let rows = self
+            .cells
+            .chunks(self.w.max(1) as usize)
+            .take(self.h as usize);
+        let widths = rows
+            .map(|row| row.iter().rposition(|c| !blank(c)).map_or(0, |x| x + 1))
+            .collect::<Vec<usize>>();
+        let h = widths.iter().rposition(|&w| w > 0).map_or(0, |y| y + 1);
+
+        [widths.into_iter().max().unwrap_or(0) as u16, h as u16]
+    }
+
+    // puts the frame bytes on the grid the way a vt100 terminal would
+    // the cursor starts at the top left cell, as Term::render expects the display to be cleared
+    fn draw(&mut self, output: &str) {
+        let [mut x, mut y] = [0u16, 0u16];
+        let mut style = String::new();
+        let mut chars = output.chars().peekable();
+        // the cell of the char drawn right before, a mark or a joiner goes into it
+        let mut last = None;
+        while let Some(c) = chars.next() {
+            let before = last.take();
+            match c {
+                '\x1b' if chars.peek() == Some(&'[') => {
+                    chars.next();
+                    let mut params = String::new();
+                    let Some(end) = chars.find(|&c| match ('\x40'..='\x7e').contains(&c) {
+                        true => true,
+                        false => {
+                            params.push(c);
+                            false
+                        }
+                    }) else {
+                        break;
+                    };
+                    let nums = params
+                        .split(';')
+                        .map(|p| p.parse::<u16>().unwrap_or(0))
+                        .collect::<Vec<u16>>();
+                    match end {
+                        // the cursor doesn't go past the last column
+                        'C' => x = x.saturating_add(nums[0].max(1)).min(self.w - 1),
+                        // the render pipeline counts the rows from 0 and the columns from 1,
+                        // see render_pipeline::cup
+                        'f' | 'H' => {
+                            y = nums[0];
+                            x = nums.get(1).copied().unwrap_or(1).saturating_sub(1);
+                        }
+                        'm' if nums.iter().all(|&n| n == 0) => style.clear(),
+                        'm' => style += &format!("\x1b[{}m", params),
+                        // modes, erasing and the like don't draw anything
+                        _ => (),
+                    }
+                }
+                // OSC sequences, such as hyperlinks, end with BEL or ST
+                '\x1b' if chars.peek() == Some(&']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || c == '\x1b' && chars.next_if_eq(&'\\').is_some() {
+                            break;
+                        }
+                    }
+                }
+                '\r' => x = 0,
+                '\n' => y += 1,
+                c if c.is_control() => (),
+                c => {
+                    let joins = |idx: &usize| joins(&self.cells[*idx].char, c);
+                    if let Some(idx) = before.filter(joins) {
+                        let base = cells(&self.cells[idx].char);
+                        self.cells[idx].char.push(c);
+                        // a variation selector can make the char wide
+                        let grown = cells(&self.cells[idx].char).saturating_sub(base);
+                        x = x.saturating_add(grown as u16);
+                        last = Some(idx);
+                        continue;
+                    }
+                    // a char after the last column goes at the start of the next row
+                    if x >= self.w {
+                        [x, y] = [0, y + 1];
+                    }
+                    if y < self.h {
+                        let idx = y as usize * self.w as usize + x as usize;
+                        self.cells[idx] = Cell {
+                            char: c.to_string(),
+                            style: style.clone(),
+                        };
+                        last = Some(idx);
+                    }
+                    x = x.saturating_add(cells(c.encode_utf8(&mut [0; 4])) as u16);
+                }
+            }
+        }
+    }
+}
+
+impl From<&str> for Frame {
+    fn from(text: &str) -> Self {
+        Self::parse(text)
+    }
+}
+
+impl From<String> for Frame {
+    fn from(text: String) -> Self {
+        Self::parse(&text)
+    }
+}
+
+impl From<&Term> for Frame {
+    fn from(term: &Term) -> Self {
+        Self::of(term)
+    }
+}
+
+impl std::fmt::Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.rows()
+            .iter()
+            .try_for_each(|row| writeln!(f, "{}", row))
+    }
+}
+
+/// the [x, y, w, h] cells the container, or the text, with id takes in term,
+/// its border and padding included, to hand to assert_region_eq
+pub fn rect(term: &Term, id: &[u8]) -> Option<[u16; 4]> {
+    let area = match *id {
+        [t, c] => term.container_ref(&[t, c])?.outer_area(),
+        [t, c, i] => term.text_ref(&[t, c, i])?.outer_area(),
+        _ => return None,
+    };
+    // the areas' columns start at 1
+    let [x, y, w, h] = area;
+
+    Some([x.saturating_sub(1), y, w, h])
+}
+
+// what the assertion macros call, the report of the mismatch if there is one
+#[doc(hidden)]
+pub fn check(found: Frame, expected: Frame, compare: Compare) -> Result<(), String> {
+    match found.diff(&expected, compare) {
+        None => Ok(()),
+        Some(diff) => Err(format!("{}\nfound frame:\n{}", diff, found)),
+    }
+}
+
+/// asserts that the frame of a Term, see Frame::of, matches the expected rows, a &str or a
+/// Frame, a Compare can be given after it, the default one leaves the trailing spaces out
+/// panics with a cell level diff of the rows that don't match
+///
+/// # Examples
+/// ```
+/// # use ragout::assert_frame_eq;
+/// # use ragout::components::{Container, Term};
+/// # use ragout::space::{border::Border, Area, Pos};
+/// # use ragout::test::{Compare, Frame};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let mut term = Term::new(0, 10, 3);
+/// # Container::builder([0, 0])
+/// #     .pos(Pos::Start, Pos::Start)
+/// #     .area(Area::Values { w: 6, h: 3 })
+/// #     .border(Border::Uniform('*'))
+/// #     .build(&mut term)?;
+/// # let before = term.clone();
+/// assert_frame_eq!(term, "******\n*    *\n******\n");
+/// assert_frame_eq!(term, Frame::of(&before), Compare::chars());
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_frame_eq {
+    ($term:expr, $expected:expr $(,)?) => {
+        $crate::assert_frame_eq!($term, $expected, $crate::test::Compare::default())
+    };
+    ($term:expr, $expected:expr, $compare:expr $(,)?) => {
+        if let Err(report) = $crate::test::check(
+            $crate::test::Frame::of(&$term),
+            $crate::test::Frame::from($expected),
+            $compare,
+        ) {
+            panic!("assertion `frame == expected` failed\n{}", report);
+        }
+    };
+}
+
+/// asserts that the [x, y, w, h] region of the frame of a Term matches the expected rows,
+/// see assert_frame_eq and rect, which gives the region of a container or a text
+///
+/// # Examples
+/// ```
+/// # use ragout::assert_region_eq;
+/// # use ragout::components::{Container, Term};
+/// # use ragout::space::{border::Border, Area, Pos};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let mut term = Term::new(0, 10, 3);
+/// # Container::builder([0, 0])
+/// #     .pos(Pos::Start, Pos::Start)
+/// #     .area(Area::Values { w: 6, h: 3 })
+/// #     .border(Border::Uniform('*'))
+/// #     .build(&mut term)?;
+/// let rect = ragout::test::rect(&term, &[0, 0]).unwrap();
+/// assert_region_eq!(term, rect, "******\n*    *\n******\n");
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_region_eq {
+    ($term:expr, $rect:expr, $expected:expr $(,)?) => {
+        $crate::assert_region_eq!($term, $rect, $expected, $crate::test::Compare::default())
+    };
+    ($term:expr, $rect:expr, $expected:expr, $compare:expr $(,)?) => {
+        if let Err(report) = $crate::test::check(
+            $crate::test::Frame::of(&$term).region($rect),
+            $crate::test::Frame::from($expected),
+            $compare,
+        ) {
+            panic!("assertion `region == expected` failed\n{}", report);
+        }
+    };
+}
+
+#[cfg(test)]
+mod frames {
+    use super::*;
+    use crate::components::{Container, Dropdown, Text};
+    use crate::inputs::keyboard::{Char, KbdEvent, KeyKind, Modifiers, CC};
+    use crate::space::{border::Border, Area, Pos};
+
+    fn term() -> Term {
+        let mut term = Term::new(0, 12, 4);
+        Container::builder([0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 8, h: 4 })
+            .border(Border::Uniform('*'))
+            .build(&mut term)
+            .unwrap();
+        Text::nonedit_builder([0, 0, 1])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 6, h: 1 })
+            .build(&mut term)
+            .unwrap();
+        let text = term.nonedit_mut(&[0, 0, 1]).unwrap();
+        text.value.set(0, Some('h'));
+        text.value.set(1, Some('i'));
+
+        term
+    }
+
+    #[test]
+    fn matches() {
+        let term = term();
+        assert_eq!(
+            Frame::of(&term).to_string(),
+            "********    \n*hi    *    \n*      *    \n********    \n"
+        );
+        assert_frame_eq!(term, "********\n*hi    *\n*      *\n********\n");
+        let exact = "********    \n*hi    *    \n*      *    \n********    \n";
+        assert_frame_eq!(term, exact, Compare::exact());
+        assert!(Frame::of(&term)
+            .diff(&"********".into(), Compare::exact())
+            .is_some());
+
+        let rect = rect(&term, &[0, 0, 1]).unwrap();
+        assert_eq!(rect, [1, 1, 6, 1]);
+        assert_region_eq!(term, rect, "hi");
+        assert_region_eq!(term, [6, 0, 3, 2], "**\n *\n");
+    }
+
+    #[test]
+    fn report() {
+        let term = term();
+        let diff = Frame::of(&term)
+            .diff(
+                &"********\n*ha    *\n*      *\n********\n".into(),
+                Compare::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            diff,
+            "1 cells differ (- expected, + found)\n\
+             -   1 |*ha    *|\n\
+             +   1 |*hi    *|\n\
+             \x20       ^       col [2]\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion `frame == expected` failed")]
+    fn mismatch() {
+        assert_frame_eq!(term(), "*");
+    }
+
+    #[test]
+    fn styles() {
+        let mut term = Term::new(0, 12, 6);
+        Container::builder([0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 8, h: 4 })
+            .build(&mut term)
+            .unwrap();
+        Text::input_builder([0, 0, 0])
+            .pos(Pos::Start, Pos::Start)
+            .area(Area::Values { w: 6, h: 1 })
+            .build(&mut term)
+            .unwrap();
+        let dropdown = Dropdown::new(vec!["a".into(), "b".into()]);
+        term.input_mut(&[0, 0, 0]).unwrap().set_dropdown(dropdown);
+        term.focus(&[0, 0, 0]).unwrap();
+        let closed = Frame::of(&term);
+        term.key(&KbdEvent {
+            char: Char::CC(CC::CR),
+            modifiers: Modifiers(0),
+            kind: KeyKind::Press,
+        });
+        let open = Frame::of(&term);
+
+        // the open list's current row is drawn in reverse video over the cells below the field
+        let [x, y, _, _] = term.dropdown_area(&[0, 0, 0]).unwrap();
+        let cell = open.cell(x - 1, y).unwrap();
+        assert_eq!(
+            [cell.char.to_string(), cell.style.clone()],
+            ["a", "\x1b[7m"]
+        );
+        assert!(closed.diff(&open, Compare::default()).is_some());
+        // a frame parsed from text has no styles to compare
+        let rows = Frame::parse(&open.to_string());
+        assert_eq!(open.diff(&rows, Compare::default()), None);
+    }
+}
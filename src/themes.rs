@@ -3,15 +3,21 @@ use std::io::StdoutLock;
 use std::io::Write;
 use std::ops::Range;
 
+pub mod base16;
+
+pub use base16::{Base16, Base16Error, Base16Mapping, Base16Slot};
+#[cfg(feature = "base16-schemes")]
+pub use base16::BASE16_SCHEMES;
+
 /// abstraction over the vt100 terminal's graphic rendition function
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct Style {
     effects: u8,
     text: Option<Color>,
     background: Option<Color>,
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 struct Color {
     r: u8,
     g: u8,
@@ -291,6 +297,29 @@ impl Style {
         self
     }
 
+    /// makes the terminals that can't display the text color show it as the 256 colors
+    /// palette color n, or the basic color basic, from 0 to 15, when the palette is not
+    /// supported either, instead of the nearest ones, see Capabilities::adapt_sgr
+    /// the fallback goes for every style with that color
+    /// returns self
+    pub fn text_fallback(self, n: u8, basic: u8) -> Self {
+        if let Some(color) = &self.text {
+            crate::console::capabilities::set_fallback(color.array(), [n, basic]);
+        }
+
+        self
+    }
+
+    /// the same as Style::text_fallback, for the background color
+    /// returns self
+    pub fn background_fallback(self, n: u8, basic: u8) -> Self {
+        if let Some(color) = &self.background {
+            crate::console::capabilities::set_fallback(color.array(), [n, basic]);
+        }
+
+        self
+    }
+
     /// changes the style background color to the provided rgb value
     pub fn background_color(mut self, color: &[u8; 3]) -> Self {
         self.background = Some(Color::new(color[0], color[1], color[2]));
@@ -320,6 +349,46 @@ impl Style {
     }
 }
 
+/// the styles of the component states, by key
+/// e.g. "input.disabled" is the style a disabled input is rendered in, see Term::set_disabled,
+/// and "focused.content" and "focused.border" the ones of the focused text, see Term::style_focus
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    styles: HashMap<&'static str, String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let mut theme = Self {
+            styles: HashMap::new(),
+        };
+        theme.set("input.disabled", &Style::new().faint());
+        theme.set("focused.content", &Style::new().underline());
+        theme.set("focused.border", &Style::new().bold());
+        theme.set("validation.valid", &Style::new().text_color(&[0, 175, 0]));
+        theme.set("validation.invalid", &Style::new().text_color(&[215, 0, 0]));
+
+        theme
+    }
+}
+
+impl Theme {
+    /// creates a new Theme with the default styles
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// changes the style under key
+    pub fn set(&mut self, key: &'static str, style: &Style) {
+        self.styles.insert(key, style.style());
+    }
+
+    /// the style under key, as its escape sequence
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.styles.get(key).map(String::as_str)
+    }
+}
+
 /// can only have one combination that results in the same sum
 /// 0 means reset all
 /// 1 means bold
@@ -422,6 +491,21 @@ mod styles {
     }
 }
 
+#[cfg(test)]
+mod theme {
+    use super::{Style, Theme};
+
+    #[test]
+    fn keys() {
+        let mut theme = Theme::new();
+        assert_eq!(theme.get("input.disabled"), Some("\x1b[2m"));
+        assert_eq!(theme.get("input.focused"), None);
+
+        theme.set("input.disabled", &Style::new().italic());
+        assert_eq!(theme.get("input.disabled"), Some("\x1b[3m"));
+    }
+}
+
 // TODO: add some template theme functions to ragout-extended
 // NOTE: border/text themes should be part of the properties and attributes functionalities
 // example custom theme on some component text/border value
@@ -0,0 +1,440 @@
+use std::path::Path;
+
+use super::{Style, Theme};
+
+/// the names of the base16 schemes built in with the base16-schemes feature,
+/// see Theme::base16_scheme
+#[cfg(feature = "base16-schemes")]
+pub const BASE16_SCHEMES: [&str; 2] = ["default-dark", "solarized-dark"];
+
+#[cfg(feature = "base16-schemes")]
+const EMBEDDED: [&str; 2] = [
+    include_str!("schemes/default-dark.yaml"),
+    include_str!("schemes/solarized-dark.yaml"),
+];
+
+/// the errors of Base16::parse, each one names the line it is about or the missing key
+#[derive(Debug, Clone, PartialEq)]
+pub enum Base16Error {
+    /// a line that is not a key: value pair, a comment nor empty
+    Syntax { line: usize },
+    /// a base00 to base0F key whose value is not a 6 digit hex color
+    Color { key: String, line: usize },
+    /// a base00 to base0F key given a second time
+    Duplicate { key: String, line: usize },
+    /// a base00 to base0F key the scheme doesn't have
+    Missing { key: String },
+    /// the scheme file could not be read
+    Io {
+        path: String,
+        kind: std::io::ErrorKind,
+    },
+}
+
+impl std::fmt::Display for Base16Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax { line } => write!(f, "line {}: expected a key: value pair", line),
+            Self::Color { key, line } => {
+                write!(f, "line {}: '{}' is not a 6 digit hex color", line, key)
+            }
+            Self::Duplicate { key, line } => {
+                write!(f, "line {}: '{}' is already given", line, key)
+            }
+            Self::Missing { key } => write!(f, "the scheme has no '{}'", key),
+            Self::Io { path, kind } => write!(f, "'{}' could not be read: {}", path, kind),
+        }
+    }
+}
+
+impl std::error::Error for Base16Error {}
+
+/// a base16 scheme, its 16 colors, base00 to base0F, and what it says about itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Base16 {
+    /// the scheme or name key
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub colors: [[u8; 3]; 16],
+}
+
+// a value without its quotes, or without the comment after it when it has none
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    for q in ['"', '\''] {
+        if let Some(rest) = value.strip_prefix(q) {
+            return rest.split(q).next().unwrap();
+        }
+    }
+
+    value.split(" #").next().unwrap().trim_end()
+}
+
+// the index of a base00 to base0F key
+fn base(key: &str) -> Option<usize> {
+    let digit = key.strip_prefix("base0")?;
+    if digit.len() != 1 {
+        return None;
+    }
+
+    usize::from_str_radix(digit, 16).ok()
+}
+
+// "rrggbb" or "#rrggbb"
+fn hex(value: &str) -> Option<[u8; 3]> {
+    let value = value.strip_prefix('#').unwrap_or(value);
+    if value.len() != 6 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |at: usize| u8::from_str_radix(&value[at..at + 2], 16).unwrap();
+
+    Some([channel(0), channel(2), channel(4)])
+}
+
+impl Base16 {
+    /// parses a base16 scheme, the yaml of the scheme repositories, key: value lines with
+    /// base00 to base0F given as "rrggbb" or "#rrggbb", quoted or not
+    /// the keys of a nested palette: block are read like the others, the keys other than the
+    /// colors, scheme, name and author are skipped, as are the comments and the empty lines
+    ///
+    /// # Errors
+    /// returns the first error of the scheme, see Base16Error, lines are counted from 1
+    pub fn parse(scheme: &str) -> Result<Self, Base16Error> {
+        let mut colors: [Option<[u8; 3]>; 16] = [None; 16];
+        let mut name = None;
+        let mut author = None;
+        for (idx, text) in scheme.lines().enumerate() {
+            let line = idx + 1;
+            let text = text.trim();
+            if text.is_empty() || text.starts_with('#') || text == "---" {
+                continue;
+            }
+            let (key, value) = text.split_once(':').ok_or(Base16Error::Syntax { line })?;
+            let key = key.trim();
+            let value = unquote(value);
+            match key {
+                "scheme" | "name" => name = Some(value.to_string()),
+                "author" => author = Some(value.to_string()),
+                _ => (),
+            }
+            let Some(at) = base(key) else {
+                continue;
+            };
+            if colors[at].is_some() {
+                return Err(Base16Error::Duplicate {
+                    key: key.to_string(),
+                    line,
+                });
+            }
+            let color = hex(value).ok_or_else(|| Base16Error::Color {
+                key: key.to_string(),
+                line,
+            })?;
+            colors[at] = Some(color);
+        }
+
+        let mut base16 = Self {
+            name,
+            author,
+            colors: [[0; 3]; 16],
+        };
+        for (at, color) in colors.iter().enumerate() {
+            base16.colors[at] = color.ok_or_else(|| Base16Error::Missing {
+                key: format!("base0{:X}", at),
+            })?;
+        }
+
+        Ok(base16)
+    }
+
+    /// reads and parses the base16 scheme at path, see Base16::parse
+    ///
+    /// # Errors
+    /// returns Io if the file can't be read, otherwise what Base16::parse returns
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Base16Error> {
+        let path = path.as_ref();
+        let scheme = std::fs::read_to_string(path).map_err(|e| Base16Error::Io {
+            path: path.display().to_string(),
+            kind: e.kind(),
+        })?;
+
+        Self::parse(&scheme)
+    }
+
+    /// the Theme of this scheme, the default Theme with the keys of mapping styled in the
+    /// colors of the scheme
+    pub fn theme(&self, mapping: &Base16Mapping) -> Theme {
+        let mut theme = Theme::default();
+        for (key, slot) in &mapping.slots {
+            let mut style = slot.style.clone();
+            if let Some(at) = slot.text {
+                style = style.text_color(&self.colors[at as usize & 15]);
+            }
+            if let Some(at) = slot.background {
+                style = style.background_color(&self.colors[at as usize & 15]);
+            }
+            theme.set(key, &style);
+        }
+
+        theme
+    }
+}
+
+/// how a key of a Theme is styled from a base16 scheme, the effects of style with
+/// the text and background colors at the given indexes, 0 for base00 to 15 for base0F
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Base16Slot {
+    pub style: Style,
+    pub text: Option<u8>,
+    pub background: Option<u8>,
+}
+
+impl Base16Slot {
+    /// a slot with the effects of style and no colors
+    pub fn new(style: Style) -> Self {
+        Self {
+            style,
+            ..Self::default()
+        }
+    }
+
+    /// changes the text color to base0n
+    /// returns self
+    pub fn text(mut self, n: u8) -> Self {
+        self.text = Some(n);
+
+        self
+    }
+
+    /// changes the background color to base0n
+    /// returns self
+    pub fn background(mut self, n: u8) -> Self {
+        self.background = Some(n);
+
+        self
+    }
+}
+
+/// the keys of a Theme made from a base16 scheme and how they are styled, see Base16::theme
+/// the default mapping is
+/// - "background": base05 on base00
+/// - "foreground": base05
+/// - "border": base03
+/// - "focused.content": underlined base07
+/// - "focused.border": bold base0D
+/// - "selection": base05 on base02
+/// - "hover": on base01
+/// - "input.disabled": faint base03
+/// - "error" and "validation.invalid": base08
+/// - "warning": base0A
+/// - "validation.valid": base0B
+#[derive(Debug, Clone, PartialEq)]
+pub struct Base16Mapping {
+    slots: Vec<(&'static str, Base16Slot)>,
+}
+
+impl Default for Base16Mapping {
+    fn default() -> Self {
+        let slot = |style: Style| Base16Slot::new(style);
+        let plain = || slot(Style::new());
+        Self {
+            slots: vec![
+                ("background", plain().text(0x5).background(0x0)),
+                ("foreground", plain().text(0x5)),
+                ("border", plain().text(0x3)),
+                ("focused.content", slot(Style::new().underline()).text(0x7)),
+                ("focused.border", slot(Style::new().bold()).text(0xD)),
+                ("selection", plain().text(0x5).background(0x2)),
+                ("hover", plain().background(0x1)),
+                ("input.disabled", slot(Style::new().faint()).text(0x3)),
+                ("error", plain().text(0x8)),
+                ("warning", plain().text(0xA)),
+                ("validation.valid", plain().text(0xB)),
+                ("validation.invalid", plain().text(0x8)),
+            ],
+        }
+    }
+}
+
+impl Base16Mapping {
+    /// the default mapping, see Base16Mapping
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// changes how key is styled, a key that isn't mapped yet is added
+    /// returns self
+    pub fn set(mut self, key: &'static str, slot: Base16Slot) -> Self {
+        match self.slots.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, s)) => *s = slot,
+            None => self.slots.push((key, slot)),
+        }
+
+        self
+    }
+
+    /// stops mapping key, the Theme keeps its default style for it if it has one
+    /// returns self
+    pub fn remove(mut self, key: &str) -> Self {
+        self.slots.retain(|(k, _)| *k != key);
+
+        self
+    }
+
+    /// how key is styled, None if it isn't mapped
+    pub fn get(&self, key: &str) -> Option<&Base16Slot> {
+        self.slots.iter().find(|(k, _)| *k == key).map(|(_, s)| s)
+    }
+}
+
+impl Theme {
+    /// the Theme of a base16 scheme with the default mapping, see Base16::parse and
+    /// Base16Mapping, Base16::theme takes another mapping
+    ///
+    /// # Errors
+    /// returns what Base16::parse returns
+    pub fn from_base16(scheme: &str) -> Result<Self, Base16Error> {
+        Ok(Base16::parse(scheme)?.theme(&Base16Mapping::default()))
+    }
+
+    /// the Theme of the base16 scheme at path, see Theme::from_base16 and Base16::from_file
+    ///
+    /// # Errors
+    /// returns what Base16::from_file returns
+    pub fn from_base16_file(path: impl AsRef<Path>) -> Result<Self, Base16Error> {
+        Ok(Base16::from_file(path)?.theme(&Base16Mapping::default()))
+    }
+
+    /// the Theme of the built in base16 scheme name, one of BASE16_SCHEMES,
+    /// with the default mapping, None if there is no such scheme
+    #[cfg(feature = "base16-schemes")]
+    pub fn base16_scheme(name: &str) -> Option<Self> {
+        let at = BASE16_SCHEMES.iter().position(|n| *n == name)?;
+
+        Some(Self::from_base16(EMBEDDED[at]).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod base16 {
+    use super::*;
+
+    const SCHEME: &str = "
+# a scheme
+scheme: \"Default Dark\"
+author: 'Chris Kempson'
+base00: \"181818\"
+base01: \"282828\"
+base02: \"383838\"
+base03: \"585858\"
+base04: \"b8b8b8\"
+base05: \"d8d8d8\"
+base06: \"e8e8e8\"
+base07: \"f8f8f8\"
+base08: \"ab4642\"
+base09: \"dc9656\"
+base0A: \"f7ca88\"
+base0B: \"a1b56c\"
+base0C: \"86c1b9\"
+base0D: \"7cafc2\"
+base0E: \"ba8baf\"
+base0F: \"a16946\"
+";
+
+    #[test]
+    fn parse() {
+        let scheme = Base16::parse(SCHEME).unwrap();
+        assert_eq!(scheme.name.as_deref(), Some("Default Dark"));
+        assert_eq!(scheme.author.as_deref(), Some("Chris Kempson"));
+        assert_eq!(scheme.colors[0], [0x18, 0x18, 0x18]);
+        assert_eq!(scheme.colors[15], [0xa1, 0x69, 0x46]);
+
+        // the palette block of the newer schemes, with #s and comments
+        let palette = SCHEME
+            .replace("base", "  base")
+            .replace("\"1", "\"#1")
+            .replace("scheme:", "palette:\nname:")
+            .replace("\"a16946\"", "a16946 # brown");
+        assert_eq!(Base16::parse(&palette).unwrap(), scheme);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |scheme: &str| Base16::parse(scheme).unwrap_err();
+        assert_eq!(
+            error(&SCHEME.replace("base03: ", "base03 ")),
+            Base16Error::Syntax { line: 8 }
+        );
+        assert_eq!(
+            error(&SCHEME.replace("7cafc2", "7cafc")),
+            Base16Error::Color {
+                key: "base0D".into(),
+                line: 18
+            }
+        );
+        assert_eq!(
+            error(&SCHEME.replace("base0E", "base0A")),
+            Base16Error::Duplicate {
+                key: "base0A".into(),
+                line: 19
+            }
+        );
+        assert_eq!(
+            error(&SCHEME.replace("base0F", "base10")),
+            Base16Error::Missing {
+                key: "base0F".into()
+            }
+        );
+        let missing = Base16::from_file("/there/is/no/scheme.yaml").unwrap_err();
+        assert!(matches!(
+            missing,
+            Base16Error::Io {
+                kind: std::io::ErrorKind::NotFound,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn mapping() {
+        let theme = Theme::from_base16(SCHEME).unwrap();
+        assert_eq!(
+            theme.get("background"),
+            Some("\x1b[38;2;216;216;216;48;2;24;24;24m")
+        );
+        assert_eq!(theme.get("focused.border"), Some("\x1b[1;38;2;124;175;194m"));
+
+        let mapping = Base16Mapping::new()
+            .set("error", Base16Slot::new(Style::new().bold()).text(0x9))
+            .set("title", Base16Slot::default().text(0xE))
+            .remove("input.disabled");
+        let theme = Base16::parse(SCHEME).unwrap().theme(&mapping);
+        assert_eq!(theme.get("error"), Some("\x1b[1;38;2;220;150;86m"));
+        assert_eq!(theme.get("title"), Some("\x1b[38;2;186;139;175m"));
+        // the default Theme's
+        assert_eq!(theme.get("input.disabled"), Some("\x1b[2m"));
+    }
+
+    #[test]
+    fn file() {
+        let path = std::env::temp_dir().join(format!("ragout-base16-{}.yaml", std::process::id()));
+        std::fs::write(&path, SCHEME).unwrap();
+        let theme = Theme::from_base16_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(theme.unwrap(), Theme::from_base16(SCHEME).unwrap());
+    }
+
+    #[cfg(feature = "base16-schemes")]
+    #[test]
+    fn embedded() {
+        for name in BASE16_SCHEMES {
+            assert!(Theme::base16_scheme(name).is_some());
+        }
+        assert_eq!(
+            Theme::base16_scheme("default-dark"),
+            Theme::from_base16(SCHEME).ok()
+        );
+        assert_eq!(Theme::base16_scheme("nord"), None);
+    }
+}